@@ -1,13 +1,25 @@
 use crate::database::update_history_summary;
 use crate::services::{
-    generate_raw, generate_summary_custom_with_hooks, test_connection, AIConfig, LongSummaryFormat,
-    LongSummaryHooks, LongSummaryProgress, SummaryStyle,
+    generate_raw, generate_summary_custom_with_hooks, get_secret, restore_url_credentials,
+    set_secret, store_url_credentials, test_connection, url_without_credentials, AIConfig,
+    AIFeature, LongSummaryFormat, LongSummaryHooks, LongSummaryProgress, SummaryStyle,
+    AI_API_KEY_SECRET, AI_PROXY_URL_SECRET, WHISPER_API_KEY_SECRET,
 };
+use crate::types::{SummaryProgress, SUMMARY_PROGRESS};
+use crate::utils::data_dir;
 use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::AppHandle;
+
+#[path = "ai/profiles.rs"]
+mod profiles;
+#[path = "ai/usage.rs"]
+mod usage;
+
+pub use profiles::*;
+pub use usage::*;
 
 static CANCELLED_SUMMARY_REQUESTS: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
 
@@ -47,15 +59,6 @@ fn is_summary_request_cancelled(request_id: &str) -> bool {
         .unwrap_or(false)
 }
 
-#[derive(Clone, serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct SummaryProgressPayload {
-    request_id: String,
-    stage: String,
-    chunk_index: Option<usize>,
-    chunk_count: usize,
-}
-
 async fn generate_summary_with_progress(
     app: &AppHandle,
     config: &AIConfig,
@@ -75,13 +78,13 @@ async fn generate_summary_with_progress(
     let progress_app = app.clone();
     let progress = move |progress: LongSummaryProgress| {
         if let Some(request_id) = progress_request_id.as_deref() {
-            let payload = SummaryProgressPayload {
+            let payload = SummaryProgress {
                 request_id: request_id.to_string(),
                 stage: progress.stage.to_string(),
                 chunk_index: progress.chunk_index,
                 chunk_count: progress.chunk_count,
             };
-            progress_app.emit("summary-progress", payload).ok();
+            SUMMARY_PROGRESS.emit(&progress_app, &payload).ok();
         }
     };
     let cancel_request_id = request_id.clone();
@@ -110,29 +113,44 @@ async fn generate_summary_with_progress(
         long_summary_words,
         &hooks,
     )
-    .await
-    .map_err(|e| e.to_wire_string());
+    .await;
     clear_cancelled_summary_request(request_id.as_deref());
-    result
+    record_ai_usage(AIFeature::Summary, config, transcript, result.as_ref().ok());
+    result.map_err(|e| e.to_wire_string())
 }
 
 /// Get the AI config file path
 fn get_config_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir =
+        data_dir(app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
     Ok(app_data_dir.join("ai_config.json"))
 }
 
-/// Save AI configuration
+/// Move API keys and server URL credentials out of the config into the
+/// encrypted secret store
+fn store_ai_secrets(app: &AppHandle, config: &mut AIConfig) -> Result<(), String> {
+    config.proxy_url =
+        store_url_credentials(app, AI_PROXY_URL_SECRET, config.proxy_url.as_deref())?;
+    set_secret(app, AI_API_KEY_SECRET, config.api_key.take().as_deref())?;
+    set_secret(
+        app,
+        WHISPER_API_KEY_SECRET,
+        config.whisper_api_key.take().as_deref(),
+    )
+}
+
+fn write_config_file(path: &PathBuf, config: &AIConfig) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write config: {}", e))
+}
+
+/// Save AI configuration. API keys go to the secret store, not the JSON file.
 #[tauri::command]
-pub async fn save_ai_config(app: AppHandle, config: AIConfig) -> Result<(), String> {
+pub async fn save_ai_config(app: AppHandle, mut config: AIConfig) -> Result<(), String> {
     let path = get_config_path(&app)?;
-    let json = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-    fs::write(&path, json).map_err(|e| format!("Failed to write config: {}", e))?;
-    Ok(())
+    store_ai_secrets(&app, &mut config)?;
+    write_config_file(&path, &config)
 }
 
 /// Load AI configuration
@@ -146,9 +164,30 @@ pub async fn get_ai_config(app: AppHandle) -> Result<AIConfig, String> {
 
     let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read config: {}", e))?;
 
-    let config: AIConfig =
+    let mut config: AIConfig =
         serde_json::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))?;
 
+    // Configs saved before keys moved to the secret store still hold them in plaintext
+    let proxy_has_credentials = config
+        .proxy_url
+        .as_deref()
+        .and_then(url_without_credentials)
+        .is_some();
+    if config.api_key.is_some() || config.whisper_api_key.is_some() || proxy_has_credentials {
+        let mut migrated = config.clone();
+        match store_ai_secrets(&app, &mut migrated) {
+            Ok(()) => write_config_file(&path, &migrated)?,
+            Err(e) => {
+                log::warn!("Keeping AI keys in plaintext config: {}", e);
+                return Ok(config);
+            }
+        }
+    }
+
+    config.api_key = get_secret(&app, AI_API_KEY_SECRET)?;
+    config.whisper_api_key = get_secret(&app, WHISPER_API_KEY_SECRET)?;
+    config.proxy_url =
+        restore_url_credentials(&app, AI_PROXY_URL_SECRET, config.proxy_url.as_deref())?;
     Ok(config)
 }
 
@@ -169,7 +208,7 @@ pub async fn generate_video_summary(
     title: Option<String>,
     request_id: Option<String>,
 ) -> Result<String, String> {
-    let config = get_ai_config(app.clone()).await?;
+    let config = ai_config_for_feature(&app, AIFeature::Summary).await?;
 
     if !config.enabled {
         return Err("AI features are disabled. Enable them in Settings.".to_string());
@@ -208,7 +247,7 @@ pub async fn generate_summary_with_options(
     long_summary_words: Option<u32>,
     request_id: Option<String>,
 ) -> Result<SummaryResult, String> {
-    let config = get_ai_config(app.clone()).await?;
+    let config = ai_config_for_feature(&app, AIFeature::Summary).await?;
 
     if !config.enabled {
         return Err("AI features are disabled. Enable them in Settings.".to_string());
@@ -389,6 +428,20 @@ pub fn get_ai_models(provider: String) -> Vec<ModelOption> {
                 label: "Qwen 3.5 Flash".to_string(),
             },
         ],
+        "anthropic" => vec![
+            ModelOption {
+                value: "claude-sonnet-4-5".to_string(),
+                label: "Claude Sonnet 4.5 (Recommended)".to_string(),
+            },
+            ModelOption {
+                value: "claude-haiku-4-5".to_string(),
+                label: "Claude Haiku 4.5".to_string(),
+            },
+            ModelOption {
+                value: "claude-opus-4-1".to_string(),
+                label: "Claude Opus 4.1".to_string(),
+            },
+        ],
         "proxy" => vec![
             ModelOption {
                 value: "gpt-5.5".to_string(),
@@ -483,18 +536,22 @@ pub struct LanguageOption {
 }
 
 /// Generate raw AI text response (no summarization wrapping)
-/// Used for subtitle translation, grammar fix, and other custom AI tasks
+/// Used for subtitle translation, grammar fix, and other custom AI tasks;
+/// `feature` picks the routed profile and defaults to `other`
 #[tauri::command]
-pub async fn generate_ai_response(app: AppHandle, prompt: String) -> Result<String, String> {
-    let config = get_ai_config(app).await?;
+pub async fn generate_ai_response(
+    app: AppHandle,
+    prompt: String,
+    feature: Option<AIFeature>,
+) -> Result<String, String> {
+    let feature = feature.unwrap_or(AIFeature::Other);
+    let config = ai_config_for_feature(&app, feature).await?;
 
     if !config.enabled {
         return Err("AI features are disabled. Enable them in Settings.".to_string());
     }
 
-    let result = generate_raw(&config, &prompt)
-        .await
-        .map_err(|e| e.to_wire_string())?;
+    let result = generate_raw_tracked(feature, &config, &prompt).await?;
 
     Ok(result.summary)
 }
@@ -0,0 +1,141 @@
+use super::*;
+use crate::services::{
+    ai_profile_secret_name, apply_ai_profile, validate_ai_profile, AIProfile, AIProfilesFile,
+};
+
+/// Serializes read-modify-write of `ai_profiles.json`
+static AI_PROFILES_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_profiles_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir =
+        data_dir(app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join("ai_profiles.json"))
+}
+
+fn read_profiles_file(app: &AppHandle) -> Result<AIProfilesFile, String> {
+    let path = get_profiles_path(app)?;
+    if !path.exists() {
+        return Ok(AIProfilesFile::default());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read AI profiles: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse AI profiles: {}", e))
+}
+
+fn write_profiles_file(app: &AppHandle, file: &AIProfilesFile) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize AI profiles: {}", e))?;
+    fs::write(get_profiles_path(app)?, json)
+        .map_err(|e| format!("Failed to write AI profiles: {}", e))
+}
+
+fn update_profiles_file<T>(
+    app: &AppHandle,
+    update: impl FnOnce(&mut AIProfilesFile) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = AI_PROFILES_LOCK
+        .lock()
+        .map_err(|e| format!("Failed to lock AI profiles: {}", e))?;
+    let mut file = read_profiles_file(app)?;
+    let value = update(&mut file)?;
+    write_profiles_file(app, &file)?;
+    Ok(value)
+}
+
+fn load_profile(app: &AppHandle, id: &str) -> Result<AIProfile, String> {
+    let mut profile = read_profiles_file(app)?
+        .profiles
+        .into_iter()
+        .find(|profile| profile.id == id)
+        .ok_or_else(|| format!("AI profile not found: {}", id))?;
+    profile.api_key = get_secret(app, &ai_profile_secret_name(id))?;
+    Ok(profile)
+}
+
+/// AI config for a feature: its routed profile, or the main settings.
+/// Fails for non-essential features once the monthly budget is spent.
+pub(crate) async fn ai_config_for_feature(
+    app: &AppHandle,
+    feature: AIFeature,
+) -> Result<AIConfig, String> {
+    let base = get_ai_config(app.clone()).await?;
+    let route = read_profiles_file(app)?.routes.get(&feature).cloned();
+    let config = match route {
+        Some(id) => apply_ai_profile(&base, &load_profile(app, &id)?),
+        None => base,
+    };
+    check_ai_budget(feature, &config)?;
+    Ok(config)
+}
+
+/// Profiles with their API keys and feature routes
+#[tauri::command]
+pub async fn get_ai_profiles(app: AppHandle) -> Result<AIProfilesFile, String> {
+    let mut file = read_profiles_file(&app)?;
+    for profile in file.profiles.iter_mut() {
+        profile.api_key = get_secret(&app, &ai_profile_secret_name(&profile.id))?;
+    }
+    Ok(file)
+}
+
+/// Add or replace a profile. The API key goes to the secret store.
+#[tauri::command]
+pub async fn save_ai_profile(app: AppHandle, mut profile: AIProfile) -> Result<(), String> {
+    validate_ai_profile(&profile)?;
+    set_secret(
+        &app,
+        &ai_profile_secret_name(&profile.id),
+        profile.api_key.take().as_deref(),
+    )?;
+    update_profiles_file(&app, |file| {
+        match file.profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) => *existing = profile,
+            None => file.profiles.push(profile),
+        }
+        Ok(())
+    })
+}
+
+/// Remove a profile; features routed to it fall back to the main settings
+#[tauri::command]
+pub async fn delete_ai_profile(app: AppHandle, id: String) -> Result<(), String> {
+    update_profiles_file(&app, |file| {
+        file.profiles.retain(|profile| profile.id != id);
+        file.routes.retain(|_, profile_id| *profile_id != id);
+        Ok(())
+    })?;
+    set_secret(&app, &ai_profile_secret_name(&id), None)
+}
+
+/// Route a feature to a profile, or back to the main settings with `None`
+#[tauri::command]
+pub async fn set_ai_feature_route(
+    app: AppHandle,
+    feature: AIFeature,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    update_profiles_file(&app, |file| {
+        match profile_id {
+            Some(id) => {
+                if !file.profiles.iter().any(|profile| profile.id == id) {
+                    return Err(format!("AI profile not found: {}", id));
+                }
+                file.routes.insert(feature, id);
+            }
+            None => {
+                file.routes.remove(&feature);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Send a short test request through a saved profile
+#[tauri::command]
+pub async fn test_ai_profile(app: AppHandle, id: String) -> Result<String, String> {
+    let base = get_ai_config(app.clone()).await?;
+    let config = apply_ai_profile(&base, &load_profile(&app, &id)?);
+    test_connection(&config)
+        .await
+        .map_err(|e| e.to_wire_string())
+}
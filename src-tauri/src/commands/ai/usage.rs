@@ -0,0 +1,119 @@
+use super::*;
+use crate::database::{ai_cost_since, ai_usage_buckets, clear_ai_usage_from_db, insert_ai_usage};
+use crate::services::{
+    ai_budget_allows, ai_model_priced, estimate_ai_cost_usd, estimate_tokens,
+    get_ai_monthly_budget, month_start, set_ai_monthly_budget, usage_range_start, AIUsageRange,
+    SummaryResult,
+};
+use crate::types::{AIUsageEntry, AIUsageReport};
+use chrono::{Local, NaiveDate};
+
+fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+fn month_cost_usd() -> Result<f64, String> {
+    ai_cost_since(&day_key(month_start(Local::now().date_naive())))
+}
+
+/// Refuse non-essential AI calls once this month's estimated spend reaches
+/// the budget, or when a budget is set and `config`'s model has no known price
+pub(crate) fn check_ai_budget(feature: AIFeature, config: &AIConfig) -> Result<(), String> {
+    let Some(budget) = get_ai_monthly_budget() else {
+        return Ok(());
+    };
+    let spent = month_cost_usd()?;
+    let priced = ai_model_priced(&config.provider, &config.model);
+    if ai_budget_allows(feature, spent, Some(budget), priced) {
+        Ok(())
+    } else if !priced {
+        Err(format!(
+            "The price of {} is unknown, so it can't be counted against your monthly AI budget. Pick a listed model or remove the budget in Settings.",
+            config.model
+        ))
+    } else {
+        Err(format!(
+            "Monthly AI budget reached (${:.2} of ${:.2}). Raise the budget in Settings to keep using this feature.",
+            spent, budget
+        ))
+    }
+}
+
+/// Store a request's tokens and cost; never fails the AI call itself. Uses
+/// the counts the provider reported, estimating only when it sent none.
+pub(crate) fn record_ai_usage(
+    feature: AIFeature,
+    config: &AIConfig,
+    prompt: &str,
+    response: Option<&SummaryResult>,
+) {
+    let (prompt_tokens, completion_tokens) = match response {
+        Some(SummaryResult {
+            usage: Some(usage), ..
+        }) => (usage.prompt_tokens, usage.completion_tokens),
+        Some(result) => (estimate_tokens(prompt), estimate_tokens(&result.summary)),
+        None => (estimate_tokens(prompt), 0),
+    };
+    let provider = serde_json::to_value(&config.provider)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let entry = AIUsageEntry {
+        feature: feature.as_str().to_string(),
+        provider,
+        cost_usd: estimate_ai_cost_usd(
+            &config.provider,
+            &config.model,
+            prompt_tokens,
+            completion_tokens,
+        ),
+        model: config.model.clone(),
+        prompt_tokens,
+        completion_tokens,
+        success: response.is_some(),
+    };
+    if let Err(e) = insert_ai_usage(&day_key(Local::now().date_naive()), &entry) {
+        log::warn!("{}", e);
+    }
+}
+
+/// `generate_raw` with the request recorded against `feature`
+pub(crate) async fn generate_raw_tracked(
+    feature: AIFeature,
+    config: &AIConfig,
+    prompt: &str,
+) -> Result<crate::services::SummaryResult, String> {
+    let result = generate_raw(config, prompt).await;
+    record_ai_usage(feature, config, prompt, result.as_ref().ok());
+    result.map_err(|e| e.to_wire_string())
+}
+
+/// Tokens and estimated cost per day and feature, with this month's budget status
+#[tauri::command]
+pub async fn get_ai_usage(range: AIUsageRange) -> Result<AIUsageReport, String> {
+    let since = usage_range_start(range, Local::now().date_naive()).map(day_key);
+    let buckets = ai_usage_buckets(since.as_deref())?;
+    let month_cost_usd = month_cost_usd()?;
+    let monthly_budget_usd = get_ai_monthly_budget();
+    Ok(AIUsageReport {
+        since,
+        total_prompt_tokens: buckets.iter().map(|b| b.prompt_tokens).sum(),
+        total_completion_tokens: buckets.iter().map(|b| b.completion_tokens).sum(),
+        total_cost_usd: buckets.iter().map(|b| b.cost_usd).sum(),
+        buckets,
+        month_cost_usd,
+        monthly_budget_usd,
+        budget_exceeded: monthly_budget_usd.is_some_and(|budget| month_cost_usd >= budget),
+    })
+}
+
+#[tauri::command]
+pub fn clear_ai_usage() -> Result<(), String> {
+    clear_ai_usage_from_db()
+}
+
+/// Sync the monthly budget (USD) from settings; `None` removes the limit
+#[tauri::command]
+pub fn set_ai_monthly_budget_cmd(budget_usd: Option<f64>) -> Result<(), String> {
+    set_ai_monthly_budget(budget_usd)
+}
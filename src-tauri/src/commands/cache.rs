@@ -0,0 +1,29 @@
+use tauri::AppHandle;
+
+use crate::services::{
+    cache_stats, clear_cache_categories, enforce_cache_limit, set_max_cache_size_mb, CacheCategory,
+    CacheStats,
+};
+
+/// Entry count and size of each cache category
+#[tauri::command]
+pub async fn get_cache_stats(app: AppHandle) -> Result<CacheStats, String> {
+    Ok(cache_stats(&app))
+}
+
+/// Remove cached files; all categories when `categories` is omitted
+#[tauri::command]
+pub async fn clear_cache(
+    app: AppHandle,
+    categories: Option<Vec<CacheCategory>>,
+) -> Result<u32, String> {
+    let categories = categories.unwrap_or_else(|| CacheCategory::ALL.to_vec());
+    Ok(clear_cache_categories(&app, &categories))
+}
+
+/// Cap the previews folder (0 = unlimited), evicting least recently used files
+#[tauri::command]
+pub async fn set_max_cache_size(app: AppHandle, max_mb: u64) -> Result<u32, String> {
+    set_max_cache_size_mb(max_mb);
+    Ok(enforce_cache_limit(&app))
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::get_video_info;
+use crate::database::{
+    delete_channel_rule_from_db, get_failed_download_options, get_history_entries_by_ids_from_db,
+    list_channel_rules_from_db, save_channel_rule_to_db,
+};
+use crate::types::{ChannelRule, DownloadProfileSettings, HistoryEntry};
+
+/// Settings a history entry was downloaded with: the saved options of a
+/// failed attempt, else its quality, format and folder
+fn settings_from_history(
+    entry: &HistoryEntry,
+    saved: Option<DownloadProfileSettings>,
+) -> DownloadProfileSettings {
+    let saved = saved.unwrap_or_default();
+    DownloadProfileSettings {
+        quality: saved.quality.or_else(|| entry.quality.clone()),
+        format: saved.format.or_else(|| entry.format.clone()),
+        output_path: saved.output_path.or_else(|| {
+            Path::new(&entry.filepath)
+                .parent()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .filter(|dir| !dir.is_empty())
+        }),
+        ..saved
+    }
+}
+
+#[tauri::command]
+pub fn get_channel_rules() -> Result<Vec<ChannelRule>, String> {
+    list_channel_rules_from_db()
+}
+
+/// Create the rule for a channel, or replace its settings
+#[tauri::command]
+pub fn save_channel_rule(
+    extractor_key: String,
+    channel_id: String,
+    channel_name: String,
+    settings: DownloadProfileSettings,
+) -> Result<ChannelRule, String> {
+    save_channel_rule_to_db(&extractor_key, &channel_id, &channel_name, &settings)
+}
+
+#[tauri::command]
+pub fn delete_channel_rule(id: String) -> Result<(), String> {
+    delete_channel_rule_from_db(&id)
+}
+
+/// "Always do this for this channel": save a history entry's settings as the
+/// rule for its channel. The channel and its site are looked up from the
+/// entry's URL unless both are given.
+#[tauri::command]
+pub async fn create_channel_rule_from_history(
+    app: AppHandle,
+    history_id: String,
+    extractor_key: Option<String>,
+    channel_id: Option<String>,
+    channel_name: Option<String>,
+) -> Result<ChannelRule, String> {
+    let entry = get_history_entries_by_ids_from_db(vec![history_id.clone()])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "History entry not found".to_string())?;
+    let extractor_key = extractor_key.filter(|key| !key.trim().is_empty());
+    let channel_id = channel_id.filter(|id| !id.trim().is_empty());
+    let (extractor_key, channel_id, channel_name) = match (extractor_key, channel_id) {
+        (Some(extractor_key), Some(channel_id)) => {
+            (extractor_key, channel_id, channel_name.unwrap_or_default())
+        }
+        (_, channel_id) => {
+            let info = get_video_info(
+                app,
+                entry.url.clone(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await?
+            .info;
+            let channel_id = channel_id
+                .or(info.channel_id)
+                .ok_or_else(|| "Could not tell which channel this entry belongs to".to_string())?;
+            let name = channel_name
+                .or(info.channel)
+                .or(info.uploader)
+                .unwrap_or_default();
+            (info.extractor_key.unwrap_or_default(), channel_id, name)
+        }
+    };
+    let (_, saved) = get_failed_download_options(&history_id)?;
+    save_channel_rule_to_db(
+        &extractor_key,
+        &channel_id,
+        &channel_name,
+        &settings_from_history(&entry, saved),
+    )
+}
@@ -6,7 +6,8 @@ use tokio::process::Command;
 
 use crate::database;
 use crate::services::{
-    build_cookie_args, build_site_header_args, get_deno_path, get_ytdlp_path, run_ytdlp_with_stderr,
+    build_cookie_args, build_site_header_args, get_deno_path, get_ytdlp_path,
+    metadata_network_args, run_ytdlp_with_stderr, ytdlp_process_env,
 };
 use crate::types::{ChannelInfo, ChannelVideo, FollowedChannel, PlaylistVideoEntry};
 use crate::utils::CommandExt;
@@ -68,9 +69,10 @@ async fn run_channel_ytdlp_with_progress(
     }
 
     if let Some((binary_path, _)) = get_ytdlp_path(app).await {
+        let env = ytdlp_process_env(app, args).await;
         let mut cmd = Command::new(binary_path);
         cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
-        cmd.hide_window();
+        cmd.process_env(&env).hide_window().utf8_output();
 
         let mut child = cmd
             .spawn()
@@ -290,12 +292,8 @@ async fn fetch_channel_videos_once(
     cookie_skip_patterns: Option<&[String]>,
     proxy_url: Option<&str>,
 ) -> Result<Vec<PlaylistVideoEntry>, String> {
-    let mut args = vec![
-        "--dump-json".to_string(),
-        "--no-warnings".to_string(),
-        "--socket-timeout".to_string(),
-        "30".to_string(),
-    ];
+    let mut args = vec!["--dump-json".to_string(), "--no-warnings".to_string()];
+    args.extend(metadata_network_args());
 
     // Only use --flat-playlist for YouTube; other platforms (Bilibili, etc.)
     // return minimal data in flat mode (no title, thumbnail, duration)
@@ -540,9 +538,8 @@ pub async fn get_channel_info(
         "1".to_string(),
         "--no-download".to_string(),
         "--no-warnings".to_string(),
-        "--socket-timeout".to_string(),
-        "30".to_string(),
     ];
+    args.extend(metadata_network_args());
 
     // Only use --flat-playlist for YouTube; other platforms return
     // minimal data in flat mode (no channel name, no thumbnails)
@@ -776,6 +773,7 @@ pub async fn update_channel_settings(
     filter_max_videos: Option<i64>,
     download_threads: Option<i64>,
     youtube_content_type: Option<String>,
+    filter_skip_shorts: Option<bool>,
 ) -> Result<(), String> {
     database::update_channel_settings_db(
         id,
@@ -793,6 +791,7 @@ pub async fn update_channel_settings(
         filter_max_videos,
         download_threads.unwrap_or(1),
         sanitize_youtube_content_type(youtube_content_type.as_deref()),
+        filter_skip_shorts.unwrap_or(false),
     )
 }
 
@@ -869,8 +868,11 @@ pub async fn update_channel_info(
 }
 
 /// Sync cookie/proxy settings from frontend so the background polling loop can use them.
+/// Proxy credentials are kept in the secret store; a proxy synced without them
+/// gets the stored ones back.
 #[tauri::command]
 pub async fn set_polling_network_config(
+    app: AppHandle,
     cookie_mode: Option<String>,
     cookie_browser: Option<String>,
     cookie_browser_profile: Option<String>,
@@ -879,6 +881,14 @@ pub async fn set_polling_network_config(
     proxy_url: Option<String>,
 ) -> Result<(), String> {
     use crate::services::polling::{set_network_config, PollingNetworkConfig};
+    use crate::services::{restore_url_credentials, store_url_credentials, PROXY_URL_SECRET};
+
+    let proxy_url = store_url_credentials(&app, PROXY_URL_SECRET, proxy_url.as_deref())
+        .and_then(|stripped| restore_url_credentials(&app, PROXY_URL_SECRET, stripped.as_deref()))
+        .unwrap_or_else(|e| {
+            log::warn!("Proxy credentials not stored: {}", e);
+            proxy_url
+        });
 
     set_network_config(PollingNetworkConfig {
         cookie_mode,
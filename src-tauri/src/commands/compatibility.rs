@@ -0,0 +1,20 @@
+use crate::services::{check_container_compatibility, CompatibilityReport};
+use crate::types::BackendError;
+
+/// Warn before download when the selected quality, format and codec won't
+/// play on `target` (quicktime, wmp, tv or all), with settings that would
+#[tauri::command]
+pub fn check_compatibility(
+    quality: String,
+    format: String,
+    codec: String,
+    target: Option<String>,
+) -> Result<CompatibilityReport, String> {
+    check_container_compatibility(
+        &quality,
+        &format,
+        &codec,
+        target.as_deref().unwrap_or("all"),
+    )
+    .map_err(|e| BackendError::from_message(e).to_wire_string())
+}
@@ -1,35 +1,41 @@
 use crate::services::{
-    check_deno_internal, check_deno_update_internal, check_ffmpeg_internal,
-    check_ffmpeg_update_internal, check_gallerydl_internal, get_all_ytdlp_versions,
-    get_channel_api_url, get_deno_download_url, get_ffmpeg_download_info, get_ffmpeg_path,
-    get_ffmpeg_source, get_latest_ffmpeg_release_info, get_ytdlp_channel,
-    get_ytdlp_channel_download_url, get_ytdlp_download_info, get_ytdlp_source,
-    get_ytdlp_version_internal, parse_ffmpeg_version, set_ffmpeg_source, set_ytdlp_channel,
-    set_ytdlp_source, system_ffmpeg_upgrade_message, system_ytdlp_upgrade_message, verify_sha256,
-    write_app_ffmpeg_release_version, DenoUpdateInfo, FfmpegUpdateInfo,
+    acquire_install_lock, check_deno_internal, check_deno_update_internal, check_ffmpeg_internal,
+    check_ffmpeg_update_internal, check_gallerydl_internal, clear_ytdlp_latest_version_cache,
+    forward_setup_progress, get_all_ytdlp_versions, get_channel_api_url, get_deno_download_url,
+    get_ffmpeg_download_info, get_ffmpeg_path, get_ffmpeg_source, get_latest_ffmpeg_release_info,
+    get_ytdlp_channel, get_ytdlp_channel_download_url, get_ytdlp_download_info, get_ytdlp_source,
+    get_ytdlp_version_internal, github_api_get, parse_ffmpeg_version, record_dependency_health,
+    refresh_dependency_health, set_ffmpeg_source, set_ytdlp_channel, set_ytdlp_source,
+    system_ffmpeg_upgrade_message, system_ytdlp_upgrade_message, verify_sha256,
+    write_app_ffmpeg_release_version, ytdlp_process_env, AppProcessRunner, DenoUpdateInfo,
+    FfmpegUpdateInfo, ProcessInvocation, ProcessRunner,
 };
 use crate::types::{
-    BackendError, DenoStatus, DependencySource, FfmpegStatus, GalleryDlStatus, YtdlpAllVersions,
-    YtdlpChannel, YtdlpChannelUpdateInfo, YtdlpVersionInfo,
+    BackendError, BinaryDownloadProgress, DenoStatus, DependencySource, DownloadRetryRequest,
+    EventContract, FfmpegStatus, GalleryDlStatus, YtdlpAllVersions, YtdlpChannel,
+    YtdlpChannelUpdateInfo, YtdlpVersionInfo, DENO_DOWNLOAD_PROGRESS, DOWNLOAD_RETRY,
+    FFMPEG_DOWNLOAD_PROGRESS,
 };
 use crate::utils::{
-    extract_deno_zip, extract_tar_gz, extract_tar_xz, extract_zip, firefox_profiles_from_ini,
-    CommandExt,
+    data_dir, extract_deno_zip, extract_tar_gz, extract_tar_xz, extract_zip,
+    firefox_profiles_from_ini, install_verified_binary, prepare_installed_binary,
+    verify_binary_runs, CommandExt,
 };
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::process::Stdio;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::AppHandle;
 use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 
-/// Download progress event payload
-#[derive(Clone, Serialize)]
-struct DownloadProgress {
-    stage: String,
-    percent: u8,
-    downloaded: u64,
-    total: u64,
+/// Emit install progress, mirrored into the setup progress during onboarding
+fn emit_install_progress(
+    contract: &EventContract<BinaryDownloadProgress>,
+    app: &AppHandle,
+    progress: &BinaryDownloadProgress,
+) -> tauri::Result<()> {
+    forward_setup_progress(app, progress);
+    contract.emit(app, progress)
 }
 
 #[derive(Deserialize)]
@@ -37,6 +43,13 @@ struct GitHubRelease {
     tag_name: String,
 }
 
+const YTDLP_STABLE_API_URL: &str = "https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest";
+
+async fn fetch_github_release(api_url: &str) -> Result<GitHubRelease, String> {
+    serde_json::from_value(github_api_get(api_url).await?)
+        .map_err(|e| format!("Failed to parse release info: {}", e))
+}
+
 #[derive(Serialize)]
 pub struct DetectedBrowser {
     pub name: String,
@@ -51,46 +64,15 @@ pub struct BrowserProfile {
 
 #[tauri::command]
 pub async fn get_ytdlp_version(app: AppHandle) -> Result<YtdlpVersionInfo, String> {
-    get_ytdlp_version_internal(&app).await
+    let result = get_ytdlp_version_internal(&app).await;
+    let version = result.as_ref().ok().map(|info| info.version.clone());
+    record_dependency_health(&app, "ytdlp", version.is_some(), version);
+    result
 }
 
 #[tauri::command]
 pub async fn check_ytdlp_update() -> Result<String, String> {
-    let client = reqwest::Client::builder()
-        .user_agent("Youwee/0.4.0")
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get("https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest")
-        .send()
-        .await
-        .map_err(|e| {
-            if e.is_timeout() {
-                "Request timed out. Please try again later.".to_string()
-            } else if e.is_connect() {
-                "Unable to connect. Please check your internet connection.".to_string()
-            } else {
-                format!("Failed to check for updates: {}", e)
-            }
-        })?;
-
-    let status = response.status();
-
-    if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::TOO_MANY_REQUESTS
-    {
-        return Err("GitHub API rate limit exceeded. Please try again later.".to_string());
-    }
-
-    if !status.is_success() {
-        return Err(format!("GitHub API error: {}", status));
-    }
-
-    let release: GitHubRelease = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+    let release = fetch_github_release(YTDLP_STABLE_API_URL).await?;
 
     Ok(release.tag_name)
 }
@@ -106,12 +88,11 @@ pub async fn update_ytdlp(app: AppHandle) -> Result<String, String> {
         .to_wire_string());
     }
 
+    let _install_lock = acquire_install_lock(&app, "yt-dlp")?;
     let (download_url, filename, checksum_filename) = get_ytdlp_download_info();
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir =
+        data_dir(&app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
     let bin_dir = app_data_dir.join("bin");
 
@@ -189,32 +170,19 @@ pub async fn update_ytdlp(app: AppHandle) -> Result<String, String> {
         .await
         .map_err(|e| format!("Failed to write binary: {}", e))?;
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = tokio::fs::metadata(&temp_path)
-            .await
-            .map_err(|e| format!("Failed to get file metadata: {}", e))?
-            .permissions();
-        perms.set_mode(0o755);
-        tokio::fs::set_permissions(&temp_path, perms)
-            .await
-            .map_err(|e| format!("Failed to set permissions: {}", e))?;
-    }
-
-    tokio::fs::rename(&temp_path, &binary_path)
-        .await
-        .map_err(|e| format!("Failed to rename binary: {}", e))?;
+    install_verified_binary(&temp_path, &binary_path, "--version").await?;
 
     // Get version
+    let env = ytdlp_process_env::<&str>(&app, &[]).await;
     let mut cmd = Command::new(&binary_path);
     cmd.args(["--version"]);
-    cmd.hide_window();
+    cmd.process_env(&env).hide_window().utf8_output();
     let output = cmd
         .output()
         .await
         .map_err(|e| format!("Failed to verify update: {}", e))?;
 
+    refresh_dependency_health(&app, "ytdlp").await;
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
@@ -254,7 +222,7 @@ async fn get_installed_channel_version(app: &AppHandle, channel: &YtdlpChannel)
         return None;
     }
 
-    let app_data_dir = app.path().app_data_dir().ok()?;
+    let app_data_dir = data_dir(app).ok()?;
 
     #[cfg(windows)]
     let binary_name = match channel {
@@ -274,11 +242,12 @@ async fn get_installed_channel_version(app: &AppHandle, channel: &YtdlpChannel)
         return None;
     }
 
+    let env = ytdlp_process_env::<&str>(app, &[]).await;
     let mut cmd = Command::new(&binary_path);
     cmd.args(["--version"])
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
-    cmd.hide_window();
+    cmd.process_env(&env).hide_window().utf8_output();
 
     let output = cmd.output().await.ok()?;
     if !output.status.success() {
@@ -309,37 +278,7 @@ pub async fn check_ytdlp_channel_update(
     let current_version = get_installed_channel_version(&app, &channel_enum).await;
 
     // Fetch latest version from GitHub
-    let client = reqwest::Client::builder()
-        .user_agent("Youwee/0.6.0")
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client.get(api_url).send().await.map_err(|e| {
-        if e.is_timeout() {
-            "Request timed out. Please try again later.".to_string()
-        } else if e.is_connect() {
-            "Unable to connect. Please check your internet connection.".to_string()
-        } else {
-            format!("Failed to check for updates: {}", e)
-        }
-    })?;
-
-    let status = response.status();
-
-    if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::TOO_MANY_REQUESTS
-    {
-        return Err("GitHub API rate limit exceeded. Please try again later.".to_string());
-    }
-
-    if !status.is_success() {
-        return Err(format!("GitHub API error: {}", status));
-    }
-
-    let release: GitHubRelease = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+    let release = fetch_github_release(api_url).await?;
 
     let latest_version = release.tag_name;
     let normalize_version = |v: &str| v.trim().trim_start_matches('v').to_string();
@@ -362,16 +301,15 @@ pub async fn download_ytdlp_channel(app: AppHandle, channel: String) -> Result<S
         return Err(BackendError::new(crate::types::code::YTDLP_SYSTEM_MANAGED, "System yt-dlp is managed externally. Switch source to App managed to install channel binaries.").with_retryable(false).to_wire_string());
     }
 
+    let _install_lock = acquire_install_lock(&app, "yt-dlp")?;
     let channel_enum = YtdlpChannel::from_str(&channel);
 
     // Get download URL for the channel
     let (download_url, checksum_filename) =
         get_ytdlp_channel_download_url(&channel_enum).ok_or("Cannot download bundled channel")?;
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir =
+        data_dir(&app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
     let bin_dir = app_data_dir.join("bin");
 
@@ -473,38 +411,50 @@ pub async fn download_ytdlp_channel(app: AppHandle, channel: String) -> Result<S
         .await
         .map_err(|e| format!("Failed to write binary: {}", e))?;
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = tokio::fs::metadata(&temp_path)
-            .await
-            .map_err(|e| format!("Failed to get file metadata: {}", e))?
-            .permissions();
-        perms.set_mode(0o755);
-        tokio::fs::set_permissions(&temp_path, perms)
-            .await
-            .map_err(|e| format!("Failed to set permissions: {}", e))?;
-    }
-
-    tokio::fs::rename(&temp_path, &binary_path)
-        .await
-        .map_err(|e| format!("Failed to rename binary: {}", e))?;
+    install_verified_binary(&temp_path, &binary_path, "--version").await?;
 
     // Get version
+    let env = ytdlp_process_env::<&str>(&app, &[]).await;
     let mut cmd = Command::new(&binary_path);
     cmd.args(["--version"]);
-    cmd.hide_window();
+    cmd.process_env(&env).hide_window().utf8_output();
     let output = cmd
         .output()
         .await
         .map_err(|e| format!("Failed to verify installation: {}", e))?;
 
+    refresh_dependency_health(&app, "ytdlp").await;
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// Update yt-dlp on the selected channel, then ask the frontend to retry download `id`
+#[tauri::command]
+pub async fn update_and_retry(app: AppHandle, id: String) -> Result<String, String> {
+    let channel = get_ytdlp_channel(&app).await;
+    let version = match channel {
+        YtdlpChannel::Bundled => update_ytdlp(app.clone()).await?,
+        _ => download_ytdlp_channel(app.clone(), channel.as_str().to_string()).await?,
+    };
+    clear_ytdlp_latest_version_cache();
+
+    DOWNLOAD_RETRY
+        .emit(
+            &app,
+            &DownloadRetryRequest {
+                id,
+                ytdlp_version: version.clone(),
+            },
+        )
+        .ok();
+
+    Ok(version)
+}
+
 #[tauri::command]
 pub async fn check_ffmpeg(app: AppHandle) -> Result<FfmpegStatus, String> {
-    check_ffmpeg_internal(&app).await
+    let status = check_ffmpeg_internal(&app).await?;
+    record_dependency_health(&app, "ffmpeg", status.installed, status.version.clone());
+    Ok(status)
 }
 
 #[tauri::command]
@@ -540,11 +490,13 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
     if info.url.is_empty() {
         return Err("Unsupported platform".to_string());
     }
+    let _install_lock = acquire_install_lock(&app, "ffmpeg")?;
 
     // Emit: Starting
-    let _ = app.emit(
-        "ffmpeg-download-progress",
-        DownloadProgress {
+    let _ = emit_install_progress(
+        &FFMPEG_DOWNLOAD_PROGRESS,
+        &app,
+        &BinaryDownloadProgress {
             stage: "checksum".to_string(),
             percent: 0,
             downloaded: 0,
@@ -552,10 +504,8 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
         },
     );
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir =
+        data_dir(&app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
     let bin_dir = app_data_dir.join("bin");
 
@@ -604,9 +554,10 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
         .ok_or_else(|| format!("Checksum not found for {}", info.checksum_filename))?;
 
     // Emit: Downloading FFmpeg
-    let _ = app.emit(
-        "ffmpeg-download-progress",
-        DownloadProgress {
+    let _ = emit_install_progress(
+        &FFMPEG_DOWNLOAD_PROGRESS,
+        &app,
+        &BinaryDownloadProgress {
             stage: "downloading".to_string(),
             percent: 0,
             downloaded: 0,
@@ -657,9 +608,10 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
         // Only emit every 5% to avoid spamming
         if percent >= last_percent + 5 || percent == 100 {
             last_percent = percent;
-            let _ = app.emit(
-                "ffmpeg-download-progress",
-                DownloadProgress {
+            let _ = emit_install_progress(
+                &FFMPEG_DOWNLOAD_PROGRESS,
+                &app,
+                &BinaryDownloadProgress {
                     stage: "downloading".to_string(),
                     percent,
                     downloaded,
@@ -680,9 +632,10 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
         .map_err(|e| format!("Failed to read downloaded file: {}", e))?;
 
     // Emit: Verifying checksum
-    let _ = app.emit(
-        "ffmpeg-download-progress",
-        DownloadProgress {
+    let _ = emit_install_progress(
+        &FFMPEG_DOWNLOAD_PROGRESS,
+        &app,
+        &BinaryDownloadProgress {
             stage: "verifying".to_string(),
             percent: 100,
             downloaded,
@@ -697,9 +650,10 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
     }
 
     // Emit: Extracting
-    let _ = app.emit(
-        "ffmpeg-download-progress",
-        DownloadProgress {
+    let _ = emit_install_progress(
+        &FFMPEG_DOWNLOAD_PROGRESS,
+        &app,
+        &BinaryDownloadProgress {
             stage: "extracting".to_string(),
             percent: 100,
             downloaded,
@@ -724,23 +678,22 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
     // Clean up temp file
     let _ = tokio::fs::remove_file(&temp_path).await;
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = tokio::fs::metadata(&ffmpeg_path)
-            .await
-            .map_err(|e| format!("Failed to get file metadata: {}", e))?
-            .permissions();
-        perms.set_mode(0o755);
-        tokio::fs::set_permissions(&ffmpeg_path, perms)
-            .await
-            .map_err(|e| format!("Failed to set permissions: {}", e))?;
+    prepare_installed_binary(&ffmpeg_path).await?;
+    #[cfg(windows)]
+    let ffprobe_path = bin_dir.join("ffprobe.exe");
+    #[cfg(not(windows))]
+    let ffprobe_path = bin_dir.join("ffprobe");
+    if ffprobe_path.exists() {
+        prepare_installed_binary(&ffprobe_path).await?;
+        verify_binary_runs(&ffprobe_path, "-version").await?;
     }
+    verify_binary_runs(&ffmpeg_path, "-version").await?;
 
     // Emit: Complete
-    let _ = app.emit(
-        "ffmpeg-download-progress",
-        DownloadProgress {
+    let _ = emit_install_progress(
+        &FFMPEG_DOWNLOAD_PROGRESS,
+        &app,
+        &BinaryDownloadProgress {
             stage: "complete".to_string(),
             percent: 100,
             downloaded,
@@ -748,17 +701,12 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
         },
     );
 
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args(["-version"])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-    cmd.hide_window();
-    let output = cmd
-        .output()
+    let output = AppProcessRunner::new(&app)
+        .run(&ProcessInvocation::binary(&ffmpeg_path, &["-version"]))
         .await
         .map_err(|e| format!("Failed to verify FFmpeg installation: {}", e))?;
 
-    if !output.status.success() {
+    if !output.success {
         let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
         let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
         let details = if !stderr.is_empty() {
@@ -766,13 +714,14 @@ pub async fn download_ffmpeg(app: AppHandle) -> Result<String, String> {
         } else if !stdout.is_empty() {
             stdout
         } else {
-            format!("exit code {}", output.status)
+            "FFmpeg exited with an error".to_string()
         };
 
         return Err(format!("Failed to verify FFmpeg installation: {}", details));
     }
 
     let binary_version = parse_ffmpeg_version(&String::from_utf8_lossy(&output.stdout));
+    refresh_dependency_health(&app, "ffmpeg").await;
     let latest_release = get_latest_ffmpeg_release_info().await.ok();
 
     if let Some(release) = latest_release {
@@ -792,7 +741,9 @@ pub async fn get_ffmpeg_path_for_ytdlp(app: AppHandle) -> Result<Option<String>,
 
 #[tauri::command]
 pub async fn check_deno(app: AppHandle) -> Result<DenoStatus, String> {
-    check_deno_internal(&app).await
+    let status = check_deno_internal(&app).await?;
+    record_dependency_health(&app, "deno", status.installed, status.version.clone());
+    Ok(status)
 }
 
 #[tauri::command]
@@ -812,11 +763,13 @@ pub async fn download_deno(app: AppHandle) -> Result<String, String> {
     if download_url.is_empty() {
         return Err("Unsupported platform".to_string());
     }
+    let _install_lock = acquire_install_lock(&app, "deno")?;
 
     // Emit: Starting
-    let _ = app.emit(
-        "deno-download-progress",
-        DownloadProgress {
+    let _ = emit_install_progress(
+        &DENO_DOWNLOAD_PROGRESS,
+        &app,
+        &BinaryDownloadProgress {
             stage: "downloading".to_string(),
             percent: 0,
             downloaded: 0,
@@ -824,10 +777,8 @@ pub async fn download_deno(app: AppHandle) -> Result<String, String> {
         },
     );
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir =
+        data_dir(&app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
     let bin_dir = app_data_dir.join("bin");
 
@@ -882,9 +833,10 @@ pub async fn download_deno(app: AppHandle) -> Result<String, String> {
         // Only emit every 5% to avoid spamming
         if percent >= last_percent + 5 || percent == 100 {
             last_percent = percent;
-            let _ = app.emit(
-                "deno-download-progress",
-                DownloadProgress {
+            let _ = emit_install_progress(
+                &DENO_DOWNLOAD_PROGRESS,
+                &app,
+                &BinaryDownloadProgress {
                     stage: "downloading".to_string(),
                     percent,
                     downloaded,
@@ -905,9 +857,10 @@ pub async fn download_deno(app: AppHandle) -> Result<String, String> {
         .map_err(|e| format!("Failed to read downloaded file: {}", e))?;
 
     // Emit: Extracting
-    let _ = app.emit(
-        "deno-download-progress",
-        DownloadProgress {
+    let _ = emit_install_progress(
+        &DENO_DOWNLOAD_PROGRESS,
+        &app,
+        &BinaryDownloadProgress {
             stage: "extracting".to_string(),
             percent: 100,
             downloaded,
@@ -928,23 +881,14 @@ pub async fn download_deno(app: AppHandle) -> Result<String, String> {
     // Clean up temp file
     let _ = tokio::fs::remove_file(&temp_path).await;
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = tokio::fs::metadata(&deno_path)
-            .await
-            .map_err(|e| format!("Failed to get file metadata: {}", e))?
-            .permissions();
-        perms.set_mode(0o755);
-        tokio::fs::set_permissions(&deno_path, perms)
-            .await
-            .map_err(|e| format!("Failed to set permissions: {}", e))?;
-    }
+    prepare_installed_binary(&deno_path).await?;
+    verify_binary_runs(&deno_path, "--version").await?;
 
     // Emit: Complete
-    let _ = app.emit(
-        "deno-download-progress",
-        DownloadProgress {
+    let _ = emit_install_progress(
+        &DENO_DOWNLOAD_PROGRESS,
+        &app,
+        &BinaryDownloadProgress {
             stage: "complete".to_string(),
             percent: 100,
             downloaded,
@@ -976,6 +920,7 @@ pub async fn download_deno(app: AppHandle) -> Result<String, String> {
         })
         .unwrap_or_default();
 
+    refresh_dependency_health(&app, "deno").await;
     Ok(version)
 }
 
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::database::add_log_internal;
+use crate::services::{
+    build_cookie_args, build_proxy_args, build_site_header_args, check_js_runtime,
+    collect_app_health, ensure_js_runtime, get_deno_path, is_app_managed_deno,
+    metadata_network_args, parse_metadata_error, run_ytdlp_timed, TimedOutputLine,
+};
+use crate::types::{
+    event_schema, AppHealth, BackendError, ExtractionPhaseTiming, ExtractionTestReport,
+    JsRuntimeCheck,
+};
+use crate::utils::{normalize_url, validate_url};
+
+const EXTRACTION_TEST_TIMEOUT_SECS: u64 = 120;
+const PHASE_ORDER: [&str; 4] = ["startup", "metadata", "player_js", "formats"];
+
+/// Map a verbose yt-dlp line to the extraction phase it starts.
+///
+/// yt-dlp announces each request before making it ("Downloading player ..."),
+/// so the time until the next line is spent in the announced phase.
+fn classify_extraction_line(line: &str) -> Option<&'static str> {
+    let lower = line.to_lowercase();
+    // Verbose output interleaves [debug] lines everywhere; only trust their keywords
+    let is_debug = lower.starts_with("[debug]");
+
+    if lower.contains("downloading player")
+        || lower.contains("[jsc")
+        || lower.contains("js challenge")
+        || lower.contains("nsig")
+        || lower.contains("n challenge")
+        || lower.contains("signature function")
+    {
+        return Some("player_js");
+    }
+    if lower.contains("m3u8 information")
+        || lower.contains("mpd manifest")
+        || lower.contains("dash manifest")
+        || lower.contains("format(s)")
+        || (!is_debug && lower.starts_with("[info]"))
+    {
+        return Some("formats");
+    }
+    if lower.contains("extracting url")
+        || lower.contains("downloading webpage")
+        || lower.contains("api json")
+        || lower.contains("client config")
+        || lower.contains("initial data")
+        || lower.contains("json metadata")
+        || (!is_debug && lower.starts_with('['))
+    {
+        return Some("metadata");
+    }
+
+    None
+}
+
+fn build_phase_timings(lines: &[TimedOutputLine], total_ms: u64) -> Vec<ExtractionPhaseTiming> {
+    let mut durations: HashMap<&'static str, (u64, usize)> = HashMap::new();
+    let mut current_phase = "startup";
+    let mut phase_started_at = 0_u64;
+
+    for line in lines {
+        let Some(next_phase) = classify_extraction_line(&line.line) else {
+            continue;
+        };
+        let entry = durations.entry(current_phase).or_insert((0, 0));
+        entry.0 += line.elapsed_ms.saturating_sub(phase_started_at);
+        durations.entry(next_phase).or_insert((0, 0)).1 += 1;
+        current_phase = next_phase;
+        phase_started_at = line.elapsed_ms;
+    }
+    durations.entry(current_phase).or_insert((0, 0)).0 += total_ms.saturating_sub(phase_started_at);
+
+    PHASE_ORDER
+        .iter()
+        .filter_map(|phase| {
+            durations
+                .get(phase)
+                .map(|(duration_ms, line_count)| ExtractionPhaseTiming {
+                    phase: phase.to_string(),
+                    duration_ms: *duration_ms,
+                    line_count: *line_count,
+                })
+        })
+        .collect()
+}
+
+fn collect_prefixed_lines(lines: &[TimedOutputLine], prefix: &str) -> Vec<String> {
+    lines
+        .iter()
+        .filter_map(|line| line.line.trim().strip_prefix(prefix))
+        .map(|message| message.trim().to_string())
+        .collect()
+}
+
+/// Same snapshot as the startup `app-health` event, for listeners that missed it
+#[tauri::command]
+pub async fn get_app_health(app: AppHandle) -> Result<AppHealth, String> {
+    Ok(collect_app_health(&app).await)
+}
+
+/// JSON schema of the versioned event payloads, for generating the frontend types
+#[tauri::command]
+pub fn get_event_schema() -> serde_json::Value {
+    event_schema()
+}
+
+/// Run a one-line script through Deno to confirm YouTube extraction can use it
+#[tauri::command]
+pub async fn verify_js_runtime(app: AppHandle) -> Result<JsRuntimeCheck, String> {
+    let Some(deno_path) = get_deno_path(&app).await else {
+        return Ok(JsRuntimeCheck {
+            installed: false,
+            working: false,
+            binary_path: None,
+            is_system: false,
+            duration_ms: 0,
+            error: None,
+        });
+    };
+    let started = std::time::Instant::now();
+    let result = check_js_runtime(&deno_path).await;
+    Ok(JsRuntimeCheck {
+        installed: true,
+        working: result.is_ok(),
+        binary_path: Some(deno_path.to_string_lossy().to_string()),
+        is_system: !is_app_managed_deno(&app, &deno_path),
+        duration_ms: started.elapsed().as_millis() as u64,
+        error: result.err(),
+    })
+}
+
+/// Run yt-dlp in simulate mode and report how long each extraction phase takes
+#[tauri::command]
+pub async fn test_extraction(
+    app: AppHandle,
+    url: String,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    cookie_skip_patterns: Option<Vec<String>>,
+    proxy_url: Option<String>,
+) -> Result<ExtractionTestReport, String> {
+    validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let url = normalize_url(&url);
+
+    let mut args = vec![
+        "--verbose".to_string(),
+        "--simulate".to_string(),
+        "--no-playlist".to_string(),
+        "--newline".to_string(),
+    ];
+    args.extend(metadata_network_args());
+
+    if url.contains("youtube.com") || url.contains("youtu.be") {
+        let deno_path = ensure_js_runtime(&app)
+            .await
+            .map_err(|e| e.to_wire_string())?;
+        if let Some(deno_path) = deno_path {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno_path.to_string_lossy()));
+        }
+    }
+
+    let cookie_args = build_cookie_args(
+        &url,
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        cookie_skip_patterns.as_deref(),
+    );
+    let proxy_args = build_proxy_args(proxy_url.as_deref());
+    let used_cookies = !cookie_args.is_empty();
+    let used_proxy = !proxy_args.is_empty();
+
+    args.extend(build_site_header_args(&url));
+    args.extend(cookie_args);
+    args.extend(proxy_args);
+    args.push("--".to_string());
+    args.push(url.clone());
+
+    add_log_internal(
+        "command",
+        &format!("yt-dlp {}", args.join(" ")),
+        Some("Extraction test"),
+        Some(&url),
+    )
+    .ok();
+
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = run_ytdlp_timed(
+        &app,
+        &args_ref,
+        Duration::from_secs(EXTRACTION_TEST_TIMEOUT_SECS),
+    )
+    .await?;
+    if output.timed_out {
+        return Err(BackendError::from_message(format!(
+            "Extraction test timed out after {} seconds. Check your network or proxy settings.",
+            EXTRACTION_TEST_TIMEOUT_SECS
+        ))
+        .to_wire_string());
+    }
+
+    let phases = build_phase_timings(&output.lines, output.elapsed_ms);
+    let slowest_phase = phases
+        .iter()
+        .max_by_key(|phase| phase.duration_ms)
+        .map(|phase| phase.phase.clone());
+    let errors = collect_prefixed_lines(&output.lines, "ERROR:");
+    let error_code = if output.success {
+        None
+    } else {
+        parse_metadata_error(&errors.join("\n")).map(|e| e.code().to_string())
+    };
+
+    Ok(ExtractionTestReport {
+        url,
+        success: output.success,
+        total_ms: output.elapsed_ms,
+        phases,
+        slowest_phase,
+        warnings: collect_prefixed_lines(&output.lines, "WARNING:"),
+        errors,
+        error_code,
+        used_cookies,
+        used_proxy,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(elapsed_ms: u64, text: &str) -> TimedOutputLine {
+        TimedOutputLine {
+            elapsed_ms,
+            is_stderr: false,
+            line: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn classify_extraction_line_recognizes_youtube_phases() {
+        assert_eq!(
+            classify_extraction_line("[debug] yt-dlp version stable@2025.01.01"),
+            None
+        );
+        assert_eq!(
+            classify_extraction_line("[debug] [youtube] [jsc:deno] Solving JS challenges"),
+            Some("player_js")
+        );
+        assert_eq!(
+            classify_extraction_line("[youtube] abc: Downloading webpage"),
+            Some("metadata")
+        );
+        assert_eq!(
+            classify_extraction_line("[youtube] abc: Downloading player 1a2b3c4d-main"),
+            Some("player_js")
+        );
+        assert_eq!(
+            classify_extraction_line("[info] abc: Downloading 1 format(s): 137+140"),
+            Some("formats")
+        );
+        assert_eq!(classify_extraction_line("plain output"), None);
+    }
+
+    #[test]
+    fn build_phase_timings_attributes_gaps_to_announced_phase() {
+        let lines = vec![
+            line(800, "[youtube] Extracting URL: https://youtu.be/abc"),
+            line(900, "[youtube] abc: Downloading webpage"),
+            line(2_000, "[youtube] abc: Downloading player 1a2b3c4d-main"),
+            line(6_000, "[youtube] abc: Downloading m3u8 information"),
+            line(6_500, "[info] abc: Downloading 1 format(s): 18"),
+        ];
+
+        let phases = build_phase_timings(&lines, 7_000);
+
+        assert_eq!(
+            phases,
+            vec![
+                ExtractionPhaseTiming {
+                    phase: "startup".to_string(),
+                    duration_ms: 800,
+                    line_count: 0,
+                },
+                ExtractionPhaseTiming {
+                    phase: "metadata".to_string(),
+                    duration_ms: 1_200,
+                    line_count: 2,
+                },
+                ExtractionPhaseTiming {
+                    phase: "player_js".to_string(),
+                    duration_ms: 4_000,
+                    line_count: 1,
+                },
+                ExtractionPhaseTiming {
+                    phase: "formats".to_string(),
+                    duration_ms: 1_000,
+                    line_count: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_prefixed_lines_extracts_warnings() {
+        let lines = vec![
+            line(
+                0,
+                "WARNING: [youtube] Falling back to generic n function search",
+            ),
+            line(1, "[youtube] abc: Downloading webpage"),
+        ];
+
+        assert_eq!(
+            collect_prefixed_lines(&lines, "WARNING:"),
+            vec!["[youtube] Falling back to generic n function search".to_string()]
+        );
+    }
+}
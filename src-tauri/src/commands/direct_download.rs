@@ -0,0 +1,202 @@
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use tauri::AppHandle;
+
+use super::download::CANCEL_FLAG;
+use crate::database::{add_history_internal, add_log_internal, update_history_download};
+use crate::services::{
+    spawn_download_integrity_check, stream_direct_download, track_active_job, ActiveJob,
+};
+use crate::types::{BackendError, DownloadProgress, DOWNLOAD_PROGRESS};
+use crate::utils::{
+    format_size, is_media_output_path, media_type_for_path, normalize_url,
+    resolve_output_directory, validate_url,
+};
+
+#[derive(serde::Serialize)]
+pub struct DirectDownloadResult {
+    pub filepath: String,
+    pub filesize: u64,
+    pub history_id: Option<String>,
+    pub resumed: bool,
+}
+
+fn error_progress(id: &str, error: &BackendError) -> DownloadProgress {
+    DownloadProgress {
+        id: id.to_string(),
+        percent: 0.0,
+        speed: String::new(),
+        eta: String::new(),
+        status: "error".to_string(),
+        title: None,
+        playlist_index: None,
+        playlist_count: None,
+        filesize: None,
+        resolution: None,
+        format_ext: None,
+        error_message: Some(error.message().to_string()),
+        error_code: Some(error.code().to_string()),
+        error_params: error.params().cloned(),
+        history_id: None,
+        filepath: None,
+        downloaded_size: None,
+        elapsed_time: None,
+        stage: None,
+        stage_detail: None,
+        indeterminate: false,
+    }
+}
+
+/// Download a plain file (MP4, PDF, ZIP, ...) without yt-dlp.
+///
+/// Used when yt-dlp reports `UNSUPPORTED_URL`. Interrupted downloads keep a
+/// `.part` file and resume on the next call; `stop_download` cancels it.
+#[tauri::command]
+pub async fn download_direct_file(
+    app: AppHandle,
+    id: String,
+    url: String,
+    output_path: String,
+    proxy_url: Option<String>,
+    history_id: Option<String>,
+    thumbnail: Option<String>,
+    verify_integrity: Option<bool>,
+) -> Result<DirectDownloadResult, String> {
+    CANCEL_FLAG.store(false, Ordering::SeqCst);
+    let _active_job = track_active_job(ActiveJob::download(&id, &url, &output_path));
+    validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let url = normalize_url(&url);
+    let output_dir =
+        resolve_output_directory(&output_path, true).map_err(|e| e.to_wire_string())?;
+
+    add_log_internal(
+        "command",
+        &format!("Direct download: {}", url),
+        None,
+        Some(&url),
+    )
+    .ok();
+
+    let outcome = match stream_direct_download(
+        &app,
+        &id,
+        &url,
+        Path::new(&output_dir),
+        proxy_url.as_deref(),
+        None,
+        &CANCEL_FLAG,
+    )
+    .await
+    {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            let level = if error.code() == crate::types::code::DOWNLOAD_CANCELLED {
+                "info"
+            } else {
+                "error"
+            };
+            add_log_internal(level, error.message(), None, Some(&url)).ok();
+            DOWNLOAD_PROGRESS
+                .emit(&app, &error_progress(&id, &error))
+                .ok();
+            return Err(error.to_wire_string());
+        }
+    };
+
+    let title = Path::new(&outcome.filepath)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("Download")
+        .to_string();
+    let format = Path::new(&outcome.filepath)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    let thumbnail = if media_type_for_path(&outcome.filepath) == "image" {
+        Some(outcome.filepath.clone())
+    } else {
+        thumbnail
+    };
+
+    let history_row_id = match history_id {
+        Some(hist_id) => update_history_download(
+            hist_id.clone(),
+            outcome.filepath.clone(),
+            Some(outcome.filesize),
+            None,
+            format.clone(),
+            None,
+        )
+        .ok()
+        .map(|_| hist_id),
+        None => add_history_internal(
+            url.clone(),
+            title.clone(),
+            thumbnail,
+            outcome.filepath.clone(),
+            Some(outcome.filesize),
+            None,
+            None,
+            format.clone(),
+            Some("direct".to_string()),
+            None,
+        )
+        .ok(),
+    };
+
+    add_log_internal(
+        "success",
+        &format!("Downloaded: {}", title),
+        Some(&format!(
+            "Size: {} · Direct download{}",
+            format_size(outcome.filesize),
+            if outcome.resumed { " (resumed)" } else { "" }
+        )),
+        Some(&url),
+    )
+    .ok();
+
+    DOWNLOAD_PROGRESS
+        .emit(
+            &app,
+            &DownloadProgress {
+                id: id.clone(),
+                percent: 100.0,
+                speed: String::new(),
+                eta: String::new(),
+                status: "finished".to_string(),
+                title: Some(title),
+                playlist_index: None,
+                playlist_count: None,
+                filesize: Some(outcome.filesize),
+                resolution: None,
+                format_ext: format,
+                error_message: None,
+                error_code: None,
+                error_params: None,
+                history_id: history_row_id.clone(),
+                filepath: Some(outcome.filepath.clone()),
+                downloaded_size: None,
+                elapsed_time: None,
+                stage: None,
+                stage_detail: None,
+                indeterminate: false,
+            },
+        )
+        .ok();
+
+    let is_playable = is_media_output_path(&outcome.filepath);
+    if verify_integrity.unwrap_or(false) && is_playable {
+        if let Some(hist_id) = history_row_id.clone() {
+            spawn_download_integrity_check(app.clone(), hist_id);
+        }
+    }
+
+    Ok(DirectDownloadResult {
+        filepath: outcome.filepath,
+        filesize: outcome.filesize,
+        history_id: history_row_id,
+        resumed: outcome.resumed,
+    })
+}
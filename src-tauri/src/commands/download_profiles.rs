@@ -0,0 +1,79 @@
+use tauri::AppHandle;
+
+use crate::database::{
+    delete_download_profile_from_db, duplicate_download_profile_in_db,
+    list_download_profiles_from_db, list_recent_inputs_from_db, save_download_profile_to_db,
+    suggest_input_options_from_db,
+};
+use crate::services::{
+    get_secret, profile_proxy_secret_name, set_secret, store_url_credentials,
+    url_without_credentials,
+};
+use crate::types::{DownloadProfile, DownloadProfileSettings, RecentInput, SuggestedInputOptions};
+
+#[tauri::command]
+pub fn get_download_profiles(app: AppHandle) -> Result<Vec<DownloadProfile>, String> {
+    let mut profiles = list_download_profiles_from_db()?;
+    // Profiles saved before proxy credentials moved to the secret store
+    for profile in &mut profiles {
+        let proxy_url = profile.settings.proxy_url.as_deref();
+        if proxy_url.and_then(url_without_credentials).is_some() {
+            let mut settings = profile.settings.clone();
+            settings.proxy_url =
+                store_url_credentials(&app, &profile_proxy_secret_name(&profile.id), proxy_url)?;
+            *profile =
+                save_download_profile_to_db(Some(profile.id.clone()), &profile.name, &settings)?;
+        }
+    }
+    Ok(profiles)
+}
+
+/// Create a download profile, or update the one with `id`. Proxy credentials
+/// go to the secret store; the profile keeps the URL without them.
+#[tauri::command]
+pub fn save_download_profile(
+    app: AppHandle,
+    id: Option<String>,
+    name: String,
+    mut settings: DownloadProfileSettings,
+) -> Result<DownloadProfile, String> {
+    let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    settings.proxy_url = store_url_credentials(
+        &app,
+        &profile_proxy_secret_name(&id),
+        settings.proxy_url.as_deref(),
+    )?;
+    save_download_profile_to_db(Some(id), &name, &settings)
+}
+
+#[tauri::command]
+pub fn delete_download_profile(app: AppHandle, id: String) -> Result<(), String> {
+    delete_download_profile_from_db(&id)?;
+    set_secret(&app, &profile_proxy_secret_name(&id), None)
+}
+
+#[tauri::command]
+pub fn duplicate_download_profile(
+    app: AppHandle,
+    id: String,
+    name: Option<String>,
+) -> Result<DownloadProfile, String> {
+    let copy = duplicate_download_profile_in_db(&id, name.as_deref())?;
+    if let Some(proxy_url) = get_secret(&app, &profile_proxy_secret_name(&id))? {
+        set_secret(&app, &profile_proxy_secret_name(&copy.id), Some(&proxy_url))?;
+    }
+    Ok(copy)
+}
+
+/// Recently downloaded URLs with the options they were downloaded with
+#[tauri::command]
+pub fn get_recent_inputs(limit: Option<u32>) -> Result<Vec<RecentInput>, String> {
+    list_recent_inputs_from_db(limit)
+}
+
+/// Suggested defaults for a pasted URL: the options it was last downloaded
+/// with, or those of the last download from the same channel
+#[tauri::command]
+pub fn get_suggested_input_options(url: String) -> Result<Option<SuggestedInputOptions>, String> {
+    suggest_input_options_from_db(&url)
+}
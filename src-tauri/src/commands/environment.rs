@@ -1,7 +1,15 @@
 use std::path::Path;
 
+use crate::utils::portable_data_dir;
+
 #[tauri::command]
 pub fn is_flatpak_environment() -> bool {
     cfg!(target_os = "linux")
         && (std::env::var_os("FLATPAK_ID").is_some() || Path::new("/.flatpak-info").exists())
 }
+
+/// App data lives in `data/` next to the executable
+#[tauri::command]
+pub fn is_portable_mode() -> bool {
+    portable_data_dir().is_some()
+}
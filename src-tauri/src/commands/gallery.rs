@@ -1,16 +1,20 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::{LazyLock, Mutex};
 
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
 use crate::database::add_history_internal;
 use crate::database::add_log_internal;
-use crate::services::{build_cookie_args, get_gallerydl_path, system_gallerydl_not_found_message};
+use crate::services::{
+    build_cookie_args, get_gallerydl_path, kill_process_tree, system_gallerydl_not_found_message,
+    track_active_job, track_child_process, ActiveJob,
+};
 use crate::types::BackendError;
-use crate::utils::{normalize_url, sanitize_output_path, validate_url, CommandExt};
+use crate::utils::{data_dir, normalize_url, sanitize_output_path, validate_url, CommandExt};
 
 const RECENT_OUTPUT_LIMIT: usize = 30;
 
@@ -31,30 +35,45 @@ fn push_recent_output(buffer: &mut VecDeque<String>, line: &str) {
     buffer.push_back(trimmed.to_string());
 }
 
-fn kill_gallery_processes() {
-    #[cfg(unix)]
-    {
-        use std::process::Command as StdCommand;
-        StdCommand::new("pkill")
-            .args(["-9", "-f", "gallery-dl"])
-            .spawn()
-            .ok();
+/// Running gallery-dl processes, so stopping never touches other programs
+static GALLERY_PROCESSES: LazyLock<Mutex<HashSet<u32>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Keeps a gallery-dl process registered until dropped
+struct GalleryProcessGuard {
+    pid: Option<u32>,
+}
+
+impl GalleryProcessGuard {
+    fn new(pid: Option<u32>) -> Self {
+        if let (Some(pid), Ok(mut processes)) = (pid, GALLERY_PROCESSES.lock()) {
+            processes.insert(pid);
+        }
+        Self { pid }
+    }
+}
+
+impl Drop for GalleryProcessGuard {
+    fn drop(&mut self) {
+        if let (Some(pid), Ok(mut processes)) = (self.pid, GALLERY_PROCESSES.lock()) {
+            processes.remove(&pid);
+        }
     }
-    #[cfg(windows)]
-    {
-        use std::process::Command as StdCommand;
-        let mut cmd = StdCommand::new("taskkill");
-        cmd.args(["/F", "/IM", "gallery-dl.exe"]);
-        cmd.hide_window();
-        cmd.spawn().ok();
+}
+
+fn kill_gallery_processes() {
+    let pids: Vec<u32> = GALLERY_PROCESSES
+        .lock()
+        .map(|processes| processes.iter().copied().collect())
+        .unwrap_or_default();
+    for pid in pids {
+        kill_process_tree(pid);
     }
 }
 
 fn archive_file_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir =
+        data_dir(app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
     Ok(app_data_dir.join("archive").join("gallery-dl.txt"))
 }
 
@@ -78,6 +97,7 @@ pub async fn download_gallery(
     proxy_url: Option<String>,
     source: Option<String>,
 ) -> Result<GalleryDownloadResult, String> {
+    let _active_job = track_active_job(ActiveJob::download(&url, &url, &output_path));
     validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
     let url = normalize_url(&url);
 
@@ -130,12 +150,15 @@ pub async fn download_gallery(
     let mut cmd = Command::new(&binary_path);
     cmd.args(&args)
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-    cmd.hide_window();
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    cmd.hide_window().new_process_group();
 
     let mut child = cmd.spawn().map_err(|e| {
         BackendError::from_message(format!("Failed to start gallery-dl: {}", e)).to_wire_string()
     })?;
+    let _gallery_process = GalleryProcessGuard::new(child.id());
+    let _child_process = track_child_process(child.id());
 
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
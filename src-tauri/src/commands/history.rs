@@ -1,18 +1,28 @@
 use std::path::{Path, PathBuf};
+use tauri::AppHandle;
 
 use crate::database::{
     add_history_internal, add_history_with_summary, assign_history_collections_in_db,
     assign_history_tags_in_db, clear_history_from_db, create_collection_in_db,
     delete_collection_from_db, delete_history_from_db, find_duplicate_downloads_in_history_db,
     get_collections_from_db, get_history_count_from_db, get_history_entries_by_ids_from_db,
-    get_history_from_db, get_tags_from_db, remove_history_from_collection_in_db,
-    remove_history_tag_from_db, rename_collection_in_db, update_history_filepath_and_title,
-    update_history_filepath_and_title_by_id, update_history_summary,
+    get_history_from_db, get_history_page_from_db, get_tags_from_db, import_history_records_in_db,
+    prune_history_in_db, remove_history_from_collection_in_db, remove_history_tag_from_db,
+    rename_collection_in_db, set_history_retention_policy, toggle_history_favorite_in_db,
+    update_history_custom_metadata_in_db, update_history_filepath_and_title,
+    update_history_filepath_and_title_by_id, update_history_note_in_db, update_history_summary,
+};
+use crate::services::{
+    load_external_history, set_privacy_mode, source_info_with_icon, verify_history_download,
+    write_playlist, PlaylistTrack,
 };
 use crate::types::{
-    DownloadDuplicateIdentity, DownloadDuplicateMatch, HistoryAdvancedFilters, HistoryCollection,
-    HistoryEntry, HistorySort, HistoryTag,
+    DownloadDuplicateIdentity, DownloadDuplicateMatch, DownloadIntegrityReport,
+    ExternalHistoryKind, HistoryAdvancedFilters, HistoryCollection, HistoryEntry,
+    HistoryImportReport, HistoryPage, HistoryPageQuery, HistoryPruneReport, HistoryRetentionPolicy,
+    HistorySort, HistoryTag, PlaylistExportFormat, PlaylistExportReport, PlaylistSelection,
 };
+use crate::utils::SourceInfo;
 
 #[tauri::command]
 pub fn add_history(
@@ -36,6 +46,21 @@ pub fn update_summary(id: String, summary: String) -> Result<(), String> {
     update_history_summary(id, summary)
 }
 
+/// Annotate a history entry; an empty note clears it
+#[tauri::command]
+pub fn update_history_note(id: String, note: String) -> Result<(), String> {
+    update_history_note_in_db(id, note)
+}
+
+/// Replace the user-defined key/value metadata of a history entry
+#[tauri::command]
+pub fn update_history_metadata(
+    id: String,
+    metadata: serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    update_history_custom_metadata_in_db(id, metadata)
+}
+
 /// Add a summary-only history entry (for videos summarized without downloading)
 #[tauri::command]
 pub fn add_summary_only_history(
@@ -111,6 +136,12 @@ pub fn clear_history() -> Result<(), String> {
     clear_history_from_db()
 }
 
+/// A page of history with the total match count and per-source/format counts
+#[tauri::command]
+pub fn get_history_page(filters: HistoryPageQuery) -> Result<HistoryPage, String> {
+    get_history_page_from_db(filters)
+}
+
 #[tauri::command]
 pub fn get_history_count(
     source: Option<String>,
@@ -120,6 +151,44 @@ pub fn get_history_count(
     get_history_count_from_db(source, search, filters)
 }
 
+/// Sync the retention settings applied whenever a download is added to history
+#[tauri::command]
+pub fn set_history_retention(policy: HistoryRetentionPolicy) -> Result<(), String> {
+    set_history_retention_policy(policy);
+    Ok(())
+}
+
+/// Privacy mode: every download runs incognito, leaving no history or logged URL
+#[tauri::command]
+pub fn set_history_privacy_mode(enabled: bool) -> Result<(), String> {
+    set_privacy_mode(enabled);
+    Ok(())
+}
+
+/// Remove history entries outside `policy` now, optionally deleting their files
+#[tauri::command]
+pub fn prune_history(policy: HistoryRetentionPolicy) -> Result<HistoryPruneReport, String> {
+    let filepaths = prune_history_in_db(&policy)?;
+    let mut report = HistoryPruneReport {
+        entries_removed: filepaths.len(),
+        files_deleted: 0,
+    };
+
+    if policy.delete_files {
+        for filepath in filepaths {
+            if !Path::new(filepath.trim()).is_file() {
+                continue;
+            }
+            match delete_history_media_file(&filepath) {
+                Ok(()) => report.files_deleted += 1,
+                Err(e) => log::warn!("Failed to delete pruned file {}: {}", filepath, e),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
 #[tauri::command]
 pub fn get_tags() -> Result<Vec<HistoryTag>, String> {
     get_tags_from_db()
@@ -158,6 +227,12 @@ pub fn assign_history_collections(
     assign_history_collections_in_db(history_id, collection_ids)
 }
 
+/// Star or unstar a library item; returns whether it is now a favorite
+#[tauri::command]
+pub fn toggle_favorite(history_id: String) -> Result<bool, String> {
+    toggle_history_favorite_in_db(history_id)
+}
+
 #[tauri::command]
 pub fn remove_history_tag(history_id: String, tag_id: String) -> Result<(), String> {
     remove_history_tag_from_db(history_id, tag_id)
@@ -171,6 +246,105 @@ pub fn remove_history_from_collection(
     remove_history_from_collection_in_db(history_id, collection_id)
 }
 
+/// Re-check a downloaded file for bit rot or an incomplete merge
+#[tauri::command]
+pub async fn verify_download(
+    app: AppHandle,
+    history_id: String,
+) -> Result<DownloadIntegrityReport, String> {
+    verify_history_download(&app, &history_id).await
+}
+
+/// Import the "already downloaded" state of another downloader: a yt-dlp
+/// archive file, a Stacher/Open Video Downloader history JSON, or a folder of
+/// `.info.json` sidecars.
+#[tauri::command]
+pub async fn import_external_history(
+    kind: ExternalHistoryKind,
+    path: String,
+) -> Result<HistoryImportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let (records, unreadable) = load_external_history(kind, Path::new(&path))?;
+        let mut report = import_history_records_in_db(kind, records)?;
+        report.scanned += unreadable;
+        report.failed += unreadable;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("History import task failed: {}", e))?
+}
+
+/// Write an M3U8 or XSPF playlist of downloaded files, for VLC, foobar2000
+/// and other players. Entries whose file is gone are left out.
+#[tauri::command]
+pub fn export_playlist(
+    selection: PlaylistSelection,
+    format: PlaylistExportFormat,
+    path: String,
+    relative_paths: Option<bool>,
+    title: Option<String>,
+) -> Result<PlaylistExportReport, String> {
+    let entries = match selection {
+        PlaylistSelection::Ids(ids) => {
+            let mut entries = get_history_entries_by_ids_from_db(ids.clone())?;
+            entries.sort_by_key(|entry| ids.iter().position(|id| id == &entry.id));
+            entries
+        }
+        PlaylistSelection::Filter(query) => {
+            get_history_page_from_db(HistoryPageQuery {
+                limit: None,
+                offset: None,
+                ..*query
+            })?
+            .entries
+        }
+    };
+
+    let selected = entries.len();
+    let tracks: Vec<PlaylistTrack> = entries
+        .into_iter()
+        .filter(|entry| !entry.filepath.is_empty() && Path::new(&entry.filepath).is_file())
+        .map(|entry| PlaylistTrack {
+            title: entry.title,
+            path: PathBuf::from(entry.filepath),
+            duration: entry.duration,
+        })
+        .collect();
+    if tracks.is_empty() {
+        return Err("None of the selected downloads have a file on disk".to_string());
+    }
+
+    let playlist_path = PathBuf::from(&path);
+    let title = title
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .or_else(|| {
+            playlist_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "Youwee".to_string());
+    write_playlist(
+        &playlist_path,
+        format,
+        &title,
+        &tracks,
+        relative_paths.unwrap_or(true),
+    )?;
+
+    Ok(PlaylistExportReport {
+        path,
+        track_count: tracks.len(),
+        skipped_missing: selected - tracks.len(),
+    })
+}
+
+/// Display name and cached favicon path of a history source ("youtube", "peertube")
+#[tauri::command]
+pub async fn get_source_info(app: AppHandle, source: String) -> SourceInfo {
+    source_info_with_icon(&app, source.trim()).await
+}
+
 #[tauri::command]
 pub fn check_file_exists(filepath: String) -> bool {
     std::path::Path::new(&filepath).exists()
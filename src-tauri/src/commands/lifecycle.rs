@@ -0,0 +1,90 @@
+use tauri::AppHandle;
+
+use crate::services::{
+    active_jobs, cancel_post_queue_action, clear_interrupted_downloads, discard_crashed_downloads,
+    dispatch_notification, get_post_queue_action, load_crashed_downloads,
+    load_interrupted_downloads, run_post_queue_action, set_post_queue_action, shutdown_gracefully,
+    ActiveJob, NotificationEvent, NotificationPayload, PostQueueAction,
+};
+use crate::types::{BackendError, DownloadJournalEntry};
+
+/// Jobs that would be interrupted by quitting now
+#[tauri::command]
+pub async fn get_active_jobs() -> Result<Vec<ActiveJob>, String> {
+    Ok(active_jobs())
+}
+
+/// Quit after the user confirmed the `quit-requested` prompt.
+/// Running downloads are interrupted gracefully and saved for resume.
+#[tauri::command]
+pub async fn confirm_quit(app: AppHandle) -> Result<(), String> {
+    shutdown_gracefully(app).await;
+    Ok(())
+}
+
+/// Downloads that were running when the app last quit
+#[tauri::command]
+pub async fn get_interrupted_downloads() -> Result<Vec<ActiveJob>, String> {
+    Ok(load_interrupted_downloads())
+}
+
+/// Forget interrupted downloads after they were resumed or dismissed
+/// (all when `ids` is omitted)
+#[tauri::command]
+pub async fn clear_interrupted_downloads_cmd(ids: Option<Vec<String>>) -> Result<(), String> {
+    clear_interrupted_downloads(ids.as_deref())
+}
+
+/// Downloads lost by a crash or forced exit, from the download journal
+#[tauri::command]
+pub async fn get_crashed_downloads() -> Result<Vec<DownloadJournalEntry>, String> {
+    Ok(load_crashed_downloads())
+}
+
+/// Clean up crashed downloads (all when `ids` is omitted) and their temp files
+#[tauri::command]
+pub async fn discard_crashed_downloads_cmd(
+    app: AppHandle,
+    ids: Option<Vec<String>>,
+) -> Result<u32, String> {
+    Ok(discard_crashed_downloads(&app, ids))
+}
+
+/// Choose what happens when the download queue drains (this session only)
+#[tauri::command]
+pub async fn set_post_queue_action_cmd(action: PostQueueAction) -> Result<(), String> {
+    set_post_queue_action(action);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_post_queue_action_cmd() -> Result<PostQueueAction, String> {
+    Ok(get_post_queue_action())
+}
+
+/// Called by the queue when its last item finished. Sleep/shutdown emit a
+/// `post-queue-action` pending event and run after the countdown unless cancelled.
+#[tauri::command]
+pub async fn notify_queue_finished(
+    app: AppHandle,
+    output_path: Option<String>,
+    completed: Option<u32>,
+    failed: Option<u32>,
+) -> Result<PostQueueAction, String> {
+    let mut payload = NotificationPayload::new(NotificationEvent::QueueFinished);
+    payload.filepath = output_path.clone();
+    payload.completed = completed;
+    payload.failed = failed;
+    dispatch_notification(payload);
+
+    run_post_queue_action(app, output_path, completed)
+        .await
+        .map_err(|e| BackendError::from_message(e).to_wire_string())
+}
+
+/// Stop a pending sleep/shutdown countdown
+#[tauri::command]
+pub async fn cancel_post_queue_action_cmd(app: AppHandle) -> Result<(), String> {
+    cancel_post_queue_action(&app);
+    Ok(())
+}
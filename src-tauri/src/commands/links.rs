@@ -0,0 +1,11 @@
+use crate::services::{extract_link_candidates, LinkCandidate};
+use crate::types::BackendError;
+
+/// Find downloadable video links in pasted text or on a web page, for the
+/// user to pick into a batch download
+#[tauri::command]
+pub async fn extract_links(text_or_url: String) -> Result<Vec<LinkCandidate>, String> {
+    extract_link_candidates(&text_or_url)
+        .await
+        .map_err(|e| BackendError::from_message(e).to_wire_string())
+}
@@ -1,8 +1,8 @@
 use crate::database::{
     add_log_internal, clear_logs_from_db, clear_plugin_logs_from_db, export_logs_from_db,
-    get_logs_from_db, get_plugin_logs_from_db,
+    get_database_recovery_report, get_logs_from_db, get_plugin_logs_from_db,
 };
-use crate::types::{LogEntry, PluginLogsPage};
+use crate::types::{DatabaseRecoveryReport, LogEntry, PluginLogsPage};
 
 #[tauri::command]
 pub fn get_logs(
@@ -46,3 +46,9 @@ pub fn clear_logs() -> Result<(), String> {
 pub fn export_logs() -> Result<String, String> {
     export_logs_from_db()
 }
+
+/// Details of the startup recovery if the database was found corrupt
+#[tauri::command]
+pub fn get_database_recovery() -> Result<Option<DatabaseRecoveryReport>, String> {
+    Ok(get_database_recovery_report())
+}
@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::transcribe_video_with_whisper;
+use crate::database::{add_log_internal, find_history_media, set_history_lyrics_path};
+use crate::services::{
+    build_lrc, get_secret, parse_subtitle_cues, preferred_subtitle_langs,
+    run_ytdlp_with_stderr_and_cookies, sibling_subtitle_files, smart_subtitle_langs, LyricLine,
+    WHISPER_API_KEY_SECRET,
+};
+use crate::types::BackendError;
+
+/// Where the timed lyrics came from
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LyricsSource {
+    /// Subtitle file already saved next to the audio
+    LocalSubtitle,
+    /// Subtitle track fetched from the source site
+    SourceSubtitle,
+    Whisper,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LrcResult {
+    pub lrc_path: String,
+    pub line_count: usize,
+    pub source: LyricsSource,
+    pub history_id: Option<String>,
+}
+
+fn read_cues(path: &Path) -> Vec<LyricLine> {
+    std::fs::read_to_string(path)
+        .map(|content| parse_subtitle_cues(&content))
+        .unwrap_or_default()
+}
+
+/// Download the site's own subtitle track for `url` without the media
+async fn fetch_source_subtitle_cues(app: &AppHandle, url: &str) -> Vec<LyricLine> {
+    let temp_dir = std::env::temp_dir().join(format!("youwee-lyrics-{}", Uuid::new_v4()));
+    if std::fs::create_dir_all(&temp_dir).is_err() {
+        return Vec::new();
+    }
+    let output_template = temp_dir
+        .join("lyrics.%(ext)s")
+        .to_string_lossy()
+        .to_string();
+    let sub_langs = smart_subtitle_langs(None, &preferred_subtitle_langs());
+    let args = [
+        "--skip-download",
+        "--write-subs",
+        "--sub-langs",
+        sub_langs.as_str(),
+        "--sub-format",
+        "vtt/srt/best",
+        "--no-playlist",
+        "--no-warnings",
+        "-o",
+        output_template.as_str(),
+        "--",
+        url,
+    ];
+    let fetched = run_ytdlp_with_stderr_and_cookies(app, &args, None, None, None, None, None, None)
+        .await
+        .is_ok_and(|output| output.success);
+
+    let cues = if fetched {
+        sibling_subtitle_files(&temp_dir.join("lyrics"))
+            .iter()
+            .map(|path| read_cues(path))
+            .find(|cues| !cues.is_empty())
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    std::fs::remove_dir_all(&temp_dir).ok();
+    cues
+}
+
+/// Write an `.lrc` lyrics file next to a downloaded track.
+///
+/// Timed lines come from a subtitle file saved next to the audio, else the
+/// source's subtitle track, else (with `use_whisper`) a Whisper transcription.
+#[tauri::command]
+pub async fn generate_lrc(
+    app: AppHandle,
+    path_or_url: String,
+    use_whisper: Option<bool>,
+    whisper_api_key: Option<String>,
+    language: Option<String>,
+    whisper_endpoint_url: Option<String>,
+    whisper_model: Option<String>,
+) -> Result<LrcResult, String> {
+    let path_or_url = path_or_url.trim().to_string();
+    let history = find_history_media(&path_or_url).unwrap_or(None);
+    let media_path = if Path::new(&path_or_url).is_file() {
+        PathBuf::from(&path_or_url)
+    } else {
+        history
+            .as_ref()
+            .map(|(_, _, filepath, _)| PathBuf::from(filepath))
+            .filter(|path| path.is_file())
+            .ok_or_else(|| {
+                BackendError::from_message("Download this track before generating lyrics")
+                    .to_wire_string()
+            })?
+    };
+    let source_url = history
+        .as_ref()
+        .map(|(_, url, _, _)| url.clone())
+        .or_else(|| Some(path_or_url.clone()).filter(|value| value.starts_with("http")));
+    let title = history.as_ref().map(|(_, _, _, title)| title.clone());
+
+    let mut source = LyricsSource::LocalSubtitle;
+    let mut cues = sibling_subtitle_files(&media_path)
+        .iter()
+        .map(|path| read_cues(path))
+        .find(|cues| !cues.is_empty())
+        .unwrap_or_default();
+
+    if cues.is_empty() {
+        if let Some(url) = source_url.as_deref() {
+            source = LyricsSource::SourceSubtitle;
+            cues = fetch_source_subtitle_cues(&app, url).await;
+        }
+    }
+
+    if cues.is_empty() && use_whisper.unwrap_or(false) {
+        let api_key = match whisper_api_key.filter(|key| !key.trim().is_empty()) {
+            Some(key) => key,
+            None => get_secret(&app, WHISPER_API_KEY_SECRET)?.unwrap_or_default(),
+        };
+        let srt = transcribe_video_with_whisper(
+            app.clone(),
+            media_path.to_string_lossy().to_string(),
+            "srt".to_string(),
+            api_key,
+            language,
+            whisper_endpoint_url,
+            whisper_model,
+        )
+        .await?;
+        source = LyricsSource::Whisper;
+        cues = parse_subtitle_cues(&srt);
+    }
+
+    if cues.is_empty() {
+        return Err(BackendError::from_message(
+            "No synced subtitles found for this track. Enable Whisper to transcribe the lyrics.",
+        )
+        .to_wire_string());
+    }
+
+    let lrc_path = media_path.with_extension("lrc");
+    std::fs::write(&lrc_path, build_lrc(&cues, title.as_deref()))
+        .map_err(|e| format!("Failed to save lyrics file: {}", e))?;
+    let lrc_path = lrc_path.to_string_lossy().to_string();
+
+    let history_id = history.map(|(id, _, _, _)| id);
+    if let Some(id) = &history_id {
+        set_history_lyrics_path(id, Some(&lrc_path))?;
+    }
+    add_log_internal(
+        "success",
+        &format!("Saved lyrics to: {}", lrc_path),
+        None,
+        source_url.as_deref(),
+    )
+    .ok();
+
+    Ok(LrcResult {
+        lrc_path,
+        line_count: cues.len(),
+        source,
+        history_id,
+    })
+}
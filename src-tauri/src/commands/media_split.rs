@@ -1,16 +1,13 @@
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
 
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
-use tokio::process::Command;
 
 use crate::database::{
     add_history_internal, assign_history_collections_in_db, delete_history_from_db,
     ensure_collection_for_download_in_db,
 };
-use crate::services::get_ffmpeg_path;
-use crate::utils::CommandExt;
+use crate::services::{get_ffmpeg_path, FfmpegRunner};
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -53,7 +50,7 @@ pub struct MediaSplitResult {
     pub segments: Vec<MediaSplitSegmentResult>,
 }
 
-fn sanitize_filename_part(value: &str, fallback: &str) -> String {
+pub(crate) fn sanitize_filename_part(value: &str, fallback: &str) -> String {
     let sanitized = value
         .chars()
         .map(|ch| match ch {
@@ -192,8 +189,7 @@ pub async fn split_media_segments(
         let start = format_timestamp(segment.start_seconds);
         let end = format_timestamp(segment.end_seconds);
 
-        let mut cmd = Command::new(&ffmpeg_path);
-        cmd.args([
+        let args: Vec<String> = [
             "-hide_banner",
             "-nostdin",
             "-y",
@@ -202,25 +198,23 @@ pub async fn split_media_segments(
             "-to",
             &end,
             "-i",
-        ])
-        .arg(&input_path)
-        .args(["-map", "0", "-c", "copy", "-avoid_negative_ts", "make_zero"])
-        .arg(&output_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-        cmd.hide_window();
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .chain([input_path.to_string_lossy().to_string()])
+        .chain(["-map", "0", "-c", "copy", "-avoid_negative_ts", "make_zero"].map(str::to_string))
+        .chain([output_path.to_string_lossy().to_string()])
+        .collect();
 
-        let output = cmd
-            .output()
-            .await
-            .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        let result = FfmpegRunner::new(&ffmpeg_path, args)
+            .run(None, |_| {})
+            .await;
+        if let Err(e) = result {
+            tokio::fs::remove_file(&output_path).await.ok();
             rollback_split_outputs(&created_output_paths, &created_history_ids).await;
             return Err(format!(
                 "FFmpeg failed while splitting {}: {}",
-                segment_name,
-                stderr.lines().last().unwrap_or("unknown error")
+                segment_name, e
             ));
         }
 
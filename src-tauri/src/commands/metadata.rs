@@ -6,33 +6,25 @@ use rusqlite::{params_from_iter, Connection};
 use std::path::Path;
 use std::process::Stdio;
 use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
 use crate::database::{add_history_internal, add_log_internal};
 use crate::services::{
     add_safe_filename_args, build_cookie_args, build_site_header_args, get_deno_path,
-    get_ffmpeg_path, get_ytdlp_path, get_ytdlp_source, run_ytdlp_with_stderr_and_cookies,
-    search_youtube_videos_internal, system_ytdlp_not_found_message,
+    get_ffmpeg_path, get_ytdlp_path, get_ytdlp_source, metadata_network_args,
+    run_ytdlp_with_stderr_and_cookies, search_youtube_videos_internal,
+    system_ytdlp_not_found_message, ytdlp_process_env,
+};
+use crate::types::{
+    BackendError, DependencySource, MetadataProgress, YoutubeSearchVideo, METADATA_PROGRESS,
 };
-use crate::types::{BackendError, DependencySource, YoutubeSearchVideo};
 use crate::utils::{normalize_url, sanitize_output_path, validate_url, CommandExt};
 
 pub static METADATA_CANCEL_FLAG: AtomicBool = AtomicBool::new(false);
 pub static DATA_EXPORT_CANCEL_FLAG: AtomicBool = AtomicBool::new(false);
 
-#[derive(Clone, serde::Serialize)]
-pub struct MetadataProgress {
-    pub id: String,
-    pub status: String, // "fetching", "finished", "error"
-    pub title: Option<String>,
-    pub thumbnail: Option<String>,
-    pub error_message: Option<String>,
-    pub error_code: Option<String>,
-    pub error_params: Option<serde_json::Value>,
-}
-
 #[tauri::command]
 pub fn cancel_metadata_fetch() {
     METADATA_CANCEL_FLAG.store(true, Ordering::SeqCst);
@@ -395,12 +387,8 @@ async fn run_export_ytdlp(
     let normalized_url = normalize_url(url);
     let is_youtube = normalized_url.contains("youtube.com") || normalized_url.contains("youtu.be");
 
-    let mut args = vec![
-        "--dump-json".to_string(),
-        "--no-warnings".to_string(),
-        "--socket-timeout".to_string(),
-        "30".to_string(),
-    ];
+    let mut args = vec!["--dump-json".to_string(), "--no-warnings".to_string()];
+    args.extend(metadata_network_args());
 
     if matches!(source, ExportSource::UrlList) {
         args.push("--no-playlist".to_string());
@@ -841,34 +829,24 @@ pub async fn fetch_metadata(
     args.push(url.clone());
 
     // Emit initial progress
-    app.emit(
-        "metadata-progress",
-        MetadataProgress {
-            id: id.clone(),
-            status: "fetching".to_string(),
-            title: None,
-            thumbnail: None,
-            error_message: None,
-            error_code: None,
-            error_params: None,
-        },
-    )
-    .ok();
+    METADATA_PROGRESS
+        .emit(
+            &app,
+            &MetadataProgress {
+                id: id.clone(),
+                status: "fetching".to_string(),
+                title: None,
+                thumbnail: None,
+                error_message: None,
+                error_code: None,
+                error_params: None,
+            },
+        )
+        .ok();
 
     // Get yt-dlp path
     if let Some((binary_path, is_bundled)) = get_ytdlp_path(&app).await {
-        let home_dir = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
-        let mut path_entries: Vec<std::path::PathBuf> = std::env::var_os("PATH")
-            .map(|paths| std::env::split_paths(&paths).collect())
-            .unwrap_or_default();
-        path_entries.extend([
-            std::path::PathBuf::from(&home_dir).join(".deno/bin"),
-            std::path::PathBuf::from(&home_dir).join(".bun/bin"),
-            std::path::PathBuf::from("/opt/homebrew/bin"),
-            std::path::PathBuf::from("/usr/local/bin"),
-        ]);
-        let extended_path = std::env::join_paths(path_entries)
-            .unwrap_or_else(|_| std::env::var_os("PATH").unwrap_or_default());
+        let env = ytdlp_process_env(&app, &args).await;
 
         // Log command with binary path info (same format as download.rs)
         let binary_info = format!("{} (bundled: {})", binary_path.display(), is_bundled);
@@ -877,11 +855,9 @@ pub async fn fetch_metadata(
 
         let mut cmd = Command::new(&binary_path);
         cmd.args(&args)
-            .env("HOME", &home_dir)
-            .env("PATH", &extended_path)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
-        cmd.hide_window();
+        cmd.process_env(&env).hide_window().utf8_output();
 
         let mut process = cmd.spawn().map_err(|e| {
             BackendError::from_message(format!("Failed to start yt-dlp: {}", e)).to_wire_string()
@@ -925,7 +901,7 @@ pub async fn fetch_metadata(
                                         video_duration = Some(dur as i64);
                                     }
 
-                                    app.emit("metadata-progress", MetadataProgress {
+                                    METADATA_PROGRESS.emit(&app, &MetadataProgress {
                                         id: id.clone(),
                                         status: "fetching".to_string(),
                                         title: video_title.clone(),
@@ -937,7 +913,7 @@ pub async fn fetch_metadata(
                                 }
                             } else if video_title.is_none() && !text.is_empty() && !text.starts_with("[") {
                                 video_title = Some(text.clone());
-                                app.emit("metadata-progress", MetadataProgress {
+                                METADATA_PROGRESS.emit(&app, &MetadataProgress {
                                     id: id.clone(),
                                     status: "fetching".to_string(),
                                     title: Some(text),
@@ -1056,38 +1032,40 @@ pub async fn fetch_metadata(
             )
             .ok();
 
-            app.emit(
-                "metadata-progress",
-                MetadataProgress {
-                    id: id.clone(),
-                    status: "finished".to_string(),
-                    title: video_title,
-                    thumbnail: video_thumbnail,
-                    error_message: None,
-                    error_code: None,
-                    error_params: None,
-                },
-            )
-            .ok();
+            METADATA_PROGRESS
+                .emit(
+                    &app,
+                    &MetadataProgress {
+                        id: id.clone(),
+                        status: "finished".to_string(),
+                        title: video_title,
+                        thumbnail: video_thumbnail,
+                        error_message: None,
+                        error_code: None,
+                        error_params: None,
+                    },
+                )
+                .ok();
             Ok(())
         } else {
             let err_msg = error_message.unwrap_or_else(|| "Failed to fetch metadata".to_string());
             let backend_err = BackendError::from_message(err_msg.clone());
             add_log_internal("error", &err_msg, None, Some(&url)).ok();
 
-            app.emit(
-                "metadata-progress",
-                MetadataProgress {
-                    id: id.clone(),
-                    status: "error".to_string(),
-                    title: video_title,
-                    thumbnail: video_thumbnail,
-                    error_message: Some(err_msg.clone()),
-                    error_code: Some(backend_err.code().to_string()),
-                    error_params: backend_err.params().cloned(),
-                },
-            )
-            .ok();
+            METADATA_PROGRESS
+                .emit(
+                    &app,
+                    &MetadataProgress {
+                        id: id.clone(),
+                        status: "error".to_string(),
+                        title: video_title,
+                        thumbnail: video_thumbnail,
+                        error_message: Some(err_msg.clone()),
+                        error_code: Some(backend_err.code().to_string()),
+                        error_params: backend_err.params().cloned(),
+                    },
+                )
+                .ok();
             Err(backend_err.to_wire_string())
         }
     } else {
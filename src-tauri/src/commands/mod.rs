@@ -1,20 +1,39 @@
 mod ai;
 mod assets;
+mod cache;
+mod channel_rules;
 mod channels;
 mod cli;
 mod cli_shortcut;
+mod compatibility;
 mod dependencies;
+mod diagnostics;
+mod direct_download;
 mod download;
+mod download_profiles;
 mod download_queue;
 mod environment;
 mod external;
 mod gallery;
 mod history;
+mod lifecycle;
+mod links;
 mod logs;
+mod lyrics;
 mod media_split;
 mod metadata;
+mod network;
+mod notifications;
 mod plugin;
+mod podcast;
+mod power;
 mod processing;
+mod search;
+mod secrets;
+mod settings_transfer;
+mod setup;
+mod share;
+mod subtitles;
 mod telegram;
 mod video;
 mod whisper;
@@ -22,21 +41,40 @@ mod youtube_search;
 
 pub use ai::*;
 pub use assets::*;
+pub use cache::*;
+pub use channel_rules::*;
 pub use channels::*;
 pub use cli::*;
 pub use cli_shortcut::*;
+pub use compatibility::*;
 pub use dependencies::*;
+pub use diagnostics::*;
+pub use direct_download::*;
 pub use download::*;
+pub use download_profiles::*;
 pub use download_queue::*;
 pub use environment::*;
 pub use external::*;
 pub use gallery::*;
 pub use history::*;
+pub use lifecycle::*;
+pub use links::*;
 pub use logs::*;
+pub use lyrics::*;
 pub use media_split::*;
 pub use metadata::*;
+pub use network::*;
+pub use notifications::*;
 pub use plugin::*;
+pub use podcast::*;
+pub use power::*;
 pub use processing::*;
+pub use search::*;
+pub use secrets::*;
+pub use settings_transfer::*;
+pub use setup::*;
+pub use share::*;
+pub use subtitles::*;
 pub use telegram::*;
 pub use video::*;
 pub use whisper::*;
@@ -0,0 +1,34 @@
+use crate::services::{
+    probe_connection_speed, set_metadata_network_settings, set_network_profile_config,
+    ConnectionSpeed, MetadataNetworkSettings, NetworkProfile, NetworkTuning,
+};
+use crate::types::BackendError;
+
+/// Sync the network profile setting from the frontend. Applies to downloads
+/// started after the call; `custom` is required for the custom profile.
+#[tauri::command]
+pub async fn set_network_profile(
+    profile: NetworkProfile,
+    custom: Option<NetworkTuning>,
+) -> Result<(), String> {
+    set_network_profile_config(profile, custom)
+        .map_err(|e| BackendError::from_message(e).to_wire_string())
+}
+
+/// Sync the socket timeout and retries used by metadata fetches (video
+/// info, playlists, search, comments and channel checks)
+#[tauri::command]
+pub async fn set_metadata_network(settings: MetadataNetworkSettings) -> Result<(), String> {
+    set_metadata_network_settings(settings);
+    Ok(())
+}
+
+/// Measure download bandwidth and suggest a network profile for it
+#[tauri::command]
+pub async fn measure_connection_speed(
+    proxy_url: Option<String>,
+) -> Result<ConnectionSpeed, String> {
+    probe_connection_speed(proxy_url.as_deref())
+        .await
+        .map_err(|e| BackendError::from_message(e).to_wire_string())
+}
@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use tauri::AppHandle;
+
+use crate::services::{
+    get_secret, get_webhooks, set_secret, set_webhooks, webhook_secret_name, WebhookConfig,
+};
+use crate::types::BackendError;
+
+const WEBHOOK_SECRET_FIELDS: [&str; 2] = ["url", "bot_token"];
+
+fn webhook_secret_field<'a>(hook: &'a mut WebhookConfig, field: &str) -> &'a mut String {
+    if field == "url" {
+        &mut hook.url
+    } else {
+        &mut hook.bot_token
+    }
+}
+
+/// Keep webhook URLs and bot tokens in the secret store. Integrations synced
+/// without them get the stored values back; removed ones are forgotten.
+pub(crate) fn sync_webhook_secrets(
+    app: &AppHandle,
+    webhooks: &mut [WebhookConfig],
+) -> Result<(), String> {
+    for hook in webhooks.iter_mut() {
+        for field in WEBHOOK_SECRET_FIELDS {
+            let name = webhook_secret_name(&hook.id, field);
+            let value = webhook_secret_field(hook, field);
+            if value.trim().is_empty() {
+                *value = get_secret(app, &name)?.unwrap_or_default();
+            } else {
+                set_secret(app, &name, Some(value.trim()))?;
+            }
+        }
+    }
+
+    let kept: HashSet<&str> = webhooks.iter().map(|hook| hook.id.as_str()).collect();
+    for removed in get_webhooks()
+        .iter()
+        .filter(|hook| !kept.contains(hook.id.as_str()))
+    {
+        for field in WEBHOOK_SECRET_FIELDS {
+            set_secret(app, &webhook_secret_name(&removed.id, field), None)?;
+        }
+    }
+    Ok(())
+}
+
+/// Sync webhook / external command integrations from the frontend settings
+#[tauri::command]
+pub fn set_notification_webhooks(
+    app: AppHandle,
+    mut webhooks: Vec<WebhookConfig>,
+) -> Result<(), String> {
+    if let Err(e) = sync_webhook_secrets(&app, &mut webhooks) {
+        log::warn!("Notification secrets not stored: {}", e);
+    }
+    set_webhooks(webhooks);
+    Ok(())
+}
+
+/// Send a test event through one integration
+#[tauri::command]
+pub async fn test_webhook(id: String) -> Result<(), String> {
+    crate::services::test_webhook(&id)
+        .await
+        .map_err(|e| BackendError::from_message(e).to_wire_string())
+}
@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use tauri::AppHandle;
+
+use super::direct_download::DirectDownloadResult;
+use super::download::CANCEL_FLAG;
+use crate::database::{add_history_internal, add_log_internal};
+use crate::services::{
+    fetch_podcast_feed, get_ffmpeg_path, stream_direct_download, track_active_job, ActiveJob,
+    FfmpegRunner,
+};
+use crate::types::{
+    BackendError, DownloadProgress, PodcastEpisode, PodcastFeed, DOWNLOAD_PROGRESS,
+};
+use crate::utils::{normalize_url, resolve_output_directory, validate_url};
+
+/// Fetch a podcast RSS feed and list its downloadable episodes
+#[tauri::command]
+pub async fn get_podcast_feed(
+    url: String,
+    proxy_url: Option<String>,
+) -> Result<PodcastFeed, String> {
+    validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let url = normalize_url(&url);
+    fetch_podcast_feed(&url, proxy_url.as_deref())
+        .await
+        .map_err(|e| BackendError::from_message(e).to_wire_string())
+}
+
+/// ffmpeg arguments that copy the audio stream and write feed metadata as tags
+fn build_podcast_tag_args(
+    input: &Path,
+    output: &Path,
+    feed: &PodcastFeed,
+    episode: &PodcastEpisode,
+) -> Vec<String> {
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        input.to_string_lossy().to_string(),
+        "-map".to_string(),
+        "0".to_string(),
+        "-c".to_string(),
+        "copy".to_string(),
+    ];
+    let mut push_tag = |key: &str, value: Option<&str>| {
+        if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+            args.push("-metadata".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+    };
+
+    push_tag("title", Some(&episode.title));
+    push_tag("artist", feed.author.as_deref().or(Some(&feed.title)));
+    push_tag("album_artist", feed.author.as_deref());
+    push_tag("album", Some(&feed.title));
+    push_tag("genre", Some("Podcast"));
+    // ID3 TDRC only needs the date part of the RFC 3339 timestamp
+    push_tag(
+        "date",
+        episode
+            .published_at
+            .as_deref()
+            .and_then(|date| date.get(..10)),
+    );
+    let track = episode.episode_number.map(|n| n.to_string());
+    push_tag("track", track.as_deref());
+    push_tag("comment", episode.description.as_deref());
+
+    if output
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mp3"))
+    {
+        // v2.3 is what most car stereos and older players read
+        args.push("-id3v2_version".to_string());
+        args.push("3".to_string());
+    }
+    args.push(output.to_string_lossy().to_string());
+    args
+}
+
+async fn tag_podcast_episode(
+    app: &AppHandle,
+    filepath: &Path,
+    feed: &PodcastFeed,
+    episode: &PodcastEpisode,
+) -> Result<(), String> {
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .ok_or_else(|| "FFmpeg not found, episode saved without tags".to_string())?;
+    let ext = filepath
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("mp3");
+    let tagged: PathBuf = filepath.with_extension(format!("tagged.{}", ext));
+
+    let result = FfmpegRunner::new(
+        ffmpeg_path,
+        build_podcast_tag_args(filepath, &tagged, feed, episode),
+    )
+    .run(None, |_| {})
+    .await;
+    if let Err(e) = result {
+        std::fs::remove_file(&tagged).ok();
+        return Err(format!("Failed to tag episode: {}", e));
+    }
+
+    std::fs::rename(&tagged, filepath).map_err(|e| format!("Failed to replace episode: {}", e))
+}
+
+/// Download one feed episode through the direct-download path and tag it from the feed
+#[tauri::command]
+pub async fn download_podcast_episode(
+    app: AppHandle,
+    id: String,
+    feed: PodcastFeed,
+    episode: PodcastEpisode,
+    output_path: String,
+    proxy_url: Option<String>,
+    write_tags: Option<bool>,
+) -> Result<DirectDownloadResult, String> {
+    CANCEL_FLAG.store(false, Ordering::SeqCst);
+    let _active_job = track_active_job(ActiveJob::download(
+        &id,
+        &episode.enclosure_url,
+        &output_path,
+    ));
+    validate_url(&episode.enclosure_url)
+        .map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let output_dir =
+        resolve_output_directory(&output_path, true).map_err(|e| e.to_wire_string())?;
+
+    let outcome = match stream_direct_download(
+        &app,
+        &id,
+        &episode.enclosure_url,
+        Path::new(&output_dir),
+        proxy_url.as_deref(),
+        Some(&episode.title),
+        &CANCEL_FLAG,
+    )
+    .await
+    {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            add_log_internal("error", error.message(), None, Some(&episode.enclosure_url)).ok();
+            return Err(error.to_wire_string());
+        }
+    };
+
+    if write_tags.unwrap_or(true) {
+        if let Err(e) =
+            tag_podcast_episode(&app, Path::new(&outcome.filepath), &feed, &episode).await
+        {
+            add_log_internal("stderr", &e, None, Some(&episode.enclosure_url)).ok();
+        }
+    }
+
+    let filesize = std::fs::metadata(&outcome.filepath)
+        .map(|m| m.len())
+        .unwrap_or(outcome.filesize);
+    let format = Path::new(&outcome.filepath)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    let history_id = add_history_internal(
+        episode.enclosure_url.clone(),
+        episode.title.clone(),
+        episode.image.clone().or_else(|| feed.image.clone()),
+        outcome.filepath.clone(),
+        Some(filesize),
+        episode.duration_seconds,
+        Some("audio".to_string()),
+        format.clone(),
+        Some("podcast".to_string()),
+        None,
+    )
+    .ok();
+
+    add_log_internal(
+        "success",
+        &format!("Downloaded: {}", episode.title),
+        Some(&format!("Podcast: {}", feed.title)),
+        Some(&episode.enclosure_url),
+    )
+    .ok();
+
+    DOWNLOAD_PROGRESS
+        .emit(
+            &app,
+            &DownloadProgress {
+                id,
+                percent: 100.0,
+                speed: String::new(),
+                eta: String::new(),
+                status: "finished".to_string(),
+                title: Some(episode.title.clone()),
+                playlist_index: None,
+                playlist_count: None,
+                filesize: Some(filesize),
+                resolution: None,
+                format_ext: format,
+                error_message: None,
+                error_code: None,
+                error_params: None,
+                history_id: history_id.clone(),
+                filepath: Some(outcome.filepath.clone()),
+                downloaded_size: None,
+                elapsed_time: None,
+                stage: None,
+                stage_detail: None,
+                indeterminate: false,
+            },
+        )
+        .ok();
+
+    Ok(DirectDownloadResult {
+        filepath: outcome.filepath,
+        filesize,
+        history_id,
+        resumed: outcome.resumed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_podcast_tag_args_writes_id3_from_feed() {
+        let feed = PodcastFeed {
+            title: "Dev & Coffee".to_string(),
+            author: Some("Jane Host".to_string()),
+            ..Default::default()
+        };
+        let episode = PodcastEpisode {
+            title: "Episode 2".to_string(),
+            published_at: Some("2024-01-02T10:00:00+00:00".to_string()),
+            episode_number: Some(2),
+            ..Default::default()
+        };
+
+        let args = build_podcast_tag_args(
+            Path::new("/tmp/ep.mp3"),
+            Path::new("/tmp/ep.tagged.mp3"),
+            &feed,
+            &episode,
+        );
+
+        assert!(args.contains(&"title=Episode 2".to_string()));
+        assert!(args.contains(&"artist=Jane Host".to_string()));
+        assert!(args.contains(&"album=Dev & Coffee".to_string()));
+        assert!(args.contains(&"date=2024-01-02".to_string()));
+        assert!(args.contains(&"track=2".to_string()));
+        assert!(args.contains(&"-id3v2_version".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("/tmp/ep.tagged.mp3"));
+    }
+}
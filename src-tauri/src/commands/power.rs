@@ -0,0 +1,12 @@
+use crate::services::{set_sleep_prevention_config, SleepInhibitMode};
+
+/// Sync the sleep prevention setting from the frontend.
+/// `mode` picks whether the display may still turn off while jobs run.
+#[tauri::command]
+pub async fn set_sleep_prevention(
+    enabled: bool,
+    mode: Option<SleepInhibitMode>,
+) -> Result<(), String> {
+    set_sleep_prevention_config(enabled, mode.unwrap_or_default());
+    Ok(())
+}
@@ -1,37 +1,85 @@
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
 use std::sync::LazyLock;
-use tauri::{AppHandle, Emitter, Manager};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
+use tauri::AppHandle;
 use tokio::sync::Mutex;
 
+use crate::commands::{ai_config_for_feature, generate_raw_tracked};
 use crate::database::get_db;
-use crate::services::{generate_raw, get_ffmpeg_path, AIConfig};
+use crate::services::{
+    apply_ffmpeg_thread_limit, enforce_cache_limit, get_ffmpeg_path, get_ffprobe_path,
+    set_process_priority_config, touch_cache_entry, track_active_job, AIConfig, AIFeature,
+    ActiveJob, FfmpegError, FfmpegRunner, ProcessPriorityConfig, ACTIVE_JOBS,
+};
+use crate::types::{
+    AudioBatchProgress, PreviewProgress, ProcessingProgress, AUDIO_BATCH_PROGRESS,
+    PREVIEW_PROGRESS, PROCESSING_PROGRESS,
+};
 use crate::utils::{
-    args_to_display_command, find_system_binary, parse_ffmpeg_command_args,
-    unix_system_binary_dirs, validate_ffmpeg_args, CommandExt,
+    args_to_display_command, data_dir, parse_ffmpeg_command_args, unique_output_path,
+    validate_ffmpeg_args,
 };
 
+#[path = "processing/animated.rs"]
+mod animated;
 #[path = "processing/attachments.rs"]
 mod attachments;
+#[path = "processing/audio_batch.rs"]
+mod audio_batch;
+#[path = "processing/burn_subtitles.rs"]
+mod burn_subtitles;
+#[path = "processing/chapters.rs"]
+mod chapters;
+#[path = "processing/clips.rs"]
+mod clips;
+#[path = "processing/compare.rs"]
+mod compare;
+#[path = "processing/estimate.rs"]
+mod estimate;
+#[path = "processing/frames.rs"]
+mod frames;
+#[path = "processing/from_history.rs"]
+mod from_history;
+#[path = "processing/job_graph.rs"]
+mod job_graph;
 #[path = "processing/jobs.rs"]
 mod jobs;
+#[path = "processing/lossless.rs"]
+mod lossless;
 #[path = "processing/metadata.rs"]
 mod metadata;
+#[path = "processing/plans.rs"]
+mod plans;
 #[path = "processing/preview.rs"]
 mod preview;
-
+#[path = "processing/prompt_templates.rs"]
+mod prompt_templates;
+#[path = "processing/recipes.rs"]
+mod recipes;
+#[path = "processing/silence.rs"]
+mod silence;
+
+use animated::*;
 pub use attachments::*;
+pub use audio_batch::*;
+use burn_subtitles::*;
+pub use chapters::*;
+pub use clips::*;
+pub use compare::*;
+pub use estimate::*;
+pub use frames::*;
+pub use from_history::*;
+pub use job_graph::*;
 pub use jobs::*;
+pub use lossless::*;
 pub use metadata::*;
+pub use plans::*;
 pub use preview::*;
-
-static ACTIVE_JOBS: LazyLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>> =
-    LazyLock::new(|| Mutex::new(HashMap::new()));
+pub use prompt_templates::*;
+pub use recipes::*;
+pub use silence::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VideoMetadata {
@@ -60,18 +108,6 @@ pub struct FFmpegCommandResult {
     pub warnings: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProcessingProgress {
-    pub job_id: String,
-    pub percent: f64,
-    pub frame: i64,
-    pub total_frames: i64,
-    pub fps: f64,
-    pub speed: String,
-    pub time: String,
-    pub size: String,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessingJob {
     pub id: String,
@@ -85,6 +121,12 @@ pub struct ProcessingJob {
     pub error_message: Option<String>,
     pub created_at: String,
     pub completed_at: Option<String>,
+    /// Output file size in bytes, recorded on completion
+    #[serde(default)]
+    pub output_size: Option<u64>,
+    /// Output duration in seconds, recorded on completion
+    #[serde(default)]
+    pub output_duration: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -126,6 +168,27 @@ pub struct ShotDetectionResult {
     pub min_interval_ms: i64,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SilenceRange {
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SilenceDetectionResult {
+    pub ranges: Vec<SilenceRange>,
+    pub total_silence_seconds: f64,
+    pub threshold_db: f64,
+    pub min_silence_seconds: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterMarker {
+    pub title: String,
+    pub start_seconds: f64,
+    pub end_seconds: Option<f64>,
+}
+
 fn contains_any(haystack: &str, needles: &[&str]) -> bool {
     needles.iter().any(|k| haystack.contains(k))
 }
@@ -474,7 +537,19 @@ pub async fn generate_processing_command(
     metadata: VideoMetadata,
     attachments: Option<Vec<ProcessingAttachment>>,
     output_dir: Option<String>,
+    template_id: Option<String>,
+    template_context: Option<HashMap<String, String>>,
 ) -> Result<FFmpegCommandResult, String> {
+    // A saved template replaces the typed request; caller values override the built-ins
+    let user_prompt = match template_id {
+        Some(template_id) => {
+            let mut context = builtin_prompt_context(&metadata, timeline_start, timeline_end);
+            context.extend(template_context.unwrap_or_default());
+            render_saved_prompt(&template_id, &context)?
+        }
+        None => user_prompt,
+    };
+
     let selection_info = if let (Some(start), Some(end)) = (timeline_start, timeline_end) {
         format!(
             "Timeline selection: {} to {} ({} seconds)",
@@ -635,14 +710,12 @@ For valid video requests:
         user_prompt,
     );
 
-    let config = load_ai_config(&app).await?;
+    let config = ai_config_for_feature(&app, AIFeature::ProcessingCommand).await?;
     if !config.enabled {
         return Err("AI is not enabled. Please configure AI in Settings.".to_string());
     }
 
-    let result = generate_raw(&config, &ai_prompt)
-        .await
-        .map_err(|e| e.to_wire_string())?;
+    let result = generate_raw_tracked(AIFeature::ProcessingCommand, &config, &ai_prompt).await?;
 
     #[cfg(debug_assertions)]
     {
@@ -787,7 +860,9 @@ For valid video requests:
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_quick_action_command(
+    app: AppHandle,
     input_path: String,
     task_type: String,
     options: HashMap<String, serde_json::Value>,
@@ -1086,37 +1161,15 @@ pub async fn generate_quick_action_command(
                 format!("Extract thumbnail at {}", format_time(time)),
             )
         }
-        "gif" => {
-            let start = timeline_start.unwrap_or(0.0);
-            let end = timeline_end.unwrap_or(start + 5.0);
-            let duration = end - start;
-            let output = output_base_dir.join(format!("{}_{}.gif", input_stem, timestamp));
-
-            let args = vec![
-                "-y".to_string(),
-                "-ss".to_string(),
-                format_time(start),
-                "-t".to_string(),
-                duration.to_string(),
-                "-i".to_string(),
-                input_path.clone(),
-                "-vf".to_string(),
-                "fps=15,scale=480:-1:flags=lanczos".to_string(),
-                "-progress".to_string(),
-                "pipe:2".to_string(),
-                output.to_string_lossy().to_string(),
-            ];
-
-            (
-                args,
-                output.to_string_lossy().to_string(),
-                format!(
-                    "Create GIF from {} to {}",
-                    format_time(start),
-                    format_time(end)
-                ),
-            )
-        }
+        "gif" => animated_export_args(
+            &input_path,
+            &output_base_dir,
+            &input_stem,
+            &timestamp,
+            timeline_start,
+            timeline_end,
+            &options,
+        )?,
         "rotate" => {
             let degrees = options
                 .get("degrees")
@@ -1151,6 +1204,35 @@ pub async fn generate_quick_action_command(
                 format!("Rotate video {}°", degrees),
             )
         }
+        "burn_subtitles" => burn_subtitles_args(
+            &input_path,
+            &output_base_dir,
+            &input_stem,
+            &timestamp,
+            &options,
+        )?,
+        "remove_silence" => {
+            let remove_internal = options
+                .get("remove_internal")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let trailing_start = if remove_internal {
+                None
+            } else {
+                let ffmpeg_path = get_ffmpeg_path(&app).await.ok_or(
+                    "FFmpeg not found. Please install FFmpeg from Settings > Dependencies.",
+                )?;
+                detect_trailing_silence(ffmpeg_path, &input_path, &options).await?
+            };
+            remove_silence_args(
+                &input_path,
+                &output_base_dir,
+                &input_stem,
+                &timestamp,
+                &options,
+                trailing_start,
+            )
+        }
         _ => return Err(format!("Unknown task type: {}", task_type)),
     };
 
@@ -1168,59 +1250,6 @@ pub async fn generate_quick_action_command(
     })
 }
 
-async fn get_ffprobe_path(app: &AppHandle) -> Option<std::path::PathBuf> {
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
-        let bin_dir = app_data_dir.join("bin");
-        #[cfg(windows)]
-        let ffprobe_path = bin_dir.join("ffprobe.exe");
-        #[cfg(not(windows))]
-        let ffprobe_path = bin_dir.join("ffprobe");
-
-        if ffprobe_path.exists() {
-            return Some(ffprobe_path);
-        }
-    }
-
-    #[cfg(windows)]
-    let binary_name = "ffprobe.exe";
-    #[cfg(not(windows))]
-    let binary_name = "ffprobe";
-
-    if let Some(path) = find_system_binary(binary_name, &unix_system_binary_dirs()) {
-        return Some(path);
-    }
-
-    if let Some(ffmpeg_path) = get_ffmpeg_path(app).await {
-        if let Some(parent) = ffmpeg_path.parent() {
-            let ffprobe_path = parent.join(binary_name);
-            if ffprobe_path.exists() {
-                return Some(ffprobe_path);
-            }
-        }
-    }
-
-    None
-}
-
-async fn load_ai_config(app: &AppHandle) -> Result<AIConfig, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|_| "Failed to get app data directory")?;
-
-    let config_path = app_data_dir.join("ai_config.json");
-
-    if config_path.exists() {
-        let content = tokio::fs::read_to_string(&config_path)
-            .await
-            .map_err(|e| format!("Failed to read AI config: {}", e))?;
-
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse AI config: {}", e))
-    } else {
-        Err("AI config not found. Please configure AI in Settings.".to_string())
-    }
-}
-
 fn format_time(seconds: f64) -> String {
     let hrs = (seconds / 3600.0) as i32;
     let mins = ((seconds % 3600.0) / 60.0) as i32;
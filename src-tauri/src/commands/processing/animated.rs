@@ -0,0 +1,224 @@
+use super::*;
+
+/// Output settings for the `gif` quick action, read from its options
+#[derive(Debug, Clone, PartialEq)]
+struct AnimatedExportSettings {
+    ext: &'static str,
+    width: u32,
+    fps: u32,
+    high_quality: bool,
+    max_colors: u32,
+    webp_quality: u32,
+    size_targeted: bool,
+}
+
+impl AnimatedExportSettings {
+    /// GIF and APNG only honor a size target through a reduced palette
+    fn uses_palette(&self) -> bool {
+        self.ext != "webp" && (self.high_quality || self.size_targeted)
+    }
+}
+
+/// Pick palette size and WebP quality so the clip lands near `target_size_kb`.
+/// Budgets are per pixel per frame, tuned on typical screen recordings.
+fn fit_size_target(
+    settings: &mut AnimatedExportSettings,
+    target_size_kb: Option<f64>,
+    duration: f64,
+) {
+    let Some(target_kb) = target_size_kb.filter(|kb| *kb > 0.0) else {
+        return;
+    };
+    let frames = (duration.max(0.1) * f64::from(settings.fps)).max(1.0);
+    let pixels = f64::from(settings.width) * f64::from(settings.width) * 9.0 / 16.0;
+    let bits_per_pixel = target_kb * 1024.0 * 8.0 / (frames * pixels);
+
+    settings.max_colors = match bits_per_pixel {
+        bpp if bpp >= 1.5 => 256,
+        bpp if bpp >= 0.8 => 128,
+        bpp if bpp >= 0.4 => 64,
+        _ => 32,
+    };
+    settings.webp_quality = (bits_per_pixel * 60.0).clamp(20.0, 90.0).round() as u32;
+    settings.size_targeted = true;
+}
+
+fn animated_export_settings(
+    options: &HashMap<String, serde_json::Value>,
+    duration: f64,
+) -> Result<AnimatedExportSettings, String> {
+    let ext = match options
+        .get("format")
+        .and_then(|v| v.as_str())
+        .unwrap_or("gif")
+    {
+        "gif" => "gif",
+        "webp" => "webp",
+        "apng" | "png" => "apng",
+        other => return Err(format!("Unsupported animation format: {}", other)),
+    };
+    let mut settings = AnimatedExportSettings {
+        ext,
+        width: options
+            .get("width")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(480)
+            .clamp(64, 1920) as u32,
+        fps: options
+            .get("fps")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(15)
+            .clamp(1, 50) as u32,
+        high_quality: options
+            .get("quality")
+            .and_then(|v| v.as_str())
+            .is_some_and(|quality| quality == "high"),
+        max_colors: 256,
+        webp_quality: 80,
+        size_targeted: false,
+    };
+    fit_size_target(
+        &mut settings,
+        options.get("target_size_kb").and_then(|v| v.as_f64()),
+        duration,
+    );
+    Ok(settings)
+}
+
+fn animated_filter(settings: &AnimatedExportSettings) -> String {
+    let base = format!(
+        "fps={},scale={}:-1:flags=lanczos",
+        settings.fps, settings.width
+    );
+    if settings.uses_palette() {
+        // Both palette passes in one graph: build a palette from the clip, then map onto it
+        format!(
+            "{},split[a][b];[a]palettegen=max_colors={}:stats_mode=diff[p];[b][p]paletteuse=dither=sierra2_4a:diff_mode=rectangle",
+            base, settings.max_colors
+        )
+    } else {
+        base
+    }
+}
+
+/// ffmpeg args for the `gif` quick action: GIF (single pass or palette
+/// optimized), animated WebP, or APNG
+pub(crate) fn animated_export_args(
+    input_path: &str,
+    output_base_dir: &Path,
+    input_stem: &str,
+    timestamp: &str,
+    timeline_start: Option<f64>,
+    timeline_end: Option<f64>,
+    options: &HashMap<String, serde_json::Value>,
+) -> Result<(Vec<String>, String, String), String> {
+    let start = timeline_start.unwrap_or(0.0);
+    let end = timeline_end.unwrap_or(start + 5.0);
+    let duration = end - start;
+    let settings = animated_export_settings(options, duration)?;
+    let file_ext = if settings.ext == "apng" {
+        "png"
+    } else {
+        settings.ext
+    };
+    let output = output_base_dir.join(format!("{}_{}.{}", input_stem, timestamp, file_ext));
+
+    let mut args = vec![
+        "-y".to_string(),
+        "-ss".to_string(),
+        format_time(start),
+        "-t".to_string(),
+        duration.to_string(),
+        "-i".to_string(),
+        input_path.to_string(),
+        if settings.uses_palette() {
+            "-filter_complex".to_string()
+        } else {
+            "-vf".to_string()
+        },
+        animated_filter(&settings),
+        "-an".to_string(),
+    ];
+    match settings.ext {
+        "webp" => args.extend([
+            "-c:v".to_string(),
+            "libwebp".to_string(),
+            "-quality".to_string(),
+            settings.webp_quality.to_string(),
+            "-loop".to_string(),
+            "0".to_string(),
+        ]),
+        "apng" => args.extend([
+            "-f".to_string(),
+            "apng".to_string(),
+            "-plays".to_string(),
+            "0".to_string(),
+        ]),
+        _ => {}
+    }
+    args.extend([
+        "-progress".to_string(),
+        "pipe:2".to_string(),
+        output.to_string_lossy().to_string(),
+    ]);
+
+    let label = match settings.ext {
+        "gif" if settings.high_quality => "high-quality GIF",
+        "gif" => "GIF",
+        "webp" => "animated WebP",
+        _ => "APNG",
+    };
+    Ok((
+        args,
+        output.to_string_lossy().to_string(),
+        format!(
+            "Create {} from {} to {}",
+            label,
+            format_time(start),
+            format_time(end)
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_quality_gif_uses_palette_passes_and_size_target_shrinks_palette() {
+        let mut options = HashMap::new();
+        options.insert("quality".to_string(), serde_json::json!("high"));
+        options.insert("target_size_kb".to_string(), serde_json::json!(500));
+
+        let settings = animated_export_settings(&options, 5.0).expect("settings");
+        assert_eq!(settings.max_colors, 64);
+        assert!(animated_filter(&settings)
+            .contains("[a]palettegen=max_colors=64:stats_mode=diff[p];[b][p]paletteuse"));
+
+        options.insert("format".to_string(), serde_json::json!("webp"));
+        let settings = animated_export_settings(&options, 5.0).expect("webp settings");
+        assert_eq!(
+            animated_filter(&settings),
+            "fps=15,scale=480:-1:flags=lanczos"
+        );
+        assert!(settings.webp_quality < 80);
+    }
+
+    #[test]
+    fn size_target_reduces_the_palette_of_plain_gif_and_apng() {
+        let mut options = HashMap::new();
+        options.insert("target_size_kb".to_string(), serde_json::json!(500));
+        for format in ["gif", "apng"] {
+            options.insert("format".to_string(), serde_json::json!(format));
+            let settings = animated_export_settings(&options, 5.0).expect("settings");
+            assert!(animated_filter(&settings).contains("palettegen=max_colors=64"));
+        }
+
+        options.remove("target_size_kb");
+        let settings = animated_export_settings(&options, 5.0).expect("settings");
+        assert_eq!(
+            animated_filter(&settings),
+            "fps=15,scale=480:-1:flags=lanczos"
+        );
+    }
+}
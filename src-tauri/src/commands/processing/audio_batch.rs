@@ -0,0 +1,275 @@
+use super::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioBatchItem {
+    pub input_path: String,
+    pub output_path: Option<String>,
+    pub status: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioBatchReport {
+    pub batch_id: String,
+    pub converted: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub cancelled: bool,
+    pub items: Vec<AudioBatchItem>,
+}
+
+/// Codec args for a target audio format; bitrate is ignored by lossless formats
+pub(super) fn audio_codec_args(target_format: &str, bitrate: &str) -> Result<Vec<String>, String> {
+    let args: Vec<&str> = match target_format {
+        "mp3" => vec!["-c:a", "libmp3lame", "-b:a", bitrate, "-id3v2_version", "3"],
+        "m4a" => vec!["-c:a", "aac", "-b:a", bitrate],
+        "opus" => vec!["-c:a", "libopus", "-b:a", bitrate],
+        "ogg" => vec!["-c:a", "libvorbis", "-b:a", bitrate],
+        "flac" => vec!["-c:a", "flac"],
+        "wav" => vec!["-c:a", "pcm_s16le"],
+        other => return Err(format!("Unsupported audio format: {}", other)),
+    };
+    Ok(args.into_iter().map(String::from).collect())
+}
+
+pub(super) fn audio_batch_output_path(input: &Path, target_format: &str) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "audio".to_string());
+    let dir = input.parent().unwrap_or_else(|| Path::new("."));
+    unique_output_path(dir, &stem, target_format)
+}
+
+pub(super) fn audio_batch_args(
+    input_path: &str,
+    output_path: &str,
+    target_format: &str,
+    bitrate: &str,
+    keep_tags: bool,
+) -> Result<Vec<String>, String> {
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        input_path.to_string(),
+        "-map".to_string(),
+        "0:a:0".to_string(),
+    ];
+    // Cover art rides along as an attached picture where the container allows it
+    if keep_tags && matches!(target_format, "mp3" | "m4a" | "flac") {
+        args.extend([
+            "-map".to_string(),
+            "0:v?".to_string(),
+            "-c:v".to_string(),
+            "copy".to_string(),
+            "-disposition:v".to_string(),
+            "attached_pic".to_string(),
+        ]);
+    }
+    args.extend([
+        "-map_metadata".to_string(),
+        if keep_tags { "0" } else { "-1" }.to_string(),
+    ]);
+    args.extend(audio_codec_args(target_format, bitrate)?);
+    apply_ffmpeg_thread_limit(&mut args);
+    args.extend([
+        "-progress".to_string(),
+        "pipe:2".to_string(),
+        output_path.to_string(),
+    ]);
+    Ok(args)
+}
+
+pub(super) fn emit_audio_batch_progress(
+    app: &AppHandle,
+    batch_id: &str,
+    index: usize,
+    total: usize,
+    input_path: &str,
+    percent: f64,
+    status: &str,
+) {
+    let _ = AUDIO_BATCH_PROGRESS.emit(
+        app,
+        &AudioBatchProgress {
+            batch_id: batch_id.to_string(),
+            index,
+            total,
+            input_path: input_path.to_string(),
+            percent,
+            status: status.to_string(),
+        },
+    );
+}
+
+/// Convert many audio files one after another, emitting `audio-batch-progress`
+/// per file. Cancel with `cancel_ffmpeg(batch_id)`; files already converted are kept.
+#[tauri::command]
+pub async fn convert_audio_batch(
+    app: AppHandle,
+    batch_id: String,
+    paths: Vec<String>,
+    target_format: String,
+    bitrate: Option<String>,
+    keep_tags: Option<bool>,
+) -> Result<AudioBatchReport, String> {
+    let target_format = target_format.trim().to_lowercase();
+    let bitrate = bitrate.unwrap_or_else(|| "192k".to_string());
+    if !bitrate.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("Invalid bitrate value".to_string());
+    }
+    audio_codec_args(&target_format, &bitrate)?;
+    let keep_tags = keep_tags.unwrap_or(true);
+    let ffmpeg_path = get_ffmpeg_path(&app).await.ok_or("FFmpeg not found")?;
+
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    ACTIVE_JOBS.lock().await.insert(batch_id.clone(), cancel_tx);
+    let _active_job = track_active_job(ActiveJob::processing(&batch_id, &target_format));
+
+    let total = paths.len();
+    let mut report = AudioBatchReport {
+        batch_id: batch_id.clone(),
+        converted: 0,
+        skipped: 0,
+        failed: 0,
+        cancelled: false,
+        items: Vec::with_capacity(total),
+    };
+
+    for (index, input_path) in paths.into_iter().enumerate() {
+        let input = Path::new(&input_path);
+        let already_target = input
+            .extension()
+            .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(&target_format));
+        if !input.is_file() || already_target {
+            report.skipped += 1;
+            report.items.push(AudioBatchItem {
+                input_path: input_path.clone(),
+                output_path: None,
+                status: "skipped".to_string(),
+                error: (!input.is_file()).then(|| "File not found".to_string()),
+            });
+            emit_audio_batch_progress(&app, &batch_id, index, total, &input_path, 100.0, "skipped");
+            continue;
+        }
+
+        let output_path = audio_batch_output_path(input, &target_format)
+            .to_string_lossy()
+            .to_string();
+        let args = audio_batch_args(
+            &input_path,
+            &output_path,
+            &target_format,
+            &bitrate,
+            keep_tags,
+        )?;
+        emit_audio_batch_progress(
+            &app,
+            &batch_id,
+            index,
+            total,
+            &input_path,
+            0.0,
+            "converting",
+        );
+
+        let result = FfmpegRunner::new(&ffmpeg_path, args)
+            .run(Some(&mut cancel_rx), |progress| {
+                let percent = progress.percent(None);
+                if percent > 0.0 {
+                    emit_audio_batch_progress(
+                        &app,
+                        &batch_id,
+                        index,
+                        total,
+                        &input_path,
+                        percent.min(99.0),
+                        "converting",
+                    );
+                }
+            })
+            .await;
+
+        match result {
+            Ok(()) => {
+                report.converted += 1;
+                report.items.push(AudioBatchItem {
+                    input_path: input_path.clone(),
+                    output_path: Some(output_path),
+                    status: "converted".to_string(),
+                    error: None,
+                });
+                emit_audio_batch_progress(
+                    &app,
+                    &batch_id,
+                    index,
+                    total,
+                    &input_path,
+                    100.0,
+                    "converted",
+                );
+            }
+            Err(FfmpegError::Cancelled) => {
+                tokio::fs::remove_file(&output_path).await.ok();
+                report.cancelled = true;
+                break;
+            }
+            Err(error @ FfmpegError::Spawn(_)) => {
+                ACTIVE_JOBS.lock().await.remove(&batch_id);
+                return Err(error.into());
+            }
+            Err(error) => {
+                tokio::fs::remove_file(&output_path).await.ok();
+                let error = error.to_string();
+                report.failed += 1;
+                report.items.push(AudioBatchItem {
+                    input_path: input_path.clone(),
+                    output_path: None,
+                    status: "failed".to_string(),
+                    error: Some(error),
+                });
+                emit_audio_batch_progress(
+                    &app,
+                    &batch_id,
+                    index,
+                    total,
+                    &input_path,
+                    100.0,
+                    "failed",
+                );
+            }
+        }
+    }
+
+    ACTIVE_JOBS.lock().await.remove(&batch_id);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::parse_ffmpeg_duration;
+
+    #[test]
+    fn audio_batch_args_keep_tags_and_cover_art() {
+        let args = audio_batch_args("in.opus", "out.mp3", "mp3", "320k", true).expect("args");
+        let joined = args.join(" ");
+        assert!(joined.contains("-map 0:a:0 -map 0:v? -c:v copy"));
+        assert!(joined.contains("-map_metadata 0 -c:a libmp3lame -b:a 320k"));
+        assert!(joined.ends_with("-progress pipe:2 out.mp3"));
+
+        let stripped = audio_batch_args("in.m4a", "out.opus", "opus", "128k", false).expect("args");
+        assert!(!stripped.contains(&"0:v?".to_string()));
+        assert!(stripped.join(" ").contains("-map_metadata -1"));
+        assert!(audio_codec_args("aiff", "128k").is_err());
+    }
+
+    #[test]
+    fn ffmpeg_duration_header_is_parsed() {
+        assert_eq!(
+            parse_ffmpeg_duration("  Duration: 00:03:20.50, start: 0.000000, bitrate: 128 kb/s"),
+            Some(200.5)
+        );
+        assert_eq!(parse_ffmpeg_duration("Duration: N/A, bitrate: N/A"), None);
+    }
+}
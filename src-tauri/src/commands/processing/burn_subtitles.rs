@@ -0,0 +1,155 @@
+use super::*;
+
+/// Escape a path for use as a filter option inside an ffmpeg filtergraph.
+/// Needs two levels: option values (`\ ' :`), then the graph (`\ ' [ ] , ;`).
+/// Windows backslashes become forward slashes, which ffmpeg accepts.
+fn escape_filter_path(path: &str) -> String {
+    let normalized = if cfg!(windows) {
+        path.replace('\\', "/")
+    } else {
+        path.to_string()
+    };
+    let escape = |value: &str, special: &[char]| {
+        let mut escaped = String::with_capacity(value.len());
+        for ch in value.chars() {
+            if special.contains(&ch) {
+                escaped.push('\\');
+            }
+            escaped.push(ch);
+        }
+        escaped
+    };
+    let option_level = escape(&normalized, &['\\', '\'', ':']);
+    escape(&option_level, &['\\', '\'', '[', ']', ',', ';'])
+}
+
+/// ASS `Alignment` (numpad layout) for a position option
+fn subtitle_alignment(position: &str) -> u8 {
+    match position {
+        "top" => 8,
+        "middle" => 5,
+        _ => 2,
+    }
+}
+
+/// Filter that renders `subtitle_path` into the video. ASS/SSA files keep their
+/// own styling via the `ass` filter; other formats get `force_style` overrides.
+pub(crate) fn burn_subtitles_filter(
+    subtitle_path: &str,
+    options: &HashMap<String, serde_json::Value>,
+) -> String {
+    let escaped = escape_filter_path(subtitle_path);
+    let is_ass = Path::new(subtitle_path)
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("ass") || ext.eq_ignore_ascii_case("ssa"))
+        .unwrap_or(false);
+    if is_ass {
+        return format!("ass={}", escaped);
+    }
+
+    let font_size = options
+        .get("font_size")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(24)
+        .clamp(8, 120);
+    let outline = options
+        .get("outline")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(2.0)
+        .clamp(0.0, 10.0);
+    let margin = options
+        .get("margin")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(20)
+        .min(500);
+    let position = options
+        .get("position")
+        .and_then(|v| v.as_str())
+        .unwrap_or("bottom");
+
+    format!(
+        "subtitles={}:force_style='Fontsize={},Outline={},MarginV={},Alignment={}'",
+        escaped,
+        font_size,
+        outline,
+        margin,
+        subtitle_alignment(position)
+    )
+}
+
+/// ffmpeg args for the `burn_subtitles` quick action
+pub(crate) fn burn_subtitles_args(
+    input_path: &str,
+    output_base_dir: &Path,
+    input_stem: &str,
+    timestamp: &str,
+    options: &HashMap<String, serde_json::Value>,
+) -> Result<(Vec<String>, String, String), String> {
+    let subtitle_path = options
+        .get("subtitle_path")
+        .and_then(|v| v.as_str())
+        .filter(|path| !path.trim().is_empty())
+        .ok_or("No subtitle file selected")?;
+    if !Path::new(subtitle_path).is_file() {
+        return Err(format!("Subtitle file not found: {}", subtitle_path));
+    }
+
+    let output = output_base_dir.join(format!("{}_subbed_{}.mp4", input_stem, timestamp));
+    let args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        input_path.to_string(),
+        "-vf".to_string(),
+        burn_subtitles_filter(subtitle_path, options),
+        "-c:v".to_string(),
+        "libx264".to_string(),
+        "-preset".to_string(),
+        "medium".to_string(),
+        "-crf".to_string(),
+        "20".to_string(),
+        "-c:a".to_string(),
+        "copy".to_string(),
+        "-progress".to_string(),
+        "pipe:2".to_string(),
+        output.to_string_lossy().to_string(),
+    ];
+
+    let subtitle_name = Path::new(subtitle_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok((
+        args,
+        output.to_string_lossy().to_string(),
+        format!("Burn subtitles from {} into the video", subtitle_name),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_path_escapes_option_and_graph_levels() {
+        assert_eq!(
+            escape_filter_path("/subs/it's [v1], take:2.srt"),
+            r"/subs/it\\\'s \[v1\]\, take\\:2.srt"
+        );
+    }
+
+    #[test]
+    fn burn_filter_uses_ass_filter_or_forced_style() {
+        let mut options = HashMap::new();
+        assert_eq!(
+            burn_subtitles_filter("/tmp/a.ASS", &options),
+            "ass=/tmp/a.ASS"
+        );
+
+        options.insert("position".to_string(), serde_json::json!("top"));
+        options.insert("font_size".to_string(), serde_json::json!(32));
+        assert_eq!(
+            burn_subtitles_filter("/tmp/a.srt", &options),
+            "subtitles=/tmp/a.srt:force_style='Fontsize=32,Outline=2,MarginV=20,Alignment=8'"
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use super::*;
+use crate::commands::transcribe_file_segments;
+use crate::database::find_history_media;
+use crate::services::{
+    build_chapter_prompt, cached_transcript, local_whisper_enabled, parse_chapter_response,
+    youtube_chapter_list, AppProcessRunner, ProcessInvocation, ProcessRunner, Transcript,
+    WhisperError,
+};
+use crate::types::{code, BackendError};
+
+/// AI chapters for a video, as markers and as a description-ready list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterSuggestion {
+    pub chapters: Vec<ChapterMarker>,
+    /// "00:00 Intro" lines for a YouTube description
+    pub chapter_list: String,
+    /// Set when the chapters were written into the file
+    pub embedded_path: Option<String>,
+}
+
+fn escape_ffmetadata_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '=' | ';' | '#' | '\\' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Build an FFMETADATA1 document with one [CHAPTER] block per marker.
+/// Missing end times run until the next chapter (or the end of the media).
+fn build_ffmetadata_chapters(
+    chapters: &[ChapterMarker],
+    media_duration: Option<f64>,
+) -> Result<String, String> {
+    if chapters.is_empty() {
+        return Err("At least one chapter is required".to_string());
+    }
+
+    let mut sorted = chapters.to_vec();
+    sorted.sort_by(|a, b| a.start_seconds.total_cmp(&b.start_seconds));
+
+    let mut document = String::from(";FFMETADATA1\n");
+    for (index, chapter) in sorted.iter().enumerate() {
+        if !chapter.start_seconds.is_finite() || chapter.start_seconds < 0.0 {
+            return Err(format!(
+                "Invalid start time for chapter '{}'",
+                chapter.title
+            ));
+        }
+        let end = chapter
+            .end_seconds
+            .or_else(|| sorted.get(index + 1).map(|next| next.start_seconds))
+            .or(media_duration)
+            .ok_or_else(|| format!("Chapter '{}' needs an end time", chapter.title))?;
+        if end <= chapter.start_seconds {
+            return Err(format!("Chapter '{}' ends before it starts", chapter.title));
+        }
+
+        let title = chapter.title.trim();
+        let title = if title.is_empty() {
+            format!("Chapter {}", index + 1)
+        } else {
+            title.to_string()
+        };
+
+        document.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            (chapter.start_seconds * 1000.0).round() as i64,
+            (end * 1000.0).round() as i64,
+            escape_ffmetadata_value(&title)
+        ));
+    }
+
+    Ok(document)
+}
+
+async fn probe_media_duration(app: &AppHandle, path: &str) -> Option<f64> {
+    let ffprobe_path = get_ffprobe_path(app).await?;
+    let invocation = ProcessInvocation::binary(
+        ffprobe_path,
+        &[
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+            path,
+        ],
+    );
+    let output = AppProcessRunner::new(app).run(&invocation).await.ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Write chapter markers into an existing local file (stream copy, replaced in place).
+#[tauri::command]
+pub async fn add_chapters(
+    app: AppHandle,
+    path: String,
+    chapters: Vec<ChapterMarker>,
+) -> Result<String, String> {
+    let input_path = Path::new(&path);
+    if !input_path.is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+
+    let ffmpeg_path = get_ffmpeg_path(&app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg from Settings > Dependencies.")?;
+
+    let needs_duration = chapters
+        .iter()
+        .max_by(|a, b| a.start_seconds.total_cmp(&b.start_seconds))
+        .is_some_and(|last| last.end_seconds.is_none());
+    let media_duration = if needs_duration {
+        probe_media_duration(&app, &path).await
+    } else {
+        None
+    };
+    let metadata = build_ffmetadata_chapters(&chapters, media_duration)?;
+
+    let parent = input_path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = input_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "output".to_string());
+    let extension = input_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+    let job_id = uuid::Uuid::new_v4();
+    let metadata_path = std::env::temp_dir().join(format!("youwee-chapters-{}.txt", job_id));
+    let temp_output = parent.join(format!(".{}.chapters-{}.{}", stem, job_id, extension));
+
+    tokio::fs::write(&metadata_path, metadata)
+        .await
+        .map_err(|e| format!("Failed to write chapter metadata: {}", e))?;
+
+    let args: Vec<String> = ["-hide_banner", "-y", "-i", &path, "-i"]
+        .into_iter()
+        .map(str::to_string)
+        .chain([metadata_path.to_string_lossy().to_string()])
+        .chain(
+            [
+                "-map",
+                "0",
+                "-map_metadata",
+                "0",
+                "-map_chapters",
+                "1",
+                "-codec",
+                "copy",
+            ]
+            .map(str::to_string),
+        )
+        .chain([temp_output.to_string_lossy().to_string()])
+        .collect();
+
+    let result = FfmpegRunner::new(&ffmpeg_path, args)
+        .run(None, |_| {})
+        .await;
+    tokio::fs::remove_file(&metadata_path).await.ok();
+    if let Err(e) = result {
+        tokio::fs::remove_file(&temp_output).await.ok();
+        return Err(format!("FFmpeg failed to write chapters: {}", e));
+    }
+
+    tokio::fs::rename(&temp_output, input_path)
+        .await
+        .map_err(|e| format!("Failed to replace original file: {}", e))?;
+
+    Ok(path)
+}
+
+/// A transcript with the downloaded file and title it belongs to
+pub(super) struct MediaTranscript {
+    pub transcript: Transcript,
+    pub local_path: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Transcript of a downloaded file or of a URL's history entry: the cached
+/// one when there is one, else a fresh Whisper transcription. Without
+/// `allow_transcribe` a missing transcript is a CONFIRM_REQUIRED error, so
+/// the user agrees before a paid transcription starts.
+pub(super) async fn transcript_for_media(
+    app: &AppHandle,
+    url_or_path: &str,
+    config: &AIConfig,
+    allow_transcribe: bool,
+) -> Result<MediaTranscript, String> {
+    let source = url_or_path.trim().to_string();
+    let history = find_history_media(&source)?;
+    let local_path = if Path::new(&source).is_file() {
+        Some(source.clone())
+    } else {
+        history
+            .as_ref()
+            .map(|(_, _, filepath, _)| filepath.clone())
+            .filter(|filepath| Path::new(filepath).is_file())
+    };
+    let title = history.map(|(_, _, _, title)| title);
+
+    let cached =
+        cached_transcript(app, &source).or_else(|| cached_transcript(app, local_path.as_deref()?));
+    let transcript = match (cached, local_path.as_deref()) {
+        (Some(transcript), _) => transcript,
+        (None, Some(path)) => {
+            if !config.enabled {
+                return Err("AI features are disabled. Enable them in Settings.".to_string());
+            }
+            if !allow_transcribe {
+                return Err(BackendError::new(
+                    code::CONFIRM_REQUIRED,
+                    "This video has no transcript yet. Transcribe it with Whisper?",
+                )
+                .with_param("reason", "transcribe")
+                .with_retryable(false)
+                .to_wire_string());
+            }
+            let api_key = config
+                .whisper_api_key
+                .clone()
+                .filter(|key| !key.is_empty())
+                .or_else(|| local_whisper_enabled().then(String::new))
+                .ok_or_else(|| String::from(WhisperError::NoApiKey))?;
+            transcribe_file_segments(
+                app,
+                path,
+                &api_key,
+                None,
+                config.whisper_endpoint_url.as_deref(),
+                config.whisper_model.as_deref(),
+                false,
+            )
+            .await?
+        }
+        (None, None) => {
+            return Err(
+                "No transcript available. Download the video first so it can be transcribed."
+                    .to_string(),
+            )
+        }
+    };
+
+    Ok(MediaTranscript {
+        transcript,
+        local_path,
+        title,
+    })
+}
+
+/// Generate chapters with AI from the transcript of a downloaded file (or the
+/// history entry of a URL); `embed` also writes the chapters into the file.
+#[tauri::command]
+pub async fn generate_chapters(
+    app: AppHandle,
+    url_or_path: String,
+    embed: Option<bool>,
+    language: Option<String>,
+) -> Result<ChapterSuggestion, String> {
+    let config = ai_config_for_feature(&app, AIFeature::Chapters).await?;
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+    let MediaTranscript {
+        transcript,
+        local_path,
+        title,
+    } = transcript_for_media(&app, &url_or_path, &config, true).await?;
+
+    let duration = match local_path.as_deref() {
+        Some(path) => probe_media_duration(&app, path).await,
+        None => None,
+    }
+    .or(transcript.duration_seconds);
+    let language = language.unwrap_or_else(|| config.summary_language.clone());
+    let prompt = build_chapter_prompt(&transcript, title.as_deref(), &language);
+    let result = generate_raw_tracked(AIFeature::Chapters, &config, &prompt).await?;
+    let generated = parse_chapter_response(&result.summary, duration)?;
+
+    let chapters: Vec<ChapterMarker> = generated
+        .iter()
+        .map(|chapter| ChapterMarker {
+            title: chapter.title.clone(),
+            start_seconds: chapter.start_seconds,
+            end_seconds: None,
+        })
+        .collect();
+    let embedded_path = if embed.unwrap_or(false) {
+        let path = local_path.ok_or("Chapters can only be embedded into a downloaded file")?;
+        Some(add_chapters(app, path, chapters.clone()).await?)
+    } else {
+        None
+    };
+
+    Ok(ChapterSuggestion {
+        chapters,
+        chapter_list: youtube_chapter_list(&generated),
+        embedded_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(title: &str, start: f64, end: Option<f64>) -> ChapterMarker {
+        ChapterMarker {
+            title: title.to_string(),
+            start_seconds: start,
+            end_seconds: end,
+        }
+    }
+
+    #[test]
+    fn ffmetadata_chapters_fill_missing_end_times() {
+        let document = build_ffmetadata_chapters(
+            &[
+                marker("Outro", 90.0, None),
+                marker("Intro = start", 0.0, None),
+            ],
+            Some(120.5),
+        )
+        .expect("build metadata");
+
+        assert_eq!(
+            document,
+            ";FFMETADATA1\n\
+             \n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=90000\ntitle=Intro \\= start\n\
+             \n[CHAPTER]\nTIMEBASE=1/1000\nSTART=90000\nEND=120500\ntitle=Outro\n"
+        );
+    }
+
+    #[test]
+    fn ffmetadata_chapters_reject_unknown_last_end() {
+        assert!(build_ffmetadata_chapters(&[marker("Only", 5.0, None)], None).is_err());
+        assert!(build_ffmetadata_chapters(&[marker("Bad", 10.0, Some(4.0))], None).is_err());
+        assert!(build_ffmetadata_chapters(&[], Some(10.0)).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use super::*;
+use crate::commands::get_ai_config;
+use crate::database::get_history_entries_by_ids_from_db;
+use crate::services::{Transcript, TranscriptSegment};
+
+/// A transcript passage containing the searched text
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMatch {
+    pub segment_index: usize,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn find_words(haystack: &[String], needle: &[String]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Narrow a match to the matched words when the segment has word timings
+fn word_range(segment: &TranscriptSegment, query: &[String]) -> Option<(f64, f64)> {
+    let words: Vec<String> = segment
+        .words
+        .iter()
+        .map(|word| normalize_words(&word.word).concat())
+        .collect();
+    let first = find_words(&words, query)?;
+    let last = &segment.words[first + query.len() - 1];
+    Some((segment.words[first].start, last.end))
+}
+
+/// Segments containing `query` (case and punctuation insensitive). A quote
+/// split across two segments matches both as one passage.
+pub fn search_transcript(transcript: &Transcript, query: &str) -> Vec<TranscriptMatch> {
+    let query = normalize_words(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let segments = &transcript.segments;
+    let mut matches = Vec::new();
+    let mut index = 0;
+    while index < segments.len() {
+        let segment = &segments[index];
+        if find_words(&normalize_words(&segment.text), &query).is_some() {
+            let (start, end) = word_range(segment, &query).unwrap_or((segment.start, segment.end));
+            matches.push(TranscriptMatch {
+                segment_index: index,
+                start,
+                end,
+                text: segment.text.clone(),
+            });
+        } else if let Some(next) = segments.get(index + 1) {
+            let joined = format!("{} {}", segment.text, next.text);
+            if find_words(&normalize_words(&joined), &query).is_some()
+                && find_words(&normalize_words(&next.text), &query).is_none()
+            {
+                matches.push(TranscriptMatch {
+                    segment_index: index,
+                    start: segment.start,
+                    end: next.end,
+                    text: joined,
+                });
+                index += 1;
+            }
+        }
+        index += 1;
+    }
+    matches
+}
+
+/// Find a quote in the transcript of a downloaded file or a URL's history
+/// entry. With no cached transcript this asks for confirmation (reason
+/// "transcribe") unless `transcribe` is set, then transcribes with Whisper.
+#[tauri::command]
+pub async fn find_in_transcript(
+    app: AppHandle,
+    video: String,
+    query: String,
+    transcribe: Option<bool>,
+) -> Result<Vec<TranscriptMatch>, String> {
+    if query.trim().is_empty() {
+        return Err("Enter text to search for".to_string());
+    }
+    let config = get_ai_config(app.clone()).await?;
+    let media = transcript_for_media(&app, &video, &config, transcribe.unwrap_or(false)).await?;
+    Ok(search_transcript(&media.transcript, &query))
+}
+
+/// Cut `start..end` out of a history entry's file with the `cut` quick action.
+/// Progress is reported as `processing-progress` for `job_id`.
+#[tauri::command]
+pub async fn extract_clip_from_match(
+    app: AppHandle,
+    history_id: String,
+    start: f64,
+    end: f64,
+    job_id: Option<String>,
+    output_dir: Option<String>,
+) -> Result<String, String> {
+    if !start.is_finite() || !end.is_finite() || start < 0.0 || end <= start {
+        return Err("The clip must end after it starts".to_string());
+    }
+    let entry = get_history_entries_by_ids_from_db(vec![history_id])?
+        .into_iter()
+        .next()
+        .ok_or("History entry not found")?;
+    let input_path = processable_history_file(&entry)?
+        .to_string_lossy()
+        .to_string();
+    let metadata = get_video_metadata(app.clone(), input_path.clone()).await?;
+    let end = if metadata.duration > 0.0 {
+        end.min(metadata.duration)
+    } else {
+        end
+    };
+
+    let command = generate_quick_action_command(
+        app.clone(),
+        input_path.clone(),
+        "cut".to_string(),
+        HashMap::new(),
+        Some(start),
+        Some(end),
+        metadata,
+        output_dir,
+    )
+    .await?;
+    let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    execute_ffmpeg_command(
+        app,
+        job_id,
+        command.command_args,
+        input_path,
+        command.output_path.clone(),
+        None,
+    )
+    .await?;
+    Ok(command.output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::TranscriptWord;
+
+    fn segment(start: f64, end: f64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            start,
+            end,
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn quotes_match_within_and_across_segments() {
+        let mut first = segment(10.0, 14.0, "So the key idea is simple.");
+        first.words = ["So", "the", "key", "idea", "is", "simple."]
+            .iter()
+            .enumerate()
+            .map(|(i, word)| TranscriptWord {
+                start: 10.0 + i as f64 * 0.5,
+                end: 10.4 + i as f64 * 0.5,
+                word: word.to_string(),
+            })
+            .collect();
+        let transcript = Transcript {
+            segments: vec![
+                first,
+                segment(14.0, 18.0, "Ship small changes,"),
+                segment(18.0, 21.0, "review them often."),
+            ],
+            ..Default::default()
+        };
+
+        let matches = search_transcript(&transcript, "KEY idea");
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].start, matches[0].end), (11.0, 11.9));
+
+        let matches = search_transcript(&transcript, "changes, review them");
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].start, matches[0].end), (14.0, 21.0));
+
+        assert!(search_transcript(&transcript, "  ").is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+use super::*;
+use crate::services::{AppProcessRunner, ProcessInvocation, ProcessRunner};
+
+const COMPARE_METRICS: [&str; 3] = ["psnr", "ssim", "vmaf"];
+
+/// One quality score of `compare_videos`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityMetricScore {
+    /// "psnr" (dB), "ssim" (0-1) or "vmaf" (0-100)
+    pub metric: String,
+    pub score: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoComparisonResult {
+    pub scores: Vec<QualityMetricScore>,
+    /// Requested metrics this FFmpeg build has no filter for
+    pub unsupported: Vec<String>,
+    pub compared_seconds: f64,
+}
+
+/// FFmpeg filter behind a metric
+fn metric_filter(metric: &str) -> &'static str {
+    match metric {
+        "vmaf" => "libvmaf",
+        "ssim" => "ssim",
+        _ => "psnr",
+    }
+}
+
+/// Filter names from `ffmpeg -filters`
+fn parse_available_filters(stdout: &str) -> HashSet<String> {
+    stdout
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Scale the processed video to the original's size and resample both to the
+/// original's frame rate, so the filters pair frames shown at the same time,
+/// then feed both to one comparison filter per metric. The processed video is
+/// the first input.
+fn comparison_filter_graph(metrics: &[&str], width: i32, height: i32, fps: f64) -> String {
+    let count = metrics.len();
+    let rate = if fps > 0.0 {
+        format!("fps={},", fps)
+    } else {
+        String::new()
+    };
+    let labels = |prefix: &str| {
+        (0..count)
+            .map(|i| format!("[{}{}]", prefix, i))
+            .collect::<String>()
+    };
+    let mut graph = format!(
+        "[0:v]scale={}:{}:flags=bicubic,{}format=yuv420p,setpts=PTS-STARTPTS,split={}{};\
+         [1:v]{}format=yuv420p,setpts=PTS-STARTPTS,split={}{}",
+        width,
+        height,
+        rate,
+        count,
+        labels("d"),
+        rate,
+        count,
+        labels("r")
+    );
+    for (index, metric) in metrics.iter().enumerate() {
+        graph.push_str(&format!(";[d{0}][r{0}]{1}", index, metric_filter(metric)));
+    }
+    graph
+}
+
+fn value_after(line: &str, key: &str) -> Option<f64> {
+    line.split(key)
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Summary lines the comparison filters print when they finish
+fn parse_comparison_scores(stderr: &str) -> Vec<QualityMetricScore> {
+    let mut scores = Vec::new();
+    for line in stderr.lines() {
+        if line.contains("Parsed_psnr") && line.contains("average:") {
+            if let Some(score) = value_after(line, "average:") {
+                scores.push(QualityMetricScore {
+                    metric: "psnr".to_string(),
+                    score,
+                    min: value_after(line, "min:"),
+                    max: value_after(line, "max:"),
+                });
+            }
+        } else if line.contains("Parsed_ssim") && line.contains("All:") {
+            if let Some(score) = value_after(line, "All:") {
+                scores.push(QualityMetricScore {
+                    metric: "ssim".to_string(),
+                    score,
+                    min: None,
+                    max: None,
+                });
+            }
+        } else if line.contains("VMAF score:") {
+            if let Some(score) = value_after(line, "VMAF score:") {
+                scores.push(QualityMetricScore {
+                    metric: "vmaf".to_string(),
+                    score,
+                    min: None,
+                    max: None,
+                });
+            }
+        }
+    }
+    scores
+}
+
+/// PSNR, SSIM and VMAF of `processed` against `original`, for judging
+/// compression presets. Metrics the FFmpeg build lacks (usually VMAF) are
+/// listed as unsupported; `max_seconds` limits how much is compared.
+/// Cancel with `cancel_ffmpeg(job_id)`.
+#[tauri::command]
+pub async fn compare_videos(
+    app: AppHandle,
+    job_id: String,
+    original: String,
+    processed: String,
+    metrics: Option<Vec<String>>,
+    max_seconds: Option<f64>,
+) -> Result<VideoComparisonResult, String> {
+    for path in [&original, &processed] {
+        if !Path::new(path).is_file() {
+            return Err(format!("Media not found: {}", path));
+        }
+    }
+    let requested: Vec<&str> = match metrics.as_deref() {
+        Some(metrics) if !metrics.is_empty() => {
+            let mut requested = Vec::new();
+            for metric in metrics {
+                let metric = metric.trim().to_lowercase();
+                let known = COMPARE_METRICS
+                    .iter()
+                    .find(|known| **known == metric)
+                    .ok_or_else(|| format!("Unknown quality metric: {}", metric))?;
+                if !requested.contains(known) {
+                    requested.push(*known);
+                }
+            }
+            requested
+        }
+        _ => COMPARE_METRICS.to_vec(),
+    };
+    let ffmpeg_path = get_ffmpeg_path(&app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg from Settings > Dependencies.")?;
+
+    let filters_output = AppProcessRunner::new(&app)
+        .run(&ProcessInvocation::binary(
+            &ffmpeg_path,
+            &["-hide_banner", "-filters"],
+        ))
+        .await?;
+    let available = parse_available_filters(&String::from_utf8_lossy(&filters_output.stdout));
+    let (supported, unsupported): (Vec<&str>, Vec<&str>) = requested
+        .into_iter()
+        .partition(|metric| available.contains(metric_filter(metric)));
+    if supported.is_empty() {
+        return Err("This FFmpeg build cannot compute any of the requested metrics".to_string());
+    }
+
+    let reference = get_video_metadata(app.clone(), original.clone()).await?;
+    if reference.width <= 0 || reference.height <= 0 {
+        return Err("The original file has no video stream".to_string());
+    }
+    let limit = max_seconds.filter(|seconds| *seconds > 0.0);
+    let mut args: Vec<String> = vec!["-hide_banner".to_string(), "-nostats".to_string()];
+    for input in [&processed, &original] {
+        if let Some(limit) = limit {
+            args.extend(["-t".to_string(), limit.to_string()]);
+        }
+        args.extend(["-i".to_string(), input.clone()]);
+    }
+    args.extend([
+        "-filter_complex".to_string(),
+        comparison_filter_graph(&supported, reference.width, reference.height, reference.fps),
+        "-f".to_string(),
+        "null".to_string(),
+        "-".to_string(),
+    ]);
+
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    ACTIVE_JOBS.lock().await.insert(job_id.clone(), cancel_tx);
+    let result = FfmpegRunner::new(&ffmpeg_path, args)
+        .run_collecting_log(Some(&mut cancel_rx), |_| {})
+        .await;
+    ACTIVE_JOBS.lock().await.remove(&job_id);
+    let stderr = match result {
+        Ok(stderr) => stderr,
+        Err(FfmpegError::Cancelled) => return Err("Comparison cancelled".to_string()),
+        Err(error) => return Err(format!("FFmpeg comparison failed: {}", error)),
+    };
+
+    Ok(VideoComparisonResult {
+        scores: parse_comparison_scores(&stderr),
+        unsupported: unsupported.into_iter().map(str::to_string).collect(),
+        compared_seconds: limit.map_or(reference.duration, |limit| limit.min(reference.duration)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comparison_graph_and_scores_cover_each_metric() {
+        let filters = " ------\n T.C psnr              VV->V      Calculate the PSNR.\n \
+                       ... ssim              VV->V      Calculate the SSIM.\n";
+        let available = parse_available_filters(filters);
+        assert!(available.contains("psnr") && available.contains("ssim"));
+        assert!(!available.contains("libvmaf"));
+
+        assert_eq!(
+            comparison_filter_graph(&["psnr", "vmaf"], 1920, 1080, 29.97),
+            "[0:v]scale=1920:1080:flags=bicubic,fps=29.97,format=yuv420p,setpts=PTS-STARTPTS,\
+             split=2[d0][d1];[1:v]fps=29.97,format=yuv420p,setpts=PTS-STARTPTS,split=2[r0][r1];\
+             [d0][r0]psnr;[d1][r1]libvmaf"
+        );
+        assert_eq!(
+            comparison_filter_graph(&["ssim"], 640, 360, 0.0),
+            "[0:v]scale=640:360:flags=bicubic,format=yuv420p,setpts=PTS-STARTPTS,split=1[d0];\
+             [1:v]format=yuv420p,setpts=PTS-STARTPTS,split=1[r0];[d0][r0]ssim"
+        );
+
+        let stderr = "[Parsed_psnr_4 @ 0x1] PSNR y:40.12 u:44.00 v:44.50 average:41.23 min:35.10 max:48.90\n\
+                      [Parsed_ssim_5 @ 0x2] SSIM Y:0.981 (17.2) U:0.990 (20.0) V:0.991 (20.4) All:0.985 (18.2)\n\
+                      [Parsed_libvmaf_6 @ 0x3] VMAF score: 93.412\n";
+        let scores = parse_comparison_scores(stderr);
+        assert_eq!(scores.len(), 3);
+        assert_eq!(scores[0].score, 41.23);
+        assert_eq!((scores[0].min, scores[0].max), (Some(35.10), Some(48.90)));
+        assert_eq!(scores[1].score, 0.985);
+        assert_eq!(scores[2].metric, "vmaf");
+        assert_eq!(scores[2].score, 93.412);
+    }
+}
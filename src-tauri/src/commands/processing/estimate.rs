@@ -0,0 +1,248 @@
+use super::*;
+
+const DEFAULT_SAMPLE_SECONDS: f64 = 10.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingEstimate {
+    pub estimated_size_mb: f64,
+    pub estimated_time_seconds: f64,
+    pub output_duration_seconds: f64,
+    pub sample_seconds: f64,
+    pub sample_size_bytes: u64,
+    pub bitrate_kbps: f64,
+}
+
+/// Seconds from an ffmpeg time value (`62.5` or `00:01:02.500`)
+fn parse_ffmpeg_time(value: &str) -> Option<f64> {
+    value.split(':').try_fold(0.0, |total, part| {
+        part.trim().parse::<f64>().ok().map(|v| total * 60.0 + v)
+    })
+}
+
+/// Value following the first `flag` in `args`
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+}
+
+/// Trim of the first input as the command describes it
+#[derive(Debug, Default, PartialEq)]
+struct CommandTrim {
+    start: f64,
+    duration: Option<f64>,
+    end: Option<f64>,
+}
+
+/// Rewrite `args` to encode `sample_seconds` from the middle of what the full
+/// command would process, writing to `sample_output`. Returns the args and the
+/// full output duration, given the input duration.
+fn build_sample_args(
+    args: &[String],
+    input_duration: f64,
+    sample_seconds: f64,
+    sample_output: &str,
+) -> Result<(Vec<String>, f64), String> {
+    let input_index = args
+        .iter()
+        .position(|arg| arg == "-i")
+        .filter(|index| index + 1 < args.len())
+        .ok_or("Command has no input file")?;
+    if args.len() < input_index + 3 {
+        return Err("Command has no output file".to_string());
+    }
+
+    let mut trim = CommandTrim::default();
+    let mut input_options = Vec::new();
+    let mut output_options = Vec::new();
+    let body = &args[..args.len() - 1];
+    let mut index = 0;
+    while index < body.len() {
+        let arg = body[index].as_str();
+        let value = body.get(index + 1).map(String::as_str);
+        match (arg, value) {
+            ("-ss", Some(value)) if index < input_index => {
+                trim.start = parse_ffmpeg_time(value).unwrap_or(0.0);
+                index += 2;
+            }
+            ("-t", Some(value)) => {
+                trim.duration = parse_ffmpeg_time(value);
+                index += 2;
+            }
+            ("-to", Some(value)) => {
+                trim.end = parse_ffmpeg_time(value);
+                index += 2;
+            }
+            ("-progress", Some(_)) => index += 2,
+            ("-y", _) => index += 1,
+            ("-i", Some(_)) if index == input_index => index += 2,
+            _ => {
+                if index < input_index {
+                    input_options.push(body[index].clone());
+                } else {
+                    output_options.push(body[index].clone());
+                }
+                index += 1;
+            }
+        }
+    }
+
+    let output_duration = trim
+        .duration
+        .or_else(|| trim.end.map(|end| end - trim.start))
+        .unwrap_or(input_duration - trim.start)
+        .max(0.0);
+    if output_duration <= 0.0 {
+        return Err("Could not determine the output duration".to_string());
+    }
+    let sample = sample_seconds.min(output_duration);
+    let sample_start = trim.start + (output_duration - sample) / 2.0;
+
+    let mut sample_args = vec![
+        "-y".to_string(),
+        "-ss".to_string(),
+        format!("{:.3}", sample_start),
+        "-t".to_string(),
+        format!("{:.3}", sample),
+    ];
+    sample_args.extend(input_options);
+    sample_args.extend(["-i".to_string(), args[input_index + 1].clone()]);
+    sample_args.extend(output_options);
+    sample_args.push(sample_output.to_string());
+    Ok((sample_args, output_duration))
+}
+
+/// Encode a short sample with the job's settings and extrapolate the final
+/// size and run time from it
+#[tauri::command]
+pub async fn estimate_processing_output(
+    app: AppHandle,
+    command_args: Vec<String>,
+    sample_seconds: Option<f64>,
+) -> Result<ProcessingEstimate, String> {
+    validate_ffmpeg_args(&command_args)?;
+    let input_path = command_args
+        .iter()
+        .position(|arg| arg == "-i")
+        .and_then(|index| command_args.get(index + 1))
+        .ok_or("Command has no input file")?
+        .clone();
+    let output_ext = command_args
+        .last()
+        .and_then(|output| Path::new(output).extension())
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+
+    let metadata = get_video_metadata(app.clone(), input_path).await?;
+    let ffmpeg_path = get_ffmpeg_path(&app).await.ok_or("FFmpeg not found")?;
+
+    let sample_dir = std::env::temp_dir().join("youwee_estimate");
+    tokio::fs::create_dir_all(&sample_dir)
+        .await
+        .map_err(|e| format!("Failed to create sample folder: {}", e))?;
+    let sample_path = sample_dir.join(format!("{}.{}", uuid::Uuid::new_v4(), output_ext));
+    let sample_output = sample_path.to_string_lossy().to_string();
+
+    let (mut args, output_duration) = build_sample_args(
+        &command_args,
+        metadata.duration,
+        sample_seconds
+            .unwrap_or(DEFAULT_SAMPLE_SECONDS)
+            .clamp(2.0, 60.0),
+        &sample_output,
+    )?;
+    args.insert(0, "-hide_banner".to_string());
+    let sample = flag_value(&args, "-t")
+        .and_then(|value| value.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_SAMPLE_SECONDS);
+
+    let started = std::time::Instant::now();
+    let result = FfmpegRunner::new(&ffmpeg_path, args)
+        .run(None, |_| {})
+        .await;
+    let elapsed = started.elapsed().as_secs_f64();
+    let sample_size = tokio::fs::metadata(&sample_path)
+        .await
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+    tokio::fs::remove_file(&sample_path).await.ok();
+
+    result.map_err(|e| format!("FFmpeg sample encode failed: {}", e))?;
+    if sample_size == 0 {
+        return Err("FFmpeg sample encode produced an empty file".to_string());
+    }
+
+    let scale = output_duration / sample;
+    Ok(ProcessingEstimate {
+        estimated_size_mb: sample_size as f64 * scale / 1_000_000.0,
+        estimated_time_seconds: elapsed * scale,
+        output_duration_seconds: output_duration,
+        sample_seconds: sample,
+        sample_size_bytes: sample_size,
+        bitrate_kbps: sample_size as f64 * 8.0 / sample / 1000.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn sample_args_take_the_middle_of_the_cut() {
+        let command = args(&[
+            "-y",
+            "-ss",
+            "00:01:00.000",
+            "-i",
+            "in.mp4",
+            "-t",
+            "40",
+            "-c:v",
+            "libx264",
+            "-progress",
+            "pipe:2",
+            "out.mp4",
+        ]);
+        let (sample, duration) =
+            build_sample_args(&command, 600.0, 10.0, "/tmp/sample.mp4").expect("sample args");
+        assert_eq!(duration, 40.0);
+        assert_eq!(
+            sample,
+            args(&[
+                "-y",
+                "-ss",
+                "75.000",
+                "-t",
+                "10.000",
+                "-i",
+                "in.mp4",
+                "-c:v",
+                "libx264",
+                "/tmp/sample.mp4"
+            ])
+        );
+    }
+
+    #[test]
+    fn sample_args_use_input_duration_without_trim() {
+        let command = args(&["-i", "in.mp4", "-vf", "scale=-1:720", "out.mp4"]);
+        let (sample, duration) =
+            build_sample_args(&command, 8.0, 10.0, "s.mp4").expect("sample args");
+        assert_eq!(duration, 8.0);
+        assert_eq!(&sample[1..5], ["-ss", "0.000", "-t", "8.000"]);
+        assert!(build_sample_args(&args(&["-i", "in.mp4"]), 8.0, 10.0, "s.mp4").is_err());
+    }
+
+    #[test]
+    fn sample_duration_is_read_by_flag_after_thread_limit() {
+        let command = args(&["-i", "in.mp4", "out.mp4"]);
+        let (mut sample, _) = build_sample_args(&command, 30.0, 5.0, "s.mp4").expect("sample args");
+        sample.splice(0..0, args(&["-threads", "2"]));
+        assert_eq!(flag_value(&sample, "-t"), Some("5.000"));
+    }
+}
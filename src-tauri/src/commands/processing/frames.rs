@@ -0,0 +1,243 @@
+use super::*;
+
+/// Which frames `export_frames` writes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FrameExportMode {
+    /// One frame every `every_seconds`
+    Interval { every_seconds: f64 },
+    /// One frame at each timestamp (seconds)
+    Timestamps { timestamps: Vec<f64> },
+    /// Every frame between `start` and `end` (seconds)
+    Range { start: f64, end: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameExportResult {
+    pub output_dir: String,
+    pub files: Vec<String>,
+}
+
+fn frame_format(format: Option<&str>) -> Result<&'static str, String> {
+    match format.unwrap_or("png").to_lowercase().as_str() {
+        "png" => Ok("png"),
+        "jpg" | "jpeg" => Ok("jpg"),
+        "webp" => Ok("webp"),
+        other => Err(format!("Unsupported frame format: {}", other)),
+    }
+}
+
+fn frame_encoder_args(ext: &str) -> Vec<String> {
+    match ext {
+        "jpg" => vec!["-q:v".to_string(), "2".to_string()],
+        "webp" => vec![
+            "-c:v".to_string(),
+            "libwebp".to_string(),
+            "-quality".to_string(),
+            "90".to_string(),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// One ffmpeg invocation per entry; timestamps need a seek each
+fn frame_export_commands(
+    input_path: &str,
+    output_dir: &Path,
+    mode: &FrameExportMode,
+    width: Option<u32>,
+    ext: &str,
+) -> Result<Vec<Vec<String>>, String> {
+    let scale = width.filter(|w| *w > 0).map(|w| format!("scale={}:-2", w));
+    let with_filters = |mut filters: Vec<String>| -> Vec<String> {
+        filters.extend(scale.clone());
+        if filters.is_empty() {
+            Vec::new()
+        } else {
+            vec!["-vf".to_string(), filters.join(",")]
+        }
+    };
+    let sequence_output = output_dir
+        .join(format!("frame_%05d.{}", ext))
+        .to_string_lossy()
+        .to_string();
+
+    let commands = match mode {
+        FrameExportMode::Interval { every_seconds } => {
+            if !every_seconds.is_finite() || *every_seconds <= 0.0 {
+                return Err("Frame interval must be greater than zero".to_string());
+            }
+            let mut args = vec!["-y".to_string(), "-i".to_string(), input_path.to_string()];
+            args.extend(with_filters(vec![format!("fps=1/{}", every_seconds)]));
+            args.extend(frame_encoder_args(ext));
+            args.push(sequence_output);
+            vec![args]
+        }
+        FrameExportMode::Range { start, end } => {
+            if !(start.is_finite() && end.is_finite()) || *start < 0.0 || end <= start {
+                return Err("Invalid frame range".to_string());
+            }
+            let mut args = vec![
+                "-y".to_string(),
+                "-ss".to_string(),
+                format_time(*start),
+                "-i".to_string(),
+                input_path.to_string(),
+                "-t".to_string(),
+                (end - start).to_string(),
+            ];
+            args.extend(with_filters(Vec::new()));
+            args.extend(frame_encoder_args(ext));
+            args.push(sequence_output);
+            vec![args]
+        }
+        FrameExportMode::Timestamps { timestamps } => {
+            if timestamps.is_empty() {
+                return Err("No timestamps selected".to_string());
+            }
+            timestamps
+                .iter()
+                .filter(|time| time.is_finite() && **time >= 0.0)
+                .map(|time| {
+                    let output = output_dir.join(format!(
+                        "frame_{:09}ms.{}",
+                        (time * 1000.0).round() as u64,
+                        ext
+                    ));
+                    let mut args = vec![
+                        "-y".to_string(),
+                        "-ss".to_string(),
+                        format_time(*time),
+                        "-i".to_string(),
+                        input_path.to_string(),
+                        "-frames:v".to_string(),
+                        "1".to_string(),
+                    ];
+                    args.extend(with_filters(Vec::new()));
+                    args.extend(frame_encoder_args(ext));
+                    args.push(output.to_string_lossy().to_string());
+                    args
+                })
+                .collect()
+        }
+    };
+    Ok(commands)
+}
+
+/// Export frames as images into a new subfolder next to the video (or in
+/// `output_dir`) and return the generated files
+#[tauri::command]
+pub async fn export_frames(
+    app: AppHandle,
+    input_path: String,
+    mode: FrameExportMode,
+    width: Option<u32>,
+    format: Option<String>,
+    output_dir: Option<String>,
+) -> Result<FrameExportResult, String> {
+    if !Path::new(&input_path).exists() {
+        return Err(format!("Video not found: {}", input_path));
+    }
+    let ffmpeg_path = get_ffmpeg_path(&app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg from Settings > Dependencies.")?;
+
+    let ext = frame_format(format.as_deref())?;
+    let input_stem = Path::new(&input_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or("output".to_string());
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let frames_dir = resolve_output_dir(&input_path, output_dir.as_deref())
+        .join(format!("{}_frames_{}", input_stem, timestamp));
+
+    let commands = frame_export_commands(&input_path, &frames_dir, &mode, width, ext)?;
+    tokio::fs::create_dir_all(&frames_dir)
+        .await
+        .map_err(|e| format!("Failed to create frames folder: {}", e))?;
+
+    let frames_dir_str = frames_dir.to_string_lossy().to_string();
+    let _active_job = track_active_job(ActiveJob::processing(&frames_dir_str, &frames_dir_str));
+    for mut args in commands {
+        args.insert(0, "-hide_banner".to_string());
+        FfmpegRunner::new(&ffmpeg_path, args)
+            .run(None, |_| {})
+            .await
+            .map_err(|e| format!("FFmpeg frame export failed: {}", e))?;
+    }
+
+    let mut files: Vec<String> = std::fs::read_dir(&frames_dir)
+        .map_err(|e| format!("Failed to list exported frames: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    files.sort();
+
+    Ok(FrameExportResult {
+        output_dir: frames_dir_str,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_export_builds_interval_and_timestamp_commands() {
+        let dir = Path::new("/out");
+        let interval = frame_export_commands(
+            "in.mp4",
+            dir,
+            &FrameExportMode::Interval { every_seconds: 5.0 },
+            Some(640),
+            "jpg",
+        )
+        .expect("interval commands");
+        assert_eq!(
+            interval,
+            [[
+                "-y",
+                "-i",
+                "in.mp4",
+                "-vf",
+                "fps=1/5,scale=640:-2",
+                "-q:v",
+                "2",
+                "/out/frame_%05d.jpg"
+            ]]
+        );
+
+        let stills = frame_export_commands(
+            "in.mp4",
+            dir,
+            &FrameExportMode::Timestamps {
+                timestamps: vec![1.5, 62.0],
+            },
+            None,
+            "png",
+        )
+        .expect("timestamp commands");
+        assert_eq!(stills.len(), 2);
+        assert_eq!(stills[1][2], "00:01:02.000");
+        assert_eq!(
+            stills[1].last().map(String::as_str),
+            Some("/out/frame_000062000ms.png")
+        );
+
+        assert!(frame_export_commands(
+            "in.mp4",
+            dir,
+            &FrameExportMode::Range {
+                start: 4.0,
+                end: 2.0
+            },
+            None,
+            "png"
+        )
+        .is_err());
+        assert!(frame_format(Some("gif")).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use super::*;
+use crate::database::get_history_entries_by_ids_from_db;
+use crate::types::HistoryEntry;
+
+/// Everything the processing screen needs to open a downloaded file
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessingSession {
+    pub job: ProcessingJob,
+    pub metadata: VideoMetadata,
+    pub history: HistoryEntry,
+}
+
+/// The file behind a history entry, if it is something FFmpeg can process
+pub(super) fn processable_history_file(entry: &HistoryEntry) -> Result<PathBuf, String> {
+    if matches!(entry.media_type.as_deref(), Some("image" | "subtitle")) {
+        return Err("Only video and audio downloads can be processed".to_string());
+    }
+    let path = PathBuf::from(&entry.filepath);
+    if entry.filepath.is_empty() || !path.is_file() {
+        return Err(format!(
+            "The downloaded file is no longer available: {}",
+            entry.filepath
+        ));
+    }
+    Ok(path)
+}
+
+/// Open a history entry in the processing screen: checks the file, probes
+/// it and creates a draft job that `save_processing_job` later fills in
+#[tauri::command]
+pub async fn create_processing_session_from_history(
+    app: AppHandle,
+    history_id: String,
+) -> Result<ProcessingSession, String> {
+    let history = get_history_entries_by_ids_from_db(vec![history_id])?
+        .into_iter()
+        .next()
+        .ok_or("History entry not found")?;
+    let input_path = processable_history_file(&history)?
+        .to_string_lossy()
+        .to_string();
+    let metadata = get_video_metadata(app, input_path.clone()).await?;
+
+    let job = ProcessingJob {
+        id: uuid::Uuid::new_v4().to_string(),
+        input_path,
+        output_path: None,
+        task_type: "custom".to_string(),
+        user_prompt: None,
+        ffmpeg_command: String::new(),
+        status: "draft".to_string(),
+        progress: 0.0,
+        error_message: None,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        completed_at: None,
+        output_size: None,
+        output_duration: None,
+    };
+    let conn = get_db()?;
+    remove_stale_draft_jobs(&conn);
+    conn.execute(
+        "INSERT INTO processing_jobs (id, input_path, output_path, task_type, user_prompt, ffmpeg_command, status, progress, created_at)
+         VALUES (?1, ?2, NULL, ?3, NULL, ?4, ?5, 0, ?6)",
+        params![job.id, job.input_path, job.task_type, job.ffmpeg_command, job.status, job.created_at],
+    )
+    .map_err(|e| format!("Failed to save job: {}", e))?;
+
+    Ok(ProcessingSession {
+        job,
+        metadata,
+        history,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_existing_media_files_can_be_processed() {
+        let dir = std::env::temp_dir().join(format!("youwee-send-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("clip.mp4");
+        std::fs::write(&file, b"data").unwrap();
+
+        let mut entry = HistoryEntry {
+            filepath: file.to_string_lossy().to_string(),
+            media_type: Some("video".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(processable_history_file(&entry).unwrap(), file);
+
+        entry.media_type = Some("subtitle".to_string());
+        assert!(processable_history_file(&entry).is_err());
+
+        entry.media_type = None;
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(processable_history_file(&entry)
+            .unwrap_err()
+            .starts_with("The downloaded file is no longer available"));
+    }
+}
@@ -0,0 +1,372 @@
+use super::*;
+use crate::commands::{get_ai_config, transcribe_file_segments};
+use crate::services::{
+    chain_progress, export_transcript, job_chains, local_whisper_enabled, ready_jobs,
+    skip_blocked_jobs, validate_job_graph, JobNode, JobNodeStatus, TranscriptExportFormat,
+};
+use crate::types::{ProcessingChainProgress, ProcessingGraphProgress, PROCESSING_GRAPH_PROGRESS};
+use tokio::task::JoinSet;
+
+/// Jobs of one graph that may run at the same time
+const MAX_PARALLEL_GRAPH_JOBS: usize = 2;
+
+struct GraphState {
+    nodes: Vec<JobNode>,
+    cancelled: bool,
+}
+
+static PROCESSING_GRAPHS: LazyLock<Mutex<HashMap<String, GraphState>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProcessingGraphTask {
+    Ffmpeg {
+        command_args: Vec<String>,
+        input_path: String,
+        output_path: String,
+        /// Burn frame number and timecode into the output
+        #[serde(default)]
+        debug_overlay: bool,
+    },
+    /// Whisper transcription saved as SRT, VTT or JSON by `output_path`'s extension
+    Transcribe {
+        input_path: String,
+        output_path: String,
+        language: Option<String>,
+    },
+}
+
+/// A job and the ids of jobs that must complete before it starts
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProcessingGraphJob {
+    pub id: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    pub task: ProcessingGraphTask,
+}
+
+fn graph_progress(
+    graph_id: &str,
+    nodes: &[JobNode],
+    job_id: &str,
+    status: JobNodeStatus,
+    error: Option<String>,
+) -> ProcessingGraphProgress {
+    let chains = job_chains(nodes)
+        .into_iter()
+        .map(|chain| {
+            let (percent, status) = chain_progress(nodes, &chain);
+            ProcessingChainProgress {
+                job_ids: chain.iter().map(|&i| nodes[i].id.clone()).collect(),
+                percent,
+                status: status.as_str().to_string(),
+            }
+        })
+        .collect();
+    ProcessingGraphProgress {
+        graph_id: graph_id.to_string(),
+        job_id: job_id.to_string(),
+        status: status.as_str().to_string(),
+        error,
+        finished: nodes.iter().filter(|n| n.status.is_finished()).count(),
+        total: nodes.len(),
+        chains,
+    }
+}
+
+/// Id of a graph job in ACTIVE_JOBS, `processing-progress` and the
+/// processing history, unique across graphs
+fn graph_job_key(graph_id: &str, job_id: &str) -> String {
+    format!("{}:{}", graph_id, job_id)
+}
+
+/// Save a graph job in the processing history as pending
+async fn persist_graph_job(app: &AppHandle, key: String, task: &ProcessingGraphTask) {
+    let (input_path, output_path, command) = match task {
+        ProcessingGraphTask::Ffmpeg {
+            command_args,
+            input_path,
+            output_path,
+            ..
+        } => (
+            input_path,
+            output_path,
+            args_to_display_command(command_args),
+        ),
+        ProcessingGraphTask::Transcribe {
+            input_path,
+            output_path,
+            ..
+        } => (
+            input_path,
+            output_path,
+            format!("whisper \"{}\" -> \"{}\"", input_path, output_path),
+        ),
+    };
+    let saved = save_processing_job(
+        app.clone(),
+        key,
+        input_path.clone(),
+        Some(output_path.clone()),
+        "custom".to_string(),
+        None,
+        command,
+    )
+    .await;
+    if let Err(e) = saved {
+        log::warn!("Failed to save processing graph job: {}", e);
+    }
+}
+
+/// Record a graph job's final status in the processing history
+async fn finish_graph_job(app: &AppHandle, key: String, status: &str, error: Option<String>) {
+    let progress = if status == "completed" { 100.0 } else { 0.0 };
+    if let Err(e) =
+        update_processing_job(app.clone(), key, status.to_string(), progress, error).await
+    {
+        log::warn!("Failed to update processing graph job: {}", e);
+    }
+}
+
+async fn emit_skipped(app: &AppHandle, graph_id: &str, nodes: &[JobNode], skipped: &[String]) {
+    for id in skipped {
+        let progress = graph_progress(graph_id, nodes, id, JobNodeStatus::Skipped, None);
+        let _ = PROCESSING_GRAPH_PROGRESS.emit(app, &progress);
+        finish_graph_job(
+            app,
+            graph_job_key(graph_id, id),
+            "cancelled",
+            Some("Skipped".to_string()),
+        )
+        .await;
+    }
+}
+
+/// Run one job under `job_id`, the namespaced key from [`graph_job_key`]
+async fn run_graph_task(
+    app: &AppHandle,
+    job_id: &str,
+    task: ProcessingGraphTask,
+) -> Result<(), String> {
+    match task {
+        ProcessingGraphTask::Ffmpeg {
+            command_args,
+            input_path,
+            output_path,
+            debug_overlay,
+        } => {
+            execute_ffmpeg_command(
+                app.clone(),
+                job_id.to_string(),
+                command_args,
+                input_path,
+                output_path,
+                Some(debug_overlay),
+            )
+            .await
+        }
+        ProcessingGraphTask::Transcribe {
+            input_path,
+            output_path,
+            language,
+        } => {
+            let config = get_ai_config(app.clone()).await?;
+            let api_key = config
+                .whisper_api_key
+                .filter(|key| !key.trim().is_empty())
+                .or_else(|| local_whisper_enabled().then(String::new))
+                .ok_or("Whisper API key is not configured")?;
+            // Cancelling drops the transcription, which stops its request or process
+            let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+            ACTIVE_JOBS
+                .lock()
+                .await
+                .insert(job_id.to_string(), cancel_tx);
+            let transcribed = tokio::select! {
+                result = transcribe_file_segments(
+                    app,
+                    &input_path,
+                    &api_key,
+                    language.as_deref(),
+                    config.whisper_endpoint_url.as_deref(),
+                    config.whisper_model.as_deref(),
+                    false,
+                ) => result,
+                Ok(()) = &mut cancel_rx => Err("Transcription cancelled".to_string()),
+            };
+            ACTIVE_JOBS.lock().await.remove(job_id);
+            let transcript = transcribed?;
+            let format = match Path::new(&output_path)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .as_deref()
+            {
+                Some("vtt") => TranscriptExportFormat::Vtt,
+                Some("json") => TranscriptExportFormat::Json,
+                _ => TranscriptExportFormat::Srt,
+            };
+            let content = export_transcript(&transcript, format)?;
+            tokio::fs::write(&output_path, content)
+                .await
+                .map_err(|e| format!("Failed to write transcript: {}", e))
+        }
+    }
+}
+
+/// Start jobs as their dependencies complete; jobs behind a failure are skipped
+async fn run_processing_graph(
+    app: AppHandle,
+    graph_id: String,
+    mut tasks: HashMap<String, ProcessingGraphTask>,
+) {
+    let mut running = JoinSet::new();
+    loop {
+        let started: Vec<String> = {
+            let mut graphs = PROCESSING_GRAPHS.lock().await;
+            let Some(state) = graphs.get_mut(&graph_id) else {
+                break;
+            };
+            let slots = MAX_PARALLEL_GRAPH_JOBS.saturating_sub(running.len());
+            let ready: Vec<String> = if state.cancelled {
+                Vec::new()
+            } else {
+                ready_jobs(&state.nodes).into_iter().take(slots).collect()
+            };
+            for id in &ready {
+                if let Some(node) = state.nodes.iter_mut().find(|n| &n.id == id) {
+                    node.status = JobNodeStatus::Running;
+                }
+                let progress =
+                    graph_progress(&graph_id, &state.nodes, id, JobNodeStatus::Running, None);
+                let _ = PROCESSING_GRAPH_PROGRESS.emit(&app, &progress);
+            }
+            ready
+        };
+        for id in started {
+            let Some(task) = tasks.remove(&id) else {
+                continue;
+            };
+            let app = app.clone();
+            let key = graph_job_key(&graph_id, &id);
+            running.spawn(async move {
+                let result = run_graph_task(&app, &key, task).await;
+                (id, result)
+            });
+        }
+
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (id, result) = match joined {
+            Ok(finished) => finished,
+            Err(e) => {
+                log::error!("Processing graph job panicked: {}", e);
+                continue;
+            }
+        };
+        let mut graphs = PROCESSING_GRAPHS.lock().await;
+        let Some(state) = graphs.get_mut(&graph_id) else {
+            break;
+        };
+        let (status, error) = match result {
+            Ok(()) => (JobNodeStatus::Completed, None),
+            Err(error) => (JobNodeStatus::Failed, Some(error)),
+        };
+        if let Some(node) = state.nodes.iter_mut().find(|n| n.id == id) {
+            node.status = status;
+        }
+        let history_status = match status {
+            JobNodeStatus::Completed => "completed",
+            _ if state.cancelled => "cancelled",
+            _ => "failed",
+        };
+        finish_graph_job(
+            &app,
+            graph_job_key(&graph_id, &id),
+            history_status,
+            error.clone(),
+        )
+        .await;
+        let skipped = skip_blocked_jobs(&mut state.nodes);
+        let progress = graph_progress(&graph_id, &state.nodes, &id, status, error);
+        let _ = PROCESSING_GRAPH_PROGRESS.emit(&app, &progress);
+        emit_skipped(&app, &graph_id, &state.nodes, &skipped).await;
+    }
+    PROCESSING_GRAPHS.lock().await.remove(&graph_id);
+}
+
+/// Queue jobs with dependencies and run them in the background; returns the
+/// graph id. Each job is saved in the processing history and reports
+/// `processing-progress` as `<graph_id>:<job_id>`.
+#[tauri::command]
+pub async fn submit_processing_graph(
+    app: AppHandle,
+    jobs: Vec<ProcessingGraphJob>,
+) -> Result<String, String> {
+    if jobs.is_empty() {
+        return Err("No jobs to run".to_string());
+    }
+    for job in &jobs {
+        if let ProcessingGraphTask::Ffmpeg { command_args, .. } = &job.task {
+            validate_ffmpeg_args(command_args)?;
+        }
+    }
+    let nodes: Vec<JobNode> = jobs
+        .iter()
+        .map(|job| JobNode {
+            id: job.id.clone(),
+            depends_on: job.depends_on.clone(),
+            status: JobNodeStatus::Pending,
+        })
+        .collect();
+    validate_job_graph(&nodes)?;
+
+    let graph_id = uuid::Uuid::new_v4().to_string();
+    for job in &jobs {
+        persist_graph_job(&app, graph_job_key(&graph_id, &job.id), &job.task).await;
+    }
+    PROCESSING_GRAPHS.lock().await.insert(
+        graph_id.clone(),
+        GraphState {
+            nodes,
+            cancelled: false,
+        },
+    );
+    let tasks = jobs.into_iter().map(|job| (job.id, job.task)).collect();
+    tauri::async_runtime::spawn(run_processing_graph(app, graph_id.clone(), tasks));
+    Ok(graph_id)
+}
+
+/// Skip the graph's pending jobs and stop its running FFmpeg and Whisper jobs
+#[tauri::command]
+pub async fn cancel_processing_graph(app: AppHandle, graph_id: String) -> Result<(), String> {
+    let running: Vec<String> = {
+        let mut graphs = PROCESSING_GRAPHS.lock().await;
+        let state = graphs
+            .get_mut(&graph_id)
+            .ok_or("Processing graph not found")?;
+        state.cancelled = true;
+        let mut skipped = Vec::new();
+        for node in state.nodes.iter_mut() {
+            if node.status == JobNodeStatus::Pending {
+                node.status = JobNodeStatus::Skipped;
+                skipped.push(node.id.clone());
+            }
+        }
+        emit_skipped(&app, &graph_id, &state.nodes, &skipped).await;
+        state
+            .nodes
+            .iter()
+            .filter(|node| node.status == JobNodeStatus::Running)
+            .map(|node| node.id.clone())
+            .collect()
+    };
+    let mut jobs = ACTIVE_JOBS.lock().await;
+    for id in running {
+        if let Some(cancel_tx) = jobs.remove(&graph_job_key(&graph_id, &id)) {
+            cancel_tx.send(()).ok();
+        }
+    }
+    Ok(())
+}
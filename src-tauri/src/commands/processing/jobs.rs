@@ -1,6 +1,90 @@
 use super::*;
 
-/// Execute FFmpeg command with progress tracking
+/// Fonts tried for the debug overlay. drawtext has no built-in font, so
+/// FFmpeg builds without fontconfig need an explicit `fontfile`.
+const DEBUG_OVERLAY_FONTS: &[&str] = &[
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "/System/Library/Fonts/Helvetica.ttc",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/liberation/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+];
+
+fn debug_overlay_font() -> Option<PathBuf> {
+    DEBUG_OVERLAY_FONTS
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+}
+
+/// Frame number and timecode in the top-left corner, for checking cut accuracy.
+/// The font path uses forward slashes and escaped colons so Windows drive
+/// letters survive filter parsing.
+fn debug_overlay_filter(font: &Path) -> String {
+    let font = font
+        .to_string_lossy()
+        .replace('\\', "/")
+        .replace(':', "\\:")
+        .replace('\'', "");
+    format!(
+        "drawtext=fontfile='{}':text='frame %{{frame_num}}  %{{pts\\:hms}}'\
+         :x=10:y=10:fontsize=h/24:fontcolor=white:box=1:boxcolor=black@0.6:boxborderw=6",
+        font
+    )
+}
+
+/// Add the debug overlay to the video filter chain. A copied video stream
+/// has to be re-encoded for the overlay, so it switches to libx264.
+fn apply_debug_overlay(args: &mut Vec<String>, font: &Path) -> Result<(), String> {
+    if args
+        .iter()
+        .any(|arg| matches!(arg.as_str(), "-filter_complex" | "-lavfi"))
+    {
+        return Err("Debug overlay can't be combined with -filter_complex".to_string());
+    }
+    if args.iter().any(|arg| arg == "-vn") {
+        return Err("Debug overlay needs a video output".to_string());
+    }
+
+    let overlay = debug_overlay_filter(font);
+    let filter_pos = args
+        .iter()
+        .position(|arg| matches!(arg.as_str(), "-vf" | "-filter:v" | "-filter:v:0"));
+    match filter_pos.filter(|pos| pos + 1 < args.len()) {
+        Some(pos) => args[pos + 1] = format!("{},{}", args[pos + 1], overlay),
+        None => {
+            let insert_pos = args.len().saturating_sub(1);
+            args.insert(insert_pos, "-vf".to_string());
+            args.insert(insert_pos + 1, overlay);
+        }
+    }
+
+    let mut i = 0;
+    while i + 1 < args.len() {
+        if args[i + 1] == "copy" {
+            match args[i].as_str() {
+                "-c:v" | "-codec:v" | "-vcodec" => args[i + 1] = "libx264".to_string(),
+                "-c" | "-codec" => {
+                    args.insert(i + 2, "-c:v".to_string());
+                    args.insert(i + 3, "libx264".to_string());
+                }
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Execute FFmpeg command with progress tracking. `debug_overlay` burns the
+/// frame number and timecode into the output for verifying cuts.
 #[tauri::command]
 pub async fn execute_ffmpeg_command(
     app: AppHandle,
@@ -8,6 +92,7 @@ pub async fn execute_ffmpeg_command(
     command_args: Vec<String>,
     input_path: String,
     output_path: String,
+    debug_overlay: Option<bool>,
 ) -> Result<(), String> {
     println!("[FFMPEG] Starting execute_ffmpeg_command");
     println!("[FFMPEG] Job ID: {}", job_id);
@@ -16,6 +101,7 @@ pub async fn execute_ffmpeg_command(
     println!("[FFMPEG] Output: {}", output_path);
 
     validate_ffmpeg_args(&command_args)?;
+    let _active_job = track_active_job(ActiveJob::processing(&job_id, &output_path));
 
     let ffmpeg_path = get_ffmpeg_path(&app).await.ok_or("FFmpeg not found")?;
     println!("[FFMPEG] FFmpeg path: {:?}", ffmpeg_path);
@@ -29,184 +115,80 @@ pub async fn execute_ffmpeg_command(
     );
 
     let mut args = command_args;
-    if !args.iter().any(|a| a == "-progress") {
-        let insert_pos = args.len().saturating_sub(1);
-        args.insert(insert_pos, "-progress".to_string());
-        args.insert(insert_pos + 1, "pipe:2".to_string());
-    }
-
-    println!("[FFMPEG] Final args count: {}", args.len());
-    for (i, arg) in args.iter().enumerate() {
-        println!("[FFMPEG]   arg[{}]: '{}'", i, arg);
+    if debug_overlay.unwrap_or(false) {
+        let font = debug_overlay_font().ok_or(
+            "Debug overlay needs a font, but none was found (install DejaVu Sans or Arial)",
+        )?;
+        apply_debug_overlay(&mut args, &font)?;
     }
+    let runner = FfmpegRunner::new(&ffmpeg_path, args).duration(total_duration_secs);
+    println!("[FFMPEG] Final args: {:?}", runner.build_args());
 
     let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
-
-    {
-        let mut jobs = ACTIVE_JOBS.lock().await;
-        jobs.insert(job_id.clone(), cancel_tx);
-    }
-
-    println!("[FFMPEG] Spawning FFmpeg process...");
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args(&args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-    cmd.hide_window();
-    let mut child = cmd.spawn().map_err(|e| {
-        println!("[FFMPEG] Failed to spawn: {}", e);
-        format!("Failed to start FFmpeg: {}", e)
-    })?;
-    println!("[FFMPEG] FFmpeg process spawned successfully");
-
-    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-    let mut reader = BufReader::new(stderr).lines();
-
-    let app_clone = app.clone();
-    let job_id_clone = job_id.clone();
-
-    let progress_task = tokio::spawn(async move {
-        let mut current_frame: i64 = 0;
-        let mut current_fps: f64 = 0.0;
-        let mut current_time = String::new();
-        let mut current_time_secs: f64 = 0.0;
-        let mut current_size = String::new();
-        let mut current_speed = String::new();
-        let mut error_lines: Vec<String> = Vec::new();
-
-        while let Ok(Some(line)) = reader.next_line().await {
-            println!("[FFMPEG STDERR] {}", line);
-
-            if line.contains("Error") || line.contains("error") || line.contains("Invalid") {
-                error_lines.push(line.clone());
-            }
-
-            if line.starts_with("frame=") {
-                if let Some(val) = line.strip_prefix("frame=") {
-                    current_frame = val
-                        .trim()
-                        .split_whitespace()
-                        .next()
-                        .and_then(|s| s.parse().ok())
-                        .unwrap_or(current_frame);
-                }
-            } else if line.starts_with("fps=") {
-                if let Some(val) = line.strip_prefix("fps=") {
-                    current_fps = val
-                        .trim()
-                        .split_whitespace()
-                        .next()
-                        .and_then(|s| s.parse().ok())
-                        .unwrap_or(current_fps);
-                }
-            } else if line.starts_with("out_time_us=") {
-                if let Some(val) = line.strip_prefix("out_time_us=") {
-                    if let Ok(us) = val.trim().parse::<i64>() {
-                        current_time_secs = us as f64 / 1_000_000.0;
-                    }
-                }
-            } else if line.starts_with("out_time=") {
-                if let Some(val) = line.strip_prefix("out_time=") {
-                    let time_str = val.trim();
-                    current_time = if let Some(dot_pos) = time_str.find('.') {
-                        time_str[..dot_pos].to_string()
-                    } else {
-                        time_str.to_string()
-                    };
-                }
-            } else if line.starts_with("total_size=") {
-                if let Some(val) = line.strip_prefix("total_size=") {
-                    let bytes: i64 = val.trim().parse().unwrap_or(0);
-                    current_size = format!("{:.1} MB", bytes as f64 / 1_000_000.0);
-                }
-            } else if line.starts_with("speed=") {
-                if let Some(val) = line.strip_prefix("speed=") {
-                    current_speed = val.trim().to_string();
-                }
-            } else if line == "progress=continue" || line == "progress=end" {
-                let percent = if total_duration_secs > 0.0 && current_time_secs > 0.0 {
-                    (current_time_secs / total_duration_secs * 100.0).min(100.0)
-                } else if total_frames > 0 && current_frame > 0 {
-                    (current_frame as f64 / total_frames as f64 * 100.0).min(100.0)
-                } else {
-                    0.0
-                };
-
-                println!(
-                    "[FFMPEG PROGRESS] time_secs={}, duration={}, percent={:.1}%",
-                    current_time_secs, total_duration_secs, percent
-                );
-
-                let progress = ProcessingProgress {
-                    job_id: job_id_clone.clone(),
-                    percent,
-                    frame: current_frame,
+    ACTIVE_JOBS.lock().await.insert(job_id.clone(), cancel_tx);
+
+    let result = runner
+        .run(Some(&mut cancel_rx), |progress| {
+            let _ = PROCESSING_PROGRESS.emit(
+                &app,
+                &ProcessingProgress {
+                    job_id: job_id.clone(),
+                    percent: progress.percent(Some(total_frames)),
+                    frame: progress.frame,
                     total_frames,
-                    fps: current_fps,
-                    speed: current_speed.clone(),
-                    time: current_time.clone(),
-                    size: current_size.clone(),
-                };
-
-                let _ = app_clone.emit("processing-progress", &progress);
-            }
-        }
-
-        if !error_lines.is_empty() {
-            println!("[FFMPEG] Collected errors: {:?}", error_lines);
-        }
-    });
-
-    tokio::select! {
-        status = child.wait() => {
-            println!("[FFMPEG] Process exited with status: {:?}", status);
-            progress_task.abort();
-
-            {
-                let mut jobs = ACTIVE_JOBS.lock().await;
-                jobs.remove(&job_id);
-            }
-
-            match status {
-                Ok(exit_status) if exit_status.success() => {
-                    println!("[FFMPEG] Success! Output: {}", output_path);
-                    let _ = app.emit("processing-progress", ProcessingProgress {
-                        job_id: job_id.clone(),
-                        percent: 100.0,
-                        frame: total_frames,
-                        total_frames,
-                        fps: 0.0,
-                        speed: "done".to_string(),
-                        time: "".to_string(),
-                        size: "".to_string(),
-                    });
-                    Ok(())
-                }
-                Ok(exit_status) => {
-                    println!("[FFMPEG] Failed with exit code: {:?}", exit_status.code());
-                    Err(format!("FFmpeg exited with code: {:?}", exit_status.code()))
-                }
-                Err(e) => {
-                    println!("[FFMPEG] Process error: {}", e);
-                    Err(format!("FFmpeg process error: {}", e))
-                }
-            }
+                    fps: progress.fps,
+                    speed: progress.speed.clone(),
+                    time: progress.time.clone(),
+                    size: format!("{:.1} MB", progress.total_size as f64 / 1_000_000.0),
+                },
+            );
+        })
+        .await;
+    ACTIVE_JOBS.lock().await.remove(&job_id);
+
+    match result {
+        Ok(()) => {
+            println!("[FFMPEG] Success! Output: {}", output_path);
+            let _ = PROCESSING_PROGRESS.emit(
+                &app,
+                &ProcessingProgress {
+                    job_id: job_id.clone(),
+                    percent: 100.0,
+                    frame: total_frames,
+                    total_frames,
+                    fps: 0.0,
+                    speed: "done".to_string(),
+                    time: "".to_string(),
+                    size: "".to_string(),
+                },
+            );
+            Ok(())
         }
-        _ = &mut cancel_rx => {
-            child.kill().await.ok();
-            progress_task.abort();
+        Err(FfmpegError::Cancelled) => {
             tokio::fs::remove_file(&output_path).await.ok();
-
-            {
-                let mut jobs = ACTIVE_JOBS.lock().await;
-                jobs.remove(&job_id);
-            }
-
             Err("Processing cancelled".to_string())
         }
+        Err(error) => {
+            println!("[FFMPEG] Failed: {}", error);
+            Err(error.into())
+        }
     }
 }
 
+/// Sync the low-priority and ffmpeg thread-limit settings from the frontend.
+/// Applies to processing jobs and yt-dlp merges started after the call.
+#[tauri::command]
+pub async fn set_process_priority(
+    low_priority: bool,
+    ffmpeg_threads: Option<u32>,
+) -> Result<(), String> {
+    set_process_priority_config(ProcessPriorityConfig {
+        low_priority,
+        ffmpeg_threads,
+    });
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn cancel_ffmpeg(job_id: String) -> Result<(), String> {
     let mut jobs = ACTIVE_JOBS.lock().await;
@@ -218,39 +200,93 @@ pub async fn cancel_ffmpeg(job_id: String) -> Result<(), String> {
     }
 }
 
+const PROCESSING_JOB_COLUMNS: &str = "id, input_path, output_path, task_type, user_prompt,
+         ffmpeg_command, status, progress, error_message, created_at, completed_at,
+         output_size, output_duration";
+
+fn processing_job_from_row(row: &rusqlite::Row) -> rusqlite::Result<ProcessingJob> {
+    Ok(ProcessingJob {
+        id: row.get(0)?,
+        input_path: row.get(1)?,
+        output_path: row.get(2)?,
+        task_type: row.get(3)?,
+        user_prompt: row.get(4)?,
+        ffmpeg_command: row.get(5)?,
+        status: row.get(6)?,
+        progress: row.get(7)?,
+        error_message: row.get(8)?,
+        created_at: row.get(9)?,
+        completed_at: row.get(10)?,
+        output_size: row.get(11)?,
+        output_duration: row.get(12)?,
+    })
+}
+
+fn get_processing_job(id: &str) -> Result<ProcessingJob, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM processing_jobs WHERE id = ?1",
+            PROCESSING_JOB_COLUMNS
+        ),
+        params![id],
+        processing_job_from_row,
+    )
+    .map_err(|_| "Processing job not found".to_string())
+}
+
+/// Remove draft jobs from `create_processing_session_from_history` that were
+/// never saved, once they are older than a day
+pub(super) fn remove_stale_draft_jobs(conn: &rusqlite::Connection) {
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+    if let Err(e) = conn.execute(
+        "DELETE FROM processing_jobs WHERE status = 'draft' AND created_at < ?1",
+        params![cutoff],
+    ) {
+        log::warn!("Failed to remove stale draft jobs: {}", e);
+    }
+}
+
+/// Store the output's size and duration; a file FFprobe can't read keeps only its size
+async fn record_processing_output(app: &AppHandle, id: &str) -> Result<(), String> {
+    let Some(output_path) = get_processing_job(id)?.output_path else {
+        return Ok(());
+    };
+    let Ok(file) = tokio::fs::metadata(&output_path).await else {
+        return Ok(());
+    };
+    let duration = get_video_metadata(app.clone(), output_path)
+        .await
+        .ok()
+        .map(|metadata| metadata.duration)
+        .filter(|duration| *duration > 0.0);
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE processing_jobs SET output_size = ?1, output_duration = ?2 WHERE id = ?3",
+        params![file.len(), duration, id],
+    )
+    .map_err(|e| format!("Failed to update job: {}", e))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_processing_history(
     _app: AppHandle,
     limit: i32,
 ) -> Result<Vec<ProcessingJob>, String> {
     let conn = get_db()?;
+    remove_stale_draft_jobs(&conn);
 
+    // Drafts are sessions still open in the editor, not jobs that ran
     let mut stmt = conn
-        .prepare(
-            "SELECT id, input_path, output_path, task_type, user_prompt, 
-         ffmpeg_command, status, progress, error_message, created_at, completed_at
-         FROM processing_jobs 
-         ORDER BY created_at DESC 
-         LIMIT ?1",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM processing_jobs WHERE status != 'draft' ORDER BY created_at DESC LIMIT ?1",
+            PROCESSING_JOB_COLUMNS
+        ))
         .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
     let jobs = stmt
-        .query_map(params![limit], |row| {
-            Ok(ProcessingJob {
-                id: row.get(0)?,
-                input_path: row.get(1)?,
-                output_path: row.get(2)?,
-                task_type: row.get(3)?,
-                user_prompt: row.get(4)?,
-                ffmpeg_command: row.get(5)?,
-                status: row.get(6)?,
-                progress: row.get(7)?,
-                error_message: row.get(8)?,
-                created_at: row.get(9)?,
-                completed_at: row.get(10)?,
-            })
-        })
+        .query_map(params![limit], processing_job_from_row)
         .map_err(|e| format!("Query failed: {}", e))?
         .filter_map(|r| r.ok())
         .collect();
@@ -258,6 +294,82 @@ pub async fn get_processing_history(
     Ok(jobs)
 }
 
+/// Output path for a re-run next to the original, e.g. `clip_rerun_20250101_120000.mp4`
+fn rerun_output_path(output_path: &str, timestamp: &str) -> PathBuf {
+    let path = Path::new(output_path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "output".to_string());
+    let file_name = match path.extension() {
+        Some(ext) => format!("{}_rerun_{}.{}", stem, timestamp, ext.to_string_lossy()),
+        None => format!("{}_rerun_{}", stem, timestamp),
+    };
+    path.with_file_name(file_name)
+}
+
+/// Run a finished job's command again as a new history entry. The input must
+/// still exist; the output gets a new name so the previous result is kept.
+#[tauri::command]
+pub async fn rerun_processing_job(app: AppHandle, id: String) -> Result<ProcessingJob, String> {
+    let job = get_processing_job(&id)?;
+    if !Path::new(&job.input_path).is_file() {
+        return Err(format!("Input file no longer exists: {}", job.input_path));
+    }
+    let mut args = parse_ffmpeg_command_args(&job.ffmpeg_command)?;
+    let previous_output = match job.output_path.clone() {
+        Some(path) => path,
+        None => args.last().cloned().ok_or("Job has no output path")?,
+    };
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let output_path = rerun_output_path(&previous_output, &timestamp)
+        .to_string_lossy()
+        .to_string();
+    match args.iter().rposition(|arg| *arg == previous_output) {
+        Some(pos) => args[pos] = output_path.clone(),
+        None => return Err("Job command does not write to its recorded output".to_string()),
+    }
+
+    let new_id = uuid::Uuid::new_v4().to_string();
+    save_processing_job(
+        app.clone(),
+        new_id.clone(),
+        job.input_path.clone(),
+        Some(output_path.clone()),
+        job.task_type,
+        job.user_prompt,
+        args_to_display_command(&args),
+    )
+    .await?;
+    let result = execute_ffmpeg_command(
+        app.clone(),
+        new_id.clone(),
+        args,
+        job.input_path,
+        output_path,
+        None,
+    )
+    .await;
+    let (status, progress, error) = match result {
+        Ok(()) => ("completed", 100.0, None),
+        Err(error) => ("failed", 0.0, Some(error)),
+    };
+    update_processing_job(app, new_id.clone(), status.to_string(), progress, error).await?;
+    get_processing_job(&new_id)
+}
+
+/// Reveal a job's output file in the system file manager
+#[tauri::command]
+pub async fn open_processing_output(id: String) -> Result<(), String> {
+    let output_path = get_processing_job(&id)?
+        .output_path
+        .ok_or("This job has no output file")?;
+    if !Path::new(&output_path).exists() {
+        return Err(format!("Output file no longer exists: {}", output_path));
+    }
+    crate::commands::open_file_location(output_path).await
+}
+
 #[tauri::command]
 pub async fn delete_processing_job(_app: AppHandle, id: String) -> Result<(), String> {
     let conn = get_db()?;
@@ -294,8 +406,12 @@ pub async fn save_processing_job(
     let status = "pending".to_string();
 
     conn.execute(
+        // Upsert so a draft job from `create_processing_session_from_history` gets filled in
         "INSERT INTO processing_jobs (id, input_path, output_path, task_type, user_prompt, ffmpeg_command, status, progress, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT(id) DO UPDATE SET input_path = excluded.input_path, output_path = excluded.output_path,
+             task_type = excluded.task_type, user_prompt = excluded.user_prompt,
+             ffmpeg_command = excluded.ffmpeg_command, status = excluded.status, progress = 0",
         params![id, input_path, output_path, task_type, user_prompt, ffmpeg_command, status, 0.0, created_at],
     )
     .map_err(|e| format!("Failed to save job: {}", e))?;
@@ -305,34 +421,46 @@ pub async fn save_processing_job(
 
 #[tauri::command]
 pub async fn update_processing_job(
-    _app: AppHandle,
+    app: AppHandle,
     id: String,
     status: String,
     progress: f64,
     error_message: Option<String>,
 ) -> Result<(), String> {
-    let conn = get_db()?;
-
-    if status == "completed" || status == "failed" || status == "cancelled" {
-        let completed_at = chrono::Utc::now().to_rfc3339();
-        conn.execute(
-            "UPDATE processing_jobs SET status = ?1, progress = ?2, error_message = ?3, completed_at = ?4 WHERE id = ?5",
-            params![status, progress, error_message, completed_at, id],
-        )
-        .map_err(|e| format!("Failed to update job: {}", e))?;
-    } else {
-        conn.execute(
-            "UPDATE processing_jobs SET status = ?1, progress = ?2, error_message = ?3 WHERE id = ?4",
-            params![status, progress, error_message, id],
-        )
-        .map_err(|e| format!("Failed to update job: {}", e))?;
+    {
+        let conn = get_db()?;
+
+        if status == "completed" || status == "failed" || status == "cancelled" {
+            let completed_at = chrono::Utc::now().to_rfc3339();
+            conn.execute(
+                "UPDATE processing_jobs SET status = ?1, progress = ?2, error_message = ?3, completed_at = ?4 WHERE id = ?5",
+                params![status, progress, error_message, completed_at, id],
+            )
+            .map_err(|e| format!("Failed to update job: {}", e))?;
+        } else {
+            conn.execute(
+                "UPDATE processing_jobs SET status = ?1, progress = ?2, error_message = ?3 WHERE id = ?4",
+                params![status, progress, error_message, id],
+            )
+            .map_err(|e| format!("Failed to update job: {}", e))?;
+        }
     }
 
+    if status == "completed" {
+        // The job itself succeeded; missing size/duration must not fail it
+        if let Err(e) = record_processing_output(&app, &id).await {
+            log::warn!("Failed to record output of processing job {}: {}", id, e);
+        }
+    }
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_processing_presets(_app: AppHandle) -> Result<Vec<ProcessingPreset>, String> {
+    list_processing_presets()
+}
+
+pub(crate) fn list_processing_presets() -> Result<Vec<ProcessingPreset>, String> {
     let conn = get_db()?;
 
     let mut stmt = conn
@@ -384,6 +512,26 @@ pub async fn save_processing_preset(
     Ok(())
 }
 
+/// Insert a preset from another install, replacing the one with the same id
+pub(crate) fn import_processing_preset(preset: &ProcessingPreset) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO processing_presets (id, name, description, task_type, prompt_template, icon, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            preset.id,
+            preset.name,
+            preset.description,
+            preset.task_type,
+            preset.prompt_template,
+            preset.icon,
+            preset.created_at
+        ],
+    )
+    .map_err(|e| format!("Failed to save preset: {}", e))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn delete_processing_preset(_app: AppHandle, id: String) -> Result<(), String> {
     let conn = get_db()?;
@@ -393,3 +541,52 @@ pub async fn delete_processing_preset(_app: AppHandle, id: String) -> Result<(),
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn debug_overlay_joins_the_video_filter_and_reencodes_copies() {
+        let mut cut = args(&["-ss", "5", "-i", "in.mp4", "-c", "copy", "out.mp4"]);
+        let font = Path::new("/fonts/DejaVuSans.ttf");
+        let overlay = debug_overlay_filter(font);
+        assert!(overlay.starts_with("drawtext=fontfile='/fonts/DejaVuSans.ttf':text="));
+        assert!(
+            debug_overlay_filter(Path::new("C:\\Windows\\Fonts\\arial.ttf"))
+                .starts_with("drawtext=fontfile='C\\:/Windows/Fonts/arial.ttf':")
+        );
+
+        apply_debug_overlay(&mut cut, font).unwrap();
+        assert_eq!(
+            cut,
+            args(&[
+                "-ss", "5", "-i", "in.mp4", "-c", "copy", "-c:v", "libx264", "-vf", &overlay,
+                "out.mp4",
+            ])
+        );
+
+        let mut scaled = args(&["-i", "in.mp4", "-vf", "scale=1280:-2", "out.mp4"]);
+        apply_debug_overlay(&mut scaled, font).unwrap();
+        assert_eq!(scaled[3], format!("scale=1280:-2,{}", overlay));
+
+        let mut complex = args(&["-i", "in.mp4", "-filter_complex", "[0:v]split", "out.mp4"]);
+        assert!(apply_debug_overlay(&mut complex, font).is_err());
+    }
+
+    #[test]
+    fn reruns_write_next_to_the_previous_output() {
+        assert_eq!(
+            rerun_output_path("/videos/clip_edited.mp4", "20250101_120000"),
+            Path::new("/videos/clip_edited_rerun_20250101_120000.mp4")
+        );
+        assert_eq!(
+            rerun_output_path("/videos/frames", "1"),
+            Path::new("/videos/frames_rerun_1")
+        );
+    }
+}
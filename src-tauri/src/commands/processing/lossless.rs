@@ -0,0 +1,469 @@
+use super::*;
+use crate::commands::sanitize_filename_part;
+use crate::database::get_history_entries_by_ids_from_db;
+use crate::types::HistoryEntry;
+
+/// Sample rates offered for lossless export; CD audio is 44.1 kHz
+const LOSSLESS_SAMPLE_RATES: [u32; 6] = [44_100, 48_000, 88_200, 96_000, 176_400, 192_000];
+/// Track numbers of a cue sheet are two digits
+const CUE_MAX_TRACKS: usize = 99;
+
+/// Settings of `export_lossless`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LosslessExportOptions {
+    /// "flac" or "alac" (written as .m4a)
+    pub format: String,
+    /// Resample to this rate; unset keeps each file's own rate
+    pub sample_rate: Option<u32>,
+    /// 16 or 24; unset lets FFmpeg keep the decoded precision
+    pub bit_depth: Option<u32>,
+    /// Shorthand for 44.1 kHz / 16-bit
+    pub cd_quality: bool,
+    /// Folder for the exported files; defaults to each download's folder
+    pub output_dir: Option<String>,
+    /// Number the tracks and write an album .cue sheet next to them
+    pub cue_sheet: bool,
+    /// Cue sheet title; defaults to the output folder name
+    pub album_title: Option<String>,
+    pub performer: Option<String>,
+    /// Copy tags and cover art from the download (default on)
+    pub keep_tags: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LosslessExportReport {
+    #[serde(flatten)]
+    pub batch: AudioBatchReport,
+    pub cue_sheet_path: Option<String>,
+}
+
+/// Codec, extension and sample formats (16-bit, 24-bit) of a lossless target
+fn lossless_target(
+    format: &str,
+) -> Result<(&'static str, &'static str, [&'static str; 2]), String> {
+    match format {
+        "flac" => Ok(("flac", "flac", ["s16", "s32"])),
+        "alac" => Ok(("alac", "m4a", ["s16p", "s32p"])),
+        other => Err(format!("Unsupported lossless format: {}", other)),
+    }
+}
+
+/// Sample rate and bit depth after applying `cd_quality`, validated
+fn lossless_resolution(
+    options: &LosslessExportOptions,
+) -> Result<(Option<u32>, Option<u32>), String> {
+    let (sample_rate, bit_depth) = if options.cd_quality {
+        (Some(44_100), Some(16))
+    } else {
+        (options.sample_rate, options.bit_depth)
+    };
+    if let Some(rate) = sample_rate.filter(|rate| !LOSSLESS_SAMPLE_RATES.contains(rate)) {
+        return Err(format!("Unsupported sample rate: {} Hz", rate));
+    }
+    if let Some(depth) = bit_depth.filter(|depth| !matches!(depth, 16 | 24)) {
+        return Err(format!("Unsupported bit depth: {}", depth));
+    }
+    Ok((sample_rate, bit_depth))
+}
+
+/// `cover_stream` is the input's attached picture, the only video stream
+/// carried over; a video track of the download is never copied into the audio
+pub(super) fn lossless_export_args(
+    input_path: &str,
+    output_path: &str,
+    format: &str,
+    sample_rate: Option<u32>,
+    bit_depth: Option<u32>,
+    keep_tags: bool,
+    cover_stream: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let (codec, _, sample_fmts) = lossless_target(format)?;
+    let mut args: Vec<String> = ["-y", "-i", input_path, "-map", "0:a:0"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    if let Some(cover) = cover_stream.filter(|_| keep_tags) {
+        args.extend([
+            "-map".to_string(),
+            format!("0:{}", cover),
+            "-c:v".to_string(),
+            "copy".to_string(),
+            "-disposition:v".to_string(),
+            "attached_pic".to_string(),
+        ]);
+    }
+    args.extend([
+        "-map_metadata".to_string(),
+        if keep_tags { "0" } else { "-1" }.to_string(),
+        "-c:a".to_string(),
+        codec.to_string(),
+    ]);
+    // Resampling and bit reduction go through swr with triangular dither
+    if sample_rate.is_some() || bit_depth == Some(16) {
+        let filter = match sample_rate {
+            Some(rate) => format!("aresample={}:dither_method=triangular", rate),
+            None => "aresample=dither_method=triangular".to_string(),
+        };
+        args.extend(["-af".to_string(), filter]);
+    }
+    if let Some(rate) = sample_rate {
+        args.extend(["-ar".to_string(), rate.to_string()]);
+    }
+    match bit_depth {
+        Some(16) => args.extend(["-sample_fmt".to_string(), sample_fmts[0].to_string()]),
+        Some(_) => args.extend([
+            "-sample_fmt".to_string(),
+            sample_fmts[1].to_string(),
+            "-bits_per_raw_sample".to_string(),
+            "24".to_string(),
+        ]),
+        None => {}
+    }
+    args.push(output_path.to_string());
+    Ok(args)
+}
+
+/// Playlist order when every entry has a position, selection order otherwise
+fn album_order(mut entries: Vec<HistoryEntry>, ids: &[String]) -> Vec<HistoryEntry> {
+    if entries.iter().all(|entry| entry.playlist_index.is_some()) {
+        entries.sort_by_key(|entry| entry.playlist_index);
+    } else {
+        entries.sort_by_key(|entry| ids.iter().position(|id| id == &entry.id));
+    }
+    entries
+}
+
+fn cue_text(value: &str) -> String {
+    value.replace('"', "'").replace(['\r', '\n'], " ")
+}
+
+/// Multi-file cue sheet: one FILE per exported track, in order
+pub(super) fn build_cue_sheet(
+    album_title: &str,
+    performer: Option<&str>,
+    tracks: &[(String, String)],
+) -> String {
+    let mut cue = String::from("REM COMMENT \"Youwee\"\n");
+    if let Some(performer) = performer {
+        cue.push_str(&format!("PERFORMER \"{}\"\n", cue_text(performer)));
+    }
+    cue.push_str(&format!("TITLE \"{}\"\n", cue_text(album_title)));
+    for (index, (file_name, title)) in tracks.iter().enumerate() {
+        cue.push_str(&format!(
+            "FILE \"{}\" WAVE\n  TRACK {:02} AUDIO\n    TITLE \"{}\"\n",
+            cue_text(file_name),
+            index + 1,
+            cue_text(title)
+        ));
+        if let Some(performer) = performer {
+            cue.push_str(&format!("    PERFORMER \"{}\"\n", cue_text(performer)));
+        }
+        cue.push_str("    INDEX 01 00:00:00\n");
+    }
+    cue
+}
+
+/// Convert audio downloads to FLAC or ALAC one after another, emitting
+/// `audio-batch-progress` per file. With `cue_sheet` the tracks are numbered
+/// in playlist order and an album .cue is written next to them. Cancel with
+/// `cancel_ffmpeg(batch_id)`; files already exported are kept.
+#[tauri::command]
+pub async fn export_lossless(
+    app: AppHandle,
+    batch_id: String,
+    history_ids: Vec<String>,
+    options: LosslessExportOptions,
+) -> Result<LosslessExportReport, String> {
+    let format = options.format.trim().to_lowercase();
+    let (_, extension, _) = lossless_target(&format)?;
+    let (sample_rate, bit_depth) = lossless_resolution(&options)?;
+    let keep_tags = options.keep_tags.unwrap_or(true);
+    if options.cue_sheet && history_ids.len() > CUE_MAX_TRACKS {
+        return Err(format!(
+            "A cue sheet holds at most {} tracks",
+            CUE_MAX_TRACKS
+        ));
+    }
+    let output_dir = options
+        .output_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    if let Some(dir) = output_dir.as_ref() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create output folder: {}", e))?;
+    }
+    let entries = album_order(
+        get_history_entries_by_ids_from_db(history_ids.clone())?,
+        &history_ids,
+    );
+    let ffmpeg_path = get_ffmpeg_path(&app).await.ok_or("FFmpeg not found")?;
+
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    ACTIVE_JOBS.lock().await.insert(batch_id.clone(), cancel_tx);
+    let _active_job = track_active_job(ActiveJob::processing(&batch_id, &format));
+
+    let total = entries.len();
+    let mut report = AudioBatchReport {
+        batch_id: batch_id.clone(),
+        converted: 0,
+        skipped: 0,
+        failed: 0,
+        cancelled: false,
+        items: Vec::with_capacity(total),
+    };
+    let mut cue_tracks: Vec<(String, String)> = Vec::new();
+    let mut cue_dir: Option<PathBuf> = output_dir.clone();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let input = match processable_history_file(entry) {
+            Ok(input) => input,
+            Err(error) => {
+                report.skipped += 1;
+                report.items.push(AudioBatchItem {
+                    input_path: entry.filepath.clone(),
+                    output_path: None,
+                    status: "skipped".to_string(),
+                    error: Some(error),
+                });
+                emit_audio_batch_progress(
+                    &app,
+                    &batch_id,
+                    index,
+                    total,
+                    &entry.filepath,
+                    100.0,
+                    "skipped",
+                );
+                continue;
+            }
+        };
+        let input_path = input.to_string_lossy().to_string();
+        let dir = output_dir
+            .clone()
+            .or_else(|| input.parent().map(Path::to_path_buf))
+            .unwrap_or_else(|| PathBuf::from("."));
+        cue_dir.get_or_insert_with(|| dir.clone());
+        let stem = if options.cue_sheet {
+            format!(
+                "{:02} - {}",
+                cue_tracks.len() + 1,
+                sanitize_filename_part(&entry.title, "Track")
+            )
+        } else {
+            input
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| "audio".to_string())
+        };
+        let output = unique_output_path(&dir, &stem, extension);
+        let output_path = output.to_string_lossy().to_string();
+        let cover_stream = if keep_tags {
+            probe_attached_picture(&app, &input_path).await
+        } else {
+            None
+        };
+        // No `?` past this point: the ACTIVE_JOBS entry must be removed below
+        let args = match lossless_export_args(
+            &input_path,
+            &output_path,
+            &format,
+            sample_rate,
+            bit_depth,
+            keep_tags,
+            cover_stream,
+        ) {
+            Ok(args) => args,
+            Err(error) => {
+                report.failed += 1;
+                report.items.push(AudioBatchItem {
+                    input_path: input_path.clone(),
+                    output_path: None,
+                    status: "failed".to_string(),
+                    error: Some(error),
+                });
+                emit_audio_batch_progress(
+                    &app,
+                    &batch_id,
+                    index,
+                    total,
+                    &input_path,
+                    100.0,
+                    "failed",
+                );
+                continue;
+            }
+        };
+        emit_audio_batch_progress(
+            &app,
+            &batch_id,
+            index,
+            total,
+            &input_path,
+            0.0,
+            "converting",
+        );
+
+        let result = FfmpegRunner::new(&ffmpeg_path, args)
+            .run(Some(&mut cancel_rx), |progress| {
+                let percent = progress.percent(None);
+                if percent > 0.0 {
+                    emit_audio_batch_progress(
+                        &app,
+                        &batch_id,
+                        index,
+                        total,
+                        &input_path,
+                        percent.min(99.0),
+                        "converting",
+                    );
+                }
+            })
+            .await;
+
+        match result {
+            Ok(()) => {
+                report.converted += 1;
+                if let Some(file_name) = output.file_name() {
+                    cue_tracks.push((file_name.to_string_lossy().to_string(), entry.title.clone()));
+                }
+                report.items.push(AudioBatchItem {
+                    input_path: input_path.clone(),
+                    output_path: Some(output_path),
+                    status: "converted".to_string(),
+                    error: None,
+                });
+                emit_audio_batch_progress(
+                    &app,
+                    &batch_id,
+                    index,
+                    total,
+                    &input_path,
+                    100.0,
+                    "converted",
+                );
+            }
+            Err(FfmpegError::Cancelled) => {
+                tokio::fs::remove_file(&output_path).await.ok();
+                report.cancelled = true;
+                break;
+            }
+            Err(error @ FfmpegError::Spawn(_)) => {
+                ACTIVE_JOBS.lock().await.remove(&batch_id);
+                return Err(error.into());
+            }
+            Err(error) => {
+                tokio::fs::remove_file(&output_path).await.ok();
+                report.failed += 1;
+                report.items.push(AudioBatchItem {
+                    input_path: input_path.clone(),
+                    output_path: None,
+                    status: "failed".to_string(),
+                    error: Some(error.to_string()),
+                });
+                emit_audio_batch_progress(
+                    &app,
+                    &batch_id,
+                    index,
+                    total,
+                    &input_path,
+                    100.0,
+                    "failed",
+                );
+            }
+        }
+    }
+    ACTIVE_JOBS.lock().await.remove(&batch_id);
+
+    let mut cue_sheet_path = None;
+    if options.cue_sheet && !cue_tracks.is_empty() {
+        let dir = cue_dir.unwrap_or_else(|| PathBuf::from("."));
+        let album_title = options
+            .album_title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map(str::to_string)
+            .or_else(|| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| "Album".to_string());
+        let cue = build_cue_sheet(&album_title, options.performer.as_deref(), &cue_tracks);
+        let path = unique_output_path(&dir, &sanitize_filename_part(&album_title, "Album"), "cue");
+        tokio::fs::write(&path, cue)
+            .await
+            .map_err(|e| format!("Failed to write cue sheet: {}", e))?;
+        cue_sheet_path = Some(path.to_string_lossy().to_string());
+    }
+
+    Ok(LosslessExportReport {
+        batch: report,
+        cue_sheet_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lossless_args_resample_with_dither_and_cue_sheet_lists_tracks() {
+        let options = LosslessExportOptions {
+            cd_quality: true,
+            sample_rate: Some(96_000),
+            ..Default::default()
+        };
+        let (rate, depth) = lossless_resolution(&options).unwrap();
+        assert_eq!((rate, depth), (Some(44_100), Some(16)));
+        let args = lossless_export_args("in.opus", "out.flac", "flac", rate, depth, true, Some(2))
+            .unwrap();
+        let joined = args.join(" ");
+        assert!(joined.contains("-map 0:a:0 -map 0:2 -c:v copy -disposition:v attached_pic"));
+        assert!(joined.contains("-map_metadata 0 -c:a flac"));
+        assert!(joined.contains("-af aresample=44100:dither_method=triangular -ar 44100"));
+        assert!(joined.ends_with("-sample_fmt s16 out.flac"));
+
+        let hires =
+            lossless_export_args("in.wav", "out.m4a", "alac", None, Some(24), false, Some(1))
+                .unwrap()
+                .join(" ");
+        assert!(!hires.contains("0:1"));
+        let video = lossless_export_args("in.mp4", "out.flac", "flac", None, None, true, None)
+            .unwrap()
+            .join(" ");
+        assert!(!video.contains("-c:v"));
+        assert_eq!(
+            attached_picture_index(&serde_json::json!({
+                "streams": [
+                    { "index": 0, "disposition": { "attached_pic": 0 } },
+                    { "index": 3, "disposition": { "attached_pic": 1 } }
+                ]
+            })),
+            Some(3)
+        );
+        assert!(hires.contains("-map_metadata -1 -c:a alac -sample_fmt s32p"));
+        assert!(!hires.contains("aresample"));
+        assert!(lossless_target("mp3").is_err());
+        assert!(lossless_resolution(&LosslessExportOptions {
+            sample_rate: Some(22_050),
+            ..Default::default()
+        })
+        .is_err());
+
+        let cue = build_cue_sheet(
+            "Live at \"Hall\"",
+            Some("Band"),
+            &[
+                ("01 - Intro.flac".to_string(), "Intro".to_string()),
+                ("02 - Encore.flac".to_string(), "Encore".to_string()),
+            ],
+        );
+        assert!(cue.contains("TITLE \"Live at 'Hall'\"\n"));
+        assert!(cue.contains(
+            "FILE \"02 - Encore.flac\" WAVE\n  TRACK 02 AUDIO\n    TITLE \"Encore\"\n    PERFORMER \"Band\"\n    INDEX 01 00:00:00\n"
+        ));
+    }
+}
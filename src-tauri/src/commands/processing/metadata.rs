@@ -1,4 +1,5 @@
 use super::*;
+use crate::services::{AppProcessRunner, ProcessInvocation, ProcessRunner};
 
 /// Get video metadata using FFprobe
 #[tauri::command]
@@ -7,23 +8,21 @@ pub async fn get_video_metadata(app: AppHandle, path: String) -> Result<VideoMet
         .await
         .ok_or("FFprobe not found. Please install FFmpeg.")?;
 
-    let mut cmd = Command::new(&ffprobe_path);
-    cmd.args([
-        "-v",
-        "quiet",
-        "-print_format",
-        "json",
-        "-show_format",
-        "-show_streams",
-        &path,
-    ]);
-    cmd.hide_window();
-    let output = cmd
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
-
-    if !output.status.success() {
+    let invocation = ProcessInvocation::binary(
+        &ffprobe_path,
+        &[
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+            &path,
+        ],
+    );
+    let output = AppProcessRunner::new(&app).run(&invocation).await?;
+
+    if !output.success {
         return Err("FFprobe failed to analyze video".to_string());
     }
 
@@ -131,6 +130,44 @@ pub async fn get_video_metadata(app: AppHandle, path: String) -> Result<VideoMet
     })
 }
 
+/// Index of the first cover-art stream in ffprobe's `-show_streams` JSON
+pub(super) fn attached_picture_index(probe: &serde_json::Value) -> Option<usize> {
+    probe
+        .get("streams")?
+        .as_array()?
+        .iter()
+        .find(|stream| {
+            stream
+                .pointer("/disposition/attached_pic")
+                .and_then(|value| value.as_i64())
+                == Some(1)
+        })
+        .and_then(|stream| stream.get("index")?.as_u64())
+        .map(|index| index as usize)
+}
+
+/// Stream index of the embedded cover art of `path`, if it has one
+pub(super) async fn probe_attached_picture(app: &AppHandle, path: &str) -> Option<usize> {
+    let ffprobe_path = get_ffprobe_path(app).await?;
+    let invocation = ProcessInvocation::binary(
+        &ffprobe_path,
+        &[
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_entries",
+            "stream=index:stream_disposition=attached_pic",
+            "-select_streams",
+            "v",
+            path,
+        ],
+    );
+    let output = AppProcessRunner::new(app).run(&invocation).await.ok()?;
+    let probe = serde_json::from_slice(&output.stdout).ok()?;
+    attached_picture_index(&probe)
+}
+
 /// Detect shot changes using FFmpeg scene detection filter.
 #[tauri::command]
 pub async fn detect_shot_changes(
@@ -152,8 +189,7 @@ pub async fn detect_shot_changes(
     let min_interval = min_interval_ms.unwrap_or(250).max(0);
     let scene_filter = format!("select=gt(scene\\,{:.3}),showinfo", threshold_value);
 
-    let mut cmd = Command::new(ffmpeg_path);
-    cmd.args([
+    let args = [
         "-hide_banner",
         "-i",
         &path,
@@ -163,20 +199,14 @@ pub async fn detect_shot_changes(
         "-f",
         "null",
         "-",
-    ]);
-    cmd.hide_window();
+    ]
+    .map(str::to_string)
+    .to_vec();
 
-    let output = cmd
-        .output()
+    let stderr = FfmpegRunner::new(ffmpeg_path, args)
+        .run_collecting_log(None, |_| {})
         .await
-        .map_err(|e| format!("Failed to run FFmpeg shot detection: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg shot detection failed: {}", stderr));
-    }
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
+        .map_err(|e| format!("FFmpeg shot detection failed: {}", e))?;
     let re = regex::Regex::new(r"pts_time:([0-9]+(?:\.[0-9]+)?)")
         .map_err(|e| format!("Failed to build regex: {}", e))?;
 
@@ -33,6 +33,88 @@ fn needs_preview_transcode(codec: &str, container_format: &str) -> bool {
     has_problematic_codec
 }
 
+fn preview_hash(input_path: &str) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    input_path.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct PreviewJob<'a> {
+    id: String,
+    input_path: &'a str,
+    kind: &'static str,
+}
+
+impl<'a> PreviewJob<'a> {
+    fn new(kind: &'static str, input_path: &'a str) -> Self {
+        PreviewJob {
+            id: format!("{}-{}", kind, preview_hash(input_path)),
+            input_path,
+            kind,
+        }
+    }
+
+    fn emit(&self, app: &AppHandle, status: &str, percent: f64) {
+        let _ = PREVIEW_PROGRESS.emit(
+            app,
+            &PreviewProgress {
+                job_id: self.id.clone(),
+                input_path: self.input_path.to_string(),
+                kind: self.kind.to_string(),
+                status: status.to_string(),
+                percent,
+            },
+        );
+    }
+}
+
+/// Run a preview FFmpeg command, emitting `preview-progress` as it goes.
+/// Stoppable with `cancel_preview`.
+async fn run_preview_ffmpeg(
+    app: &AppHandle,
+    ffmpeg_path: &Path,
+    args: Vec<String>,
+    job: &PreviewJob<'_>,
+    output: &Path,
+) -> Result<(), String> {
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    ACTIVE_JOBS.lock().await.insert(job.id.clone(), cancel_tx);
+    job.emit(app, "starting", 0.0);
+
+    let mut last_percent = 0.0;
+    let result = FfmpegRunner::new(ffmpeg_path, args)
+        .run(Some(&mut cancel_rx), |progress| {
+            let percent = progress.percent(None).min(99.0).floor();
+            if percent > last_percent {
+                last_percent = percent;
+                job.emit(app, "progress", percent);
+            }
+        })
+        .await;
+    ACTIVE_JOBS.lock().await.remove(&job.id);
+
+    match result {
+        Ok(()) => {
+            job.emit(app, "complete", 100.0);
+            Ok(())
+        }
+        Err(error) => {
+            std::fs::remove_file(output).ok();
+            let status = match error {
+                FfmpegError::Cancelled => "cancelled",
+                _ => "failed",
+            };
+            job.emit(app, status, last_percent);
+            Err(match error {
+                FfmpegError::Cancelled => "Preview cancelled".to_string(),
+                error => error.to_string(),
+            })
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn generate_video_preview(
     app: AppHandle,
@@ -53,24 +135,16 @@ pub async fn generate_video_preview(
         "FFmpeg not found. Please install FFmpeg from the Dependencies tab in Settings.".to_string()
     })?;
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|_| "Failed to get app data directory")?;
+    let app_data_dir = data_dir(&app).map_err(|_| "Failed to get app data directory")?;
     let preview_dir = app_data_dir.join("previews");
     std::fs::create_dir_all(&preview_dir).ok();
 
-    let hash = {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        input_path.hash(&mut hasher);
-        hasher.finish()
-    };
+    let hash = preview_hash(&input_path);
     let preview_path = preview_dir.join(format!("preview_{}.mp4", hash));
 
     if preview_path.exists() {
         log::info!("[PREVIEW] Cache hit: {}", preview_path.display());
+        touch_cache_entry(&preview_path);
         return Ok(preview_path.to_string_lossy().to_string());
     }
 
@@ -81,16 +155,7 @@ pub async fn generate_video_preview(
         container_format
     );
 
-    let _ = app.emit(
-        "preview-progress",
-        serde_json::json!({
-            "status": "starting",
-            "percent": 0
-        }),
-    );
-
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args([
+    let args = [
         "-y",
         "-i",
         &input_path,
@@ -107,54 +172,48 @@ pub async fn generate_video_preview(
         "-an",
         "-movflags",
         "+faststart",
-        preview_path.to_str().unwrap(),
-    ])
-    .stdout(Stdio::piped())
-    .stderr(Stdio::piped());
-    cmd.hide_window();
-    let output = cmd
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        std::fs::remove_file(&preview_path).ok();
-        log::error!("[PREVIEW] FFmpeg failed for '{}': {}", input_path, stderr);
-        return Err(format!("FFmpeg failed: {}", stderr));
+        &preview_path.to_string_lossy(),
+    ]
+    .map(String::from)
+    .to_vec();
+    let job = PreviewJob::new("video", &input_path);
+    if let Err(error) = run_preview_ffmpeg(&app, &ffmpeg_path, args, &job, &preview_path).await {
+        log::error!("[PREVIEW] FFmpeg failed for '{}': {}", input_path, error);
+        return Err(format!("FFmpeg failed: {}", error));
     }
 
     log::info!("[PREVIEW] Preview generated: {}", preview_path.display());
-
-    let _ = app.emit(
-        "preview-progress",
-        serde_json::json!({
-            "status": "complete",
-            "percent": 100
-        }),
-    );
-
+    enforce_cache_limit(&app);
     Ok(preview_path.to_string_lossy().to_string())
 }
 
+/// Stop the preview or thumbnail being generated for `input_path`
+#[tauri::command]
+pub async fn cancel_preview(input_path: String) -> Result<(), String> {
+    let mut jobs = ACTIVE_JOBS.lock().await;
+    let mut cancelled = false;
+    for kind in ["video", "thumbnail"] {
+        if let Some(cancel_tx) = jobs.remove(&PreviewJob::new(kind, &input_path).id) {
+            cancel_tx.send(()).ok();
+            cancelled = true;
+        }
+    }
+    if cancelled {
+        Ok(())
+    } else {
+        Err("No preview is being generated for this file".to_string())
+    }
+}
+
 #[tauri::command]
 pub async fn check_preview_exists(
     app: AppHandle,
     input_path: String,
 ) -> Result<Option<String>, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|_| "Failed to get app data directory")?;
+    let app_data_dir = data_dir(&app).map_err(|_| "Failed to get app data directory")?;
     let preview_dir = app_data_dir.join("previews");
 
-    let hash = {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        input_path.hash(&mut hasher);
-        hasher.finish()
-    };
+    let hash = preview_hash(&input_path);
     let preview_path = preview_dir.join(format!("preview_{}.mp4", hash));
 
     if preview_path.exists() {
@@ -166,10 +225,7 @@ pub async fn check_preview_exists(
 
 #[tauri::command]
 pub async fn cleanup_previews(app: AppHandle) -> Result<u32, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|_| "Failed to get app data directory")?;
+    let app_data_dir = data_dir(&app).map_err(|_| "Failed to get app data directory")?;
     let preview_dir = app_data_dir.join("previews");
 
     if !preview_dir.exists() {
@@ -193,7 +249,7 @@ pub async fn cleanup_previews(app: AppHandle) -> Result<u32, String> {
         }
     }
 
-    Ok(count)
+    Ok(count + enforce_cache_limit(&app))
 }
 
 #[tauri::command]
@@ -206,31 +262,22 @@ pub async fn generate_video_thumbnail(
         "FFmpeg not found. Please install FFmpeg from the Dependencies tab in Settings.".to_string()
     })?;
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|_| "Failed to get app data directory")?;
+    let app_data_dir = data_dir(&app).map_err(|_| "Failed to get app data directory")?;
     let preview_dir = app_data_dir.join("previews");
     std::fs::create_dir_all(&preview_dir).ok();
 
-    let hash = {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        input_path.hash(&mut hasher);
-        hasher.finish()
-    };
+    let hash = preview_hash(&input_path);
     let thumb_path = preview_dir.join(format!("thumb_{}.jpg", hash));
 
     if thumb_path.exists() {
         log::info!("[THUMBNAIL] Cache hit: {}", thumb_path.display());
+        touch_cache_entry(&thumb_path);
         return Ok(thumb_path.to_string_lossy().to_string());
     }
 
     log::info!("[THUMBNAIL] Generating thumbnail for '{}'", input_path);
 
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args([
+    let args = [
         "-y",
         "-ss",
         "1",
@@ -242,24 +289,18 @@ pub async fn generate_video_thumbnail(
         "scale=-2:720",
         "-q:v",
         "2",
-        thumb_path.to_str().unwrap(),
-    ])
-    .stdout(Stdio::piped())
-    .stderr(Stdio::piped());
-    cmd.hide_window();
-    let output = cmd
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        std::fs::remove_file(&thumb_path).ok();
-        log::error!("[THUMBNAIL] FFmpeg failed for '{}': {}", input_path, stderr);
-        return Err(format!("FFmpeg thumbnail failed: {}", stderr));
+        &thumb_path.to_string_lossy(),
+    ]
+    .map(String::from)
+    .to_vec();
+    let job = PreviewJob::new("thumbnail", &input_path);
+    if let Err(error) = run_preview_ffmpeg(&app, &ffmpeg_path, args, &job, &thumb_path).await {
+        log::error!("[THUMBNAIL] FFmpeg failed for '{}': {}", input_path, error);
+        return Err(format!("FFmpeg thumbnail failed: {}", error));
     }
 
     log::info!("[THUMBNAIL] Generated: {}", thumb_path.display());
+    enforce_cache_limit(&app);
     Ok(thumb_path.to_string_lossy().to_string())
 }
 
@@ -270,24 +311,16 @@ pub async fn generate_audio_preview(app: AppHandle, input_path: String) -> Resul
         "FFmpeg not found. Please install FFmpeg from the Dependencies tab in Settings.".to_string()
     })?;
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|_| "Failed to get app data directory")?;
+    let app_data_dir = data_dir(&app).map_err(|_| "Failed to get app data directory")?;
     let preview_dir = app_data_dir.join("previews");
     std::fs::create_dir_all(&preview_dir).ok();
 
-    let hash = {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        input_path.hash(&mut hasher);
-        hasher.finish()
-    };
+    let hash = preview_hash(&input_path);
     let audio_path = preview_dir.join(format!("audio_{}.wav", hash));
 
     if audio_path.exists() {
         log::info!("[AUDIO_PREVIEW] Cache hit: {}", audio_path.display());
+        touch_cache_entry(&audio_path);
         return Ok(audio_path.to_string_lossy().to_string());
     }
 
@@ -296,8 +329,7 @@ pub async fn generate_audio_preview(app: AppHandle, input_path: String) -> Resul
         input_path
     );
 
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args([
+    let args = [
         "-y",
         "-i",
         &input_path,
@@ -308,27 +340,224 @@ pub async fn generate_audio_preview(app: AppHandle, input_path: String) -> Resul
         "44100",
         "-ac",
         "1",
-        audio_path.to_str().unwrap(),
-    ])
-    .stdout(Stdio::piped())
-    .stderr(Stdio::piped());
-    cmd.hide_window();
-    let output = cmd
-        .output()
+        &audio_path.to_string_lossy(),
+    ]
+    .map(String::from)
+    .to_vec();
+    if let Err(error) = FfmpegRunner::new(&ffmpeg_path, args)
+        .run(None, |_| {})
         .await
-        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    {
         std::fs::remove_file(&audio_path).ok();
         log::error!(
             "[AUDIO_PREVIEW] FFmpeg failed for '{}': {}",
             input_path,
-            stderr
+            error
         );
-        return Err(format!("FFmpeg audio preview failed: {}", stderr));
+        return Err(format!("FFmpeg audio preview failed: {}", error));
     }
 
     log::info!("[AUDIO_PREVIEW] Generated: {}", audio_path.display());
+    enforce_cache_limit(&app);
     Ok(audio_path.to_string_lossy().to_string())
 }
+
+/// Length of a processing preview slice in seconds
+const PROCESSING_PREVIEW_SECONDS: &str = "5";
+
+/// Rewrite a processing command to encode only a short slice starting at
+/// `at_seconds` into `preview_path`, with the fastest x264/x265 preset
+fn processing_preview_args(
+    command_args: &[String],
+    at_seconds: f64,
+    preview_path: &str,
+) -> Result<Vec<String>, String> {
+    if !at_seconds.is_finite() || at_seconds < 0.0 {
+        return Err("Preview position must be zero or more seconds".to_string());
+    }
+    let (_, body) = command_args
+        .split_last()
+        .ok_or("FFmpeg command has no output")?;
+
+    // Existing trims would fight with the preview slice
+    let mut args = Vec::with_capacity(body.len() + 8);
+    let mut iter = body.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-ss" | "-t" | "-to" | "-sseof" => {
+                iter.next();
+            }
+            "-y" | "-n" => {}
+            _ => args.push(arg.clone()),
+        }
+    }
+    if !args.iter().any(|arg| arg == "-i") {
+        return Err("FFmpeg command has no input".to_string());
+    }
+    // Seek every input so overlays and mixed audio stay in step with the main video
+    let seek = format!("{:.3}", at_seconds);
+    let mut seeked = Vec::with_capacity(args.len() + 8);
+    seeked.push("-y".to_string());
+    for arg in args {
+        if arg == "-i" {
+            seeked.extend(["-ss".to_string(), seek.clone()]);
+        }
+        seeked.push(arg);
+    }
+    let mut args = seeked;
+
+    let video_codec = args
+        .windows(2)
+        .find(|pair| matches!(pair[0].as_str(), "-c:v" | "-codec:v" | "-vcodec"))
+        .map(|pair| pair[1].clone());
+    // Other encoders take different preset names (nvenc's p1-p7, etc.)
+    if matches!(video_codec.as_deref(), Some("libx264" | "libx265")) {
+        match args.iter().position(|arg| arg == "-preset") {
+            Some(pos) if pos + 1 < args.len() => args[pos + 1] = "ultrafast".to_string(),
+            _ => args.extend(["-preset".to_string(), "ultrafast".to_string()]),
+        }
+    }
+    args.extend([
+        "-t".to_string(),
+        PROCESSING_PREVIEW_SECONDS.to_string(),
+        preview_path.to_string(),
+    ]);
+    Ok(args)
+}
+
+/// Run a processing command on a 5-second slice at `at_seconds` and return
+/// the preview file, so the result can be checked before the full encode
+#[tauri::command]
+pub async fn preview_processing(
+    app: AppHandle,
+    command_args: Vec<String>,
+    at_seconds: f64,
+) -> Result<String, String> {
+    validate_ffmpeg_args(&command_args)?;
+    let ffmpeg_path = get_ffmpeg_path(&app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg from the Dependencies tab in Settings.")?;
+
+    let preview_dir = data_dir(&app)
+        .map_err(|_| "Failed to get app data directory")?
+        .join("previews");
+    std::fs::create_dir_all(&preview_dir).ok();
+
+    let hash = {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        let mut hasher = DefaultHasher::new();
+        command_args.hash(&mut hasher);
+        at_seconds.to_bits().hash(&mut hasher);
+        hasher.finish()
+    };
+    let extension = command_args
+        .last()
+        .and_then(|output| Path::new(output).extension())
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+    let preview_path = preview_dir.join(format!("processing_{}.{}", hash, extension));
+    if preview_path.exists() {
+        touch_cache_entry(&preview_path);
+        return Ok(preview_path.to_string_lossy().to_string());
+    }
+
+    let args = processing_preview_args(&command_args, at_seconds, &preview_path.to_string_lossy())?;
+    if let Err(error) = FfmpegRunner::new(&ffmpeg_path, args)
+        .run(None, |_| {})
+        .await
+    {
+        std::fs::remove_file(&preview_path).ok();
+        log::error!("[PREVIEW] Processing preview failed: {}", error);
+        return Err(format!("FFmpeg preview failed: {}", error));
+    }
+
+    enforce_cache_limit(&app);
+    Ok(preview_path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn processing_preview_encodes_a_fast_slice_into_the_cache() {
+        let command: Vec<String> = [
+            "-y",
+            "-ss",
+            "10",
+            "-i",
+            "in.mp4",
+            "-vf",
+            "eq=contrast=1.2",
+            "-c:v",
+            "libx264",
+            "-preset",
+            "slow",
+            "-to",
+            "7200",
+            "out.mp4",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let args = processing_preview_args(&command, 90.0, "/cache/p.mp4").unwrap();
+        assert_eq!(
+            args,
+            [
+                "-y",
+                "-ss",
+                "90.000",
+                "-i",
+                "in.mp4",
+                "-vf",
+                "eq=contrast=1.2",
+                "-c:v",
+                "libx264",
+                "-preset",
+                "ultrafast",
+                "-t",
+                "5",
+                "/cache/p.mp4",
+            ]
+        );
+        assert!(processing_preview_args(&command, -1.0, "/cache/p.mp4").is_err());
+
+        let overlay: Vec<String> = [
+            "-i",
+            "in.mp4",
+            "-i",
+            "logo.png",
+            "-c:v",
+            "h264_nvenc",
+            "-preset",
+            "p5",
+            "out.mp4",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let args = processing_preview_args(&overlay, 3.0, "/cache/p.mp4").unwrap();
+        assert_eq!(
+            args,
+            [
+                "-y",
+                "-ss",
+                "3.000",
+                "-i",
+                "in.mp4",
+                "-ss",
+                "3.000",
+                "-i",
+                "logo.png",
+                "-c:v",
+                "h264_nvenc",
+                "-preset",
+                "p5",
+                "-t",
+                "5",
+                "/cache/p.mp4",
+            ]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use super::*;
+use crate::database::{
+    delete_prompt_template_from_db, get_prompt_template_from_db, list_prompt_templates_from_db,
+    save_prompt_template_to_db,
+};
+use crate::types::{PromptTemplate, PromptTemplateBundle, PromptTemplateBundleItem};
+use crate::utils::render_prompt_template;
+
+const PROMPT_TEMPLATE_BUNDLE_VERSION: u32 = 1;
+
+/// Variables every template can use without the caller passing them
+pub(super) fn builtin_prompt_context(
+    metadata: &VideoMetadata,
+    timeline_start: Option<f64>,
+    timeline_end: Option<f64>,
+) -> HashMap<String, String> {
+    let mut context = HashMap::from([
+        ("filename".to_string(), metadata.filename.clone()),
+        (
+            "resolution".to_string(),
+            format!("{}x{}", metadata.width, metadata.height),
+        ),
+        ("duration".to_string(), format_time(metadata.duration)),
+        ("fps".to_string(), format!("{:.2}", metadata.fps)),
+    ]);
+    if let (Some(start), Some(end)) = (timeline_start, timeline_end) {
+        context.insert(
+            "selection".to_string(),
+            format!("{} to {}", format_time(start), format_time(end)),
+        );
+    }
+    context
+}
+
+pub(super) fn render_saved_prompt(
+    template_id: &str,
+    context: &HashMap<String, String>,
+) -> Result<String, String> {
+    let template = get_prompt_template_from_db(template_id)?
+        .ok_or_else(|| "Prompt template not found".to_string())?;
+    render_prompt_template(&template.template, context)
+}
+
+#[tauri::command]
+pub fn list_prompt_templates() -> Result<Vec<PromptTemplate>, String> {
+    list_prompt_templates_from_db()
+}
+
+#[tauri::command]
+pub fn save_prompt_template(
+    id: Option<String>,
+    name: String,
+    description: Option<String>,
+    template: String,
+) -> Result<PromptTemplate, String> {
+    save_prompt_template_to_db(id, &name, description.as_deref(), &template)
+}
+
+#[tauri::command]
+pub fn delete_prompt_template(id: String) -> Result<(), String> {
+    delete_prompt_template_from_db(&id)
+}
+
+/// Fill a saved template's variables, e.g. `{selection}` or `{target_size}`
+#[tauri::command]
+pub fn render_prompt(
+    template_id: String,
+    context: HashMap<String, String>,
+) -> Result<String, String> {
+    render_saved_prompt(&template_id, &context)
+}
+
+/// Write templates (all, or the given ids) to a shareable JSON file
+#[tauri::command]
+pub async fn export_prompt_templates(
+    output_path: String,
+    ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    let templates: Vec<PromptTemplateBundleItem> = list_prompt_templates_from_db()?
+        .into_iter()
+        .filter(|template| ids.as_ref().is_none_or(|ids| ids.contains(&template.id)))
+        .map(|template| PromptTemplateBundleItem {
+            name: template.name,
+            description: template.description,
+            template: template.template,
+        })
+        .collect();
+    let count = templates.len();
+    let bundle = PromptTemplateBundle {
+        version: PROMPT_TEMPLATE_BUNDLE_VERSION,
+        templates,
+    };
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize prompt templates: {}", e))?;
+    tokio::fs::write(&output_path, json)
+        .await
+        .map_err(|e| format!("Failed to write prompt templates: {}", e))?;
+    Ok(count)
+}
+
+/// Add templates from an exported JSON file, skipping ones already saved
+#[tauri::command]
+pub async fn import_prompt_templates(path: String) -> Result<Vec<PromptTemplate>, String> {
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read prompt templates: {}", e))?;
+    let bundle: PromptTemplateBundle = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid prompt template file: {}", e))?;
+    if bundle.version > PROMPT_TEMPLATE_BUNDLE_VERSION {
+        return Err(format!(
+            "Prompt template file version {} is newer than this app supports",
+            bundle.version
+        ));
+    }
+
+    let existing = list_prompt_templates_from_db()?;
+    let mut imported = Vec::new();
+    for item in bundle.templates {
+        let duplicate = existing
+            .iter()
+            .chain(imported.iter())
+            .any(|saved: &PromptTemplate| {
+                saved.name == item.name.trim() && saved.template == item.template
+            });
+        if duplicate {
+            continue;
+        }
+        imported.push(save_prompt_template_to_db(
+            None,
+            &item.name,
+            item.description.as_deref(),
+            &item.template,
+        )?);
+    }
+    Ok(imported)
+}
@@ -0,0 +1,485 @@
+use super::*;
+use crate::commands::{download_video, DownloadOptions};
+use crate::database::{
+    delete_download_recipe_from_db, get_download_recipe_from_db, list_download_recipes_from_db,
+    save_download_recipe_to_db,
+};
+use crate::types::{
+    DownloadProfileSettings, DownloadRecipe, RecipeProgress, RecipeRunItem, RecipeStep,
+    RECIPE_PROGRESS,
+};
+use tauri::Manager;
+
+const DEFAULT_TARGET_LUFS: f64 = -16.0;
+
+pub(crate) fn validate_recipe_steps(steps: &[RecipeStep]) -> Result<(), String> {
+    for step in steps {
+        match step {
+            RecipeStep::ExtractAudio {
+                format, bitrate, ..
+            } => {
+                let bitrate = bitrate.as_deref().unwrap_or("192k");
+                if !bitrate.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err("Invalid bitrate value".to_string());
+                }
+                audio_codec_args(format, bitrate)?;
+            }
+            RecipeStep::NormalizeLoudness {
+                target_lufs: Some(lufs),
+            } if !(-70.0..=-5.0).contains(lufs) => {
+                return Err("Loudness target must be between -70 and -5 LUFS".to_string());
+            }
+            RecipeStep::MoveTo { folder } if folder.trim().is_empty() => {
+                return Err("Move step needs a folder".to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Audio encoder for re-encoding a file in place, picked by its container
+fn container_audio_args(path: &Path) -> Result<Vec<String>, String> {
+    let ext = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let format = match ext.as_str() {
+        "mp4" | "m4v" | "mov" | "mkv" | "m4a" | "aac" => "m4a",
+        "webm" => "opus",
+        other => other,
+    };
+    audio_codec_args(format, "192k")
+}
+
+/// FFmpeg args for a step that rewrites the file; `None` for file moves
+fn recipe_step_args(
+    step: &RecipeStep,
+    input: &Path,
+    output: &Path,
+    title: Option<&str>,
+) -> Result<Option<Vec<String>>, String> {
+    let input_str = input.to_string_lossy().to_string();
+    let output_str = output.to_string_lossy().to_string();
+    let copy_all = |input: String| {
+        vec![
+            "-y".to_string(),
+            "-i".to_string(),
+            input,
+            "-map".to_string(),
+            "0".to_string(),
+            "-c".to_string(),
+            "copy".to_string(),
+        ]
+    };
+    let args = match step {
+        RecipeStep::ExtractAudio {
+            format, bitrate, ..
+        } => audio_batch_args(
+            &input_str,
+            &output_str,
+            format,
+            bitrate.as_deref().unwrap_or("192k"),
+            true,
+        )?,
+        RecipeStep::NormalizeLoudness { target_lufs } => {
+            let mut args = copy_all(input_str);
+            args.extend(container_audio_args(output)?);
+            args.extend([
+                "-af".to_string(),
+                format!(
+                    "loudnorm=I={}:TP=-1.5:LRA=11",
+                    target_lufs.unwrap_or(DEFAULT_TARGET_LUFS)
+                ),
+                output_str,
+            ]);
+            args
+        }
+        RecipeStep::Tag {
+            title: tag_title,
+            artist,
+            album,
+            genre,
+        } => {
+            let mut args = copy_all(input_str);
+            let tags = [
+                ("title", tag_title),
+                ("artist", artist),
+                ("album", album),
+                ("genre", genre),
+            ];
+            for (key, value) in tags {
+                if let Some(value) = value.as_deref().filter(|v| !v.trim().is_empty()) {
+                    let value = value.replace("{title}", title.unwrap_or_default());
+                    args.extend(["-metadata".to_string(), format!("{}={}", key, value)]);
+                }
+            }
+            if output
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("mp3"))
+            {
+                args.extend(["-id3v2_version".to_string(), "3".to_string()]);
+            }
+            args.push(output_str);
+            args
+        }
+        RecipeStep::MoveTo { .. } => return Ok(None),
+    };
+    Ok(Some(args))
+}
+
+/// Where a step writes: a new audio file, or a temp file that replaces the input
+fn recipe_step_output(step: &RecipeStep, input: &Path) -> PathBuf {
+    match step {
+        RecipeStep::ExtractAudio { format, .. } => audio_batch_output_path(input, format),
+        _ => {
+            let stem = input
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "output".to_string());
+            let ext = input
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+                .unwrap_or_default();
+            input.with_file_name(format!("{}.recipe-tmp.{}", stem, ext))
+        }
+    }
+}
+
+/// Move a file into `folder`, adding " (n)" when the name is taken
+async fn move_into_folder(path: &Path, folder: &str) -> Result<PathBuf, String> {
+    let folder = Path::new(folder);
+    tokio::fs::create_dir_all(folder)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "output".to_string());
+    let ext = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let target = std::iter::once(folder.join(format!("{}{}", stem, ext)))
+        .chain((1..).map(|n| folder.join(format!("{} ({}){}", stem, n, ext))))
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded candidates");
+    if tokio::fs::rename(path, &target).await.is_err() {
+        // Different drive: copy, then remove the original
+        tokio::fs::copy(path, &target)
+            .await
+            .map_err(|e| format!("Failed to move file: {}", e))?;
+        tokio::fs::remove_file(path).await.ok();
+    }
+    Ok(target)
+}
+
+#[tauri::command]
+pub fn get_download_recipes() -> Result<Vec<DownloadRecipe>, String> {
+    list_download_recipes_from_db()
+}
+
+/// Create a recipe, or update the one with `id`
+#[tauri::command]
+pub fn save_download_recipe(
+    id: Option<String>,
+    name: String,
+    download: DownloadProfileSettings,
+    steps: Vec<RecipeStep>,
+) -> Result<DownloadRecipe, String> {
+    validate_recipe_steps(&steps)?;
+    save_download_recipe_to_db(id, &name, &download, &steps)
+}
+
+#[tauri::command]
+pub fn delete_download_recipe(id: String) -> Result<(), String> {
+    delete_download_recipe_from_db(&id)
+}
+
+/// Download one URL with the recipe's settings and run its steps on the file
+/// Whether `cancel_ffmpeg(run_id)` was called; a dropped sender counts too
+fn recipe_cancelled(cancel_rx: &mut tokio::sync::oneshot::Receiver<()>) -> bool {
+    !matches!(
+        cancel_rx.try_recv(),
+        Err(tokio::sync::oneshot::error::TryRecvError::Empty)
+    )
+}
+
+const RECIPE_CANCELLED: &str = "Recipe cancelled";
+
+async fn run_recipe_item(
+    app: &AppHandle,
+    recipe: &DownloadRecipe,
+    url: &str,
+    output_path: &str,
+    cancel_rx: &mut tokio::sync::oneshot::Receiver<()>,
+    progress: impl Fn(&str, usize, &str, &str, Option<String>),
+) -> Result<String, String> {
+    let settings = &recipe.download;
+    let download_id = format!("{}-download", uuid::Uuid::new_v4());
+    progress("download", 1, &download_id, "running", None);
+    let summary = download_video(
+        app.clone(),
+        DownloadOptions {
+            id: download_id.clone(),
+            url: url.to_string(),
+            output_path: output_path.to_string(),
+            quality: settings
+                .quality
+                .clone()
+                .unwrap_or_else(|| "best".to_string()),
+            format: settings.format.clone().unwrap_or_else(|| "mp4".to_string()),
+            video_codec: settings
+                .video_codec
+                .clone()
+                .unwrap_or_else(|| "auto".to_string()),
+            audio_bitrate: settings
+                .audio_bitrate
+                .clone()
+                .unwrap_or_else(|| "192".to_string()),
+            subtitle_mode: settings
+                .subtitle_mode
+                .clone()
+                .unwrap_or_else(|| "off".to_string()),
+            subtitle_langs: settings.subtitle_langs.clone().unwrap_or_default(),
+            subtitle_embed: settings.subtitle_embed.unwrap_or(false),
+            subtitle_format: settings
+                .subtitle_format
+                .clone()
+                .unwrap_or_else(|| "srt".to_string()),
+            cookie_mode: settings.cookie_mode.clone(),
+            cookie_browser: settings.cookie_browser.clone(),
+            cookie_browser_profile: settings.cookie_browser_profile.clone(),
+            cookie_file_path: settings.cookie_file_path.clone(),
+            proxy_url: settings.proxy_url.clone(),
+            download_kind: Some("recipe".to_string()),
+            ..Default::default()
+        },
+    )
+    .await;
+    let item = summary.and_then(|summary| {
+        summary
+            .items
+            .into_iter()
+            .find(|item| item.filepath.is_some())
+            .ok_or_else(|| "Download produced no file".to_string())
+    });
+    let item = match item {
+        Ok(item) => item,
+        Err(error) => {
+            progress("download", 1, &download_id, "failed", Some(error.clone()));
+            return Err(error);
+        }
+    };
+    progress("download", 1, &download_id, "completed", None);
+
+    let downloaded = PathBuf::from(item.filepath.unwrap_or_default());
+    let mut current = downloaded.clone();
+    for (index, step) in recipe.steps.iter().enumerate() {
+        let stage_number = index + 2;
+        let job_id = format!("{}-stage-{}", download_id, stage_number);
+        if recipe_cancelled(cancel_rx) {
+            progress(step.kind(), stage_number, &job_id, "cancelled", None);
+            return Err(RECIPE_CANCELLED.to_string());
+        }
+        progress(step.kind(), stage_number, &job_id, "running", None);
+        let result = run_recipe_step(app, step, &current, &job_id, item.title.as_deref()).await;
+        match result {
+            Ok(next) => {
+                progress(step.kind(), stage_number, &job_id, "completed", None);
+                current = next;
+            }
+            Err(error) => {
+                progress(
+                    step.kind(),
+                    stage_number,
+                    &job_id,
+                    "failed",
+                    Some(error.clone()),
+                );
+                return Err(error);
+            }
+        }
+    }
+    Ok(current.to_string_lossy().to_string())
+}
+
+async fn run_recipe_step(
+    app: &AppHandle,
+    step: &RecipeStep,
+    current: &Path,
+    job_id: &str,
+    title: Option<&str>,
+) -> Result<PathBuf, String> {
+    if let RecipeStep::MoveTo { folder } = step {
+        return move_into_folder(current, folder).await;
+    }
+    let output = recipe_step_output(step, current);
+    let args = recipe_step_args(step, current, &output, title)?.unwrap_or_default();
+    if let Err(error) = execute_ffmpeg_command(
+        app.clone(),
+        job_id.to_string(),
+        args,
+        current.to_string_lossy().to_string(),
+        output.to_string_lossy().to_string(),
+        None,
+    )
+    .await
+    {
+        tokio::fs::remove_file(&output).await.ok();
+        return Err(error);
+    }
+    match step {
+        RecipeStep::ExtractAudio { keep_video, .. } => {
+            if !keep_video {
+                tokio::fs::remove_file(current).await.ok();
+            }
+            Ok(output)
+        }
+        _ => {
+            tokio::fs::rename(&output, current)
+                .await
+                .map_err(|e| format!("Failed to replace {}: {}", current.display(), e))?;
+            Ok(current.to_path_buf())
+        }
+    }
+}
+
+/// Apply a saved recipe to each URL in turn, reporting every stage as
+/// `recipe-progress`. A failed URL does not stop the rest of the batch;
+/// `cancel_ffmpeg(run_id)` stops it before the next URL or stage.
+#[tauri::command]
+pub async fn apply_download_recipe(
+    app: AppHandle,
+    run_id: String,
+    recipe_id: String,
+    urls: Vec<String>,
+) -> Result<Vec<RecipeRunItem>, String> {
+    let recipe =
+        get_download_recipe_from_db(&recipe_id)?.ok_or_else(|| "Recipe not found".to_string())?;
+    validate_recipe_steps(&recipe.steps)?;
+    let output_path = recipe
+        .download
+        .output_path
+        .clone()
+        .filter(|path| !path.trim().is_empty())
+        .or_else(|| {
+            app.path()
+                .download_dir()
+                .ok()
+                .map(|dir| dir.to_string_lossy().to_string())
+        })
+        .ok_or("Recipe has no output folder")?;
+
+    let item_count = urls.len();
+    let stage_count = recipe.steps.len() + 1;
+    let mut items = Vec::with_capacity(item_count);
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    ACTIVE_JOBS.lock().await.insert(run_id.clone(), cancel_tx);
+    for (index, url) in urls.into_iter().enumerate() {
+        if recipe_cancelled(&mut cancel_rx) {
+            items.push(RecipeRunItem {
+                url,
+                output_path: None,
+                error: Some(RECIPE_CANCELLED.to_string()),
+            });
+            continue;
+        }
+        let progress = |stage: &str,
+                        stage_number: usize,
+                        job_id: &str,
+                        status: &str,
+                        error: Option<String>| {
+            let payload = RecipeProgress {
+                run_id: run_id.clone(),
+                url: url.clone(),
+                item_number: index + 1,
+                item_count,
+                stage: stage.to_string(),
+                stage_number,
+                stage_count,
+                job_id: job_id.to_string(),
+                status: status.to_string(),
+                error,
+            };
+            let _ = RECIPE_PROGRESS.emit(&app, &payload);
+        };
+        let result =
+            run_recipe_item(&app, &recipe, &url, &output_path, &mut cancel_rx, progress).await;
+        items.push(match result {
+            Ok(path) => RecipeRunItem {
+                url,
+                output_path: Some(path),
+                error: None,
+            },
+            Err(error) => RecipeRunItem {
+                url,
+                output_path: None,
+                error: Some(error),
+            },
+        });
+    }
+    ACTIVE_JOBS.lock().await.remove(&run_id);
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recipe_steps_build_ffmpeg_args() {
+        let input = Path::new("/tmp/song.mp3");
+        let output =
+            recipe_step_output(&RecipeStep::NormalizeLoudness { target_lufs: None }, input);
+        assert_eq!(output, Path::new("/tmp/song.recipe-tmp.mp3"));
+
+        let args = recipe_step_args(
+            &RecipeStep::NormalizeLoudness {
+                target_lufs: Some(-14.0),
+            },
+            input,
+            &output,
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(args.contains(&"loudnorm=I=-14:TP=-1.5:LRA=11".to_string()));
+        assert!(args.contains(&"libmp3lame".to_string()));
+
+        let tag = RecipeStep::Tag {
+            title: Some("{title} (live)".to_string()),
+            artist: Some("Band".to_string()),
+            album: None,
+            genre: Some(" ".to_string()),
+        };
+        let args = recipe_step_args(&tag, input, &output, Some("Song"))
+            .unwrap()
+            .unwrap();
+        assert!(args.contains(&"title=Song (live)".to_string()));
+        assert!(args.contains(&"artist=Band".to_string()));
+        assert!(!args.iter().any(|arg| arg.starts_with("genre=")));
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("/tmp/song.recipe-tmp.mp3")
+        );
+
+        let move_to = RecipeStep::MoveTo {
+            folder: "/music".to_string(),
+        };
+        assert_eq!(recipe_step_args(&move_to, input, &output, None), Ok(None));
+        assert!(validate_recipe_steps(&[RecipeStep::NormalizeLoudness {
+            target_lufs: Some(3.0)
+        }])
+        .is_err());
+    }
+
+    #[test]
+    fn recipe_cancel_is_seen_between_stages() {
+        let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+        assert!(!recipe_cancelled(&mut cancel_rx));
+        cancel_tx.send(()).unwrap();
+        assert!(recipe_cancelled(&mut cancel_rx));
+        assert!(recipe_cancelled(&mut cancel_rx));
+    }
+}
@@ -0,0 +1,281 @@
+use super::*;
+
+const DEFAULT_SILENCE_THRESHOLD_DB: f64 = -50.0;
+const DEFAULT_MIN_SILENCE_SECONDS: f64 = 1.0;
+
+fn silence_threshold(threshold_db: Option<f64>) -> f64 {
+    threshold_db
+        .unwrap_or(DEFAULT_SILENCE_THRESHOLD_DB)
+        .clamp(-90.0, -10.0)
+}
+
+fn min_silence_seconds(min_silence: Option<f64>) -> f64 {
+    min_silence
+        .unwrap_or(DEFAULT_MIN_SILENCE_SECONDS)
+        .clamp(0.1, 60.0)
+}
+
+/// Parse `silence_start`/`silence_end` pairs from ffmpeg's silencedetect log.
+/// A silence still open at the end of input runs to `duration` when known.
+fn parse_silencedetect_output(stderr: &str, duration: Option<f64>) -> Vec<SilenceRange> {
+    let mut ranges = Vec::new();
+    let mut open_start: Option<f64> = None;
+
+    for line in stderr.lines() {
+        if let Some(rest) = line.split("silence_start:").nth(1) {
+            open_start = rest.split_whitespace().next().and_then(|v| v.parse().ok());
+        } else if let Some(rest) = line.split("silence_end:").nth(1) {
+            let end = rest
+                .split_whitespace()
+                .next()
+                .and_then(|v| v.parse::<f64>().ok());
+            if let (Some(start), Some(end)) = (open_start.take(), end) {
+                ranges.push(SilenceRange {
+                    start_seconds: start.max(0.0),
+                    end_seconds: end,
+                });
+            }
+        }
+    }
+
+    if let (Some(start), Some(end)) = (open_start, duration) {
+        if end > start {
+            ranges.push(SilenceRange {
+                start_seconds: start.max(0.0),
+                end_seconds: end,
+            });
+        }
+    }
+    ranges
+}
+
+/// Start of a silence that is still open when the silencedetect log ends
+fn trailing_silence_start(stderr: &str) -> Option<f64> {
+    let mut open_start: Option<f64> = None;
+    for line in stderr.lines() {
+        if let Some(rest) = line.split("silence_start:").nth(1) {
+            open_start = rest.split_whitespace().next().and_then(|v| v.parse().ok());
+        } else if line.contains("silence_end:") {
+            open_start = None;
+        }
+    }
+    open_start.map(|start| start.max(0.0))
+}
+
+/// Run a forward silencedetect pass and return ffmpeg's log
+async fn run_silencedetect(
+    ffmpeg_path: PathBuf,
+    path: &str,
+    threshold: f64,
+    min_silence: f64,
+) -> Result<String, String> {
+    let filter = format!("silencedetect=noise={}dB:d={}", threshold, min_silence);
+
+    let args = [
+        "-hide_banner",
+        "-nostats",
+        "-i",
+        path,
+        "-af",
+        &filter,
+        "-vn",
+        "-f",
+        "null",
+        "-",
+    ]
+    .map(str::to_string)
+    .to_vec();
+
+    FfmpegRunner::new(ffmpeg_path, args)
+        .run_collecting_log(None, |_| {})
+        .await
+        .map_err(|e| format!("FFmpeg silence detection failed: {}", e))
+}
+
+/// Where the trailing silence of `path` starts, if it ends in silence
+pub(crate) async fn detect_trailing_silence(
+    ffmpeg_path: PathBuf,
+    path: &str,
+    options: &HashMap<String, serde_json::Value>,
+) -> Result<Option<f64>, String> {
+    let threshold = silence_threshold(options.get("threshold_db").and_then(|v| v.as_f64()));
+    let min_silence = min_silence_seconds(options.get("min_silence").and_then(|v| v.as_f64()));
+    let stderr = run_silencedetect(ffmpeg_path, path, threshold, min_silence).await?;
+    Ok(trailing_silence_start(&stderr))
+}
+
+/// Audio filter that trims leading silence and, with `remove_internal`, every
+/// pause longer than `min_silence` seconds. Otherwise the end is cut at
+/// `trailing_start`, found by a forward silencedetect pass.
+pub(crate) fn silence_remove_filter(
+    threshold_db: f64,
+    min_silence: f64,
+    remove_internal: bool,
+    trailing_start: Option<f64>,
+) -> String {
+    let trim_start = format!(
+        "silenceremove=start_periods=1:start_duration=0:start_threshold={}dB",
+        threshold_db
+    );
+    if remove_internal {
+        // stop_periods=-1 cuts every later silence, including the trailing one
+        format!(
+            "{}:stop_periods=-1:stop_duration={}:stop_threshold={}dB",
+            trim_start, min_silence, threshold_db
+        )
+    } else if let Some(end) = trailing_start {
+        format!("atrim=end={},asetpts=PTS-STARTPTS,{}", end, trim_start)
+    } else {
+        trim_start
+    }
+}
+
+/// Detect silent ranges so the user can review them before removing silence
+#[tauri::command]
+pub async fn detect_silence(
+    app: AppHandle,
+    path: String,
+    threshold_db: Option<f64>,
+    min_silence_seconds: Option<f64>,
+    duration: Option<f64>,
+) -> Result<SilenceDetectionResult, String> {
+    if !Path::new(&path).exists() {
+        return Err(format!("Media not found: {}", path));
+    }
+
+    let ffmpeg_path = get_ffmpeg_path(&app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg from Settings > Dependencies.")?;
+
+    let threshold = silence_threshold(threshold_db);
+    let min_silence = self::min_silence_seconds(min_silence_seconds);
+    let stderr = run_silencedetect(ffmpeg_path, &path, threshold, min_silence).await?;
+
+    let ranges = parse_silencedetect_output(&stderr, duration);
+    let total_silence_seconds = ranges
+        .iter()
+        .map(|range| range.end_seconds - range.start_seconds)
+        .sum();
+
+    Ok(SilenceDetectionResult {
+        ranges,
+        total_silence_seconds,
+        threshold_db: threshold,
+        min_silence_seconds: min_silence,
+    })
+}
+
+/// Output extension and codec args for re-encoding filtered audio
+fn silence_output_codec(input_path: &str) -> (&'static str, Vec<String>) {
+    let ext = Path::new(input_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let codec: (&'static str, &[&str]) = match ext.as_str() {
+        "mp3" => ("mp3", &["-c:a", "libmp3lame", "-q:a", "2"]),
+        "flac" => ("flac", &["-c:a", "flac"]),
+        "wav" => ("wav", &["-c:a", "pcm_s16le"]),
+        "opus" => ("opus", &["-c:a", "libopus", "-b:a", "128k"]),
+        _ => ("m4a", &["-c:a", "aac", "-b:a", "192k"]),
+    };
+    (codec.0, codec.1.iter().map(|arg| arg.to_string()).collect())
+}
+
+/// ffmpeg args for the `remove_silence` quick action
+pub(crate) fn remove_silence_args(
+    input_path: &str,
+    output_base_dir: &Path,
+    input_stem: &str,
+    timestamp: &str,
+    options: &HashMap<String, serde_json::Value>,
+    trailing_start: Option<f64>,
+) -> (Vec<String>, String, String) {
+    let threshold = silence_threshold(options.get("threshold_db").and_then(|v| v.as_f64()));
+    let min_silence = min_silence_seconds(options.get("min_silence").and_then(|v| v.as_f64()));
+    let remove_internal = options
+        .get("remove_internal")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let (ext, codec_args) = silence_output_codec(input_path);
+    let output = output_base_dir.join(format!("{}_nosilence_{}.{}", input_stem, timestamp, ext));
+
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        input_path.to_string(),
+        "-vn".to_string(),
+        "-af".to_string(),
+        silence_remove_filter(threshold, min_silence, remove_internal, trailing_start),
+    ];
+    args.extend(codec_args);
+    args.extend([
+        "-progress".to_string(),
+        "pipe:2".to_string(),
+        output.to_string_lossy().to_string(),
+    ]);
+
+    let explanation = if remove_internal {
+        format!(
+            "Remove silence below {}dB longer than {}s",
+            threshold, min_silence
+        )
+    } else {
+        format!("Trim leading and trailing silence below {}dB", threshold)
+    };
+    (args, output.to_string_lossy().to_string(), explanation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silencedetect_output_pairs_ranges_and_closes_trailing_silence() {
+        let stderr = "[silencedetect @ 0x1] silence_start: -0.01\n\
+                      [silencedetect @ 0x1] silence_end: 2.5 | silence_duration: 2.51\n\
+                      size=N/A time=00:01:00.00\n\
+                      [silencedetect @ 0x1] silence_start: 58.2\n";
+        let ranges = parse_silencedetect_output(stderr, Some(60.0));
+        assert_eq!(
+            ranges,
+            [
+                SilenceRange {
+                    start_seconds: 0.0,
+                    end_seconds: 2.5
+                },
+                SilenceRange {
+                    start_seconds: 58.2,
+                    end_seconds: 60.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn trailing_silence_is_the_last_unclosed_start() {
+        let stderr = "[silencedetect @ 0x1] silence_start: 10\n\
+                      [silencedetect @ 0x1] silence_end: 12 | silence_duration: 2\n\
+                      [silencedetect @ 0x1] silence_start: 58.2\n";
+        assert_eq!(trailing_silence_start(stderr), Some(58.2));
+        assert_eq!(
+            trailing_silence_start(
+                "[silencedetect @ 0x1] silence_start: 10\n\
+                                    [silencedetect @ 0x1] silence_end: 12\n"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn silence_filter_trims_both_ends_or_every_long_pause() {
+        assert_eq!(
+            silence_remove_filter(-50.0, 1.0, false, Some(58.2)),
+            "atrim=end=58.2,asetpts=PTS-STARTPTS,\
+             silenceremove=start_periods=1:start_duration=0:start_threshold=-50dB"
+        );
+        assert!(!silence_remove_filter(-50.0, 1.0, false, None).contains("areverse"));
+        assert!(silence_remove_filter(-40.0, 1.5, true, None)
+            .ends_with(":stop_periods=-1:stop_duration=1.5:stop_threshold=-40dB"));
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use tauri::AppHandle;
+use tokio::sync::{oneshot, Mutex};
+use tokio::time::timeout;
+
+use super::video::parse_playlist_entries_output;
+use crate::database::add_log_internal;
+use crate::services::{
+    build_proxy_args, metadata_deadline, metadata_network_args, metadata_timeout_error,
+    parse_metadata_error, run_ytdlp_with_stderr,
+};
+use crate::types::{code, BackendError, VideoSearchResponse, VideoSearchSource};
+
+const DEFAULT_SEARCH_LIMIT: u32 = 20;
+const MAX_SEARCH_RESULTS: u32 = 500;
+const SEARCH_TIMEOUT_SECS: u64 = 60;
+
+static ACTIVE_SEARCHES: LazyLock<Mutex<HashMap<String, oneshot::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Build the yt-dlp arguments for one page of results.
+///
+/// Search extractors have no cursor, so page N asks for `offset + limit`
+/// results and skips the ones already shown with `--playlist-start`.
+fn build_search_args(
+    query: &str,
+    source: VideoSearchSource,
+    offset: u32,
+    limit: u32,
+) -> Vec<String> {
+    let mut args = vec![
+        "--flat-playlist".to_string(),
+        "--dump-single-json".to_string(),
+        "--no-warnings".to_string(),
+    ];
+    args.extend(metadata_network_args());
+    args.extend([
+        "--playlist-start".to_string(),
+        (offset + 1).to_string(),
+        "--".to_string(),
+        format!("{}{}:{}", source.prefix(), offset + limit, query),
+    ]);
+    args
+}
+
+/// Search YouTube, SoundCloud or Bilibili through yt-dlp's search extractors
+#[tauri::command]
+pub async fn search_videos(
+    app: AppHandle,
+    query: String,
+    source: Option<VideoSearchSource>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    search_id: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<VideoSearchResponse, String> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err(
+            BackendError::new(code::VALIDATION_INVALID_INPUT, "Search query is empty")
+                .to_wire_string(),
+        );
+    }
+
+    let source = source.unwrap_or_default();
+    let offset = offset.unwrap_or(0).min(MAX_SEARCH_RESULTS);
+    let limit = limit
+        .filter(|l| *l > 0)
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .min(MAX_SEARCH_RESULTS - offset);
+    if limit == 0 {
+        return Ok(VideoSearchResponse {
+            entries: Vec::new(),
+            offset,
+            has_more: false,
+        });
+    }
+
+    let mut args = build_search_args(&query, source, offset, limit);
+    if let Some(separator_index) = args.iter().position(|arg| arg == "--") {
+        args.splice(
+            separator_index..separator_index,
+            build_proxy_args(proxy_url.as_deref()),
+        );
+    }
+    add_log_internal("command", &format!("yt-dlp {}", args.join(" ")), None, None).ok();
+
+    let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+    if let Some(id) = search_id.as_ref() {
+        ACTIVE_SEARCHES.lock().await.insert(id.clone(), cancel_tx);
+    }
+
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let result = tokio::select! {
+        result = timeout(
+            metadata_deadline(Duration::from_secs(SEARCH_TIMEOUT_SECS)),
+            run_ytdlp_with_stderr(&app, &args_ref),
+        ) => Some(result),
+        // A dropped sender (no search id) disables this branch instead of cancelling
+        Ok(()) = cancel_rx => None,
+    };
+
+    if let Some(id) = search_id.as_ref() {
+        ACTIVE_SEARCHES.lock().await.remove(id);
+    }
+
+    let output = match result {
+        Some(Ok(output)) => output?,
+        Some(Err(_)) => {
+            return Err(
+                metadata_timeout_error("Search timed out. Please try again.").to_wire_string(),
+            );
+        }
+        None => {
+            return Err(
+                BackendError::new(code::DOWNLOAD_CANCELLED, "Search cancelled").to_wire_string(),
+            );
+        }
+    };
+
+    if !output.success && output.stdout.trim().is_empty() {
+        let error = parse_metadata_error(&output.stderr)
+            .unwrap_or_else(|| BackendError::from_message("Search failed"));
+        add_log_internal("error", error.message(), None, None).ok();
+        return Err(error.to_wire_string());
+    }
+
+    let entries = parse_playlist_entries_output(&output.stdout, None);
+    let has_more = entries.len() as u32 >= limit && offset + limit < MAX_SEARCH_RESULTS;
+
+    Ok(VideoSearchResponse {
+        entries,
+        offset,
+        has_more,
+    })
+}
+
+#[tauri::command]
+pub async fn cancel_video_search(search_id: String) -> Result<(), String> {
+    let mut searches = ACTIVE_SEARCHES.lock().await;
+    if let Some(cancel_tx) = searches.remove(&search_id) {
+        cancel_tx.send(()).ok();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_search_args_requests_next_page() {
+        let args = build_search_args("lofi mix", VideoSearchSource::Soundcloud, 20, 10);
+
+        assert_eq!(args.last().map(String::as_str), Some("scsearch30:lofi mix"));
+        let start = args.iter().position(|a| a == "--playlist-start").unwrap();
+        assert_eq!(args[start + 1], "21");
+    }
+}
@@ -0,0 +1,25 @@
+use tauri::AppHandle;
+
+use crate::services::{get_secret, set_github_token, set_secret};
+
+/// Read a credential (proxy password, account token, ...) from the encrypted store
+#[tauri::command]
+pub async fn get_stored_secret(app: AppHandle, name: String) -> Result<Option<String>, String> {
+    get_secret(&app, &name)
+}
+
+/// Save a credential in the encrypted store; an empty or missing value removes it
+#[tauri::command]
+pub async fn set_stored_secret(
+    app: AppHandle,
+    name: String,
+    value: Option<String>,
+) -> Result<(), String> {
+    set_secret(&app, &name, value.as_deref())
+}
+
+/// Use a personal GitHub token for release checks to raise the API rate limit
+#[tauri::command]
+pub async fn set_github_api_token(app: AppHandle, token: Option<String>) -> Result<(), String> {
+    set_github_token(&app, token.as_deref())
+}
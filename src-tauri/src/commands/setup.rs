@@ -0,0 +1,130 @@
+use tauri::{AppHandle, Manager};
+
+use super::{download_deno, download_ffmpeg, download_ytdlp_channel, set_ytdlp_channel_cmd};
+use crate::services::{
+    begin_setup_step, check_deno_internal, check_ffmpeg_internal, finish_setup_step,
+    get_ytdlp_channel, get_ytdlp_path, is_setup_completed, mark_setup_completed,
+};
+use crate::types::{SetupChoices, SetupResult, SetupStatus, SetupStepResult, YtdlpChannel};
+use crate::utils::resolve_output_directory;
+
+/// What the onboarding screen has to offer. `download_folder` defaults to the
+/// system Downloads folder.
+#[tauri::command]
+pub async fn setup_status(
+    app: AppHandle,
+    download_folder: Option<String>,
+) -> Result<SetupStatus, String> {
+    let ytdlp_installed = get_ytdlp_path(&app).await.is_some();
+    let ffmpeg_installed = check_ffmpeg_internal(&app)
+        .await
+        .map(|status| status.installed)
+        .unwrap_or(false);
+    let deno_installed = check_deno_internal(&app)
+        .await
+        .map(|status| status.installed)
+        .unwrap_or(false);
+
+    let download_folder = download_folder
+        .filter(|folder| !folder.trim().is_empty())
+        .or_else(|| {
+            app.path()
+                .download_dir()
+                .ok()
+                .map(|dir| dir.to_string_lossy().to_string())
+        });
+    // Probe without creating anything; a missing folder is created by run_setup
+    let folder_error = match download_folder.as_deref() {
+        Some(folder) => resolve_output_directory(folder, false).err(),
+        None => None,
+    };
+    let download_folder_writable = download_folder.is_some() && folder_error.is_none();
+
+    let missing = [
+        ("ytdlp", ytdlp_installed),
+        ("ffmpeg", ffmpeg_installed),
+        ("deno", deno_installed),
+        ("download_folder", download_folder_writable),
+    ]
+    .iter()
+    .filter(|(_, ready)| !ready)
+    .map(|(step, _)| step.to_string())
+    .collect();
+
+    Ok(SetupStatus {
+        completed: is_setup_completed(&app),
+        ytdlp_installed,
+        ffmpeg_installed,
+        deno_installed,
+        download_folder,
+        download_folder_writable,
+        download_folder_error: folder_error.map(|e| e.to_wire_string()),
+        missing,
+    })
+}
+
+async fn run_setup_step(app: &AppHandle, step: &str, choices: &SetupChoices) -> Result<(), String> {
+    match step {
+        "ytdlp" => {
+            // Keep a channel the user already picked; the bundled binary can't be downloaded
+            let channel = match get_ytdlp_channel(app).await {
+                YtdlpChannel::Bundled => YtdlpChannel::Stable,
+                channel => channel,
+            };
+            download_ytdlp_channel(app.clone(), channel.as_str().to_string()).await?;
+            set_ytdlp_channel_cmd(app.clone(), channel.as_str().to_string()).await
+        }
+        "ffmpeg" => download_ffmpeg(app.clone()).await.map(|_| ()),
+        "deno" => download_deno(app.clone()).await.map(|_| ()),
+        _ => {
+            let folder = choices.download_folder.as_deref().unwrap_or_default();
+            resolve_output_directory(folder, true)
+                .map(|_| ())
+                .map_err(|e| e.to_wire_string())
+        }
+    }
+}
+
+/// Install the chosen dependencies and prepare the download folder, one
+/// step after another, reporting combined `setup-progress` events. Failed
+/// steps do not stop the remaining ones.
+#[tauri::command]
+pub async fn run_setup(app: AppHandle, choices: SetupChoices) -> Result<SetupResult, String> {
+    let steps: Vec<&str> = [
+        ("ytdlp", choices.install_ytdlp),
+        ("ffmpeg", choices.install_ffmpeg),
+        ("deno", choices.install_deno),
+        ("download_folder", choices.download_folder.is_some()),
+    ]
+    .iter()
+    .filter(|(_, chosen)| *chosen)
+    .map(|(step, _)| *step)
+    .collect();
+
+    let mut results = Vec::with_capacity(steps.len());
+    for (index, step) in steps.iter().enumerate() {
+        begin_setup_step(&app, step, index as u32, steps.len() as u32);
+        let outcome = run_setup_step(&app, step, &choices).await;
+        finish_setup_step(&app, if outcome.is_ok() { "done" } else { "failed" });
+        results.push(SetupStepResult {
+            step: step.to_string(),
+            success: outcome.is_ok(),
+            message: outcome.err(),
+        });
+    }
+
+    let completed = results.iter().all(|result| result.success);
+    if completed {
+        mark_setup_completed(&app)?;
+    }
+    let download_folder = choices
+        .download_folder
+        .as_deref()
+        .and_then(|folder| resolve_output_directory(folder, false).ok());
+
+    Ok(SetupResult {
+        completed,
+        download_folder,
+        steps: results,
+    })
+}
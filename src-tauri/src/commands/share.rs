@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::database::get_history_entries_by_ids_from_db;
+use crate::services::copy_text_to_clipboard;
+use crate::types::{BackendError, HistoryCopyKind, HistoryEntry};
+
+const THUMBNAIL_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn history_entry(history_id: String) -> Result<HistoryEntry, String> {
+    get_history_entries_by_ids_from_db(vec![history_id])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "History entry not found".to_string())
+}
+
+/// `[title](url)` with the characters that would end the link escaped
+fn markdown_link(title: &str, url: &str) -> String {
+    let mut escaped = String::with_capacity(title.len());
+    for c in title.chars() {
+        if matches!(c, '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    let url = url
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29");
+    format!("[{}]({})", escaped, url)
+}
+
+fn copy_text(kind: HistoryCopyKind, entry: &HistoryEntry) -> Result<String, String> {
+    let text = match kind {
+        HistoryCopyKind::Url => entry.url.clone(),
+        HistoryCopyKind::Title => entry.title.clone(),
+        HistoryCopyKind::MarkdownLink => markdown_link(&entry.title, &entry.url),
+        HistoryCopyKind::Filepath => entry.filepath.clone(),
+    };
+    if text.trim().is_empty() {
+        return Err("Nothing to copy for this download".to_string());
+    }
+    Ok(text)
+}
+
+/// Copy a history entry's URL, title, Markdown link or file path
+#[tauri::command]
+pub async fn copy_to_clipboard(kind: HistoryCopyKind, history_id: String) -> Result<(), String> {
+    let entry =
+        history_entry(history_id).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let text =
+        copy_text(kind, &entry).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    copy_text_to_clipboard(&text)
+        .await
+        .map_err(|e| BackendError::from_message(e).to_wire_string())
+}
+
+async fn thumbnail_bytes(thumbnail: &str) -> Result<Vec<u8>, String> {
+    if !thumbnail.starts_with("http://") && !thumbnail.starts_with("https://") {
+        let local = thumbnail.strip_prefix("file://").unwrap_or(thumbnail);
+        return std::fs::read(local).map_err(|e| format!("Failed to read thumbnail: {}", e));
+    }
+    let client = reqwest::Client::builder()
+        .timeout(THUMBNAIL_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let bytes = client
+        .get(thumbnail)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download thumbnail: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download thumbnail: {}", e))?;
+    Ok(bytes.to_vec())
+}
+
+/// Save a history entry's thumbnail to `dest`, downloading it if it is
+/// remote. Returns the written path.
+#[tauri::command]
+pub async fn save_thumbnail_as(history_id: String, dest: String) -> Result<String, String> {
+    let wire = |e: String| BackendError::from_message(e).to_wire_string();
+    let entry = history_entry(history_id).map_err(wire)?;
+    let thumbnail = entry
+        .thumbnail
+        .filter(|thumbnail| !thumbnail.trim().is_empty())
+        .ok_or_else(|| wire("This download has no thumbnail".to_string()))?;
+    let dest = PathBuf::from(dest.trim());
+    if dest.as_os_str().is_empty() || dest.is_dir() {
+        return Err(wire(format!("Invalid destination: {}", dest.display())));
+    }
+
+    let bytes = thumbnail_bytes(&thumbnail).await.map_err(wire)?;
+    if let Some(parent) = dest
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| wire(format!("Failed to create folder: {}", e)))?;
+    }
+    std::fs::write(&dest, bytes).map_err(|e| wire(format!("Failed to save thumbnail: {}", e)))?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_link_escapes_title_and_url() {
+        let entry = HistoryEntry {
+            url: "https://example.com/watch (1)".to_string(),
+            title: "Intro [HD]".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            copy_text(HistoryCopyKind::MarkdownLink, &entry).unwrap(),
+            "[Intro \\[HD\\]](https://example.com/watch%20%281%29)"
+        );
+        assert!(copy_text(HistoryCopyKind::Filepath, &entry).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::database::{add_history_internal, add_log_internal, set_history_language};
+use crate::services::{
+    get_deno_path, get_ffmpeg_path, parse_ytdlp_error, privacy_mode_enabled, redact_url_args,
+    run_ytdlp_with_stderr_and_cookies, with_ytdlp_channel,
+};
+use crate::types::BackendError;
+use crate::utils::{
+    detect_source, normalize_url, resolve_output_directory, source_from_extractor, validate_url,
+};
+
+/// Formats yt-dlp's subtitle converter can write
+const SUBTITLE_OUTPUT_FORMATS: [&str; 4] = ["srt", "vtt", "ass", "lrc"];
+
+/// Language code yt-dlp puts before the extension ("Title [id].en.srt" -> "en")
+fn subtitle_file_lang(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_string_lossy().to_string();
+    let (_, lang) = stem.rsplit_once('.')?;
+    (!lang.is_empty()).then(|| lang.to_string())
+}
+
+/// `dir/base.lang.ext`, or `dir/base (n).lang.ext` when that file exists, so
+/// players still pick the track up by its language suffix
+fn free_subtitle_path(dir: &Path, name: &Path) -> PathBuf {
+    let ext = name
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let stem = name
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (base, lang) = match subtitle_file_lang(name) {
+        Some(lang) => (
+            stem[..stem.len() - lang.len() - 1].to_string(),
+            format!(".{}", lang),
+        ),
+        None => (stem, String::new()),
+    };
+    std::iter::once(dir.join(format!("{}{}{}", base, lang, ext)))
+        .chain((1..).map(|n| dir.join(format!("{} ({}){}{}", base, n, lang, ext))))
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded candidates")
+}
+
+/// Move finished subtitle files out of the staging folder under a free name,
+/// never replacing an existing file. Returns each file with its language.
+fn move_staged_subtitles(
+    staging_dir: &Path,
+    output_dir: &Path,
+) -> Result<Vec<(PathBuf, Option<String>)>, String> {
+    let mut staged: Vec<PathBuf> = std::fs::read_dir(staging_dir)
+        .map_err(|e| format!("Failed to read subtitle folder: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    staged.sort();
+
+    let mut written = Vec::with_capacity(staged.len());
+    for path in staged {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let target = free_subtitle_path(output_dir, Path::new(name));
+        // The staging folder is usually on another drive, where rename fails
+        if std::fs::rename(&path, &target).is_err() {
+            std::fs::copy(&path, &target)
+                .map_err(|e| format!("Failed to save {}: {}", target.display(), e))?;
+        }
+        written.push((target, subtitle_file_lang(&path)));
+    }
+    Ok(written)
+}
+
+/// Download only the subtitle tracks of `url` (no media), converted to
+/// `format`, and record each file in history unless privacy mode is on.
+/// Returns the written paths.
+#[tauri::command]
+pub async fn download_subtitles_only(
+    app: AppHandle,
+    url: String,
+    langs: Vec<String>,
+    format: String,
+    output_dir: String,
+    include_auto: Option<bool>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    cookie_skip_patterns: Option<Vec<String>>,
+    proxy_url: Option<String>,
+    ytdlp_channel: Option<String>,
+) -> Result<Vec<String>, String> {
+    validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let url = normalize_url(&url);
+    let format = format.trim().to_lowercase();
+    if !SUBTITLE_OUTPUT_FORMATS.contains(&format.as_str()) {
+        return Err(BackendError::from_message(format!(
+            "Invalid subtitle format '{}'. Use one of: {}",
+            format,
+            SUBTITLE_OUTPUT_FORMATS.join(", ")
+        ))
+        .to_wire_string());
+    }
+    let langs: Vec<&str> = langs
+        .iter()
+        .map(|lang| lang.trim())
+        .filter(|lang| !lang.is_empty())
+        .collect();
+    if langs.is_empty() {
+        return Err(
+            BackendError::from_message("Invalid subtitle languages: none selected")
+                .to_wire_string(),
+        );
+    }
+    let output_dir = resolve_output_directory(&output_dir, true).map_err(|e| e.to_wire_string())?;
+    let incognito = privacy_mode_enabled();
+    let log_url = (!incognito).then(|| url.clone());
+
+    // Stage in a private temp folder so only this run's files are picked up
+    // and nothing half-written ever shows up in the output folder
+    let staging_dir = std::env::temp_dir().join(format!("youwee_subs_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&staging_dir).map_err(|e| {
+        BackendError::from_message(format!("Failed to create subtitle folder: {}", e))
+            .to_wire_string()
+    })?;
+    let output_template = staging_dir
+        .join("%(title)s [%(id)s].%(ext)s")
+        .to_string_lossy()
+        .to_string();
+
+    let mut args = vec![
+        "--skip-download".to_string(),
+        "--no-simulate".to_string(),
+        "--no-playlist".to_string(),
+        "--no-warnings".to_string(),
+        "--write-subs".to_string(),
+        "--sub-langs".to_string(),
+        langs.join(","),
+        "--sub-format".to_string(),
+        format!("{}/best", format),
+        "--convert-subs".to_string(),
+        format.clone(),
+        "-o".to_string(),
+        output_template,
+        "--print".to_string(),
+        "%(title)s|||%(thumbnail)s|||%(duration)s|||%(extractor_key)s".to_string(),
+    ];
+    if include_auto.unwrap_or(true) {
+        args.push("--write-auto-subs".to_string());
+    }
+    if url.contains("youtube.com") || url.contains("youtu.be") {
+        if let Some(deno_path) = get_deno_path(&app).await {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno_path.to_string_lossy()));
+        }
+    }
+    // Converting between subtitle formats needs FFmpeg
+    if let Some(ffmpeg_path) = get_ffmpeg_path(&app).await {
+        if let Some(parent) = ffmpeg_path.parent() {
+            args.push("--ffmpeg-location".to_string());
+            args.push(parent.to_string_lossy().to_string());
+        }
+    }
+    args.push("--".to_string());
+    args.push(url.clone());
+
+    let logged_args = if incognito {
+        redact_url_args(&args, &url)
+    } else {
+        args.clone()
+    };
+    add_log_internal(
+        "command",
+        &format!("yt-dlp {}", logged_args.join(" ")),
+        None,
+        log_url.as_deref(),
+    )
+    .ok();
+
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let result = with_ytdlp_channel(
+        ytdlp_channel.as_deref(),
+        run_ytdlp_with_stderr_and_cookies(
+            &app,
+            &args_ref,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+            cookie_skip_patterns.as_deref(),
+            proxy_url.as_deref(),
+        ),
+    )
+    .await;
+
+    let output = match result {
+        Ok(output) if output.success => output,
+        Ok(output) => {
+            std::fs::remove_dir_all(&staging_dir).ok();
+            return Err(parse_ytdlp_error(&output.stderr)
+                .unwrap_or_else(|| BackendError::from_message("Failed to download subtitles"))
+                .to_wire_string());
+        }
+        Err(e) => {
+            std::fs::remove_dir_all(&staging_dir).ok();
+            return Err(e);
+        }
+    };
+    let moved = move_staged_subtitles(&staging_dir, Path::new(&output_dir));
+    std::fs::remove_dir_all(&staging_dir).ok();
+    let written = moved.map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    if written.is_empty() {
+        return Err(BackendError::from_message(format!(
+            "No subtitles found for {}",
+            langs.join(", ")
+        ))
+        .to_wire_string());
+    }
+
+    let mut printed = output
+        .stdout
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split("|||");
+    let title = printed
+        .next()
+        .map(str::trim)
+        .filter(|title| !title.is_empty() && *title != "NA")
+        .unwrap_or(url.as_str())
+        .to_string();
+    let thumbnail = printed
+        .next()
+        .map(str::trim)
+        .filter(|thumbnail| thumbnail.starts_with("http"))
+        .map(ToString::to_string);
+    let duration = printed
+        .next()
+        .and_then(|duration| duration.trim().parse::<f64>().ok())
+        .map(|duration| duration as u64);
+    let source = printed
+        .next()
+        .and_then(source_from_extractor)
+        .or_else(|| detect_source(&url));
+
+    for (path, language) in written.iter().filter(|_| !incognito) {
+        let filesize = std::fs::metadata(path).ok().map(|meta| meta.len());
+        let history_id = add_history_internal(
+            url.clone(),
+            title.clone(),
+            thumbnail.clone(),
+            path.to_string_lossy().to_string(),
+            filesize,
+            duration,
+            None,
+            Some(format.clone()),
+            source.clone(),
+            None,
+        );
+        if let Ok(history_id) = history_id {
+            set_history_language(&history_id, language.as_deref()).ok();
+        }
+    }
+
+    Ok(written
+        .iter()
+        .map(|(path, _)| path.to_string_lossy().to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staged_subtitles_move_to_output_with_their_language() {
+        let root = std::env::temp_dir().join(format!("youwee-subs-{}", Uuid::new_v4()));
+        let staging = root.join("staging");
+        std::fs::create_dir_all(&staging).unwrap();
+        std::fs::write(staging.join("Talk [abc].en.srt"), "1").unwrap();
+        std::fs::write(staging.join("Talk [abc].pt-BR.srt"), "2").unwrap();
+        std::fs::write(root.join("Talk [abc].en.srt"), "old").unwrap();
+
+        let written = move_staged_subtitles(&staging, &root).unwrap();
+        assert_eq!(
+            written,
+            vec![
+                (root.join("Talk [abc] (1).en.srt"), Some("en".to_string())),
+                (root.join("Talk [abc].pt-BR.srt"), Some("pt-BR".to_string())),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("Talk [abc].en.srt")).unwrap(),
+            "old"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("Talk [abc] (1).en.srt")).unwrap(),
+            "1"
+        );
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -1,13 +1,21 @@
-use crate::database::add_log_internal;
+use crate::database::{add_log_internal, get_channel_rule_from_db};
 use crate::services::{
-    build_cookie_args, build_proxy_args, build_site_header_args, get_deno_path, parse_ytdlp_error,
-    run_ytdlp_json_with_cookies, run_ytdlp_with_stderr, run_ytdlp_with_stderr_and_cookies,
+    build_cookie_args, build_proxy_args, build_site_extractor_args, build_site_header_args,
+    build_youtube_comment_extractor_parts, build_youtube_extractor_args, get_deno_path,
+    metadata_deadline, metadata_network_args, metadata_timeout_error, parse_format_table,
+    parse_metadata_error, run_ytdlp_json_with_cookies, run_ytdlp_with_stderr,
+    run_ytdlp_with_stderr_and_cookies, set_preferred_subtitle_langs, set_site_extractor_args,
+    with_ytdlp_channel, RawFormatRow,
 };
 use crate::types::{
-    BackendError, FormatOption, PlaylistVideoEntry, SubtitleInfo, VideoInfo, VideoInfoResponse,
+    AudioTrackInfo, BackendError, FormatOption, PlaylistVideoEntry, RelatedVideosResponse,
+    SubtitleFormat, SubtitleInfo, VideoComment, VideoCommentsResponse, VideoInfo,
+    VideoInfoResponse,
 };
-use crate::utils::{normalize_url, validate_url};
-use std::time::Duration;
+use crate::utils::{is_short_form_info, is_short_form_url, normalize_url, validate_url};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use tokio::time::timeout;
 use uuid::Uuid;
@@ -65,6 +73,61 @@ fn parse_basic_video_info_output(
     Ok((title.to_string(), thumbnail, duration))
 }
 
+/// Group audio-bearing formats by language so the UI can offer dub/track selection.
+/// Returns nothing for single-language videos.
+fn collect_audio_tracks(formats: &[FormatOption]) -> Vec<AudioTrackInfo> {
+    let mut tracks: Vec<AudioTrackInfo> = Vec::new();
+
+    for format in formats {
+        let has_audio = format
+            .acodec
+            .as_deref()
+            .is_some_and(|codec| codec != "none");
+        let Some(language) = format.language.as_deref().filter(|_| has_audio) else {
+            continue;
+        };
+        // YouTube notes look like "English (United States) original (default), medium"
+        let note = format.format_note.as_deref().unwrap_or("");
+        let is_default = note.contains("(default)");
+        let name = note
+            .split(',')
+            .next()
+            .map(|n| {
+                n.replace("(default)", "")
+                    .replace("original", "")
+                    .trim()
+                    .to_string()
+            })
+            .filter(|n| !n.is_empty());
+
+        match tracks.iter_mut().find(|t| t.language == language) {
+            Some(track) => {
+                track.format_count += 1;
+                track.is_default |= is_default;
+                if track.name.is_none() {
+                    track.name = name;
+                }
+            }
+            None => tracks.push(AudioTrackInfo {
+                language: language.to_string(),
+                name,
+                is_default,
+                format_count: 1,
+            }),
+        }
+    }
+
+    if tracks.len() < 2 {
+        return Vec::new();
+    }
+    tracks.sort_by(|a, b| {
+        b.is_default
+            .cmp(&a.is_default)
+            .then(a.language.cmp(&b.language))
+    });
+    tracks
+}
+
 fn json_string(value: &serde_json::Value, keys: &[&str]) -> Option<String> {
     keys.iter()
         .find_map(|key| value.get(*key).and_then(|v| v.as_str()))
@@ -127,7 +190,7 @@ fn playlist_entry_from_json(
     })
 }
 
-fn parse_playlist_entries_output(
+pub(crate) fn parse_playlist_entries_output(
     output: &str,
     fallback_playlist_title: Option<&str>,
 ) -> Vec<PlaylistVideoEntry> {
@@ -189,6 +252,7 @@ pub async fn get_video_transcript(
     cookie_file_path: Option<String>,
     cookie_skip_patterns: Option<Vec<String>>,
     proxy_url: Option<String>,
+    ytdlp_channel: Option<String>,
 ) -> Result<String, String> {
     // Log the URL being processed
     #[cfg(debug_assertions)]
@@ -237,7 +301,7 @@ pub async fn get_video_transcript(
     .ok();
 
     // Get Deno runtime args for YouTube
-    let deno_args: Vec<String> = if url.contains("youtube.com") || url.contains("youtu.be") {
+    let mut deno_args: Vec<String> = if url.contains("youtube.com") || url.contains("youtu.be") {
         if let Some(deno_path) = get_deno_path(&app).await {
             vec![
                 "--js-runtimes".to_string(),
@@ -249,6 +313,7 @@ pub async fn get_video_transcript(
     } else {
         vec![]
     };
+    deno_args.extend(build_site_extractor_args(&url, None));
 
     // Track if we hit a rate limit error
     let mut rate_limited = false;
@@ -279,9 +344,8 @@ pub async fn get_video_transcript(
             "--no-warnings".to_string(),
             "--no-check-certificates".to_string(),
             "--no-cache-dir".to_string(),
-            "--socket-timeout".to_string(),
-            "30".to_string(),
         ];
+        subtitle_args.extend(metadata_network_args());
         subtitle_args.extend(deno_args.clone());
         subtitle_args.push("--".to_string());
         subtitle_args.push(url_for_subs.clone());
@@ -294,16 +358,19 @@ pub async fn get_video_transcript(
         }
 
         let subtitle_result = timeout(
-            Duration::from_secs(45),
-            run_ytdlp_with_stderr_and_cookies(
-                &app,
-                &subtitle_args_ref,
-                cookie_mode.as_deref(),
-                cookie_browser.as_deref(),
-                cookie_browser_profile.as_deref(),
-                cookie_file_path.as_deref(),
-                cookie_skip_patterns.as_deref(),
-                proxy_url.as_deref(),
+            metadata_deadline(Duration::from_secs(45)),
+            with_ytdlp_channel(
+                ytdlp_channel.as_deref(),
+                run_ytdlp_with_stderr_and_cookies(
+                    &app,
+                    &subtitle_args_ref,
+                    cookie_mode.as_deref(),
+                    cookie_browser.as_deref(),
+                    cookie_browser_profile.as_deref(),
+                    cookie_file_path.as_deref(),
+                    cookie_skip_patterns.as_deref(),
+                    proxy_url.as_deref(),
+                ),
             ),
         )
         .await;
@@ -334,7 +401,7 @@ pub async fn get_video_transcript(
                     }
 
                     // Parse for known errors
-                    if let Some(error_msg) = parse_ytdlp_error(&output.stderr) {
+                    if let Some(error_msg) = parse_metadata_error(&output.stderr) {
                         if specific_error.is_none() {
                             specific_error = Some(error_msg.clone());
                         }
@@ -462,7 +529,8 @@ pub async fn get_video_transcript(
     tokio::time::sleep(Duration::from_secs(2)).await;
 
     // No subtitles found - try to get title and description as fallback
-    let info_args = vec![
+    let network_args = metadata_network_args();
+    let mut info_args = vec![
         "--skip-download",
         "--no-playlist", // Important: only get single video, not playlist
         "--ignore-no-formats-error",
@@ -470,26 +538,27 @@ pub async fn get_video_transcript(
         "%(title)s|||%(description)s",
         "--no-warnings",
         "--no-cache-dir",
-        "--socket-timeout",
-        "30",
-        "--",
-        &url_for_info,
     ];
+    info_args.extend(network_args.iter().map(String::as_str));
+    info_args.extend(["--", &url_for_info]);
 
     let info_cmd = format!("yt-dlp {}", info_args.join(" "));
     add_log_internal("command", &info_cmd, None, Some(&url)).ok();
 
     let info_result = timeout(
-        Duration::from_secs(45), // Increased from 15
-        run_ytdlp_json_with_cookies(
-            &app,
-            &info_args.iter().map(|s| *s).collect::<Vec<_>>(),
-            cookie_mode.as_deref(),
-            cookie_browser.as_deref(),
-            cookie_browser_profile.as_deref(),
-            cookie_file_path.as_deref(),
-            cookie_skip_patterns.as_deref(),
-            proxy_url.as_deref(),
+        metadata_deadline(Duration::from_secs(45)),
+        with_ytdlp_channel(
+            ytdlp_channel.as_deref(),
+            run_ytdlp_json_with_cookies(
+                &app,
+                &info_args.iter().map(|s| *s).collect::<Vec<_>>(),
+                cookie_mode.as_deref(),
+                cookie_browser.as_deref(),
+                cookie_browser_profile.as_deref(),
+                cookie_file_path.as_deref(),
+                cookie_skip_patterns.as_deref(),
+                proxy_url.as_deref(),
+            ),
         ),
     )
     .await;
@@ -573,40 +642,35 @@ pub async fn get_video_transcript(
         Err(_) => {
             #[cfg(debug_assertions)]
             println!("[TRANSCRIPT] Description fetch timed out");
-            add_log_internal(
-                "stderr",
-                "Description fetch timed out (45s)",
-                None,
-                Some(&url),
-            )
-            .ok();
+            add_log_internal("stderr", "Description fetch timed out", None, Some(&url)).ok();
         }
     }
 
     // Fallback #2: metadata extraction from dump-json (useful for Douyin/TikTok when subtitles are unavailable)
     if !rate_limited {
-        let metadata_args = vec![
+        let mut metadata_args = vec![
             "--dump-json",
             "--no-download",
             "--no-playlist",
             "--no-warnings",
             "--no-cache-dir",
-            "--socket-timeout",
-            "30",
-            "--",
-            &url_for_info,
         ];
+        metadata_args.extend(network_args.iter().map(String::as_str));
+        metadata_args.extend(["--", &url_for_info]);
         let metadata_result = timeout(
-            Duration::from_secs(45),
-            run_ytdlp_json_with_cookies(
-                &app,
-                &metadata_args.iter().copied().collect::<Vec<_>>(),
-                cookie_mode.as_deref(),
-                cookie_browser.as_deref(),
-                cookie_browser_profile.as_deref(),
-                cookie_file_path.as_deref(),
-                cookie_skip_patterns.as_deref(),
-                proxy_url.as_deref(),
+            metadata_deadline(Duration::from_secs(45)),
+            with_ytdlp_channel(
+                ytdlp_channel.as_deref(),
+                run_ytdlp_json_with_cookies(
+                    &app,
+                    &metadata_args.iter().copied().collect::<Vec<_>>(),
+                    cookie_mode.as_deref(),
+                    cookie_browser.as_deref(),
+                    cookie_browser_profile.as_deref(),
+                    cookie_file_path.as_deref(),
+                    cookie_skip_patterns.as_deref(),
+                    proxy_url.as_deref(),
+                ),
             ),
         )
         .await;
@@ -635,13 +699,7 @@ pub async fn get_video_transcript(
                 .ok();
             }
             Err(_) => {
-                add_log_internal(
-                    "stderr",
-                    "Metadata fallback timed out (45s)",
-                    None,
-                    Some(&url),
-                )
-                .ok();
+                add_log_internal("stderr", "Metadata fallback timed out", None, Some(&url)).ok();
             }
         }
     }
@@ -925,9 +983,8 @@ pub async fn get_video_basic_info(
         "--no-simulate".to_string(),
         "--no-playlist".to_string(),
         "--ignore-no-formats-error".to_string(),
-        "--socket-timeout".to_string(),
-        "15".to_string(),
     ];
+    args.extend(metadata_network_args());
 
     if url.contains("youtube.com") || url.contains("youtu.be") {
         if let Some(deno_path) = get_deno_path(&app).await {
@@ -946,7 +1003,7 @@ pub async fn get_video_basic_info(
     add_log_internal("command", &command_str, None, Some(&url)).ok();
 
     let output = match timeout(
-        Duration::from_secs(45),
+        metadata_deadline(Duration::from_secs(45)),
         run_ytdlp_with_stderr_and_cookies(
             &app,
             &args_ref,
@@ -962,7 +1019,7 @@ pub async fn get_video_basic_info(
     {
         Ok(result) => result?,
         Err(_) => {
-            let error = BackendError::from_message(
+            let error = metadata_timeout_error(
                 "Timed out fetching video info. Please try again or check your cookie/proxy settings.",
             );
             add_log_internal("error", error.message(), None, Some(&url)).ok();
@@ -975,7 +1032,7 @@ pub async fn get_video_basic_info(
     }
 
     if !output.success {
-        let parsed_error = parse_ytdlp_error(&output.stderr).unwrap_or_else(|| {
+        let parsed_error = parse_metadata_error(&output.stderr).unwrap_or_else(|| {
             let stderr = output.stderr.trim();
             if stderr.is_empty() {
                 BackendError::from_message("Failed to fetch video info.")
@@ -996,6 +1053,7 @@ pub async fn get_video_basic_info(
         thumbnail,
         duration,
         channel: None,
+        channel_id: None,
         uploader: None,
         upload_date: None,
         view_count: None,
@@ -1007,6 +1065,7 @@ pub async fn get_video_basic_info(
         is_live: None,
         was_live: None,
         live_status: None,
+        is_short: is_short_form_url(&url),
     };
 
     add_log_internal(
@@ -1020,6 +1079,8 @@ pub async fn get_video_basic_info(
     Ok(VideoInfoResponse {
         info,
         formats: Vec::new(),
+        audio_tracks: Vec::new(),
+        channel_rule: None,
     })
 }
 
@@ -1033,6 +1094,7 @@ pub async fn get_video_info(
     cookie_file_path: Option<String>,
     cookie_skip_patterns: Option<Vec<String>>,
     proxy_url: Option<String>,
+    ytdlp_channel: Option<String>,
 ) -> Result<VideoInfoResponse, String> {
     validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
     let url = normalize_url(&url);
@@ -1043,9 +1105,8 @@ pub async fn get_video_info(
         "--no-playlist".to_string(),
         "--ignore-no-formats-error".to_string(),
         "--no-warnings".to_string(),
-        "--socket-timeout".to_string(),
-        "15".to_string(),
     ];
+    args.extend(metadata_network_args());
 
     // Add Deno runtime for YouTube (required for JS extractor)
     if url.contains("youtube.com") || url.contains("youtu.be") {
@@ -1059,6 +1120,7 @@ pub async fn get_video_info(
     args.push(url.clone());
 
     let mut extra_args = build_site_header_args(&url);
+    extra_args.extend(build_site_extractor_args(&url, None));
     extra_args.extend(build_cookie_args(
         &url,
         cookie_mode.as_deref(),
@@ -1078,14 +1140,17 @@ pub async fn get_video_info(
 
     let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     let output = match timeout(
-        Duration::from_secs(45),
-        run_ytdlp_with_stderr(&app, &args_ref),
+        metadata_deadline(Duration::from_secs(45)),
+        with_ytdlp_channel(
+            ytdlp_channel.as_deref(),
+            run_ytdlp_with_stderr(&app, &args_ref),
+        ),
     )
     .await
     {
         Ok(result) => result?,
         Err(_) => {
-            let error = BackendError::from_message(
+            let error = metadata_timeout_error(
                 "Timed out fetching video info. Please try again or check your cookie/proxy settings.",
             );
             add_log_internal("error", error.message(), None, Some(&url)).ok();
@@ -1098,7 +1163,7 @@ pub async fn get_video_info(
     }
 
     if !output.success {
-        let parsed_error = parse_ytdlp_error(&output.stderr).unwrap_or_else(|| {
+        let parsed_error = parse_metadata_error(&output.stderr).unwrap_or_else(|| {
             let stderr = output.stderr.trim();
             if stderr.is_empty() {
                 BackendError::from_message("Failed to fetch video info.")
@@ -1146,6 +1211,11 @@ pub async fn get_video_info(
             .get("channel")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string()),
+        channel_id: ["channel_id", "uploader_id"]
+            .iter()
+            .find_map(|key| json.get(*key).and_then(|v| v.as_str()))
+            .filter(|id| !id.is_empty())
+            .map(|s| s.to_string()),
         uploader: json
             .get("uploader")
             .and_then(|v| v.as_str())
@@ -1179,6 +1249,7 @@ pub async fn get_video_info(
             .get("live_status")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string()),
+        is_short: !is_playlist && (is_short_form_info(&json) || is_short_form_url(&url)),
     };
 
     let formats = if let Some(formats_arr) = json.get("formats").and_then(|v| v.as_array()) {
@@ -1214,6 +1285,11 @@ pub async fn get_video_info(
                         .map(|s| s.to_string()),
                     fps: f.get("fps").and_then(|v| v.as_f64()),
                     quality: f.get("quality").and_then(|v| v.as_f64()),
+                    language: f
+                        .get("language")
+                        .and_then(|v| v.as_str())
+                        .filter(|s| !s.is_empty())
+                        .map(|s| s.to_string()),
                 })
             })
             .collect()
@@ -1229,7 +1305,22 @@ pub async fn get_video_info(
     )
     .ok();
 
-    Ok(VideoInfoResponse { info, formats })
+    let audio_tracks = collect_audio_tracks(&formats);
+    let channel_rule = info.channel_id.as_deref().and_then(|channel_id| {
+        get_channel_rule_from_db(
+            info.extractor_key.as_deref().unwrap_or_default(),
+            channel_id,
+        )
+        .ok()
+        .flatten()
+    });
+
+    Ok(VideoInfoResponse {
+        info,
+        formats,
+        audio_tracks,
+        channel_rule,
+    })
 }
 
 #[tauri::command]
@@ -1243,6 +1334,7 @@ pub async fn get_playlist_entries(
     cookie_file_path: Option<String>,
     cookie_skip_patterns: Option<Vec<String>>,
     proxy_url: Option<String>,
+    ytdlp_channel: Option<String>,
 ) -> Result<Vec<PlaylistVideoEntry>, String> {
     validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
     let url = normalize_url(&url);
@@ -1251,9 +1343,8 @@ pub async fn get_playlist_entries(
         "--flat-playlist".to_string(),
         "--dump-single-json".to_string(),
         "--no-warnings".to_string(),
-        "--socket-timeout".to_string(),
-        "30".to_string(),
     ];
+    args.extend(metadata_network_args());
 
     if let Some(l) = limit {
         if l > 0 {
@@ -1295,9 +1386,15 @@ pub async fn get_playlist_entries(
     args.push(url.clone());
 
     let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    let output_result = run_ytdlp_with_stderr(&app, &args_ref).await?;
+    let output_result = with_ytdlp_channel(
+        ytdlp_channel.as_deref(),
+        run_ytdlp_with_stderr(&app, &args_ref),
+    )
+    .await?;
     if !output_result.success && output_result.stdout.trim().is_empty() {
-        return Err(BackendError::from_message("Failed to fetch playlist info").to_wire_string());
+        let error = parse_metadata_error(&output_result.stderr)
+            .unwrap_or_else(|| BackendError::from_message("Failed to fetch playlist info"));
+        return Err(error.to_wire_string());
     }
     let output = output_result.stdout;
 
@@ -1310,171 +1407,629 @@ pub async fn get_playlist_entries(
     Ok(entries)
 }
 
+const DEFAULT_RELATED_LIMIT: u32 = 20;
+const RELATED_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Related video lookups keyed by `limit|url`; the UI asks again after every download
+static RELATED_VIDEOS_CACHE: LazyLock<Mutex<HashMap<String, (Instant, RelatedVideosResponse)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn cached_related_videos(key: &str) -> Option<RelatedVideosResponse> {
+    let mut cache = RELATED_VIDEOS_CACHE.lock().ok()?;
+    cache.retain(|_, (stored_at, _)| stored_at.elapsed() < RELATED_CACHE_TTL);
+    cache.get(key).map(|(_, response)| response.clone())
+}
+
+fn store_related_videos(key: String, response: &RelatedVideosResponse) {
+    if let Ok(mut cache) = RELATED_VIDEOS_CACHE.lock() {
+        cache.insert(key, (Instant::now(), response.clone()));
+    }
+}
+
+/// Related entries exposed directly by the extractor, excluding the source video
+fn related_entries_from_info(
+    json: &serde_json::Value,
+    source_id: Option<&str>,
+    limit: usize,
+) -> Vec<PlaylistVideoEntry> {
+    json.get("related_videos")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| playlist_entry_from_json(item, None))
+                .filter(|entry| Some(entry.id.as_str()) != source_id)
+                .take(limit)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Channel page listing recent uploads, used when the extractor has no related data
+fn channel_uploads_url(json: &serde_json::Value) -> Option<String> {
+    let channel_url = json_string(json, &["channel_url", "uploader_url"])?;
+    let lowered = channel_url.to_lowercase();
+    if lowered.contains("youtube.com/") && !lowered.trim_end_matches('/').ends_with("/videos") {
+        return Some(format!("{}/videos", channel_url.trim_end_matches('/')));
+    }
+    Some(channel_url)
+}
+
+async fn run_info_command(
+    app: &AppHandle,
+    url: &str,
+    mut args: Vec<String>,
+    cookie_mode: Option<&str>,
+    cookie_browser: Option<&str>,
+    cookie_browser_profile: Option<&str>,
+    cookie_file_path: Option<&str>,
+    cookie_skip_patterns: Option<&[String]>,
+    proxy_url: Option<&str>,
+) -> Result<String, String> {
+    if url.contains("youtube.com") || url.contains("youtu.be") {
+        if let Some(deno_path) = get_deno_path(app).await {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno_path.to_string_lossy()));
+        }
+    }
+    args.extend(build_site_header_args(url));
+    args.extend(build_cookie_args(
+        url,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        cookie_skip_patterns,
+    ));
+    args.extend(build_proxy_args(proxy_url));
+    args.push("--".to_string());
+    args.push(url.to_string());
+
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = match timeout(
+        metadata_deadline(Duration::from_secs(60)),
+        run_ytdlp_with_stderr(app, &args_ref),
+    )
+    .await
+    {
+        Ok(result) => result?,
+        Err(_) => {
+            return Err(
+                metadata_timeout_error("Timed out fetching related videos.").to_wire_string()
+            )
+        }
+    };
+
+    if !output.success && output.stdout.trim().is_empty() {
+        let error = parse_metadata_error(&output.stderr)
+            .unwrap_or_else(|| BackendError::from_message("Failed to fetch related videos."));
+        add_log_internal("error", error.message(), None, Some(url)).ok();
+        return Err(error.to_wire_string());
+    }
+
+    Ok(output.stdout)
+}
+
+/// Suggest more videos to download after `url`: the extractor's related list
+/// when it has one, otherwise the channel's recent uploads.
 #[tauri::command]
-pub async fn get_available_subtitles(
+pub async fn get_related_videos(
     app: AppHandle,
     url: String,
+    limit: Option<u32>,
     cookie_mode: Option<String>,
     cookie_browser: Option<String>,
     cookie_browser_profile: Option<String>,
     cookie_file_path: Option<String>,
     cookie_skip_patterns: Option<Vec<String>>,
     proxy_url: Option<String>,
-) -> Result<Vec<SubtitleInfo>, String> {
+) -> Result<RelatedVideosResponse, String> {
+    validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let url = normalize_url(&url);
+    let limit = limit.filter(|l| *l > 0).unwrap_or(DEFAULT_RELATED_LIMIT);
+
+    let cache_key = format!("{}|{}", limit, url);
+    if let Some(cached) = cached_related_videos(&cache_key) {
+        return Ok(cached);
+    }
+
+    let mut info_args = vec![
+        "--dump-json".to_string(),
+        "--skip-download".to_string(),
+        "--no-playlist".to_string(),
+        "--ignore-no-formats-error".to_string(),
+        "--no-warnings".to_string(),
+    ];
+    info_args.extend(metadata_network_args());
+    let info_output = run_info_command(
+        &app,
+        &url,
+        info_args,
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        cookie_skip_patterns.as_deref(),
+        proxy_url.as_deref(),
+    )
+    .await?;
+    let info: serde_json::Value = serde_json::from_str(info_output.trim()).map_err(|e| {
+        BackendError::from_message(format!("Failed to parse video info JSON: {}", e))
+            .to_wire_string()
+    })?;
+    let source_id = json_string(&info, &["id"]);
+
+    let related = related_entries_from_info(&info, source_id.as_deref(), limit as usize);
+    let response = if !related.is_empty() {
+        RelatedVideosResponse {
+            source: "extractor".to_string(),
+            entries: related,
+        }
+    } else if let Some(channel_url) = channel_uploads_url(&info) {
+        // Fetch one extra entry since the source video is usually among the latest uploads
+        let mut channel_args = vec![
+            "--flat-playlist".to_string(),
+            "--dump-single-json".to_string(),
+            "--no-warnings".to_string(),
+            "--playlist-end".to_string(),
+            (limit + 1).to_string(),
+        ];
+        channel_args.extend(metadata_network_args());
+        let channel_output = run_info_command(
+            &app,
+            &channel_url,
+            channel_args,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+            cookie_skip_patterns.as_deref(),
+            proxy_url.as_deref(),
+        )
+        .await?;
+        let entries = parse_playlist_entries_output(&channel_output, None)
+            .into_iter()
+            .filter(|entry| Some(&entry.id) != source_id.as_ref())
+            .take(limit as usize)
+            .collect();
+        RelatedVideosResponse {
+            source: "channel".to_string(),
+            entries,
+        }
+    } else {
+        RelatedVideosResponse {
+            source: "none".to_string(),
+            entries: Vec::new(),
+        }
+    };
+
+    store_related_videos(cache_key, &response);
+    Ok(response)
+}
+
+const DEFAULT_COMMENT_LIMIT: u32 = 100;
+
+fn comment_from_json(value: &serde_json::Value) -> Option<VideoComment> {
+    let text = value.get("text").and_then(|v| v.as_str())?;
+    Some(VideoComment {
+        id: json_string(value, &["id"]).unwrap_or_default(),
+        author: json_string(value, &["author"]),
+        text: text.to_string(),
+        like_count: value.get("like_count").and_then(|v| v.as_u64()),
+        is_pinned: value
+            .get("is_pinned")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        author_is_uploader: value
+            .get("author_is_uploader")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        parent: json_string(value, &["parent"]),
+        timestamp: value.get("timestamp").and_then(|v| v.as_i64()),
+    })
+}
+
+fn parse_video_comments(json: &serde_json::Value, limit: usize) -> VideoCommentsResponse {
+    let comments = json
+        .get("comments")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(comment_from_json)
+                .take(limit)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    VideoCommentsResponse {
+        comments,
+        total_count: json.get("comment_count").and_then(|v| v.as_u64()),
+    }
+}
+
+/// Fetch the top comments of a video without downloading it
+#[tauri::command]
+pub async fn get_video_comments(
+    app: AppHandle,
+    url: String,
+    limit: Option<u32>,
+    sort: Option<String>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    cookie_skip_patterns: Option<Vec<String>>,
+    proxy_url: Option<String>,
+) -> Result<VideoCommentsResponse, String> {
     validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
     let url = normalize_url(&url);
+    let limit = limit.filter(|l| *l > 0).unwrap_or(DEFAULT_COMMENT_LIMIT);
 
     let mut args = vec![
-        "--list-subs".to_string(),
+        "--dump-json".to_string(),
         "--skip-download".to_string(),
+        "--write-comments".to_string(),
+        "--no-playlist".to_string(),
+        "--ignore-no-formats-error".to_string(),
         "--no-warnings".to_string(),
     ];
+    args.extend(metadata_network_args());
 
-    // Add Deno runtime for YouTube (required for JS extractor)
+    let mut youtube_extractor_args = None;
     if url.contains("youtube.com") || url.contains("youtu.be") {
         if let Some(deno_path) = get_deno_path(&app).await {
             args.push("--js-runtimes".to_string());
             args.push(format!("deno:{}", deno_path.to_string_lossy()));
         }
+        // Stop paging once the limit is reached; comment threads can be huge
+        let comment_parts = build_youtube_comment_extractor_parts(Some(limit), sort.as_deref());
+        youtube_extractor_args = build_youtube_extractor_args(false, None, &comment_parts);
     }
+    args.extend(build_site_extractor_args(&url, youtube_extractor_args));
 
-    args.push("--".to_string());
-    args.push(url.clone());
-
-    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-
-    let output = run_ytdlp_json_with_cookies(
-        &app,
-        &args_ref,
+    args.extend(build_site_header_args(&url));
+    args.extend(build_cookie_args(
+        &url,
         cookie_mode.as_deref(),
         cookie_browser.as_deref(),
         cookie_browser_profile.as_deref(),
         cookie_file_path.as_deref(),
         cookie_skip_patterns.as_deref(),
-        proxy_url.as_deref(),
+    ));
+    args.extend(build_proxy_args(proxy_url.as_deref()));
+    args.push("--".to_string());
+    args.push(url.clone());
+
+    let command_str = format!("yt-dlp {}", args.join(" "));
+    add_log_internal("command", &command_str, None, Some(&url)).ok();
+
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = match timeout(
+        metadata_deadline(Duration::from_secs(120)),
+        run_ytdlp_with_stderr(&app, &args_ref),
     )
-    .await;
+    .await
+    {
+        Ok(result) => result?,
+        Err(_) => {
+            let error =
+                metadata_timeout_error("Timed out fetching comments. Try a lower comment limit.");
+            add_log_internal("error", error.message(), None, Some(&url)).ok();
+            return Err(error.to_wire_string());
+        }
+    };
 
-    let mut subtitles: Vec<SubtitleInfo> = Vec::new();
-
-    let lang_names: std::collections::HashMap<&str, &str> = [
-        ("en", "English"),
-        ("vi", "Vietnamese"),
-        ("ja", "Japanese"),
-        ("ko", "Korean"),
-        ("zh", "Chinese"),
-        ("zh-Hans", "Chinese (Simplified)"),
-        ("zh-Hant", "Chinese (Traditional)"),
-        ("th", "Thai"),
-        ("id", "Indonesian"),
-        ("ms", "Malay"),
-        ("fr", "French"),
-        ("de", "German"),
-        ("es", "Spanish"),
-        ("pt", "Portuguese"),
-        ("ru", "Russian"),
-        ("ar", "Arabic"),
-        ("hi", "Hindi"),
-        ("it", "Italian"),
-        ("nl", "Dutch"),
-        ("pl", "Polish"),
-        ("tr", "Turkish"),
-        ("uk", "Ukrainian"),
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    if let Ok(text) = output {
-        let mut is_auto_section = false;
-
-        for line in text.lines() {
-            let line = line.trim();
-
-            if line.contains("automatic captions") || line.contains("auto-generated") {
-                is_auto_section = true;
-                continue;
+    if !output.success {
+        let parsed_error = parse_metadata_error(&output.stderr).unwrap_or_else(|| {
+            let stderr = output.stderr.trim();
+            if stderr.is_empty() {
+                BackendError::from_message("Failed to fetch comments.")
+            } else {
+                BackendError::from_message(format!("Failed to fetch comments: {}", stderr))
             }
+        });
+        add_log_internal("error", parsed_error.message(), None, Some(&url)).ok();
+        return Err(parsed_error.to_wire_string());
+    }
+
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).map_err(|e| {
+        BackendError::from_message(format!("Failed to parse comments JSON: {}", e)).to_wire_string()
+    })?;
 
-            if line.contains("subtitles") && !line.contains("auto") {
-                is_auto_section = false;
+    Ok(parse_video_comments(&json, limit as usize))
+}
+
+/// Display name for a subtitle language: the curated name when we have one,
+/// else the name yt-dlp reported, else the code itself
+fn subtitle_language_name(lang: &str, reported: Option<&str>) -> String {
+    let curated = match lang {
+        "en" => "English",
+        "vi" => "Vietnamese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "zh" => "Chinese",
+        "zh-Hans" => "Chinese (Simplified)",
+        "zh-Hant" => "Chinese (Traditional)",
+        "th" => "Thai",
+        "id" => "Indonesian",
+        "ms" => "Malay",
+        "fr" => "French",
+        "de" => "German",
+        "es" => "Spanish",
+        "pt" => "Portuguese",
+        "ru" => "Russian",
+        "ar" => "Arabic",
+        "hi" => "Hindi",
+        "it" => "Italian",
+        "nl" => "Dutch",
+        "pl" => "Polish",
+        "tr" => "Turkish",
+        "uk" => "Ukrainian",
+        _ => {
+            return reported
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .unwrap_or(lang)
+                .to_string()
+        }
+    };
+    curated.to_string()
+}
+
+/// Subtitle tracks from the `subtitles` and `automatic_captions` objects of
+/// a `--dump-json` payload, manual tracks first
+fn parse_available_subtitles(json: &serde_json::Value) -> Vec<SubtitleInfo> {
+    let mut subtitles = Vec::new();
+    for (key, is_auto) in [("subtitles", false), ("automatic_captions", true)] {
+        let Some(tracks) = json.get(key).and_then(|v| v.as_object()) else {
+            continue;
+        };
+        for (lang, entries) in tracks {
+            // YouTube lists the live chat replay as a subtitle track
+            if lang == "live_chat" {
                 continue;
             }
-
-            if line.is_empty()
-                || line.starts_with("Language")
-                || line.starts_with("[")
-                || line.contains("Available")
-            {
+            let entries = entries.as_array().map(Vec::as_slice).unwrap_or_default();
+            let formats: Vec<SubtitleFormat> = entries
+                .iter()
+                .filter_map(|entry| {
+                    Some(SubtitleFormat {
+                        ext: entry.get("ext")?.as_str()?.to_string(),
+                        url: json_string(entry, &["url"]),
+                    })
+                })
+                .collect();
+            if formats.is_empty() {
                 continue;
             }
+            let reported = entries
+                .iter()
+                .find_map(|entry| entry.get("name").and_then(|v| v.as_str()));
+            subtitles.push(SubtitleInfo {
+                lang: lang.clone(),
+                name: subtitle_language_name(lang, reported),
+                is_auto,
+                formats,
+            });
+        }
+    }
+    subtitles
+}
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if let Some(lang_code) = parts.first() {
-                let lang = lang_code.to_string();
-                if subtitles
-                    .iter()
-                    .any(|s| s.lang == lang && s.is_auto == is_auto_section)
-                {
-                    continue;
-                }
+#[tauri::command]
+pub async fn get_available_subtitles(
+    app: AppHandle,
+    url: String,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    cookie_skip_patterns: Option<Vec<String>>,
+    proxy_url: Option<String>,
+    ytdlp_channel: Option<String>,
+) -> Result<Vec<SubtitleInfo>, String> {
+    validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let url = normalize_url(&url);
 
-                let name = lang_names
-                    .get(lang.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| lang.clone());
+    let mut args = vec![
+        "--dump-json".to_string(),
+        "--no-playlist".to_string(),
+        "--skip-download".to_string(),
+        "--no-warnings".to_string(),
+    ];
 
-                subtitles.push(SubtitleInfo {
-                    lang,
-                    name,
-                    is_auto: is_auto_section,
-                });
-            }
+    // Add Deno runtime for YouTube (required for JS extractor)
+    if url.contains("youtube.com") || url.contains("youtu.be") {
+        if let Some(deno_path) = get_deno_path(&app).await {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno_path.to_string_lossy()));
         }
     }
 
-    if subtitles.is_empty() {
-        subtitles = vec![
-            SubtitleInfo {
-                lang: "en".to_string(),
-                name: "English".to_string(),
-                is_auto: false,
-            },
-            SubtitleInfo {
-                lang: "vi".to_string(),
-                name: "Vietnamese".to_string(),
-                is_auto: false,
-            },
-            SubtitleInfo {
-                lang: "ja".to_string(),
-                name: "Japanese".to_string(),
-                is_auto: false,
-            },
-            SubtitleInfo {
-                lang: "es".to_string(),
-                name: "Spanish".to_string(),
-                is_auto: false,
-            },
-            SubtitleInfo {
-                lang: "ko".to_string(),
-                name: "Korean".to_string(),
-                is_auto: false,
-            },
-            SubtitleInfo {
-                lang: "zh".to_string(),
-                name: "Chinese".to_string(),
+    args.push("--".to_string());
+    args.push(url.clone());
+
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+    let output = with_ytdlp_channel(
+        ytdlp_channel.as_deref(),
+        run_ytdlp_json_with_cookies(
+            &app,
+            &args_ref,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+            cookie_skip_patterns.as_deref(),
+            proxy_url.as_deref(),
+        ),
+    )
+    .await;
+
+    let subtitles = match output {
+        Ok(stdout) => {
+            let json: serde_json::Value = serde_json::from_str(stdout.trim()).map_err(|e| {
+                BackendError::from_message(format!("Failed to parse subtitle JSON: {}", e))
+                    .to_wire_string()
+            })?;
+            parse_available_subtitles(&json)
+        }
+        // Keep the picker usable when the lookup fails (network, cookies, ...)
+        Err(_) => ["en", "vi", "ja", "es", "ko", "zh"]
+            .iter()
+            .map(|lang| SubtitleInfo {
+                lang: lang.to_string(),
+                name: subtitle_language_name(lang, None),
                 is_auto: false,
-            },
-        ];
-    }
+                formats: Vec::new(),
+            })
+            .collect(),
+    };
 
     Ok(subtitles)
 }
 
+/// The full `yt-dlp -F` table for picking exact format IDs
+#[tauri::command]
+pub async fn list_formats_raw(
+    app: AppHandle,
+    url: String,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    cookie_skip_patterns: Option<Vec<String>>,
+    proxy_url: Option<String>,
+    ytdlp_channel: Option<String>,
+) -> Result<Vec<RawFormatRow>, String> {
+    validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let url = normalize_url(&url);
+
+    let mut args = vec![
+        "-F".to_string(),
+        "--no-playlist".to_string(),
+        "--no-warnings".to_string(),
+    ];
+
+    // Add Deno runtime for YouTube (required for JS extractor)
+    if url.contains("youtube.com") || url.contains("youtu.be") {
+        if let Some(deno_path) = get_deno_path(&app).await {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno_path.to_string_lossy()));
+        }
+    }
+    args.extend(build_site_extractor_args(&url, None));
+
+    args.push("--".to_string());
+    args.push(url.clone());
+
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = with_ytdlp_channel(
+        ytdlp_channel.as_deref(),
+        run_ytdlp_json_with_cookies(
+            &app,
+            &args_ref,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+            cookie_skip_patterns.as_deref(),
+            proxy_url.as_deref(),
+        ),
+    )
+    .await?;
+
+    let rows = parse_format_table(&output);
+    if rows.is_empty() {
+        return Err(BackendError::from_message("yt-dlp didn't list any formats").to_wire_string());
+    }
+    Ok(rows)
+}
+
+/// Languages (usually the UI languages) the smart subtitle mode fetches besides the original
+#[tauri::command]
+pub fn set_preferred_subtitle_langs_cmd(langs: Vec<String>) -> Result<(), String> {
+    set_preferred_subtitle_langs(langs);
+    Ok(())
+}
+
+/// Per-site `--extractor-args` (site host -> `extractor:key=value;...`)
+#[tauri::command]
+pub fn set_site_extractor_args_cmd(sites: HashMap<String, String>) -> Result<(), String> {
+    set_site_extractor_args(sites).map_err(|e| e.to_wire_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn audio_format(format_id: &str, language: &str, note: &str) -> FormatOption {
+        FormatOption {
+            format_id: format_id.to_string(),
+            ext: "m4a".to_string(),
+            resolution: Some("audio only".to_string()),
+            width: None,
+            height: None,
+            vcodec: Some("none".to_string()),
+            acodec: Some("mp4a.40.2".to_string()),
+            filesize: None,
+            filesize_approx: None,
+            tbr: None,
+            format_note: Some(note.to_string()),
+            fps: None,
+            quality: None,
+            language: Some(language.to_string()),
+        }
+    }
+
+    #[test]
+    fn collect_audio_tracks_groups_dubbed_languages() {
+        let formats = vec![
+            audio_format("140-0", "es", "Spanish, medium"),
+            audio_format(
+                "140-1",
+                "en-US",
+                "English (United States) original (default), medium",
+            ),
+            audio_format(
+                "251-1",
+                "en-US",
+                "English (United States) original (default), high",
+            ),
+        ];
+
+        let tracks = collect_audio_tracks(&formats);
+
+        assert_eq!(
+            tracks,
+            vec![
+                AudioTrackInfo {
+                    language: "en-US".to_string(),
+                    name: Some("English (United States)".to_string()),
+                    is_default: true,
+                    format_count: 2,
+                },
+                AudioTrackInfo {
+                    language: "es".to_string(),
+                    name: Some("Spanish".to_string()),
+                    is_default: false,
+                    format_count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_audio_tracks_ignores_single_language_videos() {
+        let formats = vec![
+            audio_format("140", "en", "medium"),
+            audio_format("251", "en", "high"),
+        ];
+
+        assert!(collect_audio_tracks(&formats).is_empty());
+    }
+
     #[test]
     fn parse_basic_video_info_output_reads_printed_fields() {
         let output = concat!(
@@ -1552,4 +2107,99 @@ mod tests {
             Some("Fallback Playlist")
         );
     }
+
+    #[test]
+    fn parse_video_comments_truncates_and_skips_textless_entries() {
+        let json = serde_json::json!({
+            "comment_count": 1234,
+            "comments": [
+                {"id": "c1", "author": "@uploader", "text": "Pinned note", "like_count": 50,
+                 "is_pinned": true, "author_is_uploader": true, "parent": "root", "timestamp": 1700000000},
+                {"id": "c2", "author": "@viewer"},
+                {"id": "c3", "author": "@viewer", "text": "Great video", "parent": "c1"},
+                {"id": "c4", "text": "Over the limit"}
+            ]
+        });
+
+        let response = parse_video_comments(&json, 2);
+
+        assert_eq!(response.total_count, Some(1234));
+        assert_eq!(response.comments.len(), 2);
+        assert!(response.comments[0].is_pinned);
+        assert!(response.comments[0].author_is_uploader);
+        assert_eq!(response.comments[0].timestamp, Some(1_700_000_000));
+        assert_eq!(response.comments[1].id, "c3");
+        assert_eq!(response.comments[1].parent.as_deref(), Some("c1"));
+        assert_eq!(response.comments[1].like_count, None);
+    }
+
+    #[test]
+    fn related_entries_from_info_skips_source_video() {
+        let json = serde_json::json!({
+            "id": "src",
+            "related_videos": [
+                {"id": "src", "title": "Source", "url": "https://example.com/v/src"},
+                {"id": "a", "title": "First", "url": "https://example.com/v/a"},
+                {"id": "b", "title": "Second", "url": "https://example.com/v/b"}
+            ]
+        });
+
+        let entries = related_entries_from_info(&json, Some("src"), 1);
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, "a");
+        assert_eq!(entries[0].url, "https://example.com/v/a");
+    }
+
+    #[test]
+    fn channel_uploads_url_targets_youtube_videos_tab() {
+        let youtube = serde_json::json!({"channel_url": "https://www.youtube.com/channel/UC123/"});
+        let other = serde_json::json!({"uploader_url": "https://vimeo.com/someone"});
+
+        assert_eq!(
+            channel_uploads_url(&youtube).as_deref(),
+            Some("https://www.youtube.com/channel/UC123/videos")
+        );
+        assert_eq!(
+            channel_uploads_url(&other).as_deref(),
+            Some("https://vimeo.com/someone")
+        );
+        assert_eq!(channel_uploads_url(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn parse_available_subtitles_reads_manual_and_automatic_tracks() {
+        let json = serde_json::json!({
+            "subtitles": {
+                "en": [
+                    {"ext": "vtt", "url": "https://example.com/en.vtt", "name": "English (US)"},
+                    {"ext": "srv3", "url": "https://example.com/en.srv3"}
+                ],
+                "live_chat": [{"ext": "json", "url": "https://example.com/chat"}]
+            },
+            "automatic_captions": {
+                "af": [{"ext": "vtt", "url": "https://example.com/af.vtt", "name": "Afrikaans"}],
+                "en": [{"ext": "vtt", "url": "https://example.com/en-auto.vtt"}]
+            }
+        });
+
+        let subtitles = parse_available_subtitles(&json);
+        let summary: Vec<(&str, &str, bool, usize)> = subtitles
+            .iter()
+            .map(|s| (s.lang.as_str(), s.name.as_str(), s.is_auto, s.formats.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("en", "English", false, 2),
+                ("af", "Afrikaans", true, 1),
+                ("en", "English", true, 1),
+            ]
+        );
+        assert_eq!(
+            subtitles[0].formats[0].url.as_deref(),
+            Some("https://example.com/en.vtt")
+        );
+        assert!(parse_available_subtitles(&serde_json::json!({})).is_empty());
+    }
 }
@@ -1,12 +1,67 @@
 use crate::database::add_log_internal;
 use crate::services::{
-    extract_audio_for_whisper, get_ffmpeg_path, run_ytdlp_with_stderr_and_cookies,
-    transcribe_audio, WhisperError, WhisperResponseFormat,
+    available_whisper_backends, benchmark_whisper_backends, default_whisper_model,
+    delete_whisper_model, download_whisper_model, extract_audio_for_whisper, get_ffmpeg_path,
+    get_whisper_backend_config, installed_whisper_model_path, local_whisper_enabled,
+    run_ytdlp_with_stderr_and_cookies, set_whisper_backend_config, set_whisper_model_defaults,
+    transcribe_audio, whisper_model_status, WhisperBackend, WhisperBackendConfig,
+    WhisperBenchmarkReport, WhisperError, WhisperModelStatus, WhisperResponseFormat,
 };
-use std::path::Path;
+use crate::services::{
+    cache_transcript, export_transcript, label_speaker_turns, transcribe_audio_segments,
+    Transcript, TranscriptExportFormat,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 use uuid::Uuid;
 
+/// Audio file to upload for `video_path`: extracted into a temp dir for
+/// video files, the file itself for audio
+async fn prepare_whisper_audio(app: &AppHandle, video_path: &str) -> Result<String, String> {
+    let path = Path::new(video_path);
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let audio_path = if ["mp4", "mkv", "webm", "avi", "mov", "flv"].contains(&extension.as_str()) {
+        // Need to extract audio first
+        add_log_internal(
+            "info",
+            "Extracting audio from video for Whisper...",
+            None,
+            None,
+        )
+        .ok();
+
+        let temp_dir = std::env::temp_dir().join(format!("youwee_whisper_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir)
+            .map_err(|e| format!("Failed to create temp dir: {}", e))?;
+
+        let audio_output = temp_dir.join("audio.mp3");
+        let audio_output_str = audio_output.to_string_lossy().to_string();
+
+        // Get FFmpeg path
+        let ffmpeg_path = get_ffmpeg_path(app).await;
+        let ffmpeg_path_str = ffmpeg_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string());
+
+        extract_audio_for_whisper(video_path, &audio_output_str, ffmpeg_path_str.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        audio_output_str
+    } else {
+        // Already an audio file
+        video_path.to_string()
+    };
+    Ok(audio_path)
+}
+
 /// Transcribe a local video/audio file using OpenAI Whisper API
 ///
 /// This command:
@@ -31,7 +86,7 @@ pub async fn transcribe_video_with_whisper(
     )
     .ok();
 
-    if openai_api_key.is_empty() {
+    if openai_api_key.is_empty() && !local_whisper_enabled() {
         return Err(WhisperError::NoApiKey.into());
     }
 
@@ -49,50 +104,13 @@ pub async fn transcribe_video_with_whisper(
         _ => WhisperResponseFormat::Text,
     };
 
-    // Check if we need to extract audio (video files)
-    let extension = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    let audio_path = if ["mp4", "mkv", "webm", "avi", "mov", "flv"].contains(&extension.as_str()) {
-        // Need to extract audio first
-        add_log_internal(
-            "info",
-            "Extracting audio from video for Whisper...",
-            None,
-            None,
-        )
-        .ok();
-
-        let temp_dir = std::env::temp_dir().join(format!("youwee_whisper_{}", Uuid::new_v4()));
-        std::fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp dir: {}", e))?;
-
-        let audio_output = temp_dir.join("audio.mp3");
-        let audio_output_str = audio_output.to_string_lossy().to_string();
-
-        // Get FFmpeg path
-        let ffmpeg_path = get_ffmpeg_path(&app).await;
-        let ffmpeg_path_str = ffmpeg_path
-            .as_ref()
-            .map(|p| p.to_string_lossy().to_string());
-
-        extract_audio_for_whisper(&video_path, &audio_output_str, ffmpeg_path_str.as_deref())
-            .await
-            .map_err(|e| e.to_string())?;
-
-        audio_output_str
-    } else {
-        // Already an audio file
-        video_path.clone()
-    };
+    let audio_path = prepare_whisper_audio(&app, &video_path).await?;
 
     // Transcribe with Whisper
-    add_log_internal("info", "Sending audio to Whisper API...", None, None).ok();
+    add_log_internal("info", "Transcribing audio with Whisper...", None, None).ok();
 
     let result = transcribe_audio(
+        &app,
         &openai_api_key,
         &audio_path,
         format,
@@ -157,7 +175,7 @@ pub async fn transcribe_url_with_whisper(
     )
     .ok();
 
-    if openai_api_key.is_empty() {
+    if openai_api_key.is_empty() && !local_whisper_enabled() {
         return Err(WhisperError::NoApiKey.into());
     }
 
@@ -281,9 +299,16 @@ pub async fn transcribe_url_with_whisper(
     }
 
     // Transcribe with Whisper
-    add_log_internal("info", "Sending audio to Whisper API...", None, Some(&url)).ok();
+    add_log_internal(
+        "info",
+        "Transcribing audio with Whisper...",
+        None,
+        Some(&url),
+    )
+    .ok();
 
     let whisper_result = transcribe_audio(
+        &app,
         &openai_api_key,
         &audio_file,
         format,
@@ -360,3 +385,179 @@ pub async fn generate_subtitles_with_whisper(
 
     Ok(output_str)
 }
+
+/// Local Whisper models with install state and disk usage
+#[tauri::command]
+pub async fn list_whisper_models(app: AppHandle) -> Result<WhisperModelStatus, String> {
+    whisper_model_status(&app)
+}
+
+/// Download a local Whisper model; progress is sent as `whisper-model-download-progress`
+#[tauri::command]
+pub async fn download_whisper_model_cmd(
+    app: AppHandle,
+    model_id: String,
+) -> Result<String, String> {
+    let path = download_whisper_model(&app, &model_id).await?;
+    add_log_internal(
+        "info",
+        &format!("Downloaded Whisper model: {}", model_id),
+        None,
+        None,
+    )
+    .ok();
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Delete a local Whisper model; returns the bytes freed
+#[tauri::command]
+pub async fn delete_whisper_model_cmd(app: AppHandle, model_id: String) -> Result<u64, String> {
+    delete_whisper_model(&app, &model_id)
+}
+
+/// Sync the default model per language code ("*" for any other language)
+#[tauri::command]
+pub async fn set_whisper_model_defaults_cmd(
+    defaults: BTreeMap<String, String>,
+) -> Result<(), String> {
+    set_whisper_model_defaults(defaults)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperBackendOptions {
+    pub available: Vec<WhisperBackend>,
+    pub config: WhisperBackendConfig,
+}
+
+/// Backends this machine supports and the one local transcription uses
+#[tauri::command]
+pub async fn get_whisper_backends() -> Result<WhisperBackendOptions, String> {
+    Ok(WhisperBackendOptions {
+        available: available_whisper_backends(),
+        config: get_whisper_backend_config(),
+    })
+}
+
+#[tauri::command]
+pub async fn set_whisper_backend(config: WhisperBackendConfig) -> Result<(), String> {
+    set_whisper_backend_config(config)
+}
+
+/// Time a 10s sample on each available backend. Uses `model_id`, else the
+/// default model, else the smallest installed one.
+#[tauri::command]
+pub async fn benchmark_whisper(
+    app: AppHandle,
+    model_id: Option<String>,
+) -> Result<WhisperBenchmarkReport, String> {
+    let model_id = model_id
+        .or_else(|| default_whisper_model(None))
+        .filter(|id| installed_whisper_model_path(&app, id).is_some())
+        .or_else(|| {
+            whisper_model_status(&app)
+                .ok()?
+                .models
+                .into_iter()
+                .find(|model| model.installed)
+                .map(|model| model.id)
+        })
+        .ok_or("No Whisper model installed. Download one to run the benchmark.")?;
+    let model = installed_whisper_model_path(&app, &model_id)
+        .ok_or_else(|| format!("Whisper model '{}' is not installed", model_id))?;
+    benchmark_whisper_backends(&app, &model_id, &model).await
+}
+
+/// Whisper segments for a local file, cached by path for later reuse
+pub(crate) async fn transcribe_file_segments(
+    app: &AppHandle,
+    video_path: &str,
+    api_key: &str,
+    language: Option<&str>,
+    endpoint_url: Option<&str>,
+    model: Option<&str>,
+    word_timestamps: bool,
+) -> Result<Transcript, String> {
+    let audio_path = prepare_whisper_audio(app, video_path).await?;
+    let result = transcribe_audio_segments(
+        app,
+        api_key,
+        &audio_path,
+        language,
+        endpoint_url,
+        model,
+        word_timestamps,
+    )
+    .await;
+    if audio_path != video_path {
+        if let Some(parent) = Path::new(&audio_path).parent() {
+            std::fs::remove_dir_all(parent).ok();
+        }
+    }
+    let transcript = result.map_err(|e| e.to_string())?;
+    cache_transcript(app, video_path, &transcript);
+    Ok(transcript)
+}
+
+/// Transcribe a local file into timed segments. `speakers` (2 for an
+/// interview) labels speaker turns at long pauses; unset leaves them blank.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn transcribe_video_segments(
+    app: AppHandle,
+    video_path: String,
+    openai_api_key: String,
+    language: Option<String>,
+    whisper_endpoint_url: Option<String>,
+    whisper_model: Option<String>,
+    word_timestamps: Option<bool>,
+    speakers: Option<u32>,
+) -> Result<Transcript, String> {
+    if openai_api_key.is_empty() && !local_whisper_enabled() {
+        return Err(WhisperError::NoApiKey.into());
+    }
+    if !Path::new(&video_path).exists() {
+        return Err(WhisperError::FileNotFound(video_path.clone()).into());
+    }
+
+    let mut transcript = transcribe_file_segments(
+        &app,
+        &video_path,
+        &openai_api_key,
+        language.as_deref(),
+        whisper_endpoint_url.as_deref(),
+        whisper_model.as_deref(),
+        word_timestamps.unwrap_or(false),
+    )
+    .await?;
+    if let Some(speakers) = speakers {
+        label_speaker_turns(&mut transcript.segments, speakers);
+    }
+    add_log_internal(
+        "success",
+        &format!(
+            "Whisper transcription complete ({} segments)",
+            transcript.segments.len()
+        ),
+        None,
+        None,
+    )
+    .ok();
+    Ok(transcript)
+}
+
+/// Write a transcript as SRT, VTT or JSON; returns the saved path
+#[tauri::command]
+pub async fn export_transcript_file(
+    transcript: Transcript,
+    format: TranscriptExportFormat,
+    output_path: String,
+) -> Result<String, String> {
+    let output = PathBuf::from(output_path.trim());
+    if output.as_os_str().is_empty() || output.is_dir() {
+        return Err(format!("Invalid destination: {}", output.display()));
+    }
+    let content = export_transcript(&transcript, format)?;
+    std::fs::write(&output, content).map_err(|e| format!("Failed to save transcript: {}", e))?;
+    Ok(output.to_string_lossy().to_string())
+}
@@ -0,0 +1,144 @@
+use super::get_db;
+use crate::types::{AIUsageBucket, AIUsageEntry};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+pub(super) fn create_ai_usage_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS ai_usage (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            day TEXT NOT NULL,
+            feature TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            prompt_tokens INTEGER NOT NULL,
+            completion_tokens INTEGER NOT NULL,
+            cost_usd REAL,
+            success INTEGER NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_ai_usage_day ON ai_usage(day);",
+    )
+    .map_err(|e| format!("Failed to create ai_usage table: {}", e))
+}
+
+/// Record a request under `day` (local "YYYY-MM-DD")
+pub fn insert_ai_usage(day: &str, entry: &AIUsageEntry) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO ai_usage (day, feature, provider, model, prompt_tokens,
+            completion_tokens, cost_usd, success, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            day,
+            entry.feature,
+            entry.provider,
+            entry.model,
+            entry.prompt_tokens as i64,
+            entry.completion_tokens as i64,
+            entry.cost_usd,
+            entry.success,
+            Utc::now().timestamp(),
+        ],
+    )
+    .map_err(|e| format!("Failed to record AI usage: {}", e))?;
+    Ok(())
+}
+
+/// Per-day, per-feature totals from `since` on (all time when `None`)
+pub fn ai_usage_buckets(since: Option<&str>) -> Result<Vec<AIUsageBucket>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT day, feature, COUNT(*), SUM(1 - success), SUM(prompt_tokens),
+                SUM(completion_tokens), COALESCE(SUM(cost_usd), 0), SUM(cost_usd IS NULL)
+             FROM ai_usage WHERE ?1 IS NULL OR day >= ?1
+             GROUP BY day, feature ORDER BY day, feature",
+        )
+        .map_err(|e| format!("Failed to load AI usage: {}", e))?;
+    let buckets = stmt
+        .query_map(params![since], |row| {
+            Ok(AIUsageBucket {
+                day: row.get(0)?,
+                feature: row.get(1)?,
+                requests: row.get::<_, i64>(2)? as u64,
+                failures: row.get::<_, i64>(3)? as u64,
+                prompt_tokens: row.get::<_, i64>(4)? as u64,
+                completion_tokens: row.get::<_, i64>(5)? as u64,
+                cost_usd: row.get(6)?,
+                unpriced_requests: row.get::<_, i64>(7)? as u64,
+            })
+        })
+        .map_err(|e| format!("Failed to load AI usage: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to load AI usage: {}", e))?;
+    Ok(buckets)
+}
+
+/// Estimated spend from `since` (inclusive) on
+pub fn ai_cost_since(since: &str) -> Result<f64, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT COALESCE(SUM(cost_usd), 0) FROM ai_usage WHERE day >= ?1",
+        params![since],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to load AI usage: {}", e))
+}
+
+pub fn clear_ai_usage_from_db() -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM ai_usage", [])
+        .map_err(|e| format!("Failed to clear AI usage: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn entry(feature: &str, cost_usd: Option<f64>, success: bool) -> AIUsageEntry {
+        AIUsageEntry {
+            feature: feature.to_string(),
+            provider: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            prompt_tokens: 1000,
+            completion_tokens: 200,
+            cost_usd,
+            success,
+        }
+    }
+
+    #[test]
+    fn usage_is_grouped_by_day_and_feature() {
+        let _guard = db_test_guard();
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+        {
+            let conn = get_db().expect("get db");
+            create_ai_usage_table(&conn).expect("create ai_usage");
+            conn.execute("DELETE FROM ai_usage", []).unwrap();
+        }
+
+        insert_ai_usage("2026-09-30", &entry("summary", Some(0.5), true)).unwrap();
+        insert_ai_usage("2026-10-01", &entry("summary", Some(0.25), true)).unwrap();
+        insert_ai_usage("2026-10-01", &entry("summary", None, false)).unwrap();
+        insert_ai_usage("2026-10-01", &entry("chapters", Some(0.1), true)).unwrap();
+
+        let buckets = ai_usage_buckets(Some("2026-10-01")).unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[1].feature, "summary");
+        assert_eq!((buckets[1].requests, buckets[1].failures), (2, 1));
+        assert_eq!(buckets[1].unpriced_requests, 1);
+        assert_eq!(buckets[1].prompt_tokens, 2000);
+        assert!((ai_cost_since("2026-10-01").unwrap() - 0.35).abs() < 1e-9);
+        assert_eq!(ai_usage_buckets(None).unwrap().len(), 3);
+
+        clear_ai_usage_from_db().unwrap();
+        assert!(ai_usage_buckets(None).unwrap().is_empty());
+    }
+}
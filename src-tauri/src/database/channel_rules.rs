@@ -0,0 +1,246 @@
+use super::get_db;
+use crate::types::{ChannelRule, DownloadProfileSettings};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+const RULE_COLUMNS: &str =
+    "id, extractor_key, channel_id, channel_name, settings_json, created_at, updated_at";
+
+pub(super) fn create_channel_rules_table(conn: &Connection) -> Result<(), String> {
+    // Migration: Rules were unique per channel id alone, which two sites can share
+    let has_extractor_key = conn
+        .prepare("SELECT extractor_key FROM channel_rules LIMIT 0")
+        .is_ok();
+    let has_table = conn.prepare("SELECT id FROM channel_rules LIMIT 0").is_ok();
+    if has_table && !has_extractor_key {
+        conn.execute("ALTER TABLE channel_rules RENAME TO channel_rules_old", [])
+            .map_err(|e| format!("Failed to migrate channel_rules table: {}", e))?;
+    }
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS channel_rules (
+            id TEXT PRIMARY KEY,
+            extractor_key TEXT NOT NULL DEFAULT '',
+            channel_id TEXT NOT NULL,
+            channel_name TEXT NOT NULL,
+            settings_json TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            UNIQUE(extractor_key, channel_id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create channel_rules table: {}", e))?;
+    if has_table && !has_extractor_key {
+        conn.execute_batch(
+            "INSERT INTO channel_rules (id, channel_id, channel_name, settings_json, created_at, updated_at)
+             SELECT id, channel_id, channel_name, settings_json, created_at, updated_at
+             FROM channel_rules_old;
+             DROP TABLE channel_rules_old;",
+        )
+        .map_err(|e| format!("Failed to migrate channel_rules table: {}", e))?;
+    }
+    Ok(())
+}
+
+fn rule_from_row(row: &Row) -> rusqlite::Result<ChannelRule> {
+    let settings_json: String = row.get(4)?;
+    Ok(ChannelRule {
+        id: row.get(0)?,
+        extractor_key: row.get(1)?,
+        channel_id: row.get(2)?,
+        channel_name: row.get(3)?,
+        settings: serde_json::from_str(&settings_json).unwrap_or_default(),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+pub fn list_channel_rules_from_db() -> Result<Vec<ChannelRule>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM channel_rules ORDER BY channel_name COLLATE NOCASE",
+            RULE_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to load channel rules: {}", e))?;
+    let rules = stmt
+        .query_map([], rule_from_row)
+        .map_err(|e| format!("Failed to load channel rules: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to load channel rules: {}", e))?;
+    Ok(rules)
+}
+
+/// Rule for a yt-dlp channel or uploader id on the site of `extractor_key`.
+/// Rules saved before the site was recorded have an empty key and match any site.
+pub fn get_channel_rule_from_db(
+    extractor_key: &str,
+    channel_id: &str,
+) -> Result<Option<ChannelRule>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM channel_rules
+             WHERE channel_id = ?1 AND extractor_key IN (?2, '')
+             ORDER BY extractor_key DESC LIMIT 1",
+            RULE_COLUMNS
+        ),
+        params![channel_id.trim(), extractor_key.trim()],
+        rule_from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load channel rule: {}", e))
+}
+
+/// Create the rule for `channel_id` on the site of `extractor_key`, or replace
+/// the settings of its existing one
+pub fn save_channel_rule_to_db(
+    extractor_key: &str,
+    channel_id: &str,
+    channel_name: &str,
+    settings: &DownloadProfileSettings,
+) -> Result<ChannelRule, String> {
+    let extractor_key = extractor_key.trim();
+    let channel_id = channel_id.trim();
+    if channel_id.is_empty() {
+        return Err("Channel id cannot be empty".to_string());
+    }
+    let channel_name = match channel_name.trim() {
+        "" => channel_id,
+        name => name,
+    };
+    let settings_json = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    let now = Utc::now().timestamp();
+    {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO channel_rules
+                (id, extractor_key, channel_id, channel_name, settings_json, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+             ON CONFLICT(extractor_key, channel_id) DO UPDATE SET
+                channel_name = excluded.channel_name,
+                settings_json = excluded.settings_json,
+                updated_at = excluded.updated_at",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                extractor_key,
+                channel_id,
+                channel_name,
+                settings_json,
+                now
+            ],
+        )
+        .map_err(|e| format!("Failed to save channel rule: {}", e))?;
+    }
+    get_channel_rule_from_db(extractor_key, channel_id)?
+        .ok_or_else(|| "Channel rule not found".to_string())
+}
+
+pub fn delete_channel_rule_from_db(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM channel_rules WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete channel rule: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    #[test]
+    fn channel_rules_are_unique_per_site_and_channel() {
+        let _guard = db_test_guard();
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+        {
+            let conn = get_db().expect("get db");
+            create_channel_rules_table(&conn).expect("create channel rules");
+            conn.execute("DELETE FROM channel_rules", []).unwrap();
+        }
+
+        let podcast = DownloadProfileSettings {
+            quality: Some("audio".to_string()),
+            format: Some("mp3".to_string()),
+            output_path: Some("/music/Podcasts".to_string()),
+            ..Default::default()
+        };
+        let rule = save_channel_rule_to_db("Youtube", " UC123 ", "", &podcast).unwrap();
+        assert_eq!(rule.extractor_key, "Youtube");
+        assert_eq!(rule.channel_id, "UC123");
+        assert_eq!(rule.channel_name, "UC123");
+        assert!(save_channel_rule_to_db("Youtube", " ", "Nobody", &podcast).is_err());
+
+        let video = DownloadProfileSettings {
+            quality: Some("1080".to_string()),
+            ..Default::default()
+        };
+        let updated = save_channel_rule_to_db("Youtube", "UC123", "Daily Podcast", &video).unwrap();
+        assert_eq!(updated.id, rule.id);
+        assert_eq!(updated.channel_name, "Daily Podcast");
+        assert_eq!(updated.settings, video);
+        assert_eq!(list_channel_rules_from_db().unwrap(), vec![updated]);
+
+        // The same id on another site is another channel
+        assert!(get_channel_rule_from_db("Vimeo", "UC123")
+            .unwrap()
+            .is_none());
+        let other = save_channel_rule_to_db("Vimeo", "UC123", "Artist", &podcast).unwrap();
+        assert_ne!(other.id, rule.id);
+        assert_eq!(
+            get_channel_rule_from_db("Youtube", "UC123")
+                .unwrap()
+                .unwrap()
+                .settings,
+            video
+        );
+
+        delete_channel_rule_from_db(&rule.id).unwrap();
+        delete_channel_rule_from_db(&other.id).unwrap();
+        assert!(get_channel_rule_from_db("Youtube", "UC123")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn channel_rules_saved_without_a_site_keep_applying() {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        conn.execute(
+            "CREATE TABLE channel_rules (
+                id TEXT PRIMARY KEY,
+                channel_id TEXT NOT NULL UNIQUE,
+                channel_name TEXT NOT NULL,
+                settings_json TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO channel_rules VALUES ('r1', 'UC123', 'Podcast', '{}', 1, 1)",
+            [],
+        )
+        .unwrap();
+
+        create_channel_rules_table(&conn).expect("migrate channel rules");
+        create_channel_rules_table(&conn).expect("migration runs once");
+        let (extractor_key, channel_id): (String, String) = conn
+            .query_row(
+                "SELECT extractor_key, channel_id FROM channel_rules WHERE id = 'r1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((extractor_key.as_str(), channel_id.as_str()), ("", "UC123"));
+        conn.execute(
+            "INSERT INTO channel_rules (id, extractor_key, channel_id, channel_name, settings_json, created_at, updated_at)
+             VALUES ('r2', 'Vimeo', 'UC123', 'Artist', '{}', 2, 2)",
+            [],
+        )
+        .expect("same channel id on another site");
+    }
+}
@@ -63,7 +63,7 @@ pub fn get_followed_channels_db() -> Result<Vec<FollowedChannel>, String> {
                     filter_min_duration, filter_max_duration, filter_include_keywords,
                     filter_exclude_keywords, filter_max_videos, download_threads,
                     download_video_codec, download_audio_bitrate, download_preferred_fps,
-                    youtube_content_type
+                    youtube_content_type, filter_skip_shorts
              FROM followed_channels ORDER BY created_at DESC",
         )
         .map_err(|e| format!("Failed to prepare query: {}", e))?;
@@ -93,6 +93,7 @@ pub fn get_followed_channels_db() -> Result<Vec<FollowedChannel>, String> {
                 download_audio_bitrate: row.get(19)?,
                 download_preferred_fps: row.get(20)?,
                 youtube_content_type: row.get(21)?,
+                filter_skip_shorts: row.get::<_, i64>(22)? != 0,
             })
         })
         .map_err(|e| format!("Query failed: {}", e))?
@@ -111,7 +112,7 @@ pub fn get_followed_channel_db(id: String) -> Result<FollowedChannel, String> {
                 filter_min_duration, filter_max_duration, filter_include_keywords,
                 filter_exclude_keywords, filter_max_videos, download_threads,
                 download_video_codec, download_audio_bitrate, download_preferred_fps,
-                youtube_content_type
+                youtube_content_type, filter_skip_shorts
          FROM followed_channels WHERE id = ?1",
         params![id],
         |row| {
@@ -138,6 +139,7 @@ pub fn get_followed_channel_db(id: String) -> Result<FollowedChannel, String> {
                 download_audio_bitrate: row.get(19)?,
                 download_preferred_fps: row.get(20)?,
                 youtube_content_type: row.get(21)?,
+                filter_skip_shorts: row.get::<_, i64>(22)? != 0,
             })
         },
     )
@@ -161,6 +163,7 @@ pub fn update_channel_settings_db(
     filter_max_videos: Option<i64>,
     download_threads: i64,
     youtube_content_type: String,
+    filter_skip_shorts: bool,
 ) -> Result<(), String> {
     let conn = get_db()?;
     conn.execute(
@@ -169,8 +172,8 @@ pub fn update_channel_settings_db(
             download_format = ?4, download_video_codec = ?5, download_audio_bitrate = ?6,
             download_preferred_fps = ?7, filter_min_duration = ?8, filter_max_duration = ?9,
             filter_include_keywords = ?10, filter_exclude_keywords = ?11, filter_max_videos = ?12,
-            download_threads = ?13, youtube_content_type = ?14
-         WHERE id = ?15",
+            download_threads = ?13, youtube_content_type = ?14, filter_skip_shorts = ?15
+         WHERE id = ?16",
         params![
             check_interval,
             auto_download as i64,
@@ -186,6 +189,7 @@ pub fn update_channel_settings_db(
             filter_max_videos,
             download_threads,
             youtube_content_type,
+            filter_skip_shorts as i64,
             id,
         ],
     )
@@ -522,7 +526,8 @@ mod tests {
                 download_video_codec TEXT NOT NULL DEFAULT 'h264',
                 download_audio_bitrate TEXT NOT NULL DEFAULT '192',
                 download_preferred_fps TEXT NOT NULL DEFAULT 'original',
-                youtube_content_type TEXT NOT NULL DEFAULT 'videos'
+                youtube_content_type TEXT NOT NULL DEFAULT 'videos',
+                filter_skip_shorts INTEGER NOT NULL DEFAULT 0
             );
             CREATE TABLE IF NOT EXISTS channel_videos (
                 id TEXT PRIMARY KEY,
@@ -1,10 +1,25 @@
 use rusqlite::{params, Connection};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
+
+use super::ai_usage::create_ai_usage_table;
+use super::channel_rules::create_channel_rules_table;
+use super::download_journal::{create_download_journal_table, mark_download_journal_interrupted};
+use super::download_profiles::create_download_profiles_table;
+use super::download_queue::create_download_queues_table;
+use super::github_cache::create_github_cache_table;
+use super::prompt_templates::create_prompt_templates_table;
+use super::recent_inputs::create_recent_inputs_table;
+use super::recipes::create_download_recipes_table;
+use super::recovery::{check_database_integrity, recover_database, rotate_database_backups};
+use crate::types::{DatabaseRecoveryReport, DATABASE_RECOVERED};
+use crate::utils::data_dir;
 
 // Global database connection wrapped in Mutex for thread safety
 pub static DB_CONNECTION: std::sync::OnceLock<Mutex<Connection>> = std::sync::OnceLock::new();
+// Kept so the frontend can ask after the startup event has already fired
+static DATABASE_RECOVERY: Mutex<Option<DatabaseRecoveryReport>> = Mutex::new(None);
 
 #[cfg(test)]
 static DB_TEST_LOCK: std::sync::OnceLock<Mutex<()>> = std::sync::OnceLock::new();
@@ -14,7 +29,7 @@ const DATABASE_FILE_NAME: &str = "youwee.db";
 const LEGACY_DATABASE_FILE_NAME: &str = "logs.db";
 const MIGRATING_DATABASE_FILE_NAME: &str = "youwee.db.migrating";
 
-fn database_sidecar_path(path: &Path, suffix: &str) -> Option<PathBuf> {
+pub(super) fn database_sidecar_path(path: &Path, suffix: &str) -> Option<PathBuf> {
     let filename = path.file_name()?.to_string_lossy();
     Some(path.with_file_name(format!("{filename}{suffix}")))
 }
@@ -107,8 +122,10 @@ fn rebuild_history_search_index(conn: &Connection) -> Result<(), String> {
     conn.execute("DELETE FROM history_search_fts", [])
         .map_err(|e| format!("Failed to clear history search index: {}", e))?;
     conn.execute(
-        "INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary)
-         SELECT rowid, id, title, filepath, url, COALESCE(summary, '') FROM history",
+        "INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary, notes)
+         SELECT rowid, id, title, filepath, url, COALESCE(summary, ''),
+                COALESCE(notes, '') || ' ' || COALESCE(custom_metadata, '')
+         FROM history",
         [],
     )
     .map_err(|e| format!("Failed to rebuild history search index: {}", e))?;
@@ -116,6 +133,23 @@ fn rebuild_history_search_index(conn: &Connection) -> Result<(), String> {
 }
 
 fn init_history_search_index(conn: &Connection) -> Result<(), String> {
+    // FTS5 tables can't gain columns; recreate indexes built before notes existed
+    let has_notes_column = conn
+        .prepare("SELECT notes FROM history_search_fts LIMIT 0")
+        .is_ok();
+    let has_index = conn
+        .prepare("SELECT rowid FROM history_search_fts LIMIT 0")
+        .is_ok();
+    if has_index && !has_notes_column {
+        conn.execute_batch(
+            "DROP TRIGGER IF EXISTS history_search_insert;
+            DROP TRIGGER IF EXISTS history_search_delete;
+            DROP TRIGGER IF EXISTS history_search_update;
+            DROP TABLE IF EXISTS history_search_fts;",
+        )
+        .map_err(|e| format!("Failed to drop outdated history search index: {}", e))?;
+    }
+
     conn.execute(
         "CREATE VIRTUAL TABLE IF NOT EXISTS history_search_fts USING fts5(
             history_id UNINDEXED,
@@ -123,6 +157,7 @@ fn init_history_search_index(conn: &Connection) -> Result<(), String> {
             filepath,
             url,
             summary,
+            notes,
             tokenize = 'unicode61 remove_diacritics 2'
         )",
         [],
@@ -131,16 +166,16 @@ fn init_history_search_index(conn: &Connection) -> Result<(), String> {
 
     conn.execute_batch(
         "CREATE TRIGGER IF NOT EXISTS history_search_insert AFTER INSERT ON history BEGIN
-            INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary)
-            VALUES (new.rowid, new.id, new.title, new.filepath, new.url, COALESCE(new.summary, ''));
+            INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary, notes)
+            VALUES (new.rowid, new.id, new.title, new.filepath, new.url, COALESCE(new.summary, ''), COALESCE(new.notes, '') || ' ' || COALESCE(new.custom_metadata, ''));
         END;
         CREATE TRIGGER IF NOT EXISTS history_search_delete AFTER DELETE ON history BEGIN
             DELETE FROM history_search_fts WHERE rowid = old.rowid;
         END;
         CREATE TRIGGER IF NOT EXISTS history_search_update AFTER UPDATE ON history BEGIN
             DELETE FROM history_search_fts WHERE rowid = old.rowid;
-            INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary)
-            VALUES (new.rowid, new.id, new.title, new.filepath, new.url, COALESCE(new.summary, ''));
+            INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary, notes)
+            VALUES (new.rowid, new.id, new.title, new.filepath, new.url, COALESCE(new.summary, ''), COALESCE(new.notes, '') || ' ' || COALESCE(new.custom_metadata, ''));
         END;",
     )
     .map_err(|e| format!("Failed to create history search triggers: {}", e))?;
@@ -166,16 +201,32 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
         return Ok(());
     }
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let app_data_dir =
+        data_dir(app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
     std::fs::create_dir_all(&app_data_dir)
         .map_err(|e| format!("Failed to create app data directory: {}", e))?;
 
     let db_path = resolve_database_path(&app_data_dir)?;
 
+    let recovery = match check_database_integrity(&db_path) {
+        Ok(None) => {
+            if let Err(e) = rotate_database_backups(&db_path) {
+                log::warn!("Database backup failed: {}", e);
+            }
+            None
+        }
+        Ok(Some(problems)) => {
+            log::error!("Database failed integrity check, recovering: {}", problems);
+            Some(recover_database(&db_path, problems)?)
+        }
+        // Locked or unreadable is not corrupt; opening below reports real failures
+        Err(e) => {
+            log::warn!("{}", e);
+            None
+        }
+    };
+
     let conn = Connection::open(&db_path).map_err(|e| format!("Failed to open database: {}", e))?;
 
     // Create logs table
@@ -241,6 +292,78 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
         .ok(); // Ignore error if column already exists
     conn.execute("ALTER TABLE history ADD COLUMN canonical_url TEXT", [])
         .ok(); // Ignore error if column already exists
+
+    // Migration: Add integrity verification columns if they don't exist
+    conn.execute("ALTER TABLE history ADD COLUMN sha256 TEXT", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute("ALTER TABLE history ADD COLUMN integrity_status TEXT", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute(
+        "ALTER TABLE history ADD COLUMN integrity_checked_at INTEGER",
+        [],
+    )
+    .ok(); // Ignore error if column already exists
+
+    // Migration: Add media_type column ("video", "audio" or "image") for gallery posts
+    conn.execute("ALTER TABLE history ADD COLUMN media_type TEXT", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: Add user notes and key/value metadata (JSON object)
+    conn.execute("ALTER TABLE history ADD COLUMN notes TEXT", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute("ALTER TABLE history ADD COLUMN custom_metadata TEXT", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: Add playlist_index column for per-item playlist entries
+    conn.execute("ALTER TABLE history ADD COLUMN playlist_index INTEGER", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: Add shareable yt-dlp arguments (JSON array) for reproducing a download
+    conn.execute("ALTER TABLE history ADD COLUMN ytdlp_args TEXT", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: Add generated .lrc lyrics file for music entries
+    conn.execute("ALTER TABLE history ADD COLUMN lyrics_path TEXT", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: Link re-downloads in another quality to their original entry
+    conn.execute("ALTER TABLE history ADD COLUMN variant_of TEXT", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: Flag vertical short-form clips (Shorts, Reels, TikTok)
+    conn.execute(
+        "ALTER TABLE history ADD COLUMN is_short INTEGER NOT NULL DEFAULT 0",
+        [],
+    )
+    .ok(); // Ignore error if column already exists
+
+    // Migration: Add favorite flag for starring library items
+    conn.execute(
+        "ALTER TABLE history ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0",
+        [],
+    )
+    .ok(); // Ignore error if column already exists
+
+    // Migration: Keep failed and cancelled attempts with their error and settings
+    conn.execute(
+        "ALTER TABLE history ADD COLUMN status TEXT NOT NULL DEFAULT 'completed'",
+        [],
+    )
+    .ok(); // Ignore error if column already exists
+    conn.execute("ALTER TABLE history ADD COLUMN error_code TEXT", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute("ALTER TABLE history ADD COLUMN error_message TEXT", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute("ALTER TABLE history ADD COLUMN download_options TEXT", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: How the thumbnail was stored when embedding was requested
+    conn.execute("ALTER TABLE history ADD COLUMN thumbnail_embed TEXT", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: Language of subtitle-only downloads, kept out of quality
+    conn.execute("ALTER TABLE history ADD COLUMN language TEXT", [])
+        .ok(); // Ignore error if column already exists
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_history_media_id ON history(media_id)",
         [],
@@ -369,6 +492,18 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     )
     .map_err(|e| format!("Failed to create processing_jobs table: {}", e))?;
 
+    // Migration: Output file size and duration recorded when a job completes
+    conn.execute(
+        "ALTER TABLE processing_jobs ADD COLUMN output_size INTEGER",
+        [],
+    )
+    .ok(); // Ignore error if column already exists
+    conn.execute(
+        "ALTER TABLE processing_jobs ADD COLUMN output_duration REAL",
+        [],
+    )
+    .ok(); // Ignore error if column already exists
+
     // Create processing_presets table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS processing_presets (
@@ -384,6 +519,9 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     )
     .map_err(|e| format!("Failed to create processing_presets table: {}", e))?;
 
+    // User prompt templates for the processing assistant
+    create_prompt_templates_table(&conn)?;
+
     // Create processing indexes
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_processing_jobs_created ON processing_jobs(created_at DESC)",
@@ -415,7 +553,8 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
             download_video_codec TEXT NOT NULL DEFAULT 'h264',
             download_audio_bitrate TEXT NOT NULL DEFAULT '192',
             download_preferred_fps TEXT NOT NULL DEFAULT 'original',
-            youtube_content_type TEXT NOT NULL DEFAULT 'videos'
+            youtube_content_type TEXT NOT NULL DEFAULT 'videos',
+            filter_skip_shorts INTEGER NOT NULL DEFAULT 0
         )",
         [],
     )
@@ -460,15 +599,42 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     .ok();
 
     // Create persisted download queues table
+    create_download_queues_table(&conn)?;
+
+    // Crash-safe journal of running downloads
+    create_download_journal_table(&conn)?;
+    mark_download_journal_interrupted(&conn);
+
+    // Named download settings ("4K archive", "quick audio", ...)
+    create_download_profiles_table(&conn)?;
+
+    // Download settings plus post-processing stages, applied to URLs as one pipeline
+    create_download_recipes_table(&conn)?;
+
+    // Last pasted URLs and their options, suggested when a URL or channel comes back
+    create_recent_inputs_table(&conn)?;
+
+    // Download settings always used for a channel ("always do this for this channel")
+    create_channel_rules_table(&conn)?;
+
+    // Release lookups revalidated with ETags to spare the GitHub rate limit
+    create_github_cache_table(&conn)?;
+
+    // Tokens and estimated cost of AI requests, for usage reports and the monthly budget
+    create_ai_usage_table(&conn)?;
+
+    // Media already downloaded by other tools, imported from their archives
     conn.execute(
-        "CREATE TABLE IF NOT EXISTS download_queues (
-            queue_kind TEXT PRIMARY KEY,
-            items_json TEXT NOT NULL,
-            updated_at INTEGER NOT NULL
+        "CREATE TABLE IF NOT EXISTS download_archive (
+            media_id TEXT PRIMARY KEY,
+            url TEXT,
+            title TEXT,
+            imported_from TEXT NOT NULL,
+            created_at INTEGER NOT NULL
         )",
         [],
     )
-    .map_err(|e| format!("Failed to create download_queues table: {}", e))?;
+    .map_err(|e| format!("Failed to create download_archive table: {}", e))?;
 
     // Migration: Add download_threads column if it doesn't exist
     conn.execute(
@@ -505,13 +671,41 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     )
     .ok();
 
+    // Migration: Add the skip-Shorts/Reels auto-download filter
+    conn.execute(
+        "ALTER TABLE followed_channels ADD COLUMN filter_skip_shorts INTEGER NOT NULL DEFAULT 0",
+        [],
+    )
+    .ok();
+
     DB_CONNECTION
         .set(Mutex::new(conn))
         .map_err(|_| "Database already initialized".to_string())?;
 
+    if let Some(report) = recovery {
+        log::warn!(
+            "Database recovered: {} row(s) salvaged, {} lost, corrupt copy at {}",
+            report.rows_recovered,
+            report.rows_lost,
+            report.corrupt_path
+        );
+        DATABASE_RECOVERED.emit(app, &report).ok();
+        if let Ok(mut guard) = DATABASE_RECOVERY.lock() {
+            *guard = Some(report);
+        }
+    }
+
     Ok(())
 }
 
+/// Recovery performed at startup, if the database was found corrupt
+pub fn get_database_recovery_report() -> Option<DatabaseRecoveryReport> {
+    DATABASE_RECOVERY
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+}
+
 /// Get database connection
 pub fn get_db() -> Result<std::sync::MutexGuard<'static, Connection>, String> {
     DB_CONNECTION
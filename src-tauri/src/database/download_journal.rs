@@ -0,0 +1,152 @@
+use super::get_db;
+use crate::types::DownloadJournalEntry;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+pub(super) fn create_download_journal_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_journal (
+            id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            output_path TEXT NOT NULL,
+            output_template TEXT NOT NULL,
+            args_json TEXT NOT NULL,
+            title TEXT,
+            stage TEXT,
+            percent REAL NOT NULL DEFAULT 0,
+            downloaded_size TEXT,
+            started_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            interrupted INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_journal table: {}", e))?;
+    Ok(())
+}
+
+/// Rows still present at startup belong to downloads a previous run never finished
+pub(super) fn mark_download_journal_interrupted(conn: &Connection) {
+    conn.execute("UPDATE download_journal SET interrupted = 1", [])
+        .ok();
+}
+
+pub fn save_download_journal_entry(entry: &DownloadJournalEntry) -> Result<(), String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp();
+    let args_json = serde_json::to_string(&entry.args).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO download_journal (id, url, output_path, output_template, args_json, title, stage, percent, downloaded_size, started_at, updated_at, interrupted)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10, 0)",
+        params![
+            entry.id,
+            entry.url,
+            entry.output_path,
+            entry.output_template,
+            args_json,
+            entry.title,
+            entry.stage,
+            entry.percent,
+            entry.downloaded_size,
+            now
+        ],
+    )
+    .map_err(|e| format!("Failed to save download journal: {}", e))?;
+    Ok(())
+}
+
+pub fn update_download_journal_progress(
+    id: &str,
+    title: Option<&str>,
+    stage: Option<&str>,
+    percent: f64,
+    downloaded_size: Option<&str>,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE download_journal SET title = COALESCE(?1, title), stage = COALESCE(?2, stage), percent = ?3, downloaded_size = COALESCE(?4, downloaded_size), updated_at = ?5 WHERE id = ?6",
+        params![title, stage, percent, downloaded_size, Utc::now().timestamp(), id],
+    )
+    .map_err(|e| format!("Failed to update download journal: {}", e))?;
+    Ok(())
+}
+
+pub fn remove_download_journal_entry(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM download_journal WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to update download journal: {}", e))?;
+    Ok(())
+}
+
+pub fn load_download_journal(interrupted_only: bool) -> Result<Vec<DownloadJournalEntry>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, url, output_path, output_template, args_json, title, stage, percent, downloaded_size, started_at, updated_at, interrupted
+             FROM download_journal WHERE interrupted = 1 OR ?1 = 0 ORDER BY started_at",
+        )
+        .map_err(|e| format!("Failed to read download journal: {}", e))?;
+    let rows = stmt
+        .query_map(params![interrupted_only], |row| {
+            let args_json: String = row.get(4)?;
+            Ok(DownloadJournalEntry {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                output_path: row.get(2)?,
+                output_template: row.get(3)?,
+                args: serde_json::from_str(&args_json).unwrap_or_default(),
+                title: row.get(5)?,
+                stage: row.get(6)?,
+                percent: row.get(7)?,
+                downloaded_size: row.get(8)?,
+                started_at: row.get(9)?,
+                updated_at: row.get(10)?,
+                interrupted: row.get::<_, i64>(11)? != 0,
+            })
+        })
+        .map_err(|e| format!("Failed to read download journal: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read download journal: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    #[test]
+    fn journal_rows_from_a_previous_run_are_reported_as_interrupted() {
+        let _guard = db_test_guard();
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+        {
+            let conn = get_db().expect("get db");
+            create_download_journal_table(&conn).expect("create journal");
+            conn.execute("DELETE FROM download_journal", []).unwrap();
+        }
+
+        let entry = DownloadJournalEntry {
+            id: "job-1".to_string(),
+            url: "https://youtu.be/x".to_string(),
+            output_path: "/tmp".to_string(),
+            args: vec!["--newline".to_string()],
+            ..Default::default()
+        };
+        save_download_journal_entry(&entry).unwrap();
+        update_download_journal_progress("job-1", Some("Video"), Some("merging"), 100.0, None)
+            .unwrap();
+        assert!(load_download_journal(true).unwrap().is_empty());
+
+        mark_download_journal_interrupted(&get_db().unwrap());
+        let interrupted = load_download_journal(true).unwrap();
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].stage.as_deref(), Some("merging"));
+        assert_eq!(interrupted[0].args, vec!["--newline".to_string()]);
+
+        remove_download_journal_entry("job-1").unwrap();
+        assert!(load_download_journal(false).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+use super::get_db;
+use crate::types::{DownloadProfile, DownloadProfileSettings};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+pub(super) fn create_download_profiles_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_profiles (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            settings_json TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_profiles table: {}", e))?;
+    Ok(())
+}
+
+fn profile_from_row(row: &Row) -> rusqlite::Result<DownloadProfile> {
+    let settings_json: String = row.get(2)?;
+    Ok(DownloadProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        settings: serde_json::from_str(&settings_json).unwrap_or_default(),
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+fn validate_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+pub fn list_download_profiles_from_db() -> Result<Vec<DownloadProfile>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, settings_json, created_at, updated_at
+             FROM download_profiles ORDER BY name COLLATE NOCASE",
+        )
+        .map_err(|e| format!("Failed to load download profiles: {}", e))?;
+    let profiles = stmt
+        .query_map([], profile_from_row)
+        .map_err(|e| format!("Failed to load download profiles: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to load download profiles: {}", e))?;
+    Ok(profiles)
+}
+
+pub fn get_download_profile_from_db(id: &str) -> Result<Option<DownloadProfile>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT id, name, settings_json, created_at, updated_at
+         FROM download_profiles WHERE id = ?1",
+        params![id],
+        profile_from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load download profile: {}", e))
+}
+
+/// Create a profile, or update it when `id` names an existing one
+pub fn save_download_profile_to_db(
+    id: Option<String>,
+    name: &str,
+    settings: &DownloadProfileSettings,
+) -> Result<DownloadProfile, String> {
+    let name = validate_profile_name(name)?;
+    let settings_json = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let now = Utc::now().timestamp();
+    {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO download_profiles (id, name, settings_json, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                settings_json = excluded.settings_json,
+                updated_at = excluded.updated_at",
+            params![id, name, settings_json, now],
+        )
+        .map_err(|e| format!("Failed to save download profile: {}", e))?;
+    }
+    get_download_profile_from_db(&id)?.ok_or_else(|| "Download profile not found".to_string())
+}
+
+pub fn delete_download_profile_from_db(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM download_profiles WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete download profile: {}", e))?;
+    Ok(())
+}
+
+/// Copy a profile under `name`, or "<name> (copy)"
+pub fn duplicate_download_profile_in_db(
+    id: &str,
+    name: Option<&str>,
+) -> Result<DownloadProfile, String> {
+    let source = get_download_profile_from_db(id)?
+        .ok_or_else(|| "Download profile not found".to_string())?;
+    let name = name
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} (copy)", source.name));
+    save_download_profile_to_db(None, &name, &source.settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    #[test]
+    fn profiles_can_be_saved_updated_duplicated_and_deleted() {
+        let _guard = db_test_guard();
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+        {
+            let conn = get_db().expect("get db");
+            create_download_profiles_table(&conn).expect("create profiles");
+            conn.execute("DELETE FROM download_profiles", []).unwrap();
+        }
+
+        let settings = DownloadProfileSettings {
+            quality: Some("4k".to_string()),
+            format: Some("mkv".to_string()),
+            ..Default::default()
+        };
+        let profile = save_download_profile_to_db(None, " 4K archive ", &settings).unwrap();
+        assert_eq!(profile.name, "4K archive");
+        assert_eq!(profile.settings, settings);
+        assert!(save_download_profile_to_db(None, "  ", &settings).is_err());
+
+        let audio = DownloadProfileSettings {
+            quality: Some("audio".to_string()),
+            format: Some("mp3".to_string()),
+            ..Default::default()
+        };
+        let updated =
+            save_download_profile_to_db(Some(profile.id.clone()), "Quick audio", &audio).unwrap();
+        assert_eq!(updated.id, profile.id);
+        assert_eq!(updated.settings.format.as_deref(), Some("mp3"));
+
+        let copy = duplicate_download_profile_in_db(&profile.id, None).unwrap();
+        assert_ne!(copy.id, profile.id);
+        assert_eq!(copy.name, "Quick audio (copy)");
+        assert_eq!(copy.settings, audio);
+        assert_eq!(list_download_profiles_from_db().unwrap().len(), 2);
+
+        delete_download_profile_from_db(&profile.id).unwrap();
+        assert!(get_download_profile_from_db(&profile.id).unwrap().is_none());
+        assert_eq!(list_download_profiles_from_db().unwrap(), vec![copy]);
+    }
+}
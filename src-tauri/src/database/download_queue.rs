@@ -1,8 +1,22 @@
 use super::get_db;
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 
-const VALID_QUEUE_KINDS: &[&str] = &["youtube", "universal", "gallery"];
+// "interrupted" holds downloads cut off by quitting, see `services::quit_guard`
+const VALID_QUEUE_KINDS: &[&str] = &["youtube", "universal", "gallery", "interrupted"];
+
+pub(super) fn create_download_queues_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_queues (
+            queue_kind TEXT PRIMARY KEY,
+            items_json TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_queues table: {}", e))?;
+    Ok(())
+}
 
 fn validate_queue_kind(queue_kind: &str) -> Result<(), String> {
     if VALID_QUEUE_KINDS.contains(&queue_kind) {
@@ -58,3 +72,50 @@ pub fn clear_download_queue_from_db(queue_kind: String) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn reset_download_queues() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+        let conn = get_db().expect("get db");
+        create_download_queues_table(&conn).expect("create download queues");
+        conn.execute("DELETE FROM download_queues", []).unwrap();
+    }
+
+    #[test]
+    fn interrupted_queue_is_saved_apart_from_the_live_queues() {
+        let _guard = db_test_guard();
+        reset_download_queues();
+
+        save_download_queue_to_db("youtube".to_string(), "[1]".to_string()).unwrap();
+        save_download_queue_to_db("interrupted".to_string(), "[2]".to_string()).unwrap();
+        save_download_queue_to_db("interrupted".to_string(), "[3]".to_string()).unwrap();
+        assert_eq!(
+            load_download_queue_from_db("interrupted".to_string()).unwrap(),
+            Some("[3]".to_string())
+        );
+
+        clear_download_queue_from_db("interrupted".to_string()).unwrap();
+        assert_eq!(
+            load_download_queue_from_db("interrupted".to_string()).unwrap(),
+            None
+        );
+        assert_eq!(
+            load_download_queue_from_db("youtube".to_string()).unwrap(),
+            Some("[1]".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_queue_kinds_are_rejected() {
+        assert!(save_download_queue_to_db("other".to_string(), "[]".to_string()).is_err());
+        assert!(load_download_queue_from_db("other".to_string()).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use super::get_db;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Last GitHub API response for a URL, replayed on `304 Not Modified`
+pub struct CachedGithubResponse {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+    pub checked_at: i64,
+}
+
+pub(super) fn create_github_cache_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS github_api_cache (
+            url TEXT PRIMARY KEY,
+            etag TEXT,
+            last_modified TEXT,
+            body TEXT NOT NULL,
+            checked_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create github_api_cache table: {}", e))?;
+    Ok(())
+}
+
+pub fn get_github_cache(url: &str) -> Result<Option<CachedGithubResponse>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT etag, last_modified, body, checked_at FROM github_api_cache WHERE url = ?1",
+        params![url],
+        |row| {
+            Ok(CachedGithubResponse {
+                etag: row.get(0)?,
+                last_modified: row.get(1)?,
+                body: row.get(2)?,
+                checked_at: row.get(3)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read GitHub cache: {}", e))
+}
+
+pub fn save_github_cache(
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+    body: &str,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO github_api_cache (url, etag, last_modified, body, checked_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![url, etag, last_modified, body, Utc::now().timestamp()],
+    )
+    .map_err(|e| format!("Failed to save GitHub cache: {}", e))?;
+    Ok(())
+}
+
+/// Record that the cached response was revalidated just now
+pub fn touch_github_cache(url: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE github_api_cache SET checked_at = ?1 WHERE url = ?2",
+        params![Utc::now().timestamp(), url],
+    )
+    .map_err(|e| format!("Failed to update GitHub cache: {}", e))?;
+    Ok(())
+}
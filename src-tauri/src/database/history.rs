@@ -2,12 +2,37 @@ use std::collections::HashMap;
 
 use super::get_db;
 use crate::types::{
-    DownloadDuplicateIdentity, DownloadDuplicateMatch, HistoryAdvancedFilters, HistoryCollection,
-    HistoryEntry, HistoryFilterMatchMode, HistoryMediaType, HistorySearchScope, HistorySort,
-    HistoryTag,
+    DownloadDuplicateIdentity, DownloadDuplicateMatch, DownloadProfileSettings,
+    ExternalHistoryKind, FailedDownloadRecord, HistoryAdvancedFilters, HistoryCollection,
+    HistoryEntry, HistoryFacetCount, HistoryFilterMatchMode, HistoryImportReport, HistoryMediaType,
+    HistoryPage, HistoryPageQuery, HistoryRetentionPolicy, HistorySearchScope, HistorySort,
+    HistoryStatus, HistoryTag, ImportedHistoryRecord, ThumbnailEmbed,
 };
+use crate::utils::media_type_for_path;
 use chrono::Utc;
-use rusqlite::{params, params_from_iter, types::Value, Connection};
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension};
+use std::sync::Mutex;
+
+// Retention applied after every insert, synced from settings
+static HISTORY_RETENTION: Mutex<HistoryRetentionPolicy> = Mutex::new(HistoryRetentionPolicy {
+    max_entries: None,
+    max_age_days: None,
+    only_missing_files: false,
+    delete_files: false,
+});
+
+pub fn set_history_retention_policy(policy: HistoryRetentionPolicy) {
+    if let Ok(mut guard) = HISTORY_RETENTION.lock() {
+        *guard = policy;
+    }
+}
+
+pub fn get_history_retention_policy() -> HistoryRetentionPolicy {
+    HISTORY_RETENTION
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
 
 fn parse_history_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
     let filepath: String = row.get(4)?;
@@ -32,14 +57,30 @@ fn parse_history_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
         file_exists,
         summary: row.get(11)?,
         time_range: row.get(12)?,
+        media_type: row.get(13)?,
+        favorite: row.get::<_, Option<i64>>(14)?.unwrap_or(0) != 0,
+        notes: row.get(15)?,
+        custom_metadata: row
+            .get::<_, Option<String>>(16)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        playlist_index: row.get(17)?,
+        lyrics_path: row.get(18)?,
+        variant_of: row.get(19)?,
+        is_short: row.get::<_, Option<i64>>(20)?.unwrap_or(0) != 0,
+        status: HistoryStatus::from_db(row.get::<_, Option<String>>(21)?.as_deref()),
+        error_code: row.get(22)?,
+        error_message: row.get(23)?,
+        thumbnail_embed: ThumbnailEmbed::from_db(row.get::<_, Option<String>>(24)?.as_deref()),
+        language: row.get(25)?,
         tags: Vec::new(),
         collections: Vec::new(),
     })
 }
 
 fn audio_media_sql_condition(history_alias: &str) -> String {
+    // Rows written before media_type existed fall back to format/quality heuristics
     format!(
-        "(LOWER(COALESCE({0}.format, '')) IN ('mp3', 'm4a', 'opus', 'flac', 'wav', 'aac', 'ogg', 'oga') OR LOWER(COALESCE({0}.quality, '')) LIKE '%audio%')",
+        "({0}.media_type = 'audio' OR ({0}.media_type IS NULL AND (LOWER(COALESCE({0}.format, '')) IN ('mp3', 'm4a', 'opus', 'flac', 'wav', 'aac', 'ogg', 'oga') OR LOWER(COALESCE({0}.quality, '')) LIKE '%audio%')))",
         history_alias
     )
 }
@@ -148,7 +189,7 @@ fn extract_youtube_video_id(url: &str) -> Option<String> {
     }
 }
 
-fn canonicalize_download_url(url: &str) -> String {
+pub(super) fn canonicalize_download_url(url: &str) -> String {
     let trimmed = url.trim();
     if let Some(video_id) = extract_youtube_video_id(trimmed) {
         return format!("https://www.youtube.com/watch?v={video_id}");
@@ -211,7 +252,8 @@ fn find_legacy_duplicate_download(
         .prepare(
             "SELECT id, title, thumbnail, filepath, downloaded_at, url, source
              FROM history
-             WHERE media_id IS NULL OR canonical_url IS NULL
+             WHERE (media_id IS NULL OR canonical_url IS NULL)
+               AND COALESCE(status, 'completed') = 'completed'
              ORDER BY downloaded_at DESC",
         )
         .map_err(|e| format!("Failed to prepare legacy duplicate lookup: {}", e))?;
@@ -284,6 +326,7 @@ fn build_fts_query(search: &str, scope: HistorySearchScope) -> Option<String> {
         HistorySearchScope::All => "",
         HistorySearchScope::Metadata => "{title filepath url}:",
         HistorySearchScope::Summary => "summary:",
+        HistorySearchScope::Notes => "notes:",
     };
     Some(
         terms
@@ -294,6 +337,80 @@ fn build_fts_query(search: &str, scope: HistorySearchScope) -> Option<String> {
     )
 }
 
+/// `%value%` for a LIKE with `ESCAPE '\'`, so `%` and `_` in user input match literally
+fn like_contains_pattern(value: &str) -> String {
+    let mut pattern = String::with_capacity(value.len() + 2);
+    pattern.push('%');
+    for ch in value.chars() {
+        if matches!(ch, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// `FROM ... WHERE ...` and its parameters, shared by the history list and count
+struct HistoryQuery {
+    from_where: String,
+    params: Vec<Value>,
+    /// Full-text matched, so results can be ordered by relevance
+    ranked: bool,
+}
+
+fn build_history_query(
+    conn: &Connection,
+    source: Option<&str>,
+    search: Option<&str>,
+    filters: Option<&HistoryAdvancedFilters>,
+) -> HistoryQuery {
+    let trimmed_search = search.map(str::trim).filter(|s| !s.is_empty());
+    let search_scope = filters
+        .and_then(|filter| filter.search_scope.clone())
+        .unwrap_or_default();
+    let fts_query = trimmed_search
+        .and_then(|value| build_fts_query(value, search_scope))
+        .filter(|_| history_search_fts_available(conn));
+
+    let mut from_where = if fts_query.is_some() {
+        String::from(
+            "FROM history h
+             JOIN history_search_fts ON history_search_fts.rowid = h.rowid
+             WHERE history_search_fts MATCH ?",
+        )
+    } else {
+        String::from("FROM history h WHERE 1=1")
+    };
+    let ranked = fts_query.is_some();
+    let mut params: Vec<Value> = fts_query.into_iter().map(Value::from).collect();
+    apply_history_filters(
+        &mut from_where,
+        &mut params,
+        "h",
+        source,
+        if ranked { None } else { trimmed_search },
+        filters,
+    );
+    HistoryQuery {
+        from_where,
+        params,
+        ranked,
+    }
+}
+
+fn history_order_clause(sort: HistorySort, ranked: bool) -> &'static str {
+    match sort {
+        HistorySort::Recent if ranked => {
+            " ORDER BY bm25(history_search_fts) ASC, h.downloaded_at DESC"
+        }
+        HistorySort::Recent => " ORDER BY h.downloaded_at DESC",
+        HistorySort::Oldest => " ORDER BY h.downloaded_at ASC",
+        HistorySort::Title => " ORDER BY LOWER(h.title) ASC",
+        HistorySort::Size => " ORDER BY h.filesize IS NULL ASC, h.filesize DESC",
+    }
+}
+
 fn apply_relation_filter(
     query: &mut String,
     params: &mut Vec<Value>,
@@ -347,20 +464,20 @@ fn apply_history_filters(
         let search_scope = filters
             .and_then(|filter| filter.search_scope.clone())
             .unwrap_or_default();
-        let search_pattern = format!("%{}%", search_text);
+        let search_pattern = like_contains_pattern(search_text);
         match search_scope {
             HistorySearchScope::All => {
                 query.push_str(&format!(
-                    " AND ({history_alias}.title LIKE ? OR {history_alias}.filepath LIKE ? OR {history_alias}.url LIKE ? OR COALESCE({history_alias}.summary, '') LIKE ?)"
+                    " AND ({history_alias}.title LIKE ? ESCAPE '\\' OR {history_alias}.filepath LIKE ? ESCAPE '\\' OR {history_alias}.url LIKE ? ESCAPE '\\' OR COALESCE({history_alias}.summary, '') LIKE ? ESCAPE '\\' OR COALESCE({history_alias}.notes, '') LIKE ? ESCAPE '\\' OR COALESCE({history_alias}.custom_metadata, '') LIKE ? ESCAPE '\\')"
                 ));
-                params.push(Value::from(search_pattern.clone()));
-                params.push(Value::from(search_pattern.clone()));
-                params.push(Value::from(search_pattern.clone()));
+                for _ in 0..5 {
+                    params.push(Value::from(search_pattern.clone()));
+                }
                 params.push(Value::from(search_pattern));
             }
             HistorySearchScope::Metadata => {
                 query.push_str(&format!(
-                    " AND ({history_alias}.title LIKE ? OR {history_alias}.filepath LIKE ? OR {history_alias}.url LIKE ?)"
+                    " AND ({history_alias}.title LIKE ? ESCAPE '\\' OR {history_alias}.filepath LIKE ? ESCAPE '\\' OR {history_alias}.url LIKE ? ESCAPE '\\')"
                 ));
                 params.push(Value::from(search_pattern.clone()));
                 params.push(Value::from(search_pattern.clone()));
@@ -368,10 +485,17 @@ fn apply_history_filters(
             }
             HistorySearchScope::Summary => {
                 query.push_str(&format!(
-                    " AND COALESCE({history_alias}.summary, '') LIKE ?"
+                    " AND COALESCE({history_alias}.summary, '') LIKE ? ESCAPE '\\'"
                 ));
                 params.push(Value::from(search_pattern));
             }
+            HistorySearchScope::Notes => {
+                query.push_str(&format!(
+                    " AND (COALESCE({history_alias}.notes, '') LIKE ? ESCAPE '\\' OR COALESCE({history_alias}.custom_metadata, '') LIKE ? ESCAPE '\\')"
+                ));
+                params.push(Value::from(search_pattern.clone()));
+                params.push(Value::from(search_pattern));
+            }
         }
     }
 
@@ -385,12 +509,39 @@ fn apply_history_filters(
                 query.push_str(" AND NOT ");
                 query.push_str(&audio_media_sql_condition(history_alias));
                 query.push_str(&format!(
-                    " AND COALESCE({history_alias}.source, '') != 'data_export'"
+                    " AND COALESCE({history_alias}.source, '') != 'data_export' AND COALESCE({history_alias}.media_type, '') NOT IN ('image', 'subtitle')"
                 ));
             }
+            Some(HistoryMediaType::Image) => {
+                query.push_str(&format!(" AND {history_alias}.media_type = 'image'"));
+            }
+            Some(HistoryMediaType::Subtitle) => {
+                query.push_str(&format!(" AND {history_alias}.media_type = 'subtitle'"));
+            }
             _ => {}
         }
 
+        if filter.favorites_only == Some(true) {
+            query.push_str(&format!(" AND {history_alias}.favorite = 1"));
+        }
+        if let Some(is_short) = filter.is_short {
+            query.push_str(&format!(
+                " AND COALESCE({history_alias}.is_short, 0) = {}",
+                is_short as i64
+            ));
+        }
+        if let Some(statuses) = filter.statuses.as_ref().filter(|s| !s.is_empty()) {
+            let placeholders = vec!["?"; statuses.len()].join(", ");
+            query.push_str(&format!(
+                " AND COALESCE({history_alias}.status, 'completed') IN ({placeholders})"
+            ));
+            params.extend(
+                statuses
+                    .iter()
+                    .map(|status| Value::from(status.as_str().to_string())),
+            );
+        }
+
         if let Some(from) = filter.downloaded_at_from {
             query.push_str(&format!(" AND {history_alias}.downloaded_at >= ?"));
             params.push(Value::from(from));
@@ -435,14 +586,14 @@ fn apply_history_filters(
                 }
                 if quality == "audio" {
                     query.push_str(&format!(
-                        "(LOWER(COALESCE({history_alias}.quality, '')) LIKE ? OR LOWER(COALESCE({history_alias}.format, '')) IN ('mp3', 'm4a', 'opus', 'flac', 'wav', 'aac', 'ogg', 'oga'))"
+                        "(LOWER(COALESCE({history_alias}.quality, '')) LIKE ? ESCAPE '\\' OR LOWER(COALESCE({history_alias}.format, '')) IN ('mp3', 'm4a', 'opus', 'flac', 'wav', 'aac', 'ogg', 'oga'))"
                     ));
                 } else {
                     query.push_str(&format!(
-                        "LOWER(COALESCE({history_alias}.quality, '')) LIKE ?"
+                        "LOWER(COALESCE({history_alias}.quality, '')) LIKE ? ESCAPE '\\'"
                     ));
                 }
-                params.push(Value::from(format!("%{}%", quality)));
+                params.push(Value::from(like_contains_pattern(quality)));
             }
             query.push(')');
         }
@@ -687,10 +838,16 @@ pub fn add_history_internal(
     let id = uuid::Uuid::new_v4().to_string();
     let now = Utc::now().timestamp();
     let (media_id, canonical_url) = build_history_identity(&url, source.as_deref());
+    // gallery-dl entries point at a folder of images rather than a single file
+    let media_type = if format.as_deref() == Some("gallery") {
+        "image"
+    } else {
+        media_type_for_path(&filepath)
+    };
 
     conn.execute(
-        "INSERT OR REPLACE INTO history (id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, time_range, media_id, canonical_url)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        "INSERT OR REPLACE INTO history (id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, time_range, media_id, canonical_url, media_type)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
         params![
             id,
             url,
@@ -705,11 +862,17 @@ pub fn add_history_internal(
             now,
             time_range,
             media_id,
-            canonical_url
+            canonical_url,
+            media_type
         ],
     )
     .map_err(|e| format!("Failed to add history: {}", e))?;
 
+    // Automatic pruning never touches files on disk
+    if let Err(e) = prune_history_rows(&conn, &get_history_retention_policy(), Some(&id)) {
+        log::warn!("Failed to apply history retention: {}", e);
+    }
+
     conn.execute(
         "DELETE FROM history_tags WHERE history_id NOT IN (SELECT id FROM history)",
         [],
@@ -724,6 +887,89 @@ pub fn add_history_internal(
     Ok(id)
 }
 
+fn collect_history_rows(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<(String, String)>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Failed to prepare history pruning: {}", e))?;
+    let rows = stmt
+        .query_map(params, |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("Failed to query history pruning: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read history pruning: {}", e))
+}
+
+/// Delete history rows outside the retention policy, never removing `keep_id`.
+/// Returns the file paths of the removed entries.
+fn prune_history_rows(
+    conn: &Connection,
+    policy: &HistoryRetentionPolicy,
+    keep_id: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let mut expired: Vec<(String, String)> = Vec::new();
+
+    if let Some(days) = policy.max_age_days {
+        let cutoff = Utc::now().timestamp() - i64::from(days) * 86_400;
+        let rows = collect_history_rows(
+            conn,
+            "SELECT id, filepath FROM history WHERE downloaded_at < ?1",
+            params![cutoff],
+        )?;
+        expired.extend(rows.into_iter().filter(|(_, filepath)| {
+            !policy.only_missing_files || !std::path::Path::new(filepath).exists()
+        }));
+    }
+
+    if let Some(max_entries) = policy.max_entries {
+        let rows = collect_history_rows(
+            conn,
+            "SELECT id, filepath FROM history ORDER BY downloaded_at DESC, rowid DESC LIMIT -1 OFFSET ?1",
+            params![i64::from(max_entries)],
+        )?;
+        for row in rows {
+            if !expired.iter().any(|(id, _)| id == &row.0) {
+                expired.push(row);
+            }
+        }
+    }
+
+    expired.retain(|(id, _)| Some(id.as_str()) != keep_id);
+    if expired.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start history pruning: {}", e))?;
+    for (id, _) in &expired {
+        tx.execute(
+            "DELETE FROM history_tags WHERE history_id = ?1",
+            params![id],
+        )
+        .map_err(|e| format!("Failed to delete history tags: {}", e))?;
+        tx.execute(
+            "DELETE FROM history_collections WHERE history_id = ?1",
+            params![id],
+        )
+        .map_err(|e| format!("Failed to delete history collections: {}", e))?;
+        tx.execute("DELETE FROM history WHERE id = ?1", params![id])
+            .map_err(|e| format!("Failed to delete history: {}", e))?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to save history pruning: {}", e))?;
+
+    Ok(expired.into_iter().map(|(_, filepath)| filepath).collect())
+}
+
+/// Remove history entries outside `policy`, returning their file paths
+pub fn prune_history_in_db(policy: &HistoryRetentionPolicy) -> Result<Vec<String>, String> {
+    let conn = get_db()?;
+    prune_history_rows(&conn, policy, None)
+}
+
 pub fn update_history_summary(id: String, summary: String) -> Result<(), String> {
     let conn = get_db()?;
     conn.execute(
@@ -734,6 +980,79 @@ pub fn update_history_summary(id: String, summary: String) -> Result<(), String>
     Ok(())
 }
 
+/// Set or clear (empty text) the user's note on a history entry
+pub fn update_history_note_in_db(id: String, note: String) -> Result<(), String> {
+    let conn = get_db()?;
+    ensure_history_exists(&conn, &id)?;
+    let note = Some(note.trim().to_string()).filter(|note| !note.is_empty());
+    conn.execute(
+        "UPDATE history SET notes = ?1 WHERE id = ?2",
+        params![note, id],
+    )
+    .map_err(|e| format!("Failed to update note: {}", e))?;
+    Ok(())
+}
+
+/// Replace the custom key/value metadata of a history entry
+pub fn update_history_custom_metadata_in_db(
+    id: String,
+    metadata: serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    ensure_history_exists(&conn, &id)?;
+    let json = if metadata.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::to_string(&metadata)
+                .map_err(|e| format!("Failed to serialize metadata: {}", e))?,
+        )
+    };
+    conn.execute(
+        "UPDATE history SET custom_metadata = ?1 WHERE id = ?2",
+        params![json, id],
+    )
+    .map_err(|e| format!("Failed to update metadata: {}", e))?;
+    Ok(())
+}
+
+pub fn get_history_sha256(id: &str) -> Result<Option<String>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT sha256 FROM history WHERE id = ?1",
+        params![id],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .map_err(|e| format!("Failed to read history checksum: {}", e))
+}
+
+/// File paths of the most recent downloads, newest first
+pub fn recent_history_filepaths(limit: usize) -> Result<Vec<String>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare("SELECT filepath FROM history WHERE filepath != '' ORDER BY downloaded_at DESC LIMIT ?1")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let rows = stmt
+        .query_map(params![limit as i64], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to query history: {}", e))?;
+    Ok(rows.filter_map(|row| row.ok()).collect())
+}
+
+pub fn update_history_integrity(
+    id: &str,
+    sha256: Option<&str>,
+    integrity_status: &str,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "UPDATE history SET sha256 = COALESCE(?1, sha256), integrity_status = ?2, integrity_checked_at = ?3 WHERE id = ?4",
+        params![sha256, integrity_status, now, id],
+    )
+    .map_err(|e| format!("Failed to update history integrity: {}", e))?;
+    Ok(())
+}
+
 pub fn update_history_download(
     id: String,
     filepath: String,
@@ -745,13 +1064,123 @@ pub fn update_history_download(
     let conn = get_db()?;
     let now = Utc::now().timestamp();
     conn.execute(
-        "UPDATE history SET filepath = ?1, filesize = ?2, quality = ?3, format = ?4, downloaded_at = ?5, time_range = ?6 WHERE id = ?7",
-        params![filepath, filesize, quality, format, now, time_range, id],
+        "UPDATE history SET filepath = ?1, filesize = ?2, quality = ?3, format = ?4, downloaded_at = ?5, time_range = ?6, sha256 = NULL, integrity_status = NULL, media_type = ?7, status = 'completed', error_code = NULL, error_message = NULL WHERE id = ?8",
+        params![filepath, filesize, quality, format, now, time_range, media_type_for_path(&filepath), id],
+    )
+    .map_err(|e| format!("Failed to update history: {}", e))?;
+    Ok(())
+}
+
+pub fn set_history_playlist_index(id: &str, playlist_index: Option<u32>) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET playlist_index = ?1 WHERE id = ?2",
+        params![playlist_index, id],
+    )
+    .map_err(|e| format!("Failed to update history: {}", e))?;
+    Ok(())
+}
+
+pub fn set_history_ytdlp_args(id: &str, args: &[String]) -> Result<(), String> {
+    let conn = get_db()?;
+    let json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE history SET ytdlp_args = ?1 WHERE id = ?2",
+        params![json, id],
+    )
+    .map_err(|e| format!("Failed to update history: {}", e))?;
+    Ok(())
+}
+
+/// Attach a generated `.lrc` lyrics file to a library entry
+pub fn set_history_lyrics_path(id: &str, lyrics_path: Option<&str>) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET lyrics_path = ?1 WHERE id = ?2",
+        params![lyrics_path, id],
+    )
+    .map_err(|e| format!("Failed to update history: {}", e))?;
+    Ok(())
+}
+
+/// Link an entry to the one it was re-downloaded from in another quality
+pub fn set_history_variant_of(id: &str, variant_of: Option<&str>) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET variant_of = ?1 WHERE id = ?2",
+        params![variant_of, id],
+    )
+    .map_err(|e| format!("Failed to update history: {}", e))?;
+    Ok(())
+}
+
+/// How the entry's thumbnail was stored, or None when embedding was not requested
+pub fn set_history_thumbnail_embed(
+    id: &str,
+    thumbnail_embed: Option<ThumbnailEmbed>,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET thumbnail_embed = ?1 WHERE id = ?2",
+        params![thumbnail_embed.map(ThumbnailEmbed::as_str), id],
+    )
+    .map_err(|e| format!("Failed to update history: {}", e))?;
+    Ok(())
+}
+
+/// Track language of a subtitle file entry
+pub fn set_history_language(id: &str, language: Option<&str>) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET language = ?1 WHERE id = ?2",
+        params![language, id],
+    )
+    .map_err(|e| format!("Failed to update history: {}", e))?;
+    Ok(())
+}
+
+/// Flag an entry as a vertical short-form clip
+pub fn set_history_is_short(id: &str, is_short: bool) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET is_short = ?1 WHERE id = ?2",
+        params![is_short as i64, id],
     )
     .map_err(|e| format!("Failed to update history: {}", e))?;
     Ok(())
 }
 
+/// Latest entry downloaded to `filepath` or from `url`: (id, url, filepath, title)
+pub fn find_history_media(
+    path_or_url: &str,
+) -> Result<Option<(String, String, String, String)>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT id, url, filepath, title FROM history
+         WHERE (filepath = ?1 OR url = ?1) AND COALESCE(status, 'completed') = 'completed'
+         ORDER BY downloaded_at DESC LIMIT 1",
+        params![path_or_url],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read history: {}", e))
+}
+
+/// Recorded yt-dlp arguments; `None` for entries saved before they were kept
+pub fn get_history_ytdlp_args(id: &str) -> Result<Option<Vec<String>>, String> {
+    let conn = get_db()?;
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT ytdlp_args FROM history WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read history: {}", e))?
+        .ok_or_else(|| "History entry not found".to_string())?;
+    Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+}
+
 pub fn find_duplicate_downloads_in_history_db(
     identities: Vec<DownloadDuplicateIdentity>,
 ) -> Result<Vec<DownloadDuplicateMatch>, String> {
@@ -778,8 +1207,9 @@ pub fn find_duplicate_downloads_in_history_db(
             .prepare(
                 "SELECT id, title, thumbnail, filepath, downloaded_at, media_id, canonical_url
                  FROM history
-                 WHERE (?1 IS NOT NULL AND media_id = ?1)
-                    OR (?2 IS NOT NULL AND canonical_url = ?2)
+                 WHERE ((?1 IS NOT NULL AND media_id = ?1)
+                    OR (?2 IS NOT NULL AND canonical_url = ?2))
+                   AND COALESCE(status, 'completed') = 'completed'
                  ORDER BY downloaded_at DESC
                  LIMIT 1",
             )
@@ -805,6 +1235,10 @@ pub fn find_duplicate_downloads_in_history_db(
                     find_legacy_duplicate_download(&conn, media_id, canonical_url.as_deref())?
                 {
                     matches.push(item);
+                } else if let Some(item) =
+                    find_archived_download(&conn, media_id, canonical_url.as_deref())?
+                {
+                    matches.push(item);
                 }
             }
             Err(error) => return Err(format!("Failed to lookup duplicate download: {}", error)),
@@ -814,6 +1248,157 @@ pub fn find_duplicate_downloads_in_history_db(
     Ok(matches)
 }
 
+/// Media known only from an imported download archive; there is no history row or file
+fn find_archived_download(
+    conn: &Connection,
+    media_id: Option<&str>,
+    canonical_url: Option<&str>,
+) -> Result<Option<DownloadDuplicateMatch>, String> {
+    let result = conn.query_row(
+        "SELECT media_id, url, title, created_at FROM download_archive
+         WHERE (?1 IS NOT NULL AND media_id = ?1)
+            OR (?2 IS NOT NULL AND url = ?2)
+         LIMIT 1",
+        params![media_id, canonical_url],
+        |row| {
+            let archived_media_id: String = row.get(0)?;
+            let url: Option<String> = row.get(1)?;
+            let title: Option<String> = row.get(2)?;
+            Ok(duplicate_match_from_values(
+                String::new(),
+                title.unwrap_or_else(|| archived_media_id.clone()),
+                None,
+                String::new(),
+                row.get(3)?,
+                Some(archived_media_id),
+                url,
+            ))
+        },
+    );
+
+    match result {
+        Ok(item) => Ok(Some(item)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        // Databases created before the archive table existed have nothing to match
+        Err(rusqlite::Error::SqliteFailure(_, Some(message)))
+            if message.contains("no such table") =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(format!("Failed to lookup archived download: {}", e)),
+    }
+}
+
+/// YouTube archive entries carry no URL, but one can be rebuilt from the id
+fn url_for_media_id(media_id: &str) -> Option<String> {
+    let (extractor, id) = media_id.split_once(':')?;
+    (extractor == "youtube" && !id.is_empty())
+        .then(|| format!("https://www.youtube.com/watch?v={id}"))
+}
+
+/// Write records parsed from another downloader into history and the download
+/// archive, skipping media that is already known.
+pub fn import_history_records_in_db(
+    kind: ExternalHistoryKind,
+    records: Vec<ImportedHistoryRecord>,
+) -> Result<HistoryImportReport, String> {
+    let conn = get_db()?;
+    let imported_from = serde_json::to_value(kind)
+        .ok()
+        .and_then(|value| value.as_str().map(ToString::to_string))
+        .unwrap_or_default();
+    let now = Utc::now().timestamp();
+    let mut report = HistoryImportReport {
+        scanned: records.len(),
+        ..Default::default()
+    };
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start history import: {}", e))?;
+    for record in records {
+        let url = record
+            .url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(ToString::to_string)
+            .or_else(|| record.media_id.as_deref().and_then(url_for_media_id));
+        let media_id = record.media_id.clone().or_else(|| {
+            url.as_deref()
+                .and_then(|url| build_download_media_id(url, None))
+        });
+        let canonical_url = url.as_deref().map(canonicalize_download_url);
+
+        if url.is_none() && media_id.is_none() {
+            report.failed += 1;
+            continue;
+        }
+
+        let known: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM history
+                 WHERE (?1 IS NOT NULL AND media_id = ?1)
+                    OR (?2 IS NOT NULL AND canonical_url = ?2))",
+                params![media_id, canonical_url],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to check imported history: {}", e))?;
+
+        if let Some(media_id) = media_id.as_deref() {
+            report.archived += tx
+                .execute(
+                    "INSERT OR IGNORE INTO download_archive (media_id, url, title, imported_from, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![media_id, canonical_url, record.title, imported_from, now],
+                )
+                .map_err(|e| format!("Failed to add download archive entry: {}", e))?;
+        }
+
+        if known {
+            report.duplicates += 1;
+            continue;
+        }
+        let Some(url) = url else {
+            // Archive-only entry, e.g. a non-YouTube line from a yt-dlp archive
+            continue;
+        };
+
+        let filepath = record.filepath.unwrap_or_default();
+        let media_type = (!filepath.is_empty()).then(|| media_type_for_path(&filepath));
+        let title = record
+            .title
+            .filter(|title| !title.trim().is_empty())
+            .or_else(|| media_id.clone())
+            .unwrap_or_else(|| url.clone());
+        tx.execute(
+            "INSERT INTO history (id, url, title, thumbnail, filepath, filesize, duration, format, source, downloaded_at, media_id, canonical_url, media_type)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                url,
+                title,
+                record.thumbnail,
+                filepath,
+                record.filesize,
+                record.duration,
+                record.format,
+                record.source,
+                record.downloaded_at.unwrap_or(now),
+                media_id,
+                canonical_url,
+                media_type
+            ],
+        )
+        .map_err(|e| format!("Failed to add imported history: {}", e))?;
+        report.imported += 1;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to save history import: {}", e))?;
+
+    Ok(report)
+}
+
 pub fn update_history_filepath_and_title(
     old_filepath: String,
     new_filepath: String,
@@ -873,6 +1458,88 @@ pub fn add_history_with_summary(
     Ok(id)
 }
 
+/// Save a failed or cancelled download. A retry of a failed entry updates
+/// that entry; anything else adds a new one. Returns the entry id.
+pub fn record_failed_download_in_db(record: &FailedDownloadRecord) -> Result<String, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp();
+    let options_json = serde_json::to_string(&record.options).map_err(|e| e.to_string())?;
+    let args_json = serde_json::to_string(&record.ytdlp_args).map_err(|e| e.to_string())?;
+    let title = record.title.clone().unwrap_or_else(|| record.url.clone());
+
+    if let Some(retry_of) = record.retry_of.as_deref() {
+        let updated = conn
+            .execute(
+                "UPDATE history SET status = ?1, error_code = ?2, error_message = ?3, download_options = ?4, ytdlp_args = ?5, downloaded_at = ?6
+                 WHERE id = ?7 AND COALESCE(status, 'completed') != 'completed'",
+                params![
+                    record.status.as_str(),
+                    record.error_code,
+                    record.error_message,
+                    options_json,
+                    args_json,
+                    now,
+                    retry_of
+                ],
+            )
+            .map_err(|e| format!("Failed to update history: {}", e))?;
+        if updated > 0 {
+            return Ok(retry_of.to_string());
+        }
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let (media_id, canonical_url) = build_history_identity(&record.url, record.source.as_deref());
+    conn.execute(
+        "INSERT INTO history (id, url, title, thumbnail, filepath, quality, format, source, downloaded_at, time_range, media_id, canonical_url, status, error_code, error_message, download_options, ytdlp_args)
+         VALUES (?1, ?2, ?3, ?4, '', ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        params![
+            id,
+            record.url,
+            title,
+            record.thumbnail,
+            record.options.quality,
+            record.options.format,
+            record.source,
+            now,
+            record.time_range,
+            media_id,
+            canonical_url,
+            record.status.as_str(),
+            record.error_code,
+            record.error_message,
+            options_json,
+            args_json
+        ],
+    )
+    .map_err(|e| format!("Failed to add history: {}", e))?;
+
+    if let Err(e) = prune_history_rows(&conn, &get_history_retention_policy(), Some(&id)) {
+        log::warn!("Failed to apply history retention: {}", e);
+    }
+    Ok(id)
+}
+
+/// Status and saved settings of a failed or cancelled entry
+pub fn get_failed_download_options(
+    id: &str,
+) -> Result<(HistoryStatus, Option<DownloadProfileSettings>), String> {
+    let conn = get_db()?;
+    let (status, json): (Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT status, download_options FROM history WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read history: {}", e))?
+        .ok_or_else(|| "History entry not found".to_string())?;
+    Ok((
+        HistoryStatus::from_db(status.as_deref()),
+        json.and_then(|json| serde_json::from_str(&json).ok()),
+    ))
+}
+
 pub fn get_history_from_db(
     limit: Option<i64>,
     offset: Option<i64>,
@@ -885,54 +1552,36 @@ pub fn get_history_from_db(
 
     let limit = limit.filter(|value| *value > 0);
     let offset = offset.unwrap_or(0).max(0);
-    let trimmed_search = search.as_deref().map(str::trim).filter(|s| !s.is_empty());
-    let search_scope = filters
-        .as_ref()
-        .and_then(|filter| filter.search_scope.clone())
-        .unwrap_or_default();
-    let fts_query = trimmed_search
-        .and_then(|value| build_fts_query(value, search_scope))
-        .filter(|_| history_search_fts_available(&conn));
-
-    let mut query = if fts_query.is_some() {
-        String::from(
-            "SELECT h.id, h.url, h.title, h.thumbnail, h.filepath, h.filesize, h.duration, h.quality, h.format, h.source, h.downloaded_at, h.summary, h.time_range
-             FROM history h
-             JOIN history_search_fts ON history_search_fts.rowid = h.rowid
-             WHERE history_search_fts MATCH ?",
-        )
-    } else {
-        String::from(
-            "SELECT h.id, h.url, h.title, h.thumbnail, h.filepath, h.filesize, h.duration, h.quality, h.format, h.source, h.downloaded_at, h.summary, h.time_range
-             FROM history h WHERE 1=1",
-        )
-    };
-    let mut query_params: Vec<Value> = Vec::new();
-    if let Some(value) = fts_query.clone() {
-        query_params.push(Value::from(value));
-    }
-    apply_history_filters(
-        &mut query,
-        &mut query_params,
-        "h",
+    let history_query = build_history_query(
+        &conn,
         source.as_deref(),
-        if fts_query.is_some() {
-            None
-        } else {
-            trimmed_search
-        },
+        search.as_deref(),
         filters.as_ref(),
     );
 
-    match sort.unwrap_or_default() {
-        HistorySort::Recent if fts_query.is_some() => {
-            query.push_str(" ORDER BY bm25(history_search_fts) ASC, h.downloaded_at DESC")
-        }
-        HistorySort::Recent => query.push_str(" ORDER BY h.downloaded_at DESC"),
-        HistorySort::Oldest => query.push_str(" ORDER BY h.downloaded_at ASC"),
-        HistorySort::Title => query.push_str(" ORDER BY LOWER(h.title) ASC"),
-        HistorySort::Size => query.push_str(" ORDER BY h.filesize IS NULL ASC, h.filesize DESC"),
-    }
+    query_history_entries(
+        &conn,
+        history_query,
+        sort.unwrap_or_default(),
+        limit,
+        offset,
+    )
+}
+
+fn query_history_entries(
+    conn: &Connection,
+    history_query: HistoryQuery,
+    sort: HistorySort,
+    limit: Option<i64>,
+    offset: i64,
+) -> Result<Vec<HistoryEntry>, String> {
+    let mut query = format!(
+        "SELECT h.id, h.url, h.title, h.thumbnail, h.filepath, h.filesize, h.duration, h.quality, h.format, h.source, h.downloaded_at, h.summary, h.time_range, h.media_type, h.favorite, h.notes, h.custom_metadata, h.playlist_index, h.lyrics_path, h.variant_of, h.is_short, h.status, h.error_code, h.error_message, h.thumbnail_embed, h.language
+         {}",
+        history_query.from_where
+    );
+    query.push_str(history_order_clause(sort, history_query.ranked));
+    let mut query_params = history_query.params;
     if let Some(limit) = limit {
         query.push_str(" LIMIT ? OFFSET ?");
         query_params.push(Value::from(limit));
@@ -949,7 +1598,7 @@ pub fn get_history_from_db(
         .filter_map(|r| r.ok())
         .collect();
 
-    hydrate_history_metadata(&conn, &mut entries)?;
+    hydrate_history_metadata(conn, &mut entries)?;
 
     Ok(entries)
 }
@@ -962,7 +1611,7 @@ pub fn get_history_entries_by_ids_from_db(ids: Vec<String>) -> Result<Vec<Histor
     let conn = get_db()?;
     let placeholders = vec!["?"; ids.len()].join(", ");
     let query = format!(
-        "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, time_range
+        "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, time_range, media_type, favorite, notes, custom_metadata, playlist_index, lyrics_path, variant_of, is_short, status, error_code, error_message, thumbnail_embed, language
          FROM history
          WHERE id IN ({})",
         placeholders
@@ -1018,41 +1667,14 @@ pub fn get_history_count_from_db(
     filters: Option<HistoryAdvancedFilters>,
 ) -> Result<i64, String> {
     let conn = get_db()?;
-
-    let trimmed_search = search.as_deref().map(str::trim).filter(|s| !s.is_empty());
-    let search_scope = filters
-        .as_ref()
-        .and_then(|filter| filter.search_scope.clone())
-        .unwrap_or_default();
-    let fts_query = trimmed_search
-        .and_then(|value| build_fts_query(value, search_scope))
-        .filter(|_| history_search_fts_available(&conn));
-
-    let mut query = if fts_query.is_some() {
-        String::from(
-            "SELECT COUNT(*) FROM history h
-             JOIN history_search_fts ON history_search_fts.rowid = h.rowid
-             WHERE history_search_fts MATCH ?",
-        )
-    } else {
-        String::from("SELECT COUNT(*) FROM history h WHERE 1=1")
-    };
-    let mut query_params: Vec<Value> = Vec::new();
-    if let Some(value) = fts_query.clone() {
-        query_params.push(Value::from(value));
-    }
-    apply_history_filters(
-        &mut query,
-        &mut query_params,
-        "h",
+    let history_query = build_history_query(
+        &conn,
         source.as_deref(),
-        if fts_query.is_some() {
-            None
-        } else {
-            trimmed_search
-        },
+        search.as_deref(),
         filters.as_ref(),
     );
+    let query = format!("SELECT COUNT(*) {}", history_query.from_where);
+    let query_params = history_query.params;
 
     let count: i64 = conn
         .query_row(&query, params_from_iter(query_params.iter()), |row| {
@@ -1063,6 +1685,83 @@ pub fn get_history_count_from_db(
     Ok(count)
 }
 
+/// Counts of the rows matching `history_query`, grouped by `value_sql`
+fn query_history_facet(
+    conn: &Connection,
+    history_query: &HistoryQuery,
+    value_sql: &str,
+) -> Result<Vec<HistoryFacetCount>, String> {
+    let query = format!(
+        "SELECT {} AS value, COUNT(*) {} GROUP BY value",
+        value_sql, history_query.from_where
+    );
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| format!("Failed to prepare facet query: {}", e))?;
+    let mut facets: Vec<HistoryFacetCount> = stmt
+        .query_map(params_from_iter(history_query.params.iter()), |row| {
+            Ok(HistoryFacetCount {
+                value: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })
+        .map_err(|e| format!("Facet query failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    Ok(facets)
+}
+
+/// A page of history with the total match count and facet counts, read under
+/// one lock so the numbers agree with the entries. Each facet ignores its own
+/// filter, so every chip shows what picking it instead would match.
+pub fn get_history_page_from_db(page: HistoryPageQuery) -> Result<HistoryPage, String> {
+    let conn = get_db()?;
+    let history_query = build_history_query(
+        &conn,
+        page.source.as_deref(),
+        page.search.as_deref(),
+        page.filters.as_ref(),
+    );
+
+    let without_source =
+        build_history_query(&conn, None, page.search.as_deref(), page.filters.as_ref());
+    let sources = query_history_facet(&conn, &without_source, "COALESCE(h.source, '')")?;
+    let filters_without_formats = page.filters.clone().map(|filters| HistoryAdvancedFilters {
+        formats: None,
+        ..filters
+    });
+    let without_formats = build_history_query(
+        &conn,
+        page.source.as_deref(),
+        page.search.as_deref(),
+        filters_without_formats.as_ref(),
+    );
+    let formats = query_history_facet(&conn, &without_formats, "LOWER(COALESCE(h.format, ''))")?;
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) {}", history_query.from_where),
+            params_from_iter(history_query.params.iter()),
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to count history: {}", e))?;
+    let entries = query_history_entries(
+        &conn,
+        history_query,
+        page.sort.unwrap_or_default(),
+        page.limit.filter(|value| *value > 0),
+        page.offset.unwrap_or(0).max(0),
+    )?;
+
+    Ok(HistoryPage {
+        entries,
+        total,
+        sources,
+        formats,
+    })
+}
+
 pub fn get_tags_from_db() -> Result<Vec<HistoryTag>, String> {
     let conn = get_db()?;
     let mut stmt = conn
@@ -1292,6 +1991,24 @@ pub fn add_history_collection_in_db(
     Ok(())
 }
 
+/// Flip the favorite flag of a history entry, returning the new value
+pub fn toggle_history_favorite_in_db(history_id: String) -> Result<bool, String> {
+    let conn = get_db()?;
+    ensure_history_exists(&conn, &history_id)?;
+    conn.execute(
+        "UPDATE history SET favorite = 1 - COALESCE(favorite, 0) WHERE id = ?1",
+        params![history_id],
+    )
+    .map_err(|e| format!("Failed to update favorite: {}", e))?;
+    conn.query_row(
+        "SELECT favorite FROM history WHERE id = ?1",
+        params![history_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|favorite| favorite != 0)
+    .map_err(|e| format!("Failed to read favorite: {}", e))
+}
+
 pub fn remove_history_tag_from_db(history_id: String, tag_id: String) -> Result<(), String> {
     let conn = get_db()?;
     conn.execute(
@@ -1387,19 +2104,27 @@ mod tests {
                 filepath,
                 url,
                 summary,
+                notes,
                 tokenize = 'unicode61 remove_diacritics 2'
             );
+            CREATE TABLE IF NOT EXISTS download_archive (
+                media_id TEXT PRIMARY KEY,
+                url TEXT,
+                title TEXT,
+                imported_from TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
             CREATE TRIGGER IF NOT EXISTS history_search_insert AFTER INSERT ON history BEGIN
-                INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary)
-                VALUES (new.rowid, new.id, new.title, new.filepath, new.url, COALESCE(new.summary, ''));
+                INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary, notes)
+                VALUES (new.rowid, new.id, new.title, new.filepath, new.url, COALESCE(new.summary, ''), COALESCE(new.notes, '') || ' ' || COALESCE(new.custom_metadata, ''));
             END;
             CREATE TRIGGER IF NOT EXISTS history_search_delete AFTER DELETE ON history BEGIN
                 DELETE FROM history_search_fts WHERE rowid = old.rowid;
             END;
             CREATE TRIGGER IF NOT EXISTS history_search_update AFTER UPDATE ON history BEGIN
                 DELETE FROM history_search_fts WHERE rowid = old.rowid;
-                INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary)
-                VALUES (new.rowid, new.id, new.title, new.filepath, new.url, COALESCE(new.summary, ''));
+                INSERT INTO history_search_fts (rowid, history_id, title, filepath, url, summary, notes)
+                VALUES (new.rowid, new.id, new.title, new.filepath, new.url, COALESCE(new.summary, ''), COALESCE(new.notes, '') || ' ' || COALESCE(new.custom_metadata, ''));
             END;",
         )
         .expect("create tables");
@@ -1411,6 +2136,54 @@ mod tests {
             .ok();
         conn.execute("ALTER TABLE history ADD COLUMN canonical_url TEXT", [])
             .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN sha256 TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN integrity_status TEXT", [])
+            .ok();
+        conn.execute(
+            "ALTER TABLE history ADD COLUMN integrity_checked_at INTEGER",
+            [],
+        )
+        .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN media_type TEXT", [])
+            .ok();
+        conn.execute(
+            "ALTER TABLE history ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN notes TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN custom_metadata TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN playlist_index INTEGER", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN ytdlp_args TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN lyrics_path TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN variant_of TEXT", [])
+            .ok();
+        conn.execute(
+            "ALTER TABLE history ADD COLUMN is_short INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "ALTER TABLE history ADD COLUMN status TEXT NOT NULL DEFAULT 'completed'",
+            [],
+        )
+        .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN error_code TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN error_message TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN download_options TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN thumbnail_embed TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN language TEXT", [])
+            .ok();
         conn.execute("DELETE FROM history_search_fts", [])
             .expect("clear history search");
         conn.execute("DELETE FROM history_tags", [])
@@ -1420,6 +2193,8 @@ mod tests {
         conn.execute("DELETE FROM tags", []).expect("clear tags");
         conn.execute("DELETE FROM collections", [])
             .expect("clear collections");
+        conn.execute("DELETE FROM download_archive", [])
+            .expect("clear download archive");
         conn.execute("DELETE FROM history", [])
             .expect("clear history");
     }
@@ -1442,12 +2217,148 @@ mod tests {
         .expect("insert searchable history row");
     }
 
+    #[test]
+    fn like_pattern_escapes_wildcards() {
+        assert_eq!(like_contains_pattern("100%_a\\b"), "%100\\%\\_a\\\\b%");
+    }
+
+    #[test]
+    fn history_like_search_matches_wildcards_literally() {
+        // No FTS table here, so the LIKE fallback is used
+        let conn = rusqlite::Connection::open_in_memory().expect("open db");
+        conn.execute_batch(
+            "CREATE TABLE history (id TEXT PRIMARY KEY, title TEXT NOT NULL, filepath TEXT NOT NULL,
+                url TEXT NOT NULL, summary TEXT, notes TEXT, custom_metadata TEXT, filesize INTEGER,
+                downloaded_at INTEGER NOT NULL);
+            INSERT INTO history (id, title, filepath, url, filesize, downloaded_at) VALUES
+                ('a', '100% pure', '/a.mp4', 'u1', 10, 1),
+                ('b', '1000 things', '/b.mp4', 'u2', 30, 2),
+                ('c', 'snake_case', '/c.mp4', 'u3', 20, 3),
+                ('d', 'snakeXcase', '/d.mp4', 'u4', NULL, 4);",
+        )
+        .expect("seed history");
+
+        let search_ids = |search: &str, sort: HistorySort| -> Vec<String> {
+            let query = build_history_query(&conn, None, Some(search), None);
+            assert!(!query.ranked);
+            let sql = format!(
+                "SELECT h.id {}{}",
+                query.from_where,
+                history_order_clause(sort, query.ranked)
+            );
+            let mut stmt = conn.prepare(&sql).expect("prepare");
+            stmt.query_map(params_from_iter(query.params.iter()), |row| row.get(0))
+                .expect("query")
+                .map(|row| row.expect("row"))
+                .collect()
+        };
+
+        assert_eq!(search_ids("100%", HistorySort::Recent), ["a"]);
+        assert_eq!(search_ids("snake_", HistorySort::Recent), ["c"]);
+        assert_eq!(search_ids("/", HistorySort::Size), ["b", "c", "a", "d"]);
+        assert_eq!(search_ids("/", HistorySort::Title), ["a", "b", "c", "d"]);
+    }
+
     #[test]
     fn build_normalized_tag_name_strips_hash_and_underscores() {
         assert_eq!(normalize_tag_name("#Hoc_tap"), "hoc tap");
         assert_eq!(normalize_tag_name("  #Giải_trí  "), "giải trí");
     }
 
+    #[test]
+    fn failed_downloads_are_listed_by_status_and_retried_in_place() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        insert_history_row("done", "/downloads/done.mp4");
+        let record = FailedDownloadRecord {
+            url: "https://example.com/broken".to_string(),
+            status: HistoryStatus::Failed,
+            error_code: "YT_HTTP_403".to_string(),
+            error_message: "HTTP Error 403".to_string(),
+            options: DownloadProfileSettings {
+                quality: Some("1080".to_string()),
+                output_path: Some("/downloads".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let failed_id = record_failed_download_in_db(&record).expect("record failure");
+
+        let failed_only = HistoryAdvancedFilters {
+            statuses: Some(vec![HistoryStatus::Failed, HistoryStatus::Cancelled]),
+            ..Default::default()
+        };
+        let entries = get_history_from_db(None, None, None, None, Some(failed_only.clone()), None)
+            .expect("list failed");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].title, record.url);
+        assert_eq!(entries[0].error_code.as_deref(), Some("YT_HTTP_403"));
+        let (_, options) = get_failed_download_options(&failed_id).expect("read options");
+        assert_eq!(options, Some(record.options.clone()));
+
+        // A failed retry updates the entry; retrying a completed one adds a new entry
+        let retry = FailedDownloadRecord {
+            status: HistoryStatus::Cancelled,
+            retry_of: Some(failed_id.clone()),
+            ..record.clone()
+        };
+        assert_eq!(record_failed_download_in_db(&retry).unwrap(), failed_id);
+        let redownload = FailedDownloadRecord {
+            retry_of: Some("done".to_string()),
+            ..record
+        };
+        assert_ne!(record_failed_download_in_db(&redownload).unwrap(), "done");
+        assert_eq!(
+            get_history_count_from_db(None, None, Some(failed_only.clone())).unwrap(),
+            2
+        );
+
+        update_history_download(
+            failed_id.clone(),
+            "/downloads/fixed.mp4".to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("complete retry");
+        let (status, _) = get_failed_download_options(&failed_id).unwrap();
+        assert_eq!(status, HistoryStatus::Completed);
+        assert_eq!(
+            get_history_count_from_db(None, None, Some(failed_only)).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn history_integrity_keeps_checksum_until_file_is_replaced() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        let history_id = uuid::Uuid::new_v4().to_string();
+        insert_history_row(&history_id, "/downloads/a.mp4");
+
+        update_history_integrity(&history_id, Some("abc123"), "ok").expect("store checksum");
+        update_history_integrity(&history_id, None, "corrupt").expect("keep checksum");
+        assert_eq!(
+            get_history_sha256(&history_id).expect("read checksum"),
+            Some("abc123".to_string())
+        );
+
+        update_history_download(
+            history_id.clone(),
+            "/downloads/b.mp4".to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("update download");
+        assert_eq!(
+            get_history_sha256(&history_id).expect("read checksum"),
+            None
+        );
+    }
+
     #[test]
     fn assign_history_tags_reuses_existing_tag() {
         let _guard = db_test_guard();
@@ -1740,4 +2651,240 @@ mod tests {
         let _ = fs::remove_file(&old_path);
         let _ = fs::remove_dir_all(old_path.parent().unwrap_or_else(|| Path::new("/")));
     }
+
+    #[test]
+    fn import_history_records_skips_known_media_and_archives_the_rest() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        add_history_internal(
+            "https://www.youtube.com/watch?v=known1".to_string(),
+            "Known".to_string(),
+            None,
+            "/tmp/known.mp4".to_string(),
+            None,
+            None,
+            None,
+            None,
+            Some("youtube".to_string()),
+            None,
+        )
+        .expect("add existing history");
+
+        let record = |media_id: Option<&str>, url: Option<&str>| ImportedHistoryRecord {
+            media_id: media_id.map(ToString::to_string),
+            url: url.map(ToString::to_string),
+            ..Default::default()
+        };
+        let report = import_history_records_in_db(
+            ExternalHistoryKind::YtdlpArchive,
+            vec![
+                record(Some("youtube:known1"), None),
+                record(Some("youtube:new1"), None),
+                record(Some("vimeo:42"), None),
+                record(None, None),
+            ],
+        )
+        .expect("import records");
+
+        assert_eq!(
+            report,
+            HistoryImportReport {
+                scanned: 4,
+                imported: 1,
+                duplicates: 1,
+                archived: 3,
+                failed: 1,
+            }
+        );
+        let matches = find_duplicate_downloads_in_history_db(vec![
+            DownloadDuplicateIdentity {
+                media_id: None,
+                canonical_url: Some("https://youtu.be/new1".to_string()),
+            },
+            DownloadDuplicateIdentity {
+                media_id: Some("vimeo:42".to_string()),
+                canonical_url: None,
+            },
+        ])
+        .expect("find duplicates");
+        assert_eq!(matches.len(), 2);
+        assert!(!matches[0].history_id.is_empty());
+        assert!(matches[1].history_id.is_empty());
+    }
+
+    #[test]
+    fn prune_history_applies_age_and_entry_limits() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        let kept_file = make_temp_file("kept.mp4");
+        let now = Utc::now().timestamp();
+        let conn = get_db().expect("get db");
+        for (id, filepath, age_days) in [
+            ("old-missing", "/tmp/youwee-gone.mp4".to_string(), 90),
+            ("old-present", kept_file.to_string_lossy().to_string(), 60),
+            ("recent-1", "/tmp/recent-1.mp4".to_string(), 2),
+            ("recent-2", "/tmp/recent-2.mp4".to_string(), 1),
+        ] {
+            conn.execute(
+                "INSERT INTO history (id, url, title, filepath, downloaded_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, "https://example.com/v", id, filepath, now - age_days * 86_400],
+            )
+            .expect("insert history row");
+        }
+        drop(conn);
+
+        let removed = prune_history_in_db(&HistoryRetentionPolicy {
+            max_age_days: Some(30),
+            only_missing_files: true,
+            ..Default::default()
+        })
+        .expect("prune by age");
+        assert_eq!(removed, ["/tmp/youwee-gone.mp4"]);
+
+        let removed = prune_history_in_db(&HistoryRetentionPolicy {
+            max_entries: Some(2),
+            ..Default::default()
+        })
+        .expect("prune by count");
+        assert_eq!(removed, [kept_file.to_string_lossy().to_string()]);
+
+        let conn = get_db().expect("get db");
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+            .expect("count history");
+        assert_eq!(count, 2);
+        drop(conn);
+        let _ = fs::remove_dir_all(kept_file.parent().unwrap_or_else(|| Path::new("/")));
+    }
+
+    #[test]
+    fn toggle_favorite_flips_flag_and_filters_history() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        insert_history_row("fav", "/tmp/fav.mp4");
+        insert_history_row("plain", "/tmp/plain.mp4");
+
+        assert!(toggle_history_favorite_in_db("fav".to_string()).expect("star"));
+        let favorites = get_history_from_db(
+            None,
+            None,
+            None,
+            None,
+            Some(HistoryAdvancedFilters {
+                favorites_only: Some(true),
+                ..Default::default()
+            }),
+            None,
+        )
+        .expect("favorites");
+        assert_eq!(favorites.len(), 1);
+        assert!(favorites[0].favorite);
+
+        assert!(!toggle_history_favorite_in_db("fav".to_string()).expect("unstar"));
+        assert!(toggle_history_favorite_in_db("missing".to_string()).is_err());
+    }
+
+    #[test]
+    fn history_page_returns_total_and_facets_with_the_page() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        {
+            let conn = get_db().expect("get db");
+            for (id, source, format) in [
+                ("a", "youtube", "mp4"),
+                ("b", "youtube", "MP3"),
+                ("c", "vimeo", "mp4"),
+            ] {
+                conn.execute(
+                    "INSERT INTO history (id, url, title, filepath, source, format, downloaded_at) VALUES (?1, 'https://example.com', 'clip', '/tmp/clip', ?2, ?3, 0)",
+                    params![id, source, format],
+                )
+                .expect("insert history row");
+            }
+        }
+
+        let page = get_history_page_from_db(HistoryPageQuery {
+            limit: Some(1),
+            ..Default::default()
+        })
+        .expect("history page");
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.total, 3);
+        assert_eq!(
+            page.sources[0],
+            HistoryFacetCount {
+                value: "youtube".to_string(),
+                count: 2
+            }
+        );
+        assert_eq!(page.formats.len(), 2);
+        assert_eq!(page.formats[1].value, "mp3");
+
+        let filtered = get_history_page_from_db(HistoryPageQuery {
+            source: Some("vimeo".to_string()),
+            ..Default::default()
+        })
+        .expect("filtered page");
+        assert_eq!((filtered.total, filtered.entries.len()), (1, 1));
+        // The source facet still lists every source while one is selected
+        assert_eq!(filtered.sources.len(), 2);
+        assert_eq!(filtered.formats.len(), 1);
+
+        let by_format = get_history_page_from_db(HistoryPageQuery {
+            filters: Some(HistoryAdvancedFilters {
+                formats: Some(vec!["mp3".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .expect("format page");
+        assert_eq!(by_format.total, 1);
+        assert_eq!(by_format.formats.len(), 2);
+        assert_eq!(by_format.sources.len(), 1);
+    }
+
+    #[test]
+    fn history_notes_and_metadata_are_searchable() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        insert_history_row("noted", "/tmp/noted.mp4");
+        insert_history_row("other", "/tmp/other.mp4");
+
+        update_history_note_in_db(
+            "noted".to_string(),
+            "Reference for edit at 3:20".to_string(),
+        )
+        .expect("update note");
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("client".to_string(), serde_json::json!("Acme"));
+        update_history_custom_metadata_in_db("noted".to_string(), metadata)
+            .expect("update metadata");
+
+        for search in ["reference", "acme"] {
+            let result = get_history_from_db(
+                Some(50),
+                Some(0),
+                None,
+                Some(search.to_string()),
+                Some(HistoryAdvancedFilters {
+                    search_scope: Some(HistorySearchScope::Notes),
+                    ..Default::default()
+                }),
+                None,
+            )
+            .expect("search notes");
+            assert_eq!(result.len(), 1, "search {search}");
+            assert_eq!(
+                result[0].notes.as_deref(),
+                Some("Reference for edit at 3:20")
+            );
+            assert_eq!(
+                result[0]
+                    .custom_metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.get("client")),
+                Some(&serde_json::json!("Acme"))
+            );
+        }
+    }
 }
@@ -1,11 +1,29 @@
+mod ai_usage;
+mod channel_rules;
 mod channels;
 mod connection;
+mod download_journal;
+mod download_profiles;
 mod download_queue;
+mod github_cache;
 mod history;
 mod logs;
+mod prompt_templates;
+mod recent_inputs;
+mod recipes;
+mod recovery;
 
+pub use ai_usage::*;
+pub use channel_rules::*;
 pub use channels::*;
 pub use connection::*;
+pub use download_journal::*;
+pub use download_profiles::*;
 pub use download_queue::*;
+pub use github_cache::*;
 pub use history::*;
 pub use logs::*;
+pub use prompt_templates::*;
+pub use recent_inputs::*;
+pub use recipes::*;
+pub use recovery::*;
@@ -0,0 +1,149 @@
+use super::get_db;
+use crate::types::PromptTemplate;
+use crate::utils::prompt_template_variables;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+pub(super) fn create_prompt_templates_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS prompt_templates (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            template TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create prompt_templates table: {}", e))?;
+    Ok(())
+}
+
+fn template_from_row(row: &Row) -> rusqlite::Result<PromptTemplate> {
+    let template: String = row.get(3)?;
+    Ok(PromptTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        variables: prompt_template_variables(&template),
+        template,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+pub fn list_prompt_templates_from_db() -> Result<Vec<PromptTemplate>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, description, template, created_at, updated_at
+             FROM prompt_templates ORDER BY name COLLATE NOCASE",
+        )
+        .map_err(|e| format!("Failed to load prompt templates: {}", e))?;
+    let templates = stmt
+        .query_map([], template_from_row)
+        .map_err(|e| format!("Failed to load prompt templates: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to load prompt templates: {}", e))?;
+    Ok(templates)
+}
+
+pub fn get_prompt_template_from_db(id: &str) -> Result<Option<PromptTemplate>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT id, name, description, template, created_at, updated_at
+         FROM prompt_templates WHERE id = ?1",
+        params![id],
+        template_from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load prompt template: {}", e))
+}
+
+/// Create a template, or update it when `id` names an existing one
+pub fn save_prompt_template_to_db(
+    id: Option<String>,
+    name: &str,
+    description: Option<&str>,
+    template: &str,
+) -> Result<PromptTemplate, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    if template.trim().is_empty() {
+        return Err("Template text cannot be empty".to_string());
+    }
+    let description = description.map(str::trim).filter(|d| !d.is_empty());
+    let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let now = Utc::now().timestamp();
+    {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO prompt_templates (id, name, description, template, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                description = excluded.description,
+                template = excluded.template,
+                updated_at = excluded.updated_at",
+            params![id, name, description, template, now],
+        )
+        .map_err(|e| format!("Failed to save prompt template: {}", e))?;
+    }
+    get_prompt_template_from_db(&id)?.ok_or_else(|| "Prompt template not found".to_string())
+}
+
+pub fn delete_prompt_template_from_db(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM prompt_templates WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete prompt template: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    #[test]
+    fn templates_are_saved_with_their_variables() {
+        let _guard = db_test_guard();
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+        {
+            let conn = get_db().expect("get db");
+            create_prompt_templates_table(&conn).expect("create prompt templates");
+            conn.execute("DELETE FROM prompt_templates", []).unwrap();
+        }
+
+        let saved = save_prompt_template_to_db(
+            None,
+            " Shrink clip ",
+            Some(""),
+            "Cut {selection} and compress to {target_size}",
+        )
+        .unwrap();
+        assert_eq!(saved.name, "Shrink clip");
+        assert_eq!(saved.description, None);
+        assert_eq!(saved.variables, ["selection", "target_size"]);
+        assert!(save_prompt_template_to_db(None, "Empty", None, " ").is_err());
+
+        let updated = save_prompt_template_to_db(
+            Some(saved.id.clone()),
+            "Shrink",
+            None,
+            "Scale to {resolution}",
+        )
+        .unwrap();
+        assert_eq!(updated.variables, ["resolution"]);
+        assert_eq!(list_prompt_templates_from_db().unwrap(), vec![updated]);
+
+        delete_prompt_template_from_db(&saved.id).unwrap();
+        assert!(list_prompt_templates_from_db().unwrap().is_empty());
+    }
+}
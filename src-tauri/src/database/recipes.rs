@@ -0,0 +1,149 @@
+use super::get_db;
+use crate::types::{DownloadProfileSettings, DownloadRecipe, RecipeStep};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+pub(super) fn create_download_recipes_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_recipes (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            download_json TEXT NOT NULL,
+            steps_json TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_recipes table: {}", e))?;
+    Ok(())
+}
+
+fn recipe_from_row(row: &Row) -> rusqlite::Result<DownloadRecipe> {
+    let download_json: String = row.get(2)?;
+    let steps_json: String = row.get(3)?;
+    Ok(DownloadRecipe {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        download: serde_json::from_str(&download_json).unwrap_or_default(),
+        steps: serde_json::from_str(&steps_json).unwrap_or_default(),
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+pub fn list_download_recipes_from_db() -> Result<Vec<DownloadRecipe>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, download_json, steps_json, created_at, updated_at
+             FROM download_recipes ORDER BY name COLLATE NOCASE",
+        )
+        .map_err(|e| format!("Failed to load recipes: {}", e))?;
+    let recipes = stmt
+        .query_map([], recipe_from_row)
+        .map_err(|e| format!("Failed to load recipes: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to load recipes: {}", e))?;
+    Ok(recipes)
+}
+
+pub fn get_download_recipe_from_db(id: &str) -> Result<Option<DownloadRecipe>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT id, name, download_json, steps_json, created_at, updated_at
+         FROM download_recipes WHERE id = ?1",
+        params![id],
+        recipe_from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load recipe: {}", e))
+}
+
+/// Create a recipe, or update it when `id` names an existing one
+pub fn save_download_recipe_to_db(
+    id: Option<String>,
+    name: &str,
+    download: &DownloadProfileSettings,
+    steps: &[RecipeStep],
+) -> Result<DownloadRecipe, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Recipe name cannot be empty".to_string());
+    }
+    let download_json = serde_json::to_string(download).map_err(|e| e.to_string())?;
+    let steps_json = serde_json::to_string(steps).map_err(|e| e.to_string())?;
+    let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let now = Utc::now().timestamp();
+    {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO download_recipes (id, name, download_json, steps_json, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                download_json = excluded.download_json,
+                steps_json = excluded.steps_json,
+                updated_at = excluded.updated_at",
+            params![id, name, download_json, steps_json, now],
+        )
+        .map_err(|e| format!("Failed to save recipe: {}", e))?;
+    }
+    get_download_recipe_from_db(&id)?.ok_or_else(|| "Recipe not found".to_string())
+}
+
+pub fn delete_download_recipe_from_db(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM download_recipes WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete recipe: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    #[test]
+    fn recipes_keep_their_download_settings_and_steps() {
+        let _guard = db_test_guard();
+        if DB_CONNECTION.get().is_none() {
+            let conn = Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+        {
+            let conn = get_db().expect("get db");
+            create_download_recipes_table(&conn).expect("create recipes");
+            conn.execute("DELETE FROM download_recipes", []).unwrap();
+        }
+
+        let download = DownloadProfileSettings {
+            quality: Some("1080".to_string()),
+            ..Default::default()
+        };
+        let steps = vec![
+            RecipeStep::ExtractAudio {
+                format: "mp3".to_string(),
+                bitrate: None,
+                keep_video: false,
+            },
+            RecipeStep::NormalizeLoudness { target_lufs: None },
+            RecipeStep::MoveTo {
+                folder: "/music".to_string(),
+            },
+        ];
+        let recipe = save_download_recipe_to_db(None, " Music ", &download, &steps).unwrap();
+        assert_eq!(recipe.name, "Music");
+        assert_eq!(recipe.steps, steps);
+        assert!(save_download_recipe_to_db(None, " ", &download, &steps).is_err());
+
+        let updated =
+            save_download_recipe_to_db(Some(recipe.id.clone()), "Music", &download, &[]).unwrap();
+        assert!(updated.steps.is_empty());
+        assert_eq!(list_download_recipes_from_db().unwrap(), vec![updated]);
+
+        delete_download_recipe_from_db(&recipe.id).unwrap();
+        assert!(get_download_recipe_from_db(&recipe.id).unwrap().is_none());
+    }
+}
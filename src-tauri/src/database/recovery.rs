@@ -0,0 +1,266 @@
+use rusqlite::{params, Connection, ErrorCode, OpenFlags};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::connection::database_sidecar_path;
+use crate::types::DatabaseRecoveryReport;
+
+const BACKUP_COUNT: usize = 3;
+const BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn backup_path(db_path: &Path, index: usize) -> Option<PathBuf> {
+    database_sidecar_path(db_path, &format!(".bak{}", index))
+}
+
+/// SQLite errors that mean the file itself is damaged, as opposed to locked,
+/// unreadable or out of disk
+fn is_corruption_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+    )
+}
+
+/// Run `PRAGMA quick_check`. Returns `Ok(Some(problems))` only when the file
+/// is corrupt; an error means the check itself could not run (locked,
+/// permissions, I/O), which must not be treated as corruption. A missing file
+/// counts as healthy.
+pub fn check_database_integrity(path: &Path) -> Result<Option<String>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let problems =
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).and_then(|conn| {
+            let mut stmt = conn.prepare("PRAGMA quick_check")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect::<Result<Vec<_>, _>>()
+        });
+    match problems {
+        Ok(problems) => match problems.as_slice() {
+            [ok] if ok.eq_ignore_ascii_case("ok") => Ok(None),
+            _ => Ok(Some(problems.join("; "))),
+        },
+        Err(e) if is_corruption_error(&e) => Ok(Some(e.to_string())),
+        Err(e) => Err(format!("Failed to check database: {e}")),
+    }
+}
+
+/// Snapshot a healthy database as `.bak1`, shifting older snapshots down.
+/// Skipped when the newest snapshot is less than a day old.
+pub fn rotate_database_backups(db_path: &Path) -> Result<(), String> {
+    let Some(newest) = backup_path(db_path, 1) else {
+        return Ok(());
+    };
+    let is_recent = std::fs::metadata(&newest)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < BACKUP_INTERVAL);
+    if !db_path.exists() || is_recent {
+        return Ok(());
+    }
+
+    for index in (1..BACKUP_COUNT).rev() {
+        if let (Some(from), Some(to)) =
+            (backup_path(db_path, index), backup_path(db_path, index + 1))
+        {
+            if from.exists() {
+                std::fs::rename(&from, &to).ok();
+            }
+        }
+    }
+
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open database for backup: {e}"))?;
+    conn.execute(
+        "VACUUM INTO ?1",
+        params![newest.to_string_lossy().to_string()],
+    )
+    .map_err(|e| format!("Failed to back up database: {e}"))?;
+    Ok(())
+}
+
+/// Copy the rows of every table readable in `source` into the empty database
+/// at `target`. Returns (tables, rows copied, rows lost).
+fn salvage_rows(source: &Path, target: &Path) -> Result<(usize, usize, usize), String> {
+    let conn = Connection::open(target).map_err(|e| format!("Failed to create database: {e}"))?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS salvage",
+        params![source.to_string_lossy().to_string()],
+    )
+    .map_err(|e| format!("Failed to open corrupt database: {e}"))?;
+
+    // FTS indexes and their shadow tables are rebuilt by init_database
+    let tables: Vec<(String, String)> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT name, sql FROM salvage.sqlite_master
+                 WHERE type = 'table' AND sql IS NOT NULL
+                   AND name NOT LIKE 'sqlite_%' AND name NOT LIKE 'history_search_fts%'",
+            )
+            .map_err(|e| format!("Failed to read corrupt schema: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to read corrupt schema: {e}"))?;
+        rows.filter_map(|row| row.ok()).collect()
+    };
+
+    let (mut recovered_tables, mut copied, mut lost) = (0, 0, 0);
+    for (name, sql) in tables {
+        if conn.execute(&sql, []).is_err() {
+            continue;
+        }
+        let quoted = format!("\"{}\"", name.replace('"', "\"\""));
+        let bulk = format!("INSERT OR IGNORE INTO main.{quoted} SELECT * FROM salvage.{quoted}");
+        if let Ok(rows) = conn.execute(&bulk, []) {
+            recovered_tables += 1;
+            copied += rows;
+            continue;
+        }
+
+        // A damaged page aborts the bulk copy; fall back to one row at a time
+        let rowids: Vec<i64> = conn
+            .prepare(&format!("SELECT rowid FROM salvage.{quoted}"))
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| row.get(0))
+                    .map(|rows| rows.filter_map(|row| row.ok()).collect())
+            })
+            .unwrap_or_default();
+        let single = format!(
+            "INSERT OR IGNORE INTO main.{quoted} SELECT * FROM salvage.{quoted} WHERE rowid = ?1"
+        );
+        let mut table_rows = 0;
+        for rowid in rowids {
+            match conn.execute(&single, params![rowid]) {
+                Ok(rows) => table_rows += rows,
+                Err(_) => lost += 1,
+            }
+        }
+        if table_rows > 0 {
+            recovered_tables += 1;
+            copied += table_rows;
+        }
+    }
+
+    conn.execute("DETACH DATABASE salvage", []).ok();
+    Ok((recovered_tables, copied, lost))
+}
+
+/// Move a corrupt database aside and start a fresh one at `db_path` holding
+/// whatever rows can still be read, falling back to the newest backup.
+pub fn recover_database(
+    db_path: &Path,
+    integrity_error: String,
+) -> Result<DatabaseRecoveryReport, String> {
+    let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    let corrupt_path = database_sidecar_path(db_path, &format!(".corrupt-{stamp}"))
+        .ok_or("Invalid database path")?;
+    std::fs::rename(db_path, &corrupt_path)
+        .map_err(|e| format!("Failed to move corrupt database aside: {e}"))?;
+    for suffix in ["-wal", "-shm"] {
+        if let (Some(from), Some(to)) = (
+            database_sidecar_path(db_path, suffix),
+            database_sidecar_path(&corrupt_path, suffix),
+        ) {
+            if from.exists() {
+                std::fs::rename(from, to).ok();
+            }
+        }
+    }
+
+    let mut report = DatabaseRecoveryReport {
+        corrupt_path: corrupt_path.to_string_lossy().to_string(),
+        integrity_error,
+        restored_from_backup: false,
+        tables_recovered: 0,
+        rows_recovered: 0,
+        rows_lost: 0,
+    };
+    match salvage_rows(&corrupt_path, db_path) {
+        Ok((tables, rows, lost)) if rows > 0 => {
+            report.tables_recovered = tables;
+            report.rows_recovered = rows;
+            report.rows_lost = lost;
+            return Ok(report);
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Could not salvage rows from corrupt database: {}", e),
+    }
+
+    // Nothing readable: start over from the newest healthy backup instead
+    std::fs::remove_file(db_path).ok();
+    let backup = (1..=BACKUP_COUNT)
+        .filter_map(|index| backup_path(db_path, index))
+        .find(|path| path.exists() && matches!(check_database_integrity(path), Ok(None)));
+    if let Some(backup) = backup {
+        std::fs::copy(&backup, db_path)
+            .map_err(|e| format!("Failed to restore database backup: {e}"))?;
+        report.restored_from_backup = true;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("youwee-db-recovery-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn recovery_salvages_rows_into_a_fresh_database() {
+        let dir = temp_db_dir();
+        let db_path = dir.join("youwee.db");
+        let conn = Connection::open(&db_path).expect("open database");
+        conn.execute_batch(
+            "CREATE TABLE logs (id TEXT PRIMARY KEY, message TEXT NOT NULL);
+            INSERT INTO logs VALUES ('a', 'first'), ('b', 'second');",
+        )
+        .expect("seed database");
+        drop(conn);
+        assert_eq!(check_database_integrity(&db_path), Ok(None));
+
+        let report = recover_database(&db_path, "test".to_string()).expect("recover");
+        assert_eq!((report.tables_recovered, report.rows_recovered), (1, 2));
+        assert!(Path::new(&report.corrupt_path).exists());
+        let count: i64 = Connection::open(&db_path)
+            .expect("open recovered database")
+            .query_row("SELECT COUNT(*) FROM logs", [], |row| row.get(0))
+            .expect("count rows");
+        assert_eq!(count, 2);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn a_database_that_cannot_be_opened_is_not_reported_as_corrupt() {
+        let dir = temp_db_dir();
+        // A directory exists but can't be opened as a database file
+        assert!(check_database_integrity(&dir).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn unreadable_database_is_restored_from_backup() {
+        let dir = temp_db_dir();
+        let db_path = dir.join("youwee.db");
+        Connection::open(&db_path)
+            .expect("open database")
+            .execute_batch("CREATE TABLE logs (id TEXT PRIMARY KEY);")
+            .expect("seed database");
+        rotate_database_backups(&db_path).expect("backup");
+        std::fs::write(&db_path, b"not a sqlite database").expect("corrupt database");
+
+        let error = check_database_integrity(&db_path)
+            .expect("check runs")
+            .expect("corrupt database");
+        let report = recover_database(&db_path, error).expect("recover");
+        assert!(report.restored_from_backup);
+        assert_eq!(check_database_integrity(&db_path), Ok(None));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
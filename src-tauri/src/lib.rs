@@ -3,54 +3,29 @@
 //! This is the main entry point for the Tauri application.
 //! The codebase is organized into the following modules:
 //!
-//! - `types`: Data structures (VideoInfo, DownloadProgress, etc.)
+//! - `types`: Data structures and event payloads (VideoInfo, DownloadProgress, etc.)
 //! - `database`: SQLite operations for logs and history
 //! - `utils`: Helper functions (format_size, parse_progress, etc.)
 //! - `services`: Core services (yt-dlp, FFmpeg, Deno runtime)
 //! - `commands`: Tauri commands exposed to the frontend
+//! - `tray`: System tray icon and menu
 
 pub mod commands;
 pub mod database;
 pub mod services;
+mod tray;
 pub mod types;
 pub mod utils;
 
-use serde::Deserialize;
+pub use tray::rebuild_tray_menu;
+
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Mutex;
-use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder};
-use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{Emitter, Manager};
 use tauri_plugin_deep_link::DeepLinkExt;
 
 /// Whether to hide the dock icon when closing the window (macOS only)
 static HIDE_DOCK_ON_CLOSE: AtomicBool = AtomicBool::new(false);
 
-/// Current UI language for tray menu translations (default: "en")
-static TRAY_LANG: Mutex<String> = Mutex::new(String::new());
-
-/// Schedule status text for tray menu (empty = no schedule active)
-static TRAY_SCHEDULE_STATUS: Mutex<String> = Mutex::new(String::new());
-
-/// Download queue summary shown in the tray menu.
-static TRAY_DOWNLOAD_STATUS: Mutex<TrayDownloadStatus> = Mutex::new(TrayDownloadStatus {
-    pending: 0,
-    downloading: 0,
-    completed: 0,
-    error: 0,
-    active: false,
-});
-
-#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct TrayDownloadStatus {
-    pending: u32,
-    downloading: u32,
-    completed: u32,
-    error: u32,
-    active: bool,
-}
-
 #[cfg(target_os = "linux")]
 fn configure_linux_webkit_env() {
     // Work around WebKitGTK/GBM crashes seen on some Arch-based systems.
@@ -61,7 +36,7 @@ fn configure_linux_webkit_env() {
 }
 
 /// Show the main window and restore dock icon if needed
-fn show_main_window(app_handle: &tauri::AppHandle) {
+pub(crate) fn show_main_window(app_handle: &tauri::AppHandle) {
     if let Some(window) = app_handle.get_webview_window("main") {
         #[cfg(target_os = "macos")]
         {
@@ -91,46 +66,17 @@ fn set_hide_dock_on_close(hide: bool) {
     HIDE_DOCK_ON_CLOSE.store(hide, Ordering::SeqCst);
 }
 
-/// Tauri command: rebuild the system tray menu with current channel info
-#[tauri::command]
-fn rebuild_tray_menu_cmd(app: tauri::AppHandle, lang: Option<String>) {
-    if let Some(l) = lang {
-        if let Ok(mut stored) = TRAY_LANG.lock() {
-            *stored = l;
-        }
-    }
-    rebuild_tray_menu(&app);
-}
-
-/// Tauri command: update the schedule status shown in the tray menu
-#[tauri::command]
-fn update_tray_schedule(app: tauri::AppHandle, status: String) {
-    if let Ok(mut stored) = TRAY_SCHEDULE_STATUS.lock() {
-        *stored = status;
-    }
-    rebuild_tray_menu(&app);
-}
-
-/// Tauri command: update the download queue summary shown in the tray menu
-#[tauri::command]
-fn update_tray_download_status(app: tauri::AppHandle, status: TrayDownloadStatus) {
-    let mut should_rebuild = false;
-    if let Ok(mut stored) = TRAY_DOWNLOAD_STATUS.lock() {
-        if *stored != status {
-            *stored = status;
-            should_rebuild = true;
-        }
-    }
-    if should_rebuild {
-        rebuild_tray_menu(&app);
-    }
-}
-
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     #[cfg(target_os = "linux")]
     configure_linux_webkit_env();
 
+    // Keep WebView2 storage (frontend settings) on the portable drive too
+    #[cfg(windows)]
+    if let Some(dir) = utils::portable_data_dir() {
+        std::env::set_var("WEBVIEW2_USER_DATA_FOLDER", dir.join("webview"));
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
             // Protocol-handler style links (youwee://download?...) embedded in argv.
@@ -284,12 +230,22 @@ pub fn run() {
             if let Err(e) = database::init_database(&app.handle()) {
                 log::error!("Failed to initialize database: {}", e);
             }
+            services::load_github_token(app.handle());
+
+            // Sweep scratch files and fragments left behind by the last session
+            let cleanup_handle = app.handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                services::run_temp_janitor(&cleanup_handle);
+            });
 
             // Start background channel polling
             services::polling::start_polling(app.handle().clone());
 
+            // Report dependency, database and resume status to the frontend
+            services::spawn_app_health_report(app.handle().clone());
+
             // Setup system tray
-            setup_tray(app)?;
+            tray::setup_tray(app)?;
 
             if has_initial_links || !has_cli_request {
                 show_main_window(&app.handle());
@@ -323,14 +279,33 @@ pub fn run() {
             // Download commands
             commands::download_video,
             commands::stop_download,
+            commands::export_download_command,
+            commands::redownload,
+            commands::retry_failed,
+            commands::set_audio_companion_defaults,
+            commands::set_download_guard,
+            commands::set_site_download_limits,
+            commands::get_site_download_limits,
+            commands::check_compatibility,
+            commands::download_direct_file,
+            commands::get_podcast_feed,
+            commands::download_podcast_episode,
             commands::download_gallery,
             commands::stop_gallery_download,
             // Video info commands
             commands::get_video_basic_info,
             commands::get_video_info,
+            commands::get_video_comments,
+            commands::get_related_videos,
             commands::get_playlist_entries,
             commands::search_youtube_videos,
+            commands::search_videos,
+            commands::cancel_video_search,
             commands::get_available_subtitles,
+            commands::download_subtitles_only,
+            commands::list_formats_raw,
+            commands::set_preferred_subtitle_langs_cmd,
+            commands::set_site_extractor_args_cmd,
             commands::get_video_transcript,
             // yt-dlp commands
             commands::get_ytdlp_version,
@@ -344,6 +319,7 @@ pub fn run() {
             commands::get_all_ytdlp_versions_cmd,
             commands::check_ytdlp_channel_update,
             commands::download_ytdlp_channel,
+            commands::update_and_retry,
             // FFmpeg commands
             commands::check_ffmpeg,
             commands::get_ffmpeg_source_cmd,
@@ -356,6 +332,9 @@ pub fn run() {
             commands::check_deno_update,
             commands::download_deno,
             commands::check_gallerydl,
+            // First-run setup
+            commands::setup_status,
+            commands::run_setup,
             // Browser detection
             commands::detect_installed_browsers,
             commands::get_browser_profiles,
@@ -366,6 +345,7 @@ pub fn run() {
             commands::add_log,
             commands::clear_logs,
             commands::export_logs,
+            commands::get_database_recovery,
             // History commands
             commands::add_history,
             commands::get_history,
@@ -373,7 +353,11 @@ pub fn run() {
             commands::find_duplicate_downloads,
             commands::delete_history,
             commands::clear_history,
+            commands::set_history_retention,
+            commands::set_history_privacy_mode,
+            commands::prune_history,
             commands::get_history_count,
+            commands::get_history_page,
             commands::get_tags,
             commands::get_collections,
             commands::create_collection,
@@ -382,9 +366,16 @@ pub fn run() {
             commands::assign_history_tags,
             commands::assign_history_collections,
             commands::remove_history_tag,
+            commands::toggle_favorite,
             commands::remove_history_from_collection,
             commands::open_file_location,
             commands::check_file_exists,
+            commands::verify_download,
+            commands::import_external_history,
+            commands::export_playlist,
+            commands::get_source_info,
+            commands::copy_to_clipboard,
+            commands::save_thumbnail_as,
             // Asset scope & history helpers
             commands::allow_asset_file,
             commands::sync_asset_scope_paths,
@@ -392,12 +383,25 @@ pub fn run() {
             commands::sync_history_renamed_entry,
             commands::split_media_segments,
             commands::update_summary,
+            commands::update_history_note,
+            commands::update_history_metadata,
             commands::add_summary_only_history,
             commands::open_macos_privacy_settings,
             // AI commands
             commands::save_ai_config,
             commands::get_ai_config,
+            commands::get_stored_secret,
+            commands::set_stored_secret,
+            commands::set_github_api_token,
             commands::test_ai_connection,
+            commands::get_ai_profiles,
+            commands::save_ai_profile,
+            commands::delete_ai_profile,
+            commands::set_ai_feature_route,
+            commands::test_ai_profile,
+            commands::get_ai_usage,
+            commands::clear_ai_usage,
+            commands::set_ai_monthly_budget_cmd,
             commands::generate_video_summary,
             commands::generate_summary_with_options,
             commands::cancel_summary_generation,
@@ -407,29 +411,88 @@ pub fn run() {
             // Processing commands
             commands::get_video_metadata,
             commands::detect_shot_changes,
+            commands::detect_silence,
+            commands::export_frames,
+            commands::convert_audio_batch,
+            commands::export_lossless,
+            commands::compare_videos,
+            commands::estimate_processing_output,
+            commands::add_chapters,
+            commands::generate_chapters,
+            commands::find_in_transcript,
+            commands::extract_clip_from_match,
             commands::get_image_metadata,
             commands::get_processing_attachment_info,
             commands::generate_processing_command,
             commands::generate_quick_action_command,
             commands::execute_ffmpeg_command,
+            commands::preview_processing,
+            commands::submit_processing_graph,
+            commands::cancel_processing_graph,
+            commands::generate_processing_plan,
+            commands::execute_processing_plan,
+            commands::discard_processing_plan,
+            commands::get_download_recipes,
+            commands::save_download_recipe,
+            commands::delete_download_recipe,
+            commands::apply_download_recipe,
             commands::cancel_ffmpeg,
+            commands::set_process_priority,
+            commands::set_sleep_prevention,
+            commands::set_network_profile,
+            commands::set_metadata_network,
+            commands::measure_connection_speed,
+            commands::get_active_jobs,
+            commands::confirm_quit,
+            commands::get_interrupted_downloads,
+            commands::clear_interrupted_downloads_cmd,
+            commands::get_crashed_downloads,
+            commands::discard_crashed_downloads_cmd,
+            commands::set_post_queue_action_cmd,
+            commands::get_post_queue_action_cmd,
+            commands::notify_queue_finished,
+            commands::cancel_post_queue_action_cmd,
             commands::get_processing_history,
             commands::save_processing_job,
+            commands::create_processing_session_from_history,
             commands::update_processing_job,
             commands::delete_processing_job,
             commands::clear_processing_history,
+            commands::rerun_processing_job,
+            commands::open_processing_output,
             commands::get_processing_presets,
+            commands::list_prompt_templates,
+            commands::save_prompt_template,
+            commands::delete_prompt_template,
+            commands::render_prompt,
+            commands::export_prompt_templates,
+            commands::import_prompt_templates,
             commands::save_processing_preset,
             commands::delete_processing_preset,
             commands::generate_video_preview,
             commands::generate_video_thumbnail,
             commands::generate_audio_preview,
+            commands::cancel_preview,
             commands::check_preview_exists,
             commands::cleanup_previews,
+            commands::get_cache_stats,
+            commands::clear_cache,
+            commands::set_max_cache_size,
             // Whisper commands
             commands::transcribe_video_with_whisper,
             commands::transcribe_url_with_whisper,
             commands::generate_subtitles_with_whisper,
+            commands::transcribe_video_segments,
+            commands::export_transcript_file,
+            commands::list_whisper_models,
+            commands::download_whisper_model_cmd,
+            commands::delete_whisper_model_cmd,
+            commands::set_whisper_model_defaults_cmd,
+            commands::get_whisper_backends,
+            commands::set_whisper_backend,
+            commands::benchmark_whisper,
+            commands::generate_lrc,
+            commands::extract_links,
             // Metadata commands
             commands::fetch_metadata,
             commands::extract_data_rows,
@@ -477,10 +540,29 @@ pub fn run() {
             commands::set_telegram_config,
             commands::get_telegram_status,
             commands::send_telegram_reply,
+            commands::set_notification_webhooks,
+            commands::test_webhook,
             commands::load_download_queue,
             commands::save_download_queue,
             commands::clear_download_queue,
+            commands::get_download_profiles,
+            commands::save_download_profile,
+            commands::delete_download_profile,
+            commands::duplicate_download_profile,
+            commands::get_recent_inputs,
+            commands::get_suggested_input_options,
+            commands::get_channel_rules,
+            commands::save_channel_rule,
+            commands::delete_channel_rule,
+            commands::create_channel_rule_from_history,
+            commands::export_settings,
+            commands::import_settings,
             commands::is_flatpak_environment,
+            commands::is_portable_mode,
+            commands::test_extraction,
+            commands::verify_js_runtime,
+            commands::get_event_schema,
+            commands::get_app_health,
             // External deep-link commands
             commands::consume_pending_external_links,
             commands::consume_pending_cli_download_requests,
@@ -489,9 +571,9 @@ pub fn run() {
             commands::install_cli_shortcut,
             // System commands
             set_hide_dock_on_close,
-            rebuild_tray_menu_cmd,
-            update_tray_schedule,
-            update_tray_download_status,
+            tray::rebuild_tray_menu_cmd,
+            tray::update_tray_schedule,
+            tray::update_tray_download_status,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -508,406 +590,18 @@ pub fn run() {
                         show_main_window(_app_handle);
                     }
                 }
-                tauri::RunEvent::ExitRequested { .. } => {
-                    // Stop polling on exit
-                    services::polling::stop_polling();
-                }
-                _ => {}
-            }
-        });
-}
-
-/// Setup system tray icon and menu
-fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    // Build a minimal initial menu (will be replaced by rebuild_tray_menu)
-    let show = MenuItemBuilder::with_id("show", "Open Youwee").build(app)?;
-    let quit = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
-    let menu = MenuBuilder::new(app)
-        .item(&show)
-        .separator()
-        .item(&quit)
-        .build()?;
-
-    #[cfg(target_os = "macos")]
-    let icon = tauri::image::Image::from_bytes(include_bytes!("../icons/tray-template.png"))
-        .expect("Failed to load tray icon");
-    #[cfg(not(target_os = "macos"))]
-    let icon = tauri::image::Image::from_bytes(include_bytes!("../icons/32x32.png"))
-        .expect("Failed to load tray icon");
-
-    let app_handle = app.handle().clone();
-    let app_handle_menu = app.handle().clone();
-
-    let tray_builder = TrayIconBuilder::with_id("main-tray")
-        .icon(icon)
-        .tooltip("Youwee")
-        .menu(&menu)
-        .on_menu_event(move |_tray, event| {
-            let id = event.id().as_ref();
-            if let Some(channel_id) = id.strip_prefix("ch_") {
-                // Channel item clicked: open app and navigate to channel
-                show_main_window(&app_handle_menu);
-                let _ = app_handle_menu.emit("tray-open-channel", channel_id.to_string());
-            } else {
-                match id {
-                    "check_now" => {
-                        services::polling::stop_polling();
-                        services::polling::start_polling(app_handle_menu.clone());
-                    }
-                    "settings" => {
-                        show_main_window(&app_handle_menu);
-                        let _ = app_handle_menu.emit("tray-open-settings", ());
-                    }
-                    "check_update" => {
-                        show_main_window(&app_handle_menu);
-                        let _ = app_handle_menu.emit("tray-check-update", ());
-                    }
-                    "show" => {
-                        show_main_window(&app_handle_menu);
-                    }
-                    "browser_extension" => {
-                        show_main_window(&app_handle_menu);
-                        let _ = app_handle_menu.emit("tray-open-extension", ());
-                    }
-                    "quit" => {
+                tauri::RunEvent::ExitRequested { api, .. } => {
+                    // Hold the exit until the frontend confirms interrupting running jobs
+                    if services::should_block_quit() {
+                        api.prevent_exit();
+                        show_main_window(_app_handle);
+                        services::emit_quit_requested(_app_handle);
+                    } else {
+                        // Stop polling on exit
                         services::polling::stop_polling();
-                        app_handle_menu.exit(0);
                     }
-                    _ => {}
                 }
-            }
-        })
-        .on_tray_icon_event(move |_tray, event| {
-            // Left-click tray icon -> show/focus window
-            if let TrayIconEvent::Click {
-                button: MouseButton::Left,
-                button_state: MouseButtonState::Up,
-                ..
-            } = event
-            {
-                show_main_window(&app_handle);
+                _ => {}
             }
         });
-
-    #[cfg(target_os = "macos")]
-    let tray_builder = tray_builder.icon_as_template(true);
-
-    tray_builder.build(app)?;
-
-    // Populate tray menu with channel info
-    rebuild_tray_menu(&app.handle());
-
-    Ok(())
-}
-
-/// Rebuild the system tray menu with current followed channels and new video counts.
-/// Called after follow/unfollow, polling finds new videos, or downloads complete.
-pub fn rebuild_tray_menu(app_handle: &tauri::AppHandle) {
-    if let Err(e) = rebuild_tray_menu_inner(app_handle) {
-        log::error!("Failed to rebuild tray menu: {}", e);
-    }
-}
-
-/// Get the stored tray language code
-fn get_tray_lang() -> String {
-    TRAY_LANG.lock().map(|l| l.clone()).unwrap_or_default()
-}
-
-/// Translate a tray menu key based on the current language
-fn tray_text(key: &str) -> &'static str {
-    let lang = get_tray_lang();
-    let lang = if lang.is_empty() || lang.starts_with("en") {
-        "en"
-    } else if lang.starts_with("vi") {
-        "vi"
-    } else if lang.starts_with("zh") {
-        "zh-CN"
-    } else if lang.starts_with("fr") {
-        "fr"
-    } else if lang.starts_with("ja") {
-        "ja"
-    } else if lang.starts_with("es") {
-        "es"
-    } else {
-        "en"
-    };
-
-    match (lang, key) {
-        // Vietnamese
-        ("vi", "followed_channels") => "Kênh đang theo dõi",
-        ("vi", "no_channels") => "Chưa theo dõi kênh nào",
-        ("vi", "new_suffix") => "mới",
-        ("vi", "download_queue") => "Hàng đợi tải xuống",
-        ("vi", "downloading") => "Đang tải",
-        ("vi", "pending") => "Chờ tải",
-        ("vi", "completed") => "Hoàn tất",
-        ("vi", "errors") => "Lỗi",
-        ("vi", "idle") => "Rảnh",
-        ("vi", "remote_download") => "Tải từ xa",
-        ("vi", "running") => "Đang chạy",
-        ("vi", "disabled") => "Đã tắt",
-        ("vi", "check_all") => "Kiểm tra kênh theo dõi ngay",
-        ("vi", "settings") => "Cài đặt",
-        ("vi", "check_update") => "Kiểm tra cập nhật...",
-        ("vi", "open") => "Mở Youwee",
-        ("vi", "browser_extension") => "Browser Extension",
-        ("vi", "quit") => "Thoát",
-        // Chinese
-        ("zh-CN", "followed_channels") => "已关注的频道",
-        ("zh-CN", "no_channels") => "尚未关注任何频道",
-        ("zh-CN", "new_suffix") => "个新视频",
-        ("zh-CN", "download_queue") => "下载队列",
-        ("zh-CN", "downloading") => "下载中",
-        ("zh-CN", "pending") => "等待中",
-        ("zh-CN", "completed") => "已完成",
-        ("zh-CN", "errors") => "错误",
-        ("zh-CN", "idle") => "空闲",
-        ("zh-CN", "remote_download") => "远程下载",
-        ("zh-CN", "running") => "运行中",
-        ("zh-CN", "disabled") => "已禁用",
-        ("zh-CN", "check_all") => "立即检查已关注频道",
-        ("zh-CN", "settings") => "设置",
-        ("zh-CN", "check_update") => "检查更新...",
-        ("zh-CN", "open") => "打开 Youwee",
-        ("zh-CN", "browser_extension") => "浏览器扩展",
-        ("zh-CN", "quit") => "退出",
-        // French
-        ("fr", "followed_channels") => "Chaines suivies",
-        ("fr", "no_channels") => "Aucune chaine suivie",
-        ("fr", "new_suffix") => "nouvelles",
-        ("fr", "download_queue") => "File de telechargement",
-        ("fr", "downloading") => "Telechargement",
-        ("fr", "pending") => "En attente",
-        ("fr", "completed") => "Termines",
-        ("fr", "errors") => "Erreurs",
-        ("fr", "idle") => "Inactif",
-        ("fr", "remote_download") => "Telechargement distant",
-        ("fr", "running") => "En cours",
-        ("fr", "disabled") => "Desactive",
-        ("fr", "check_all") => "Verifier les chaines suivies maintenant",
-        ("fr", "settings") => "Parametres",
-        ("fr", "check_update") => "Verifier les mises a jour...",
-        ("fr", "open") => "Ouvrir Youwee",
-        ("fr", "browser_extension") => "Extension navigateur",
-        ("fr", "quit") => "Quitter",
-        // Japanese
-        ("ja", "followed_channels") => "フォロー中のチャンネル",
-        ("ja", "no_channels") => "フォロー中のチャンネルはありません",
-        ("ja", "new_suffix") => "件の新着",
-        ("ja", "download_queue") => "ダウンロードキュー",
-        ("ja", "downloading") => "ダウンロード中",
-        ("ja", "pending") => "待機中",
-        ("ja", "completed") => "完了",
-        ("ja", "errors") => "エラー",
-        ("ja", "idle") => "待機中",
-        ("ja", "remote_download") => "リモートダウンロード",
-        ("ja", "running") => "実行中",
-        ("ja", "disabled") => "無効",
-        ("ja", "check_all") => "フォロー中チャンネルを今すぐチェック",
-        ("ja", "settings") => "設定",
-        ("ja", "check_update") => "更新をチェック...",
-        ("ja", "open") => "Youwee を開く",
-        ("ja", "browser_extension") => "ブラウザ拡張機能",
-        ("ja", "quit") => "終了",
-        // Spanish
-        ("es", "followed_channels") => "Canales seguidos",
-        ("es", "no_channels") => "No sigues ningún canal",
-        ("es", "new_suffix") => "nuevos",
-        ("es", "download_queue") => "Cola de descargas",
-        ("es", "downloading") => "Descargando",
-        ("es", "pending") => "Pendiente",
-        ("es", "completed") => "Completado",
-        ("es", "errors") => "Errores",
-        ("es", "idle") => "Inactivo",
-        ("es", "remote_download") => "Descarga remota",
-        ("es", "running") => "Ejecutándose",
-        ("es", "disabled") => "Deshabilitado",
-        ("es", "check_all") => "Comprobar canales seguidos ahora",
-        ("es", "settings") => "Ajustes",
-        ("es", "check_update") => "Buscar actualizaciones...",
-        ("es", "open") => "Abrir Youwee",
-        ("es", "browser_extension") => "Extensión de navegador",
-        ("es", "quit") => "Salir",
-        // English (default)
-        (_, "followed_channels") => "Followed Channels",
-        (_, "no_channels") => "No channels followed",
-        (_, "new_suffix") => "new",
-        (_, "download_queue") => "Download Queue",
-        (_, "downloading") => "Downloading",
-        (_, "pending") => "Pending",
-        (_, "completed") => "Completed",
-        (_, "errors") => "Errors",
-        (_, "idle") => "Idle",
-        (_, "remote_download") => "Remote Download",
-        (_, "running") => "Running",
-        (_, "disabled") => "Disabled",
-        (_, "check_all") => "Check Followed Channels Now",
-        (_, "settings") => "Settings",
-        (_, "check_update") => "Check for Updates...",
-        (_, "open") => "Open Youwee",
-        (_, "browser_extension") => "Browser Extension",
-        (_, "quit") => "Quit",
-        _ => "???",
-    }
-}
-
-fn tray_download_status() -> TrayDownloadStatus {
-    TRAY_DOWNLOAD_STATUS
-        .lock()
-        .map(|status| status.clone())
-        .unwrap_or(TrayDownloadStatus {
-            pending: 0,
-            downloading: 0,
-            completed: 0,
-            error: 0,
-            active: false,
-        })
-}
-
-fn tray_download_summary(status: &TrayDownloadStatus) -> String {
-    let total = status.pending + status.downloading + status.completed + status.error;
-    let activity = if status.active || status.downloading > 0 {
-        tray_text("downloading")
-    } else {
-        tray_text("idle")
-    };
-    format!("{} ({})", activity, total)
-}
-
-fn tray_remote_status_label() -> String {
-    let status = services::telegram::get_status();
-    let state = match status.state {
-        services::telegram::TelegramStatusState::Running => tray_text("running"),
-        services::telegram::TelegramStatusState::Disabled => tray_text("disabled"),
-        services::telegram::TelegramStatusState::Error => tray_text("errors"),
-    };
-
-    if let Some(message) = status.message.filter(|message| !message.trim().is_empty()) {
-        format!("{}: {}", state, message)
-    } else {
-        state.to_string()
-    }
-}
-
-fn rebuild_tray_menu_inner(
-    app_handle: &tauri::AppHandle,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let channels = database::get_followed_channels_db().unwrap_or_default();
-    let channel_count = channels.len();
-    let download_status = tray_download_status();
-
-    // Build channel submenu
-    let submenu_label = format!("{} ({})", tray_text("followed_channels"), channel_count);
-    let mut submenu = SubmenuBuilder::new(app_handle, &submenu_label);
-
-    if channels.is_empty() {
-        let item = MenuItemBuilder::with_id("no_channels", tray_text("no_channels"))
-            .enabled(false)
-            .build(app_handle)?;
-        submenu = submenu.item(&item);
-    } else {
-        for ch in &channels {
-            let count = database::get_new_videos_count_db(Some(ch.id.clone())).unwrap_or(0);
-            let label = if count > 0 {
-                format!("{} ({} {})", ch.name, count, tray_text("new_suffix"))
-            } else {
-                ch.name.clone()
-            };
-            let item_id = format!("ch_{}", ch.id);
-            let item = MenuItemBuilder::with_id(item_id, &label).build(app_handle)?;
-            submenu = submenu.item(&item);
-        }
-    }
-
-    let built_submenu = submenu.build()?;
-    let download_label = format!(
-        "{} - {}",
-        tray_text("download_queue"),
-        tray_download_summary(&download_status)
-    );
-    let download_status_item = MenuItemBuilder::with_id("download_status", &download_label)
-        .enabled(false)
-        .build(app_handle)?;
-    let download_pending_item = MenuItemBuilder::with_id(
-        "download_pending",
-        format!("{}: {}", tray_text("pending"), download_status.pending),
-    )
-    .enabled(false)
-    .build(app_handle)?;
-    let download_downloading_item = MenuItemBuilder::with_id(
-        "download_downloading",
-        format!(
-            "{}: {}",
-            tray_text("downloading"),
-            download_status.downloading
-        ),
-    )
-    .enabled(false)
-    .build(app_handle)?;
-    let download_completed_item = MenuItemBuilder::with_id(
-        "download_completed",
-        format!("{}: {}", tray_text("completed"), download_status.completed),
-    )
-    .enabled(false)
-    .build(app_handle)?;
-    let download_error_item = MenuItemBuilder::with_id(
-        "download_error",
-        format!("{}: {}", tray_text("errors"), download_status.error),
-    )
-    .enabled(false)
-    .build(app_handle)?;
-    let download_submenu = SubmenuBuilder::new(app_handle, tray_text("download_queue"))
-        .item(&download_status_item)
-        .separator()
-        .item(&download_downloading_item)
-        .item(&download_pending_item)
-        .item(&download_completed_item)
-        .item(&download_error_item)
-        .build()?;
-
-    let remote_status = MenuItemBuilder::with_id(
-        "remote_download_status",
-        format!(
-            "{} - {}",
-            tray_text("remote_download"),
-            tray_remote_status_label()
-        ),
-    )
-    .enabled(false)
-    .build(app_handle)?;
-
-    // Build full menu
-    let check_now =
-        MenuItemBuilder::with_id("check_now", tray_text("check_all")).build(app_handle)?;
-    let settings = MenuItemBuilder::with_id("settings", tray_text("settings")).build(app_handle)?;
-    let check_update =
-        MenuItemBuilder::with_id("check_update", tray_text("check_update")).build(app_handle)?;
-    let show = MenuItemBuilder::with_id("show", tray_text("open")).build(app_handle)?;
-    let browser_extension =
-        MenuItemBuilder::with_id("browser_extension", tray_text("browser_extension"))
-            .build(app_handle)?;
-    let quit = MenuItemBuilder::with_id("quit", tray_text("quit")).build(app_handle)?;
-
-    let menu = MenuBuilder::new(app_handle)
-        .item(&show)
-        .separator()
-        .item(&download_submenu)
-        .item(&remote_status)
-        .separator()
-        .item(&built_submenu)
-        .item(&check_now)
-        .separator()
-        .item(&settings)
-        .item(&browser_extension)
-        .separator()
-        .item(&check_update)
-        .separator()
-        .item(&quit)
-        .build()?;
-
-    if let Some(tray) = app_handle.tray_by_id("main-tray") {
-        tray.set_menu(Some(menu))?;
-    }
-
-    Ok(())
 }
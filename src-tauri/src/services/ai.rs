@@ -2,13 +2,22 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::{code, BackendError};
 
+#[path = "ai/chapters.rs"]
+mod chapters;
 #[path = "ai/dispatch.rs"]
 mod dispatch;
+#[path = "ai/profiles.rs"]
+mod profiles;
 #[path = "ai/providers.rs"]
 mod providers;
+#[path = "ai/usage.rs"]
+mod usage;
 
+pub use chapters::*;
 pub use dispatch::*;
+pub use profiles::*;
 use providers::*;
+pub use usage::*;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -20,6 +29,7 @@ pub enum AIProvider {
     Ollama,
     LmStudio,
     Proxy,
+    Anthropic,
 }
 
 impl Default for AIProvider {
@@ -91,11 +101,35 @@ impl Default for AIConfig {
     }
 }
 
+/// Tokens a provider billed for one or more requests
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AIUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl AIUsage {
+    /// Sum of two reports; unknown when neither is known
+    pub fn combine(total: Option<AIUsage>, usage: Option<AIUsage>) -> Option<AIUsage> {
+        match (total, usage) {
+            (Some(total), Some(usage)) => Some(AIUsage {
+                prompt_tokens: total.prompt_tokens + usage.prompt_tokens,
+                completion_tokens: total.completion_tokens + usage.completion_tokens,
+            }),
+            (total, usage) => total.or(usage),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SummaryResult {
     pub summary: String,
     pub provider: String,
     pub model: String,
+    /// Token counts from the provider's response, summed over every request
+    /// a long summary made; `None` when the provider doesn't report them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<AIUsage>,
 }
 
 pub const DEFAULT_LONG_SUMMARY_WORDS: u32 = 8_000;
@@ -66,6 +66,7 @@ pub async fn generate_summary_custom_with_hooks(
     let chunk_count = chunks.len();
     let resolved_format = resolve_long_summary_format(long_summary_format, style);
     let mut chunk_summaries = Vec::with_capacity(chunk_count);
+    let mut usage = None;
 
     for (index, chunk) in chunks.iter().enumerate() {
         hooks.ensure_not_cancelled()?;
@@ -89,6 +90,7 @@ pub async fn generate_summary_custom_with_hooks(
             resolved_format,
         );
         let result = generate_raw_for_provider(config, &prompt).await?;
+        usage = AIUsage::combine(usage, result.usage);
         chunk_summaries.push(ChunkSummary {
             index: index + 1,
             summary: result.summary,
@@ -109,6 +111,7 @@ pub async fn generate_summary_custom_with_hooks(
         title,
         resolved_format,
         hooks,
+        &mut usage,
     )
     .await?;
 
@@ -120,9 +123,12 @@ pub async fn generate_summary_custom_with_hooks(
             build_parts_prompt(&chunk_summaries, style, language, title)
         }
     };
-    generate_raw_for_provider(config, &prompt).await
+    let mut result = generate_raw_for_provider(config, &prompt).await?;
+    result.usage = AIUsage::combine(usage, result.usage);
+    Ok(result)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn reduce_chunk_summaries_for_composition(
     config: &AIConfig,
     chunk_summaries: Vec<ChunkSummary>,
@@ -131,6 +137,7 @@ async fn reduce_chunk_summaries_for_composition(
     title: Option<&str>,
     resolved_format: ResolvedLongSummaryFormat,
     hooks: &LongSummaryHooks<'_>,
+    usage: &mut Option<AIUsage>,
 ) -> Result<Vec<ChunkSummary>, AIError> {
     let mut summaries = chunk_summaries;
 
@@ -155,6 +162,7 @@ async fn reduce_chunk_summaries_for_composition(
                 batch_count,
             );
             let result = generate_raw_for_provider(config, &prompt).await?;
+            *usage = AIUsage::combine(*usage, result.usage);
             reduced.push(ChunkSummary {
                 index: batch_index + 1,
                 summary: result.summary,
@@ -284,6 +292,20 @@ async fn generate_summary_custom_once(
             )
             .await
         }
+        AIProvider::Anthropic => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_with_anthropic(
+                api_key,
+                &config.model,
+                transcript,
+                style,
+                language,
+                title,
+                config.timeout_seconds,
+                config.summary_max_tokens,
+            )
+            .await
+        }
     }
 }
 
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{AIConfig, AIProvider};
+
+/// Longest profile id; keeps `ai.profile.<id>` inside the secret name limit
+const MAX_PROFILE_ID_LEN: usize = 48;
+
+/// A named provider + model pair that AI features can be routed to
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AIProfile {
+    pub id: String,
+    pub name: String,
+    pub provider: AIProvider,
+    pub model: String,
+    /// Server URL for Ollama, LM Studio and proxy profiles
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+/// AI features that can each use their own profile
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AIFeature {
+    Summary,
+    Translation,
+    ProcessingCommand,
+    Chapters,
+    Other,
+}
+
+impl AIFeature {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AIFeature::Summary => "summary",
+            AIFeature::Translation => "translation",
+            AIFeature::ProcessingCommand => "processing_command",
+            AIFeature::Chapters => "chapters",
+            AIFeature::Other => "other",
+        }
+    }
+}
+
+/// Contents of `ai_profiles.json`; API keys live in the secret store
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AIProfilesFile {
+    #[serde(default)]
+    pub profiles: Vec<AIProfile>,
+    /// Feature -> profile id; unrouted features use the main AI settings
+    #[serde(default)]
+    pub routes: BTreeMap<AIFeature, String>,
+}
+
+pub fn ai_profile_secret_name(id: &str) -> String {
+    format!("ai.profile.{}", id)
+}
+
+pub fn validate_ai_profile(profile: &AIProfile) -> Result<(), String> {
+    let id = profile.id.as_str();
+    if id.is_empty()
+        || id.len() > MAX_PROFILE_ID_LEN
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Profile id must be 1-{} letters, digits, '-' or '_'",
+            MAX_PROFILE_ID_LEN
+        ));
+    }
+    if profile.name.trim().is_empty() {
+        return Err("Profile name is required".to_string());
+    }
+    if profile.model.trim().is_empty() {
+        return Err("Profile model is required".to_string());
+    }
+    Ok(())
+}
+
+/// The main config with the profile's provider, model and limits swapped in
+pub fn apply_ai_profile(base: &AIConfig, profile: &AIProfile) -> AIConfig {
+    let mut config = base.clone();
+    config.provider = profile.provider.clone();
+    config.model = profile.model.trim().to_string();
+    config.api_key = profile.api_key.clone();
+    if let Some(url) = profile.base_url.clone() {
+        match profile.provider {
+            AIProvider::Ollama => config.ollama_url = Some(url),
+            AIProvider::LmStudio => config.lmstudio_url = Some(url),
+            AIProvider::Proxy => config.proxy_url = Some(url),
+            _ => {}
+        }
+    }
+    if profile.timeout_seconds.is_some() {
+        config.timeout_seconds = profile.timeout_seconds;
+    }
+    if profile.max_tokens.is_some() {
+        config.summary_max_tokens = profile.max_tokens;
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_overrides_provider_model_and_server() {
+        let base = AIConfig {
+            api_key: Some("main-key".to_string()),
+            timeout_seconds: Some(120),
+            ..AIConfig::default()
+        };
+        let profile = AIProfile {
+            id: "local-fast".to_string(),
+            name: "Local".to_string(),
+            provider: AIProvider::Ollama,
+            model: "qwen2.5:7b".to_string(),
+            base_url: Some("http://192.168.1.5:11434".to_string()),
+            api_key: None,
+            timeout_seconds: None,
+            max_tokens: Some(512),
+        };
+        assert!(validate_ai_profile(&profile).is_ok());
+        let config = apply_ai_profile(&base, &profile);
+        assert_eq!(config.provider, AIProvider::Ollama);
+        assert_eq!(
+            config.ollama_url.as_deref(),
+            Some("http://192.168.1.5:11434")
+        );
+        assert_eq!(config.api_key, None);
+        assert_eq!(config.timeout_seconds, Some(120));
+        assert_eq!(config.summary_max_tokens, Some(512));
+
+        let bad = AIProfile {
+            id: "../x".to_string(),
+            ..profile
+        };
+        assert!(validate_ai_profile(&bad).is_err());
+        assert_eq!(
+            serde_json::to_value(AIFeature::ProcessingCommand).unwrap(),
+            AIFeature::ProcessingCommand.as_str()
+        );
+    }
+}
@@ -28,13 +28,8 @@ fn normalized_summary_max_tokens(custom_max_tokens: Option<u32>) -> Option<u32>
 /// We intentionally maintain a short allowlist of legacy families because they
 /// are effectively frozen, while newer OpenAI models have consistently moved to
 /// the new parameter style.
-const OPENAI_LEGACY_MODEL_PREFIXES: &[&str] = &[
-    "gpt-3.5",
-    "gpt-4-",
-    "gpt-4o",
-    "gpt-4.1",
-    "chatgpt-4o",
-];
+const OPENAI_LEGACY_MODEL_PREFIXES: &[&str] =
+    &["gpt-3.5", "gpt-4-", "gpt-4o", "gpt-4.1", "chatgpt-4o"];
 
 fn openai_is_legacy_model(model: &str) -> bool {
     let model = model.to_lowercase();
@@ -88,7 +83,8 @@ fn adjust_openai_request(body: &mut serde_json::Value, error: &OpenAIErrorDetail
 
     let names_temperature = |s: &str| s.contains("temperature");
     // Substring match, so this also fires for "max_completion_tokens".
-    let names_max_tokens = |s: &str| s.contains("max_tokens") || s.contains("max_completion_tokens");
+    let names_max_tokens =
+        |s: &str| s.contains("max_tokens") || s.contains("max_completion_tokens");
 
     let targets_temperature = error
         .param
@@ -142,7 +138,9 @@ async fn post_openai_chat(
 
     // Initial attempt plus one retry per successful parameter correction.
     for adjustments_left in (0..=MAX_OPENAI_PARAM_ADJUSTMENTS).rev() {
-        let response = send(&body).await.map_err(|e| AIError::NetworkError(e.to_string()))?;
+        let response = send(&body)
+            .await
+            .map_err(|e| AIError::NetworkError(e.to_string()))?;
         let status = response.status();
         let response_text = response.text().await.unwrap_or_default();
 
@@ -283,6 +281,46 @@ fn openai_compatible_finish_reason(json: &serde_json::Value) -> Option<&str> {
         .or_else(|| choice.get("stop_reason").and_then(|reason| reason.as_str()))
 }
 
+/// Token counts the provider reported: OpenAI-compatible and Anthropic
+/// `usage`, Gemini `usageMetadata` or Ollama's eval counts
+fn response_usage(json: &serde_json::Value) -> Option<AIUsage> {
+    let count = |value: &serde_json::Value, keys: &[&str]| {
+        keys.iter()
+            .filter_map(|key| value.get(*key).and_then(|count| count.as_u64()))
+            .reduce(|total, count| total + count)
+    };
+    let (prompt_tokens, completion_tokens) = if let Some(usage) = json.get("usage") {
+        (
+            count(usage, &["prompt_tokens", "input_tokens"]),
+            count(usage, &["completion_tokens", "output_tokens"]),
+        )
+    } else if let Some(usage) = json.get("usageMetadata") {
+        (
+            count(usage, &["promptTokenCount"]),
+            count(usage, &["candidatesTokenCount", "thoughtsTokenCount"]),
+        )
+    } else {
+        (
+            count(json, &["prompt_eval_count"]),
+            count(json, &["eval_count"]),
+        )
+    };
+    if prompt_tokens.is_none() && completion_tokens.is_none() {
+        return None;
+    }
+    Some(AIUsage {
+        prompt_tokens: prompt_tokens.unwrap_or(0),
+        completion_tokens: completion_tokens.unwrap_or(0),
+    })
+}
+
+fn response_text_usage(response_text: &str) -> Option<AIUsage> {
+    serde_json::from_str(response_text)
+        .ok()
+        .as_ref()
+        .and_then(response_usage)
+}
+
 fn parse_openai_compatible_response(
     provider_label: &str,
     status: reqwest::StatusCode,
@@ -461,6 +499,7 @@ pub async fn generate_with_gemini(
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "Gemini".to_string(),
+        usage: response_usage(&json),
         model: model.to_string(),
     })
 }
@@ -497,6 +536,7 @@ pub async fn generate_with_openai(
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "OpenAI".to_string(),
+        usage: response_text_usage(&response_text),
         model: model.to_string(),
     })
 }
@@ -562,6 +602,7 @@ pub async fn generate_with_ollama(
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "Ollama".to_string(),
+        usage: response_usage(&json),
         model: model.to_string(),
     })
 }
@@ -605,6 +646,7 @@ pub async fn generate_with_deepseek(
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "DeepSeek".to_string(),
+        usage: response_text_usage(&response_text),
         model: model.to_string(),
     })
 }
@@ -648,6 +690,7 @@ pub async fn generate_with_qwen(
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "Qwen".to_string(),
+        usage: response_text_usage(&response_text),
         model: model.to_string(),
     })
 }
@@ -684,6 +727,7 @@ pub async fn generate_with_proxy(
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "Proxy".to_string(),
+        usage: response_text_usage(&response_text),
         model: model.to_string(),
     })
 }
@@ -734,6 +778,7 @@ pub async fn generate_with_lmstudio(
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
         provider: "LM Studio".to_string(),
+        usage: response_text_usage(&response_text),
         model: model.to_string(),
     })
 }
@@ -820,6 +865,7 @@ async fn generate_raw_with_gemini(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "Gemini".to_string(),
+        usage: response_usage(&json),
     })
 }
 
@@ -855,6 +901,7 @@ async fn generate_raw_with_openai(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "OpenAI".to_string(),
+        usage: response_text_usage(&response_text),
     })
 }
 
@@ -911,6 +958,7 @@ async fn generate_raw_with_ollama(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "Ollama".to_string(),
+        usage: response_usage(&json),
     })
 }
 
@@ -954,6 +1002,7 @@ async fn generate_raw_with_lmstudio(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "LM Studio".to_string(),
+        usage: response_text_usage(&response_text),
     })
 }
 
@@ -991,6 +1040,7 @@ async fn generate_raw_with_deepseek(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "DeepSeek".to_string(),
+        usage: response_text_usage(&response_text),
     })
 }
 
@@ -1028,6 +1078,7 @@ async fn generate_raw_with_qwen(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "Qwen".to_string(),
+        usage: response_text_usage(&response_text),
     })
 }
 
@@ -1061,6 +1112,144 @@ async fn generate_raw_with_proxy(
         summary: text.to_string(),
         model: model.to_string(),
         provider: "Proxy".to_string(),
+        usage: response_text_usage(&response_text),
+    })
+}
+
+const ANTHROPIC_MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+/// The Messages API requires `max_tokens`; used when the config leaves it unlimited
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 8192;
+
+fn parse_anthropic_response(
+    status: reqwest::StatusCode,
+    response_text: &str,
+) -> Result<(String, Option<AIUsage>), AIError> {
+    if !status.is_success() {
+        let detail = extract_openai_compatible_error(response_text)
+            .unwrap_or_else(|| response_snippet(response_text));
+        return Err(AIError::ApiError(format!(
+            "Anthropic API returned HTTP {}: {}",
+            status, detail
+        )));
+    }
+
+    let json: serde_json::Value = serde_json::from_str(response_text).map_err(|e| {
+        AIError::ParseError(format!(
+            "Anthropic API returned invalid JSON: {}. Response: {}",
+            e,
+            response_snippet(response_text)
+        ))
+    })?;
+
+    let text = json
+        .get("content")
+        .and_then(|content| content.as_array())
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|block| block.get("text").and_then(|text| text.as_str()))
+                .collect::<Vec<_>>()
+                .join("")
+        })
+        .filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| {
+            AIError::ParseError(format!(
+                "Anthropic API response did not contain text. Response: {}",
+                response_snippet(response_text)
+            ))
+        })?;
+
+    if json.get("stop_reason").and_then(|reason| reason.as_str()) == Some("max_tokens") {
+        return Err(AIError::ApiError(
+            "Anthropic API response was cut off by the output token limit. Try again with a shorter transcript or a higher max token setting.".to_string(),
+        ));
+    }
+
+    Ok((text, response_usage(&json)))
+}
+
+async fn post_anthropic_message(
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    temperature: f64,
+    timeout_seconds: Option<u64>,
+    max_tokens: Option<u32>,
+) -> Result<(String, Option<AIUsage>), AIError> {
+    let client = ai_client(timeout_seconds)?;
+    let body = serde_json::json!({
+        "model": model,
+        "max_tokens": normalized_summary_max_tokens(max_tokens).unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS),
+        "temperature": temperature,
+        "messages": [{ "role": "user", "content": prompt }]
+    });
+    let response = client
+        .post(ANTHROPIC_MESSAGES_URL)
+        .header("Content-Type", "application/json")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_API_VERSION)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| AIError::NetworkError(e.to_string()))?;
+    let status = response.status();
+    let response_text = response.text().await.unwrap_or_default();
+    parse_anthropic_response(status, &response_text)
+}
+
+pub async fn generate_with_anthropic(
+    api_key: &str,
+    model: &str,
+    transcript: &str,
+    style: &SummaryStyle,
+    language: &str,
+    title: Option<&str>,
+    timeout_seconds: Option<u64>,
+    summary_max_tokens: Option<u32>,
+) -> Result<SummaryResult, AIError> {
+    let prompt = build_prompt(transcript, style, language, title);
+    let (summary, usage) = post_anthropic_message(
+        api_key,
+        model,
+        &prompt,
+        0.7,
+        timeout_seconds,
+        summary_max_tokens,
+    )
+    .await?;
+
+    Ok(SummaryResult {
+        summary: summary.trim().to_string(),
+        provider: "Anthropic".to_string(),
+        usage,
+        model: model.to_string(),
+    })
+}
+
+async fn generate_raw_with_anthropic(
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    timeout_seconds: Option<u64>,
+    summary_max_tokens: Option<u32>,
+) -> Result<SummaryResult, AIError> {
+    let (text, usage) = post_anthropic_message(
+        api_key,
+        model,
+        prompt,
+        0.3,
+        timeout_seconds,
+        summary_max_tokens,
+    )
+    .await?;
+
+    Ok(SummaryResult {
+        summary: text,
+        model: model.to_string(),
+        provider: "Anthropic".to_string(),
+        usage,
     })
 }
 
@@ -1157,6 +1346,17 @@ pub(super) async fn generate_raw_for_provider(
             )
             .await
         }
+        AIProvider::Anthropic => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_raw_with_anthropic(
+                api_key,
+                &config.model,
+                prompt,
+                config.timeout_seconds,
+                config.summary_max_tokens,
+            )
+            .await
+        }
     }
 }
 
@@ -1191,6 +1391,63 @@ mod tests {
         assert_eq!(summary_max_tokens_for_config(&config), None);
     }
 
+    #[test]
+    fn anthropic_parser_joins_text_blocks_and_rejects_truncation() {
+        let response = r#"{
+            "content": [
+                { "type": "text", "text": "Part one, " },
+                { "type": "text", "text": "part two." }
+            ],
+            "stop_reason": "end_turn",
+            "usage": { "input_tokens": 120, "output_tokens": 8 }
+        }"#;
+        assert_eq!(
+            parse_anthropic_response(reqwest::StatusCode::OK, response).unwrap(),
+            (
+                "Part one, part two.".to_string(),
+                Some(AIUsage {
+                    prompt_tokens: 120,
+                    completion_tokens: 8
+                })
+            )
+        );
+
+        let truncated = r#"{
+            "content": [{ "type": "text", "text": "Partial" }],
+            "stop_reason": "max_tokens"
+        }"#;
+        assert!(parse_anthropic_response(reqwest::StatusCode::OK, truncated).is_err());
+    }
+
+    #[test]
+    fn provider_usage_fields_are_read() {
+        let usage = |prompt_tokens, completion_tokens| {
+            Some(AIUsage {
+                prompt_tokens,
+                completion_tokens,
+            })
+        };
+        assert_eq!(
+            response_text_usage(r#"{"usage":{"prompt_tokens":50,"completion_tokens":7}}"#),
+            usage(50, 7)
+        );
+        assert_eq!(
+            response_usage(&serde_json::json!({
+                "usageMetadata": {
+                    "promptTokenCount": 40,
+                    "candidatesTokenCount": 10,
+                    "thoughtsTokenCount": 5
+                }
+            })),
+            usage(40, 15)
+        );
+        assert_eq!(
+            response_usage(&serde_json::json!({ "prompt_eval_count": 30, "eval_count": 4 })),
+            usage(30, 4)
+        );
+        assert_eq!(response_text_usage(r#"{"choices":[]}"#), None);
+    }
+
     #[test]
     fn openai_compatible_parser_rejects_length_truncated_output() {
         let response = r#"{
@@ -1309,10 +1566,7 @@ mod tests {
         let mut body = serde_json::json!({
             "temperature": 0.7
         });
-        let error = error_with_param(
-            "This model does not support setting temperature.",
-            None,
-        );
+        let error = error_with_param("This model does not support setting temperature.", None);
 
         assert!(adjust_openai_request(&mut body, &error));
         assert!(body.get("temperature").is_none());
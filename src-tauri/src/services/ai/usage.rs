@@ -0,0 +1,164 @@
+use std::sync::Mutex;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::{AIFeature, AIProvider};
+
+/// USD per million prompt / completion tokens, matched by model prefix.
+/// More specific prefixes come first.
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gpt-5-nano", 0.05, 0.40),
+    ("gpt-5-mini", 0.25, 2.00),
+    ("gpt-5", 1.25, 10.00),
+    ("claude-opus", 15.00, 75.00),
+    ("claude-sonnet", 3.00, 15.00),
+    ("claude-haiku", 1.00, 5.00),
+    ("gemini-2.5-pro", 1.25, 10.00),
+    ("gemini-2.5-flash-lite", 0.10, 0.40),
+    ("gemini-2.0-flash", 0.10, 0.40),
+    ("gemini-2.5-flash", 0.30, 2.50),
+    ("deepseek-reasoner", 0.55, 2.19),
+    ("deepseek-chat", 0.27, 1.10),
+    ("qwen-turbo", 0.05, 0.20),
+    ("qwen-plus", 0.40, 1.20),
+    ("qwen-max", 1.60, 6.40),
+];
+
+static AI_MONTHLY_BUDGET_USD: Mutex<Option<f64>> = Mutex::new(None);
+
+/// Period covered by a usage report
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AIUsageRange {
+    Today,
+    Last7Days,
+    Last30Days,
+    ThisMonth,
+    All,
+}
+
+/// Rough token count, about four characters per token
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Estimated USD cost of a request; free for local servers, `None` when the
+/// model's price is unknown (recorded as unpriced)
+pub fn estimate_ai_cost_usd(
+    provider: &AIProvider,
+    model: &str,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+) -> Option<f64> {
+    if matches!(provider, AIProvider::Ollama | AIProvider::LmStudio) {
+        return Some(0.0);
+    }
+    let model = model.to_lowercase();
+    let model = model.rsplit('/').next().unwrap_or(&model);
+    MODEL_PRICES
+        .iter()
+        .find(|(prefix, _, _)| model.starts_with(prefix))
+        .map(|(_, prompt_price, completion_price)| {
+            (prompt_tokens as f64 * prompt_price + completion_tokens as f64 * completion_price)
+                / 1_000_000.0
+        })
+}
+
+/// First day a range covers, `None` for all time
+pub fn usage_range_start(range: AIUsageRange, today: NaiveDate) -> Option<NaiveDate> {
+    match range {
+        AIUsageRange::Today => Some(today),
+        AIUsageRange::Last7Days => Some(today - Duration::days(6)),
+        AIUsageRange::Last30Days => Some(today - Duration::days(29)),
+        AIUsageRange::ThisMonth => Some(month_start(today)),
+        AIUsageRange::All => None,
+    }
+}
+
+pub fn month_start(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).unwrap_or(day)
+}
+
+pub fn set_ai_monthly_budget(budget_usd: Option<f64>) -> Result<(), String> {
+    if budget_usd.is_some_and(|budget| !budget.is_finite() || budget <= 0.0) {
+        return Err("Monthly budget must be a positive amount".to_string());
+    }
+    if let Ok(mut guard) = AI_MONTHLY_BUDGET_USD.lock() {
+        *guard = budget_usd;
+    }
+    Ok(())
+}
+
+pub fn get_ai_monthly_budget() -> Option<f64> {
+    AI_MONTHLY_BUDGET_USD.lock().ok().and_then(|guard| *guard)
+}
+
+/// Whether requests to `model` have a known price (or are free)
+pub fn ai_model_priced(provider: &AIProvider, model: &str) -> bool {
+    estimate_ai_cost_usd(provider, model, 0, 0).is_some()
+}
+
+/// Whether `feature` may run after `month_cost_usd` was spent this month.
+/// Summaries keep working past the budget; helpers like chapters stop, and
+/// so do models without a known price once a budget is set, since their
+/// spend can't be counted against it.
+pub fn ai_budget_allows(
+    feature: AIFeature,
+    month_cost_usd: f64,
+    budget_usd: Option<f64>,
+    model_priced: bool,
+) -> bool {
+    feature == AIFeature::Summary
+        || budget_usd.is_none_or(|budget| model_priced && month_cost_usd < budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn costs_follow_model_prices_and_budget_spares_summaries() {
+        let cost = estimate_ai_cost_usd(&AIProvider::OpenAI, "gpt-4o-mini", 1_000_000, 100_000);
+        assert!((cost.unwrap() - 0.21).abs() < 1e-9);
+        let routed = estimate_ai_cost_usd(&AIProvider::Proxy, "openai/gpt-4o", 1_000_000, 0);
+        assert!((routed.unwrap() - 2.5).abs() < 1e-9);
+        assert_eq!(
+            estimate_ai_cost_usd(&AIProvider::Ollama, "llama3", 5000, 5000),
+            Some(0.0)
+        );
+        assert_eq!(
+            estimate_ai_cost_usd(&AIProvider::OpenAI, "custom-model", 1, 1),
+            None
+        );
+
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(
+            usage_range_start(AIUsageRange::ThisMonth, today),
+            NaiveDate::from_ymd_opt(2026, 10, 1)
+        );
+        assert_eq!(
+            usage_range_start(AIUsageRange::Last7Days, today),
+            NaiveDate::from_ymd_opt(2026, 10, 10)
+        );
+
+        assert!(ai_budget_allows(AIFeature::Chapters, 4.0, Some(5.0), true));
+        assert!(!ai_budget_allows(AIFeature::Chapters, 5.0, Some(5.0), true));
+        assert!(!ai_budget_allows(
+            AIFeature::Chapters,
+            0.0,
+            Some(5.0),
+            false
+        ));
+        assert!(ai_budget_allows(AIFeature::Chapters, 0.0, None, false));
+        assert!(ai_budget_allows(AIFeature::Summary, 9.0, Some(5.0), true));
+        assert!(ai_budget_allows(AIFeature::Translation, 9.0, None, true));
+        assert!(!ai_model_priced(&AIProvider::OpenAI, "custom-model"));
+        assert!(ai_model_priced(&AIProvider::LmStudio, "custom-model"));
+    }
+}
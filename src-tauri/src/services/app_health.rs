@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use super::polling::POLLING_ACTIVE;
+use super::{
+    check_deno_internal, check_ffmpeg_internal, get_ytdlp_version_internal, load_crashed_downloads,
+    load_interrupted_downloads,
+};
+use crate::database::{get_database_recovery_report, get_db, get_followed_channels_db};
+use crate::types::{AppHealth, DependencyHealth, APP_HEALTH};
+use crate::utils::data_dir;
+
+const DEPENDENCY_HEALTH_FILE: &str = "dependency-health.json";
+/// Let the window mount its listeners before the report goes out
+const STARTUP_REPORT_DELAY: Duration = Duration::from_secs(2);
+
+fn dependency_health_path(app: &AppHandle) -> Option<PathBuf> {
+    data_dir(app)
+        .ok()
+        .map(|dir| dir.join(DEPENDENCY_HEALTH_FILE))
+}
+
+fn read_dependency_health(path: &Path) -> HashMap<String, DependencyHealth> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn write_dependency_health(
+    path: &Path,
+    name: &str,
+    installed: bool,
+    version: Option<String>,
+) -> Result<(), String> {
+    let mut cache = read_dependency_health(path);
+    cache.insert(
+        name.to_string(),
+        DependencyHealth {
+            installed,
+            version,
+            checked_at: Some(chrono::Utc::now().timestamp()),
+        },
+    );
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&cache)
+        .map_err(|e| format!("Failed to serialize dependency health: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to save dependency health: {}", e))
+}
+
+/// Remember the result of a "ytdlp", "ffmpeg" or "deno" check for the next startup
+pub fn record_dependency_health(
+    app: &AppHandle,
+    name: &str,
+    installed: bool,
+    version: Option<String>,
+) {
+    let Some(path) = dependency_health_path(app) else {
+        return;
+    };
+    if let Err(e) = write_dependency_health(&path, name, installed, version) {
+        log::warn!("{}", e);
+    }
+}
+
+/// Cached status, checking now only when the dependency was never checked
+async fn dependency_health(app: &AppHandle, name: &str) -> DependencyHealth {
+    let cached = dependency_health_path(app)
+        .map(|path| read_dependency_health(&path))
+        .and_then(|mut cache| cache.remove(name));
+    if let Some(cached) = cached {
+        return cached;
+    }
+    refresh_dependency_health(app, name).await
+}
+
+/// Check "ytdlp", "ffmpeg" or "deno" now and cache the result, e.g. after an
+/// install or update replaced the binary
+pub async fn refresh_dependency_health(app: &AppHandle, name: &str) -> DependencyHealth {
+    let (installed, version) = match name {
+        "ytdlp" => match get_ytdlp_version_internal(app).await {
+            Ok(info) => (true, Some(info.version)),
+            Err(_) => (false, None),
+        },
+        "ffmpeg" => match check_ffmpeg_internal(app).await {
+            Ok(status) => (status.installed, status.version),
+            Err(_) => (false, None),
+        },
+        _ => match check_deno_internal(app).await {
+            Ok(status) => (status.installed, status.version),
+            Err(_) => (false, None),
+        },
+    };
+    record_dependency_health(app, name, installed, version.clone());
+    DependencyHealth {
+        installed,
+        version,
+        checked_at: Some(chrono::Utc::now().timestamp()),
+    }
+}
+
+/// Dependency, database, resume and polling status in one snapshot
+pub async fn collect_app_health(app: &AppHandle) -> AppHealth {
+    // The integrity check itself runs while the database opens at startup
+    let (database_status, database_error) = if let Err(e) = get_db() {
+        ("unavailable", Some(e))
+    } else if let Some(report) = get_database_recovery_report() {
+        ("recovered", Some(report.integrity_error))
+    } else {
+        ("ok", None)
+    };
+    let scheduled_jobs = if POLLING_ACTIVE.load(Ordering::SeqCst) {
+        get_followed_channels_db().map(|c| c.len()).unwrap_or(0)
+    } else {
+        0
+    };
+    AppHealth {
+        ytdlp: dependency_health(app, "ytdlp").await,
+        ffmpeg: dependency_health(app, "ffmpeg").await,
+        deno: dependency_health(app, "deno").await,
+        database_status: database_status.to_string(),
+        database_error,
+        resumable_downloads: load_interrupted_downloads().len() + load_crashed_downloads().len(),
+        scheduled_jobs,
+    }
+}
+
+/// Emit `app-health` once the window had a moment to start listening
+pub fn spawn_app_health_report(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_REPORT_DELAY).await;
+        let health = collect_app_health(&app).await;
+        let _ = APP_HEALTH.emit(&app, &health);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependency_health_cache_keeps_each_dependency() {
+        let dir = std::env::temp_dir().join(format!("youwee-health-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(DEPENDENCY_HEALTH_FILE);
+        assert!(read_dependency_health(&path).is_empty());
+
+        write_dependency_health(&path, "ffmpeg", true, Some("7.1".to_string())).unwrap();
+        write_dependency_health(&path, "deno", false, None).unwrap();
+        let cache = read_dependency_health(&path);
+        assert_eq!(cache["ffmpeg"].version.as_deref(), Some("7.1"));
+        assert!(cache["ffmpeg"].installed);
+        assert!(!cache["deno"].installed);
+        assert!(cache["deno"].checked_at.is_some());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
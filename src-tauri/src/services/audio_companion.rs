@@ -0,0 +1,298 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::{get_ffmpeg_path, FfmpegError, FfmpegRunner, ACTIVE_JOBS};
+use crate::database::{add_history_internal, add_log_internal, set_history_variant_of};
+use crate::types::{AudioCompanionResult, AUDIO_COMPANION};
+use crate::utils::resolve_output_directory;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioCompanionFormat {
+    #[default]
+    Mp3,
+    M4a,
+}
+
+impl AudioCompanionFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::M4a => "m4a",
+        }
+    }
+}
+
+/// "Also save audio": extract an audio copy once a video download finishes
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioCompanionOptions {
+    pub enabled: bool,
+    #[serde(default)]
+    pub format: AudioCompanionFormat,
+    /// kbps, e.g. "192"
+    pub bitrate: Option<String>,
+    /// e.g. the Music preset folder; next to the video when unset
+    pub output_dir: Option<String>,
+}
+
+/// Download details recorded on the companion's history entry
+#[derive(Clone, Debug)]
+pub struct AudioCompanionOrigin {
+    pub url: String,
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    pub source: Option<String>,
+    pub video_history_id: Option<String>,
+    pub incognito: bool,
+}
+
+static AUDIO_COMPANION_DEFAULT: Mutex<Option<AudioCompanionOptions>> = Mutex::new(None);
+
+fn companion_bitrate(options: &AudioCompanionOptions) -> Result<u32, String> {
+    match options.bitrate.as_deref().map(str::trim) {
+        None | Some("") => Ok(192),
+        Some(value) => value
+            .trim_end_matches(['k', 'K'])
+            .parse::<u32>()
+            .ok()
+            .filter(|kbps| (32..=512).contains(kbps))
+            .ok_or_else(|| format!("Invalid audio bitrate '{}'", value)),
+    }
+}
+
+/// Save the default used by downloads that don't pass their own options
+pub fn set_audio_companion_default(options: AudioCompanionOptions) -> Result<(), String> {
+    companion_bitrate(&options)?;
+    if let Some(dir) = options.output_dir.as_deref().filter(|dir| !dir.is_empty()) {
+        resolve_output_directory(dir, true).map_err(|e| e.message().to_string())?;
+    }
+    if let Ok(mut guard) = AUDIO_COMPANION_DEFAULT.lock() {
+        *guard = Some(options);
+    }
+    Ok(())
+}
+
+/// Options for one download: its own, else the saved default; None when off
+pub fn resolve_audio_companion(
+    per_download: Option<AudioCompanionOptions>,
+) -> Option<AudioCompanionOptions> {
+    per_download
+        .or_else(|| {
+            AUDIO_COMPANION_DEFAULT
+                .lock()
+                .ok()
+                .and_then(|guard| guard.clone())
+        })
+        .filter(|options| options.enabled)
+}
+
+/// `cancel_ffmpeg` id of the extraction for `video_path`
+pub fn audio_companion_job_id(video_path: &str) -> String {
+    format!("audio-companion:{}", video_path)
+}
+
+/// Free path for the audio copy of `video`, numbered if the name is taken
+pub fn audio_companion_path(
+    video: &Path,
+    format: AudioCompanionFormat,
+    output_dir: Option<&Path>,
+) -> PathBuf {
+    let stem = video
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "audio".to_string());
+    let dir = output_dir
+        .or_else(|| video.parent())
+        .unwrap_or_else(|| Path::new("."));
+    let ext = format.extension();
+    let candidate = dir.join(format!("{}.{}", stem, ext));
+    if !candidate.exists() {
+        return candidate;
+    }
+    (1..)
+        .map(|n| dir.join(format!("{} ({}).{}", stem, n, ext)))
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
+
+fn audio_companion_args(
+    input: &Path,
+    output: &Path,
+    format: AudioCompanionFormat,
+    kbps: u32,
+) -> Vec<String> {
+    let bitrate = format!("{}k", kbps);
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        input.to_string_lossy().to_string(),
+        "-map".to_string(),
+        "0:a:0".to_string(),
+        "-vn".to_string(),
+        "-map_metadata".to_string(),
+        "0".to_string(),
+    ];
+    let codec: [&str; 6] = match format {
+        AudioCompanionFormat::Mp3 => [
+            "-c:a",
+            "libmp3lame",
+            "-b:a",
+            &bitrate,
+            "-id3v2_version",
+            "3",
+        ],
+        AudioCompanionFormat::M4a => ["-c:a", "aac", "-b:a", &bitrate, "-movflags", "+faststart"],
+    };
+    args.extend(codec.iter().map(|arg| arg.to_string()));
+    args.push(output.to_string_lossy().to_string());
+    args
+}
+
+async fn extract_audio_companion(
+    app: &AppHandle,
+    job_id: &str,
+    video: &Path,
+    options: &AudioCompanionOptions,
+) -> Result<PathBuf, String> {
+    let kbps = companion_bitrate(options)?;
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg to save audio copies.")?;
+    let output_dir = match options.output_dir.as_deref().filter(|dir| !dir.is_empty()) {
+        Some(dir) => Some(PathBuf::from(
+            resolve_output_directory(dir, true).map_err(|e| e.message().to_string())?,
+        )),
+        None => None,
+    };
+    let output = audio_companion_path(video, options.format, output_dir.as_deref());
+
+    let runner = FfmpegRunner::new(
+        &ffmpeg_path,
+        audio_companion_args(video, &output, options.format, kbps),
+    );
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    ACTIVE_JOBS
+        .lock()
+        .await
+        .insert(job_id.to_string(), cancel_tx);
+    let result = runner.run(Some(&mut cancel_rx), |_| {}).await;
+    ACTIVE_JOBS.lock().await.remove(job_id);
+
+    match result {
+        Ok(()) => Ok(output),
+        Err(FfmpegError::Cancelled) => {
+            std::fs::remove_file(&output).ok();
+            Err("Audio extraction cancelled".to_string())
+        }
+        Err(error) => {
+            std::fs::remove_file(&output).ok();
+            Err(format!("Audio extraction failed: {}", error))
+        }
+    }
+}
+
+/// Extract the audio copy of a finished video in the background, record it
+/// in history linked to the video's entry and emit `audio-companion`. The job
+/// can be cancelled with [`audio_companion_job_id`] of the video path.
+pub fn spawn_audio_companion(
+    app: AppHandle,
+    video_path: String,
+    options: AudioCompanionOptions,
+    origin: AudioCompanionOrigin,
+) {
+    tauri::async_runtime::spawn(async move {
+        let job_id = audio_companion_job_id(&video_path);
+        let extracted =
+            extract_audio_companion(&app, &job_id, Path::new(&video_path), &options).await;
+        let result = match extracted {
+            Ok(audio_path) => {
+                let audio_path = audio_path.to_string_lossy().to_string();
+                let history_id = if origin.incognito {
+                    None
+                } else {
+                    add_history_internal(
+                        origin.url.clone(),
+                        origin.title.unwrap_or_else(|| origin.url.clone()),
+                        origin.thumbnail,
+                        audio_path.clone(),
+                        std::fs::metadata(&audio_path).ok().map(|meta| meta.len()),
+                        None,
+                        companion_bitrate(&options)
+                            .ok()
+                            .map(|kbps| format!("{}kbps", kbps)),
+                        Some(options.format.extension().to_string()),
+                        origin.source,
+                        None,
+                    )
+                    .ok()
+                };
+                if let (Some(id), Some(video_id)) = (&history_id, &origin.video_history_id) {
+                    set_history_variant_of(id, Some(video_id)).ok();
+                }
+                AudioCompanionResult {
+                    job_id,
+                    video_path,
+                    audio_path: Some(audio_path),
+                    history_id,
+                    error: None,
+                }
+            }
+            Err(error) => {
+                let log_url = (!origin.incognito).then_some(origin.url.as_str());
+                add_log_internal("error", &error, None, log_url).ok();
+                AudioCompanionResult {
+                    job_id,
+                    video_path,
+                    audio_path: None,
+                    history_id: None,
+                    error: Some(error),
+                }
+            }
+        };
+        AUDIO_COMPANION.emit(&app, &result).ok();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn companion_args_and_path_follow_format() {
+        let args = audio_companion_args(
+            Path::new("/v/clip.mkv"),
+            Path::new("/v/clip.m4a"),
+            AudioCompanionFormat::M4a,
+            160,
+        );
+        assert_eq!(
+            args.join(" "),
+            "-y -i /v/clip.mkv -map 0:a:0 -vn -map_metadata 0 -c:a aac -b:a 160k -movflags +faststart /v/clip.m4a"
+        );
+
+        let music = Path::new("/music");
+        assert_eq!(
+            audio_companion_path(
+                Path::new("/v/clip.mkv"),
+                AudioCompanionFormat::Mp3,
+                Some(music)
+            ),
+            PathBuf::from("/music/clip.mp3")
+        );
+
+        let options = AudioCompanionOptions {
+            enabled: true,
+            bitrate: Some("320k".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(companion_bitrate(&options), Ok(320));
+        assert_eq!(
+            resolve_audio_companion(Some(AudioCompanionOptions::default())),
+            None
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::download_temp::{cleanup_orphaned_download_temp, orphaned_download_temp_dirs};
+use crate::utils::data_dir;
+
+/// Default cap for generated previews, thumbnails and audio previews
+pub const DEFAULT_MAX_CACHE_MB: u64 = 2048;
+
+const SUBTITLE_TEMP_PREFIX: &str = "youwee_subs_";
+// A transcript fetch may still be writing to a fresh subtitle dir
+const SUBTITLE_TEMP_MIN_AGE: Duration = Duration::from_secs(5 * 60);
+
+// Synced from the frontend settings; 0 disables the cap
+static MAX_CACHE_MB: Mutex<u64> = Mutex::new(DEFAULT_MAX_CACHE_MB);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheCategory {
+    Previews,
+    Thumbnails,
+    AudioPreviews,
+    Subtitles,
+    DownloadTemp,
+}
+
+impl CacheCategory {
+    pub const ALL: [CacheCategory; 5] = [
+        CacheCategory::Previews,
+        CacheCategory::Thumbnails,
+        CacheCategory::AudioPreviews,
+        CacheCategory::Subtitles,
+        CacheCategory::DownloadTemp,
+    ];
+
+    /// Category of a file in the previews folder, from its name prefix
+    fn of_preview_file(name: &str) -> CacheCategory {
+        if name.starts_with("thumb_") {
+            CacheCategory::Thumbnails
+        } else if name.starts_with("audio_") {
+            CacheCategory::AudioPreviews
+        } else {
+            CacheCategory::Previews
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheCategoryStats {
+    pub category: CacheCategory,
+    pub count: u64,
+    pub bytes: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub categories: Vec<CacheCategoryStats>,
+    pub total_bytes: u64,
+    pub max_bytes: Option<u64>,
+}
+
+pub fn set_max_cache_size_mb(max_mb: u64) {
+    if let Ok(mut guard) = MAX_CACHE_MB.lock() {
+        *guard = max_mb;
+    }
+}
+
+/// Cap for the previews folder in bytes, `None` when unlimited
+pub fn max_cache_bytes() -> Option<u64> {
+    let max_mb = MAX_CACHE_MB
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or(DEFAULT_MAX_CACHE_MB);
+    (max_mb > 0).then(|| max_mb * 1024 * 1024)
+}
+
+pub fn preview_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    data_dir(app)
+        .map(|dir| dir.join("previews"))
+        .map_err(|_| "Failed to get app data directory".to_string())
+}
+
+/// Mark a cache entry as used so LRU eviction keeps it
+pub fn touch_cache_entry(path: &Path) {
+    if let Ok(file) = std::fs::File::options().append(true).open(path) {
+        file.set_modified(SystemTime::now()).ok();
+    }
+}
+
+fn path_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| path_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn remove_path(path: &Path) -> bool {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Failed to remove cache entry {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+fn preview_files(dir: &Path) -> Vec<(PathBuf, CacheCategory, u64, SystemTime)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|meta| meta.is_file())?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            Some((
+                entry.path(),
+                CacheCategory::of_preview_file(&name),
+                metadata.len(),
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            ))
+        })
+        .collect()
+}
+
+fn subtitle_temp_dirs(min_age: Duration) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(SUBTITLE_TEMP_PREFIX)
+        })
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= min_age)
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Delete least recently used files in `dir` until it fits in `max_bytes`.
+/// Returns the number of files removed.
+pub fn evict_lru(dir: &Path, max_bytes: u64) -> u32 {
+    let mut files = preview_files(dir);
+    let mut total: u64 = files.iter().map(|(_, _, size, _)| size).sum();
+    if total <= max_bytes {
+        return 0;
+    }
+
+    files.sort_by_key(|(_, _, _, modified)| *modified);
+    let mut removed = 0;
+    for (path, _, size, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if remove_path(&path) {
+            total = total.saturating_sub(size);
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Apply the configured cap to the previews folder
+pub fn enforce_cache_limit(app: &AppHandle) -> u32 {
+    match (preview_cache_dir(app), max_cache_bytes()) {
+        (Ok(dir), Some(max_bytes)) => evict_lru(&dir, max_bytes),
+        _ => 0,
+    }
+}
+
+pub fn cache_stats(app: &AppHandle) -> CacheStats {
+    let mut categories: Vec<CacheCategoryStats> = CacheCategory::ALL
+        .iter()
+        .map(|category| CacheCategoryStats {
+            category: *category,
+            count: 0,
+            bytes: 0,
+        })
+        .collect();
+    let mut add = |category: CacheCategory, bytes: u64| {
+        if let Some(stats) = categories.iter_mut().find(|s| s.category == category) {
+            stats.count += 1;
+            stats.bytes += bytes;
+        }
+    };
+
+    if let Ok(dir) = preview_cache_dir(app) {
+        for (_, category, size, _) in preview_files(&dir) {
+            add(category, size);
+        }
+    }
+    for dir in subtitle_temp_dirs(Duration::ZERO) {
+        add(CacheCategory::Subtitles, path_size(&dir));
+    }
+    for dir in orphaned_download_temp_dirs(app) {
+        add(CacheCategory::DownloadTemp, path_size(&dir));
+    }
+
+    CacheStats {
+        total_bytes: categories.iter().map(|stats| stats.bytes).sum(),
+        categories,
+        max_bytes: max_cache_bytes(),
+    }
+}
+
+/// Remove everything in the given categories. Temp files of running or
+/// interrupted downloads are kept. Returns the number of entries removed.
+pub fn clear_cache_categories(app: &AppHandle, categories: &[CacheCategory]) -> u32 {
+    let mut removed = 0;
+    if let Ok(dir) = preview_cache_dir(app) {
+        for (path, category, _, _) in preview_files(&dir) {
+            if categories.contains(&category) && remove_path(&path) {
+                removed += 1;
+            }
+        }
+    }
+    if categories.contains(&CacheCategory::Subtitles) {
+        for dir in subtitle_temp_dirs(SUBTITLE_TEMP_MIN_AGE) {
+            if remove_path(&dir) {
+                removed += 1;
+            }
+        }
+    }
+    if categories.contains(&CacheCategory::DownloadTemp) {
+        removed += cleanup_orphaned_download_temp(app);
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_files_are_categorized_by_prefix() {
+        assert_eq!(
+            CacheCategory::of_preview_file("thumb_1.jpg"),
+            CacheCategory::Thumbnails
+        );
+        assert_eq!(
+            CacheCategory::of_preview_file("audio_1.wav"),
+            CacheCategory::AudioPreviews
+        );
+        assert_eq!(
+            CacheCategory::of_preview_file("preview_1.mp4"),
+            CacheCategory::Previews
+        );
+    }
+
+    #[test]
+    fn evict_lru_removes_oldest_files_first() {
+        let dir = std::env::temp_dir().join(format!("youwee-cache-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        for (name, age) in [
+            ("preview_a.mp4", 30),
+            ("thumb_b.jpg", 20),
+            ("audio_c.wav", 10),
+        ] {
+            let path = dir.join(name);
+            std::fs::write(&path, [0u8; 100]).unwrap();
+            std::fs::File::options()
+                .append(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(age))
+                .unwrap();
+        }
+        touch_cache_entry(&dir.join("preview_a.mp4"));
+
+        assert_eq!(evict_lru(&dir, 300), 0);
+        assert_eq!(evict_lru(&dir, 150), 2);
+        assert!(dir.join("preview_a.mp4").exists());
+        assert!(!dir.join("thumb_b.jpg").exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,72 @@
+use std::process::Stdio;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::utils::CommandExt;
+
+/// Clipboard tools to try in order, as (program, args) reading text on stdin
+fn clipboard_tools() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[])]
+    } else if cfg!(windows) {
+        // clip.exe mangles non-ASCII text; read stdin as UTF-8 instead
+        vec![(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "[Console]::InputEncoding = [Text.Encoding]::UTF8; Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+            ],
+        )]
+    } else {
+        let mut tools: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            tools.push(("wl-copy", &[]));
+        }
+        tools.push(("xclip", &["-selection", "clipboard"]));
+        tools.push(("xsel", &["--clipboard", "--input"]));
+        tools
+    }
+}
+
+async fn pipe_to_tool(program: &str, args: &[&str], text: &str) -> Result<(), String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    cmd.hide_window();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+    }
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+/// Put `text` on the system clipboard using the platform's own tool, so it
+/// works the same from the window and from tray or context menus
+pub async fn copy_text_to_clipboard(text: &str) -> Result<(), String> {
+    let mut last_error = "No clipboard tool available".to_string();
+    for (program, args) in clipboard_tools() {
+        match pipe_to_tool(program, args, text).await {
+            Ok(()) => return Ok(()),
+            Err(error) => last_error = error,
+        }
+    }
+    Err(format!("Failed to copy to clipboard: {}", last_error))
+}
@@ -0,0 +1,327 @@
+use serde::Serialize;
+
+/// Players the advisor knows the codec support of
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PlaybackTarget {
+    QuickTime,
+    WindowsMediaPlayer,
+    Tv,
+}
+
+impl PlaybackTarget {
+    const ALL: [Self; 3] = [Self::QuickTime, Self::WindowsMediaPlayer, Self::Tv];
+
+    fn parse(target: &str) -> Result<Vec<Self>, String> {
+        match target.trim().to_ascii_lowercase().as_str() {
+            "" | "all" | "any" => Ok(Self::ALL.to_vec()),
+            "quicktime" | "apple" | "ios" => Ok(vec![Self::QuickTime]),
+            "wmp" | "windows" => Ok(vec![Self::WindowsMediaPlayer]),
+            "tv" => Ok(vec![Self::Tv]),
+            other => Err(format!("Unknown playback target '{}'", other)),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::QuickTime => "QuickTime",
+            Self::WindowsMediaPlayer => "Windows Media Player",
+            Self::Tv => "TVs",
+        }
+    }
+
+    fn container_issue(self, container: &str) -> Option<String> {
+        match (self, container) {
+            (Self::QuickTime, "mkv" | "webm") => Some(format!(
+                "QuickTime can't open {} files",
+                container.to_ascii_uppercase()
+            )),
+            (Self::WindowsMediaPlayer, "webm") => {
+                Some("Windows Media Player needs the Web Media Extensions for WebM".to_string())
+            }
+            (Self::Tv, "webm") => Some("Most TVs can't play WebM files".to_string()),
+            _ => None,
+        }
+    }
+
+    fn video_issue(self, codec: &str) -> Option<String> {
+        let message = match (self, codec) {
+            (Self::QuickTime, "vp9") => "QuickTime doesn't play VP9 video",
+            (Self::QuickTime, "av1") => "QuickTime only plays AV1 on Macs with an M3 chip or newer",
+            (Self::WindowsMediaPlayer, "vp9") => {
+                "Windows Media Player needs the VP9 Video Extensions from the Microsoft Store"
+            }
+            (Self::WindowsMediaPlayer, "av1") => {
+                "Windows Media Player needs the AV1 Video Extension from the Microsoft Store"
+            }
+            (Self::Tv, "vp9") => "Many TVs can't play VP9 video from USB or DLNA",
+            (Self::Tv, "av1") => "Only TVs from around 2020 onwards decode AV1 video",
+            _ => return None,
+        };
+        Some(message.to_string())
+    }
+
+    fn audio_issue(self, codec: &str) -> Option<String> {
+        let message = match (self, codec) {
+            (Self::QuickTime, "opus") => "QuickTime doesn't play Opus audio",
+            (Self::WindowsMediaPlayer, "opus") => {
+                "Windows Media Player needs the Web Media Extensions for Opus audio"
+            }
+            (Self::Tv, "opus") => "Most TVs can't decode Opus audio",
+            _ => return None,
+        };
+        Some(message.to_string())
+    }
+}
+
+/// One problem a playback target has with the selected output
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityWarning {
+    pub target: String,
+    pub message: String,
+}
+
+/// Settings that avoid the warnings
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilitySuggestion {
+    pub quality: String,
+    pub format: String,
+    pub video_codec: String,
+    /// No H.264 stream exists at this quality, so the file is re-encoded after download
+    pub reencode: bool,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityReport {
+    pub compatible: bool,
+    pub warnings: Vec<CompatibilityWarning>,
+    pub suggestion: Option<CompatibilitySuggestion>,
+}
+
+fn is_audio_only(quality: &str, format: &str) -> bool {
+    quality == "audio" || matches!(format, "mp3" | "m4a" | "opus")
+}
+
+/// Video and audio codec the download most likely ends up with, following
+/// the preferences of `build_format_string` and what YouTube offers: no
+/// H.264 above 1080p, Opus unless an M4A stream is asked for.
+fn expected_codecs(
+    quality: &str,
+    format: &str,
+    video_codec: &str,
+) -> (Option<&'static str>, &'static str) {
+    if is_audio_only(quality, format) {
+        let audio = match format {
+            "mp3" => "mp3",
+            "opus" => "opus",
+            _ => "aac",
+        };
+        return (None, audio);
+    }
+
+    let is_high_res = matches!(quality, "8k" | "4k" | "2k");
+    let has_height = is_high_res || matches!(quality, "1080" | "720" | "480" | "360");
+    let auto_video = if quality == "8k" { "av1" } else { "vp9" };
+    let video = match video_codec {
+        "vp9" => "vp9",
+        "av1" => "av1",
+        "h264" if format != "webm" && !is_high_res => "h264",
+        // H.264 falls back to the MP4 (AV1) stream at high resolutions
+        "h264" if format == "mp4" => "av1",
+        _ if format == "mp4" && has_height && !is_high_res => "h264",
+        _ => auto_video,
+    };
+    let explicit_codec = matches!(video_codec, "h264" | "vp9" | "av1");
+    let audio = if format == "mp4" && has_height && (explicit_codec || !is_high_res) {
+        "aac"
+    } else {
+        "opus"
+    };
+    (Some(video), audio)
+}
+
+fn suggest(
+    quality: &str,
+    format: &str,
+    video_codec: &str,
+    video_conflict: bool,
+) -> CompatibilitySuggestion {
+    if is_audio_only(quality, format) {
+        return CompatibilitySuggestion {
+            quality: quality.to_string(),
+            format: "m4a".to_string(),
+            video_codec: video_codec.to_string(),
+            reencode: false,
+            reason: "M4A (AAC) audio plays everywhere".to_string(),
+        };
+    }
+    // Without a height limit the best audio is Opus even in MP4
+    let quality = if matches!(quality, "best" | "") {
+        "1080"
+    } else {
+        quality
+    };
+    if !video_conflict {
+        return CompatibilitySuggestion {
+            quality: quality.to_string(),
+            format: "mp4".to_string(),
+            video_codec: video_codec.to_string(),
+            reencode: false,
+            reason: "MP4 with AAC audio is the most widely supported container".to_string(),
+        };
+    }
+    if matches!(quality, "8k" | "4k" | "2k") {
+        return CompatibilitySuggestion {
+            quality: quality.to_string(),
+            format: "mp4".to_string(),
+            video_codec: "h264".to_string(),
+            reencode: true,
+            reason:
+                "H.264 isn't offered above 1080p; re-encode after download (slow) or pick 1080p"
+                    .to_string(),
+        };
+    }
+    CompatibilitySuggestion {
+        quality: quality.to_string(),
+        format: "mp4".to_string(),
+        video_codec: "h264".to_string(),
+        reencode: false,
+        reason: "MP4 with H.264 video and AAC audio plays on every target".to_string(),
+    }
+}
+
+/// Warn about a quality/format/codec selection that `target` (quicktime,
+/// wmp, tv or all) won't play, and suggest settings that will
+pub fn check_container_compatibility(
+    quality: &str,
+    format: &str,
+    video_codec: &str,
+    target: &str,
+) -> Result<CompatibilityReport, String> {
+    let targets = PlaybackTarget::parse(target)?;
+    let (video, audio) = expected_codecs(quality, format, video_codec);
+
+    let mut warnings = Vec::new();
+    let mut video_conflict = false;
+    for target in targets {
+        let video_issue = video.and_then(|codec| target.video_issue(codec));
+        video_conflict |= video_issue.is_some();
+        let container_issue = video.and_then(|_| target.container_issue(format));
+        for message in [container_issue, video_issue, target.audio_issue(audio)]
+            .into_iter()
+            .flatten()
+        {
+            warnings.push(CompatibilityWarning {
+                target: target.label().to_string(),
+                message,
+            });
+        }
+    }
+
+    let suggestion =
+        (!warnings.is_empty()).then(|| suggest(quality, format, video_codec, video_conflict));
+    Ok(CompatibilityReport {
+        compatible: warnings.is_empty(),
+        warnings,
+        suggestion,
+    })
+}
+
+/// yt-dlp flags that merge losslessly into MKV, then re-encode to an
+/// H.264/AAC MP4 (`--recode-video` skips files already in the target container)
+pub fn compatibility_reencode_args() -> Vec<String> {
+    [
+        "--merge-output-format",
+        "mkv",
+        "--recode-video",
+        "mp4",
+        "--postprocessor-args",
+        "VideoConvertor:-c:v libx264 -preset medium -crf 20 -c:a aac -b:a 192k",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mp4_with_vp9_or_opus_warns_for_quicktime() {
+        let report = check_container_compatibility("4k", "mp4", "auto", "quicktime").unwrap();
+        assert!(!report.compatible);
+        let messages: Vec<_> = report.warnings.iter().map(|w| w.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "QuickTime doesn't play VP9 video",
+                "QuickTime doesn't play Opus audio"
+            ]
+        );
+        let suggestion = report.suggestion.unwrap();
+        assert_eq!(
+            (suggestion.format.as_str(), suggestion.video_codec.as_str()),
+            ("mp4", "h264")
+        );
+        assert!(suggestion.reencode);
+
+        let report = check_container_compatibility("1080", "webm", "auto", "quicktime").unwrap();
+        let suggestion = report.suggestion.unwrap();
+        assert!(!suggestion.reencode);
+        assert_eq!(suggestion.video_codec, "h264");
+
+        let report = check_container_compatibility("audio", "opus", "auto", "all").unwrap();
+        assert_eq!(report.warnings.len(), 3);
+        assert_eq!(report.suggestion.unwrap().format, "m4a");
+    }
+
+    #[test]
+    fn suggestions_are_compatible_and_defaults_pass() {
+        for target in ["quicktime", "wmp", "tv", "all"] {
+            assert!(
+                check_container_compatibility("1080", "mp4", "auto", target)
+                    .unwrap()
+                    .compatible
+            );
+            assert!(
+                check_container_compatibility("720", "mp4", "h264", target)
+                    .unwrap()
+                    .compatible
+            );
+            for (quality, format, codec) in [
+                ("1080", "mkv", "vp9"),
+                ("best", "webm", "av1"),
+                ("480", "mkv", "h264"),
+            ] {
+                let report = check_container_compatibility(quality, format, codec, target).unwrap();
+                if let Some(s) = report.suggestion {
+                    assert!(
+                        check_container_compatibility(
+                            &s.quality,
+                            &s.format,
+                            &s.video_codec,
+                            target
+                        )
+                        .unwrap()
+                        .compatible,
+                        "{} {} {} -> {:?}",
+                        quality,
+                        format,
+                        codec,
+                        s
+                    );
+                }
+            }
+        }
+        // MKV itself is fine outside Apple's players; only its Opus audio isn't
+        let report = check_container_compatibility("720", "mkv", "h264", "wmp").unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].message.contains("Opus"));
+        assert_eq!(report.suggestion.unwrap().video_codec, "h264");
+        assert!(check_container_compatibility("720", "mp4", "h264", "vlc").is_err());
+    }
+}
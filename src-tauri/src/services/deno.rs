@@ -1,12 +1,20 @@
-use crate::types::DenoStatus;
+use super::github::github_api_get;
+use crate::types::{code, BackendError, DenoStatus};
 #[cfg(not(windows))]
 use crate::utils::unix_system_binary_dirs;
-use crate::utils::{find_system_binary, CommandExt};
-use std::path::PathBuf;
+use crate::utils::{data_dir, find_system_binary, CommandExt};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use tauri::{AppHandle, Manager};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
 use tokio::process::Command;
 
+const JS_RUNTIME_CHECK_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Deno binaries that already ran a script this session
+static VERIFIED_JS_RUNTIMES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
 fn get_system_deno_path() -> Option<PathBuf> {
     #[cfg(windows)]
     let binary_name = "deno.exe";
@@ -34,7 +42,7 @@ fn get_system_deno_path() -> Option<PathBuf> {
 /// Get the Deno binary path (app data or system)
 pub async fn get_deno_path(app: &AppHandle) -> Option<PathBuf> {
     // First check app data directory
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
+    if let Ok(app_data_dir) = data_dir(app) {
         let bin_dir = app_data_dir.join("bin");
         #[cfg(windows)]
         let deno_path = bin_dir.join("deno.exe");
@@ -49,10 +57,94 @@ pub async fn get_deno_path(app: &AppHandle) -> Option<PathBuf> {
     get_system_deno_path()
 }
 
+/// Run `deno eval` to prove the binary starts and executes JavaScript. A
+/// corrupt or quarantined binary fails here instead of deep inside yt-dlp.
+pub async fn check_js_runtime(deno_path: &Path) -> Result<(), String> {
+    let mut cmd = Command::new(deno_path);
+    cmd.args(["eval", "console.log(1 + 1)"])
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    cmd.hide_window();
+
+    let result = match tokio::time::timeout(JS_RUNTIME_CHECK_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) if output.status.success() => {
+            if String::from_utf8_lossy(&output.stdout).trim() == "2" {
+                Ok(())
+            } else {
+                Err("Deno ran but printed an unexpected result".to_string())
+            }
+        }
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(stderr
+                .lines()
+                .rev()
+                .find(|line| !line.trim().is_empty())
+                .map(|line| line.trim().to_string())
+                .unwrap_or_else(|| format!("Deno exited with {}", output.status)))
+        }
+        Ok(Err(e)) => Err(format!("Failed to start Deno: {}", e)),
+        Err(_) => Err(format!(
+            "Deno did not respond within {} seconds",
+            JS_RUNTIME_CHECK_TIMEOUT.as_secs()
+        )),
+    };
+
+    if let Ok(mut verified) = VERIFIED_JS_RUNTIMES.lock() {
+        verified.retain(|path| path != deno_path);
+        if result.is_ok() {
+            verified.push(deno_path.to_path_buf());
+        }
+    }
+    result
+}
+
+/// Whether `deno_path` is the copy installed into app data (and can be reinstalled)
+pub fn is_app_managed_deno(app: &AppHandle, deno_path: &Path) -> bool {
+    data_dir(app).is_ok_and(|dir| deno_path.starts_with(dir.join("bin")))
+}
+
+/// Structured JS_RUNTIME_BROKEN error; `canReinstall` lets the UI offer a fresh copy
+pub fn js_runtime_broken_error(
+    deno_path: &Path,
+    reason: &str,
+    can_reinstall: bool,
+) -> BackendError {
+    BackendError::new(
+        code::JS_RUNTIME_BROKEN,
+        format!("The JavaScript runtime (Deno) is broken: {}", reason),
+    )
+    .with_param("binaryPath", deno_path.to_string_lossy().to_string())
+    .with_param("reason", reason)
+    .with_param("canReinstall", can_reinstall)
+    .with_retryable(false)
+}
+
+/// Deno path for YouTube extraction, checked the first time it is used this
+/// session. `None` when Deno isn't installed at all.
+pub async fn ensure_js_runtime(app: &AppHandle) -> Result<Option<PathBuf>, BackendError> {
+    let Some(deno_path) = get_deno_path(app).await else {
+        return Ok(None);
+    };
+    let already_verified = VERIFIED_JS_RUNTIMES
+        .lock()
+        .map(|verified| verified.contains(&deno_path))
+        .unwrap_or(false);
+    if !already_verified {
+        check_js_runtime(&deno_path).await.map_err(|reason| {
+            js_runtime_broken_error(&deno_path, &reason, is_app_managed_deno(app, &deno_path))
+        })?;
+    }
+    Ok(Some(deno_path))
+}
+
 /// Check Deno runtime status
 pub async fn check_deno_internal(app: &AppHandle) -> Result<DenoStatus, String> {
     // First check app data directory
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
+    if let Ok(app_data_dir) = data_dir(app) {
         let bin_dir = app_data_dir.join("bin");
         #[cfg(windows)]
         let deno_path = bin_dir.join("deno.exe");
@@ -216,29 +308,7 @@ pub async fn check_deno_update_internal(app: &AppHandle) -> Result<DenoUpdateInf
     }
 
     // Fetch latest release from GitHub API
-    let client = reqwest::Client::builder()
-        .user_agent("Youwee/0.6.0")
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get("https://api.github.com/repos/denoland/deno/releases/latest")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to fetch release info: HTTP {}",
-            response.status()
-        ));
-    }
-
-    let json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+    let json = github_api_get("https://api.github.com/repos/denoland/deno/releases/latest").await?;
 
     let tag_name = json["tag_name"].as_str().ok_or("No tag_name in release")?;
 
@@ -261,3 +331,33 @@ pub async fn check_deno_update_internal(app: &AppHandle) -> Result<DenoUpdateInf
         release_url: html_url,
     })
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn fake_deno(dir: &Path, name: &str, script: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn js_runtime_check_requires_evaluated_output() {
+        let dir = std::env::temp_dir().join(format!("youwee-deno-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let working = fake_deno(&dir, "working", "echo 2");
+        let crashing = fake_deno(&dir, "crashing", "echo 'Killed: 9' >&2; exit 137");
+
+        assert!(check_js_runtime(&working).await.is_ok());
+        assert!(VERIFIED_JS_RUNTIMES.lock().unwrap().contains(&working));
+        assert_eq!(check_js_runtime(&crashing).await.unwrap_err(), "Killed: 9");
+        assert!(check_js_runtime(&dir.join("missing")).await.is_err());
+
+        let error = js_runtime_broken_error(&crashing, "Killed: 9", true);
+        assert_eq!(error.code(), code::JS_RUNTIME_BROKEN);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
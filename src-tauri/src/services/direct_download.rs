@@ -0,0 +1,470 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_DISPOSITION, CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tokio::io::AsyncWriteExt;
+
+use crate::types::{code, BackendError, DownloadProgress, DOWNLOAD_PROGRESS};
+use crate::utils::{format_size, unique_output_path};
+
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+const PARTIAL_PREFIX: &str = ".youwee-";
+const PARTIAL_SUFFIX: &str = ".part";
+const MAX_FILENAME_CHARS: usize = 180;
+/// A stalled connection fails after this long without data; the .part file resumes it
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+const HLS_CONTENT_TYPES: [&str; 3] = [
+    "application/vnd.apple.mpegurl",
+    "application/x-mpegurl",
+    "audio/mpegurl",
+];
+
+/// File saved by the native HTTP downloader
+pub struct DirectDownloadOutcome {
+    pub filepath: String,
+    pub filesize: u64,
+    pub resumed: bool,
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn sanitize_filename(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').to_string();
+    cleaned.chars().take(MAX_FILENAME_CHARS).collect()
+}
+
+/// Pick a file name from `Content-Disposition`, falling back to the last URL segment
+pub fn direct_download_filename(url: &str, content_disposition: Option<&str>) -> String {
+    let from_header = content_disposition.and_then(|header| {
+        // RFC 5987 `filename*=UTF-8''name` wins over the plain `filename=`
+        let extended = header.split(';').find_map(|part| {
+            part.trim()
+                .strip_prefix("filename*=")
+                .and_then(|value| value.split("''").nth(1))
+                .map(percent_decode)
+        });
+        extended.or_else(|| {
+            header.split(';').find_map(|part| {
+                part.trim()
+                    .strip_prefix("filename=")
+                    .map(|value| value.trim_matches('"').to_string())
+            })
+        })
+    });
+
+    let from_url = || {
+        reqwest::Url::parse(url).ok().and_then(|parsed| {
+            parsed
+                .path_segments()
+                .and_then(|mut segments| segments.next_back().map(percent_decode))
+                .filter(|segment| !segment.trim().is_empty())
+        })
+    };
+
+    from_header
+        .map(|name| sanitize_filename(&name))
+        .filter(|name| !name.is_empty())
+        .or_else(|| from_url().map(|name| sanitize_filename(&name)))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "download".to_string())
+}
+
+/// Total size from a `Content-Range: bytes 100-199/1000` header
+fn content_range_total(header: &str) -> Option<u64> {
+    header.rsplit('/').next()?.trim().parse().ok()
+}
+
+/// `.part` file of `url`, keyed by the URL so a changed file name still
+/// resumes and two URLs serving the same name don't mix their bytes
+fn partial_path_for(output_dir: &Path, url: &str) -> PathBuf {
+    let key = hex::encode(Sha256::digest(url.as_bytes()));
+    output_dir.join(format!(
+        "{}{}{}",
+        PARTIAL_PREFIX,
+        &key[..16],
+        PARTIAL_SUFFIX
+    ))
+}
+
+/// Whether `name` is a `.part` file written by [`partial_path_for`]
+pub(crate) fn is_direct_partial_file(name: &str) -> bool {
+    name.starts_with(PARTIAL_PREFIX) && name.ends_with(PARTIAL_SUFFIX)
+}
+
+/// HLS playlists are text manifests; saving them would produce a useless file
+fn is_hls_playlist(filename: &str, content_type: Option<&str>) -> bool {
+    let content_type = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase());
+    content_type.is_some_and(|value| HLS_CONTENT_TYPES.contains(&value.as_str()))
+        || filename.to_ascii_lowercase().ends_with(".m3u8")
+}
+
+/// `filename` in `output_dir`, numbered when a file with that name exists
+fn unique_target_path(output_dir: &Path, filename: &str) -> PathBuf {
+    let path = Path::new(filename);
+    match (
+        path.file_stem().and_then(|stem| stem.to_str()),
+        path.extension().and_then(|ext| ext.to_str()),
+    ) {
+        (Some(stem), Some(ext)) => unique_output_path(output_dir, stem, ext),
+        _ => unique_output_path(output_dir, filename, ""),
+    }
+}
+
+fn format_eta(seconds: u64) -> String {
+    let (hours, minutes, secs) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+fn http_error(message: String) -> BackendError {
+    BackendError::new(code::NETWORK_REQUEST_FAILED, message).with_retryable(true)
+}
+
+fn hls_playlist_error() -> BackendError {
+    BackendError::new(
+        code::UNSUPPORTED_URL,
+        "This link is an HLS (.m3u8) stream playlist, not a file. Download it through yt-dlp instead.",
+    )
+    .with_retryable(false)
+}
+
+/// Stream `url` into `output_dir` (named `preferred_stem` plus the served
+/// extension when given), resuming a previous `.part` file with a
+/// Range request when the server supports it. An existing file with the
+/// same name is never replaced; HLS playlists are rejected. Progress is emitted as
+/// `download-progress` events with the same shape yt-dlp downloads use.
+pub async fn stream_direct_download(
+    app: &AppHandle,
+    id: &str,
+    url: &str,
+    output_dir: &Path,
+    proxy_url: Option<&str>,
+    preferred_stem: Option<&str>,
+    cancel_flag: &AtomicBool,
+) -> Result<DirectDownloadOutcome, BackendError> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (compatible; Youwee)")
+        .connect_timeout(Duration::from_secs(20));
+    if let Some(proxy) = proxy_url.map(str::trim).filter(|p| !p.is_empty()) {
+        let proxy = reqwest::Proxy::all(proxy)
+            .map_err(|e| BackendError::from_message(format!("Invalid proxy URL: {}", e)))?;
+        builder = builder.proxy(proxy);
+    }
+    let client = builder
+        .build()
+        .map_err(|e| BackendError::from_message(format!("Failed to create HTTP client: {}", e)))?;
+
+    let head = client.head(url).send().await.ok();
+    let head_disposition = head
+        .as_ref()
+        .and_then(|r| r.headers().get(CONTENT_DISPOSITION))
+        .and_then(|v| v.to_str().ok())
+        .map(ToString::to_string);
+    let mut filename = direct_download_filename(
+        head.as_ref().map(|r| r.url().as_str()).unwrap_or(url),
+        head_disposition.as_deref(),
+    );
+    if let Some(stem) = preferred_stem
+        .map(sanitize_filename)
+        .filter(|stem| !stem.is_empty())
+    {
+        filename = match Path::new(&filename).extension().and_then(|e| e.to_str()) {
+            Some(ext) => format!("{}.{}", stem, ext),
+            None => stem,
+        };
+    }
+    let head_content_type = head
+        .as_ref()
+        .and_then(|r| r.headers().get(CONTENT_TYPE))
+        .and_then(|v| v.to_str().ok());
+    if is_hls_playlist(&filename, head_content_type) {
+        return Err(hls_playlist_error());
+    }
+    let partial = partial_path_for(output_dir, url);
+
+    let existing_bytes = tokio::fs::metadata(&partial)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    let mut request = client.get(url);
+    if existing_bytes > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing_bytes));
+    }
+
+    let response = request.send().await.map_err(|e| {
+        if e.is_timeout() {
+            BackendError::new(code::NETWORK_TIMEOUT, "Direct download timed out")
+                .with_retryable(true)
+        } else {
+            http_error(format!("Direct download failed: {}", e))
+        }
+    })?;
+
+    let status = response.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE && existing_bytes > 0 {
+        // The previous attempt already received every byte
+        let target = unique_target_path(output_dir, &filename);
+        tokio::fs::rename(&partial, &target)
+            .await
+            .map_err(|e| BackendError::from_message(format!("Failed to finalize file: {}", e)))?;
+        return Ok(DirectDownloadOutcome {
+            filepath: target.to_string_lossy().to_string(),
+            filesize: existing_bytes,
+            resumed: true,
+        });
+    }
+    if !status.is_success() {
+        return Err(http_error(format!(
+            "Direct download failed: HTTP {}",
+            status
+        )));
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    if is_hls_playlist(&filename, content_type) {
+        return Err(hls_playlist_error());
+    }
+
+    let resumed = status == StatusCode::PARTIAL_CONTENT && existing_bytes > 0;
+    let total_size = if resumed {
+        response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(content_range_total)
+    } else {
+        response.content_length()
+    };
+
+    let mut file = if resumed {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&partial)
+            .await
+    } else {
+        // Server ignored the Range header; start over
+        tokio::fs::File::create(&partial).await
+    }
+    .map_err(|e| BackendError::from_message(format!("Failed to open output file: {}", e)))?;
+
+    let mut downloaded = if resumed { existing_bytes } else { 0 };
+    let session_start_bytes = downloaded;
+    let started_at = Instant::now();
+    let mut last_emit = Instant::now() - PROGRESS_EMIT_INTERVAL;
+    let mut stream = response.bytes_stream();
+
+    loop {
+        let chunk = match tokio::time::timeout(STREAM_IDLE_TIMEOUT, stream.next()).await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(_) => {
+                file.flush().await.ok();
+                return Err(BackendError::new(
+                    code::NETWORK_TIMEOUT,
+                    format!(
+                        "No data received for {} seconds. Retry to resume.",
+                        STREAM_IDLE_TIMEOUT.as_secs()
+                    ),
+                )
+                .with_retryable(true));
+            }
+        };
+        if cancel_flag.load(Ordering::SeqCst) {
+            // Keep the .part file so the next attempt can resume
+            file.flush().await.ok();
+            return Err(
+                BackendError::new(code::DOWNLOAD_CANCELLED, "Download cancelled")
+                    .with_retryable(false),
+            );
+        }
+        let chunk = chunk.map_err(|e| http_error(format!("Connection interrupted: {}", e)))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| BackendError::from_message(format!("Failed to write file: {}", e)))?;
+        downloaded += chunk.len() as u64;
+
+        if last_emit.elapsed() >= PROGRESS_EMIT_INTERVAL {
+            last_emit = Instant::now();
+            let elapsed = started_at.elapsed().as_secs_f64().max(0.001);
+            let bytes_per_sec = (downloaded - session_start_bytes) as f64 / elapsed;
+            let percent = total_size
+                .filter(|total| *total > 0)
+                .map(|total| (downloaded as f64 / total as f64 * 100.0).min(100.0))
+                .unwrap_or(0.0);
+            let eta = match total_size {
+                Some(total) if bytes_per_sec > 0.0 && total > downloaded => {
+                    format_eta(((total - downloaded) as f64 / bytes_per_sec) as u64)
+                }
+                _ => String::new(),
+            };
+
+            DOWNLOAD_PROGRESS
+                .emit(
+                    app,
+                    &DownloadProgress {
+                        id: id.to_string(),
+                        percent,
+                        speed: format!("{}/s", format_size(bytes_per_sec as u64)),
+                        eta,
+                        status: "downloading".to_string(),
+                        title: Some(filename.clone()),
+                        playlist_index: None,
+                        playlist_count: None,
+                        filesize: total_size,
+                        resolution: None,
+                        format_ext: None,
+                        error_message: None,
+                        error_code: None,
+                        error_params: None,
+                        history_id: None,
+                        filepath: None,
+                        downloaded_size: Some(format_size(downloaded)),
+                        elapsed_time: None,
+                        stage: Some("downloading".to_string()),
+                        stage_detail: None,
+                        indeterminate: false,
+                    },
+                )
+                .ok();
+        }
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| BackendError::from_message(format!("Failed to write file: {}", e)))?;
+    drop(file);
+
+    if let Some(total) = total_size {
+        if downloaded < total {
+            return Err(http_error(format!(
+                "Download ended early ({} of {}). Retry to resume.",
+                format_size(downloaded),
+                format_size(total)
+            )));
+        }
+    }
+
+    let target = unique_target_path(output_dir, &filename);
+    tokio::fs::rename(&partial, &target)
+        .await
+        .map_err(|e| BackendError::from_message(format!("Failed to finalize file: {}", e)))?;
+
+    Ok(DirectDownloadOutcome {
+        filepath: target.to_string_lossy().to_string(),
+        filesize: downloaded,
+        resumed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direct_download_filename_prefers_content_disposition() {
+        assert_eq!(
+            direct_download_filename(
+                "https://cdn.example.com/dl?id=1",
+                Some("attachment; filename=\"Report 2024.pdf\""),
+            ),
+            "Report 2024.pdf"
+        );
+        assert_eq!(
+            direct_download_filename(
+                "https://cdn.example.com/dl?id=1",
+                Some("attachment; filename=\"fallback.pdf\"; filename*=UTF-8''B%C3%A0i%20h%E1%BB%8Dc.mp4"),
+            ),
+            "Bài học.mp4"
+        );
+    }
+
+    #[test]
+    fn direct_download_filename_falls_back_to_url_segment() {
+        assert_eq!(
+            direct_download_filename("https://example.com/media/clip%201.mp4?token=x", None),
+            "clip 1.mp4"
+        );
+        assert_eq!(
+            direct_download_filename("https://example.com/a/..%2F..%2Fetc", None),
+            "_.._etc"
+        );
+        assert_eq!(
+            direct_download_filename("https://example.com/", None),
+            "download"
+        );
+    }
+
+    #[test]
+    fn partial_files_are_keyed_by_url_and_finished_files_are_never_replaced() {
+        let dir = std::env::temp_dir().join(format!("youwee-direct-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let a = partial_path_for(&dir, "https://a.example.com/file.mp4");
+        let b = partial_path_for(&dir, "https://b.example.com/file.mp4");
+        assert_ne!(a, b);
+        assert_eq!(a, partial_path_for(&dir, "https://a.example.com/file.mp4"));
+
+        assert_eq!(unique_target_path(&dir, "clip.mp4"), dir.join("clip.mp4"));
+        std::fs::write(dir.join("clip.mp4"), b"x").unwrap();
+        std::fs::write(dir.join("README"), b"x").unwrap();
+        assert_eq!(
+            unique_target_path(&dir, "clip.mp4"),
+            dir.join("clip (1).mp4")
+        );
+        assert_eq!(unique_target_path(&dir, "README"), dir.join("README (1)"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn hls_playlists_are_detected_by_type_or_name() {
+        assert!(is_hls_playlist(
+            "index",
+            Some("application/vnd.apple.mpegURL; charset=utf-8")
+        ));
+        assert!(is_hls_playlist("master.M3U8", None));
+        assert!(!is_hls_playlist("clip.mp4", Some("video/mp4")));
+    }
+
+    #[test]
+    fn content_range_total_reads_full_size() {
+        assert_eq!(content_range_total("bytes 100-199/1000"), Some(1000));
+        assert_eq!(content_range_total("bytes 100-199/*"), None);
+    }
+}
@@ -0,0 +1,283 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{code, BackendError};
+use crate::utils::format_size;
+
+/// Pre-download limits; a download over one needs the user's confirmation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadGuardLimits {
+    pub max_duration_minutes: Option<u64>,
+    pub max_size_mb: Option<u64>,
+}
+
+impl DownloadGuardLimits {
+    pub fn is_active(&self) -> bool {
+        self.max_duration_minutes.is_some_and(|minutes| minutes > 0)
+            || self.max_size_mb.is_some_and(|mb| mb > 0)
+    }
+}
+
+/// What a dry run says about the download about to start
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DownloadGuardProbe {
+    pub duration: Option<f64>,
+    pub estimated_size: Option<u64>,
+    pub is_live: bool,
+}
+
+/// Limits the user already confirmed going over for this download
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DownloadGuardOverrides {
+    pub duration: bool,
+    pub size: bool,
+}
+
+// Synced from the frontend settings; both limits off by default
+static DOWNLOAD_GUARD_LIMITS: Mutex<DownloadGuardLimits> = Mutex::new(DownloadGuardLimits {
+    max_duration_minutes: None,
+    max_size_mb: None,
+});
+
+pub fn set_download_guard_limits(limits: DownloadGuardLimits) {
+    if let Ok(mut guard) = DOWNLOAD_GUARD_LIMITS.lock() {
+        *guard = limits;
+    }
+}
+
+pub fn download_guard_limits() -> DownloadGuardLimits {
+    DOWNLOAD_GUARD_LIMITS
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or_default()
+}
+
+/// `HH:MM:SS.ms`, `MM:SS` or plain seconds; `inf` is None
+fn parse_section_time(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let mut seconds = 0.0;
+    for part in value.split(':') {
+        let part: f64 = part.trim().parse().ok()?;
+        if !part.is_finite() || part < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + part;
+    }
+    Some(if negative { -seconds } else { seconds })
+}
+
+/// Seconds covered by `--download-sections` time ranges like
+/// `*00:01:00-00:02:30`, given the full length. None for chapter patterns or
+/// anything else that can't be measured before downloading.
+pub fn download_sections_seconds(sections: &str, total: Option<f64>) -> Option<f64> {
+    let mut covered = 0.0;
+    for range in sections.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        let range = range.strip_prefix('*')?;
+        let (start, end) = range
+            .strip_prefix('-')
+            .and_then(|rest| rest.split_once('-').map(|(s, e)| (format!("-{}", s), e)))
+            .or_else(|| range.split_once('-').map(|(s, e)| (s.to_string(), e)))?;
+        let resolve = |value: f64| match total {
+            Some(total) if value < 0.0 => (total + value).max(0.0),
+            Some(total) => value.min(total),
+            None => value,
+        };
+        let start = resolve(parse_section_time(&start)?);
+        let end = match end.trim() {
+            "inf" | "infinite" => total?,
+            end => resolve(parse_section_time(end)?),
+        };
+        covered += (end - start).max(0.0);
+    }
+    Some(covered)
+}
+
+/// Narrow a full-entry probe to the sections actually downloaded; the size
+/// is scaled by the share of the duration kept
+pub fn probe_for_sections(probe: DownloadGuardProbe, sections: &str) -> DownloadGuardProbe {
+    let Some(seconds) = download_sections_seconds(sections, probe.duration) else {
+        return probe;
+    };
+    let estimated_size = match (probe.estimated_size, probe.duration) {
+        (Some(size), Some(duration)) if duration > 0.0 => {
+            Some((size as f64 * (seconds / duration).min(1.0)) as u64)
+        }
+        (size, _) => size,
+    };
+    DownloadGuardProbe {
+        duration: Some(seconds),
+        estimated_size,
+        is_live: probe.is_live,
+    }
+}
+
+/// One probe for a whole playlist: durations and sizes summed, live if any
+/// entry is. None when there are no entries.
+pub fn combine_guard_probes(probes: &[DownloadGuardProbe]) -> Option<DownloadGuardProbe> {
+    if probes.is_empty() {
+        return None;
+    }
+    let sum = |values: Vec<Option<f64>>| -> Option<f64> {
+        values
+            .into_iter()
+            .flatten()
+            .fold(None, |total, value| Some(total.unwrap_or(0.0) + value))
+    };
+    Some(DownloadGuardProbe {
+        duration: sum(probes.iter().map(|probe| probe.duration).collect()),
+        estimated_size: sum(probes
+            .iter()
+            .map(|probe| probe.estimated_size.map(|size| size as f64))
+            .collect())
+        .map(|size| size as u64),
+        is_live: probes.iter().any(|probe| probe.is_live),
+    })
+}
+
+fn format_minutes(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{} min", minutes)
+    }
+}
+
+/// CONFIRM_REQUIRED error for the first limit the probe goes over; the
+/// frontend asks the user and retries with that limit overridden
+pub fn check_download_guard(
+    limits: DownloadGuardLimits,
+    probe: &DownloadGuardProbe,
+    overrides: DownloadGuardOverrides,
+) -> Result<(), BackendError> {
+    if !overrides.duration {
+        if let Some(max_minutes) = limits.max_duration_minutes.filter(|minutes| *minutes > 0) {
+            let max_seconds = (max_minutes * 60) as f64;
+            if probe.is_live {
+                return Err(BackendError::new(
+                    code::CONFIRM_REQUIRED,
+                    "This is a live stream; it may record for longer than your duration limit",
+                )
+                .with_param("reason", "live")
+                .with_param("maxDurationMinutes", max_minutes)
+                .with_retryable(false));
+            }
+            if let Some(duration) = probe.duration.filter(|duration| *duration > max_seconds) {
+                return Err(BackendError::new(
+                    code::CONFIRM_REQUIRED,
+                    format!(
+                        "This video is {} long, over your {} limit",
+                        format_minutes(duration),
+                        format_minutes(max_seconds)
+                    ),
+                )
+                .with_param("reason", "duration")
+                .with_param("durationSeconds", duration)
+                .with_param("maxDurationMinutes", max_minutes)
+                .with_retryable(false));
+            }
+        }
+    }
+    if !overrides.size {
+        if let Some(max_mb) = limits.max_size_mb.filter(|mb| *mb > 0) {
+            let max_bytes = max_mb * 1024 * 1024;
+            if let Some(size) = probe.estimated_size.filter(|size| *size > max_bytes) {
+                return Err(BackendError::new(
+                    code::CONFIRM_REQUIRED,
+                    format!(
+                        "This download is about {}, over your {} limit",
+                        format_size(size),
+                        format_size(max_bytes)
+                    ),
+                )
+                .with_param("reason", "size")
+                .with_param("estimatedSize", size)
+                .with_param("maxSizeMb", max_mb)
+                .with_retryable(false));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guard_asks_for_confirmation_until_overridden() {
+        let limits = DownloadGuardLimits {
+            max_duration_minutes: Some(120),
+            max_size_mb: Some(1024),
+        };
+        let probe = DownloadGuardProbe {
+            duration: Some(12.0 * 3600.0),
+            estimated_size: Some(3 * 1024 * 1024 * 1024),
+            is_live: false,
+        };
+
+        let error = check_download_guard(limits, &probe, DownloadGuardOverrides::default())
+            .expect_err("too long");
+        assert_eq!(error.code(), code::CONFIRM_REQUIRED);
+        assert_eq!(error.params().unwrap()["reason"], "duration");
+        assert_eq!(
+            error.message(),
+            "This video is 12h 00m long, over your 2h 00m limit"
+        );
+
+        let overrides = DownloadGuardOverrides {
+            duration: true,
+            size: false,
+        };
+        let error = check_download_guard(limits, &probe, overrides).expect_err("too big");
+        assert_eq!(error.params().unwrap()["reason"], "size");
+
+        let overrides = DownloadGuardOverrides {
+            duration: true,
+            size: true,
+        };
+        assert!(check_download_guard(limits, &probe, overrides).is_ok());
+        assert!(check_download_guard(
+            DownloadGuardLimits::default(),
+            &probe,
+            DownloadGuardOverrides::default()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn playlist_and_section_probes_are_measured() {
+        let entry = DownloadGuardProbe {
+            duration: Some(3600.0),
+            estimated_size: Some(1000),
+            is_live: false,
+        };
+        let unknown = DownloadGuardProbe::default();
+        let combined = combine_guard_probes(&[entry, unknown, entry]).expect("entries");
+        assert_eq!(combined.duration, Some(7200.0));
+        assert_eq!(combined.estimated_size, Some(2000));
+        assert!(!combined.is_live);
+        assert_eq!(combine_guard_probes(&[]), None);
+
+        assert_eq!(
+            download_sections_seconds("*00:10:00-00:25:00", Some(3600.0)),
+            Some(900.0)
+        );
+        assert_eq!(
+            download_sections_seconds("*-300-inf", Some(3600.0)),
+            Some(300.0)
+        );
+        assert_eq!(download_sections_seconds("intro", Some(3600.0)), None);
+
+        let clipped = probe_for_sections(entry, "*0-900");
+        assert_eq!(clipped.duration, Some(900.0));
+        assert_eq!(clipped.estimated_size, Some(250));
+        assert_eq!(probe_for_sections(entry, "intro"), entry);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+
+use super::{load_interrupted_downloads, remove_job_temp_dirs};
+use crate::database::{
+    load_download_journal, remove_download_journal_entry, save_download_journal_entry,
+    update_download_journal_progress,
+};
+use crate::types::{DownloadJournalEntry, DownloadProgress};
+
+/// Progress is written at most this often per download; stage changes always are
+const JOURNAL_WRITE_INTERVAL: Duration = Duration::from_secs(2);
+
+type JournalWrites = HashMap<String, (Instant, Option<String>)>;
+
+// Last write time and stage per journaled download
+static LAST_WRITES: Mutex<Option<JournalWrites>> = Mutex::new(None);
+
+/// Keeps a download in the journal until it ends, successfully or not
+pub struct DownloadJournalGuard {
+    id: String,
+}
+
+impl Drop for DownloadJournalGuard {
+    fn drop(&mut self) {
+        if let Ok(mut guard) = LAST_WRITES.lock() {
+            if let Some(writes) = guard.as_mut() {
+                writes.remove(&self.id);
+            }
+        }
+        remove_download_journal_entry(&self.id).ok();
+    }
+}
+
+pub fn start_download_journal(entry: DownloadJournalEntry) -> DownloadJournalGuard {
+    if let Err(e) = save_download_journal_entry(&entry) {
+        log::warn!("Failed to journal download {}: {}", entry.id, e);
+    }
+    DownloadJournalGuard { id: entry.id }
+}
+
+/// Record a `downloading` progress event for the journaled download
+pub fn journal_download_progress(progress: &DownloadProgress) {
+    if progress.status != "downloading" {
+        return;
+    }
+    let Ok(mut guard) = LAST_WRITES.lock() else {
+        return;
+    };
+    let writes = guard.get_or_insert_with(HashMap::new);
+    let due = match writes.get(&progress.id) {
+        Some((last_write, stage)) => {
+            *stage != progress.stage || last_write.elapsed() >= JOURNAL_WRITE_INTERVAL
+        }
+        None => true,
+    };
+    if !due {
+        return;
+    }
+    writes.insert(
+        progress.id.clone(),
+        (Instant::now(), progress.stage.clone()),
+    );
+    drop(guard);
+
+    update_download_journal_progress(
+        &progress.id,
+        progress.title.as_deref(),
+        progress.stage.as_deref(),
+        progress.percent,
+        progress.downloaded_size.as_deref(),
+    )
+    .ok();
+}
+
+/// Downloads a previous run lost without a clean quit. Those saved by the
+/// quit prompt are listed by `load_interrupted_downloads` instead.
+pub fn load_crashed_downloads() -> Vec<DownloadJournalEntry> {
+    let saved_on_quit: Vec<String> = load_interrupted_downloads()
+        .into_iter()
+        .map(|job| job.id)
+        .collect();
+    load_download_journal(true)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| !saved_on_quit.contains(&entry.id))
+        .collect()
+}
+
+/// Forget crashed downloads (all when `ids` is `None`) and remove their
+/// temp directories. Returns the number of entries discarded.
+pub fn discard_crashed_downloads(app: &AppHandle, ids: Option<Vec<String>>) -> u32 {
+    let mut discarded = 0;
+    for entry in load_download_journal(true).unwrap_or_default() {
+        if ids.as_ref().is_some_and(|ids| !ids.contains(&entry.id)) {
+            continue;
+        }
+        remove_job_temp_dirs(app, &entry.id);
+        if remove_download_journal_entry(&entry.id).is_ok() {
+            discarded += 1;
+        }
+    }
+    discarded
+}
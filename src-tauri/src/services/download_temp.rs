@@ -0,0 +1,278 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::AppHandle;
+
+use super::power::{active_jobs, ActiveJobKind};
+use super::quit_guard::{is_shutting_down, load_interrupted_downloads};
+use crate::database::load_download_journal;
+use crate::types::BackendError;
+use crate::utils::{data_dir, resolve_output_directory};
+
+/// Folder created inside the configured temp location. Only this folder is
+/// ever cleaned up so a shared scratch disk is never touched outside it.
+const DOWNLOAD_TEMP_SUBDIR: &str = "youwee-temp";
+/// Remembers which temp locations were used so orphans can be swept on startup.
+const DOWNLOAD_TEMP_ROOTS_FILE: &str = "download_temp_roots.json";
+
+static ROOTS_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Per-download working directory passed to yt-dlp via `--paths temp:`.
+///
+/// Fragments and intermediate streams land here; the directory is removed
+/// when the guard is dropped, after yt-dlp has moved the final file.
+pub struct DownloadTempDir {
+    path: PathBuf,
+}
+
+impl DownloadTempDir {
+    pub fn ytdlp_paths_arg(&self) -> String {
+        format!("temp:{}", self.path.to_string_lossy())
+    }
+}
+
+impl Drop for DownloadTempDir {
+    fn drop(&mut self) {
+        // Fragments of downloads interrupted by quitting are kept for resume
+        if is_shutting_down() {
+            return;
+        }
+        if self.path.exists() {
+            if let Err(e) = std::fs::remove_dir_all(&self.path) {
+                log::warn!(
+                    "Failed to remove download temp dir {}: {}",
+                    self.path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Create a working directory for one download inside `base_dir`.
+pub fn prepare_download_temp_dir(
+    app: &AppHandle,
+    base_dir: &str,
+    job_id: &str,
+) -> Result<DownloadTempDir, BackendError> {
+    let base = resolve_output_directory(base_dir, true)?;
+    let path = job_temp_dir(Path::new(&base), job_id);
+    std::fs::create_dir_all(&path).map_err(|e| {
+        BackendError::from_message(format!("Failed to create download temp directory: {}", e))
+    })?;
+
+    if let Ok(app_data_dir) = data_dir(app) {
+        remember_temp_root(&app_data_dir, &base);
+    }
+
+    Ok(DownloadTempDir { path })
+}
+
+fn known_temp_roots(app: &AppHandle) -> Vec<String> {
+    let Ok(app_data_dir) = data_dir(app) else {
+        return Vec::new();
+    };
+    let _guard = ROOTS_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    read_temp_roots(&app_data_dir)
+}
+
+/// Job directories of downloads that are running or saved for resume
+fn job_dirs_in_use() -> HashSet<String> {
+    load_interrupted_downloads()
+        .into_iter()
+        .chain(
+            active_jobs()
+                .into_iter()
+                .filter(|job| job.kind == ActiveJobKind::Download),
+        )
+        .map(|job| job.id)
+        .chain(
+            load_download_journal(false)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| entry.id),
+        )
+        .map(|id| safe_job_dir_name(&id))
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Remove the temp directories of one download from every known root
+pub fn remove_job_temp_dirs(app: &AppHandle, job_id: &str) -> u32 {
+    let name = safe_job_dir_name(job_id);
+    if name.is_empty() {
+        return 0;
+    }
+    known_temp_roots(app)
+        .iter()
+        .map(|root| Path::new(root).join(DOWNLOAD_TEMP_SUBDIR).join(&name))
+        .filter(|dir| dir.is_dir() && std::fs::remove_dir_all(dir).is_ok())
+        .count() as u32
+}
+
+/// Remove leftovers from downloads that were interrupted by a crash or forced quit.
+/// Returns the number of job directories removed.
+pub fn cleanup_orphaned_download_temp(app: &AppHandle) -> u32 {
+    let keep = job_dirs_in_use();
+    known_temp_roots(app)
+        .iter()
+        .map(|root| sweep_temp_root(Path::new(root), &keep))
+        .sum()
+}
+
+/// Leftover job directories that `cleanup_orphaned_download_temp` would remove
+pub fn orphaned_download_temp_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let keep = job_dirs_in_use();
+    known_temp_roots(app)
+        .iter()
+        .filter_map(|root| std::fs::read_dir(Path::new(root).join(DOWNLOAD_TEMP_SUBDIR)).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|entry| !keep.contains(entry.file_name().to_string_lossy().as_ref()))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+fn safe_job_dir_name(job_id: &str) -> String {
+    job_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn job_temp_dir(base: &Path, job_id: &str) -> PathBuf {
+    let safe_id = safe_job_dir_name(job_id);
+    let safe_id = if safe_id.is_empty() {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        safe_id
+    };
+    base.join(DOWNLOAD_TEMP_SUBDIR).join(safe_id)
+}
+
+fn sweep_temp_root(root: &Path, keep: &HashSet<String>) -> u32 {
+    let temp_dir = root.join(DOWNLOAD_TEMP_SUBDIR);
+    let entries = match std::fs::read_dir(&temp_dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    let mut removed = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        if keep.contains(entry.file_name().to_string_lossy().as_ref()) {
+            continue;
+        }
+        let path = entry.path();
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        match result {
+            Ok(()) => removed += 1,
+            Err(e) => log::warn!("Failed to remove orphaned temp {}: {}", path.display(), e),
+        }
+    }
+    std::fs::remove_dir(&temp_dir).ok();
+    removed
+}
+
+fn read_temp_roots(app_data_dir: &Path) -> Vec<String> {
+    std::fs::read_to_string(app_data_dir.join(DOWNLOAD_TEMP_ROOTS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Vec<String>>(&content).ok())
+        .unwrap_or_default()
+}
+
+fn remember_temp_root(app_data_dir: &Path, root: &str) {
+    let _guard = ROOTS_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut roots = read_temp_roots(app_data_dir);
+    if roots.iter().any(|existing| existing == root) {
+        return;
+    }
+    roots.push(root.to_string());
+
+    std::fs::create_dir_all(app_data_dir).ok();
+    if let Ok(content) = serde_json::to_string_pretty(&roots) {
+        if let Err(e) = std::fs::write(app_data_dir.join(DOWNLOAD_TEMP_ROOTS_FILE), content) {
+            log::warn!("Failed to save download temp roots: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_temp_root() -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("youwee-dl-temp-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn job_temp_dir_stays_inside_youwee_subfolder() {
+        let base = Path::new("/scratch");
+        assert_eq!(
+            job_temp_dir(base, "abc-123"),
+            PathBuf::from("/scratch/youwee-temp/abc-123")
+        );
+        assert_eq!(
+            job_temp_dir(base, "../etc"),
+            PathBuf::from("/scratch/youwee-temp/___etc")
+        );
+    }
+
+    #[test]
+    fn sweep_temp_root_only_removes_youwee_temp_contents() {
+        let root = make_temp_root();
+        let keep = root.join("keep.mp4");
+        std::fs::write(&keep, b"final").unwrap();
+        let job = root.join(DOWNLOAD_TEMP_SUBDIR).join("job-1");
+        std::fs::create_dir_all(&job).unwrap();
+        std::fs::write(job.join("video.f137.mp4.part-Frag3"), b"fragment").unwrap();
+
+        assert_eq!(sweep_temp_root(&root, &HashSet::new()), 1);
+        assert!(keep.exists());
+        assert!(!root.join(DOWNLOAD_TEMP_SUBDIR).exists());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn sweep_temp_root_keeps_interrupted_jobs() {
+        let root = make_temp_root();
+        let temp = root.join(DOWNLOAD_TEMP_SUBDIR);
+        std::fs::create_dir_all(temp.join("job-1")).unwrap();
+        std::fs::create_dir_all(temp.join("job-2")).unwrap();
+        let keep = HashSet::from(["job-2".to_string()]);
+
+        assert_eq!(sweep_temp_root(&root, &keep), 1);
+        assert!(!temp.join("job-1").exists());
+        assert!(temp.join("job-2").exists());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn remember_temp_root_deduplicates_entries() {
+        let app_data = make_temp_root();
+        remember_temp_root(&app_data, "/mnt/fast");
+        remember_temp_root(&app_data, "/mnt/fast");
+        remember_temp_root(&app_data, "/tmp/other");
+
+        assert_eq!(
+            read_temp_roots(&app_data),
+            vec!["/mnt/fast".to_string(), "/tmp/other".to_string()]
+        );
+
+        std::fs::remove_dir_all(&app_data).ok();
+    }
+}
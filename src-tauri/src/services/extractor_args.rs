@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::ytdlp_args::validation_error;
+use crate::types::BackendError;
+
+/// User `--extractor-args` values keyed by site host, e.g.
+/// `youtube.com` -> `youtube:player_client=web_safari`
+static SITE_EXTRACTOR_ARGS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// `https://www.YouTube.com/watch` / `youtube.com` -> `youtube.com`
+fn normalize_site(site: &str) -> Option<String> {
+    let site = site.trim();
+    let with_scheme = if site.contains("://") {
+        site.to_string()
+    } else {
+        format!("https://{}", site.trim_start_matches('/'))
+    };
+    let parsed = reqwest::Url::parse(&with_scheme).ok()?;
+    let host = parsed.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+fn is_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Check an `extractor:key=value;key2=value2` string and return it trimmed
+pub fn validate_extractor_args(value: &str) -> Result<String, BackendError> {
+    let value = value.trim();
+    let Some((extractor, parts)) = value.split_once(':') else {
+        return Err(validation_error(format!(
+            "Extractor args '{}' must look like extractor:key=value",
+            value
+        )));
+    };
+    if !is_identifier(extractor) {
+        return Err(validation_error(format!(
+            "Invalid extractor name '{}' in extractor args",
+            extractor
+        )));
+    }
+    for part in parts.split(';') {
+        let valid = part.split_once('=').is_some_and(|(key, value)| {
+            is_identifier(key)
+                && !value.is_empty()
+                && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+        });
+        if !valid {
+            return Err(validation_error(format!(
+                "Invalid extractor arg '{}' for {}. Use key=value pairs separated by ';'",
+                part, extractor
+            )));
+        }
+    }
+    Ok(value.to_string())
+}
+
+/// Replace the per-site extractor args. Blank values are dropped.
+pub fn set_site_extractor_args(sites: HashMap<String, String>) -> Result<(), BackendError> {
+    let mut entries = Vec::new();
+    for (site, value) in sites {
+        if value.trim().is_empty() {
+            continue;
+        }
+        let host = normalize_site(&site)
+            .ok_or_else(|| validation_error(format!("Invalid site '{}'", site)))?;
+        entries.push((host, validate_extractor_args(&value)?));
+    }
+    entries.sort();
+    *SITE_EXTRACTOR_ARGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = entries;
+    Ok(())
+}
+
+/// Current per-site extractor args, keyed by host
+pub fn get_site_extractor_args() -> HashMap<String, String> {
+    SITE_EXTRACTOR_ARGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .cloned()
+        .collect()
+}
+
+fn host_matches(url_host: &str, site: &str) -> bool {
+    url_host == site
+        || url_host
+            .strip_suffix(site)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+fn site_extractor_args(url: &str, sites: &[(String, String)]) -> Vec<String> {
+    let Some(url_host) = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_ascii_lowercase))
+    else {
+        return Vec::new();
+    };
+    // Short links are served by the same extractor as the main site
+    let url_host = match url_host.as_str() {
+        "youtu.be" => "youtube.com".to_string(),
+        _ => url_host,
+    };
+    sites
+        .iter()
+        .filter(|(site, _)| host_matches(&url_host, site))
+        .map(|(_, value)| value.clone())
+        .collect()
+}
+
+/// One value per extractor; yt-dlp keeps only the last `--extractor-args`
+/// given for the same extractor, so parts are joined in order instead
+fn merge_extractor_args(values: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut merged: Vec<(String, Vec<String>)> = Vec::new();
+    for value in values {
+        let Some((extractor, parts)) = value.split_once(':') else {
+            continue;
+        };
+        let extractor = extractor.to_ascii_lowercase();
+        let parts = parts.split(';').map(str::to_string);
+        match merged.iter_mut().find(|(name, _)| *name == extractor) {
+            Some((_, existing)) => existing.extend(parts),
+            None => merged.push((extractor, parts.collect())),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(extractor, parts)| format!("{}:{}", extractor, parts.join(";")))
+        .collect()
+}
+
+/// `--extractor-args` flags for `url`: the app's own `builtin` value followed
+/// by the user's settings for the site, so user keys take precedence
+pub fn build_site_extractor_args(url: &str, builtin: Option<String>) -> Vec<String> {
+    let sites = SITE_EXTRACTOR_ARGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    merge_extractor_args(builtin.into_iter().chain(site_extractor_args(url, &sites)))
+        .into_iter()
+        .flat_map(|value| ["--extractor-args".to_string(), value])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_key_value_format() {
+        assert_eq!(
+            validate_extractor_args(" youtube:player_client=web_safari,ios ").unwrap(),
+            "youtube:player_client=web_safari,ios"
+        );
+        assert!(validate_extractor_args(
+            "tiktok:api_hostname=api16-normal-c-useast1a.tiktokv.com;app_version=35.1.3"
+        )
+        .is_ok());
+        for invalid in [
+            "player_client=web",
+            "youtube:",
+            "youtube:player_client",
+            "youtube:player_client=",
+            "you tube:a=b",
+            "youtube:a=b c",
+            "youtube:a=b;;c=d",
+        ] {
+            assert!(validate_extractor_args(invalid).is_err(), "{}", invalid);
+        }
+        assert_eq!(
+            normalize_site("https://www.YouTube.com/watch?v=1").as_deref(),
+            Some("youtube.com")
+        );
+    }
+
+    #[test]
+    fn site_args_merge_with_builtin_value() {
+        let sites = vec![
+            (
+                "youtube.com".to_string(),
+                "youtube:player_client=web_safari".to_string(),
+            ),
+            (
+                "tiktok.com".to_string(),
+                "tiktok:api_hostname=example.com".to_string(),
+            ),
+        ];
+        let url = "https://m.youtube.com/watch?v=1";
+        let user = site_extractor_args(url, &sites);
+        assert_eq!(user, vec!["youtube:player_client=web_safari"]);
+        assert_eq!(site_extractor_args("https://youtu.be/abc", &sites), user);
+        assert!(site_extractor_args("https://notyoutube.com/x", &sites).is_empty());
+
+        let merged = merge_extractor_args(
+            Some("youtube:player_js_version=actual;max_comments=5".to_string())
+                .into_iter()
+                .chain(user),
+        );
+        assert_eq!(
+            merged,
+            vec!["youtube:player_js_version=actual;max_comments=5;player_client=web_safari"]
+        );
+    }
+}
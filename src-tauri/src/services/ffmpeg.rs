@@ -1,9 +1,9 @@
+use super::github::github_api_get;
+use super::{AppProcessRunner, ProcessInvocation, ProcessRunner};
 use crate::types::{DependencySource, FfmpegStatus};
-use crate::utils::{find_system_binary, unix_system_binary_dirs, CommandExt};
-use std::path::PathBuf;
-use std::process::Stdio;
-use tauri::{AppHandle, Manager};
-use tokio::process::Command;
+use crate::utils::{data_dir, find_system_binary, unix_system_binary_dirs};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
 
 const SOURCE_CONFIG_FILE: &str = "ffmpeg-source.txt";
 const RELEASE_VERSION_FILE: &str = "ffmpeg-release-version.txt";
@@ -28,15 +28,13 @@ pub fn system_ffmpeg_upgrade_message() -> String {
 }
 
 fn get_ffmpeg_source_config_path(app: &AppHandle) -> Option<PathBuf> {
-    app.path()
-        .app_data_dir()
+    data_dir(app)
         .ok()
         .map(|p| p.join("bin").join(SOURCE_CONFIG_FILE))
 }
 
 fn get_ffmpeg_release_version_path(app: &AppHandle) -> Option<PathBuf> {
-    app.path()
-        .app_data_dir()
+    data_dir(app)
         .ok()
         .map(|p| p.join("bin").join(RELEASE_VERSION_FILE))
 }
@@ -99,7 +97,7 @@ pub async fn set_ffmpeg_source(app: &AppHandle, source: &DependencySource) -> Re
 }
 
 fn get_app_ffmpeg_path(app: &AppHandle) -> Option<PathBuf> {
-    let app_data_dir = app.path().app_data_dir().ok()?;
+    let app_data_dir = data_dir(app).ok()?;
     let bin_dir = app_data_dir.join("bin");
     #[cfg(windows)]
     let ffmpeg_path = bin_dir.join("ffmpeg.exe");
@@ -131,36 +129,77 @@ pub async fn get_ffmpeg_path(app: &AppHandle) -> Option<PathBuf> {
     }
 }
 
+/// Get the FFprobe binary path (app data, system, or next to FFmpeg)
+pub async fn get_ffprobe_path(app: &AppHandle) -> Option<PathBuf> {
+    if let Ok(app_data_dir) = data_dir(app) {
+        let bin_dir = app_data_dir.join("bin");
+        #[cfg(windows)]
+        let ffprobe_path = bin_dir.join("ffprobe.exe");
+        #[cfg(not(windows))]
+        let ffprobe_path = bin_dir.join("ffprobe");
+
+        if ffprobe_path.exists() {
+            return Some(ffprobe_path);
+        }
+    }
+
+    #[cfg(windows)]
+    let binary_name = "ffprobe.exe";
+    #[cfg(not(windows))]
+    let binary_name = "ffprobe";
+
+    if let Some(path) = find_system_binary(binary_name, &unix_system_binary_dirs()) {
+        return Some(path);
+    }
+
+    if let Some(ffmpeg_path) = get_ffmpeg_path(app).await {
+        if let Some(parent) = ffmpeg_path.parent() {
+            let ffprobe_path = parent.join(binary_name);
+            if ffprobe_path.exists() {
+                return Some(ffprobe_path);
+            }
+        }
+    }
+
+    None
+}
+
+/// Version reported by `ffmpeg -version`, or None if it doesn't run cleanly
+pub async fn probe_ffmpeg_version(
+    runner: &dyn ProcessRunner,
+    ffmpeg_path: &Path,
+) -> Option<String> {
+    let invocation = ProcessInvocation::binary(ffmpeg_path, &["-version"]);
+    let output = runner.run(&invocation).await.ok()?;
+    if !output.success {
+        return None;
+    }
+    Some(parse_ffmpeg_version(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
 /// Check FFmpeg status
 pub async fn check_ffmpeg_internal(app: &AppHandle) -> Result<FfmpegStatus, String> {
     if let Some(ffmpeg_path) = get_ffmpeg_path(app).await {
-        let mut cmd = Command::new(&ffmpeg_path);
-        cmd.args(["-version"])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-        cmd.hide_window();
-
-        if let Ok(output) = cmd.output().await {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let binary_version = parse_ffmpeg_version(&stdout);
-                let app_path = get_app_ffmpeg_path(app);
-                let is_system = app_path.as_ref().map(|p| p != &ffmpeg_path).unwrap_or(true);
-                let version = if is_system {
-                    binary_version
-                } else {
-                    read_app_ffmpeg_release_version(app)
-                        .await
-                        .unwrap_or(binary_version)
-                };
-
-                return Ok(FfmpegStatus {
-                    installed: true,
-                    version: Some(version),
-                    binary_path: Some(ffmpeg_path.to_string_lossy().to_string()),
-                    is_system,
-                });
-            }
+        let runner = AppProcessRunner::new(app);
+        if let Some(binary_version) = probe_ffmpeg_version(&runner, &ffmpeg_path).await {
+            let app_path = get_app_ffmpeg_path(app);
+            let is_system = app_path.as_ref().map(|p| p != &ffmpeg_path).unwrap_or(true);
+            let version = if is_system {
+                binary_version
+            } else {
+                read_app_ffmpeg_release_version(app)
+                    .await
+                    .unwrap_or(binary_version)
+            };
+
+            return Ok(FfmpegStatus {
+                installed: true,
+                version: Some(version),
+                binary_path: Some(ffmpeg_path.to_string_lossy().to_string()),
+                is_system,
+            });
         }
     }
 
@@ -344,29 +383,7 @@ pub async fn get_latest_ffmpeg_release_info() -> Result<FfmpegReleaseInfo, Strin
         return Err("Unsupported platform".to_string());
     }
 
-    let client = reqwest::Client::builder()
-        .user_agent("Youwee/0.6.0")
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get(api_url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to fetch release info: HTTP {}",
-            response.status()
-        ));
-    }
-
-    let json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+    let json = github_api_get(api_url).await?;
 
     let tag_name = json["tag_name"].as_str().ok_or("No tag_name in release")?;
 
@@ -424,7 +441,9 @@ pub async fn check_ffmpeg_update_internal(app: &AppHandle) -> Result<FfmpegUpdat
 
 #[cfg(test)]
 mod tests {
-    use super::{ffmpeg_version_has_update, normalize_ffmpeg_release_version};
+    use super::{
+        ffmpeg_version_has_update, normalize_ffmpeg_release_version, probe_ffmpeg_version,
+    };
 
     #[test]
     fn normalizes_ffmpeg_macos_release_tags() {
@@ -446,4 +465,29 @@ mod tests {
         ));
         assert!(!ffmpeg_version_has_update("2026.06.11", "2026.06.11"));
     }
+
+    #[tokio::test]
+    async fn probes_version_from_recorded_ffmpeg_output() {
+        use crate::services::{FakeProcessRunner, ProcessOutput};
+
+        let runner = FakeProcessRunner::replay([
+            Ok(ProcessOutput::recorded(
+                "ffmpeg version 7.1-static Copyright (c) 2000-2024\nbuilt with gcc 8\n",
+                "",
+                true,
+            )),
+            Ok(ProcessOutput::recorded(
+                "",
+                "dyld: Library not loaded",
+                false,
+            )),
+        ]);
+        let path = std::path::Path::new("/opt/ffmpeg");
+        assert_eq!(
+            probe_ffmpeg_version(&runner, path).await.as_deref(),
+            Some("7.1-static")
+        );
+        assert_eq!(probe_ffmpeg_version(&runner, path).await, None);
+        assert_eq!(runner.calls()[0].args, vec!["-version"]);
+    }
 }
@@ -0,0 +1,361 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::LazyLock;
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{oneshot, Mutex};
+
+use super::{apply_ffmpeg_thread_limit, background_command, track_child_process};
+
+/// Error lines kept for the failure message
+const ERROR_TAIL_LINES: usize = 5;
+
+/// Cancel senders of running FFmpeg jobs by job id, fired by `cancel_ffmpeg`
+pub static ACTIVE_JOBS: LazyLock<Mutex<HashMap<String, oneshot::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// One `-progress pipe:2` report from a running FFmpeg
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FfmpegProgress {
+    pub frame: i64,
+    pub fps: f64,
+    pub time_secs: f64,
+    /// `out_time` without the fraction, e.g. "00:01:05"
+    pub time: String,
+    pub total_size: i64,
+    pub speed: String,
+    /// Input duration from the `Duration:` header or [`FfmpegRunner::duration`]
+    pub duration: Option<f64>,
+    /// Set on the final report (`progress=end`)
+    pub finished: bool,
+}
+
+impl FfmpegProgress {
+    /// Share done (0-100) by time, falling back to frames when only those are known
+    pub fn percent(&self, total_frames: Option<i64>) -> f64 {
+        match (self.duration, total_frames) {
+            (Some(duration), _) if duration > 0.0 && self.time_secs > 0.0 => {
+                (self.time_secs / duration * 100.0).min(100.0)
+            }
+            (_, Some(total)) if total > 0 && self.frame > 0 => {
+                (self.frame as f64 / total as f64 * 100.0).min(100.0)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// Input duration from ffmpeg's `Duration: 00:03:20.12` header line
+pub fn parse_ffmpeg_duration(line: &str) -> Option<f64> {
+    let value = line
+        .trim()
+        .strip_prefix("Duration:")?
+        .split(',')
+        .next()?
+        .trim();
+    let mut parts = value.split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Reads FFmpeg stderr line by line, collecting progress and error lines
+#[derive(Debug, Default)]
+pub struct FfmpegProgressParser {
+    current: FfmpegProgress,
+    error_lines: VecDeque<String>,
+    last_line: Option<String>,
+}
+
+impl FfmpegProgressParser {
+    pub fn new(duration: Option<f64>) -> Self {
+        Self {
+            current: FfmpegProgress {
+                duration,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Feed one stderr line; returns a report when a progress block ends
+    pub fn feed(&mut self, line: &str) -> Option<FfmpegProgress> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        if let Some(duration) = parse_ffmpeg_duration(line) {
+            self.current.duration.get_or_insert(duration);
+            return None;
+        }
+        let first_value = |value: &str| value.split_whitespace().next().map(str::to_string);
+        match line.split_once('=') {
+            Some(("frame", value)) => {
+                if let Some(frame) = first_value(value).and_then(|v| v.parse().ok()) {
+                    self.current.frame = frame;
+                }
+            }
+            Some(("fps", value)) => {
+                if let Some(fps) = first_value(value).and_then(|v| v.parse().ok()) {
+                    self.current.fps = fps;
+                }
+            }
+            Some(("out_time_us", value)) => {
+                if let Ok(us) = value.trim().parse::<i64>() {
+                    self.current.time_secs = us as f64 / 1_000_000.0;
+                }
+            }
+            Some(("out_time", value)) => {
+                let value = value.trim();
+                self.current.time = value.split('.').next().unwrap_or(value).to_string();
+            }
+            Some(("total_size", value)) => {
+                self.current.total_size = value.trim().parse().unwrap_or(0);
+            }
+            Some(("speed", value)) => self.current.speed = value.trim().to_string(),
+            Some(("progress", value)) => {
+                self.current.finished = value.trim() == "end";
+                return Some(self.current.clone());
+            }
+            Some(_) => {}
+            None => {
+                if line.contains("Error") || line.contains("error") || line.contains("Invalid") {
+                    if self.error_lines.len() == ERROR_TAIL_LINES {
+                        self.error_lines.pop_front();
+                    }
+                    self.error_lines.push_back(line.to_string());
+                }
+                self.last_line = Some(line.to_string());
+            }
+        }
+        None
+    }
+
+    /// Lines that look like errors, or else the last non-progress line
+    pub fn error_message(&self) -> Option<String> {
+        if self.error_lines.is_empty() {
+            self.last_line.clone()
+        } else {
+            Some(Vec::from(self.error_lines.clone()).join("\n"))
+        }
+    }
+}
+
+/// Why an FFmpeg run did not produce its output
+#[derive(Clone, Debug, PartialEq)]
+pub enum FfmpegError {
+    Spawn(String),
+    Cancelled,
+    Failed {
+        code: Option<i32>,
+        message: Option<String>,
+    },
+}
+
+impl fmt::Display for FfmpegError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FfmpegError::Spawn(error) => write!(f, "Failed to start FFmpeg: {}", error),
+            FfmpegError::Cancelled => write!(f, "Processing cancelled"),
+            FfmpegError::Failed {
+                message: Some(message),
+                ..
+            } => write!(f, "{}", message),
+            FfmpegError::Failed { code, .. } => write!(f, "FFmpeg exited with code: {:?}", code),
+        }
+    }
+}
+
+impl From<FfmpegError> for String {
+    fn from(error: FfmpegError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Runs FFmpeg at background priority with the thread limit applied and
+/// progress read from `-progress pipe:2`
+#[derive(Clone, Debug)]
+pub struct FfmpegRunner {
+    program: PathBuf,
+    args: Vec<String>,
+    duration: Option<f64>,
+}
+
+impl FfmpegRunner {
+    /// `args` end with the output path, like a normal ffmpeg command line
+    pub fn new(ffmpeg_path: impl Into<PathBuf>, args: Vec<String>) -> Self {
+        Self {
+            program: ffmpeg_path.into(),
+            args,
+            duration: None,
+        }
+    }
+
+    /// Known length of the output, for inputs trimmed with `-ss`/`-t`
+    pub fn duration(mut self, seconds: f64) -> Self {
+        self.duration = (seconds > 0.0).then_some(seconds);
+        self
+    }
+
+    /// Final argv: thread limit and `-progress pipe:2` right before the output
+    pub fn build_args(&self) -> Vec<String> {
+        let mut args = self.args.clone();
+        apply_ffmpeg_thread_limit(&mut args);
+        if !args.iter().any(|arg| arg == "-progress") {
+            let insert_pos = args.len().saturating_sub(1);
+            args.splice(
+                insert_pos..insert_pos,
+                ["-progress".to_string(), "pipe:2".to_string()],
+            );
+        }
+        args
+    }
+
+    /// Run to completion, calling `on_progress` for every report. The process
+    /// is killed when `cancel` fires; removing partial output is up to the caller.
+    pub async fn run(
+        self,
+        cancel: Option<&mut oneshot::Receiver<()>>,
+        on_progress: impl FnMut(&FfmpegProgress),
+    ) -> Result<(), FfmpegError> {
+        self.execute(cancel, on_progress, |_| {}).await
+    }
+
+    /// [`run`](Self::run) that also returns everything FFmpeg wrote to stderr,
+    /// for analysis passes (silencedetect, showinfo, quality metrics) that
+    /// report their results in the log
+    pub async fn run_collecting_log(
+        self,
+        cancel: Option<&mut oneshot::Receiver<()>>,
+        on_progress: impl FnMut(&FfmpegProgress),
+    ) -> Result<String, FfmpegError> {
+        let mut log = String::new();
+        self.execute(cancel, on_progress, |line| {
+            log.push_str(line);
+            log.push('\n');
+        })
+        .await?;
+        Ok(log)
+    }
+
+    async fn execute(
+        self,
+        cancel: Option<&mut oneshot::Receiver<()>>,
+        mut on_progress: impl FnMut(&FfmpegProgress),
+        mut on_line: impl FnMut(&str),
+    ) -> Result<(), FfmpegError> {
+        let mut child = background_command(&self.program)
+            .args(self.build_args())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| FfmpegError::Spawn(e.to_string()))?;
+        let _tracked = track_child_process(child.id());
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| FfmpegError::Spawn("stderr was not captured".to_string()))?;
+        let mut reader = BufReader::new(stderr).lines();
+        let mut parser = FfmpegProgressParser::new(self.duration);
+
+        // A missing or dropped sender never cancels
+        let cancel = async move {
+            match cancel {
+                Some(cancel) => {
+                    if cancel.await.is_err() {
+                        std::future::pending::<()>().await;
+                    }
+                }
+                None => std::future::pending::<()>().await,
+            }
+        };
+        let mut cancel = std::pin::pin!(cancel);
+        loop {
+            tokio::select! {
+                line = reader.next_line() => {
+                    let Ok(Some(line)) = line else {
+                        break;
+                    };
+                    on_line(&line);
+                    if let Some(progress) = parser.feed(&line) {
+                        on_progress(&progress);
+                    }
+                }
+                _ = &mut cancel => {
+                    child.kill().await.ok();
+                    return Err(FfmpegError::Cancelled);
+                }
+            }
+        }
+
+        let status = tokio::select! {
+            status = child.wait() => status,
+            _ = &mut cancel => {
+                child.kill().await.ok();
+                return Err(FfmpegError::Cancelled);
+            }
+        };
+        match status {
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => Err(FfmpegError::Failed {
+                code: status.code(),
+                message: parser.error_message(),
+            }),
+            Err(e) => Err(FfmpegError::Failed {
+                code: None,
+                message: Some(format!("FFmpeg process error: {}", e)),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser_reports_progress_blocks_and_error_lines() {
+        let mut parser = FfmpegProgressParser::new(None);
+        let stderr = [
+            "  Duration: 00:01:40.00, start: 0.000000, bitrate: 1200 kb/s",
+            "frame=250",
+            "fps=48.5",
+            "out_time_us=50000000",
+            "out_time=00:00:50.000000",
+            "total_size=1048576",
+            "speed=2.1x",
+            "progress=continue",
+        ];
+        let reports: Vec<FfmpegProgress> =
+            stderr.iter().filter_map(|line| parser.feed(line)).collect();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.duration, Some(100.0));
+        assert_eq!(report.frame, 250);
+        assert_eq!(report.time, "00:00:50");
+        assert_eq!(report.speed, "2.1x");
+        assert_eq!(report.percent(None), 50.0);
+        assert!(!report.finished);
+
+        assert_eq!(parser.feed("progress=end").map(|p| p.finished), Some(true));
+        parser.feed("[libx264 @ 0x1] broken frame");
+        assert_eq!(
+            parser.error_message().as_deref(),
+            Some("[libx264 @ 0x1] broken frame")
+        );
+        parser.feed("Error while filtering: Invalid argument");
+        assert_eq!(
+            parser.error_message().as_deref(),
+            Some("Error while filtering: Invalid argument")
+        );
+
+        let runner = FfmpegRunner::new("ffmpeg", vec!["-i".into(), "a.mp4".into(), "b.mp4".into()]);
+        let args = runner.build_args();
+        assert_eq!(args.last().map(String::as_str), Some("b.mp4"));
+        assert!(args.windows(2).any(|pair| pair == ["-progress", "pipe:2"]));
+    }
+}
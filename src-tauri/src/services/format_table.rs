@@ -0,0 +1,218 @@
+use serde::Serialize;
+
+/// One row of the `yt-dlp -F` table, as printed
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RawFormatRow {
+    pub format_id: String,
+    pub ext: String,
+    pub resolution: Option<String>,
+    pub fps: Option<String>,
+    pub filesize: Option<String>,
+    pub tbr: Option<String>,
+    pub protocol: Option<String>,
+    pub vcodec: Option<String>,
+    pub acodec: Option<String>,
+    pub note: Option<String>,
+}
+
+fn is_column_divider(c: char) -> bool {
+    c == '│' || c == '|'
+}
+
+fn is_rule_line(line: &str) -> bool {
+    !line.is_empty()
+        && line
+            .chars()
+            .all(|c| matches!(c, '─' | '-' | '┼' | '+' | ' '))
+}
+
+/// Header words with their character span; `MORE INFO` is one column
+fn header_columns(header: &[char]) -> Vec<(String, usize, usize)> {
+    let mut columns: Vec<(String, usize, usize)> = Vec::new();
+    let mut start = None;
+    for i in 0..=header.len() {
+        let is_space = header
+            .get(i)
+            .is_none_or(|c| c.is_whitespace() || is_column_divider(*c));
+        match (start, is_space) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                let word: String = header[s..i].iter().collect();
+                match columns.last_mut() {
+                    Some((name, _, end)) if name == "MORE" && word == "INFO" => {
+                        *name = "MORE INFO".to_string();
+                        *end = i;
+                    }
+                    _ => columns.push((word, s, i)),
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    columns
+}
+
+/// Parse the table `yt-dlp -F` prints. Columns are padded to a shared
+/// width, some left- and some right-aligned, and values may contain spaces
+/// (`audio only`, `~ 80.45MiB`), so a cell is made of the character runs
+/// that aren't blank in every row, each assigned to the header it overlaps.
+pub fn parse_format_table(output: &str) -> Vec<RawFormatRow> {
+    let lines: Vec<&str> = output.lines().map(str::trim_end).collect();
+    let Some(header_index) = lines.iter().position(|line| {
+        let mut words = line.split_whitespace();
+        words.next() == Some("ID") && words.next() == Some("EXT")
+    }) else {
+        return Vec::new();
+    };
+
+    let header: Vec<char> = lines[header_index].chars().collect();
+    let rows: Vec<Vec<char>> = lines[header_index + 1..]
+        .iter()
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| !line.trim().is_empty() && !is_rule_line(line.trim()))
+        .map(|line| line.chars().collect())
+        .collect();
+    let columns = header_columns(&header);
+    if columns.is_empty() {
+        return Vec::new();
+    }
+
+    let width = rows
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0)
+        .max(header.len());
+    let blank_everywhere: Vec<bool> = (0..width)
+        .map(|i| {
+            rows.iter().chain(std::iter::once(&header)).all(|row| {
+                row.get(i)
+                    .is_none_or(|c| c.is_whitespace() || is_column_divider(*c))
+            })
+        })
+        .collect();
+
+    let column_for = |start: usize, end: usize| {
+        let overlap =
+            |(_, s, e): &(String, usize, usize)| end.min(*e).saturating_sub(start.max(*s));
+        let distance = |(_, s, e): &(String, usize, usize)| {
+            if end <= *s {
+                s - end
+            } else {
+                start.saturating_sub(*e)
+            }
+        };
+        columns
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, column)| (overlap(column), std::cmp::Reverse(distance(column))))
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    };
+
+    rows.iter()
+        .filter_map(|row| {
+            let mut cells: Vec<Vec<String>> = vec![Vec::new(); columns.len()];
+            let mut start = None;
+            for i in 0..=width {
+                let blank = blank_everywhere.get(i).copied().unwrap_or(true);
+                match (start, blank) {
+                    (None, false) => start = Some(i),
+                    (Some(s), true) => {
+                        let text: String = row
+                            .get(s..i.min(row.len()))
+                            .unwrap_or_default()
+                            .iter()
+                            .collect();
+                        let text = text.trim();
+                        if !text.is_empty() {
+                            cells[column_for(s, i)].push(text.to_string());
+                        }
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+            let cell = |name: &str| {
+                columns
+                    .iter()
+                    .position(|(column, _, _)| column == name)
+                    .map(|index| cells[index].join(" "))
+                    .filter(|value| !value.is_empty())
+            };
+            let format_id = cell("ID")?;
+            Some(RawFormatRow {
+                format_id,
+                ext: cell("EXT").unwrap_or_default(),
+                resolution: cell("RESOLUTION"),
+                fps: cell("FPS"),
+                filesize: cell("FILESIZE"),
+                tbr: cell("TBR"),
+                protocol: cell("PROTO"),
+                vcodec: cell("VCODEC"),
+                acodec: cell("ACODEC"),
+                note: cell("MORE INFO"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = "\
+[youtube] Extracting URL: https://www.youtube.com/watch?v=dQw4w9WgXcQ
+[info] Available formats for dQw4w9WgXcQ:
+ID  EXT   RESOLUTION FPS CH │   FILESIZE   TBR PROTO │ VCODEC        VBR ACODEC      ABR ASR MORE INFO
+─────────────────────────────────────────────────────────────────────────────────────────────────────────
+sb2 mhtml 48x27        0    │                  mhtml │ images                                storyboard
+139 m4a   audio only      2 │    1.18MiB   49k https │ audio only        mp4a.40.5   49k 22k [en] low, m4a_dash
+137 mp4   1920x1080   25    │ ~ 80.45MiB 4388k https │ avc1.640028 4388k video only          1080p, mp4_dash
+18  mp4   640x360     25  2 │    8.73MiB  476k https │ avc1.42001E       mp4a.40.2   44k 44k [en] 360p
+";
+
+    #[test]
+    fn parses_rows_with_spaces_inside_cells() {
+        let rows = parse_format_table(TABLE);
+        assert_eq!(rows.len(), 4);
+
+        let audio = &rows[1];
+        assert_eq!(audio.format_id, "139");
+        assert_eq!(audio.ext, "m4a");
+        assert_eq!(audio.resolution.as_deref(), Some("audio only"));
+        assert_eq!(audio.fps, None);
+        assert_eq!(audio.filesize.as_deref(), Some("1.18MiB"));
+        assert_eq!(audio.protocol.as_deref(), Some("https"));
+        assert_eq!(audio.vcodec.as_deref(), Some("audio only"));
+        assert_eq!(audio.acodec.as_deref(), Some("mp4a.40.5"));
+        assert_eq!(audio.note.as_deref(), Some("[en] low, m4a_dash"));
+
+        let video = &rows[2];
+        assert_eq!(video.resolution.as_deref(), Some("1920x1080"));
+        assert_eq!(video.fps.as_deref(), Some("25"));
+        assert_eq!(video.filesize.as_deref(), Some("~ 80.45MiB"));
+        assert_eq!(video.tbr.as_deref(), Some("4388k"));
+        assert_eq!(video.vcodec.as_deref(), Some("avc1.640028"));
+        assert_eq!(video.acodec.as_deref(), Some("video only"));
+        assert_eq!(video.note.as_deref(), Some("1080p, mp4_dash"));
+
+        assert_eq!(rows[0].note.as_deref(), Some("storyboard"));
+        assert_eq!(rows[0].filesize, None);
+    }
+
+    #[test]
+    fn ascii_table_and_missing_header() {
+        let ascii = "ID    EXT RESOLUTION | PROTO\n\
+                     ---------------------------\n\
+                     hls-1 mp4 1280x720   | m3u8\n";
+        let rows = parse_format_table(ascii);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].format_id, "hls-1");
+        assert_eq!(rows[0].protocol.as_deref(), Some("m3u8"));
+
+        assert!(parse_format_table("ERROR: Unsupported URL").is_empty());
+    }
+}
@@ -1,11 +1,11 @@
 use std::path::PathBuf;
 use std::process::Stdio;
 
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use tokio::process::Command;
 
 use crate::types::{BackendError, GalleryDlStatus};
-use crate::utils::{find_system_binary, unix_system_binary_dirs, CommandExt};
+use crate::utils::{data_dir, find_system_binary, unix_system_binary_dirs, CommandExt};
 
 pub fn system_gallerydl_not_found_message() -> String {
     #[cfg(target_os = "macos")]
@@ -37,7 +37,7 @@ pub fn get_system_gallerydl_path() -> Option<PathBuf> {
 }
 
 fn get_app_gallerydl_path(app: &AppHandle) -> Option<PathBuf> {
-    let app_data_dir = app.path().app_data_dir().ok()?;
+    let app_data_dir = data_dir(app).ok()?;
     #[cfg(windows)]
     let binary_name = "gallery-dl.exe";
     #[cfg(not(windows))]
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::header::{
+    HeaderMap, AUTHORIZATION, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::StatusCode;
+use tauri::AppHandle;
+
+use super::{get_secret, set_secret};
+use crate::database::{get_github_cache, save_github_cache, touch_github_cache};
+
+pub const GITHUB_TOKEN_SECRET: &str = "github.token";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Cached responses younger than this are served without contacting GitHub
+const FRESH_FOR_SECS: i64 = 5 * 60;
+const RATE_LIMIT_ERROR: &str = "GitHub API rate limit exceeded. Please try again later.";
+
+static GITHUB_TOKEN: Mutex<Option<String>> = Mutex::new(None);
+/// Unix time until which GitHub told us to stop sending requests
+static RATE_LIMITED_UNTIL: AtomicI64 = AtomicI64::new(0);
+/// One lock per URL so concurrent checks wait for a single request
+static URL_LOCKS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+enum Fetched {
+    NotModified,
+    Body {
+        etag: Option<String>,
+        last_modified: Option<String>,
+        body: String,
+    },
+}
+
+/// Load the user's GitHub token from the secret store at startup
+pub fn load_github_token(app: &AppHandle) {
+    match get_secret(app, GITHUB_TOKEN_SECRET) {
+        Ok(token) => store_token(token.as_deref()),
+        Err(e) => log::warn!("Failed to load GitHub token: {}", e),
+    }
+}
+
+/// Save (or with `None` remove) the token used for GitHub API requests
+pub fn set_github_token(app: &AppHandle, token: Option<&str>) -> Result<(), String> {
+    set_secret(app, GITHUB_TOKEN_SECRET, token)?;
+    store_token(token);
+    RATE_LIMITED_UNTIL.store(0, Ordering::Relaxed);
+    Ok(())
+}
+
+fn store_token(token: Option<&str>) {
+    let token = token
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string);
+    *GITHUB_TOKEN.lock().unwrap_or_else(|e| e.into_inner()) = token;
+}
+
+fn github_token() -> Option<String> {
+    GITHUB_TOKEN
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+fn is_fresh(checked_at: i64, now: i64) -> bool {
+    (0..FRESH_FOR_SECS).contains(&(now - checked_at))
+}
+
+/// When GitHub allows the next request, from `Retry-After` or an exhausted
+/// `X-RateLimit-Remaining` with its `X-RateLimit-Reset` timestamp
+fn blocked_until(
+    remaining: Option<&str>,
+    reset: Option<&str>,
+    retry_after: Option<&str>,
+    now: i64,
+) -> Option<i64> {
+    if let Some(seconds) = retry_after.and_then(|value| value.trim().parse::<i64>().ok()) {
+        return Some(now + seconds);
+    }
+    if remaining.map(str::trim) == Some("0") {
+        return reset.and_then(|value| value.trim().parse::<i64>().ok());
+    }
+    None
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn url_lock(url: &str) -> Arc<tokio::sync::Mutex<()>> {
+    URL_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(url.to_string())
+        .or_default()
+        .clone()
+}
+
+fn parse_body(body: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(body).map_err(|e| format!("Failed to parse release info: {}", e))
+}
+
+async fn request(
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<Fetched, String> {
+    let now = Utc::now().timestamp();
+    if RATE_LIMITED_UNTIL.load(Ordering::Relaxed) > now {
+        return Err(RATE_LIMIT_ERROR.to_string());
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent("Youwee/0.6.0")
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut builder = client
+        .get(url)
+        .header("Accept", "application/vnd.github+json");
+    if let Some(token) = github_token() {
+        builder = builder.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+    if let Some(etag) = etag {
+        builder = builder.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        builder = builder.header(IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = builder.send().await.map_err(|e| {
+        if e.is_timeout() {
+            "Request timed out. Please try again later.".to_string()
+        } else if e.is_connect() {
+            "Unable to connect. Please check your internet connection.".to_string()
+        } else {
+            format!("Failed to check for updates: {}", e)
+        }
+    })?;
+
+    let status = response.status();
+    let headers = response.headers();
+    if let Some(until) = blocked_until(
+        header_str(headers, "x-ratelimit-remaining"),
+        header_str(headers, "x-ratelimit-reset"),
+        header_str(headers, RETRY_AFTER.as_str()),
+        now,
+    ) {
+        RATE_LIMITED_UNTIL.store(until, Ordering::Relaxed);
+    }
+
+    let etag = header_str(headers, ETAG.as_str()).map(str::to_string);
+    let last_modified = header_str(headers, LAST_MODIFIED.as_str()).map(str::to_string);
+
+    match status {
+        StatusCode::NOT_MODIFIED => return Ok(Fetched::NotModified),
+        StatusCode::UNAUTHORIZED => {
+            return Err("GitHub rejected the configured token. Update or remove it.".to_string())
+        }
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
+            return Err(RATE_LIMIT_ERROR.to_string())
+        }
+        status if !status.is_success() => return Err(format!("GitHub API error: {}", status)),
+        _ => {}
+    }
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read release info: {}", e))?;
+    Ok(Fetched::Body {
+        etag,
+        last_modified,
+        body,
+    })
+}
+
+/// GET a GitHub API URL as JSON.
+///
+/// Responses are cached in SQLite and revalidated with `If-None-Match`, which
+/// doesn't count against the rate limit. Concurrent calls for the same URL
+/// share one request, and a cached response is served when GitHub is
+/// unreachable or rate limited.
+pub async fn github_api_get(url: &str) -> Result<serde_json::Value, String> {
+    let lock = url_lock(url);
+    let _guard = lock.lock().await;
+
+    // A concurrent check may have refreshed the cache while we waited
+    let cached = get_github_cache(url).ok().flatten();
+    if let Some(cached) = &cached {
+        if is_fresh(cached.checked_at, Utc::now().timestamp()) {
+            return parse_body(&cached.body);
+        }
+    }
+
+    let result = request(
+        url,
+        cached.as_ref().and_then(|c| c.etag.as_deref()),
+        cached.as_ref().and_then(|c| c.last_modified.as_deref()),
+    )
+    .await;
+    match (result, cached) {
+        (Ok(Fetched::NotModified), Some(cached)) => {
+            touch_github_cache(url).ok();
+            parse_body(&cached.body)
+        }
+        (Ok(Fetched::NotModified), None) => Err("GitHub API error: 304 Not Modified".to_string()),
+        (
+            Ok(Fetched::Body {
+                etag,
+                last_modified,
+                body,
+            }),
+            _,
+        ) => {
+            let json = parse_body(&body)?;
+            if let Err(e) = save_github_cache(url, etag.as_deref(), last_modified.as_deref(), &body)
+            {
+                log::warn!("{}", e);
+            }
+            Ok(json)
+        }
+        (Err(e), Some(cached)) => {
+            log::warn!("GitHub request failed, using cached response: {}", e);
+            parse_body(&cached.body)
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_headers_pause_requests() {
+        let now = 1_700_000_000;
+        assert_eq!(
+            blocked_until(Some("0"), Some("1700000900"), None, now),
+            Some(1_700_000_900)
+        );
+        assert_eq!(
+            blocked_until(Some("42"), Some("1700000900"), None, now),
+            None
+        );
+        assert_eq!(
+            blocked_until(Some("12"), None, Some("60"), now),
+            Some(now + 60)
+        );
+        assert_eq!(blocked_until(None, None, None, now), None);
+
+        assert!(is_fresh(now - 10, now));
+        assert!(!is_fresh(now - FRESH_FOR_SECS, now));
+        assert!(!is_fresh(now + 10, now));
+    }
+}
@@ -0,0 +1,317 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::types::{ExternalHistoryKind, ImportedHistoryRecord};
+use crate::utils::is_media_output_path;
+
+// Sidecar folders are usually flat or one folder per channel/playlist
+const INFO_JSON_MAX_DEPTH: usize = 4;
+
+/// Parse a yt-dlp `--download-archive` file. Each line is `<extractor> <id>`.
+pub fn parse_ytdlp_archive(content: &str) -> Vec<ImportedHistoryRecord> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (extractor, id) = line.split_once(char::is_whitespace)?;
+            let (extractor, id) = (extractor.to_lowercase(), id.trim());
+            if id.is_empty() {
+                return None;
+            }
+            Some(ImportedHistoryRecord {
+                media_id: Some(format!("{}:{}", extractor, id)),
+                source: Some(extractor),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+fn first_str(item: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| item.get(*key).and_then(Value::as_str))
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(ToString::to_string)
+}
+
+fn first_u64(item: &Value, keys: &[&str]) -> Option<u64> {
+    keys.iter()
+        .filter_map(|key| item.get(*key))
+        .find_map(|value| {
+            value.as_u64().or_else(|| {
+                value
+                    .as_f64()
+                    .filter(|v| *v >= 0.0)
+                    .map(|v| v.round() as u64)
+            })
+        })
+}
+
+/// Unix seconds from a number (seconds or milliseconds) or an RFC 3339 string
+fn first_timestamp(item: &Value, keys: &[&str]) -> Option<i64> {
+    keys.iter()
+        .filter_map(|key| item.get(*key))
+        .find_map(|value| {
+            if let Some(number) = value.as_i64() {
+                // Anything past year 2286 in seconds is really milliseconds
+                return Some(if number > 9_999_999_999 {
+                    number / 1000
+                } else {
+                    number
+                });
+            }
+            value
+                .as_str()
+                .and_then(|text| chrono::DateTime::parse_from_rfc3339(text).ok())
+                .map(|date| date.timestamp())
+        })
+}
+
+/// Parse the history JSON exported by Stacher or Open Video Downloader. Both
+/// have changed their layout between releases, so common field names are tried.
+pub fn parse_downloader_history_json(content: &str) -> Result<Vec<ImportedHistoryRecord>, String> {
+    let root: Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid history JSON: {}", e))?;
+    let items = match &root {
+        Value::Array(items) => items,
+        Value::Object(_) => ["history", "downloads", "items", "entries", "data"]
+            .iter()
+            .find_map(|key| root.get(*key).and_then(Value::as_array))
+            .ok_or_else(|| "History JSON has no list of downloads".to_string())?,
+        _ => return Err("History JSON has no list of downloads".to_string()),
+    };
+
+    Ok(items
+        .iter()
+        .filter(|item| item.is_object())
+        .map(|item| ImportedHistoryRecord {
+            url: first_str(
+                item,
+                &[
+                    "webpage_url",
+                    "url",
+                    "originalUrl",
+                    "original_url",
+                    "videoUrl",
+                    "link",
+                ],
+            ),
+            title: first_str(item, &["title", "name", "fileName"]),
+            thumbnail: first_str(item, &["thumbnail", "thumbnailUrl", "thumb"]),
+            filepath: first_str(
+                item,
+                &[
+                    "filepath",
+                    "filePath",
+                    "path",
+                    "outputPath",
+                    "output",
+                    "location",
+                ],
+            ),
+            filesize: first_u64(item, &["filesize", "fileSize", "size"]),
+            duration: first_u64(item, &["duration"]),
+            format: first_str(item, &["ext", "extension", "format"]),
+            source: first_str(item, &["extractor", "site", "source"]).map(|s| s.to_lowercase()),
+            media_id: None,
+            downloaded_at: first_timestamp(
+                item,
+                &[
+                    "downloadedAt",
+                    "finishedAt",
+                    "createdAt",
+                    "created_at",
+                    "timestamp",
+                    "date",
+                ],
+            ),
+        })
+        .collect())
+}
+
+/// Media file next to `name.info.json` with the same base name
+fn sidecar_media_file(info_path: &Path) -> Option<PathBuf> {
+    let file_name = info_path.file_name()?.to_str()?;
+    let stem = file_name.strip_suffix(".info.json")?;
+    let dir = info_path.parent()?;
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                return false;
+            };
+            let path_str = path.to_string_lossy();
+            name.strip_prefix(stem)
+                .is_some_and(|rest| rest.starts_with('.') && !rest[1..].contains('.'))
+                && is_media_output_path(&path_str)
+        })
+}
+
+/// Parse one `.info.json` sidecar, locating the media file it describes
+pub fn parse_info_json(info_path: &Path) -> Result<ImportedHistoryRecord, String> {
+    let content = std::fs::read_to_string(info_path)
+        .map_err(|e| format!("Failed to read {}: {}", info_path.display(), e))?;
+    let info: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid info JSON {}: {}", info_path.display(), e))?;
+
+    let extractor = first_str(&info, &["extractor_key", "extractor"]).map(|s| s.to_lowercase());
+    let media_id = extractor
+        .as_deref()
+        .zip(first_str(&info, &["id"]))
+        .map(|(extractor, id)| format!("{}:{}", extractor, id));
+    let filepath = first_str(&info, &["filepath", "_filename", "filename"])
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+        .or_else(|| sidecar_media_file(info_path));
+    let metadata = filepath
+        .as_deref()
+        .and_then(|path| std::fs::metadata(path).ok());
+    let downloaded_at = metadata
+        .as_ref()
+        .and_then(|meta| meta.modified().ok())
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .or_else(|| first_timestamp(&info, &["epoch"]));
+
+    Ok(ImportedHistoryRecord {
+        url: first_str(&info, &["webpage_url", "original_url"]),
+        title: first_str(&info, &["title", "fulltitle"]),
+        thumbnail: first_str(&info, &["thumbnail"]),
+        filesize: metadata
+            .as_ref()
+            .map(|meta| meta.len())
+            .or_else(|| first_u64(&info, &["filesize", "filesize_approx"])),
+        duration: first_u64(&info, &["duration"]),
+        format: filepath
+            .as_deref()
+            .and_then(|path| path.extension())
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .or_else(|| first_str(&info, &["ext"])),
+        filepath: filepath.map(|path| path.to_string_lossy().to_string()),
+        source: extractor,
+        media_id,
+        downloaded_at,
+    })
+}
+
+fn collect_info_json_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            if depth < INFO_JSON_MAX_DEPTH {
+                collect_info_json_files(&path, depth + 1, files);
+            }
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(".info.json"))
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// Parse every `.info.json` under `dir`. Returns the records and the number of
+/// sidecars that could not be read.
+pub fn scan_info_json_folder(dir: &Path) -> Result<(Vec<ImportedHistoryRecord>, usize), String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a folder", dir.display()));
+    }
+    let mut files = Vec::new();
+    collect_info_json_files(dir, 0, &mut files);
+    files.sort();
+
+    let mut records = Vec::new();
+    let mut failed = 0;
+    for file in files {
+        match parse_info_json(&file) {
+            Ok(record) => records.push(record),
+            Err(e) => {
+                log::warn!("Skipping sidecar during history import: {}", e);
+                failed += 1;
+            }
+        }
+    }
+    Ok((records, failed))
+}
+
+/// Read another downloader's history. Returns the parsed records and the
+/// number of entries that could not be read.
+pub fn load_external_history(
+    kind: ExternalHistoryKind,
+    path: &Path,
+) -> Result<(Vec<ImportedHistoryRecord>, usize), String> {
+    if kind == ExternalHistoryKind::InfoJson {
+        return scan_info_json_folder(path);
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    match kind {
+        ExternalHistoryKind::YtdlpArchive => Ok((parse_ytdlp_archive(&content), 0)),
+        _ => parse_downloader_history_json(&content).map(|records| (records, 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ytdlp_archive_lines_become_media_ids() {
+        let records =
+            parse_ytdlp_archive("youtube dQw4w9WgXcQ\n\n# comment\nVimeo 12345\nbroken\n");
+        let ids: Vec<_> = records
+            .iter()
+            .map(|record| record.media_id.as_deref().unwrap_or_default())
+            .collect();
+        assert_eq!(ids, ["youtube:dQw4w9WgXcQ", "vimeo:12345"]);
+    }
+
+    #[test]
+    fn downloader_history_json_accepts_wrapped_lists_and_ms_timestamps() {
+        let records = parse_downloader_history_json(
+            r#"{"downloads":[{"url":"https://youtu.be/abc","name":"Clip","filePath":"/tmp/clip.mp4","createdAt":1700000000000},{"title":"no url"}]}"#,
+        )
+        .expect("parse history");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].url.as_deref(), Some("https://youtu.be/abc"));
+        assert_eq!(records[0].title.as_deref(), Some("Clip"));
+        assert_eq!(records[0].filepath.as_deref(), Some("/tmp/clip.mp4"));
+        assert_eq!(records[0].downloaded_at, Some(1_700_000_000));
+        assert!(records[1].url.is_none());
+    }
+
+    #[test]
+    fn info_json_sidecar_finds_media_file_and_skips_thumbnail() {
+        let dir = std::env::temp_dir().join(format!("youwee-import-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        std::fs::write(
+            dir.join("Song [abc].info.json"),
+            r#"{"id":"abc","extractor_key":"Youtube","title":"Song","webpage_url":"https://www.youtube.com/watch?v=abc","duration":61.4}"#,
+        )
+        .expect("write info json");
+        std::fs::write(dir.join("Song [abc].webp"), b"thumb").expect("write thumbnail");
+        std::fs::write(dir.join("Song [abc].m4a"), b"audio").expect("write media");
+
+        let (records, failed) = scan_info_json_folder(&dir).expect("scan folder");
+        assert_eq!(failed, 0);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].media_id.as_deref(), Some("youtube:abc"));
+        assert_eq!(records[0].duration, Some(61));
+        assert_eq!(records[0].format.as_deref(), Some("m4a"));
+        assert!(records[0]
+            .filepath
+            .as_deref()
+            .is_some_and(|path| path.ends_with("Song [abc].m4a")));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
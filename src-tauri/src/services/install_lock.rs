@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use super::termination::process_exists;
+use crate::types::{code, BackendError};
+use crate::utils::data_dir;
+
+/// A lockfile without a readable owner pid and older than this was left by a
+/// crashed install
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(30 * 60);
+
+static INSTALLS_IN_PROGRESS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Held while a dependency binary is being downloaded into `bin/`. Releases
+/// the in-process slot and removes the lockfile when dropped.
+pub struct InstallLock {
+    dependency: String,
+    lockfile: Option<PathBuf>,
+}
+
+impl Drop for InstallLock {
+    fn drop(&mut self) {
+        if let Some(lockfile) = &self.lockfile {
+            std::fs::remove_file(lockfile).ok();
+        }
+        if let Ok(mut guard) = INSTALLS_IN_PROGRESS.lock() {
+            if let Some(installs) = guard.as_mut() {
+                installs.remove(&self.dependency);
+            }
+        }
+    }
+}
+
+fn already_in_progress(dependency: &str) -> String {
+    BackendError::new(
+        code::INSTALL_IN_PROGRESS,
+        format!("{} install already in progress", dependency),
+    )
+    .with_param("dependency", dependency)
+    .with_retryable(false)
+    .to_wire_string()
+}
+
+/// A lock is stale once the process that wrote it is gone, however long a
+/// slow install has been running
+fn is_stale(lockfile: &Path) -> bool {
+    let owner = std::fs::read_to_string(lockfile)
+        .ok()
+        .and_then(|contents| contents.trim().parse::<u32>().ok());
+    if let Some(pid) = owner {
+        return !process_exists(pid);
+    }
+    std::fs::metadata(lockfile)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age >= LOCK_STALE_AFTER)
+}
+
+/// Create `.{dependency}.install.lock` in `dir`, failing if another
+/// instance holds a fresh one
+fn create_lockfile(dir: &Path, dependency: &str) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create bin directory: {}", e))?;
+    let lockfile = dir.join(format!(".{}.install.lock", dependency));
+    for _ in 0..2 {
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lockfile)
+        {
+            Ok(mut file) => {
+                writeln!(file, "{}", std::process::id()).ok();
+                return Ok(lockfile);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                if !is_stale(&lockfile) {
+                    return Err(already_in_progress(dependency));
+                }
+                log::warn!("Removing stale install lock {}", lockfile.display());
+                std::fs::remove_file(&lockfile).ok();
+            }
+            Err(e) => return Err(format!("Failed to create install lock: {}", e)),
+        }
+    }
+    Err(already_in_progress(dependency))
+}
+
+fn acquire_in(dir: Option<&Path>, dependency: &str) -> Result<InstallLock, String> {
+    {
+        let mut guard = INSTALLS_IN_PROGRESS
+            .lock()
+            .map_err(|_| "Install lock poisoned".to_string())?;
+        if !guard
+            .get_or_insert_with(HashSet::new)
+            .insert(dependency.to_string())
+        {
+            return Err(already_in_progress(dependency));
+        }
+    }
+    // From here the lock owns the slot, so an early return releases it
+    let mut lock = InstallLock {
+        dependency: dependency.to_string(),
+        lockfile: None,
+    };
+    if let Some(dir) = dir {
+        lock.lockfile = Some(create_lockfile(dir, dependency)?);
+    }
+    Ok(lock)
+}
+
+/// Reserve `dependency` for one install across windows and app instances.
+/// A second caller gets an `INSTALL_IN_PROGRESS` error and can follow the
+/// running install through its progress events.
+pub fn acquire_install_lock(app: &AppHandle, dependency: &str) -> Result<InstallLock, String> {
+    let bin_dir = data_dir(app).ok().map(|dir| dir.join("bin"));
+    acquire_in(bin_dir.as_deref(), dependency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_install_is_rejected_until_the_first_finishes() {
+        let dir =
+            std::env::temp_dir().join(format!("youwee-install-lock-{}", uuid::Uuid::new_v4()));
+        let first = acquire_in(Some(&dir), "test-dep").expect("first install");
+        let lockfile = dir.join(".test-dep.install.lock");
+        assert!(lockfile.exists());
+
+        let err = acquire_in(Some(&dir), "test-dep")
+            .err()
+            .expect("second install");
+        assert!(err.contains(code::INSTALL_IN_PROGRESS));
+        // Another app instance only sees the lockfile
+        assert!(create_lockfile(&dir, "test-dep").is_err());
+
+        drop(first);
+        assert!(!lockfile.exists());
+        drop(acquire_in(Some(&dir), "test-dep").expect("install after release"));
+
+        // A lock whose owner has exited is taken over
+        let mut exited = std::process::Command::new(std::env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .expect("spawn short-lived process");
+        let dead_pid = exited.id();
+        exited.wait().expect("wait for process");
+        std::fs::write(&lockfile, format!("{}\n", dead_pid)).unwrap();
+        assert!(is_stale(&lockfile));
+        std::fs::write(&lockfile, format!("{}\n", std::process::id())).unwrap();
+        assert!(!is_stale(&lockfile));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
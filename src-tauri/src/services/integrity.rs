@@ -0,0 +1,402 @@
+use std::io::Read;
+use std::path::Path;
+
+use tauri::AppHandle;
+
+use crate::database::{
+    add_log_internal, get_history_entries_by_ids_from_db, get_history_sha256,
+    update_history_integrity,
+};
+use crate::services::{
+    get_ffmpeg_path, get_ffprobe_path, AppProcessRunner, FfmpegRunner, ProcessInvocation,
+    ProcessRunner,
+};
+use crate::types::{DownloadIntegrityReport, DOWNLOAD_INTEGRITY};
+
+/// Smallest accepted gap between expected and probed duration, in seconds
+const MIN_DURATION_TOLERANCE_SECS: f64 = 2.0;
+/// Relative tolerance so long videos are not flagged for container rounding
+const DURATION_TOLERANCE_RATIO: f64 = 0.02;
+/// Seconds decoded from the end of the file to catch truncated downloads
+const TAIL_DECODE_SECONDS: &str = "5";
+
+#[derive(Debug, Default, PartialEq)]
+struct ProbeSummary {
+    format_duration: Option<f64>,
+    video_duration: Option<f64>,
+    audio_duration: Option<f64>,
+    stream_count: usize,
+}
+
+/// Compute the SHA256 of a file without loading it into memory
+pub fn compute_file_sha256(path: &Path) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to open file for hashing: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read file for hashing: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Re-check the file behind a history entry and persist the outcome
+pub async fn verify_history_download(
+    app: &AppHandle,
+    history_id: &str,
+) -> Result<DownloadIntegrityReport, String> {
+    let entry = get_history_entries_by_ids_from_db(vec![history_id.to_string()])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("History entry not found: {}", history_id))?;
+    let previous_sha256 = get_history_sha256(history_id)?;
+    // Partial downloads are expected to be shorter than the source video
+    let expected_duration = entry.duration.filter(|_| entry.time_range.is_none());
+
+    let mut report = DownloadIntegrityReport {
+        history_id: history_id.to_string(),
+        filepath: entry.filepath.clone(),
+        previous_sha256: previous_sha256.clone(),
+        expected_duration,
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+
+    let mut duration_mismatch = false;
+    let path = Path::new(&entry.filepath);
+    if entry.filepath.is_empty() || !path.is_file() {
+        report.status = "missing".to_string();
+        report.issues.push("File no longer exists".to_string());
+        update_history_integrity(history_id, None, &report.status)?;
+        return Ok(report);
+    }
+
+    let hash_path = path.to_path_buf();
+    let sha256 = tokio::task::spawn_blocking(move || compute_file_sha256(&hash_path))
+        .await
+        .map_err(|e| format!("Hashing task failed: {}", e))??;
+
+    match get_ffprobe_path(app).await {
+        Some(ffprobe_path) => {
+            let probe = probe_media(&AppProcessRunner::new(app), &ffprobe_path, path).await;
+            match probe {
+                Ok(summary) => {
+                    report.decodable = summary.stream_count > 0;
+                    report.duration_seconds = summary.format_duration;
+                    let issues = duration_issues(&summary, expected_duration);
+                    duration_mismatch = !issues.is_empty();
+                    report.issues.extend(issues);
+                    if !report.decodable {
+                        report.issues.push("No media streams found".to_string());
+                    }
+                }
+                Err(e) => report.issues.push(e),
+            }
+        }
+        None => {
+            // Without ffprobe only the checksum can be compared
+            report.decodable = true;
+            report
+                .issues
+                .push("FFprobe not found, container was not validated".to_string());
+        }
+    }
+
+    if report.decodable {
+        if let Some(ffmpeg_path) = get_ffmpeg_path(app).await {
+            if let Err(e) = decode_tail(&ffmpeg_path, path).await {
+                report.decodable = false;
+                report.issues.push(e);
+            }
+        }
+    }
+
+    let corrupt = !report.decodable || duration_mismatch;
+    let changed = previous_sha256
+        .as_deref()
+        .is_some_and(|previous| !previous.eq_ignore_ascii_case(&sha256));
+
+    report.status = if corrupt {
+        "corrupt"
+    } else if changed {
+        "changed"
+    } else {
+        "ok"
+    }
+    .to_string();
+    if changed {
+        report
+            .issues
+            .push("Checksum differs from the one recorded after download".to_string());
+    }
+
+    // Keep the first good checksum as the reference for future bit-rot checks
+    let sha_to_store = if previous_sha256.is_none() && report.status == "ok" {
+        Some(sha256.as_str())
+    } else {
+        None
+    };
+    update_history_integrity(history_id, sha_to_store, &report.status)?;
+    report.sha256 = Some(sha256);
+
+    Ok(report)
+}
+
+/// Run the integrity check in the background after a download finishes
+pub fn spawn_download_integrity_check(app: AppHandle, history_id: String) {
+    tauri::async_runtime::spawn(async move {
+        match verify_history_download(&app, &history_id).await {
+            Ok(report) => {
+                if report.status != "ok" {
+                    add_log_internal(
+                        "error",
+                        &format!("Integrity check failed ({})", report.status),
+                        Some(&report.issues.join("; ")),
+                        None,
+                    )
+                    .ok();
+                }
+                DOWNLOAD_INTEGRITY.emit(&app, &report).ok();
+            }
+            Err(e) => log::warn!("Integrity check for {} failed: {}", history_id, e),
+        }
+    });
+}
+
+async fn probe_media(
+    runner: &dyn ProcessRunner,
+    ffprobe_path: &Path,
+    path: &Path,
+) -> Result<ProbeSummary, String> {
+    let path = path.to_string_lossy();
+    let invocation = ProcessInvocation::binary(
+        ffprobe_path,
+        &[
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration:stream=codec_type,duration",
+            "-of",
+            "json",
+            &path,
+        ],
+    );
+    let output = runner.run(&invocation).await?;
+    if !output.success {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "FFprobe could not read the container: {}",
+            stderr.lines().next().unwrap_or("unknown error").trim()
+        ));
+    }
+
+    parse_probe_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+async fn decode_tail(ffmpeg_path: &Path, path: &Path) -> Result<(), String> {
+    let args = vec![
+        "-v".to_string(),
+        "error".to_string(),
+        "-sseof".to_string(),
+        format!("-{}", TAIL_DECODE_SECONDS),
+        "-i".to_string(),
+        path.to_string_lossy().to_string(),
+        "-f".to_string(),
+        "null".to_string(),
+        "-".to_string(),
+    ];
+    let log = FfmpegRunner::new(ffmpeg_path, args)
+        .run_collecting_log(None, |_| {})
+        .await
+        .map_err(|e| format!("Decoding the end of the file failed: {}", e))?;
+    match first_decode_error(&log) {
+        Some(error) => Err(format!("Decoding the end of the file failed: {}", error)),
+        None => Ok(()),
+    }
+}
+
+/// First line `-v error` printed, skipping the runner's `key=value` progress reports
+fn first_decode_error(log: &str) -> Option<&str> {
+    log.lines().map(str::trim).find(|line| {
+        !line.is_empty()
+            && !line
+                .split_once('=')
+                .is_some_and(|(key, _)| key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+    })
+}
+
+fn parse_probe_output(stdout: &str) -> Result<ProbeSummary, String> {
+    let json: serde_json::Value = serde_json::from_str(stdout)
+        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
+    let parse_duration = |value: Option<&serde_json::Value>| {
+        value
+            .and_then(|d| d.as_str())
+            .and_then(|d| d.parse::<f64>().ok())
+    };
+
+    let mut summary = ProbeSummary {
+        format_duration: parse_duration(json.get("format").and_then(|f| f.get("duration"))),
+        ..Default::default()
+    };
+
+    if let Some(streams) = json.get("streams").and_then(|s| s.as_array()) {
+        summary.stream_count = streams.len();
+        for stream in streams {
+            let duration = parse_duration(stream.get("duration"));
+            match stream.get("codec_type").and_then(|c| c.as_str()) {
+                Some("video") if summary.video_duration.is_none() => {
+                    summary.video_duration = duration
+                }
+                Some("audio") if summary.audio_duration.is_none() => {
+                    summary.audio_duration = duration
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+fn duration_tolerance(seconds: f64) -> f64 {
+    (seconds * DURATION_TOLERANCE_RATIO).max(MIN_DURATION_TOLERANCE_SECS)
+}
+
+fn duration_issues(summary: &ProbeSummary, expected_duration: Option<u64>) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    if let (Some(expected), Some(actual)) = (expected_duration, summary.format_duration) {
+        let expected = expected as f64;
+        if (expected - actual).abs() > duration_tolerance(expected) {
+            issues.push(format!(
+                "File duration {:.0}s does not match expected {:.0}s",
+                actual, expected
+            ));
+        }
+    }
+
+    if let (Some(video), Some(audio)) = (summary.video_duration, summary.audio_duration) {
+        if (video - audio).abs() > duration_tolerance(video.max(audio)) {
+            issues.push(format!(
+                "Audio and video streams differ in length ({:.0}s vs {:.0}s)",
+                audio, video
+            ));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{FakeProcessRunner, ProcessOutput};
+
+    #[tokio::test]
+    async fn probe_media_reports_unreadable_containers() {
+        let runner = FakeProcessRunner::replay([
+            Ok(ProcessOutput::recorded(
+                r#"{"format":{"duration":"12.5"},"streams":[{"codec_type":"audio"}]}"#,
+                "",
+                true,
+            )),
+            Ok(ProcessOutput::recorded(
+                "",
+                "clip.mp4: Invalid data found when processing input\n",
+                false,
+            )),
+        ]);
+        let ffprobe = Path::new("/bin/ffprobe");
+        let summary = probe_media(&runner, ffprobe, Path::new("/videos/clip.mp4"))
+            .await
+            .unwrap();
+        assert_eq!(summary.format_duration, Some(12.5));
+        assert_eq!(summary.stream_count, 1);
+        assert_eq!(
+            probe_media(&runner, ffprobe, Path::new("/videos/clip.mp4")).await,
+            Err("FFprobe could not read the container: \
+                 clip.mp4: Invalid data found when processing input"
+                .to_string())
+        );
+        assert_eq!(runner.calls()[0].args.last().unwrap(), "/videos/clip.mp4");
+    }
+
+    #[test]
+    fn decode_errors_skip_progress_reports() {
+        let log = "frame=120\nout_time_us=5000000\nprogress=end\n";
+        assert_eq!(first_decode_error(log), None);
+        let log = "frame=12\n[h264 @ 0x1] error while decoding MB 3 7, bytestream -5\n";
+        assert_eq!(
+            first_decode_error(log),
+            Some("[h264 @ 0x1] error while decoding MB 3 7, bytestream -5")
+        );
+    }
+
+    #[test]
+    fn parse_probe_output_reads_format_and_stream_durations() {
+        let summary = parse_probe_output(
+            r#"{
+                "streams": [
+                    {"codec_type": "video", "duration": "120.040000"},
+                    {"codec_type": "audio", "duration": "119.980000"}
+                ],
+                "format": {"duration": "120.040000"}
+            }"#,
+        )
+        .expect("parse");
+
+        assert_eq!(summary.stream_count, 2);
+        assert_eq!(summary.format_duration, Some(120.04));
+        assert_eq!(summary.video_duration, Some(120.04));
+        assert_eq!(summary.audio_duration, Some(119.98));
+    }
+
+    #[test]
+    fn duration_issues_flags_truncated_file_and_incomplete_merge() {
+        let summary = ProbeSummary {
+            format_duration: Some(300.0),
+            video_duration: Some(300.0),
+            audio_duration: Some(95.0),
+            stream_count: 2,
+        };
+        let issues = duration_issues(&summary, Some(600));
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].contains("duration"));
+        assert!(issues[1].contains("streams differ"));
+    }
+
+    #[test]
+    fn duration_issues_tolerates_container_rounding() {
+        let summary = ProbeSummary {
+            format_duration: Some(3601.5),
+            video_duration: Some(3601.5),
+            audio_duration: Some(3600.2),
+            stream_count: 2,
+        };
+
+        assert!(duration_issues(&summary, Some(3600)).is_empty());
+    }
+
+    #[test]
+    fn compute_file_sha256_matches_known_digest() {
+        let path =
+            std::env::temp_dir().join(format!("youwee-sha-test-{}.bin", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"hello").unwrap();
+
+        assert_eq!(
+            compute_file_sha256(&path).unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+
+        std::fs::remove_file(&path).ok();
+    }
+}
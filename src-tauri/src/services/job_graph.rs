@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobNodeStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    /// Not run because a dependency failed, was skipped or the graph was cancelled
+    Skipped,
+}
+
+impl JobNodeStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobNodeStatus::Completed | JobNodeStatus::Failed | JobNodeStatus::Skipped
+        )
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobNodeStatus::Pending => "pending",
+            JobNodeStatus::Running => "running",
+            JobNodeStatus::Completed => "completed",
+            JobNodeStatus::Failed => "failed",
+            JobNodeStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// A job in a processing graph and the jobs it waits for
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobNode {
+    pub id: String,
+    pub depends_on: Vec<String>,
+    pub status: JobNodeStatus,
+}
+
+/// Reject duplicate ids, unknown dependencies and cycles
+pub fn validate_job_graph(nodes: &[JobNode]) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    for node in nodes {
+        if !ids.insert(node.id.as_str()) {
+            return Err(format!("Duplicate job id: {}", node.id));
+        }
+    }
+    for node in nodes {
+        if let Some(missing) = node
+            .depends_on
+            .iter()
+            .find(|dep| !ids.contains(dep.as_str()))
+        {
+            return Err(format!(
+                "Job {} depends on unknown job {}",
+                node.id, missing
+            ));
+        }
+    }
+
+    // Kahn's algorithm: anything left unvisited sits on a cycle
+    let mut remaining: HashMap<&str, usize> = nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node.depends_on.len()))
+        .collect();
+    let mut ready: Vec<&str> = remaining
+        .iter()
+        .filter(|(_, deps)| **deps == 0)
+        .map(|(id, _)| *id)
+        .collect();
+    let mut visited = 0;
+    while let Some(id) = ready.pop() {
+        visited += 1;
+        for node in nodes
+            .iter()
+            .filter(|node| node.depends_on.iter().any(|d| d == id))
+        {
+            let count = remaining.get_mut(node.id.as_str()).expect("known id");
+            *count -= node.depends_on.iter().filter(|d| *d == id).count();
+            if *count == 0 {
+                ready.push(node.id.as_str());
+            }
+        }
+    }
+    if visited != nodes.len() {
+        return Err("Job dependencies form a cycle".to_string());
+    }
+    Ok(())
+}
+
+fn status_of(nodes: &[JobNode], id: &str) -> Option<JobNodeStatus> {
+    nodes
+        .iter()
+        .find(|node| node.id == id)
+        .map(|node| node.status)
+}
+
+/// Pending jobs whose dependencies all completed
+pub fn ready_jobs(nodes: &[JobNode]) -> Vec<String> {
+    nodes
+        .iter()
+        .filter(|node| node.status == JobNodeStatus::Pending)
+        .filter(|node| {
+            node.depends_on
+                .iter()
+                .all(|dep| status_of(nodes, dep) == Some(JobNodeStatus::Completed))
+        })
+        .map(|node| node.id.clone())
+        .collect()
+}
+
+/// Mark pending jobs downstream of a failed or skipped job as skipped;
+/// returns the ids that changed
+pub fn skip_blocked_jobs(nodes: &mut [JobNode]) -> Vec<String> {
+    let mut skipped = Vec::new();
+    loop {
+        let blocked: Vec<usize> = (0..nodes.len())
+            .filter(|&i| nodes[i].status == JobNodeStatus::Pending)
+            .filter(|&i| {
+                nodes[i].depends_on.iter().any(|dep| {
+                    matches!(
+                        status_of(nodes, dep),
+                        Some(JobNodeStatus::Failed | JobNodeStatus::Skipped)
+                    )
+                })
+            })
+            .collect();
+        if blocked.is_empty() {
+            return skipped;
+        }
+        for i in blocked {
+            nodes[i].status = JobNodeStatus::Skipped;
+            skipped.push(nodes[i].id.clone());
+        }
+    }
+}
+
+/// Jobs connected through dependencies, as index lists in submission order
+pub fn job_chains(nodes: &[JobNode]) -> Vec<Vec<usize>> {
+    let index: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.id.as_str(), i))
+        .collect();
+    let mut parent: Vec<usize> = (0..nodes.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for (i, node) in nodes.iter().enumerate() {
+        for dep in &node.depends_on {
+            if let Some(&j) = index.get(dep.as_str()) {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+    }
+    let mut chains: Vec<(usize, Vec<usize>)> = Vec::new();
+    for i in 0..nodes.len() {
+        let r = root(&mut parent, i);
+        match chains.iter_mut().find(|(chain_root, _)| *chain_root == r) {
+            Some((_, members)) => members.push(i),
+            None => chains.push((r, vec![i])),
+        }
+    }
+    chains.into_iter().map(|(_, members)| members).collect()
+}
+
+/// Share of finished jobs (0-100) and overall status of a chain
+pub fn chain_progress(nodes: &[JobNode], chain: &[usize]) -> (f64, JobNodeStatus) {
+    let statuses: Vec<JobNodeStatus> = chain.iter().map(|&i| nodes[i].status).collect();
+    let finished = statuses
+        .iter()
+        .filter(|status| status.is_finished())
+        .count();
+    let percent = if statuses.is_empty() {
+        100.0
+    } else {
+        finished as f64 / statuses.len() as f64 * 100.0
+    };
+    let status = if statuses.contains(&JobNodeStatus::Failed) {
+        JobNodeStatus::Failed
+    } else if statuses.contains(&JobNodeStatus::Running) {
+        JobNodeStatus::Running
+    } else if finished < statuses.len() {
+        JobNodeStatus::Pending
+    } else if statuses.iter().all(|s| *s == JobNodeStatus::Skipped) {
+        JobNodeStatus::Skipped
+    } else {
+        JobNodeStatus::Completed
+    };
+    (percent, status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, deps: &[&str]) -> JobNode {
+        JobNode {
+            id: id.to_string(),
+            depends_on: deps.iter().map(|d| d.to_string()).collect(),
+            status: JobNodeStatus::Pending,
+        }
+    }
+
+    #[test]
+    fn failed_dependency_skips_downstream_jobs_in_its_chain() {
+        let mut nodes = vec![
+            node("transcribe", &[]),
+            node("burn", &["transcribe"]),
+            node("upload", &["burn"]),
+            node("thumbnail", &[]),
+        ];
+        validate_job_graph(&nodes).unwrap();
+        assert_eq!(ready_jobs(&nodes), ["transcribe", "thumbnail"]);
+        assert_eq!(job_chains(&nodes), vec![vec![0, 1, 2], vec![3]]);
+
+        nodes[0].status = JobNodeStatus::Failed;
+        nodes[3].status = JobNodeStatus::Completed;
+        assert_eq!(skip_blocked_jobs(&mut nodes), ["burn", "upload"]);
+        assert!(ready_jobs(&nodes).is_empty());
+        assert_eq!(
+            chain_progress(&nodes, &[0, 1, 2]),
+            (100.0, JobNodeStatus::Failed)
+        );
+
+        let cycle = vec![node("a", &["b"]), node("b", &["a"])];
+        assert!(validate_job_graph(&cycle).is_err());
+        assert!(validate_job_graph(&[node("a", &["missing"])]).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use futures_util::{stream, StreamExt};
+use regex::Regex;
+use reqwest::Url;
+use serde::Serialize;
+
+use crate::utils::{detect_source, normalize_url, validate_url};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_CANDIDATES: usize = 500;
+/// Pages larger than this are cut off before scanning
+const MAX_PAGE_BYTES: usize = 4 * 1024 * 1024;
+/// Short links resolved at the same time
+const RESOLVE_CONCURRENCY: usize = 8;
+
+/// Hosts that only redirect to the real page
+const SHORT_LINK_HOSTS: &[&str] = &[
+    "t.co",
+    "bit.ly",
+    "tinyurl.com",
+    "goo.gl",
+    "ow.ly",
+    "buff.ly",
+    "is.gd",
+    "lnkd.in",
+    "vm.tiktok.com",
+    "vt.tiktok.com",
+    "fb.watch",
+    "b23.tv",
+];
+
+const DIRECT_MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "m4v", "webm", "mkv", "mov", "m3u8", "mpd", "mp3", "m4a", "flac", "wav", "ogg", "opus",
+];
+
+static URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"https?://[^\s"'<>`\\]+"#).expect("valid URL regex"));
+static HREF_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(?:href|src|data-src)\s*=\s*["']([^"']+)["']"#).expect("valid href regex")
+});
+
+/// A link found by [`extract_link_candidates`]
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkCandidate {
+    pub url: String,
+    pub source: String,
+    /// Short link the URL was resolved from
+    pub resolved_from: Option<String>,
+}
+
+/// Absolute http(s) URL without HTML escapes, trailing punctuation or fragment
+fn clean_link(raw: &str, base: Option<&Url>) -> Option<Url> {
+    let raw = raw.trim().replace("&amp;", "&");
+    let mut raw = raw.as_str();
+    loop {
+        let trimmed = raw.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '"']);
+        // Keep the `)` of `Foo_(film)` but not the one closing `(see https://...)`
+        let trimmed = match trimmed.strip_suffix(')') {
+            Some(inner) if !inner.contains('(') => inner,
+            _ => trimmed,
+        };
+        if trimmed == raw {
+            break;
+        }
+        raw = trimmed;
+    }
+    let mut url = match base {
+        Some(base) => base.join(raw).ok()?,
+        None => Url::parse(raw).ok()?,
+    };
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    url.set_fragment(None);
+    Some(url)
+}
+
+/// Every URL in `text`, in order and without duplicates. With a `base`,
+/// `href`/`src` attributes are resolved against it too.
+pub fn scan_links(text: &str, base: Option<&Url>) -> Vec<String> {
+    let absolute = URL_RE
+        .find_iter(text)
+        .filter_map(|found| clean_link(found.as_str(), None));
+    let attributes = base.into_iter().flat_map(|base| {
+        HREF_RE
+            .captures_iter(text)
+            .filter_map(move |captures| clean_link(&captures[1], Some(base)))
+    });
+
+    let mut seen = HashSet::new();
+    absolute
+        .chain(attributes)
+        .map(String::from)
+        .filter(|url| seen.insert(url.clone()))
+        .collect()
+}
+
+fn is_short_link(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_ascii_lowercase))
+        .is_some_and(|host| SHORT_LINK_HOSTS.contains(&host.trim_start_matches("www.")))
+}
+
+fn is_direct_media(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .and_then(|parsed| {
+            let path = parsed.path().to_ascii_lowercase();
+            path.rsplit_once('.').map(|(_, ext)| ext.to_string())
+        })
+        .is_some_and(|ext| DIRECT_MEDIA_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Links scraped from a page are only kept for known video sites and media
+/// files; pasted text is taken as the user's own selection
+fn is_video_link(url: &str, from_page: bool) -> bool {
+    !from_page || detect_source(url).is_some_and(|source| source != "other") || is_direct_media(url)
+}
+
+/// Follow a short link's redirects to the page it points at
+async fn resolve_short_link(client: &reqwest::Client, url: &str) -> Option<String> {
+    let response = match client.head(url).send().await {
+        Ok(response) if response.status().is_success() || response.status().is_redirection() => {
+            response
+        }
+        // Some shorteners reject HEAD
+        _ => client.get(url).send().await.ok()?,
+    };
+    let resolved = response.url().to_string();
+    (resolved != url).then_some(resolved)
+}
+
+async fn fetch_page(client: &reqwest::Client, url: &str) -> Result<(Url, String), String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch page: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch page: HTTP {}", response.status()));
+    }
+    let final_url = response.url().clone();
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Failed to read page: {}", e))?;
+        let remaining = MAX_PAGE_BYTES - body.len();
+        body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+        if body.len() >= MAX_PAGE_BYTES {
+            break;
+        }
+    }
+    Ok((final_url, String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// Video links in pasted text, or on the web page when the input is one URL.
+/// Short links are resolved and the result is deduplicated.
+pub async fn extract_link_candidates(text_or_url: &str) -> Result<Vec<LinkCandidate>, String> {
+    let input = text_or_url.trim();
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (compatible; Youwee/0.6.0)")
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let single_url = (!input.contains(char::is_whitespace) && validate_url(input).is_ok())
+        .then_some(input)
+        .filter(|url| !is_video_link(url, true) && !is_short_link(url));
+    let (links, from_page) = match single_url {
+        Some(page_url) => {
+            let (base, html) = fetch_page(&client, page_url).await?;
+            let links = scan_links(&html, Some(&base))
+                .into_iter()
+                .filter(|link| *link != base.as_str())
+                .collect();
+            (links, true)
+        }
+        None => (scan_links(input, None), false),
+    };
+
+    // Ordered so candidates keep the order they appear in
+    let resolved: Vec<_> = stream::iter(links.into_iter().take(MAX_CANDIDATES))
+        .map(|link| {
+            let client = &client;
+            async move {
+                if is_short_link(&link) {
+                    if let Some(target) = resolve_short_link(client, &link).await {
+                        return (target, Some(link));
+                    }
+                }
+                (link, None)
+            }
+        })
+        .buffered(RESOLVE_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut seen = HashSet::new();
+    Ok(resolved
+        .into_iter()
+        .map(|(url, resolved_from)| (normalize_url(&url), resolved_from))
+        .filter(|(url, _)| is_video_link(url, from_page) && seen.insert(url.clone()))
+        .map(|(url, resolved_from)| LinkCandidate {
+            source: detect_source(&url).unwrap_or_else(|| "other".to_string()),
+            url,
+            resolved_from,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_pasted_text_without_duplicates() {
+        let text = "Watch https://www.youtube.com/watch?v=abc&amp;t=5. Also \
+            (see https://vimeo.com/123), https://www.youtube.com/watch?v=abc&t=5#comments \
+            and https://en.wikipedia.org/wiki/Foo_(film)!";
+        assert_eq!(
+            scan_links(text, None),
+            vec![
+                "https://www.youtube.com/watch?v=abc&t=5",
+                "https://vimeo.com/123",
+                "https://en.wikipedia.org/wiki/Foo_(film)",
+            ]
+        );
+    }
+
+    #[test]
+    fn page_links_resolve_relative_paths_and_keep_videos() {
+        let base = Url::parse("https://blog.example.com/posts/1").unwrap();
+        let html = r#"<link href="/style.css"><a href="https://youtu.be/xyz">clip</a>
+            <video src="media/intro.mp4"></video><a href='https://t.co/AbC'>short</a>
+            <a href="https://blog.example.com/about">about</a>"#;
+        let links = scan_links(html, Some(&base));
+        assert_eq!(
+            links,
+            vec![
+                "https://youtu.be/xyz",
+                "https://t.co/AbC",
+                "https://blog.example.com/about",
+                "https://blog.example.com/style.css",
+                "https://blog.example.com/posts/media/intro.mp4",
+            ]
+        );
+
+        let kept: Vec<_> = links
+            .iter()
+            .filter(|link| is_video_link(link, true))
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                "https://youtu.be/xyz",
+                "https://blog.example.com/posts/media/intro.mp4"
+            ]
+        );
+        assert!(is_short_link("https://t.co/AbC"));
+        assert!(!is_short_link("https://youtu.be/xyz"));
+    }
+}
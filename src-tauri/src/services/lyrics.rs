@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+
+/// One timed line of lyrics
+#[derive(Clone, Debug, PartialEq)]
+pub struct LyricLine {
+    pub start: f64,
+    pub text: String,
+}
+
+/// `01:02:03,500`, `02:03.500` -> seconds
+fn parse_cue_timestamp(value: &str) -> Option<f64> {
+    let value = value.trim().replace(',', ".");
+    value
+        .split(':')
+        .try_fold(0.0, |total, part| {
+            part.trim().parse::<f64>().ok().map(|n| total * 60.0 + n)
+        })
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+}
+
+/// Drop inline markup: `<c>`, `<00:00:01.000>`, `<i>` and `{\an8}` style tags
+fn strip_cue_markup(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut closing = None;
+    for c in line.chars() {
+        match (closing, c) {
+            (None, '<') => closing = Some('>'),
+            (None, '{') => closing = Some('}'),
+            (Some(end), c) if c == end => closing = None,
+            (None, c) => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// YouTube's rolling captions hold the previous text for a moment in cues this short
+const HOLD_CUE_MAX_SECONDS: f64 = 0.05;
+
+/// Timed lines of an SRT or WebVTT file. Only caption artifacts are dropped:
+/// the same line twice at one timestamp, the line a rolling cue carries over
+/// from the previous one, and hold cues that just repeat it. A chorus sung
+/// again later is kept.
+pub fn parse_subtitle_cues(content: &str) -> Vec<LyricLine> {
+    let mut cues: Vec<LyricLine> = Vec::new();
+    let mut previous: Vec<String> = Vec::new();
+    let mut lines = content
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}'));
+
+    while let Some(line) = lines.next() {
+        let Some((start, end)) = line.split_once("-->") else {
+            continue;
+        };
+        let Some(start) = parse_cue_timestamp(start) else {
+            continue;
+        };
+        let end = end.split_whitespace().next().and_then(parse_cue_timestamp);
+        let mut cue_lines: Vec<String> = lines
+            .by_ref()
+            .take_while(|line| !line.trim().is_empty())
+            .map(strip_cue_markup)
+            .filter(|line| !line.is_empty())
+            .collect();
+        if cue_lines.is_empty() {
+            continue;
+        }
+        let is_hold = end.is_some_and(|end| end - start <= HOLD_CUE_MAX_SECONDS);
+        if is_hold && cue_lines.iter().all(|line| previous.contains(line)) {
+            continue;
+        }
+
+        let current = cue_lines.clone();
+        let rolled_over = cue_lines.len() > 1 && previous.last() == cue_lines.first();
+        if rolled_over {
+            cue_lines.remove(0);
+        }
+        cue_lines.dedup();
+        previous = current;
+
+        let text = cue_lines.join(" ");
+        let repeated_at_same_time = cues
+            .iter()
+            .rev()
+            .take_while(|cue| cue.start == start)
+            .any(|cue| cue.text == text);
+        if !repeated_at_same_time {
+            cues.push(LyricLine { start, text });
+        }
+    }
+    cues
+}
+
+fn format_lrc_timestamp(seconds: f64) -> String {
+    let centis = (seconds.max(0.0) * 100.0).round() as u64;
+    format!(
+        "[{:02}:{:02}.{:02}]",
+        centis / 6000,
+        centis / 100 % 60,
+        centis % 100
+    )
+}
+
+/// Render lyrics as an `.lrc` file
+pub fn build_lrc(lines: &[LyricLine], title: Option<&str>) -> String {
+    let mut lrc = String::new();
+    if let Some(title) = title.map(str::trim).filter(|title| !title.is_empty()) {
+        lrc.push_str(&format!("[ti:{}]\n", title));
+    }
+    lrc.push_str("[re:Youwee]\n");
+    for line in lines {
+        lrc.push_str(&format_lrc_timestamp(line.start));
+        lrc.push_str(&line.text);
+        lrc.push('\n');
+    }
+    lrc
+}
+
+/// Subtitle files saved next to `media` (`song.en.vtt`, `song.srt`, ...)
+pub fn sibling_subtitle_files(media: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (media.parent(), media.file_stem()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let is_subtitle = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    ext.eq_ignore_ascii_case("srt") || ext.eq_ignore_ascii_case("vtt")
+                });
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            is_subtitle && name.starts_with(&format!("{}.", stem))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vtt_cues_become_lrc_lines_without_rolling_duplicates() {
+        let vtt = "WEBVTT\nKind: captions\n\n\
+            00:00:01.000 --> 00:00:03.000 align:start position:0%\n\
+            <c>Never</c><00:00:01.500><c> gonna</c>\n\n\
+            00:00:03.000 --> 00:00:05.000\n\
+            Never gonna\n\
+            give you up\n\n\
+            01:02:03.456 --> 01:02:04.000\n\
+            {\\an8}Outro\n";
+        let cues = parse_subtitle_cues(vtt);
+        assert_eq!(
+            cues,
+            vec![
+                LyricLine {
+                    start: 1.0,
+                    text: "Never gonna".to_string()
+                },
+                LyricLine {
+                    start: 3.0,
+                    text: "give you up".to_string()
+                },
+                LyricLine {
+                    start: 3723.456,
+                    text: "Outro".to_string()
+                },
+            ]
+        );
+
+        let lrc = build_lrc(&cues, Some("Song"));
+        assert_eq!(
+            lrc,
+            "[ti:Song]\n[re:Youwee]\n[00:01.00]Never gonna\n[00:03.00]give you up\n[62:03.46]Outro\n"
+        );
+    }
+
+    #[test]
+    fn repeated_chorus_lines_are_kept() {
+        let srt = "1\n00:00:01,000 --> 00:00:02,000\nNa na na\n\n\
+                   2\n00:00:02,000 --> 00:00:03,000\nNa na na\n\n\
+                   3\n00:00:02,000 --> 00:00:03,000\nNa na na\n";
+        let cues = parse_subtitle_cues(srt);
+        assert_eq!(
+            cues.iter().map(|cue| cue.start).collect::<Vec<_>>(),
+            [1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn srt_timestamps_use_comma_millis() {
+        let srt =
+            "1\n00:00:12,340 --> 00:00:14,000\nHello\n\n2\n00:00:15,000 --> 00:00:16,000\nWorld\n";
+        let cues = parse_subtitle_cues(srt);
+        assert_eq!(cues.len(), 2);
+        assert!((cues[0].start - 12.34).abs() < 1e-9);
+        assert_eq!(format_lrc_timestamp(cues[0].start), "[00:12.34]");
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::{Path, PathBuf};
+
+use super::{is_format_stream_name, FfmpegRunner};
+use crate::utils::unique_output_path;
+
+/// A merge yt-dlp gave up on, with the streams it left behind
+#[derive(Debug, PartialEq, Eq)]
+pub struct FailedMerge {
+    pub target: PathBuf,
+    pub streams: Vec<PathBuf>,
+}
+
+fn mentions_merge_failure(line: &str) -> bool {
+    let line = line.to_ascii_lowercase();
+    line.contains("could not write header")
+        || (line.starts_with("error:")
+            && (line.contains("postprocessing") || line.contains("merg")))
+}
+
+/// Detect a failed stream-copy merge from yt-dlp's last output lines and the
+/// destinations it announced. Only counts when the merged file is missing
+/// and the separate streams are still on disk.
+pub fn detect_failed_merge(
+    recent_lines: &[String],
+    destinations: &[PathBuf],
+) -> Option<FailedMerge> {
+    if !recent_lines.iter().any(|line| mentions_merge_failure(line)) {
+        return None;
+    }
+    let is_stream = |path: &PathBuf| {
+        path.file_name()
+            .is_some_and(|name| is_format_stream_name(&name.to_string_lossy()))
+    };
+    let target = destinations.iter().rev().find(|path| !is_stream(path))?;
+    if target.exists() {
+        return None;
+    }
+    let mut streams: Vec<PathBuf> = Vec::new();
+    for path in destinations
+        .iter()
+        .filter(|path| is_stream(path) && path.exists())
+    {
+        if !streams.contains(path) {
+            streams.push(path.clone());
+        }
+    }
+    (streams.len() >= 2).then(|| FailedMerge {
+        target: target.clone(),
+        streams,
+    })
+}
+
+/// ffmpeg arguments for each retry: MP4 with only the audio re-encoded to
+/// AAC (when an MP4-family container was asked for), then a plain MKV copy.
+/// Outputs get a free name so an existing file is never replaced.
+fn merge_attempts(merge: &FailedMerge, output_dir: &Path) -> Vec<(PathBuf, Vec<String>)> {
+    let stem = merge
+        .target
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "video".to_string());
+    let extension = merge
+        .target
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_else(|| "mp4".to_string());
+    let inputs: Vec<String> = merge
+        .streams
+        .iter()
+        .flat_map(|stream| ["-i".to_string(), stream.to_string_lossy().to_string()])
+        .collect();
+    let maps: Vec<String> = (0..merge.streams.len())
+        .flat_map(|index| ["-map".to_string(), index.to_string()])
+        .collect();
+    let command = |codec_args: &[&str], output: &Path| {
+        let mut args = vec!["-n".to_string(), "-hide_banner".to_string()];
+        args.extend(inputs.iter().cloned());
+        args.extend(maps.iter().cloned());
+        args.extend(codec_args.iter().map(|arg| arg.to_string()));
+        args.push(output.to_string_lossy().to_string());
+        args
+    };
+
+    let mut attempts = Vec::new();
+    if matches!(extension.as_str(), "mp4" | "m4v" | "mov") {
+        let target = unique_output_path(output_dir, &stem, &extension);
+        let args = command(
+            &[
+                "-c:v",
+                "copy",
+                "-c:a",
+                "aac",
+                "-b:a",
+                "192k",
+                "-movflags",
+                "+faststart",
+            ],
+            &target,
+        );
+        attempts.push((target, args));
+    }
+    let mkv = unique_output_path(output_dir, &stem, "mkv");
+    let args = command(&["-c", "copy"], &mkv);
+    attempts.push((mkv, args));
+    attempts
+}
+
+/// Merge the streams of a failed merge into `output_dir` ourselves and
+/// remove them once a retry succeeds. Returns the saved file.
+pub async fn recover_failed_merge(
+    ffmpeg: &Path,
+    merge: &FailedMerge,
+    output_dir: &Path,
+) -> Result<PathBuf, String> {
+    let mut last_error = String::from("No merge attempt was made");
+    for (output, args) in merge_attempts(merge, output_dir) {
+        match FfmpegRunner::new(ffmpeg, args).run(None, |_| {}).await {
+            Ok(()) if output.exists() => {
+                for stream in &merge.streams {
+                    std::fs::remove_file(stream).ok();
+                }
+                return Ok(output);
+            }
+            Ok(()) => last_error = "FFmpeg did not write the merged file".to_string(),
+            Err(error) => last_error = error.to_string(),
+        }
+        std::fs::remove_file(&output).ok();
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_failed_merge_only_when_streams_remain() {
+        let dir = std::env::temp_dir().join(format!("youwee-merge-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("Clip.f303.webm");
+        let audio = dir.join("Clip.f251.webm");
+        std::fs::write(&video, b"").unwrap();
+        std::fs::write(&audio, b"").unwrap();
+        let destinations = vec![video.clone(), audio.clone(), dir.join("Clip.mp4")];
+        let failed = vec![
+            "[Merger] Merging formats into \"Clip.mp4\"".to_string(),
+            "ERROR: Postprocessing: Conversion failed!".to_string(),
+        ];
+
+        let merge = detect_failed_merge(&failed, &destinations).unwrap();
+        assert_eq!(merge.target, dir.join("Clip.mp4"));
+        assert_eq!(merge.streams, vec![video.clone(), audio.clone()]);
+        assert!(
+            detect_failed_merge(&["ERROR: HTTP Error 403".to_string()], &destinations).is_none()
+        );
+
+        std::fs::remove_file(&audio).unwrap();
+        assert!(detect_failed_merge(&failed, &destinations).is_none());
+        std::fs::remove_dir_all(&dir).ok();
+
+        let out = Path::new("/out");
+        let attempts = merge_attempts(&merge, out);
+        assert_eq!(attempts[0].0, out.join("Clip.mp4"));
+        assert!(attempts[0].1.windows(2).any(|pair| pair == ["-c:a", "aac"]));
+        assert_eq!(attempts[1].0, out.join("Clip.mkv"));
+        assert!(attempts[1].1.windows(2).any(|pair| pair == ["-c", "copy"]));
+        assert_eq!(attempts[1].1[0], "-n");
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Clip.mkv"), b"keep").unwrap();
+        let attempts = merge_attempts(&merge, &dir);
+        assert_eq!(attempts[1].0, dir.join("Clip (1).mkv"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
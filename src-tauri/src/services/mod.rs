@@ -1,21 +1,105 @@
 mod ai;
+mod app_health;
+mod audio_companion;
+mod cache;
+mod clipboard;
+mod command_export;
+mod compatibility;
 mod deno;
+mod direct_download;
+mod download_guard;
+mod download_journal;
+mod download_temp;
+mod extractor_args;
 mod ffmpeg;
+mod ffmpeg_runner;
+mod format_table;
 mod gallerydl;
+mod github;
+mod history_import;
+mod install_lock;
+mod integrity;
+mod job_graph;
+mod link_extract;
+mod lyrics;
+mod merge_recovery;
+mod network_profile;
+mod notifications;
+mod playlist_export;
 mod plugin;
+mod podcast;
 pub mod polling;
+mod post_queue;
+mod power;
+mod privacy;
+mod process_priority;
+mod process_runner;
+mod quit_guard;
+mod secrets;
+mod setup;
+mod site_limits;
+mod source_icons;
+mod subtitle_langs;
 pub mod telegram;
+mod temp_janitor;
+mod termination;
+mod transcript;
 mod whisper;
+mod whisper_local;
+mod whisper_models;
 mod youtube_search;
 mod ytdlp;
 mod ytdlp_args;
+mod ytdlp_update;
 
 pub use ai::*;
+pub use app_health::*;
+pub use audio_companion::*;
+pub use cache::*;
+pub use clipboard::*;
+pub use command_export::*;
+pub use compatibility::*;
 pub use deno::*;
+pub use direct_download::*;
+pub use download_guard::*;
+pub use download_journal::*;
+pub use download_temp::*;
+pub use extractor_args::*;
 pub use ffmpeg::*;
+pub use ffmpeg_runner::*;
+pub use format_table::*;
 pub use gallerydl::*;
+pub use github::*;
+pub use history_import::*;
+pub use install_lock::*;
+pub use integrity::*;
+pub use job_graph::*;
+pub use link_extract::*;
+pub use lyrics::*;
+pub use merge_recovery::*;
+pub use network_profile::*;
+pub use notifications::*;
+pub use playlist_export::*;
 pub use plugin::*;
+pub use podcast::*;
+pub use post_queue::*;
+pub use power::*;
+pub use privacy::*;
+pub use process_priority::*;
+pub use process_runner::*;
+pub use quit_guard::*;
+pub use secrets::*;
+pub use setup::*;
+pub use site_limits::*;
+pub use source_icons::*;
+pub use subtitle_langs::*;
+pub use temp_janitor::*;
+pub use termination::*;
+pub use transcript::*;
 pub use whisper::*;
+pub use whisper_local::*;
+pub use whisper_models::*;
 pub use youtube_search::*;
 pub use ytdlp::*;
 pub use ytdlp_args::*;
+pub use ytdlp_update::*;
@@ -0,0 +1,391 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use super::parse_ytdlp_error;
+use crate::types::{code, BackendError};
+
+/// How aggressively downloads use the connection, synced from the frontend
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkProfile {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+    Custom,
+}
+
+/// yt-dlp transfer settings a network profile maps to
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkTuning {
+    pub concurrent_fragments: u32,
+    /// e.g. "10M"; splits plain HTTP downloads into ranged requests
+    pub http_chunk_size: Option<String>,
+    pub retries: u32,
+    pub fragment_retries: u32,
+    pub socket_timeout: Option<u32>,
+}
+
+impl NetworkProfile {
+    pub fn tuning(self) -> NetworkTuning {
+        match self {
+            Self::Slow => NetworkTuning {
+                concurrent_fragments: 1,
+                http_chunk_size: Some("1M".to_string()),
+                retries: 10,
+                fragment_retries: 10,
+                socket_timeout: Some(60),
+            },
+            Self::Normal | Self::Custom => NetworkTuning {
+                concurrent_fragments: 1,
+                http_chunk_size: None,
+                retries: 3,
+                fragment_retries: 3,
+                socket_timeout: None,
+            },
+            Self::Fast => NetworkTuning {
+                concurrent_fragments: 8,
+                http_chunk_size: Some("10M".to_string()),
+                retries: 5,
+                fragment_retries: 5,
+                socket_timeout: Some(20),
+            },
+        }
+    }
+}
+
+static NETWORK_TUNING: Mutex<Option<NetworkTuning>> = Mutex::new(None);
+
+fn is_valid_chunk_size(size: &str) -> bool {
+    let digits = size.trim_end_matches(['K', 'M', 'G', 'k', 'm', 'g']);
+    !digits.is_empty()
+        && digits.len() + 1 >= size.len()
+        && digits.parse::<u64>().is_ok_and(|n| n > 0)
+}
+
+/// Select the profile used by downloads started after the call. `custom` is
+/// only read for [`NetworkProfile::Custom`] and clamped to sane ranges.
+pub fn set_network_profile_config(
+    profile: NetworkProfile,
+    custom: Option<NetworkTuning>,
+) -> Result<(), String> {
+    let tuning = match (profile, custom) {
+        (NetworkProfile::Custom, Some(custom)) => {
+            let http_chunk_size = custom
+                .http_chunk_size
+                .map(|size| size.trim().to_string())
+                .filter(|size| !size.is_empty());
+            if let Some(size) = http_chunk_size.as_deref() {
+                if !is_valid_chunk_size(size) {
+                    return Err(format!("Invalid HTTP chunk size '{}'", size));
+                }
+            }
+            NetworkTuning {
+                concurrent_fragments: custom.concurrent_fragments.clamp(1, 32),
+                http_chunk_size,
+                retries: custom.retries.min(50),
+                fragment_retries: custom.fragment_retries.min(50),
+                socket_timeout: custom.socket_timeout.map(|secs| secs.clamp(1, 300)),
+            }
+        }
+        (NetworkProfile::Custom, None) => {
+            return Err("Invalid network profile: custom settings are missing".to_string())
+        }
+        (profile, _) => profile.tuning(),
+    };
+    if let Ok(mut guard) = NETWORK_TUNING.lock() {
+        *guard = Some(tuning);
+    }
+    Ok(())
+}
+
+pub fn get_network_tuning() -> NetworkTuning {
+    NETWORK_TUNING
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .unwrap_or_else(|| NetworkProfile::Normal.tuning())
+}
+
+/// yt-dlp flags for `tuning`
+pub fn network_tuning_args(tuning: &NetworkTuning) -> Vec<String> {
+    let mut args = vec![
+        "--retries".to_string(),
+        tuning.retries.to_string(),
+        "--fragment-retries".to_string(),
+        tuning.fragment_retries.to_string(),
+    ];
+    if tuning.concurrent_fragments > 1 {
+        args.push("--concurrent-fragments".to_string());
+        args.push(tuning.concurrent_fragments.to_string());
+    }
+    if let Some(size) = tuning.http_chunk_size.as_ref() {
+        args.push("--http-chunk-size".to_string());
+        args.push(size.clone());
+    }
+    if let Some(secs) = tuning.socket_timeout {
+        args.push("--socket-timeout".to_string());
+        args.push(secs.to_string());
+    }
+    args
+}
+
+/// Timeouts and retries for metadata fetches (video info, playlists,
+/// search, comments), synced from the frontend
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataNetworkSettings {
+    pub socket_timeout: u32,
+    pub retries: u32,
+    pub extractor_retries: u32,
+}
+
+impl Default for MetadataNetworkSettings {
+    fn default() -> Self {
+        Self {
+            socket_timeout: 30,
+            retries: 3,
+            extractor_retries: 3,
+        }
+    }
+}
+
+static METADATA_NETWORK: Mutex<Option<MetadataNetworkSettings>> = Mutex::new(None);
+
+/// Apply to metadata commands started after the call, clamped to sane ranges
+pub fn set_metadata_network_settings(settings: MetadataNetworkSettings) {
+    let settings = MetadataNetworkSettings {
+        socket_timeout: settings.socket_timeout.clamp(1, 300),
+        retries: settings.retries.min(20),
+        extractor_retries: settings.extractor_retries.min(20),
+    };
+    if let Ok(mut guard) = METADATA_NETWORK.lock() {
+        *guard = Some(settings);
+    }
+}
+
+pub fn get_metadata_network_settings() -> MetadataNetworkSettings {
+    METADATA_NETWORK
+        .lock()
+        .ok()
+        .and_then(|guard| *guard)
+        .unwrap_or_default()
+}
+
+/// yt-dlp flags every metadata command adds
+pub fn metadata_network_args() -> Vec<String> {
+    let settings = get_metadata_network_settings();
+    vec![
+        "--socket-timeout".to_string(),
+        settings.socket_timeout.to_string(),
+        "--retries".to_string(),
+        settings.retries.to_string(),
+        "--extractor-retries".to_string(),
+        settings.extractor_retries.to_string(),
+    ]
+}
+
+/// Overall deadline for a metadata command: `base`, or longer when custom
+/// timeout and retry settings could not finish within it. The defaults keep
+/// each command's own deadline.
+pub fn metadata_deadline(base: Duration) -> Duration {
+    let settings = get_metadata_network_settings();
+    if settings == MetadataNetworkSettings::default() {
+        return base;
+    }
+    let attempts = u64::from(settings.retries.max(settings.extractor_retries)) + 1;
+    base.max(Duration::from_secs(
+        u64::from(settings.socket_timeout) * attempts,
+    ))
+}
+
+/// `METADATA_TIMEOUT` error carrying the limits in effect, so the UI can
+/// suggest raising them or checking the proxy
+pub fn metadata_timeout_error(message: impl Into<String>) -> BackendError {
+    let settings = get_metadata_network_settings();
+    BackendError::new(code::METADATA_TIMEOUT, message)
+        .with_param("socketTimeout", settings.socket_timeout)
+        .with_param("retries", settings.retries)
+        .with_param("extractorRetries", settings.extractor_retries)
+}
+
+/// Like [`parse_ytdlp_error`], but socket timeouts become [`metadata_timeout_error`]
+pub fn parse_metadata_error(stderr: &str) -> Option<BackendError> {
+    // Retried timeouts are logged as warnings; only the error that ended the run counts
+    let final_error = stderr
+        .lines()
+        .map(str::trim)
+        .rfind(|line| line.starts_with("ERROR:"));
+    if let Some(line) = final_error {
+        let lower = line.to_lowercase();
+        if lower.contains("timed out") || lower.contains("timeout") {
+            return Some(metadata_timeout_error(format!(
+                "Timed out fetching metadata: {}",
+                line
+            )));
+        }
+    }
+    parse_ytdlp_error(stderr)
+}
+
+const SPEED_PROBE_URL: &str = "https://speed.cloudflare.com/__down?bytes=25000000";
+const SPEED_PROBE_MAX_DURATION: Duration = Duration::from_secs(8);
+
+/// Result of the bandwidth probe
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionSpeed {
+    pub bytes: u64,
+    pub duration_ms: u64,
+    pub megabits_per_second: f64,
+    pub recommended_profile: NetworkProfile,
+}
+
+fn recommend_profile(megabits_per_second: f64) -> NetworkProfile {
+    if megabits_per_second < 5.0 {
+        NetworkProfile::Slow
+    } else if megabits_per_second < 50.0 {
+        NetworkProfile::Normal
+    } else {
+        NetworkProfile::Fast
+    }
+}
+
+/// Download a test file for a few seconds and report the throughput
+pub async fn probe_connection_speed(proxy_url: Option<&str>) -> Result<ConnectionSpeed, String> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent("Youwee/0.6.0")
+        .timeout(SPEED_PROBE_MAX_DURATION + Duration::from_secs(5));
+    if let Some(proxy) = proxy_url.map(str::trim).filter(|proxy| !proxy.is_empty()) {
+        builder =
+            builder.proxy(reqwest::Proxy::all(proxy).map_err(|e| format!("Invalid proxy: {}", e))?);
+    }
+    let client = builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let started = Instant::now();
+    let response = client
+        .get(SPEED_PROBE_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Speed test request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Speed test failed with status: {}",
+            response.status()
+        ));
+    }
+
+    // Time from the first byte so connection setup doesn't count as bandwidth
+    let mut transfer_started = None;
+    let mut bytes = 0_u64;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Speed test request failed: {}", e))?;
+        transfer_started.get_or_insert_with(Instant::now);
+        bytes += chunk.len() as u64;
+        if started.elapsed() >= SPEED_PROBE_MAX_DURATION {
+            break;
+        }
+    }
+
+    let elapsed = transfer_started
+        .map(|at| at.elapsed())
+        .unwrap_or_else(|| started.elapsed())
+        .max(Duration::from_millis(1));
+    let megabits_per_second = (bytes as f64 * 8.0 / 1_000_000.0) / elapsed.as_secs_f64();
+    Ok(ConnectionSpeed {
+        bytes,
+        duration_ms: elapsed.as_millis() as u64,
+        megabits_per_second: (megabits_per_second * 10.0).round() / 10.0,
+        recommended_profile: recommend_profile(megabits_per_second),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_map_to_ytdlp_flags() {
+        let normal = network_tuning_args(&NetworkProfile::Normal.tuning());
+        assert_eq!(normal, vec!["--retries", "3", "--fragment-retries", "3"]);
+        let fast = network_tuning_args(&NetworkProfile::Fast.tuning());
+        assert!(fast
+            .windows(2)
+            .any(|pair| pair == ["--concurrent-fragments", "8"]));
+        assert!(fast
+            .windows(2)
+            .any(|pair| pair == ["--http-chunk-size", "10M"]));
+
+        assert!(is_valid_chunk_size("512K"));
+        assert!(!is_valid_chunk_size("10MB"));
+        assert!(!is_valid_chunk_size("M"));
+        assert_eq!(recommend_profile(2.0), NetworkProfile::Slow);
+        assert_eq!(recommend_profile(300.0), NetworkProfile::Fast);
+    }
+
+    /// Puts back the process-wide metadata settings other tests may rely on
+    struct RestoreMetadataNetwork(Option<MetadataNetworkSettings>);
+
+    impl Drop for RestoreMetadataNetwork {
+        fn drop(&mut self) {
+            if let Ok(mut guard) = METADATA_NETWORK.lock() {
+                *guard = self.0;
+            }
+        }
+    }
+
+    #[test]
+    fn metadata_settings_apply_to_flags_and_timeout_errors() {
+        let _restore = RestoreMetadataNetwork(*METADATA_NETWORK.lock().unwrap());
+        set_metadata_network_settings(MetadataNetworkSettings::default());
+        assert_eq!(
+            metadata_deadline(Duration::from_secs(45)),
+            Duration::from_secs(45)
+        );
+
+        set_metadata_network_settings(MetadataNetworkSettings {
+            socket_timeout: 0,
+            retries: 2,
+            extractor_retries: 99,
+        });
+        assert_eq!(
+            metadata_network_args(),
+            vec![
+                "--socket-timeout",
+                "1",
+                "--retries",
+                "2",
+                "--extractor-retries",
+                "20"
+            ]
+        );
+        assert_eq!(
+            metadata_deadline(Duration::from_secs(45)),
+            Duration::from_secs(45)
+        );
+
+        let error = parse_metadata_error(
+            "ERROR: [youtube] abc: Unable to download API page: The read operation timed out",
+        )
+        .expect("timeout error");
+        assert_eq!(error.code(), code::METADATA_TIMEOUT);
+        assert_eq!(error.params().unwrap()["extractorRetries"], 20);
+        assert_ne!(
+            parse_metadata_error("ERROR: This video is unavailable.").map(|e| e.code().to_string()),
+            Some(code::METADATA_TIMEOUT.to_string())
+        );
+        let retried = "WARNING: [youtube] abc: The read operation timed out. Retrying (1/3)...\n\
+                       ERROR: [youtube] abc: Private video. Sign in if you've been granted access";
+        assert_ne!(
+            parse_metadata_error(retried).map(|e| e.code().to_string()),
+            Some(code::METADATA_TIMEOUT.to_string())
+        );
+    }
+}
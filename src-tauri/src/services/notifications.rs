@@ -0,0 +1,403 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+use crate::database::add_log_internal;
+use crate::utils::CommandExt;
+
+const TELEGRAM_API_BASE: &str = "https://api.telegram.org";
+const NOTIFICATION_TIMEOUT_SECS: u64 = 15;
+
+static WEBHOOKS: Mutex<Vec<WebhookConfig>> = Mutex::new(Vec::new());
+
+/// App event an integration can subscribe to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationEvent {
+    DownloadCompleted,
+    DownloadFailed,
+    QueueFinished,
+    Test,
+}
+
+impl NotificationEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationEvent::DownloadCompleted => "download-completed",
+            NotificationEvent::DownloadFailed => "download-failed",
+            NotificationEvent::QueueFinished => "queue-finished",
+            NotificationEvent::Test => "test",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookKind {
+    /// Generic JSON POST
+    #[default]
+    Json,
+    Discord,
+    Telegram,
+    /// External program run with templated arguments
+    Command,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebhookConfig {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub kind: WebhookKind,
+    /// Endpoint for JSON and Discord webhooks
+    pub url: String,
+    pub bot_token: String,
+    pub chat_id: String,
+    pub command: String,
+    pub args: Vec<String>,
+    /// Events that fire this integration; empty means all of them
+    pub events: Vec<NotificationEvent>,
+    /// Request body (JSON) or message text with `{{field}}` placeholders
+    pub template: Option<String>,
+}
+
+impl WebhookConfig {
+    fn subscribed_to(&self, event: NotificationEvent) -> bool {
+        event == NotificationEvent::Test || self.events.is_empty() || self.events.contains(&event)
+    }
+
+    fn label(&self) -> &str {
+        if self.name.trim().is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// Fields available to templates and sent as the default JSON body
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPayload {
+    pub event: NotificationEvent,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub filepath: Option<String>,
+    pub message: Option<String>,
+    pub completed: Option<u32>,
+    pub failed: Option<u32>,
+    pub timestamp: String,
+}
+
+impl NotificationPayload {
+    pub fn new(event: NotificationEvent) -> Self {
+        Self {
+            event,
+            title: None,
+            url: None,
+            filepath: None,
+            message: None,
+            completed: None,
+            failed: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn default_text(&self) -> String {
+        let title = self.title.as_deref().unwrap_or("Unknown");
+        match self.event {
+            NotificationEvent::DownloadCompleted => format!("Downloaded: {}", title),
+            NotificationEvent::DownloadFailed => match self.message.as_deref() {
+                Some(message) => format!("Download failed: {} ({})", title, message),
+                None => format!("Download failed: {}", title),
+            },
+            NotificationEvent::QueueFinished => format!(
+                "Download queue finished: {} completed, {} failed",
+                self.completed.unwrap_or(0),
+                self.failed.unwrap_or(0)
+            ),
+            NotificationEvent::Test => "Youwee test notification".to_string(),
+        }
+    }
+}
+
+pub fn set_webhooks(webhooks: Vec<WebhookConfig>) {
+    let sanitized = webhooks
+        .into_iter()
+        .filter(|hook| !hook.id.trim().is_empty())
+        .map(|hook| WebhookConfig {
+            url: hook.url.trim().to_string(),
+            bot_token: hook.bot_token.trim().to_string(),
+            chat_id: hook.chat_id.trim().to_string(),
+            command: hook.command.trim().to_string(),
+            ..hook
+        })
+        .collect();
+    if let Ok(mut guard) = WEBHOOKS.lock() {
+        *guard = sanitized;
+    }
+}
+
+pub fn get_webhooks() -> Vec<WebhookConfig> {
+    WEBHOOKS
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+/// Where a rendered template ends up, which decides how values are escaped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TemplateTarget {
+    /// Plain message text, sent as-is
+    Text,
+    /// Inside JSON string literals
+    Json,
+    /// Discord message content, where markdown and mentions are live
+    Discord,
+    /// One argv entry of an external command (no shell is involved)
+    Argument,
+}
+
+fn escape_value(text: String, target: TemplateTarget) -> String {
+    match target {
+        TemplateTarget::Text => text,
+        TemplateTarget::Json => {
+            let quoted = Value::String(text).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+        TemplateTarget::Discord => {
+            let mut escaped = String::with_capacity(text.len());
+            for ch in text.chars() {
+                if matches!(
+                    ch,
+                    '\\' | '*' | '_' | '~' | '`' | '|' | '>' | '<' | '@' | '#'
+                ) {
+                    escaped.push('\\');
+                }
+                escaped.push(ch);
+            }
+            escaped
+        }
+        // Control characters can't travel in argv, and a value must not turn
+        // into an option of the program
+        TemplateTarget::Argument => text
+            .chars()
+            .filter(|ch| !ch.is_control())
+            .collect::<String>()
+            .trim_start_matches('-')
+            .to_string(),
+    }
+}
+
+/// Replace `{{field}}` placeholders with payload values, escaped for `target`
+pub fn render_template(
+    template: &str,
+    payload: &NotificationPayload,
+    target: TemplateTarget,
+) -> String {
+    let Ok(Value::Object(fields)) = serde_json::to_value(payload) else {
+        return template.to_string();
+    };
+    let mut rendered = template.to_string();
+    for (key, value) in fields {
+        let text = match value {
+            Value::String(text) => text,
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        rendered = rendered.replace(&format!("{{{{{}}}}}", key), &escape_value(text, target));
+    }
+    rendered
+}
+
+fn message_text(
+    hook: &WebhookConfig,
+    payload: &NotificationPayload,
+    target: TemplateTarget,
+) -> String {
+    match hook.template.as_deref().filter(|t| !t.trim().is_empty()) {
+        Some(template) => render_template(template, payload, target),
+        None => payload.default_text(),
+    }
+}
+
+async fn post_json(client: &Client, url: &str, body: &Value) -> Result<(), String> {
+    if url.is_empty() {
+        return Err("Webhook URL is required".to_string());
+    }
+    let response = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        // The URL can hold a token (Telegram bot, Slack hook), keep it out of logs
+        .map_err(|e| format!("Request failed: {}", e.without_url()))?;
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+async fn run_command(hook: &WebhookConfig, payload: &NotificationPayload) -> Result<(), String> {
+    if hook.command.is_empty() {
+        return Err("Command is required".to_string());
+    }
+    let payload_json = serde_json::to_string(payload).unwrap_or_default();
+    let mut cmd = Command::new(&hook.command);
+    cmd.args(
+        hook.args
+            .iter()
+            .map(|arg| render_template(arg, payload, TemplateTarget::Argument)),
+    )
+    .env("YOUWEE_EVENT", payload.event.as_str())
+    .env("YOUWEE_PAYLOAD", payload_json)
+    .kill_on_drop(true)
+    .hide_window();
+    let output = tokio::time::timeout(Duration::from_secs(NOTIFICATION_TIMEOUT_SECS), cmd.output())
+        .await
+        .map_err(|_| format!("{} timed out", hook.command))?
+        .map_err(|e| format!("Failed to run {}: {}", hook.command, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            hook.command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+async fn send_notification(
+    hook: &WebhookConfig,
+    payload: &NotificationPayload,
+) -> Result<(), String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(NOTIFICATION_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    match hook.kind {
+        WebhookKind::Json => {
+            let body = match hook.template.as_deref().filter(|t| !t.trim().is_empty()) {
+                Some(template) => {
+                    serde_json::from_str(&render_template(template, payload, TemplateTarget::Json))
+                        .map_err(|e| format!("Template is not valid JSON: {}", e))?
+                }
+                None => serde_json::to_value(payload).unwrap_or_default(),
+            };
+            post_json(&client, &hook.url, &body).await
+        }
+        WebhookKind::Discord => {
+            let body = json!({
+                "content": message_text(hook, payload, TemplateTarget::Discord),
+                "allowed_mentions": { "parse": [] },
+            });
+            post_json(&client, &hook.url, &body).await
+        }
+        WebhookKind::Telegram => {
+            if hook.bot_token.is_empty() || hook.chat_id.is_empty() {
+                return Err("Telegram bot token and chat ID are required".to_string());
+            }
+            let url = format!("{}/bot{}/sendMessage", TELEGRAM_API_BASE, hook.bot_token);
+            let body = json!({
+                "chat_id": hook.chat_id,
+                "text": message_text(hook, payload, TemplateTarget::Text),
+            });
+            post_json(&client, &url, &body).await
+        }
+        WebhookKind::Command => run_command(hook, payload).await,
+    }
+}
+
+/// Fire every enabled integration subscribed to the payload's event.
+/// Runs in the background; failures are written to the log.
+pub fn dispatch_notification(payload: NotificationPayload) {
+    let hooks: Vec<WebhookConfig> = WEBHOOKS
+        .lock()
+        .map(|guard| {
+            guard
+                .iter()
+                .filter(|hook| hook.enabled && hook.subscribed_to(payload.event))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    if hooks.is_empty() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        for hook in hooks {
+            if let Err(error) = send_notification(&hook, &payload).await {
+                add_log_internal(
+                    "error",
+                    &format!("Notification \"{}\" failed", hook.label()),
+                    Some(&error),
+                    payload.url.as_deref(),
+                )
+                .ok();
+            }
+        }
+    });
+}
+
+/// Send a test event through one integration, enabled or not
+pub async fn test_webhook(id: &str) -> Result<(), String> {
+    let hook = WEBHOOKS
+        .lock()
+        .map_err(|_| "Failed to read notification settings".to_string())?
+        .iter()
+        .find(|hook| hook.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Notification integration not found: {}", id))?;
+    let mut payload = NotificationPayload::new(NotificationEvent::Test);
+    payload.title = Some("Test notification".to_string());
+    send_notification(&hook, &payload).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_fill_fields_and_escape_json() {
+        let mut payload = NotificationPayload::new(NotificationEvent::DownloadCompleted);
+        payload.title = Some("He said \"hi\"".to_string());
+        payload.completed = Some(3);
+
+        assert_eq!(
+            render_template(
+                "{{event}}: {{title}} ({{completed}}){{url}}",
+                &payload,
+                TemplateTarget::Text
+            ),
+            "download-completed: He said \"hi\" (3)"
+        );
+        let body = render_template(r#"{"text": "{{title}}"}"#, &payload, TemplateTarget::Json);
+        let parsed: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["text"], "He said \"hi\"");
+
+        payload.title = Some("--exec=rm\n@everyone *x*".to_string());
+        assert_eq!(
+            render_template("{{title}}", &payload, TemplateTarget::Argument),
+            "exec=rm@everyone *x*"
+        );
+        assert_eq!(
+            render_template("Done: {{title}}", &payload, TemplateTarget::Discord),
+            "Done: --exec=rm\n\\@everyone \\*x\\*"
+        );
+
+        let hook = WebhookConfig {
+            events: vec![NotificationEvent::QueueFinished],
+            ..Default::default()
+        };
+        assert!(hook.subscribed_to(NotificationEvent::QueueFinished));
+        assert!(!hook.subscribed_to(NotificationEvent::DownloadCompleted));
+        assert!(hook.subscribed_to(NotificationEvent::Test));
+    }
+}
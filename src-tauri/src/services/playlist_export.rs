@@ -0,0 +1,165 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::types::PlaylistExportFormat;
+
+/// One local file in an exported playlist
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaylistTrack {
+    pub title: String,
+    pub path: PathBuf,
+    pub duration: Option<u64>,
+}
+
+/// `path` relative to the folder `base`, or None when they share no root
+/// (different drives on Windows)
+pub fn relative_path(path: &Path, base: &Path) -> Option<PathBuf> {
+    let path_parts: Vec<Component> = path.components().collect();
+    let base_parts: Vec<Component> = base.components().collect();
+    let common = path_parts
+        .iter()
+        .zip(&base_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return None;
+    }
+    let mut relative = PathBuf::new();
+    for _ in common..base_parts.len() {
+        relative.push("..");
+    }
+    for part in &path_parts[common..] {
+        relative.push(part);
+    }
+    Some(relative)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Percent-encode a path for a URI, keeping `/` separators
+fn uri_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.replace('\\', "/").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// How a track's file is referenced from a playlist saved in `playlist_dir`
+fn track_location(track: &PlaylistTrack, playlist_dir: Option<&Path>) -> (PathBuf, bool) {
+    match playlist_dir.and_then(|dir| relative_path(&track.path, dir)) {
+        Some(relative) => (relative, true),
+        None => (track.path.clone(), false),
+    }
+}
+
+pub fn render_m3u8(title: &str, tracks: &[PlaylistTrack], playlist_dir: Option<&Path>) -> String {
+    let mut out = format!("#EXTM3U\n#PLAYLIST:{}\n", title.replace('\n', " "));
+    for track in tracks {
+        let (location, _) = track_location(track, playlist_dir);
+        let duration = track.duration.map(|d| d as i64).unwrap_or(-1);
+        out.push_str(&format!(
+            "#EXTINF:{},{}\n{}\n",
+            duration,
+            track.title.replace('\n', " "),
+            location.display()
+        ));
+    }
+    out
+}
+
+pub fn render_xspf(title: &str, tracks: &[PlaylistTrack], playlist_dir: Option<&Path>) -> String {
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n  <title>{}</title>\n  <trackList>\n",
+        xml_escape(title)
+    );
+    for track in tracks {
+        let (location, is_relative) = track_location(track, playlist_dir);
+        let location = location.to_string_lossy();
+        let uri = if is_relative {
+            uri_path(&location)
+        } else if location.starts_with('/') {
+            format!("file://{}", uri_path(&location))
+        } else {
+            // Windows drive paths: file:///C:/...
+            format!("file:///{}", uri_path(&location))
+        };
+        out.push_str("    <track>\n");
+        out.push_str(&format!(
+            "      <location>{}</location>\n",
+            xml_escape(&uri)
+        ));
+        out.push_str(&format!(
+            "      <title>{}</title>\n",
+            xml_escape(&track.title)
+        ));
+        if let Some(duration) = track.duration {
+            out.push_str(&format!("      <duration>{}</duration>\n", duration * 1000));
+        }
+        out.push_str("    </track>\n");
+    }
+    out.push_str("  </trackList>\n</playlist>\n");
+    out
+}
+
+/// Write `tracks` to `path`; `relative_paths` makes entries relative to the
+/// playlist's folder where possible so the folder can be moved as a whole
+pub fn write_playlist(
+    path: &Path,
+    format: PlaylistExportFormat,
+    title: &str,
+    tracks: &[PlaylistTrack],
+    relative_paths: bool,
+) -> Result<(), String> {
+    let playlist_dir = relative_paths.then(|| path.parent()).flatten();
+    let content = match format {
+        PlaylistExportFormat::M3u8 => render_m3u8(title, tracks, playlist_dir),
+        PlaylistExportFormat::Xspf => render_xspf(title, tracks, playlist_dir),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create playlist folder: {}", e))?;
+    }
+    std::fs::write(path, content).map_err(|e| format!("Failed to write playlist: {}", e))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_relative_and_absolute_locations() {
+        let tracks = vec![
+            PlaylistTrack {
+                title: "Talk & Q/A".to_string(),
+                path: PathBuf::from("/music/talks/Talk 1.m4a"),
+                duration: Some(61),
+            },
+            PlaylistTrack {
+                title: "Song".to_string(),
+                path: PathBuf::from("/other/Song.mp3"),
+                duration: None,
+            },
+        ];
+        let dir = Path::new("/music/playlists");
+
+        assert_eq!(
+            render_m3u8("Mix", &tracks, Some(dir)),
+            "#EXTM3U\n#PLAYLIST:Mix\n#EXTINF:61,Talk & Q/A\n../talks/Talk 1.m4a\n#EXTINF:-1,Song\n../../other/Song.mp3\n"
+        );
+        let xspf = render_xspf("Mix", &tracks, None);
+        assert!(xspf.contains("<location>file:///music/talks/Talk%201.m4a</location>"));
+        assert!(xspf.contains("<title>Talk &amp; Q/A</title>"));
+        assert!(xspf.contains("<duration>61000</duration>"));
+    }
+}
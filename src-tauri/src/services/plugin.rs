@@ -5,7 +5,7 @@ use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter};
 use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use uuid::Uuid;
@@ -20,9 +20,9 @@ use crate::types::{
     PluginPermissionApproval, PluginPermissionRequest, PluginProvider, PluginRuntimeLanguage,
     PluginSummary, PluginToolPermission, PluginTriggerWorkflow, PluginWorkflowFailurePolicy,
     PluginWorkflowRun, PluginWorkflowRunStatus, PluginWorkflowStepSnapshot,
-    PostDownloadPluginPayload,
+    PostDownloadPluginPayload, DOWNLOAD_PROGRESS,
 };
-use crate::utils::CommandExt;
+use crate::utils::{data_dir, CommandExt};
 
 mod bridge;
 mod compatibility;
@@ -338,8 +338,11 @@ fn mark_failed_download_recovered(
         filepath: Some(filepath.to_string()),
         downloaded_size: None,
         elapsed_time: None,
+        stage: None,
+        stage_detail: None,
+        indeterminate: false,
     };
-    app.emit("download-progress", progress).ok();
+    DOWNLOAD_PROGRESS.emit(app, &progress).ok();
 }
 
 const PLUGINS_DIR_NAME: &str = "plugins";
@@ -446,10 +449,8 @@ pub struct PluginRuntimeLocaleInput {
 }
 
 pub fn plugins_root(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let app_data_dir =
+        data_dir(app).map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
     Ok(app_data_dir.join(PLUGINS_DIR_NAME))
 }
 
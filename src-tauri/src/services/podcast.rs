@@ -0,0 +1,277 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::types::{PodcastEpisode, PodcastFeed};
+
+const FEED_TIMEOUT_SECS: u64 = 30;
+
+static ITEM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<item\b[^>]*>(.*?)</item>").expect("valid item regex"));
+static ATTR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid attribute regex")
+});
+
+fn decode_xml_entities(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let Some(end) = tail.find(';').filter(|end| *end <= 10) else {
+            decoded.push('&');
+            rest = &tail[1..];
+            continue;
+        };
+        let entity = &tail[1..end];
+        let replacement = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match replacement {
+            Some(c) => {
+                decoded.push(c);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &tail[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The first `<tag ...>` in `block` as (start, end) byte offsets
+fn find_open_tag(block: &str, tag: &str) -> Option<(usize, usize)> {
+    let needle = format!("<{}", tag);
+    block.match_indices(&needle).find_map(|(start, _)| {
+        let rest = &block[start + needle.len()..];
+        let boundary = rest.chars().next()?;
+        if boundary != '>' && boundary != '/' && !boundary.is_whitespace() {
+            return None;
+        }
+        Some((start, start + needle.len() + rest.find('>')? + 1))
+    })
+}
+
+/// Text content of the first `<tag>` in `block`, with CDATA unwrapped
+fn tag_text(block: &str, tag: &str) -> Option<String> {
+    let (start, end) = find_open_tag(block, tag)?;
+    if block[start..end].ends_with("/>") {
+        return None;
+    }
+    let close = block[end..].find(&format!("</{}>", tag))?;
+    let raw = block[end..end + close].trim();
+    let text = match raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|inner| inner.strip_suffix("]]>"))
+    {
+        Some(cdata) => cdata.trim().to_string(),
+        None => decode_xml_entities(raw),
+    };
+    Some(text).filter(|text| !text.is_empty())
+}
+
+/// Attribute value of the first `<tag ...>` in `block`
+fn tag_attr(block: &str, tag: &str, attr: &str) -> Option<String> {
+    let (start, end) = find_open_tag(block, tag)?;
+    ATTR_RE
+        .captures_iter(&block[start..end])
+        .find(|caps| caps.get(1).is_some_and(|name| name.as_str() == attr))
+        .and_then(|caps| caps.get(2).or_else(|| caps.get(3)))
+        .map(|m| decode_xml_entities(m.as_str().trim()))
+        .filter(|value| !value.is_empty())
+}
+
+/// Parse `<itunes:duration>` which may be seconds, `MM:SS` or `HH:MM:SS`
+pub fn parse_itunes_duration(value: &str) -> Option<u64> {
+    let parts: Vec<&str> = value.trim().split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let mut seconds = 0u64;
+    for part in parts {
+        let number: f64 = part.trim().parse().ok()?;
+        seconds = seconds * 60 + number as u64;
+    }
+    Some(seconds)
+}
+
+fn parse_feed_date(value: &str) -> String {
+    chrono::DateTime::parse_from_rfc2822(value.trim())
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(value.trim()))
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_else(|_| value.trim().to_string())
+}
+
+fn split_items(xml: &str) -> Vec<&str> {
+    ITEM_RE
+        .captures_iter(xml)
+        .filter_map(|caps| caps.get(1).map(|m| m.as_str()))
+        .collect()
+}
+
+fn parse_episode(item: &str) -> Option<PodcastEpisode> {
+    // Episodes without an audio enclosure can't be downloaded
+    let enclosure_url = tag_attr(item, "enclosure", "url")?;
+    let title = tag_text(item, "title")
+        .or_else(|| tag_text(item, "itunes:title"))
+        .unwrap_or_else(|| "Untitled episode".to_string());
+
+    Some(PodcastEpisode {
+        guid: tag_text(item, "guid").unwrap_or_else(|| enclosure_url.clone()),
+        title,
+        published_at: tag_text(item, "pubDate").map(|date| parse_feed_date(&date)),
+        duration_seconds: tag_text(item, "itunes:duration")
+            .and_then(|duration| parse_itunes_duration(&duration)),
+        enclosure_type: tag_attr(item, "enclosure", "type"),
+        enclosure_length: tag_attr(item, "enclosure", "length")
+            .and_then(|length| length.parse().ok())
+            .filter(|length| *length > 0),
+        description: tag_text(item, "itunes:summary").or_else(|| tag_text(item, "description")),
+        image: tag_attr(item, "itunes:image", "href"),
+        episode_number: tag_text(item, "itunes:episode").and_then(|n| n.parse().ok()),
+        season_number: tag_text(item, "itunes:season").and_then(|n| n.parse().ok()),
+        enclosure_url,
+    })
+}
+
+/// Parse an RSS 2.0 podcast feed (with iTunes extensions)
+pub fn parse_podcast_feed(url: &str, xml: &str) -> Result<PodcastFeed, String> {
+    if !xml.contains("<rss") && !xml.contains("<channel") {
+        return Err("Not a podcast RSS feed".to_string());
+    }
+    // Channel-level tags come before the first item; item titles must not leak in
+    let header = xml.split("<item").next().unwrap_or(xml);
+
+    Ok(PodcastFeed {
+        url: url.to_string(),
+        title: tag_text(header, "title").unwrap_or_else(|| "Podcast".to_string()),
+        author: tag_text(header, "itunes:author").or_else(|| tag_text(header, "author")),
+        description: tag_text(header, "description").or_else(|| tag_text(header, "itunes:summary")),
+        image: tag_attr(header, "itunes:image", "href").or_else(|| tag_text(header, "url")),
+        link: tag_text(header, "link"),
+        episodes: split_items(xml)
+            .into_iter()
+            .filter_map(parse_episode)
+            .collect(),
+    })
+}
+
+/// Download and parse a podcast feed
+pub async fn fetch_podcast_feed(url: &str, proxy_url: Option<&str>) -> Result<PodcastFeed, String> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent("Youwee/0.6.0")
+        .timeout(Duration::from_secs(FEED_TIMEOUT_SECS));
+    if let Some(proxy) = proxy_url.map(str::trim).filter(|p| !p.is_empty()) {
+        builder = builder
+            .proxy(reqwest::Proxy::all(proxy).map_err(|e| format!("Invalid proxy URL: {}", e))?);
+    }
+    let client = builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch podcast feed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch podcast feed: HTTP {}",
+            response.status()
+        ));
+    }
+    let xml = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read podcast feed: {}", e))?;
+
+    parse_podcast_feed(url, &xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Dev &amp; Coffee</title>
+    <itunes:author>Jane Host</itunes:author>
+    <itunes:image href="https://example.com/cover.jpg"/>
+    <description><![CDATA[Weekly <b>chats</b>]]></description>
+    <item>
+      <title>Episode 2: Rust</title>
+      <guid isPermaLink="false">ep-2</guid>
+      <pubDate>Tue, 02 Jan 2024 10:00:00 +0000</pubDate>
+      <itunes:duration>1:02:03</itunes:duration>
+      <itunes:episode>2</itunes:episode>
+      <enclosure url="https://cdn.example.com/ep2.mp3?x=1&amp;y=2" type="audio/mpeg" length="12345"/>
+    </item>
+    <item>
+      <title>Trailer without audio</title>
+    </item>
+    <item>
+      <title>Episode 1</title>
+      <itunes:duration>930</itunes:duration>
+      <enclosure type="audio/mp4" url='https://cdn.example.com/ep1.m4a'/>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn parse_podcast_feed_reads_channel_and_episodes() {
+        let feed = parse_podcast_feed("https://example.com/feed.xml", SAMPLE_FEED).unwrap();
+
+        assert_eq!(feed.title, "Dev & Coffee");
+        assert_eq!(feed.author.as_deref(), Some("Jane Host"));
+        assert_eq!(feed.image.as_deref(), Some("https://example.com/cover.jpg"));
+        assert_eq!(feed.description.as_deref(), Some("Weekly <b>chats</b>"));
+        assert_eq!(feed.episodes.len(), 2);
+
+        let latest = &feed.episodes[0];
+        assert_eq!(latest.guid, "ep-2");
+        assert_eq!(
+            latest.enclosure_url,
+            "https://cdn.example.com/ep2.mp3?x=1&y=2"
+        );
+        assert_eq!(latest.duration_seconds, Some(3723));
+        assert_eq!(latest.episode_number, Some(2));
+        assert_eq!(latest.enclosure_length, Some(12345));
+        assert_eq!(
+            latest.published_at.as_deref(),
+            Some("2024-01-02T10:00:00+00:00")
+        );
+
+        let first = &feed.episodes[1];
+        assert_eq!(first.guid, "https://cdn.example.com/ep1.m4a");
+        assert_eq!(first.enclosure_type.as_deref(), Some("audio/mp4"));
+        assert_eq!(first.duration_seconds, Some(930));
+    }
+
+    #[test]
+    fn parse_podcast_feed_rejects_html() {
+        assert!(parse_podcast_feed("https://example.com", "<html></html>").is_err());
+    }
+
+    #[test]
+    fn decode_xml_entities_handles_numeric_references() {
+        assert_eq!(
+            decode_xml_entities("Caf&#233; &#x26; more &bogus"),
+            "Café & more &bogus"
+        );
+    }
+}
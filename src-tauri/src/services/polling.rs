@@ -4,10 +4,11 @@ use tauri::{AppHandle, Emitter};
 
 use crate::database;
 use crate::services::{
-    build_cookie_args, build_site_header_args, get_deno_path, run_ytdlp_with_stderr,
+    build_cookie_args, build_site_header_args, fetch_podcast_feed, get_deno_path,
+    metadata_network_args, run_ytdlp_with_stderr,
 };
-use crate::types::{ChannelVideo, FollowedChannel};
-use crate::utils::normalize_channel_content_urls;
+use crate::types::{ChannelVideo, FollowedChannel, PodcastFeed};
+use crate::utils::{is_short_form_info, normalize_channel_content_urls};
 
 /// Cookie/proxy configuration synced from the frontend for background polling.
 #[derive(Clone, Default)]
@@ -202,6 +203,31 @@ pub fn stop_polling() {
     POLLING_ACTIVE.store(false, Ordering::SeqCst);
 }
 
+/// Render feed episodes in the yt-dlp `--dump-json` line shape the channel
+/// checker already understands, so filters and new-episode detection are shared.
+fn podcast_feed_json_lines(feed: &PodcastFeed, limit: usize) -> String {
+    feed.episodes
+        .iter()
+        .take(limit)
+        .map(|episode| {
+            serde_json::json!({
+                "id": episode.guid,
+                "title": episode.title,
+                "url": episode.enclosure_url,
+                "thumbnail": episode.image.as_ref().or(feed.image.as_ref()),
+                "duration": episode.duration_seconds,
+                "upload_date": episode
+                    .published_at
+                    .as_deref()
+                    .and_then(|date| date.get(..10))
+                    .map(|date| date.replace('-', "")),
+            })
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Check if enough time has passed since last check for a channel
 fn should_check_channel(channel: &FollowedChannel) -> bool {
     match &channel.last_checked_at {
@@ -225,22 +251,30 @@ async fn check_channel_for_new_videos(
     channel: &FollowedChannel,
 ) -> Result<usize, String> {
     let limit = channel.filter_max_videos.unwrap_or(20) as u32;
-    let channel_urls =
-        normalize_channel_content_urls(&channel.url, Some(&channel.youtube_content_type));
+    let is_podcast = channel.platform == "podcast";
+    let channel_urls = if is_podcast {
+        Vec::new()
+    } else {
+        normalize_channel_content_urls(&channel.url, Some(&channel.youtube_content_type))
+    };
     let net = get_network_config();
     let mut output = String::new();
 
+    if is_podcast {
+        let feed = fetch_podcast_feed(&channel.url, net.proxy_url.as_deref()).await?;
+        output = podcast_feed_json_lines(&feed, limit as usize);
+    }
+
     for channel_url in channel_urls {
         let is_youtube = channel_url.contains("youtube.com") || channel_url.contains("youtu.be");
 
         let mut args = vec![
             "--dump-json".to_string(),
             "--no-warnings".to_string(),
-            "--socket-timeout".to_string(),
-            "30".to_string(),
             "--playlist-end".to_string(),
             limit.to_string(),
         ];
+        args.extend(metadata_network_args());
 
         // Only use --flat-playlist for YouTube; other platforms (Bilibili, etc.)
         // return minimal data in flat mode (no title, thumbnail, duration)
@@ -373,6 +407,10 @@ async fn check_channel_for_new_videos(
                 }
             }
 
+            if channel.filter_skip_shorts && is_short_form_info(&json) {
+                continue;
+            }
+
             // Keyword filters
             if let Some(ref include_kw) = channel.filter_include_keywords {
                 if !include_kw.is_empty() {
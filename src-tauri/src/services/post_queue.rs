@@ -0,0 +1,219 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::AppHandle;
+use tokio::process::Command;
+
+use super::power::active_jobs;
+pub use crate::types::PostQueueAction;
+use crate::types::{PostQueueActionEvent, POST_QUEUE_ACTION};
+use crate::utils::CommandExt;
+
+/// Seconds the user has to cancel a sleep/shutdown once the queue finishes
+pub const POST_QUEUE_CONFIRM_SECONDS: u64 = 60;
+
+// Session-only: a shutdown left armed must not survive a restart
+static ARMED_POST_QUEUE_ACTION: Mutex<PostQueueAction> = Mutex::new(PostQueueAction::None);
+// Bumped on cancel so a pending countdown knows it was superseded
+static PENDING_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub fn set_post_queue_action(action: PostQueueAction) {
+    if let Ok(mut guard) = ARMED_POST_QUEUE_ACTION.lock() {
+        *guard = action;
+    }
+}
+
+pub fn get_post_queue_action() -> PostQueueAction {
+    ARMED_POST_QUEUE_ACTION
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or_default()
+}
+
+/// Abort a pending sleep/shutdown countdown
+pub fn cancel_post_queue_action(app: &AppHandle) {
+    PENDING_GENERATION.fetch_add(1, Ordering::SeqCst);
+    emit_action_status(app, get_post_queue_action(), "cancelled", None, None);
+}
+
+fn emit_action_status(
+    app: &AppHandle,
+    action: PostQueueAction,
+    status: &str,
+    delay_seconds: Option<u64>,
+    error: Option<String>,
+) {
+    POST_QUEUE_ACTION
+        .emit(
+            app,
+            &PostQueueActionEvent {
+                action,
+                status: status.to_string(),
+                delay_seconds,
+                error,
+            },
+        )
+        .ok();
+}
+
+/// OS command that puts the machine to sleep or powers it off
+pub fn power_action_command(action: PostQueueAction) -> Option<(&'static str, Vec<&'static str>)> {
+    #[cfg(target_os = "windows")]
+    return match action {
+        PostQueueAction::Sleep => Some((
+            "rundll32.exe",
+            vec!["powrprof.dll,SetSuspendState", "0,1,0"],
+        )),
+        PostQueueAction::Shutdown => Some(("shutdown", vec!["/s", "/t", "0"])),
+        _ => None,
+    };
+    #[cfg(target_os = "macos")]
+    return match action {
+        PostQueueAction::Sleep => Some(("pmset", vec!["sleepnow"])),
+        // Asking System Events lets apps save state, unlike `shutdown -h`
+        PostQueueAction::Shutdown => Some((
+            "osascript",
+            vec!["-e", "tell application \"System Events\" to shut down"],
+        )),
+        _ => None,
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    return match action {
+        PostQueueAction::Sleep => Some(("systemctl", vec!["suspend"])),
+        PostQueueAction::Shutdown => Some(("systemctl", vec!["poweroff"])),
+        _ => None,
+    };
+}
+
+async fn run_power_action(action: PostQueueAction) -> Result<(), String> {
+    let (program, args) =
+        power_action_command(action).ok_or_else(|| "Not a power action".to_string())?;
+    let mut cmd = Command::new(program);
+    cmd.args(&args);
+    cmd.hide_window();
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn open_folder(path: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut cmd = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut cmd = std::process::Command::new("explorer");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut cmd = std::process::Command::new("xdg-open");
+    cmd.arg(path);
+    cmd.hide_window();
+    cmd.spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open download folder: {}", e))
+}
+
+fn send_queue_notification(app: &AppHandle, completed: Option<u32>) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let body = match completed {
+        Some(1) => "Download queue finished: 1 item".to_string(),
+        Some(count) => format!("Download queue finished: {} items", count),
+        None => "Download queue finished".to_string(),
+    };
+    let _ = app
+        .notification()
+        .builder()
+        .title("Youwee")
+        .body(&body)
+        .show();
+}
+
+/// Run the session's post-queue action. Sleep and shutdown emit a `pending`
+/// event first and only run if not cancelled within the countdown.
+pub async fn run_post_queue_action(
+    app: AppHandle,
+    output_path: Option<String>,
+    completed: Option<u32>,
+) -> Result<PostQueueAction, String> {
+    let action = get_post_queue_action();
+    match action {
+        PostQueueAction::None => {}
+        PostQueueAction::Notify => send_queue_notification(&app, completed),
+        PostQueueAction::OpenFolder => {
+            let folder = output_path
+                .filter(|path| !path.trim().is_empty())
+                .ok_or_else(|| "No download folder to open".to_string())?;
+            open_folder(&folder)?;
+        }
+        PostQueueAction::Sleep | PostQueueAction::Shutdown => {
+            let generation = PENDING_GENERATION.load(Ordering::SeqCst);
+            emit_action_status(
+                &app,
+                action,
+                "pending",
+                Some(POST_QUEUE_CONFIRM_SECONDS),
+                None,
+            );
+            tokio::time::sleep(Duration::from_secs(POST_QUEUE_CONFIRM_SECONDS)).await;
+            if PENDING_GENERATION.load(Ordering::SeqCst) != generation {
+                return Ok(PostQueueAction::None);
+            }
+            // A download or processing job started during the countdown; the
+            // user re-arms the action for that work instead of it firing later
+            let running = active_jobs().len();
+            if running > 0 {
+                set_post_queue_action(PostQueueAction::None);
+                emit_action_status(
+                    &app,
+                    action,
+                    "cancelled",
+                    None,
+                    Some(format!("{} job(s) started during the countdown", running)),
+                );
+                return Ok(PostQueueAction::None);
+            }
+
+            // One-shot: waking the machine up must not re-arm it for the next queue
+            set_post_queue_action(PostQueueAction::None);
+            if let Err(e) = run_power_action(action).await {
+                emit_action_status(&app, action, "failed", None, Some(e.clone()));
+                return Err(e);
+            }
+        }
+    }
+
+    if action != PostQueueAction::None {
+        emit_action_status(&app, action, "done", None, None);
+    }
+    Ok(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_power_actions_need_confirmation() {
+        assert!(PostQueueAction::Shutdown.needs_confirmation());
+        assert!(PostQueueAction::Sleep.needs_confirmation());
+        assert!(!PostQueueAction::Notify.needs_confirmation());
+        assert!(power_action_command(PostQueueAction::OpenFolder).is_none());
+        assert!(power_action_command(PostQueueAction::Shutdown).is_some());
+    }
+
+    #[test]
+    fn post_queue_action_uses_snake_case_on_the_wire() {
+        assert_eq!(
+            serde_json::to_string(&PostQueueAction::OpenFolder).unwrap(),
+            "\"open_folder\""
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(windows))]
+use super::process_runner::{ProcessInvocation, ProcessTarget};
+pub use crate::types::{ActiveJob, ActiveJobKind};
+
+/// What stays awake while jobs are running
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SleepInhibitMode {
+    /// Prevent system sleep but let the display turn off
+    #[default]
+    System,
+    /// Keep the display on as well
+    Display,
+}
+
+struct PowerState {
+    enabled: bool,
+    mode: SleepInhibitMode,
+    jobs: Vec<(u64, ActiveJob)>,
+    inhibitor: Option<SleepInhibitor>,
+    // Swapped in tests so no OS assertion is taken
+    acquire: fn(SleepInhibitMode) -> Option<SleepInhibitor>,
+}
+
+static POWER_STATE: Mutex<PowerState> = Mutex::new(PowerState {
+    enabled: true,
+    mode: SleepInhibitMode::System,
+    jobs: Vec::new(),
+    inhibitor: None,
+    acquire: SleepInhibitor::acquire,
+});
+
+static NEXT_JOB_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+impl PowerState {
+    fn sync_inhibitor(&mut self) {
+        let wanted = self.enabled && !self.jobs.is_empty();
+        match &self.inhibitor {
+            Some(current) if wanted && current.mode == self.mode => {}
+            _ if wanted => {
+                // Release first so a mode switch never holds two assertions
+                self.inhibitor = None;
+                self.inhibitor = (self.acquire)(self.mode);
+            }
+            _ => self.inhibitor = None,
+        }
+    }
+
+    fn add_job(&mut self, token: u64, job: ActiveJob) {
+        self.jobs.push((token, job));
+        self.sync_inhibitor();
+    }
+
+    fn remove_job(&mut self, token: u64) {
+        self.jobs.retain(|(job_token, _)| *job_token != token);
+        self.sync_inhibitor();
+    }
+}
+
+/// OS sleep assertion, released on drop
+struct SleepInhibitor {
+    mode: SleepInhibitMode,
+    // The inhibit process, or on Windows the channel whose closing wakes the
+    // thread that owns the execution state
+    _hold: Box<dyn Send>,
+}
+
+/// `caffeinate` holds an IOPMAssertion and exits with us (`-w`)
+#[cfg(target_os = "macos")]
+fn inhibit_invocation(mode: SleepInhibitMode) -> Option<ProcessInvocation> {
+    let flag = match mode {
+        SleepInhibitMode::System => "-i",
+        SleepInhibitMode::Display => "-di",
+    };
+    let pid = std::process::id().to_string();
+    Some(ProcessInvocation::binary(
+        "/usr/bin/caffeinate",
+        &[flag, "-w", &pid],
+    ))
+}
+
+#[cfg(all(not(windows), not(target_os = "macos")))]
+fn inhibit_invocation(mode: SleepInhibitMode) -> Option<ProcessInvocation> {
+    use crate::utils::{find_system_binary, unix_system_binary_dirs};
+
+    let dirs = unix_system_binary_dirs();
+    let inhibit = find_system_binary("systemd-inhibit", &dirs)?;
+    let cat = find_system_binary("cat", &dirs)?;
+    Some(systemd_inhibit_invocation(inhibit, &cat, mode))
+}
+
+/// `systemd-inhibit` holds the lock for as long as `cat` reads our stdin,
+/// so it is released even if the app crashes
+#[cfg(all(not(windows), not(target_os = "macos")))]
+fn systemd_inhibit_invocation(
+    inhibit: std::path::PathBuf,
+    cat: &std::path::Path,
+    mode: SleepInhibitMode,
+) -> ProcessInvocation {
+    let what = match mode {
+        SleepInhibitMode::System => "--what=sleep",
+        SleepInhibitMode::Display => "--what=sleep:idle",
+    };
+    let cat = cat.to_string_lossy();
+    ProcessInvocation::binary(
+        inhibit,
+        &[
+            what,
+            "--who=Youwee",
+            "--why=Downloads in progress",
+            "--mode=block",
+            &cat,
+        ],
+    )
+}
+
+#[cfg(windows)]
+fn execution_state_flags(
+    mode: SleepInhibitMode,
+) -> windows_sys::Win32::System::Power::EXECUTION_STATE {
+    use windows_sys::Win32::System::Power::{
+        ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+    };
+
+    match mode {
+        SleepInhibitMode::System => ES_CONTINUOUS | ES_SYSTEM_REQUIRED,
+        SleepInhibitMode::Display => ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED,
+    }
+}
+
+/// Child holding the inhibit lock; closing its stdin and killing it releases the lock
+#[cfg(not(windows))]
+struct InhibitProcess(std::process::Child);
+
+#[cfg(not(windows))]
+impl Drop for InhibitProcess {
+    fn drop(&mut self) {
+        drop(self.0.stdin.take());
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+impl SleepInhibitor {
+    #[cfg(not(windows))]
+    fn acquire(mode: SleepInhibitMode) -> Option<Self> {
+        use std::process::Stdio;
+
+        let invocation = inhibit_invocation(mode)?;
+        let ProcessTarget::Binary(program) = &invocation.target else {
+            return None;
+        };
+        let child = std::process::Command::new(program)
+            .args(&invocation.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        Some(Self {
+            mode,
+            _hold: Box::new(InhibitProcess(child)),
+        })
+    }
+
+    #[cfg(windows)]
+    fn acquire(mode: SleepInhibitMode) -> Option<Self> {
+        use windows_sys::Win32::System::Power::{SetThreadExecutionState, ES_CONTINUOUS};
+
+        let flags = execution_state_flags(mode);
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        // The execution state belongs to the calling thread, so park a thread on it
+        std::thread::Builder::new()
+            .name("sleep-inhibitor".to_string())
+            .spawn(move || {
+                unsafe {
+                    SetThreadExecutionState(flags);
+                }
+                let _ = release_rx.recv();
+                unsafe {
+                    SetThreadExecutionState(ES_CONTINUOUS);
+                }
+            })
+            .ok()?;
+        Some(Self {
+            mode,
+            _hold: Box::new(release_tx),
+        })
+    }
+}
+
+/// Keeps the job registered (and the machine awake) until dropped
+pub struct ActiveJobGuard {
+    token: u64,
+    state: &'static Mutex<PowerState>,
+}
+
+impl Drop for ActiveJobGuard {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.remove_job(self.token);
+        }
+    }
+}
+
+/// Register a running job; sleep is inhibited while any guard is alive
+pub fn track_active_job(job: ActiveJob) -> ActiveJobGuard {
+    track_job_in(&POWER_STATE, job)
+}
+
+fn track_job_in(state: &'static Mutex<PowerState>, job: ActiveJob) -> ActiveJobGuard {
+    let token = NEXT_JOB_TOKEN.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut guard) = state.lock() {
+        guard.add_job(token, job);
+    }
+    ActiveJobGuard { token, state }
+}
+
+/// Snapshot of the jobs currently running
+pub fn active_jobs() -> Vec<ActiveJob> {
+    POWER_STATE
+        .lock()
+        .map(|state| state.jobs.iter().map(|(_, job)| job.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Update the sleep prevention setting, applying it to jobs already running
+pub fn set_sleep_prevention_config(enabled: bool, mode: SleepInhibitMode) {
+    if let Ok(mut state) = POWER_STATE.lock() {
+        state.enabled = enabled;
+        state.mode = mode;
+        state.sync_inhibitor();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    static ACQUIRED: AtomicUsize = AtomicUsize::new(0);
+    static RELEASED: AtomicUsize = AtomicUsize::new(0);
+    // The counters are shared, so tests reading them run one at a time
+    static COUNTER_LOCK: Mutex<()> = Mutex::new(());
+
+    struct FakeHold;
+
+    impl Drop for FakeHold {
+        fn drop(&mut self) {
+            RELEASED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn fake_acquire(mode: SleepInhibitMode) -> Option<SleepInhibitor> {
+        ACQUIRED.fetch_add(1, Ordering::SeqCst);
+        Some(SleepInhibitor {
+            mode,
+            _hold: Box::new(FakeHold),
+        })
+    }
+
+    fn test_state() -> &'static Mutex<PowerState> {
+        Box::leak(Box::new(Mutex::new(PowerState {
+            enabled: true,
+            mode: SleepInhibitMode::System,
+            jobs: Vec::new(),
+            inhibitor: None,
+            acquire: fake_acquire,
+        })))
+    }
+
+    fn counts() -> (usize, usize) {
+        (
+            ACQUIRED.load(Ordering::SeqCst),
+            RELEASED.load(Ordering::SeqCst),
+        )
+    }
+
+    #[test]
+    fn nested_jobs_share_one_inhibitor_until_the_last_guard_drops() {
+        let _serial = COUNTER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let state = test_state();
+        let before = counts();
+
+        let first = track_job_in(state, ActiveJob::download("a", "https://a", "/tmp"));
+        let second = track_job_in(state, ActiveJob::download("b", "https://b", "/tmp"));
+        assert_eq!(counts(), (before.0 + 1, before.1));
+
+        drop(first);
+        assert!(state.lock().unwrap().inhibitor.is_some());
+        assert_eq!(counts(), (before.0 + 1, before.1));
+
+        drop(second);
+        assert!(state.lock().unwrap().inhibitor.is_none());
+        assert_eq!(counts(), (before.0 + 1, before.1 + 1));
+    }
+
+    #[test]
+    fn mode_switch_replaces_the_held_inhibitor() {
+        let _serial = COUNTER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let state = test_state();
+        let _job = track_job_in(state, ActiveJob::download("a", "https://a", "/tmp"));
+        let before = counts();
+
+        let mut guard = state.lock().unwrap();
+        guard.mode = SleepInhibitMode::Display;
+        guard.sync_inhibitor();
+        assert_eq!(counts(), (before.0 + 1, before.1 + 1));
+        assert_eq!(
+            guard.inhibitor.as_ref().map(|inhibitor| inhibitor.mode),
+            Some(SleepInhibitMode::Display)
+        );
+
+        guard.enabled = false;
+        guard.sync_inhibitor();
+        assert!(guard.inhibitor.is_none());
+    }
+
+    #[cfg(all(not(windows), not(target_os = "macos")))]
+    #[test]
+    fn linux_inhibits_through_systemd_inhibit() {
+        let invocation = systemd_inhibit_invocation(
+            "/usr/bin/systemd-inhibit".into(),
+            std::path::Path::new("/usr/bin/cat"),
+            SleepInhibitMode::Display,
+        );
+        assert_eq!(invocation.program_name(), "systemd-inhibit");
+        assert_eq!(invocation.args[0], "--what=sleep:idle");
+        assert_eq!(
+            invocation.args.last().map(String::as_str),
+            Some("/usr/bin/cat")
+        );
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn macos_inhibits_through_caffeinate() {
+        let invocation = inhibit_invocation(SleepInhibitMode::System).unwrap();
+        assert_eq!(invocation.program_name(), "caffeinate");
+        assert_eq!(invocation.args[..2], ["-i", "-w"]);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_only_keeps_the_display_on_in_display_mode() {
+        use windows_sys::Win32::System::Power::ES_DISPLAY_REQUIRED;
+
+        assert_eq!(
+            execution_state_flags(SleepInhibitMode::System) & ES_DISPLAY_REQUIRED,
+            0
+        );
+        assert_ne!(
+            execution_state_flags(SleepInhibitMode::Display) & ES_DISPLAY_REQUIRED,
+            0
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Global privacy setting: every download behaves as incognito
+static PRIVACY_MODE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_privacy_mode(enabled: bool) {
+    PRIVACY_MODE.store(enabled, Ordering::SeqCst);
+}
+
+pub fn privacy_mode_enabled() -> bool {
+    PRIVACY_MODE.load(Ordering::SeqCst)
+}
+
+const REDACTED: &str = "[redacted]";
+
+static EMBEDDED_URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b[a-z][a-z0-9+.-]*://[^\s,;]+").unwrap());
+
+/// Command line for the log with the source URL and any other URL (referer,
+/// headers, `--flag=<url>` values) replaced
+pub fn redact_url_args(args: &[String], url: &str) -> Vec<String> {
+    args.iter()
+        .map(|arg| {
+            let arg = if url.is_empty() {
+                arg.clone()
+            } else {
+                arg.replace(url, REDACTED)
+            };
+            EMBEDDED_URL_RE.replace_all(&arg, REDACTED).into_owned()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn redacts_the_source_and_embedded_urls() {
+        let url = "https://example.com/watch?v=abc";
+        let redacted = redact_url_args(
+            &args(&[
+                "-o",
+                "%(title)s.%(ext)s",
+                "--referer=https://example.com/page",
+                "--add-header",
+                "Referer:https://example.com/page",
+                "ytsearch:secret query",
+                url,
+            ]),
+            url,
+        );
+        assert_eq!(
+            redacted,
+            args(&[
+                "-o",
+                "%(title)s.%(ext)s",
+                "--referer=[redacted]",
+                "--add-header",
+                "Referer:[redacted]",
+                "ytsearch:secret query",
+                "[redacted]",
+            ])
+        );
+        assert_eq!(
+            redact_url_args(&args(&["ytsearch:secret query"]), "ytsearch:secret query"),
+            args(&["[redacted]"])
+        );
+    }
+}
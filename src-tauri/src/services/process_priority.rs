@@ -0,0 +1,164 @@
+use std::ffi::OsStr;
+use std::sync::Mutex;
+
+use tokio::process::Command;
+
+use crate::utils::CommandExt;
+
+/// Priority settings for heavy ffmpeg work, synced from the frontend.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProcessPriorityConfig {
+    pub low_priority: bool,
+    pub ffmpeg_threads: Option<u32>,
+}
+
+static PROCESS_PRIORITY_CONFIG: Mutex<ProcessPriorityConfig> = Mutex::new(ProcessPriorityConfig {
+    low_priority: false,
+    ffmpeg_threads: None,
+});
+
+/// Update the priority settings used for new ffmpeg and yt-dlp processes.
+pub fn set_process_priority_config(config: ProcessPriorityConfig) {
+    if let Ok(mut guard) = PROCESS_PRIORITY_CONFIG.lock() {
+        *guard = ProcessPriorityConfig {
+            ffmpeg_threads: config.ffmpeg_threads.filter(|threads| *threads > 0),
+            ..config
+        };
+    }
+}
+
+pub fn get_process_priority_config() -> ProcessPriorityConfig {
+    PROCESS_PRIORITY_CONFIG
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or_default()
+}
+
+/// Wrapper prefix (`ionice -c 3 nice -n 10`) used to start a process at low priority
+#[cfg(not(windows))]
+fn low_priority_prefix() -> Vec<String> {
+    use crate::utils::{find_system_binary, unix_system_binary_dirs};
+
+    let dirs = unix_system_binary_dirs();
+    let mut prefix = Vec::new();
+    #[cfg(target_os = "linux")]
+    if let Some(ionice) = find_system_binary("ionice", &dirs) {
+        // Idle I/O class: only touch the disk when nothing else wants it
+        prefix.extend([
+            ionice.to_string_lossy().to_string(),
+            "-c".to_string(),
+            "3".to_string(),
+        ]);
+    }
+    if let Some(nice) = find_system_binary("nice", &dirs) {
+        prefix.extend([
+            nice.to_string_lossy().to_string(),
+            "-n".to_string(),
+            "10".to_string(),
+        ]);
+    }
+    prefix
+}
+
+/// Build a hidden-window command for `program`, started at low priority when
+/// the setting is on. Callers must not call `hide_window()` on the result, as
+/// that would reset the Windows priority flag.
+pub fn background_command(program: impl AsRef<OsStr>) -> Command {
+    let config = get_process_priority_config();
+
+    #[cfg(not(windows))]
+    if config.low_priority {
+        let prefix = low_priority_prefix();
+        if let Some((wrapper, wrapper_args)) = prefix.split_first() {
+            let mut cmd = Command::new(wrapper);
+            cmd.args(wrapper_args).arg(program);
+            return cmd;
+        }
+    }
+
+    let mut cmd = Command::new(program);
+    if config.low_priority {
+        cmd.below_normal_priority();
+    } else {
+        cmd.hide_window();
+    }
+    cmd
+}
+
+/// `-threads N` output option for ffmpeg, empty when unlimited
+pub fn ffmpeg_thread_args() -> Vec<String> {
+    match get_process_priority_config().ffmpeg_threads {
+        Some(threads) => vec!["-threads".to_string(), threads.to_string()],
+        None => Vec::new(),
+    }
+}
+
+/// Insert the thread limit right before the output path unless the args already set one
+pub fn apply_ffmpeg_thread_limit(args: &mut Vec<String>) {
+    if args.iter().any(|arg| arg == "-threads") {
+        return;
+    }
+    let insert_pos = args.len().saturating_sub(1);
+    for (offset, arg) in ffmpeg_thread_args().into_iter().enumerate() {
+        args.insert(insert_pos + offset, arg);
+    }
+}
+
+/// Thread limit for the ffmpeg runs yt-dlp does while merging and post-processing
+pub fn ytdlp_postprocessor_thread_args() -> Vec<String> {
+    match get_process_priority_config().ffmpeg_threads {
+        Some(threads) => vec![
+            "--postprocessor-args".to_string(),
+            format!("ffmpeg:-threads {}", threads),
+        ],
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Puts the previous global config back, also when an assertion fails
+    struct RestoreConfig(ProcessPriorityConfig);
+
+    impl Drop for RestoreConfig {
+        fn drop(&mut self) {
+            set_process_priority_config(self.0);
+        }
+    }
+
+    #[test]
+    fn thread_limit_goes_before_output_and_respects_existing_value() {
+        let _restore = RestoreConfig(get_process_priority_config());
+        set_process_priority_config(ProcessPriorityConfig {
+            low_priority: false,
+            ffmpeg_threads: Some(2),
+        });
+
+        let mut args: Vec<String> = ["-i", "in.mp4", "-c:v", "libx264", "out.mp4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        apply_ffmpeg_thread_limit(&mut args);
+        assert_eq!(
+            args,
+            ["-i", "in.mp4", "-c:v", "libx264", "-threads", "2", "out.mp4"]
+        );
+
+        let mut explicit: Vec<String> = ["-i", "in.mp4", "-threads", "8", "out.mp4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        apply_ffmpeg_thread_limit(&mut explicit);
+        assert_eq!(explicit, ["-i", "in.mp4", "-threads", "8", "out.mp4"]);
+
+        assert_eq!(
+            ytdlp_postprocessor_thread_args(),
+            ["--postprocessor-args", "ffmpeg:-threads 2"]
+        );
+
+        set_process_priority_config(ProcessPriorityConfig::default());
+        assert!(ffmpeg_thread_args().is_empty());
+    }
+}
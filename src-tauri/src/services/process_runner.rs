@@ -0,0 +1,225 @@
+use std::ffi::OsString;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+
+use tauri::AppHandle;
+use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::ShellExt;
+use tokio::process::Command;
+
+use crate::utils::{CommandExt, PYTHON_UTF8_ENV};
+
+/// Executable a [`ProcessRunner`] starts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProcessTarget {
+    /// A binary on disk, or a bare name looked up on PATH
+    Binary(PathBuf),
+    /// A sidecar shipped with the app, by its configured name
+    Sidecar(String),
+}
+
+/// One run of an external tool
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcessInvocation {
+    pub target: ProcessTarget,
+    pub args: Vec<String>,
+    /// Replaces the inherited environment when non-empty
+    pub env: Vec<(OsString, OsString)>,
+}
+
+impl ProcessInvocation {
+    pub fn binary(path: impl Into<PathBuf>, args: &[&str]) -> Self {
+        Self {
+            target: ProcessTarget::Binary(path.into()),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: Vec::new(),
+        }
+    }
+
+    /// Short name for error messages ("yt-dlp", "ffmpeg")
+    pub fn program_name(&self) -> String {
+        match &self.target {
+            ProcessTarget::Binary(path) => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string()),
+            ProcessTarget::Sidecar(name) => name.clone(),
+        }
+    }
+}
+
+/// Captured output of a finished process
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProcessOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub success: bool,
+}
+
+pub type ProcessFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<ProcessOutput, String>> + Send + 'a>>;
+
+/// Starts external tools and collects their output. Services take a runner
+/// so tests can replay recorded yt-dlp/ffmpeg output instead of spawning them.
+pub trait ProcessRunner: Send + Sync {
+    fn run<'a>(&'a self, invocation: &'a ProcessInvocation) -> ProcessFuture<'a>;
+}
+
+/// Runs binaries with tokio and sidecars through the shell plugin
+pub struct AppProcessRunner<'a> {
+    app: &'a AppHandle,
+}
+
+impl<'a> AppProcessRunner<'a> {
+    pub fn new(app: &'a AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+async fn run_binary(path: &Path, invocation: &ProcessInvocation) -> Result<ProcessOutput, String> {
+    let mut cmd = Command::new(path);
+    // Timed out or cancelled callers drop this future; don't leave the tool running
+    cmd.args(&invocation.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if !invocation.env.is_empty() {
+        cmd.process_env(&invocation.env);
+    }
+    cmd.hide_window().utf8_output();
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", invocation.program_name(), e))?;
+    Ok(ProcessOutput {
+        stdout: output.stdout,
+        stderr: output.stderr,
+        success: output.status.success(),
+    })
+}
+
+async fn run_sidecar(
+    app: &AppHandle,
+    name: &str,
+    invocation: &ProcessInvocation,
+) -> Result<ProcessOutput, String> {
+    let mut sidecar = app
+        .shell()
+        .sidecar(name)
+        .map_err(|e| format!("Failed to start {}: {}", name, e))?;
+    if !invocation.env.is_empty() {
+        sidecar = sidecar.env_clear().envs(invocation.env.iter().cloned());
+    }
+    let (mut rx, _child) = sidecar
+        .envs(PYTHON_UTF8_ENV)
+        .args(&invocation.args)
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", name, e))?;
+
+    let mut output = ProcessOutput {
+        success: true,
+        ..Default::default()
+    };
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(bytes) => output.stdout.extend_from_slice(&bytes),
+            CommandEvent::Stderr(bytes) => output.stderr.extend_from_slice(&bytes),
+            CommandEvent::Error(err) => return Err(format!("Process error: {}", err)),
+            CommandEvent::Terminated(status) => output.success = status.code == Some(0),
+            _ => {}
+        }
+    }
+    Ok(output)
+}
+
+impl ProcessRunner for AppProcessRunner<'_> {
+    fn run<'a>(&'a self, invocation: &'a ProcessInvocation) -> ProcessFuture<'a> {
+        Box::pin(async move {
+            match &invocation.target {
+                ProcessTarget::Binary(path) => run_binary(path, invocation).await,
+                ProcessTarget::Sidecar(name) => run_sidecar(self.app, name, invocation).await,
+            }
+        })
+    }
+}
+
+/// Replays recorded outputs in order and remembers what it was asked to run
+#[cfg(test)]
+pub struct FakeProcessRunner {
+    outputs: std::sync::Mutex<std::collections::VecDeque<Result<ProcessOutput, String>>>,
+    calls: std::sync::Mutex<Vec<ProcessInvocation>>,
+}
+
+#[cfg(test)]
+impl FakeProcessRunner {
+    pub fn replay(outputs: impl IntoIterator<Item = Result<ProcessOutput, String>>) -> Self {
+        Self {
+            outputs: std::sync::Mutex::new(outputs.into_iter().collect()),
+            calls: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn calls(&self) -> Vec<ProcessInvocation> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl ProcessOutput {
+    pub fn recorded(stdout: &str, stderr: &str, success: bool) -> Self {
+        Self {
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+            success,
+        }
+    }
+}
+
+#[cfg(test)]
+impl ProcessRunner for FakeProcessRunner {
+    fn run<'a>(&'a self, invocation: &'a ProcessInvocation) -> ProcessFuture<'a> {
+        self.calls.lock().unwrap().push(invocation.clone());
+        let output = self.outputs.lock().unwrap().pop_front().unwrap_or_else(|| {
+            Err(format!(
+                "No recorded output for {}",
+                invocation.program_name()
+            ))
+        });
+        Box::pin(async move { output })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fake_runner_replays_outputs_in_order() {
+        let runner = FakeProcessRunner::replay([
+            Ok(ProcessOutput::recorded("2025.01.01\n", "", true)),
+            Err("Failed to run ffmpeg: not found".to_string()),
+        ]);
+        let ytdlp = ProcessInvocation::binary("/bin/yt-dlp", &["--version"]);
+        let ffmpeg = ProcessInvocation::binary("/bin/ffmpeg", &["-version"]);
+
+        assert_eq!(runner.run(&ytdlp).await.unwrap().stdout, b"2025.01.01\n");
+        assert!(runner.run(&ffmpeg).await.is_err());
+        assert!(runner.run(&ffmpeg).await.is_err());
+        assert_eq!(runner.calls().len(), 3);
+        assert_eq!(runner.calls()[0].program_name(), "yt-dlp");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn app_runner_captures_binary_output() {
+        let invocation = ProcessInvocation::binary("sh", &["-c", "echo out; echo err >&2; exit 3"]);
+        let output = run_binary(Path::new("sh"), &invocation).await.unwrap();
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+        assert!(!output.success);
+    }
+}
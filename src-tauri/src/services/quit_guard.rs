@@ -0,0 +1,193 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+
+use super::power::{active_jobs, ActiveJob, ActiveJobKind};
+use super::termination::{app_child_pids, interrupt_process, kill_process_tree};
+use crate::database::{
+    clear_download_queue_from_db, load_download_queue_from_db, save_download_queue_to_db,
+};
+use crate::types::{QuitRequest, QUIT_REQUESTED};
+
+/// Queue kind under which downloads cut off by quitting are saved for resume
+pub const INTERRUPTED_QUEUE_KIND: &str = "interrupted";
+
+const GRACEFUL_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+static QUIT_CONFIRMED: AtomicBool = AtomicBool::new(false);
+
+/// Whether an exit request must wait for the user to confirm
+pub fn should_block_quit() -> bool {
+    !QUIT_CONFIRMED.load(Ordering::SeqCst) && !active_jobs().is_empty()
+}
+
+/// True once the user confirmed quitting; partial files must be kept from then on
+pub fn is_shutting_down() -> bool {
+    QUIT_CONFIRMED.load(Ordering::SeqCst)
+}
+
+/// Ask the frontend to confirm quitting while jobs are running
+pub fn emit_quit_requested(app: &AppHandle) {
+    let jobs = active_jobs();
+    let active_downloads = jobs
+        .iter()
+        .filter(|job| job.kind == ActiveJobKind::Download)
+        .count();
+    QUIT_REQUESTED
+        .emit(
+            app,
+            &QuitRequest {
+                active_downloads,
+                active_processing: jobs.len() - active_downloads,
+                jobs,
+            },
+        )
+        .ok();
+}
+
+/// Downloads saved by the last confirmed quit, for the frontend to resume
+pub fn load_interrupted_downloads() -> Vec<ActiveJob> {
+    load_download_queue_from_db(INTERRUPTED_QUEUE_KIND.to_string())
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Forget saved downloads once the frontend resumed or dismissed them
+/// (all when `ids` is `None`)
+pub fn clear_interrupted_downloads(ids: Option<&[String]>) -> Result<(), String> {
+    let remaining: Vec<ActiveJob> = match ids {
+        Some(ids) => load_interrupted_downloads()
+            .into_iter()
+            .filter(|job| !ids.contains(&job.id))
+            .collect(),
+        None => Vec::new(),
+    };
+    if remaining.is_empty() {
+        return clear_download_queue_from_db(INTERRUPTED_QUEUE_KIND.to_string());
+    }
+    let json = serde_json::to_string(&remaining)
+        .map_err(|e| format!("Failed to serialize interrupted downloads: {}", e))?;
+    save_download_queue_to_db(INTERRUPTED_QUEUE_KIND.to_string(), json)
+}
+
+fn persist_interrupted_downloads(jobs: &[ActiveJob]) {
+    let downloads: Vec<&ActiveJob> = jobs
+        .iter()
+        .filter(|job| job.kind == ActiveJobKind::Download)
+        .collect();
+    if downloads.is_empty() {
+        return;
+    }
+    match serde_json::to_string(&downloads) {
+        Ok(json) => {
+            if let Err(e) = save_download_queue_to_db(INTERRUPTED_QUEUE_KIND.to_string(), json) {
+                log::warn!("Failed to save interrupted downloads: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize interrupted downloads: {}", e),
+    }
+}
+
+/// Stop the app's own child processes; without `force` they get a chance to
+/// flush and keep `.part` files
+fn terminate_child_processes(force: bool) {
+    for pid in app_child_pids() {
+        if force {
+            kill_process_tree(pid);
+        } else if !interrupt_process(pid) {
+            log::warn!("Failed to interrupt child process {}", pid);
+        }
+    }
+}
+
+/// Quit after the user confirmed: save running downloads for resume, interrupt
+/// children, give them a few seconds to exit, then kill what's left.
+pub async fn shutdown_gracefully(app: AppHandle) {
+    QUIT_CONFIRMED.store(true, Ordering::SeqCst);
+    persist_interrupted_downloads(&active_jobs());
+
+    terminate_child_processes(false);
+    let started = Instant::now();
+    while !app_child_pids().is_empty() && started.elapsed() < GRACEFUL_EXIT_TIMEOUT {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    terminate_child_processes(true);
+
+    super::polling::stop_polling();
+    app.exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, get_db, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn reset_download_queues() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = rusqlite::Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+        let conn = get_db().expect("get db");
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS download_queues (
+                queue_kind TEXT PRIMARY KEY,
+                items_json TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .expect("create download queues");
+        conn.execute("DELETE FROM download_queues", []).unwrap();
+    }
+
+    #[test]
+    fn quitting_saves_only_running_downloads_for_resume() {
+        let _guard = db_test_guard();
+        reset_download_queues();
+
+        let download = ActiveJob::download("a", "https://example.com/a", "/tmp")
+            .with_options(Some(serde_json::json!({ "quality": "1080" })));
+        persist_interrupted_downloads(&[
+            download.clone(),
+            ActiveJob::processing("p", "/tmp/p.mp4"),
+        ]);
+
+        assert_eq!(load_interrupted_downloads(), vec![download]);
+    }
+
+    #[test]
+    fn quitting_without_downloads_keeps_the_saved_queue() {
+        let _guard = db_test_guard();
+        reset_download_queues();
+
+        let earlier = ActiveJob::download("a", "https://example.com/a", "/tmp");
+        persist_interrupted_downloads(std::slice::from_ref(&earlier));
+        persist_interrupted_downloads(&[ActiveJob::processing("p", "/tmp/p.mp4")]);
+
+        assert_eq!(load_interrupted_downloads(), vec![earlier]);
+    }
+
+    #[test]
+    fn restored_downloads_are_cleared_by_id() {
+        let _guard = db_test_guard();
+        reset_download_queues();
+
+        let first = ActiveJob::download("a", "https://example.com/a", "/tmp");
+        let second = ActiveJob::download("b", "https://example.com/b", "/tmp");
+        persist_interrupted_downloads(&[first, second.clone()]);
+
+        clear_interrupted_downloads(Some(&["a".to_string()])).unwrap();
+        assert_eq!(load_interrupted_downloads(), vec![second]);
+
+        clear_interrupted_downloads(Some(&["b".to_string()])).unwrap();
+        assert!(load_interrupted_downloads().is_empty());
+        assert_eq!(
+            load_download_queue_from_db(INTERRUPTED_QUEUE_KIND.to_string()).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use tauri::AppHandle;
+
+use crate::utils::{data_dir, portable_data_dir};
+
+/// Stored secrets, AES-256-GCM encrypted as one JSON map
+const SECRETS_FILE: &str = "secrets.enc";
+/// Master key when the OS keychain is unavailable, and always in portable mode
+const MASTER_KEY_FILE: &str = "secrets.key";
+const KEYRING_SERVICE: &str = "com.vanloctech.youwee";
+const KEYRING_USER: &str = "secrets-master-key";
+const FILE_MAGIC: &[u8] = b"YWS1";
+const NONCE_LEN: usize = 12;
+
+pub const AI_API_KEY_SECRET: &str = "ai.api_key";
+pub const WHISPER_API_KEY_SECRET: &str = "ai.whisper_api_key";
+/// AI server URL, only when it carries `user:pass@` credentials
+pub const AI_PROXY_URL_SECRET: &str = "ai.proxy_url";
+/// Network proxy, only when it carries `user:pass@` credentials
+pub const PROXY_URL_SECRET: &str = "network.proxy_url";
+
+// Serializes read-modify-write cycles of the secrets file
+static SECRETS_LOCK: Mutex<()> = Mutex::new(());
+
+type MasterKey = [u8; 32];
+
+fn seal(key: &MasterKey, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "Failed to encrypt secrets".to_string())?;
+    Ok([FILE_MAGIC, nonce.as_slice(), &ciphertext].concat())
+}
+
+fn open(key: &MasterKey, data: &[u8]) -> Result<Vec<u8>, String> {
+    let body = data
+        .strip_prefix(FILE_MAGIC)
+        .filter(|body| body.len() > NONCE_LEN)
+        .ok_or_else(|| "Secrets file is not recognized".to_string())?;
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Stored secrets cannot be decrypted with this device's key".to_string())
+}
+
+fn parse_key(encoded: &str) -> Option<MasterKey> {
+    hex::decode(encoded.trim()).ok()?.try_into().ok()
+}
+
+fn new_key() -> MasterKey {
+    Aes256Gcm::generate_key(&mut OsRng).into()
+}
+
+fn key_from_file(dir: &Path, create: bool) -> Result<Option<MasterKey>, String> {
+    let path = dir.join(MASTER_KEY_FILE);
+    if let Ok(encoded) = std::fs::read_to_string(&path) {
+        return parse_key(&encoded)
+            .map(Some)
+            .ok_or_else(|| "Secrets key file is corrupted".to_string());
+    }
+    if !create {
+        return Ok(None);
+    }
+
+    let key = new_key();
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to save secrets key: {}", e))?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to save secrets key: {}", e))?;
+    std::io::Write::write_all(&mut file, hex::encode(key).as_bytes())
+        .map_err(|e| format!("Failed to save secrets key: {}", e))?;
+    Ok(Some(key))
+}
+
+/// Master key from the OS keychain, falling back to a key file in `dir`.
+/// A new key is generated only when `create` is set.
+fn master_key(dir: &Path, create: bool) -> Result<Option<MasterKey>, String> {
+    // Portable installs keep the key on the drive so they work on any machine
+    if portable_data_dir().is_none() {
+        match keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER) {
+            Ok(entry) => match entry.get_password() {
+                Ok(stored) => {
+                    if let Some(key) = parse_key(&stored) {
+                        return Ok(Some(key));
+                    }
+                    log::warn!("Ignoring malformed secrets key in the OS keychain");
+                }
+                Err(keyring::Error::NoEntry) => {
+                    // A key file from an earlier keychain outage stays authoritative
+                    if let Some(key) = key_from_file(dir, false)? {
+                        return Ok(Some(key));
+                    }
+                    if !create {
+                        return Ok(None);
+                    }
+                    let key = new_key();
+                    match entry.set_password(&hex::encode(key)) {
+                        Ok(()) => return Ok(Some(key)),
+                        Err(e) => log::warn!("OS keychain rejected the secrets key: {}", e),
+                    }
+                }
+                Err(e) => log::warn!("OS keychain unavailable, using key file: {}", e),
+            },
+            Err(e) => log::warn!("OS keychain unavailable, using key file: {}", e),
+        }
+    }
+    key_from_file(dir, create)
+}
+
+fn read_secrets(dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    let Ok(data) = std::fs::read(dir.join(SECRETS_FILE)) else {
+        return Ok(BTreeMap::new());
+    };
+    let key = master_key(dir, false)?
+        .ok_or_else(|| "The key for stored secrets is missing".to_string())?;
+    serde_json::from_slice(&open(&key, &data)?)
+        .map_err(|e| format!("Failed to parse stored secrets: {}", e))
+}
+
+fn write_secrets(dir: &Path, secrets: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = dir.join(SECRETS_FILE);
+    if secrets.is_empty() {
+        std::fs::remove_file(&path).ok();
+        return Ok(());
+    }
+    let key = master_key(dir, true)?
+        .ok_or_else(|| "Failed to create a key for stored secrets".to_string())?;
+    let plaintext =
+        serde_json::to_vec(secrets).map_err(|e| format!("Failed to save secrets: {}", e))?;
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, seal(&key, &plaintext)?)
+        .map_err(|e| format!("Failed to save secrets: {}", e))?;
+    std::fs::rename(&temp_path, &path).map_err(|e| format!("Failed to save secrets: {}", e))
+}
+
+fn validate_secret_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid secret name: {}", name))
+    }
+}
+
+fn secrets_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    data_dir(app).map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+pub fn get_secret(app: &AppHandle, name: &str) -> Result<Option<String>, String> {
+    validate_secret_name(name)?;
+    let _guard = SECRETS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(read_secrets(&secrets_dir(app)?)?.remove(name))
+}
+
+/// Store a secret; `None` or an empty value removes it
+pub fn set_secret(app: &AppHandle, name: &str, value: Option<&str>) -> Result<(), String> {
+    validate_secret_name(name)?;
+    let dir = secrets_dir(app)?;
+    let _guard = SECRETS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut secrets = read_secrets(&dir)?;
+    let changed = match value.filter(|value| !value.is_empty()) {
+        Some(value) => {
+            secrets
+                .insert(name.to_string(), value.to_string())
+                .as_deref()
+                != Some(value)
+        }
+        None => secrets.remove(name).is_some(),
+    };
+    if changed {
+        write_secrets(&dir, &secrets)?;
+    }
+    Ok(())
+}
+
+/// Secret holding `field` (`url`, `bot_token`) of the notification integration `id`
+pub fn webhook_secret_name(id: &str, field: &str) -> String {
+    format!("webhook.{}.{}", id, field)
+}
+
+/// Secret holding the credentialed proxy URL of the download profile `id`
+pub fn profile_proxy_secret_name(id: &str) -> String {
+    format!("profile.{}.proxy_url", id)
+}
+
+/// `url` without its `user:pass@` part, or None when it has none
+pub fn url_without_credentials(url: &str) -> Option<String> {
+    let scheme_end = url.find("://")? + 3;
+    let authority_end = url[scheme_end..]
+        .find('/')
+        .map_or(url.len(), |index| scheme_end + index);
+    let at = url[scheme_end..authority_end].rfind('@')?;
+    Some(format!(
+        "{}{}",
+        &url[..scheme_end],
+        &url[scheme_end + at + 1..]
+    ))
+}
+
+/// Keep a URL with credentials under `name` and return the URL to save in
+/// plain settings, with the credentials removed
+pub fn store_url_credentials(
+    app: &AppHandle,
+    name: &str,
+    url: Option<&str>,
+) -> Result<Option<String>, String> {
+    let Some(url) = url.map(str::trim).filter(|url| !url.is_empty()) else {
+        set_secret(app, name, None)?;
+        return Ok(None);
+    };
+    match url_without_credentials(url) {
+        Some(stripped) => {
+            set_secret(app, name, Some(url))?;
+            Ok(Some(stripped))
+        }
+        None => Ok(Some(url.to_string())),
+    }
+}
+
+/// `url` with the credentials stored under `name` put back, when they were
+/// saved for this same URL. Stale credentials for another URL are dropped.
+pub fn restore_url_credentials(
+    app: &AppHandle,
+    name: &str,
+    url: Option<&str>,
+) -> Result<Option<String>, String> {
+    let Some(url) = url.map(str::trim).filter(|url| !url.is_empty()) else {
+        return Ok(None);
+    };
+    if url_without_credentials(url).is_some() {
+        return Ok(Some(url.to_string()));
+    }
+    match get_secret(app, name)? {
+        Some(stored) if url_without_credentials(&stored).as_deref() == Some(url) => {
+            Ok(Some(stored))
+        }
+        Some(_) => {
+            set_secret(app, name, None)?;
+            Ok(Some(url.to_string()))
+        }
+        None => Ok(Some(url.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_secrets_round_trip_and_reject_tampering() {
+        let key = new_key();
+        let sealed = seal(&key, b"{\"ai.api_key\":\"sk-123\"}").unwrap();
+        assert!(sealed.starts_with(FILE_MAGIC));
+        assert_eq!(open(&key, &sealed).unwrap(), b"{\"ai.api_key\":\"sk-123\"}");
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(open(&key, &tampered).is_err());
+        assert!(open(&new_key(), &sealed).is_err());
+        assert!(open(&key, b"plain json").is_err());
+
+        assert!(validate_secret_name("proxy.password").is_ok());
+        assert!(validate_secret_name("../escape").is_err());
+    }
+
+    #[test]
+    fn url_credentials_are_split_from_the_authority_only() {
+        assert_eq!(
+            url_without_credentials("http://user:p@ss@proxy.local:8080/path@x").as_deref(),
+            Some("http://proxy.local:8080/path@x")
+        );
+        assert_eq!(url_without_credentials("socks5://proxy.local:1080"), None);
+        assert_eq!(url_without_credentials("proxy.local"), None);
+        assert!(validate_secret_name(&webhook_secret_name("3f2a-01", "bot_token")).is_ok());
+    }
+}
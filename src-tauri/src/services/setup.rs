@@ -0,0 +1,104 @@
+use std::sync::Mutex;
+
+use tauri::AppHandle;
+
+use crate::types::{BinaryDownloadProgress, SetupProgress, SETUP_PROGRESS};
+use crate::utils::data_dir;
+
+/// Written to the app data folder once onboarding has finished
+const SETUP_COMPLETE_FILE: &str = "setup_complete";
+
+struct SetupStep {
+    name: String,
+    index: u32,
+    count: u32,
+}
+
+// Step of `run_setup` currently installing, if any
+static ACTIVE_STEP: Mutex<Option<SetupStep>> = Mutex::new(None);
+
+/// Overall percent when `index` of `count` steps is `step_percent` done
+fn combined_percent(index: u32, count: u32, step_percent: u8) -> u8 {
+    if count == 0 {
+        return 100;
+    }
+    let done = index.min(count) * 100 + u32::from(step_percent.min(100));
+    (done / count).min(100) as u8
+}
+
+fn emit_step(app: &AppHandle, step: &SetupStep, stage: &str, step_percent: u8) {
+    SETUP_PROGRESS
+        .emit(
+            app,
+            &SetupProgress {
+                step: step.name.clone(),
+                stage: stage.to_string(),
+                step_index: step.index,
+                step_count: step.count,
+                percent: combined_percent(step.index, step.count, step_percent),
+            },
+        )
+        .ok();
+}
+
+/// Mark `name` as the running setup step and announce it
+pub fn begin_setup_step(app: &AppHandle, name: &str, index: u32, count: u32) {
+    let step = SetupStep {
+        name: name.to_string(),
+        index,
+        count,
+    };
+    emit_step(app, &step, "starting", 0);
+    if let Ok(mut guard) = ACTIVE_STEP.lock() {
+        *guard = Some(step);
+    }
+}
+
+/// Close the running setup step with its final stage ("done" or "failed")
+pub fn finish_setup_step(app: &AppHandle, stage: &str) {
+    let step = ACTIVE_STEP.lock().ok().and_then(|mut guard| guard.take());
+    if let Some(step) = step {
+        emit_step(app, &step, stage, 100);
+    }
+}
+
+/// Mirror a dependency install's progress into the setup progress, when the
+/// install was started by `run_setup`
+pub fn forward_setup_progress(app: &AppHandle, progress: &BinaryDownloadProgress) {
+    if let Ok(guard) = ACTIVE_STEP.lock() {
+        if let Some(step) = guard.as_ref() {
+            emit_step(app, step, &progress.stage, progress.percent);
+        }
+    }
+}
+
+pub fn is_setup_completed(app: &AppHandle) -> bool {
+    data_dir(app)
+        .map(|dir| dir.join(SETUP_COMPLETE_FILE).exists())
+        .unwrap_or(false)
+}
+
+pub fn mark_setup_completed(app: &AppHandle) -> Result<(), String> {
+    let dir = data_dir(app).map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to save setup state: {}", e))?;
+    std::fs::write(
+        dir.join(SETUP_COMPLETE_FILE),
+        chrono::Utc::now().to_rfc3339(),
+    )
+    .map_err(|e| format!("Failed to save setup state: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combined_percent_spreads_steps_evenly() {
+        assert_eq!(combined_percent(0, 3, 0), 0);
+        assert_eq!(combined_percent(0, 3, 60), 20);
+        assert_eq!(combined_percent(1, 3, 50), 50);
+        assert_eq!(combined_percent(2, 3, 100), 100);
+        assert_eq!(combined_percent(5, 3, 100), 100);
+        assert_eq!(combined_percent(0, 0, 0), 100);
+    }
+}
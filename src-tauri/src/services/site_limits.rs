@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// How many downloads may run at once against one site, and how far apart
+/// they must start, so batches don't trip the site's rate limiting
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteConcurrencyLimit {
+    /// History source id, e.g. "youtube"
+    pub source: String,
+    /// 0 leaves the number of parallel downloads unlimited
+    pub max_concurrent: u32,
+    pub min_interval_secs: u64,
+}
+
+pub fn default_site_concurrency_limits() -> Vec<SiteConcurrencyLimit> {
+    vec![SiteConcurrencyLimit {
+        source: "youtube".to_string(),
+        max_concurrent: 2,
+        min_interval_secs: 5,
+    }]
+}
+
+#[derive(Default)]
+struct SiteState {
+    active: u32,
+    last_start: Option<Instant>,
+}
+
+/// Running downloads and last start time per site
+#[derive(Default)]
+struct SiteThrottle {
+    sites: HashMap<String, SiteState>,
+}
+
+impl SiteThrottle {
+    /// Take a slot for `limit.source`, or say how long to wait before trying again
+    fn try_acquire(&mut self, limit: &SiteConcurrencyLimit, now: Instant) -> Result<(), Duration> {
+        let state = self.sites.entry(limit.source.clone()).or_default();
+        let spacing = Duration::from_secs(limit.min_interval_secs);
+        if let Some(wait) = state
+            .last_start
+            .map(|last| spacing.saturating_sub(now.saturating_duration_since(last)))
+            .filter(|wait| !wait.is_zero())
+        {
+            return Err(wait);
+        }
+        if limit.max_concurrent > 0 && state.active >= limit.max_concurrent {
+            return Err(SLOT_POLL_INTERVAL);
+        }
+        state.active += 1;
+        state.last_start = Some(now);
+        Ok(())
+    }
+
+    fn release(&mut self, source: &str) {
+        if let Some(state) = self.sites.get_mut(source) {
+            state.active = state.active.saturating_sub(1);
+        }
+    }
+}
+
+const SLOT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static SITE_LIMITS: LazyLock<Mutex<Vec<SiteConcurrencyLimit>>> =
+    LazyLock::new(|| Mutex::new(default_site_concurrency_limits()));
+static SITE_THROTTLE: LazyLock<Mutex<SiteThrottle>> =
+    LazyLock::new(|| Mutex::new(SiteThrottle::default()));
+
+pub fn set_site_concurrency_limits(limits: Vec<SiteConcurrencyLimit>) {
+    if let Ok(mut guard) = SITE_LIMITS.lock() {
+        *guard = limits
+            .into_iter()
+            .filter(|limit| !limit.source.trim().is_empty())
+            .map(|limit| SiteConcurrencyLimit {
+                source: limit.source.trim().to_lowercase(),
+                ..limit
+            })
+            .collect();
+    }
+}
+
+pub fn get_site_concurrency_limits() -> Vec<SiteConcurrencyLimit> {
+    SITE_LIMITS
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+/// A running download's claim on its site; released when dropped
+pub struct SiteSlot {
+    source: Option<String>,
+}
+
+impl Drop for SiteSlot {
+    fn drop(&mut self) {
+        if let Some(source) = self.source.take() {
+            if let Ok(mut throttle) = SITE_THROTTLE.lock() {
+                throttle.release(&source);
+            }
+        }
+    }
+}
+
+/// Wait until `source` has a free slot under its configured limit. Calls
+/// `on_wait` once when the download has to wait; returns None if
+/// `is_cancelled` turns true first. Sites without a limit never wait.
+pub async fn acquire_site_slot(
+    source: Option<&str>,
+    is_cancelled: impl Fn() -> bool,
+    mut on_wait: impl FnMut(),
+) -> Option<SiteSlot> {
+    let limit = source.and_then(|source| {
+        get_site_concurrency_limits()
+            .into_iter()
+            .find(|limit| limit.source == source)
+    });
+    let Some(limit) = limit else {
+        return Some(SiteSlot { source: None });
+    };
+
+    let mut waited = false;
+    loop {
+        if is_cancelled() {
+            return None;
+        }
+        let attempt = SITE_THROTTLE
+            .lock()
+            .map(|mut throttle| throttle.try_acquire(&limit, Instant::now()))
+            .unwrap_or(Ok(()));
+        match attempt {
+            Ok(()) => {
+                return Some(SiteSlot {
+                    source: Some(limit.source),
+                })
+            }
+            Err(wait) => {
+                if !waited {
+                    waited = true;
+                    on_wait();
+                }
+                tokio::time::sleep(wait.min(SLOT_POLL_INTERVAL)).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_caps_parallel_jobs_and_spaces_starts() {
+        let limit = SiteConcurrencyLimit {
+            source: "youtube".to_string(),
+            max_concurrent: 2,
+            min_interval_secs: 5,
+        };
+        let mut throttle = SiteThrottle::default();
+        let start = Instant::now();
+
+        assert!(throttle.try_acquire(&limit, start).is_ok());
+        assert_eq!(
+            throttle.try_acquire(&limit, start + Duration::from_secs(2)),
+            Err(Duration::from_secs(3))
+        );
+        assert!(throttle
+            .try_acquire(&limit, start + Duration::from_secs(5))
+            .is_ok());
+        assert_eq!(
+            throttle.try_acquire(&limit, start + Duration::from_secs(20)),
+            Err(SLOT_POLL_INTERVAL)
+        );
+
+        throttle.release("youtube");
+        assert!(throttle
+            .try_acquire(&limit, start + Duration::from_secs(20))
+            .is_ok());
+    }
+}
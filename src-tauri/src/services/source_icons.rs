@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::utils::{data_dir, source_info, SourceInfo};
+
+const ICON_TIMEOUT: Duration = Duration::from_secs(10);
+/// Larger responses are error pages, not favicons
+const MAX_ICON_BYTES: usize = 512 * 1024;
+
+fn cached_icon_path(app: &AppHandle, id: &str) -> Option<PathBuf> {
+    let dir = data_dir(app).ok()?.join("favicons");
+    Some(dir.join(format!("{}.ico", id)))
+}
+
+async fn fetch_favicon(domain: &str) -> Result<Vec<u8>, String> {
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (compatible; Youwee/0.6.0)")
+        .timeout(ICON_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(format!("https://{}/favicon.ico", domain))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to fetch icon for {}: {}", domain, e))?;
+    let is_image = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_none_or(|value| value.starts_with("image/"));
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to fetch icon for {}: {}", domain, e))?;
+    if !is_image || bytes.is_empty() || bytes.len() > MAX_ICON_BYTES {
+        return Err(format!("{} did not return a favicon", domain));
+    }
+    Ok(bytes.to_vec())
+}
+
+/// Display name of a history source plus its favicon, downloaded once into
+/// the app data folder and served from there afterwards
+pub async fn source_info_with_icon(app: &AppHandle, id: &str) -> SourceInfo {
+    let mut info = source_info(id);
+    let (Some(domain), Some(path)) = (info.domain.clone(), cached_icon_path(app, &info.id)) else {
+        return info;
+    };
+    if !path.exists() {
+        let Ok(bytes) = fetch_favicon(&domain).await else {
+            return info;
+        };
+        let saved = path
+            .parent()
+            .is_some_and(|dir| std::fs::create_dir_all(dir).is_ok())
+            && std::fs::write(&path, bytes).is_ok();
+        if !saved {
+            return info;
+        }
+    }
+    info.icon_path = Some(path.to_string_lossy().to_string());
+    info
+}
@@ -0,0 +1,70 @@
+use std::sync::Mutex;
+
+/// Subtitle languages the user reads (usually the UI languages), in order
+static PREFERRED_SUBTITLE_LANGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+const FALLBACK_SUBTITLE_LANG: &str = "en";
+/// YouTube names the auto-captions of the spoken language `<lang>-orig`
+const ORIGINAL_TRACK_PATTERN: &str = ".*-orig";
+
+pub fn set_preferred_subtitle_langs(langs: Vec<String>) {
+    *PREFERRED_SUBTITLE_LANGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = langs;
+}
+
+pub fn preferred_subtitle_langs() -> Vec<String> {
+    PREFERRED_SUBTITLE_LANGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// `pt-BR` / `pt_BR` -> `pt`
+fn primary_language(lang: &str) -> Option<String> {
+    let primary = lang.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    (primary.len() >= 2 && primary.chars().all(|c| c.is_ascii_alphabetic())).then_some(primary)
+}
+
+/// `--sub-langs` value for the smart subtitle mode: the video's original
+/// language, then the preferred languages, then English. Each entry also
+/// matches regional and original-track variants (`en-US`, `en-orig`).
+/// Without a known language the original track is matched by its `-orig` name.
+pub fn smart_subtitle_langs(video_language: Option<&str>, preferred: &[String]) -> String {
+    let mut langs: Vec<String> = Vec::new();
+    let candidates = video_language
+        .into_iter()
+        .chain(preferred.iter().map(String::as_str))
+        .chain([FALLBACK_SUBTITLE_LANG]);
+    for lang in candidates.filter_map(primary_language) {
+        if !langs.contains(&lang) {
+            langs.push(lang);
+        }
+    }
+    let original = video_language
+        .is_none()
+        .then(|| ORIGINAL_TRACK_PATTERN.to_string());
+    original
+        .into_iter()
+        .chain(langs.iter().map(|lang| format!("{}(-.*)?", lang)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smart_langs_put_original_first_and_fall_back_to_english() {
+        assert_eq!(
+            smart_subtitle_langs(Some("ja"), &["vi".to_string(), "en-US".to_string()]),
+            "ja(-.*)?,vi(-.*)?,en(-.*)?"
+        );
+        assert_eq!(
+            smart_subtitle_langs(Some("pt-BR"), &["PT_pt".to_string(), "".to_string()]),
+            "pt(-.*)?,en(-.*)?"
+        );
+        assert_eq!(smart_subtitle_langs(None, &[]), ".*-orig,en(-.*)?");
+    }
+}
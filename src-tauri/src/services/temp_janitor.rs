@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use super::direct_download::is_direct_partial_file;
+use super::download_temp::cleanup_orphaned_download_temp;
+use super::quit_guard::load_interrupted_downloads;
+use crate::database::{load_download_journal, recent_history_filepaths};
+
+/// Scratch entries Youwee creates in the system temp folder
+const TEMP_PREFIXES: [&str; 5] = [
+    "youwee_subs_",
+    "youwee_whisper_",
+    "youwee_estimate",
+    "youwee-chapters-",
+    "youwee-fp-",
+];
+const TEMP_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+// Partial downloads stay resumable for a while after a crash
+const PARTIAL_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const RECENT_DOWNLOADS_SCANNED: usize = 200;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct JanitorReport {
+    pub temp_entries: u32,
+    pub partial_files: u32,
+    pub download_temp_dirs: u32,
+    pub bytes_freed: u64,
+}
+
+fn is_stale(path: &Path, max_age: Duration) -> bool {
+    std::fs::symlink_metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age >= max_age)
+}
+
+fn entry_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| entry_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+fn remove_entry(path: &Path) -> Option<u64> {
+    let size = entry_size(path);
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Ok(()) => Some(size),
+        Err(e) => {
+            log::warn!("Failed to remove stale temp {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Remove stale Youwee scratch entries directly inside `temp_dir`
+fn sweep_temp_dir(temp_dir: &Path, max_age: Duration, report: &mut JanitorReport) {
+    let Ok(entries) = std::fs::read_dir(temp_dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let matches_prefix = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .is_some_and(|name| TEMP_PREFIXES.iter().any(|prefix| name.starts_with(prefix)));
+        if matches_prefix && is_stale(&path, max_age) {
+            if let Some(size) = remove_entry(&path) {
+                report.temp_entries += 1;
+                report.bytes_freed += size;
+            }
+        }
+    }
+}
+
+/// Remove stale direct-download `.part` files directly inside a download folder.
+/// yt-dlp's own `.part`/`.ytdl` files there can't be told apart from another
+/// tool's, so only `download_temp` folders are swept for those.
+fn sweep_download_dir(dir: &Path, max_age: Duration, report: &mut JanitorReport) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let is_partial = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .is_some_and(|name| is_direct_partial_file(&name));
+        if is_partial && path.is_file() && is_stale(&path, max_age) {
+            if let Some(size) = remove_entry(&path) {
+                report.partial_files += 1;
+                report.bytes_freed += size;
+            }
+        }
+    }
+}
+
+/// Folders recent downloads went to, minus those holding downloads saved for
+/// resume or still in the download journal
+fn download_dirs_to_scan() -> Vec<PathBuf> {
+    let resumable: HashSet<PathBuf> = load_interrupted_downloads()
+        .into_iter()
+        .filter_map(|job| job.output_path.map(PathBuf::from))
+        .chain(
+            load_download_journal(false)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| PathBuf::from(entry.output_path)),
+        )
+        .collect();
+    let mut dirs: Vec<PathBuf> = recent_history_filepaths(RECENT_DOWNLOADS_SCANNED)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|filepath| Path::new(filepath).parent().map(Path::to_path_buf))
+        .filter(|dir| !dir.as_os_str().is_empty() && !resumable.contains(dir))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Startup sweep of scratch files left behind by crashes or forced quits
+pub fn run_temp_janitor(app: &AppHandle) -> JanitorReport {
+    let mut report = JanitorReport::default();
+    sweep_temp_dir(&std::env::temp_dir(), TEMP_MAX_AGE, &mut report);
+    for dir in download_dirs_to_scan() {
+        sweep_download_dir(&dir, PARTIAL_MAX_AGE, &mut report);
+    }
+    report.download_temp_dirs = cleanup_orphaned_download_temp(app);
+
+    if report != JanitorReport::default() {
+        log::info!(
+            "Temp cleanup: removed {} temp item(s), {} partial download file(s), {} download temp folder(s), freed {} bytes",
+            report.temp_entries,
+            report.partial_files,
+            report.download_temp_dirs,
+            report.bytes_freed
+        );
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweeps_only_youwee_entries_and_partial_files() {
+        let root = std::env::temp_dir().join(format!("youwee-janitor-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("youwee_subs_1")).unwrap();
+        std::fs::write(root.join("youwee_subs_1").join("a.vtt"), b"subs").unwrap();
+        std::fs::write(root.join("other.tmp"), b"keep").unwrap();
+        std::fs::write(root.join(".youwee-0123456789abcdef.part"), b"part").unwrap();
+        std::fs::write(root.join("video.mp4.part"), b"other").unwrap();
+        std::fs::write(root.join("video.mp4.ytdl"), b"state").unwrap();
+        std::fs::write(root.join("video.mp4"), b"final").unwrap();
+
+        let mut report = JanitorReport::default();
+        sweep_temp_dir(&root, Duration::ZERO, &mut report);
+        sweep_download_dir(&root, Duration::ZERO, &mut report);
+        assert_eq!(report.temp_entries, 1);
+        assert_eq!(report.partial_files, 1);
+        assert_eq!(report.bytes_freed, 8);
+        assert!(root.join("other.tmp").exists());
+        assert!(root.join("video.mp4.part").exists());
+        assert!(root.join("video.mp4.ytdl").exists());
+        assert!(root.join("video.mp4").exists());
+
+        let mut report = JanitorReport::default();
+        std::fs::write(root.join(".youwee-fresh.part"), b"part").unwrap();
+        sweep_download_dir(&root, Duration::from_secs(3600), &mut report);
+        assert_eq!(report.partial_files, 0);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
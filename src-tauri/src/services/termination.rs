@@ -0,0 +1,526 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long yt-dlp gets to handle the interrupt and remove its temp files
+pub const GRACEFUL_CANCEL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A running download and everything it spawned
+#[derive(Default)]
+struct TrackedProcess {
+    /// Ignored the interrupt and had to be killed
+    escalated: bool,
+    /// Job holding the process tree; closing it kills what is left
+    #[cfg(windows)]
+    job: Option<JobObject>,
+}
+
+/// Running download processes by pid
+static DOWNLOAD_PROCESSES: LazyLock<Mutex<HashMap<u32, TrackedProcess>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Windows job object the download and its ffmpeg children run in.
+/// Processes created by a process in a job join it automatically.
+#[cfg(windows)]
+struct JobObject(windows_sys::Win32::Foundation::HANDLE);
+
+// The handle is only used through thread-safe Win32 calls
+#[cfg(windows)]
+unsafe impl Send for JobObject {}
+
+#[cfg(windows)]
+impl JobObject {
+    fn for_process(pid: u32) -> Option<Self> {
+        use windows_sys::Win32::Foundation::CloseHandle;
+        use windows_sys::Win32::System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        };
+        use windows_sys::Win32::System::Threading::{
+            OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE,
+        };
+
+        unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                return None;
+            }
+            let job = JobObject(handle);
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            SetInformationJobObject(
+                job.0,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+            if process.is_null() {
+                return None;
+            }
+            let assigned = AssignProcessToJobObject(job.0, process) != 0;
+            CloseHandle(process);
+            assigned.then_some(job)
+        }
+    }
+
+    fn terminate(&self) -> bool {
+        use windows_sys::Win32::System::JobObjects::TerminateJobObject;
+        unsafe { TerminateJobObject(self.0, 1) != 0 }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for JobObject {
+    fn drop(&mut self) {
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
+/// Keeps a download process registered for cancellation until dropped
+pub struct DownloadProcessGuard {
+    pid: Option<u32>,
+}
+
+impl DownloadProcessGuard {
+    /// True when the process ignored the interrupt and was killed, so its
+    /// partial files were left behind
+    pub fn escalated(&self) -> bool {
+        self.pid.is_some_and(|pid| {
+            DOWNLOAD_PROCESSES
+                .lock()
+                .map(|processes| processes.get(&pid).is_some_and(|process| process.escalated))
+                .unwrap_or(false)
+        })
+    }
+}
+
+impl Drop for DownloadProcessGuard {
+    fn drop(&mut self) {
+        if let (Some(pid), Ok(mut processes)) = (self.pid, DOWNLOAD_PROCESSES.lock()) {
+            processes.remove(&pid);
+        }
+    }
+}
+
+/// Register a running yt-dlp download so a cancel interrupts it first and
+/// only ever kills its own process tree. On Windows the process is put in a
+/// job object, which must happen before it starts ffmpeg.
+pub fn track_download_process(pid: Option<u32>) -> DownloadProcessGuard {
+    if let Some(pid) = pid {
+        let process = TrackedProcess {
+            #[cfg(windows)]
+            job: JobObject::for_process(pid),
+            ..Default::default()
+        };
+        if let Ok(mut processes) = DOWNLOAD_PROCESSES.lock() {
+            processes.insert(pid, process);
+        }
+    }
+    DownloadProcessGuard { pid }
+}
+
+/// Other children the app started (gallery-dl, processing FFmpeg); only
+/// these are stopped on quit, never same-named processes of other programs
+static CHILD_PROCESSES: LazyLock<Mutex<HashSet<u32>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Keeps a child process registered until dropped
+pub struct ChildProcessGuard {
+    pid: Option<u32>,
+}
+
+impl Drop for ChildProcessGuard {
+    fn drop(&mut self) {
+        if let (Some(pid), Ok(mut processes)) = (self.pid, CHILD_PROCESSES.lock()) {
+            processes.remove(&pid);
+        }
+    }
+}
+
+/// Register a running child process so quitting can stop it by pid
+pub fn track_child_process(pid: Option<u32>) -> ChildProcessGuard {
+    if let (Some(pid), Ok(mut processes)) = (pid, CHILD_PROCESSES.lock()) {
+        processes.insert(pid);
+    }
+    ChildProcessGuard { pid }
+}
+
+/// Pids of every tracked download and child process
+pub fn app_child_pids() -> Vec<u32> {
+    let mut pids = tracked_pids();
+    if let Ok(processes) = CHILD_PROCESSES.lock() {
+        pids.extend(processes.iter().copied());
+    }
+    pids
+}
+
+fn tracked_pids() -> Vec<u32> {
+    DOWNLOAD_PROCESSES
+        .lock()
+        .map(|processes| processes.keys().copied().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn send_signal(signal: &str, target: &str) -> bool {
+    std::process::Command::new("kill")
+        .args([signal, "--", target])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Send SIGINT to the process group `pid` leads (the process alone when it
+/// isn't a group leader), like pressing Ctrl+C in a terminal
+#[cfg(unix)]
+pub fn interrupt_process(pid: u32) -> bool {
+    send_signal("-INT", &format!("-{}", pid)) || send_signal("-INT", &pid.to_string())
+}
+
+/// Whether a process with `pid` is still running
+#[cfg(unix)]
+pub fn process_exists(pid: u32) -> bool {
+    send_signal("-0", &pid.to_string())
+}
+
+/// Every process below `pid`, parents before their children
+#[cfg(unix)]
+fn descendant_pids(pid: u32) -> Vec<u32> {
+    let mut found = Vec::new();
+    let mut pending = vec![pid];
+    while let Some(parent) = pending.pop() {
+        let Ok(output) = std::process::Command::new("pgrep")
+            .args(["-P", &parent.to_string()])
+            .output()
+        else {
+            break;
+        };
+        let children: Vec<u32> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect();
+        found.extend(&children);
+        pending.extend(children);
+    }
+    found
+}
+
+/// SIGKILL `pid` and its descendants: the whole process group when `pid`
+/// leads one, otherwise the processes found below it (collected first, as
+/// they are re-parented once `pid` dies)
+#[cfg(unix)]
+pub fn kill_process_tree(pid: u32) {
+    if send_signal("-KILL", &format!("-{}", pid)) {
+        return;
+    }
+    let descendants = descendant_pids(pid);
+    for target in std::iter::once(pid).chain(descendants) {
+        send_signal("-KILL", &target.to_string());
+    }
+}
+
+/// Terminate `pid` and its descendants through its job object, or with
+/// `taskkill /T` when it isn't tracked in one
+#[cfg(windows)]
+pub fn kill_process_tree(pid: u32) {
+    use crate::utils::CommandExt;
+
+    let terminated = DOWNLOAD_PROCESSES
+        .lock()
+        .ok()
+        .and_then(|processes| {
+            let job = processes.get(&pid)?.job.as_ref()?;
+            Some(job.terminate())
+        })
+        .unwrap_or(false);
+    if !terminated {
+        let mut cmd = std::process::Command::new("taskkill");
+        cmd.args(["/F", "/T", "/PID", &pid.to_string()]);
+        cmd.hide_window();
+        cmd.status().ok();
+    }
+}
+
+/// Deliver Ctrl+C through the process's (hidden) console. Python turns it
+/// into KeyboardInterrupt; Ctrl+Break would end it without any cleanup.
+#[cfg(windows)]
+pub fn interrupt_process(pid: u32) -> bool {
+    use windows_sys::Win32::System::Console::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, SetConsoleCtrlHandler, CTRL_C_EVENT,
+    };
+
+    // A process can only be attached to one console at a time
+    static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+    let _guard = CONSOLE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    /// Lets the app handle Ctrl+C again and leaves the borrowed console.
+    /// Children spawned later inherit the ignore flag, so it must not stick.
+    struct IgnoredCtrlC;
+
+    impl Drop for IgnoredCtrlC {
+        fn drop(&mut self) {
+            unsafe {
+                SetConsoleCtrlHandler(None, 0);
+                FreeConsole();
+            }
+        }
+    }
+
+    unsafe {
+        FreeConsole();
+        if AttachConsole(pid) == 0 {
+            return false;
+        }
+        // Don't let the event we send to the shared console end the app itself
+        SetConsoleCtrlHandler(None, 1);
+        let _restore = IgnoredCtrlC;
+        GenerateConsoleCtrlEvent(CTRL_C_EVENT, 0) != 0
+    }
+}
+
+/// Whether a process with `pid` is still running
+#[cfg(windows)]
+pub fn process_exists(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return false;
+        }
+        let mut exit_code = 0u32;
+        let running =
+            GetExitCodeProcess(process, &mut exit_code) != 0 && exit_code == STILL_ACTIVE as u32;
+        CloseHandle(process);
+        running
+    }
+}
+
+/// Wait for `child` to exit on its own, killing its process tree after
+/// `timeout`. Returns false when it had to be killed.
+pub async fn wait_or_kill(child: &mut tokio::process::Child, timeout: Duration) -> bool {
+    if tokio::time::timeout(timeout, child.wait()).await.is_ok() {
+        return true;
+    }
+    if let Some(pid) = child.id() {
+        kill_process_tree(pid);
+    }
+    child.kill().await.ok();
+    false
+}
+
+/// Interrupt every tracked download and wait for them to exit. Processes
+/// still running after the timeout are marked as escalated and their pids
+/// returned for the caller to kill.
+pub async fn interrupt_download_processes(timeout: Duration) -> Vec<u32> {
+    for pid in tracked_pids() {
+        if !interrupt_process(pid) {
+            log::warn!("Failed to interrupt download process {}", pid);
+        }
+    }
+    let started = Instant::now();
+    while !tracked_pids().is_empty() && started.elapsed() < timeout {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    mark_escalated()
+}
+
+/// Kill the process trees of every tracked download, leaving unrelated
+/// yt-dlp and ffmpeg processes on the machine alone
+pub fn kill_download_processes() {
+    for pid in mark_escalated() {
+        kill_process_tree(pid);
+    }
+}
+
+/// Flag every tracked download as killed and return their pids
+fn mark_escalated() -> Vec<u32> {
+    let Ok(mut processes) = DOWNLOAD_PROCESSES.lock() else {
+        return Vec::new();
+    };
+    processes
+        .iter_mut()
+        .map(|(pid, process)| {
+            process.escalated = true;
+            *pid
+        })
+        .collect()
+}
+
+/// File yt-dlp announced it is writing, from `[download] Destination: ...`
+/// or `[Merger] Merging formats into "..."`
+pub fn download_destination(line: &str) -> Option<PathBuf> {
+    let line = line.trim();
+    let path = line
+        .strip_prefix("[download] Destination:")
+        .or_else(|| line.strip_prefix("[Merger] Merging formats into"))?
+        .trim()
+        .trim_matches('"');
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// A single-format stream yt-dlp downloads before merging, like
+/// `title.f137.mp4` or `title.fhls-720p.mp4`
+pub(crate) fn is_format_stream_name(name: &str) -> bool {
+    name.rsplit('.')
+        .nth(1)
+        .filter(|_| name.matches('.').count() >= 2)
+        .and_then(|part| part.strip_prefix('f'))
+        .is_some_and(|id| {
+            id.chars().any(|c| c.is_ascii_digit())
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// Leftovers of an interrupted write to `destination`: `.part`, `.ytdl` and
+/// fragment files, the merger's `.temp` output, and per-format streams
+/// (`title.f137.mp4`) that were never merged
+fn partial_files_for(destination: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(name)) = (
+        destination.parent(),
+        destination.file_name().map(|name| name.to_string_lossy()),
+    ) else {
+        return Vec::new();
+    };
+    let merge_temp = match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}.temp.{}", stem, ext),
+        None => format!("{}.temp", name),
+    };
+    let is_format_stream = is_format_stream_name(&name);
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            file_name.strip_prefix(name.as_ref()).is_some_and(|rest| {
+                rest == ".part" || rest == ".ytdl" || rest.starts_with(".part-Frag")
+            }) || file_name == merge_temp
+                || (is_format_stream && file_name == name)
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Remove what a killed download left behind for each destination it announced
+pub fn remove_partial_downloads(destinations: &[PathBuf]) {
+    for path in destinations.iter().flat_map(|dest| partial_files_for(dest)) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            log::warn!("Failed to remove partial file {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn killing_a_tree_spares_unrelated_processes() {
+        let mut unrelated = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let mut parent = std::process::Command::new("sh")
+            .args(["-c", "sleep 30 & sleep 30 & wait"])
+            .spawn()
+            .unwrap();
+        let pid = parent.id();
+        let started = Instant::now();
+        while descendant_pids(pid).len() < 2 && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let children = descendant_pids(pid);
+        assert_eq!(children.len(), 2);
+
+        kill_process_tree(pid);
+        parent.wait().unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        for child in children {
+            // Orphans may linger as zombies until init reaps them
+            let state = std::process::Command::new("ps")
+                .args(["-o", "stat=", "-p", &child.to_string()])
+                .output()
+                .unwrap();
+            let state = String::from_utf8_lossy(&state.stdout);
+            assert!(
+                state.trim().is_empty() || state.starts_with('Z'),
+                "{} survived",
+                child
+            );
+        }
+        assert!(unrelated.try_wait().unwrap().is_none());
+        unrelated.kill().ok();
+        unrelated.wait().ok();
+    }
+
+    #[test]
+    fn destinations_come_from_download_and_merger_lines() {
+        assert_eq!(
+            download_destination("[download] Destination: /tmp/Song.f137.mp4"),
+            Some(PathBuf::from("/tmp/Song.f137.mp4"))
+        );
+        assert_eq!(
+            download_destination(r#"[Merger] Merging formats into "/tmp/Song.mp4""#),
+            Some(PathBuf::from("/tmp/Song.mp4"))
+        );
+        assert_eq!(download_destination("[download]  42.0% of 3.00MiB"), None);
+    }
+
+    #[test]
+    fn partial_files_match_only_the_interrupted_download() {
+        let dir = std::env::temp_dir().join(format!("youwee-partials-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "Song.f137.mp4",
+            "Song.f137.mp4.part",
+            "Song.f137.mp4.part-Frag12",
+            "Song.f137.mp4.ytdl",
+            "Song.temp.mp4",
+            "Song.mp4",
+            "Other.f137.mp4.part",
+            "Song (1).mp4",
+            "my.file.mp4",
+        ] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        remove_partial_downloads(&[
+            dir.join("Song.f137.mp4"),
+            dir.join("Song.mp4"),
+            dir.join("my.file.mp4"),
+        ]);
+
+        let mut left: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(
+            left,
+            vec![
+                "Other.f137.mp4.part",
+                "Song (1).mp4",
+                "Song.mp4",
+                "my.file.mp4"
+            ]
+        );
+    }
+}
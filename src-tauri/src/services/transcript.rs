@@ -0,0 +1,252 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use super::{format_srt_timestamp, format_vtt_timestamp};
+use crate::utils::data_dir;
+
+/// A pause this long (seconds) between segments is treated as a speaker change
+pub const SPEAKER_CHANGE_PAUSE_SECONDS: f64 = 1.2;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    pub start: f64,
+    pub end: f64,
+    pub word: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start: f64,
+    pub end: f64,
+    pub speaker: Option<String>,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptWord>,
+}
+
+/// A timed transcript, exportable as SRT, VTT or JSON
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub language: Option<String>,
+    pub duration_seconds: Option<f64>,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptExportFormat {
+    Srt,
+    Vtt,
+    Json,
+}
+
+#[derive(Deserialize)]
+struct VerboseSegment {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct VerboseTranscript {
+    text: Option<String>,
+    duration: Option<f64>,
+    language: Option<String>,
+    segments: Option<Vec<VerboseSegment>>,
+    words: Option<Vec<TranscriptWord>>,
+}
+
+/// Build a transcript from a `verbose_json` response, attaching each word to
+/// the segment it starts in
+pub fn parse_verbose_transcript(response_text: &str) -> Result<Transcript, String> {
+    let verbose: VerboseTranscript =
+        serde_json::from_str(response_text).map_err(|e| e.to_string())?;
+    let mut segments: Vec<TranscriptSegment> = verbose
+        .segments
+        .unwrap_or_default()
+        .into_iter()
+        .map(|segment| TranscriptSegment {
+            start: segment.start,
+            end: segment.end,
+            speaker: None,
+            text: segment.text.trim().to_string(),
+            words: Vec::new(),
+        })
+        .collect();
+    if segments.is_empty() {
+        let text = verbose
+            .text
+            .filter(|text| !text.trim().is_empty())
+            .ok_or("No text or segments in Whisper verbose_json response")?;
+        segments.push(TranscriptSegment {
+            start: 0.0,
+            end: verbose.duration.unwrap_or(5.0).max(1.0),
+            text: text.trim().to_string(),
+            ..Default::default()
+        });
+    }
+
+    let last = segments.len() - 1;
+    for word in verbose.words.unwrap_or_default() {
+        let index = segments
+            .iter()
+            .position(|segment| word.start < segment.end)
+            .unwrap_or(last);
+        segments[index].words.push(word);
+    }
+
+    Ok(Transcript {
+        language: verbose.language,
+        duration_seconds: verbose.duration,
+        segments,
+    })
+}
+
+/// Label speaker turns: a long pause switches to the next of `speakers`
+/// voices. A simple heuristic for two-person interviews and podcasts, not
+/// voice recognition.
+pub fn label_speaker_turns(segments: &mut [TranscriptSegment], speakers: u32) {
+    if speakers == 0 {
+        return;
+    }
+    let mut current = 0;
+    let mut previous_end: Option<f64> = None;
+    for segment in segments.iter_mut() {
+        if previous_end.is_some_and(|end| segment.start - end >= SPEAKER_CHANGE_PAUSE_SECONDS) {
+            current = (current + 1) % speakers;
+        }
+        segment.speaker = Some(format!("Speaker {}", current + 1));
+        previous_end = Some(segment.end);
+    }
+}
+
+fn cue_end(segment: &TranscriptSegment) -> f64 {
+    segment.end.max(segment.start + 0.1)
+}
+
+/// Render a transcript; speakers appear as "Speaker 1: " in SRT and as
+/// `<v Speaker 1>` voice tags in VTT
+pub fn export_transcript(
+    transcript: &Transcript,
+    format: TranscriptExportFormat,
+) -> Result<String, String> {
+    match format {
+        TranscriptExportFormat::Srt => Ok(transcript
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(idx, segment)| {
+                let speaker = segment
+                    .speaker
+                    .as_deref()
+                    .map(|speaker| format!("{}: ", speaker))
+                    .unwrap_or_default();
+                format!(
+                    "{}\n{} --> {}\n{}{}\n",
+                    idx + 1,
+                    format_srt_timestamp(segment.start),
+                    format_srt_timestamp(cue_end(segment)),
+                    speaker,
+                    segment.text
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")),
+        TranscriptExportFormat::Vtt => {
+            let cues = transcript
+                .segments
+                .iter()
+                .map(|segment| {
+                    let speaker = segment
+                        .speaker
+                        .as_deref()
+                        .map(|speaker| format!("<v {}>", speaker))
+                        .unwrap_or_default();
+                    format!(
+                        "{} --> {}\n{}{}\n",
+                        format_vtt_timestamp(segment.start),
+                        format_vtt_timestamp(cue_end(segment)),
+                        speaker,
+                        segment.text
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok(format!("WEBVTT\n\n{}", cues))
+        }
+        TranscriptExportFormat::Json => {
+            serde_json::to_string_pretty(transcript).map_err(|e| e.to_string())
+        }
+    }
+}
+
+fn transcript_cache_path(app: &AppHandle, source: &str) -> Option<PathBuf> {
+    let key = hex::encode(Sha256::digest(source.as_bytes()));
+    data_dir(app)
+        .ok()
+        .map(|dir| dir.join("transcripts").join(format!("{}.json", key)))
+}
+
+/// Transcript saved earlier for a file path or URL
+pub fn cached_transcript(app: &AppHandle, source: &str) -> Option<Transcript> {
+    let content = std::fs::read_to_string(transcript_cache_path(app, source)?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Keep a transcript so chaptering and exports don't pay for Whisper again
+pub fn cache_transcript(app: &AppHandle, source: &str, transcript: &Transcript) {
+    let Some(path) = transcript_cache_path(app, source) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    if let Ok(json) = serde_json::to_string(transcript) {
+        std::fs::write(path, json).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_attach_to_segments_and_pauses_switch_speakers() {
+        let response = r#"{
+            "language": "english",
+            "duration": 9.0,
+            "segments": [
+                {"start": 0.0, "end": 2.0, "text": " Welcome to the show."},
+                {"start": 2.1, "end": 4.0, "text": " Thanks for having me."},
+                {"start": 5.5, "end": 8.0, "text": " Glad you came."}
+            ],
+            "words": [
+                {"start": 0.0, "end": 0.5, "word": "Welcome"},
+                {"start": 2.1, "end": 2.6, "word": "Thanks"},
+                {"start": 5.5, "end": 5.9, "word": "Glad"}
+            ]
+        }"#;
+        let mut transcript = parse_verbose_transcript(response).unwrap();
+        assert_eq!(transcript.segments[1].words[0].word, "Thanks");
+        assert_eq!(transcript.segments[2].words.len(), 1);
+
+        label_speaker_turns(&mut transcript.segments, 2);
+        let speakers: Vec<_> = transcript
+            .segments
+            .iter()
+            .map(|segment| segment.speaker.as_deref().unwrap())
+            .collect();
+        assert_eq!(speakers, ["Speaker 1", "Speaker 1", "Speaker 2"]);
+
+        let srt = export_transcript(&transcript, TranscriptExportFormat::Srt).unwrap();
+        assert!(
+            srt.starts_with("1\n00:00:00,000 --> 00:00:02,000\nSpeaker 1: Welcome to the show.\n")
+        );
+        let vtt = export_transcript(&transcript, TranscriptExportFormat::Vtt).unwrap();
+        assert!(vtt.contains("00:00:05.500 --> 00:00:08.000\n<v Speaker 2>Glad you came.\n"));
+    }
+}
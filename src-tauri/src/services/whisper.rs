@@ -2,8 +2,14 @@ use reqwest::multipart::{Form, Part};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use tauri::AppHandle;
 use tokio::fs;
 
+use super::{
+    local_whisper_enabled, parse_verbose_transcript, transcribe_locally, FfmpegRunner,
+    ProcessInvocation, ProcessRunner, Transcript,
+};
+
 /// Whisper API response format
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -67,6 +73,7 @@ pub enum WhisperError {
     NetworkError(String),
     ParseError(String),
     FfmpegError(String),
+    LocalError(String),
 }
 
 impl std::fmt::Display for WhisperError {
@@ -90,6 +97,7 @@ impl std::fmt::Display for WhisperError {
             WhisperError::NetworkError(msg) => write!(f, "Network error: {}", msg),
             WhisperError::ParseError(msg) => write!(f, "Failed to parse Whisper response: {}", msg),
             WhisperError::FfmpegError(msg) => write!(f, "FFmpeg error: {}", msg),
+            WhisperError::LocalError(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -106,7 +114,7 @@ const MAX_FILE_SIZE: u64 = 25 * 1024 * 1024;
 /// Supported audio formats for Whisper
 const SUPPORTED_FORMATS: &[&str] = &["mp3", "mp4", "mpeg", "mpga", "m4a", "wav", "webm", "ogg"];
 
-fn format_srt_timestamp(seconds: f64) -> String {
+pub(crate) fn format_srt_timestamp(seconds: f64) -> String {
     let secs = seconds.max(0.0);
     let total_ms = (secs * 1000.0).round() as u64;
     let ms = total_ms % 1000;
@@ -118,7 +126,7 @@ fn format_srt_timestamp(seconds: f64) -> String {
     format!("{:02}:{:02}:{:02},{:03}", h, m, s, ms)
 }
 
-fn format_vtt_timestamp(seconds: f64) -> String {
+pub(crate) fn format_vtt_timestamp(seconds: f64) -> String {
     let secs = seconds.max(0.0);
     let total_ms = (secs * 1000.0).round() as u64;
     let ms = total_ms % 1000;
@@ -209,24 +217,37 @@ fn convert_verbose_json_to_subtitle(
     })
 }
 
-/// Transcribe audio file using OpenAI Whisper API
-///
-/// # Arguments
-/// * `api_key` - OpenAI API key
-/// * `audio_path` - Path to audio/video file
-/// * `response_format` - Desired output format (text, srt, vtt, json)
-/// * `language` - Optional language hint (e.g., "en", "vi", "ja")
-///
-/// # Returns
-/// Transcription text or subtitle content
-pub async fn transcribe_audio(
+/// Result in `format` from a verbose_json transcript
+fn verbose_json_result(
+    response_text: &str,
+    format: WhisperResponseFormat,
+) -> Result<WhisperResult, WhisperError> {
+    if matches!(
+        format,
+        WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt
+    ) {
+        return convert_verbose_json_to_subtitle(response_text, format);
+    }
+    let verbose: WhisperVerboseResponse =
+        serde_json::from_str(response_text).map_err(|e| WhisperError::ParseError(e.to_string()))?;
+    let with_details = format == WhisperResponseFormat::VerboseJson;
+    Ok(WhisperResult {
+        text: verbose.text.unwrap_or_default(),
+        duration_seconds: verbose.duration.filter(|_| with_details),
+        language: verbose.language.filter(|_| with_details),
+    })
+}
+
+/// Upload audio to a Whisper-compatible endpoint and return the raw response body
+async fn post_transcription(
     api_key: &str,
     audio_path: &str,
-    response_format: WhisperResponseFormat,
+    api_response_format: &WhisperResponseFormat,
+    timestamp_granularities: &[&str],
     language: Option<&str>,
     endpoint_url: Option<&str>,
     model: Option<&str>,
-) -> Result<WhisperResult, WhisperError> {
+) -> Result<String, WhisperError> {
     let path = Path::new(audio_path);
 
     // Validate file exists
@@ -285,23 +306,13 @@ pub async fn transcribe_audio(
 
     let model_name = model.unwrap_or("whisper-1");
 
-    let requested_format = response_format.clone();
-    let api_response_format = match requested_format {
-        WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt => {
-            WhisperResponseFormat::VerboseJson
-        }
-        _ => requested_format.clone(),
-    };
-
     let mut form = Form::new()
         .part("file", file_part)
         .text("model", model_name.to_string())
         .text("response_format", api_response_format.to_string());
 
-    // Needed so providers that support only json/text/verbose_json still return
-    // enough timing data for local SRT/VTT conversion.
-    if matches!(api_response_format, WhisperResponseFormat::VerboseJson) {
-        form = form.text("timestamp_granularities[]", "segment");
+    for granularity in timestamp_granularities {
+        form = form.text("timestamp_granularities[]", granularity.to_string());
     }
 
     // Add language hint if provided
@@ -349,6 +360,61 @@ pub async fn transcribe_audio(
         )));
     }
 
+    Ok(response_text)
+}
+
+/// Transcribe audio file using OpenAI Whisper API, or whisper.cpp when local
+/// transcription is selected
+///
+/// # Arguments
+/// * `app` - App handle, used to find whisper.cpp and the local models
+/// * `api_key` - OpenAI API key
+/// * `audio_path` - Path to audio/video file
+/// * `response_format` - Desired output format (text, srt, vtt, json)
+/// * `language` - Optional language hint (e.g., "en", "vi", "ja")
+///
+/// # Returns
+/// Transcription text or subtitle content
+pub async fn transcribe_audio(
+    app: &AppHandle,
+    api_key: &str,
+    audio_path: &str,
+    response_format: WhisperResponseFormat,
+    language: Option<&str>,
+    endpoint_url: Option<&str>,
+    model: Option<&str>,
+) -> Result<WhisperResult, WhisperError> {
+    if local_whisper_enabled() {
+        let response_text = transcribe_locally(app, audio_path, language, model)
+            .await
+            .map_err(WhisperError::LocalError)?;
+        return verbose_json_result(&response_text, response_format);
+    }
+    let requested_format = response_format.clone();
+    let api_response_format = match requested_format {
+        WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt => {
+            WhisperResponseFormat::VerboseJson
+        }
+        _ => requested_format.clone(),
+    };
+    // Needed so providers that support only json/text/verbose_json still return
+    // enough timing data for local SRT/VTT conversion.
+    let granularities: &[&str] = if api_response_format == WhisperResponseFormat::VerboseJson {
+        &["segment"]
+    } else {
+        &[]
+    };
+    let response_text = post_transcription(
+        api_key,
+        audio_path,
+        &api_response_format,
+        granularities,
+        language,
+        endpoint_url,
+        model,
+    )
+    .await?;
+
     // Parse response based on requested format
     match requested_format {
         WhisperResponseFormat::Text | WhisperResponseFormat::Srt | WhisperResponseFormat::Vtt => {
@@ -403,6 +469,41 @@ pub async fn transcribe_audio(
     }
 }
 
+/// Transcribe into timed segments, with per-word timings when `word_timestamps`
+/// is set. Local whisper.cpp runs give segment timings only.
+pub async fn transcribe_audio_segments(
+    app: &AppHandle,
+    api_key: &str,
+    audio_path: &str,
+    language: Option<&str>,
+    endpoint_url: Option<&str>,
+    model: Option<&str>,
+    word_timestamps: bool,
+) -> Result<Transcript, WhisperError> {
+    if local_whisper_enabled() {
+        let response_text = transcribe_locally(app, audio_path, language, model)
+            .await
+            .map_err(WhisperError::LocalError)?;
+        return parse_verbose_transcript(&response_text).map_err(WhisperError::ParseError);
+    }
+    let granularities: &[&str] = if word_timestamps {
+        &["segment", "word"]
+    } else {
+        &["segment"]
+    };
+    let response_text = post_transcription(
+        api_key,
+        audio_path,
+        &WhisperResponseFormat::VerboseJson,
+        granularities,
+        language,
+        endpoint_url,
+        model,
+    )
+    .await?;
+    parse_verbose_transcript(&response_text).map_err(WhisperError::ParseError)
+}
+
 /// Extract audio from video file using FFmpeg
 /// Creates a compressed MP3 file suitable for Whisper API
 ///
@@ -418,42 +519,13 @@ pub async fn extract_audio_for_whisper(
     output_path: &str,
     ffmpeg_path: Option<&str>,
 ) -> Result<String, WhisperError> {
-    use crate::utils::CommandExt;
-    use tokio::process::Command;
-
     let ffmpeg = ffmpeg_path.unwrap_or("ffmpeg");
 
     // Extract audio as mono MP3 at 64kbps (compact for Whisper)
     // -vn: no video
     // -ac 1: mono audio (smaller file)
     // -b:a 64k: 64kbps bitrate (good enough for speech)
-    let mut cmd = Command::new(ffmpeg);
-    cmd.args([
-        "-i",
-        input_path,
-        "-vn",
-        "-acodec",
-        "libmp3lame",
-        "-ac",
-        "1",
-        "-b:a",
-        "64k",
-        "-y", // Overwrite output
-        output_path,
-    ]);
-    cmd.hide_window();
-    let output = cmd
-        .output()
-        .await
-        .map_err(|e| WhisperError::FfmpegError(format!("Failed to run FFmpeg: {}", e)))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(WhisperError::FfmpegError(format!(
-            "FFmpeg failed: {}",
-            stderr
-        )));
-    }
+    encode_speech_mp3(ffmpeg, input_path, output_path, "64k").await?;
 
     // Verify output file exists and is within size limit
     let metadata = fs::metadata(output_path)
@@ -464,33 +536,8 @@ pub async fn extract_audio_for_whisper(
         // Try again with even lower bitrate
         let _ = fs::remove_file(output_path).await;
 
-        let mut cmd2 = Command::new(ffmpeg);
-        cmd2.args([
-            "-i",
-            input_path,
-            "-vn",
-            "-acodec",
-            "libmp3lame",
-            "-ac",
-            "1",
-            "-b:a",
-            "32k", // Even lower bitrate
-            "-y",
-            output_path,
-        ]);
-        cmd2.hide_window();
-        let output = cmd2
-            .output()
-            .await
-            .map_err(|e| WhisperError::FfmpegError(format!("Failed to run FFmpeg: {}", e)))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(WhisperError::FfmpegError(format!(
-                "FFmpeg failed: {}",
-                stderr
-            )));
-        }
+        // Even lower bitrate
+        encode_speech_mp3(ffmpeg, input_path, output_path, "32k").await?;
 
         // Check size again
         let metadata = fs::metadata(output_path)
@@ -505,33 +552,61 @@ pub async fn extract_audio_for_whisper(
     Ok(output_path.to_string())
 }
 
+/// Mono MP3 of the audio of `input_path` at `bitrate`, replacing `output_path`
+async fn encode_speech_mp3(
+    ffmpeg: &str,
+    input_path: &str,
+    output_path: &str,
+    bitrate: &str,
+) -> Result<(), WhisperError> {
+    let args = [
+        "-i",
+        input_path,
+        "-vn",
+        "-acodec",
+        "libmp3lame",
+        "-ac",
+        "1",
+        "-b:a",
+        bitrate,
+        "-y", // Overwrite output
+        output_path,
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    FfmpegRunner::new(ffmpeg, args)
+        .run(None, |_| {})
+        .await
+        .map_err(|e| WhisperError::FfmpegError(format!("FFmpeg failed: {}", e)))
+}
+
 /// Get audio duration in seconds using FFprobe
 pub async fn get_audio_duration(
+    runner: &dyn ProcessRunner,
     audio_path: &str,
     ffprobe_path: Option<&str>,
 ) -> Result<f64, WhisperError> {
-    use crate::utils::CommandExt;
-    use tokio::process::Command;
-
     let ffprobe = ffprobe_path.unwrap_or("ffprobe");
 
-    let mut cmd = Command::new(ffprobe);
-    cmd.args([
-        "-v",
-        "error",
-        "-show_entries",
-        "format=duration",
-        "-of",
-        "default=noprint_wrappers=1:nokey=1",
-        audio_path,
-    ]);
-    cmd.hide_window();
-    let output = cmd
-        .output()
+    let invocation = ProcessInvocation::binary(
+        ffprobe,
+        &[
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+            audio_path,
+        ],
+    );
+    let output = runner
+        .run(&invocation)
         .await
-        .map_err(|e| WhisperError::FfmpegError(format!("Failed to run ffprobe: {}", e)))?;
+        .map_err(WhisperError::FfmpegError)?;
 
-    if !output.status.success() {
+    if !output.success {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(WhisperError::FfmpegError(format!(
             "ffprobe failed: {}",
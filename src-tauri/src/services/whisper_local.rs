@@ -0,0 +1,504 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+
+use super::{
+    background_command, default_whisper_model, get_ffmpeg_path, installed_whisper_model_path,
+    FfmpegRunner,
+};
+#[cfg(not(windows))]
+use crate::utils::unix_system_binary_dirs;
+use crate::utils::{data_dir, decode_process_output, find_system_binary, CommandExt};
+
+/// Hardware a local whisper.cpp transcription runs on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhisperBackend {
+    #[default]
+    Cpu,
+    Metal,
+    Cuda,
+    Rocm,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperBackendConfig {
+    pub backend: WhisperBackend,
+    /// CPU threads; the machine's core count (up to 8) when unset
+    pub threads: Option<u32>,
+    /// Transcribe with whisper.cpp on this machine instead of the Whisper API
+    #[serde(default)]
+    pub local: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperBenchmarkResult {
+    pub backend: WhisperBackend,
+    pub seconds: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperBenchmarkReport {
+    pub model_id: String,
+    pub results: Vec<WhisperBenchmarkResult>,
+    pub fastest: Option<WhisperBackend>,
+}
+
+static WHISPER_BACKEND_CONFIG: Mutex<WhisperBackendConfig> = Mutex::new(WhisperBackendConfig {
+    backend: WhisperBackend::Cpu,
+    threads: None,
+    local: false,
+});
+
+const BENCHMARK_SAMPLE_SECONDS: u32 = 10;
+
+pub fn set_whisper_backend_config(config: WhisperBackendConfig) -> Result<(), String> {
+    if config.threads == Some(0) {
+        return Err("Thread count must be at least 1".to_string());
+    }
+    if !available_whisper_backends().contains(&config.backend) {
+        return Err(format!(
+            "The {:?} backend is not available on this machine",
+            config.backend
+        ));
+    }
+    if let Ok(mut guard) = WHISPER_BACKEND_CONFIG.lock() {
+        *guard = config;
+    }
+    Ok(())
+}
+
+pub fn get_whisper_backend_config() -> WhisperBackendConfig {
+    WHISPER_BACKEND_CONFIG
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or_default()
+}
+
+/// Whether transcriptions run on whisper.cpp rather than the API
+pub fn local_whisper_enabled() -> bool {
+    get_whisper_backend_config().local
+}
+
+fn has_system_tool(name: &str) -> bool {
+    #[cfg(windows)]
+    let (binary, dirs) = (format!("{}.exe", name), Vec::new());
+    #[cfg(not(windows))]
+    let (binary, dirs) = (name.to_string(), unix_system_binary_dirs());
+    find_system_binary(&binary, &dirs).is_some()
+}
+
+/// Backends this machine can use: CPU always, Metal on macOS, CUDA/ROCm when
+/// the vendor tools are installed
+pub fn available_whisper_backends() -> Vec<WhisperBackend> {
+    let mut backends = vec![WhisperBackend::Cpu];
+    if cfg!(target_os = "macos") {
+        backends.push(WhisperBackend::Metal);
+    }
+    if has_system_tool("nvidia-smi") {
+        backends.push(WhisperBackend::Cuda);
+    }
+    if has_system_tool("rocm-smi") || has_system_tool("rocminfo") {
+        backends.push(WhisperBackend::Rocm);
+    }
+    backends
+}
+
+fn default_thread_count() -> u32 {
+    std::thread::available_parallelism()
+        .map(|n| n.get().min(8) as u32)
+        .unwrap_or(4)
+}
+
+const GENERIC_CLI_NAMES: &[&str] = &["whisper-cli", "whisper-cpp"];
+
+/// whisper.cpp binaries to try for `backend`, best first. A build only
+/// supports the GPU API it was compiled for, so each GPU backend looks for
+/// its own build; the plain CLI stands in for a GPU backend only when it is
+/// the one GPU backend here, as two of them would otherwise run the same thing.
+fn whisper_cli_names(backend: WhisperBackend, gpu_backends: usize) -> Vec<&'static str> {
+    let dedicated: &[&str] = match backend {
+        WhisperBackend::Cpu => &[],
+        WhisperBackend::Metal => &["whisper-cli-metal"],
+        WhisperBackend::Cuda => &["whisper-cli-cuda"],
+        WhisperBackend::Rocm => &["whisper-cli-hip", "whisper-cli-rocm"],
+    };
+    let mut names = dedicated.to_vec();
+    if backend == WhisperBackend::Cpu || gpu_backends <= 1 {
+        names.extend(GENERIC_CLI_NAMES);
+    }
+    names
+}
+
+/// The whisper.cpp CLI that runs on `backend`, from app data or the system
+pub fn find_whisper_cli(app: &AppHandle, backend: WhisperBackend) -> Result<PathBuf, String> {
+    let gpu_backends = available_whisper_backends()
+        .into_iter()
+        .filter(|available| *available != WhisperBackend::Cpu)
+        .count();
+    let names: Vec<String> = whisper_cli_names(backend, gpu_backends)
+        .into_iter()
+        .map(|name| {
+            if cfg!(windows) {
+                format!("{}.exe", name)
+            } else {
+                name.to_string()
+            }
+        })
+        .collect();
+
+    let app_dir = data_dir(app).ok().map(|dir| dir.join("bin"));
+    #[cfg(windows)]
+    let fallback_dirs = Vec::new();
+    #[cfg(not(windows))]
+    let fallback_dirs = unix_system_binary_dirs();
+    let found = names.iter().find_map(|name| {
+        app_dir
+            .as_ref()
+            .map(|dir| dir.join(name))
+            .filter(|path| path.exists())
+            .or_else(|| find_system_binary(name, &fallback_dirs))
+    });
+    found.ok_or_else(|| match backend {
+        WhisperBackend::Cpu => {
+            "whisper.cpp not found. Please install whisper-cli to transcribe locally.".to_string()
+        }
+        _ => format!(
+            "No {:?} build of whisper.cpp found. Install it as {}.",
+            backend,
+            names.first().map(String::as_str).unwrap_or("whisper-cli")
+        ),
+    })
+}
+
+/// whisper.cpp arguments for one run; GPU builds use the GPU unless `-ng` is passed
+pub fn whisper_cli_args(
+    model: &Path,
+    audio: &Path,
+    config: &WhisperBackendConfig,
+    language: Option<&str>,
+) -> Vec<String> {
+    let threads = config.threads.unwrap_or_else(default_thread_count);
+    let mut args = vec![
+        "-m".to_string(),
+        model.to_string_lossy().to_string(),
+        "-f".to_string(),
+        audio.to_string_lossy().to_string(),
+        "-t".to_string(),
+        threads.to_string(),
+        "-np".to_string(),
+    ];
+    if config.backend == WhisperBackend::Cpu {
+        args.push("-ng".to_string());
+    }
+    if let Some(language) = language {
+        args.extend(["-l".to_string(), language.to_string()]);
+    }
+    args
+}
+
+/// Run whisper.cpp on a 16 kHz WAV and return its stdout transcript
+pub async fn run_whisper_cli(
+    cli: &Path,
+    model: &Path,
+    audio: &Path,
+    config: &WhisperBackendConfig,
+    language: Option<&str>,
+) -> Result<String, String> {
+    let mut cmd = background_command(cli);
+    cmd.args(whisper_cli_args(model, audio, config, language))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    cmd.hide_window();
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start whisper.cpp: {}", e))?;
+    if !output.status.success() {
+        let stderr = decode_process_output(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("unknown error");
+        return Err(format!("whisper.cpp failed: {}", reason.trim()));
+    }
+    Ok(decode_process_output(&output.stdout))
+}
+
+/// `HH:MM:SS.mmm` from a whisper.cpp segment header
+fn parse_cli_timestamp(value: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in value.trim().split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// whisper.cpp stdout (`[00:00:00.000 --> 00:00:02.500]  text` per segment)
+/// in the Whisper API's verbose_json shape
+pub fn whisper_cli_verbose_json(stdout: &str, language: Option<&str>) -> String {
+    let segments: Vec<(f64, f64, &str)> = stdout
+        .lines()
+        .filter_map(|line| {
+            let (times, text) = line.trim().strip_prefix('[')?.split_once(']')?;
+            let (start, end) = times.split_once("-->")?;
+            Some((
+                parse_cli_timestamp(start)?,
+                parse_cli_timestamp(end)?,
+                text.trim(),
+            ))
+        })
+        .filter(|(_, _, text)| !text.is_empty())
+        .collect();
+    let text = segments
+        .iter()
+        .map(|(_, _, text)| *text)
+        .collect::<Vec<_>>()
+        .join(" ");
+    json!({
+        "text": text,
+        "language": language,
+        "duration": segments.last().map(|(_, end, _)| *end),
+        "segments": segments
+            .iter()
+            .map(|(start, end, text)| json!({ "start": start, "end": end, "text": text }))
+            .collect::<Vec<_>>(),
+    })
+    .to_string()
+}
+
+/// Transcribe with whisper.cpp on the configured backend, using `model` when
+/// it is an installed local model and the language's default otherwise.
+/// Returns the transcript in the API's verbose_json shape.
+pub async fn transcribe_locally(
+    app: &AppHandle,
+    audio_path: &str,
+    language: Option<&str>,
+    model: Option<&str>,
+) -> Result<String, String> {
+    let config = get_whisper_backend_config();
+    let cli = find_whisper_cli(app, config.backend)?;
+    let model_id = model
+        .filter(|id| installed_whisper_model_path(app, id).is_some())
+        .map(str::to_string)
+        .or_else(|| default_whisper_model(language))
+        .ok_or("No local Whisper model selected. Download one or pick a default model.")?;
+    let model_path = installed_whisper_model_path(app, &model_id)
+        .ok_or_else(|| format!("Whisper model '{}' is not installed", model_id))?;
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg to transcribe locally.")?;
+
+    // whisper.cpp only reads 16 kHz WAV
+    let temp_dir =
+        std::env::temp_dir().join(format!("youwee_whisper_local_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
+    let wav = temp_dir.join("audio.wav");
+    let converted = FfmpegRunner::new(
+        &ffmpeg_path,
+        vec![
+            "-y".to_string(),
+            "-i".to_string(),
+            audio_path.to_string(),
+            "-vn".to_string(),
+            "-ar".to_string(),
+            "16000".to_string(),
+            "-ac".to_string(),
+            "1".to_string(),
+            "-c:a".to_string(),
+            "pcm_s16le".to_string(),
+            wav.to_string_lossy().to_string(),
+        ],
+    )
+    .run(None, |_| {})
+    .await;
+    let result = match converted {
+        Ok(()) => {
+            let cli_language = language.filter(|lang| !lang.is_empty()).unwrap_or("auto");
+            run_whisper_cli(&cli, &model_path, &wav, &config, Some(cli_language)).await
+        }
+        Err(error) => Err(format!(
+            "Failed to prepare audio for whisper.cpp: {}",
+            error
+        )),
+    };
+    std::fs::remove_dir_all(&temp_dir).ok();
+    Ok(whisper_cli_verbose_json(&result?, language))
+}
+
+/// 10s, 16 kHz mono WAV the benchmark transcribes; made once with FFmpeg
+async fn benchmark_sample(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = data_dir(app)
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join("whisper-models");
+    let sample = dir.join("benchmark-sample.wav");
+    if sample.is_file() {
+        return Ok(sample);
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .ok_or("FFmpeg not found. Please install FFmpeg to run the benchmark.")?;
+    let args = vec![
+        "-y".to_string(),
+        "-f".to_string(),
+        "lavfi".to_string(),
+        "-i".to_string(),
+        format!("sine=frequency=220:duration={}", BENCHMARK_SAMPLE_SECONDS),
+        "-ar".to_string(),
+        "16000".to_string(),
+        "-ac".to_string(),
+        "1".to_string(),
+        sample.to_string_lossy().to_string(),
+    ];
+    if let Err(error) = FfmpegRunner::new(&ffmpeg_path, args)
+        .run(None, |_| {})
+        .await
+    {
+        std::fs::remove_file(&sample).ok();
+        return Err(format!("Failed to create the benchmark sample: {}", error));
+    }
+    Ok(sample)
+}
+
+fn fastest_backend(results: &[WhisperBenchmarkResult]) -> Option<WhisperBackend> {
+    results
+        .iter()
+        .filter_map(|result| result.seconds.map(|seconds| (result.backend, seconds)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(backend, _)| backend)
+}
+
+/// Transcribe the sample on every available backend and time each run
+pub async fn benchmark_whisper_backends(
+    app: &AppHandle,
+    model_id: &str,
+    model: &Path,
+) -> Result<WhisperBenchmarkReport, String> {
+    find_whisper_cli(app, WhisperBackend::Cpu)?;
+    let sample = benchmark_sample(app).await?;
+    let threads = get_whisper_backend_config().threads;
+
+    let mut results = Vec::new();
+    for backend in available_whisper_backends() {
+        let config = WhisperBackendConfig {
+            backend,
+            threads,
+            local: true,
+        };
+        let started = Instant::now();
+        let result = match find_whisper_cli(app, backend) {
+            Ok(cli) => run_whisper_cli(&cli, model, &sample, &config, None).await,
+            Err(error) => Err(error),
+        };
+        results.push(match result {
+            Ok(_) => WhisperBenchmarkResult {
+                backend,
+                seconds: Some(started.elapsed().as_secs_f64()),
+                error: None,
+            },
+            Err(error) => WhisperBenchmarkResult {
+                backend,
+                seconds: None,
+                error: Some(error),
+            },
+        });
+    }
+    Ok(WhisperBenchmarkReport {
+        model_id: model_id.to_string(),
+        fastest: fastest_backend(&results),
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_backend_disables_gpu_and_fastest_run_wins() {
+        let cpu = WhisperBackendConfig {
+            backend: WhisperBackend::Cpu,
+            threads: Some(4),
+            local: true,
+        };
+        let args = whisper_cli_args(
+            Path::new("/m/ggml-base.bin"),
+            Path::new("/a.wav"),
+            &cpu,
+            Some("de"),
+        );
+        assert_eq!(
+            args.join(" "),
+            "-m /m/ggml-base.bin -f /a.wav -t 4 -np -ng -l de"
+        );
+        let metal = WhisperBackendConfig {
+            backend: WhisperBackend::Metal,
+            ..cpu
+        };
+        assert!(
+            !whisper_cli_args(Path::new("m"), Path::new("a"), &metal, None).contains(&"-ng".into())
+        );
+
+        // Each GPU backend runs its own build once there is more than one
+        assert_eq!(
+            whisper_cli_names(WhisperBackend::Cuda, 2),
+            vec!["whisper-cli-cuda"]
+        );
+        assert_eq!(
+            whisper_cli_names(WhisperBackend::Rocm, 2),
+            vec!["whisper-cli-hip", "whisper-cli-rocm"]
+        );
+        assert_eq!(
+            whisper_cli_names(WhisperBackend::Cuda, 1),
+            vec!["whisper-cli-cuda", "whisper-cli", "whisper-cpp"]
+        );
+        assert_eq!(
+            whisper_cli_names(WhisperBackend::Cpu, 2),
+            vec!["whisper-cli", "whisper-cpp"]
+        );
+
+        let results = vec![
+            WhisperBenchmarkResult {
+                backend: WhisperBackend::Cpu,
+                seconds: Some(6.5),
+                error: None,
+            },
+            WhisperBenchmarkResult {
+                backend: WhisperBackend::Cuda,
+                seconds: None,
+                error: Some("no device".to_string()),
+            },
+            WhisperBenchmarkResult {
+                backend: WhisperBackend::Metal,
+                seconds: Some(1.2),
+                error: None,
+            },
+        ];
+        assert_eq!(fastest_backend(&results), Some(WhisperBackend::Metal));
+    }
+
+    #[test]
+    fn cli_output_becomes_verbose_json() {
+        let stdout = "\n[00:00:00.000 --> 00:00:02.500]   Hello there.\n[00:01:02.500 --> 00:01:04.000]  Bye.\n";
+        let verbose: serde_json::Value =
+            serde_json::from_str(&whisper_cli_verbose_json(stdout, Some("en"))).unwrap();
+        assert_eq!(verbose["text"], "Hello there. Bye.");
+        assert_eq!(verbose["language"], "en");
+        assert_eq!(verbose["duration"], 64.0);
+        assert_eq!(verbose["segments"][1]["start"], 62.5);
+        assert_eq!(verbose["segments"][0]["text"], "Hello there.");
+    }
+}
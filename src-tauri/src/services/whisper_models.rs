@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tokio::io::AsyncWriteExt;
+
+use super::acquire_install_lock;
+use crate::types::{WhisperModelDownloadProgress, WHISPER_MODEL_DOWNLOAD_PROGRESS};
+use crate::utils::data_dir;
+
+const MODEL_REPO_API: &str = "https://huggingface.co/api/models/ggerganov/whisper.cpp/tree/main";
+const MODEL_REPO_FILES: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// A whisper.cpp (ggml) model we offer to download
+struct WhisperModelSpec {
+    id: &'static str,
+    name: &'static str,
+    file_name: &'static str,
+    size_bytes: u64,
+}
+
+const WHISPER_MODELS: &[WhisperModelSpec] = &[
+    WhisperModelSpec {
+        id: "tiny",
+        name: "Tiny",
+        file_name: "ggml-tiny.bin",
+        size_bytes: 77_691_713,
+    },
+    WhisperModelSpec {
+        id: "base",
+        name: "Base",
+        file_name: "ggml-base.bin",
+        size_bytes: 147_951_465,
+    },
+    WhisperModelSpec {
+        id: "small",
+        name: "Small",
+        file_name: "ggml-small.bin",
+        size_bytes: 487_601_967,
+    },
+    WhisperModelSpec {
+        id: "medium",
+        name: "Medium",
+        file_name: "ggml-medium.bin",
+        size_bytes: 1_533_763_059,
+    },
+    WhisperModelSpec {
+        id: "large",
+        name: "Large (v3)",
+        file_name: "ggml-large-v3.bin",
+        size_bytes: 3_095_033_483,
+    },
+];
+
+/// Language code -> model id; "*" applies to every other language
+static WHISPER_MODEL_DEFAULTS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperModelInfo {
+    pub id: String,
+    pub name: String,
+    pub file_name: String,
+    /// Download size
+    pub size_bytes: u64,
+    pub installed: bool,
+    pub path: Option<String>,
+    /// Languages this model is the default for
+    pub default_languages: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperModelStatus {
+    pub models: Vec<WhisperModelInfo>,
+    pub models_dir: String,
+    /// Bytes used by installed models
+    pub disk_usage_bytes: u64,
+}
+
+#[derive(Deserialize)]
+struct RepoFile {
+    path: String,
+    lfs: Option<RepoLfs>,
+}
+
+#[derive(Deserialize)]
+struct RepoLfs {
+    oid: String,
+}
+
+fn model_spec(id: &str) -> Result<&'static WhisperModelSpec, String> {
+    WHISPER_MODELS
+        .iter()
+        .find(|model| model.id == id)
+        .ok_or_else(|| format!("Unknown Whisper model '{}'", id))
+}
+
+fn whisper_models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    data_dir(app)
+        .map(|dir| dir.join("whisper-models"))
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+/// Path of an installed model, None when it isn't downloaded
+pub fn installed_whisper_model_path(app: &AppHandle, id: &str) -> Option<PathBuf> {
+    let spec = model_spec(id).ok()?;
+    let path = whisper_models_dir(app).ok()?.join(spec.file_name);
+    path.is_file().then_some(path)
+}
+
+/// Replace the per-language default models
+pub fn set_whisper_model_defaults(defaults: BTreeMap<String, String>) -> Result<(), String> {
+    let mut normalized = BTreeMap::new();
+    for (language, model) in defaults {
+        model_spec(&model)?;
+        normalized.insert(language.trim().to_lowercase(), model);
+    }
+    if let Ok(mut guard) = WHISPER_MODEL_DEFAULTS.lock() {
+        *guard = normalized;
+    }
+    Ok(())
+}
+
+fn default_model_in(defaults: &BTreeMap<String, String>, language: Option<&str>) -> Option<String> {
+    let language = language.map(|lang| lang.trim().to_lowercase());
+    let primary = language
+        .as_deref()
+        .and_then(|lang| lang.split(['-', '_']).next())
+        .map(str::to_string);
+    [language, primary, Some("*".to_string())]
+        .into_iter()
+        .flatten()
+        .find_map(|key| defaults.get(&key).cloned())
+}
+
+/// Default model for a language ("pt-BR" falls back to "pt", then "*")
+pub fn default_whisper_model(language: Option<&str>) -> Option<String> {
+    let defaults = WHISPER_MODEL_DEFAULTS.lock().ok()?;
+    default_model_in(&defaults, language)
+}
+
+/// Every known model with its install state, plus total disk usage
+pub fn whisper_model_status(app: &AppHandle) -> Result<WhisperModelStatus, String> {
+    let dir = whisper_models_dir(app)?;
+    let defaults = WHISPER_MODEL_DEFAULTS
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default();
+    let mut disk_usage_bytes = 0;
+    let models = WHISPER_MODELS
+        .iter()
+        .map(|spec| {
+            let path = dir.join(spec.file_name);
+            let installed = match std::fs::metadata(&path) {
+                Ok(meta) if meta.is_file() => {
+                    disk_usage_bytes += meta.len();
+                    true
+                }
+                _ => false,
+            };
+            WhisperModelInfo {
+                id: spec.id.to_string(),
+                name: spec.name.to_string(),
+                file_name: spec.file_name.to_string(),
+                size_bytes: spec.size_bytes,
+                installed,
+                path: installed.then(|| path.to_string_lossy().to_string()),
+                default_languages: defaults
+                    .iter()
+                    .filter(|(_, model)| model.as_str() == spec.id)
+                    .map(|(language, _)| language.clone())
+                    .collect(),
+            }
+        })
+        .collect();
+    Ok(WhisperModelStatus {
+        models,
+        models_dir: dir.to_string_lossy().to_string(),
+        disk_usage_bytes,
+    })
+}
+
+/// SHA-256 the model repository publishes for `file_name`
+async fn expected_model_sha256(
+    client: &reqwest::Client,
+    file_name: &str,
+) -> Result<String, String> {
+    let files: Vec<RepoFile> = client
+        .get(MODEL_REPO_API)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to fetch model checksums: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse model checksums: {}", e))?;
+    files
+        .into_iter()
+        .find(|file| file.path == file_name)
+        .and_then(|file| file.lfs)
+        .map(|lfs| lfs.oid)
+        .ok_or_else(|| format!("No checksum published for {}", file_name))
+}
+
+fn emit_model_progress(app: &AppHandle, id: &str, stage: &str, downloaded: u64, total: u64) {
+    let percent = if total > 0 {
+        ((downloaded as f64 / total as f64) * 100.0).min(100.0) as u8
+    } else {
+        0
+    };
+    let _ = WHISPER_MODEL_DOWNLOAD_PROGRESS.emit(
+        app,
+        &WhisperModelDownloadProgress {
+            model_id: id.to_string(),
+            stage: stage.to_string(),
+            percent,
+            downloaded,
+            total,
+        },
+    );
+}
+
+/// Download a model into app data, verifying its SHA-256 before it is used
+pub async fn download_whisper_model(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    let spec = model_spec(id)?;
+    let _install_lock = acquire_install_lock(app, &format!("whisper-{}", spec.id))?;
+    let dir = whisper_models_dir(app)?;
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create models directory: {}", e))?;
+
+    emit_model_progress(app, spec.id, "downloading", 0, spec.size_bytes);
+
+    let client = reqwest::Client::builder()
+        .user_agent("Youwee/0.6.0")
+        .connect_timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let expected_sha256 = expected_model_sha256(&client, spec.file_name).await?;
+
+    let response = client
+        .get(format!("{}/{}", MODEL_REPO_FILES, spec.file_name))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download Whisper model: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Download failed with status: {}",
+            response.status()
+        ));
+    }
+    let total = response.content_length().unwrap_or(spec.size_bytes);
+
+    let temp_path = dir.join(format!("{}.part", spec.file_name));
+    let mut file = tokio::fs::File::create(&temp_path)
+        .await
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut downloaded: u64 = 0;
+    let mut last_percent: u64 = 0;
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                drop(file);
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                return Err(format!("Download error: {}", e));
+            }
+        };
+        if let Err(e) = file.write_all(&chunk).await {
+            drop(file);
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(format!("Failed to write chunk: {}", e));
+        }
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+
+        // Only emit every 2% to avoid spamming on multi-GB models
+        let percent = downloaded * 100 / total.max(1);
+        if percent >= last_percent + 2 {
+            last_percent = percent;
+            emit_model_progress(app, spec.id, "downloading", downloaded, total);
+        }
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to flush file: {}", e))?;
+    drop(file);
+
+    emit_model_progress(app, spec.id, "verifying", downloaded, total);
+    let actual_sha256 = hex::encode(hasher.finalize());
+    if !actual_sha256.eq_ignore_ascii_case(&expected_sha256) {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(format!(
+            "Checksum mismatch for {}: the download is corrupt, please try again",
+            spec.file_name
+        ));
+    }
+
+    let model_path = dir.join(spec.file_name);
+    tokio::fs::rename(&temp_path, &model_path)
+        .await
+        .map_err(|e| format!("Failed to install model: {}", e))?;
+
+    emit_model_progress(app, spec.id, "complete", downloaded, total);
+    Ok(model_path)
+}
+
+/// Remove an installed model; returns the bytes freed
+pub fn delete_whisper_model(app: &AppHandle, id: &str) -> Result<u64, String> {
+    let spec = model_spec(id)?;
+    let path = whisper_models_dir(app)?.join(spec.file_name);
+    let size = match std::fs::metadata(&path) {
+        Ok(meta) => meta.len(),
+        Err(_) => return Ok(0),
+    };
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete model: {}", e))?;
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_model_falls_back_from_region_to_language_to_wildcard() {
+        let defaults = BTreeMap::from([
+            ("pt".to_string(), "medium".to_string()),
+            ("*".to_string(), "base".to_string()),
+        ]);
+        assert_eq!(
+            default_model_in(&defaults, Some("pt-BR")).as_deref(),
+            Some("medium")
+        );
+        assert_eq!(
+            default_model_in(&defaults, Some("en")).as_deref(),
+            Some("base")
+        );
+        assert_eq!(default_model_in(&defaults, None).as_deref(), Some("base"));
+        assert_eq!(default_model_in(&BTreeMap::new(), Some("en")), None);
+
+        assert!(set_whisper_model_defaults(BTreeMap::from([(
+            "en".to_string(),
+            "huge".to_string()
+        )]))
+        .is_err());
+    }
+}
@@ -1,15 +1,24 @@
+use crate::services::{
+    get_deno_path, AppProcessRunner, ProcessInvocation, ProcessRunner, ProcessTarget,
+};
 use crate::types::{
     BackendError, DependencySource, YtdlpAllVersions, YtdlpChannel, YtdlpChannelInfo,
     YtdlpVersionInfo,
 };
 use crate::utils::{
-    find_system_binary, resolve_firefox_profile_for_cookies, unix_system_binary_dirs, CommandExt,
+    build_process_env, data_dir, decode_process_output, find_system_binary,
+    resolve_firefox_profile_for_cookies, tool_path_dirs, unix_system_binary_dirs, CommandExt,
+    PYTHON_UTF8_ENV, YTDLP_ENCODING_ARGS,
 };
+use std::ffi::OsString;
+use std::future::Future;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_shell::process::CommandEvent;
 use tauri_plugin_shell::ShellExt;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
 
 const CHANNEL_CONFIG_FILE: &str = "ytdlp-channel.txt";
@@ -65,8 +74,7 @@ pub fn system_ytdlp_upgrade_message() -> String {
 
 /// Get the config file path for storing yt-dlp source selection
 fn get_source_config_path(app: &AppHandle) -> Option<PathBuf> {
-    app.path()
-        .app_data_dir()
+    data_dir(app)
         .ok()
         .map(|p| p.join("bin").join(SOURCE_CONFIG_FILE))
 }
@@ -109,8 +117,7 @@ fn get_system_ytdlp_path() -> Option<PathBuf> {
 
 /// Get the config file path for storing channel selection
 fn get_channel_config_path(app: &AppHandle) -> Option<PathBuf> {
-    app.path()
-        .app_data_dir()
+    data_dir(app)
         .ok()
         .map(|p| p.join("bin").join(CHANNEL_CONFIG_FILE))
 }
@@ -166,8 +173,7 @@ fn get_channel_binary_name(channel: &YtdlpChannel) -> &'static str {
 
 /// Get the path to a specific channel's binary in app_data_dir
 fn get_channel_binary_path(app: &AppHandle, channel: &YtdlpChannel) -> Option<PathBuf> {
-    app.path()
-        .app_data_dir()
+    data_dir(app)
         .ok()
         .map(|p| p.join("bin").join(get_channel_binary_name(channel)))
 }
@@ -179,7 +185,7 @@ fn get_legacy_ytdlp_path(app: &AppHandle) -> Option<PathBuf> {
     #[cfg(not(windows))]
     let binary_name = "yt-dlp";
 
-    app.path().app_data_dir().ok().and_then(|app_data_dir| {
+    data_dir(app).ok().and_then(|app_data_dir| {
         let legacy_binary = app_data_dir.join("bin").join(binary_name);
         if legacy_binary.exists() {
             Some(legacy_binary)
@@ -205,6 +211,14 @@ fn get_bundled_ytdlp_path() -> Option<PathBuf> {
     None
 }
 
+/// Path of the `yt-dlp` sidecar the shell plugin would run, so it can be
+/// spawned like any other binary (own process group, tracked for cancel)
+pub fn sidecar_ytdlp_path() -> Option<PathBuf> {
+    let exe_path = std::env::current_exe().ok()?;
+    let sidecar = exe_path.parent()?.join(LEGACY_BUNDLED_YTDLP_BINARY_NAME);
+    sidecar.exists().then_some(sidecar)
+}
+
 /// Get info for all yt-dlp channels (lightweight - no binary execution)
 pub async fn get_all_ytdlp_versions(app: &AppHandle) -> YtdlpAllVersions {
     let current_channel = get_ytdlp_channel(app).await;
@@ -320,49 +334,117 @@ pub fn get_channel_api_url(channel: &YtdlpChannel) -> Option<&'static str> {
     }
 }
 
-/// Get the path to yt-dlp binary based on current channel setting
-/// Returns: (path, is_bundled)
-pub async fn get_ytdlp_path(app: &AppHandle) -> Option<(PathBuf, bool)> {
+tokio::task_local! {
+    /// Channel picked for a single invocation instead of the saved setting
+    static CHANNEL_OVERRIDE: YtdlpChannel;
+}
+
+/// Run `future` with every yt-dlp invocation inside it using `channel`.
+/// `None` keeps the channel selected in settings.
+pub async fn with_ytdlp_channel<F: Future>(channel: Option<&str>, future: F) -> F::Output {
+    match channel.map(YtdlpChannel::from_str) {
+        Some(channel) => CHANNEL_OVERRIDE.scope(channel, future).await,
+        None => future.await,
+    }
+}
+
+/// Channel yt-dlp runs from: the per-invocation override or the saved setting
+pub async fn effective_ytdlp_channel(app: &AppHandle) -> YtdlpChannel {
+    match CHANNEL_OVERRIDE.try_with(YtdlpChannel::clone) {
+        Ok(channel) => channel,
+        Err(_) => get_ytdlp_channel(app).await,
+    }
+}
+
+/// The yt-dlp binary an invocation will run and where it came from
+pub struct ResolvedYtdlp {
+    pub path: PathBuf,
+    pub is_bundled: bool,
+    /// `None` for a system-managed yt-dlp
+    pub channel: Option<YtdlpChannel>,
+    /// Set when the requested channel isn't installed and another binary is used
+    pub fallback_from: Option<YtdlpChannel>,
+}
+
+impl ResolvedYtdlp {
+    /// Short description for logs, e.g. `nightly: /path/to/yt-dlp`
+    pub fn label(&self) -> String {
+        let channel = self.channel.as_ref().map_or("system", YtdlpChannel::as_str);
+        match &self.fallback_from {
+            Some(requested) => format!(
+                "{} ({} not installed): {}",
+                channel,
+                requested.as_str(),
+                self.path.display()
+            ),
+            None => format!("{}: {}", channel, self.path.display()),
+        }
+    }
+}
+
+/// Resolve the yt-dlp binary for the selected (or overridden) channel
+pub async fn resolve_ytdlp_binary(app: &AppHandle) -> Option<ResolvedYtdlp> {
     let source = get_ytdlp_source(app).await;
 
     if source == DependencySource::System {
-        return get_system_ytdlp_path().map(|p| (p, false));
+        return get_system_ytdlp_path().map(|path| ResolvedYtdlp {
+            path,
+            is_bundled: false,
+            channel: None,
+            fallback_from: None,
+        });
     }
 
-    let channel = get_ytdlp_channel(app).await;
-
-    match channel {
-        YtdlpChannel::Bundled => {
-            // Bundled must mean the sidecar shipped with the current app build.
-            // Keep the old app-data binary only as a compatibility fallback for
-            // installs that predate the Youwee-specific sidecar name.
-            if let Some(bundled) = get_bundled_ytdlp_path() {
-                return Some((bundled, true));
-            }
-            if let Some(legacy_binary) = get_legacy_ytdlp_path(app) {
-                return Some((legacy_binary, false));
-            }
+    let channel = effective_ytdlp_channel(app).await;
+    let found = |path, is_bundled, actual: YtdlpChannel| {
+        let fallback_from = (actual != channel).then(|| channel.clone());
+        ResolvedYtdlp {
+            path,
+            is_bundled,
+            channel: Some(actual),
+            fallback_from,
         }
-        YtdlpChannel::Stable | YtdlpChannel::Nightly => {
-            // Check channel-specific binary first
-            if let Some(channel_path) = get_channel_binary_path(app, &channel) {
-                if channel_path.exists() {
-                    return Some((channel_path, false));
-                }
-            }
+    };
+
+    let resolved = match channel {
+        // Bundled must mean the sidecar shipped with the current app build.
+        // Keep the old app-data binary only as a compatibility fallback for
+        // installs that predate the Youwee-specific sidecar name.
+        YtdlpChannel::Bundled => get_bundled_ytdlp_path()
+            .map(|path| found(path, true, YtdlpChannel::Bundled))
+            .or_else(|| {
+                get_legacy_ytdlp_path(app).map(|path| found(path, false, YtdlpChannel::Bundled))
+            }),
+        YtdlpChannel::Stable | YtdlpChannel::Nightly => get_channel_binary_path(app, &channel)
+            .filter(|path| path.exists())
+            .map(|path| found(path, false, channel.clone()))
             // Fallback to bundled if channel binary not found
-            if let Some(bundled) = get_bundled_ytdlp_path() {
-                return Some((bundled, true));
-            }
-        }
-    }
+            .or_else(|| {
+                get_bundled_ytdlp_path().map(|path| found(path, true, YtdlpChannel::Bundled))
+            })
+            // Final fallback: app_data_dir/bin/yt-dlp (legacy location)
+            .or_else(|| {
+                get_legacy_ytdlp_path(app).map(|path| found(path, false, YtdlpChannel::Bundled))
+            }),
+    };
 
-    // Final fallback: check app_data_dir/bin/yt-dlp (legacy location)
-    if let Some(legacy_binary) = get_legacy_ytdlp_path(app) {
-        return Some((legacy_binary, false));
+    if let Some(resolved) = &resolved {
+        if resolved.fallback_from.is_some() {
+            log::warn!(
+                "Selected yt-dlp channel unavailable, using {}",
+                resolved.label()
+            );
+        }
     }
+    resolved
+}
 
-    None
+/// Get the path to yt-dlp binary based on current channel setting
+/// Returns: (path, is_bundled)
+pub async fn get_ytdlp_path(app: &AppHandle) -> Option<(PathBuf, bool)> {
+    let resolved = resolve_ytdlp_binary(app).await?;
+    log::debug!("Running yt-dlp from {}", resolved.label());
+    Some((resolved.path, resolved.is_bundled))
 }
 
 /// Result of yt-dlp command with both stdout and stderr
@@ -372,25 +454,120 @@ pub struct YtdlpOutput {
     pub success: bool,
 }
 
-/// Helper to run yt-dlp command and get output with stderr
-pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<YtdlpOutput, String> {
+/// Value of a `--proxy` flag in yt-dlp arguments
+fn proxy_from_args<S: AsRef<str>>(args: &[S]) -> Option<&str> {
+    args.windows(2)
+        .find(|pair| pair[0].as_ref() == "--proxy")
+        .map(|pair| pair[1].as_ref())
+}
+
+/// Environment for a yt-dlp spawn: the app's bin dir and Deno lead PATH and
+/// proxy variables are only set when the arguments configure a proxy
+pub async fn ytdlp_process_env<S: AsRef<str>>(
+    app: &AppHandle,
+    args: &[S],
+) -> Vec<(OsString, OsString)> {
+    let mut dirs = Vec::new();
+    if let Ok(app_data_dir) = data_dir(app) {
+        dirs.push(app_data_dir.join("bin"));
+    }
+    if let Some(deno_dir) = get_deno_path(app)
+        .await
+        .and_then(|deno| deno.parent().map(PathBuf::from))
+    {
+        dirs.push(deno_dir);
+    }
+    let home = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" });
+    dirs.extend(tool_path_dirs(home.as_deref().map(std::path::Path::new)));
+    build_process_env(std::env::vars_os(), &dirs, proxy_from_args(args))
+}
+
+/// Where to run yt-dlp from, following the source setting: the resolved
+/// binary, then the bundled sidecar, then `yt-dlp` on PATH in auto mode
+pub async fn ytdlp_invocation(app: &AppHandle, args: &[&str]) -> Result<ProcessInvocation, String> {
+    let env = ytdlp_process_env(app, args).await;
     let source = get_ytdlp_source(app).await;
 
     // Try to get yt-dlp path (prioritizes user-updated version)
-    if let Some((binary_path, _)) = get_ytdlp_path(app).await {
-        let mut cmd = Command::new(&binary_path);
-        cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
-        cmd.hide_window();
+    let target = if let Some((binary_path, _)) = get_ytdlp_path(app).await {
+        ProcessTarget::Binary(binary_path)
+    } else if source == DependencySource::System {
+        return Err(BackendError::new(
+            crate::types::code::YTDLP_SYSTEM_NOT_FOUND,
+            system_ytdlp_not_found_message(),
+        )
+        .to_wire_string());
+    } else if app.shell().sidecar("yt-dlp").is_ok() {
+        ProcessTarget::Sidecar("yt-dlp".to_string())
+    } else if source == DependencySource::Auto {
+        ProcessTarget::Binary(PathBuf::from("yt-dlp"))
+    } else {
+        return Err(BackendError::from_message(
+            "App-managed yt-dlp not found. Please install it from Settings > Dependencies.",
+        )
+        .to_wire_string());
+    };
 
-        let output = cmd.output().await.map_err(|e| {
-            BackendError::from_message(format!("Failed to run yt-dlp: {}", e)).to_wire_string()
-        })?;
+    Ok(ProcessInvocation {
+        target,
+        args: YTDLP_ENCODING_ARGS
+            .iter()
+            .chain(args)
+            .map(|arg| arg.to_string())
+            .collect(),
+        env,
+    })
+}
 
-        return Ok(YtdlpOutput {
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            success: output.status.success(),
-        });
+/// Helper to run yt-dlp command and get output with stderr
+pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<YtdlpOutput, String> {
+    let invocation = ytdlp_invocation(app, args).await?;
+    run_ytdlp_with_stderr_using(&AppProcessRunner::new(app), &invocation).await
+}
+
+pub async fn run_ytdlp_with_stderr_using(
+    runner: &dyn ProcessRunner,
+    invocation: &ProcessInvocation,
+) -> Result<YtdlpOutput, String> {
+    let output = runner
+        .run(invocation)
+        .await
+        .map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    Ok(YtdlpOutput {
+        stdout: decode_process_output(&output.stdout),
+        stderr: decode_process_output(&output.stderr),
+        success: output.success,
+    })
+}
+
+/// Output line captured with its offset from process start
+pub struct TimedOutputLine {
+    pub elapsed_ms: u64,
+    pub is_stderr: bool,
+    pub line: String,
+}
+
+/// Result of a yt-dlp run where every line is timestamped on arrival
+pub struct YtdlpTimedOutput {
+    pub lines: Vec<TimedOutputLine>,
+    pub success: bool,
+    pub elapsed_ms: u64,
+    /// The process was killed after `timeout`
+    pub timed_out: bool,
+}
+
+/// Run yt-dlp and record when each output line arrives (used for diagnostics).
+/// The process is killed once `timeout` has passed.
+pub async fn run_ytdlp_timed(
+    app: &AppHandle,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<YtdlpTimedOutput, String> {
+    let env = ytdlp_process_env(app, args).await;
+    let source = get_ytdlp_source(app).await;
+
+    if let Some((binary_path, _)) = get_ytdlp_path(app).await {
+        return run_timed_process(Command::new(&binary_path), args, &env, timeout).await;
     }
 
     if source == DependencySource::System {
@@ -401,27 +578,46 @@ pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<Ytd
         .to_wire_string());
     }
 
-    // Fallback to sidecar
-    let sidecar_result = app.shell().sidecar("yt-dlp");
-
-    match sidecar_result {
+    match app.shell().sidecar("yt-dlp") {
         Ok(sidecar) => {
-            let (mut rx, _child) = sidecar.args(args).spawn().map_err(|e| {
-                BackendError::from_message(format!("Failed to start yt-dlp: {}", e))
-                    .to_wire_string()
-            })?;
+            let started = Instant::now();
+            let deadline = tokio::time::Instant::now() + timeout;
+            let (mut rx, child) = sidecar
+                .env_clear()
+                .envs(env.iter().cloned())
+                .envs(PYTHON_UTF8_ENV)
+                .args(YTDLP_ENCODING_ARGS)
+                .args(args)
+                .spawn()
+                .map_err(|e| {
+                    BackendError::from_message(format!("Failed to start yt-dlp: {}", e))
+                        .to_wire_string()
+                })?;
 
-            let mut stdout = String::new();
-            let mut stderr = String::new();
+            let mut lines = Vec::new();
             let mut success = true;
 
-            while let Some(event) = rx.recv().await {
+            loop {
+                let event = match tokio::time::timeout_at(deadline, rx.recv()).await {
+                    Ok(Some(event)) => event,
+                    Ok(None) => break,
+                    Err(_) => {
+                        child.kill().ok();
+                        return Ok(YtdlpTimedOutput {
+                            lines,
+                            success: false,
+                            elapsed_ms: started.elapsed().as_millis() as u64,
+                            timed_out: true,
+                        });
+                    }
+                };
+                let elapsed_ms = started.elapsed().as_millis() as u64;
                 match event {
                     CommandEvent::Stdout(bytes) => {
-                        stdout.push_str(&String::from_utf8_lossy(&bytes));
+                        push_timed_lines(&mut lines, elapsed_ms, false, &bytes);
                     }
                     CommandEvent::Stderr(bytes) => {
-                        stderr.push_str(&String::from_utf8_lossy(&bytes));
+                        push_timed_lines(&mut lines, elapsed_ms, true, &bytes);
                     }
                     CommandEvent::Error(err) => {
                         return Err(
@@ -436,28 +632,16 @@ pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<Ytd
                 }
             }
 
-            Ok(YtdlpOutput {
-                stdout,
-                stderr,
+            Ok(YtdlpTimedOutput {
+                lines,
                 success,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                timed_out: false,
             })
         }
         Err(_) => {
             if source == DependencySource::Auto {
-                let mut cmd = Command::new("yt-dlp");
-                cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
-                cmd.hide_window();
-
-                let output = cmd.output().await.map_err(|e| {
-                    BackendError::from_message(format!("Failed to run yt-dlp: {}", e))
-                        .to_wire_string()
-                })?;
-
-                Ok(YtdlpOutput {
-                    stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-                    stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-                    success: output.status.success(),
-                })
+                run_timed_process(Command::new("yt-dlp"), args, &env, timeout).await
             } else {
                 Err(BackendError::from_message(
                     "App-managed yt-dlp not found. Please install it from Settings > Dependencies.",
@@ -468,6 +652,95 @@ pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<Ytd
     }
 }
 
+fn push_timed_lines(
+    lines: &mut Vec<TimedOutputLine>,
+    elapsed_ms: u64,
+    is_stderr: bool,
+    bytes: &[u8],
+) {
+    for line in decode_process_output(bytes).lines() {
+        if !line.trim().is_empty() {
+            lines.push(TimedOutputLine {
+                elapsed_ms,
+                is_stderr,
+                line: line.to_string(),
+            });
+        }
+    }
+}
+
+async fn run_timed_process(
+    mut cmd: Command,
+    args: &[&str],
+    env: &[(OsString, OsString)],
+    timeout: Duration,
+) -> Result<YtdlpTimedOutput, String> {
+    cmd.args(YTDLP_ENCODING_ARGS)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    cmd.process_env(env).hide_window().utf8_output();
+
+    let started = Instant::now();
+    let mut child = cmd.spawn().map_err(|e| {
+        BackendError::from_message(format!("Failed to run yt-dlp: {}", e)).to_wire_string()
+    })?;
+
+    async fn read_timed<R: AsyncRead + Unpin>(
+        reader: Option<R>,
+        is_stderr: bool,
+        started: Instant,
+    ) -> Vec<TimedOutputLine> {
+        let mut collected = Vec::new();
+        if let Some(reader) = reader {
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if !line.trim().is_empty() {
+                    collected.push(TimedOutputLine {
+                        elapsed_ms: started.elapsed().as_millis() as u64,
+                        is_stderr,
+                        line,
+                    });
+                }
+            }
+        }
+        collected
+    }
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let finished = tokio::time::timeout(timeout, async {
+        let (stdout_lines, stderr_lines) = tokio::join!(
+            read_timed(stdout, false, started),
+            read_timed(stderr, true, started)
+        );
+        (stdout_lines, stderr_lines, child.wait().await)
+    })
+    .await;
+    let Ok((stdout_lines, stderr_lines, status)) = finished else {
+        child.kill().await.ok();
+        return Ok(YtdlpTimedOutput {
+            lines: Vec::new(),
+            success: false,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            timed_out: true,
+        });
+    };
+    let status = status.map_err(|e| format!("Failed to wait for yt-dlp: {}", e))?;
+
+    let mut lines = stdout_lines;
+    lines.extend(stderr_lines);
+    lines.sort_by_key(|line| line.elapsed_ms);
+
+    Ok(YtdlpTimedOutput {
+        lines,
+        success: status.success(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        timed_out: false,
+    })
+}
+
 /// Parse yt-dlp stderr for common errors and return structured backend error
 pub fn parse_ytdlp_error(stderr: &str) -> Option<BackendError> {
     let stderr_lower = stderr.to_lowercase();
@@ -629,115 +902,28 @@ pub fn is_upcoming_live_error(stderr: &str) -> bool {
 
 /// Helper to run yt-dlp command and get JSON output
 pub async fn run_ytdlp_json(app: &AppHandle, args: &[&str]) -> Result<String, String> {
-    let source = get_ytdlp_source(app).await;
-
-    // Try to get yt-dlp path (prioritizes user-updated version)
-    if let Some((binary_path, _)) = get_ytdlp_path(app).await {
-        let mut cmd = Command::new(&binary_path);
-        cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
-        cmd.hide_window();
-
-        let output = cmd.output().await.map_err(|e| {
-            BackendError::from_message(format!("Failed to run yt-dlp: {}", e)).to_wire_string()
-        })?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if let Some(parsed_error) = parse_ytdlp_error(&stderr) {
-                return Err(parsed_error.to_wire_string());
-            }
-            return Err(BackendError::from_message("yt-dlp command failed").to_wire_string());
-        }
-
-        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
-    }
-
-    if source == DependencySource::System {
-        return Err(BackendError::new(
-            crate::types::code::YTDLP_SYSTEM_NOT_FOUND,
-            system_ytdlp_not_found_message(),
-        )
-        .to_wire_string());
-    }
-
-    // Fallback to sidecar
-    let sidecar_result = app.shell().sidecar("yt-dlp");
-
-    match sidecar_result {
-        Ok(sidecar) => {
-            let (mut rx, _child) = sidecar.args(args).spawn().map_err(|e| {
-                BackendError::from_message(format!("Failed to start yt-dlp: {}", e))
-                    .to_wire_string()
-            })?;
-
-            let mut output = String::new();
-            let mut stderr_output = String::new();
-
-            while let Some(event) = rx.recv().await {
-                match event {
-                    CommandEvent::Stdout(bytes) => {
-                        output.push_str(&String::from_utf8_lossy(&bytes));
-                    }
-                    CommandEvent::Stderr(bytes) => {
-                        stderr_output.push_str(&String::from_utf8_lossy(&bytes));
-                    }
-                    CommandEvent::Error(err) => {
-                        return Err(
-                            BackendError::from_message(format!("Process error: {}", err))
-                                .to_wire_string(),
-                        );
-                    }
-                    CommandEvent::Terminated(status) => {
-                        if status.code != Some(0) {
-                            // Parse stderr for user-friendly error
-                            if let Some(parsed_error) = parse_ytdlp_error(&stderr_output) {
-                                return Err(parsed_error.to_wire_string());
-                            }
-                            return Err(BackendError::from_message("yt-dlp command failed")
-                                .to_wire_string());
-                        }
-                    }
-                    _ => {}
-                }
-            }
-
-            Ok(output)
-        }
-        Err(_) => {
-            if source == DependencySource::Auto {
-                let mut cmd = Command::new("yt-dlp");
-                cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
-                cmd.hide_window();
-
-                let output = cmd.output().await.map_err(|e| {
-                    BackendError::from_message(format!("Failed to run yt-dlp: {}", e))
-                        .to_wire_string()
-                })?;
-
-                if !output.status.success() {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    // Parse stderr for user-friendly error
-                    if let Some(parsed_error) = parse_ytdlp_error(&stderr) {
-                        return Err(parsed_error.to_wire_string());
-                    }
-                    return Err(
-                        BackendError::from_message("yt-dlp command failed").to_wire_string()
-                    );
-                }
+    let invocation = ytdlp_invocation(app, args).await?;
+    run_ytdlp_json_using(&AppProcessRunner::new(app), &invocation).await
+}
 
-                Ok(String::from_utf8_lossy(&output.stdout).to_string())
-            } else {
-                Err(BackendError::from_message(
-                    "App-managed yt-dlp not found. Please install it from Settings > Dependencies.",
-                )
-                .to_wire_string())
-            }
+pub async fn run_ytdlp_json_using(
+    runner: &dyn ProcessRunner,
+    invocation: &ProcessInvocation,
+) -> Result<String, String> {
+    let output = run_ytdlp_with_stderr_using(runner, invocation).await?;
+    if !output.success {
+        // Parse stderr for user-friendly error
+        if let Some(parsed_error) = parse_ytdlp_error(&output.stderr) {
+            return Err(parsed_error.to_wire_string());
         }
+        return Err(BackendError::from_message("yt-dlp command failed").to_wire_string());
     }
+    Ok(output.stdout)
 }
 
 /// Get yt-dlp version
 pub async fn get_ytdlp_version_internal(app: &AppHandle) -> Result<YtdlpVersionInfo, String> {
+    let env = ytdlp_process_env::<&str>(app, &[]).await;
     let source = get_ytdlp_source(app).await;
 
     // Try to get yt-dlp path (prioritizes user-updated version)
@@ -746,13 +932,13 @@ pub async fn get_ytdlp_version_internal(app: &AppHandle) -> Result<YtdlpVersionI
         cmd.args(["--version"])
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
-        cmd.hide_window();
+        cmd.process_env(&env).hide_window().utf8_output();
 
         let output = cmd.output().await.map_err(|e| {
             BackendError::from_message(format!("Failed to run yt-dlp: {}", e)).to_wire_string()
         })?;
 
-        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let version = decode_process_output(&output.stdout).trim().to_string();
         let bin_path = binary_path.to_string_lossy().to_string();
 
         return Ok(YtdlpVersionInfo {
@@ -777,15 +963,21 @@ pub async fn get_ytdlp_version_internal(app: &AppHandle) -> Result<YtdlpVersionI
 
     let (version, is_bundled, binary_path) = match sidecar_result {
         Ok(sidecar) => {
-            let (mut rx, _child) = sidecar.args(["--version"]).spawn().map_err(|e| {
-                BackendError::from_message(format!("Failed to start yt-dlp: {}", e))
-                    .to_wire_string()
-            })?;
+            let (mut rx, _child) = sidecar
+                .env_clear()
+                .envs(env.iter().cloned())
+                .envs(PYTHON_UTF8_ENV)
+                .args(["--version"])
+                .spawn()
+                .map_err(|e| {
+                    BackendError::from_message(format!("Failed to start yt-dlp: {}", e))
+                        .to_wire_string()
+                })?;
 
             let mut output = String::new();
             while let Some(event) = rx.recv().await {
                 if let CommandEvent::Stdout(bytes) = event {
-                    output.push_str(&String::from_utf8_lossy(&bytes));
+                    output.push_str(&decode_process_output(&bytes));
                 }
             }
 
@@ -811,13 +1003,13 @@ pub async fn get_ytdlp_version_internal(app: &AppHandle) -> Result<YtdlpVersionI
             cmd.args(["--version"])
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped());
-            cmd.hide_window();
+            cmd.process_env(&env).hide_window().utf8_output();
 
             let output = cmd.output().await.map_err(|e| {
                 BackendError::from_message(format!("yt-dlp not found: {}", e)).to_wire_string()
             })?;
 
-            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let version = decode_process_output(&output.stdout).trim().to_string();
 
             let bin_path = get_system_ytdlp_path()
                 .map(|p| p.to_string_lossy().to_string())
@@ -1277,4 +1469,55 @@ mod tests {
         assert!(merged[..separator_index].contains(&"--user-agent".to_string()));
         assert!(merged[..separator_index].contains(&"--referer".to_string()));
     }
+
+    #[tokio::test]
+    async fn channel_override_only_applies_inside_scope() {
+        let current = || CHANNEL_OVERRIDE.try_with(YtdlpChannel::clone).ok();
+
+        assert_eq!(current(), None);
+        let inside = with_ytdlp_channel(Some("nightly"), async { current() }).await;
+        assert_eq!(inside, Some(YtdlpChannel::Nightly));
+        assert_eq!(with_ytdlp_channel(None, async { current() }).await, None);
+    }
+
+    #[test]
+    fn resolved_label_names_channel_and_fallback() {
+        let resolved = ResolvedYtdlp {
+            path: PathBuf::from("/bin/yt-dlp"),
+            is_bundled: true,
+            channel: Some(YtdlpChannel::Bundled),
+            fallback_from: Some(YtdlpChannel::Nightly),
+        };
+        assert_eq!(
+            resolved.label(),
+            "bundled (nightly not installed): /bin/yt-dlp"
+        );
+    }
+
+    #[tokio::test]
+    async fn json_run_maps_recorded_failure_to_friendly_error() {
+        use crate::services::FakeProcessRunner;
+        use crate::services::ProcessOutput;
+        use crate::types::parse_wire_error_string;
+
+        let runner = FakeProcessRunner::replay([
+            Ok(ProcessOutput::recorded(
+                "",
+                "ERROR: [youtube] abc123: Private video. Sign in if you've been granted access",
+                false,
+            )),
+            Ok(ProcessOutput::recorded("{\"id\":\"abc123\"}\n", "", true)),
+        ]);
+        let invocation = ProcessInvocation::binary("/bin/yt-dlp", &["-J", "abc123"]);
+
+        let error = run_ytdlp_json_using(&runner, &invocation)
+            .await
+            .unwrap_err();
+        let wire = parse_wire_error_string(&error).unwrap();
+        assert!(wire.message.starts_with("This video is private."));
+
+        let json = run_ytdlp_json_using(&runner, &invocation).await.unwrap();
+        assert_eq!(json, "{\"id\":\"abc123\"}\n");
+        assert_eq!(runner.calls()[1].args, vec!["-J", "abc123"]);
+    }
 }
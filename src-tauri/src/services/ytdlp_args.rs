@@ -1,7 +1,7 @@
 use crate::types::{code, BackendError};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct YtdlpAdvancedOption {
     pub id: String,
@@ -173,6 +173,7 @@ pub fn build_ytdlp_advanced_args(
 pub fn build_youtube_extractor_args(
     use_actual_player_js: bool,
     player_client: Option<&str>,
+    extra_parts: &[String],
 ) -> Option<String> {
     let mut parts = Vec::new();
     if use_actual_player_js {
@@ -183,6 +184,7 @@ pub fn build_youtube_extractor_args(
             parts.push(format!("player-client={}", player_client));
         }
     }
+    parts.extend(extra_parts.iter().cloned());
     if parts.is_empty() {
         None
     } else {
@@ -190,6 +192,24 @@ pub fn build_youtube_extractor_args(
     }
 }
 
+/// YouTube extractor arg parts limiting how many comments are fetched and in which order.
+/// Merged into the single `--extractor-args youtube:` value by the caller.
+pub fn build_youtube_comment_extractor_parts(
+    max_comments: Option<u32>,
+    comment_sort: Option<&str>,
+) -> Vec<String> {
+    let mut parts = Vec::new();
+    if let Some(max_comments) = max_comments.filter(|count| *count > 0) {
+        parts.push(format!("max_comments={}", max_comments));
+    }
+    match comment_sort {
+        Some("top") => parts.push("comment_sort=top".to_string()),
+        Some("new") => parts.push("comment_sort=new".to_string()),
+        _ => {}
+    }
+    parts
+}
+
 pub fn redact_ytdlp_advanced_args(args: &[String]) -> Vec<String> {
     let mut redacted = Vec::with_capacity(args.len());
     let mut redact_next_value_for: Option<&str> = None;
@@ -440,7 +460,7 @@ fn redact_header_value(header: &str) -> String {
     }
 }
 
-fn validation_error(message: impl Into<String>) -> BackendError {
+pub(super) fn validation_error(message: impl Into<String>) -> BackendError {
     BackendError::new(code::VALIDATION_INVALID_INPUT, message).with_retryable(false)
 }
 
@@ -550,7 +570,7 @@ mod tests {
 
     #[test]
     fn build_youtube_extractor_args_merges_actual_player_js_and_player_client() {
-        let merged = build_youtube_extractor_args(true, Some("web_safari"));
+        let merged = build_youtube_extractor_args(true, Some("web_safari"), &[]);
 
         assert_eq!(
             merged,
@@ -558,6 +578,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_youtube_extractor_args_merges_comment_limits() {
+        let comment_parts = build_youtube_comment_extractor_parts(Some(50), Some("top"));
+        let merged = build_youtube_extractor_args(false, None, &comment_parts);
+
+        assert_eq!(
+            merged,
+            Some("youtube:max_comments=50;comment_sort=top".to_string())
+        );
+        assert!(build_youtube_comment_extractor_parts(Some(0), Some("random")).is_empty());
+    }
+
     #[test]
     fn redact_ytdlp_advanced_args_redacts_custom_headers() {
         let redacted = redact_ytdlp_advanced_args(&[
@@ -0,0 +1,158 @@
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+
+use super::github::github_api_get;
+use super::ytdlp::{
+    effective_ytdlp_channel, get_channel_api_url, get_ytdlp_source, get_ytdlp_version_internal,
+};
+use crate::types::{BackendError, DependencySource, YtdlpChannel};
+
+const LATEST_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const LATEST_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Latest release tag per channel, so a failing queue doesn't hammer the GitHub API
+static LATEST_VERSION_CACHE: LazyLock<Mutex<Vec<(YtdlpChannel, Instant, String)>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Installed and latest yt-dlp version when the selected channel has a newer release
+pub struct YtdlpUpdateHint {
+    pub current_version: String,
+    pub latest_version: String,
+    pub channel: YtdlpChannel,
+    pub can_auto_update: bool,
+}
+
+/// Errors yt-dlp prints when a site changed and the extractor needs an update
+pub fn is_outdated_extractor_error(lines: &[String]) -> bool {
+    lines.iter().any(|line| {
+        let lower = line.to_lowercase();
+        lower.contains("unable to extract")
+            || lower.contains("nsig extraction failed")
+            || lower.contains("signature extraction failed")
+            || lower.contains("unable to decrypt nsig")
+            || (lower.contains("please report this issue") && lower.contains("yt-dlp -u"))
+    })
+}
+
+fn normalize_version(version: &str) -> &str {
+    version.trim().trim_start_matches('v')
+}
+
+/// yt-dlp versions are zero-padded dates (`2024.08.06`, nightly adds `.HHMMSS`)
+pub fn is_newer_ytdlp_version(current: &str, latest: &str) -> bool {
+    let (current, latest) = (normalize_version(current), normalize_version(latest));
+    !current.is_empty() && !latest.is_empty() && latest > current
+}
+
+fn cached_latest_version(channel: &YtdlpChannel) -> Option<String> {
+    let cache = LATEST_VERSION_CACHE.lock().ok()?;
+    cache
+        .iter()
+        .find(|(cached, fetched_at, _)| {
+            cached == channel && fetched_at.elapsed() < LATEST_CACHE_TTL
+        })
+        .map(|(_, _, version)| version.clone())
+}
+
+fn store_latest_version(channel: &YtdlpChannel, version: &str) {
+    if let Ok(mut cache) = LATEST_VERSION_CACHE.lock() {
+        cache.retain(|(cached, _, _)| cached != channel);
+        cache.push((channel.clone(), Instant::now(), version.to_string()));
+    }
+}
+
+/// Forget cached release tags, e.g. after the binary was updated
+pub fn clear_ytdlp_latest_version_cache() {
+    if let Ok(mut cache) = LATEST_VERSION_CACHE.lock() {
+        cache.clear();
+    }
+}
+
+async fn fetch_latest_version(channel: &YtdlpChannel) -> Option<String> {
+    if let Some(version) = cached_latest_version(channel) {
+        return Some(version);
+    }
+
+    // The bundled binary tracks stable releases
+    let api_url =
+        get_channel_api_url(channel).or_else(|| get_channel_api_url(&YtdlpChannel::Stable))?;
+    let release = tokio::time::timeout(LATEST_CHECK_TIMEOUT, github_api_get(api_url))
+        .await
+        .ok()?
+        .ok()?;
+    let tag = release.get("tag_name")?.as_str()?.trim().to_string();
+    if tag.is_empty() {
+        return None;
+    }
+
+    store_latest_version(channel, &tag);
+    Some(tag)
+}
+
+/// Compare the installed yt-dlp against the latest release of the selected
+/// channel. Returns `None` when up to date or when the check can't complete.
+pub async fn check_ytdlp_update_hint(app: &AppHandle) -> Option<YtdlpUpdateHint> {
+    let channel = effective_ytdlp_channel(app).await;
+    let current_version = get_ytdlp_version_internal(app).await.ok()?.version;
+    let latest_version = fetch_latest_version(&channel).await?;
+    if !is_newer_ytdlp_version(&current_version, &latest_version) {
+        return None;
+    }
+
+    Some(YtdlpUpdateHint {
+        current_version,
+        latest_version,
+        channel,
+        can_auto_update: get_ytdlp_source(app).await != DependencySource::System,
+    })
+}
+
+/// Attach the update hint to a download error caused by an outdated extractor
+pub fn with_ytdlp_update_hint(error: BackendError, hint: &YtdlpUpdateHint) -> BackendError {
+    let message = format!(
+        "{} An update is available ({} → {}), try updating yt-dlp.",
+        error.message(),
+        hint.current_version,
+        hint.latest_version
+    );
+    error
+        .with_message(message)
+        .with_retryable(true)
+        .with_param("ytdlpUpdateAvailable", true)
+        .with_param("currentVersion", hint.current_version.clone())
+        .with_param("latestVersion", hint.latest_version.clone())
+        .with_param("channel", hint.channel.as_str())
+        .with_param("canAutoUpdate", hint.can_auto_update)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outdated_extractor_errors_are_detected() {
+        let lines = vec![
+            "[youtube] abc: Downloading webpage".to_string(),
+            "ERROR: [youtube] abc: Unable to extract uploader id; please report this issue"
+                .to_string(),
+        ];
+        assert!(is_outdated_extractor_error(&lines));
+        assert!(is_outdated_extractor_error(&[
+            "WARNING: [youtube] nsig extraction failed: You may experience throttling".to_string()
+        ]));
+        assert!(!is_outdated_extractor_error(&[
+            "ERROR: [youtube] abc: Private video".to_string()
+        ]));
+    }
+
+    #[test]
+    fn newer_version_compares_date_tags() {
+        assert!(is_newer_ytdlp_version("2024.08.06", "2024.10.07"));
+        assert!(is_newer_ytdlp_version("2024.08.06", "v2024.08.06.232736"));
+        assert!(!is_newer_ytdlp_version("2024.10.07", "2024.10.07"));
+        assert!(!is_newer_ytdlp_version("2024.12.01", "2024.10.07"));
+        assert!(!is_newer_ytdlp_version("", "2024.10.07"));
+    }
+}
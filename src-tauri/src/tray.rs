@@ -0,0 +1,465 @@
+//! System tray icon and menu: followed channels, download queue summary
+//! and the remote download status, translated to the UI language
+
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::Emitter;
+
+use crate::{database, services, show_main_window};
+
+/// Current UI language for tray menu translations (default: "en")
+static TRAY_LANG: Mutex<String> = Mutex::new(String::new());
+
+/// Schedule status text for tray menu (empty = no schedule active)
+static TRAY_SCHEDULE_STATUS: Mutex<String> = Mutex::new(String::new());
+
+/// Download queue summary shown in the tray menu.
+static TRAY_DOWNLOAD_STATUS: Mutex<TrayDownloadStatus> = Mutex::new(TrayDownloadStatus {
+    pending: 0,
+    downloading: 0,
+    completed: 0,
+    error: 0,
+    active: false,
+});
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayDownloadStatus {
+    pending: u32,
+    downloading: u32,
+    completed: u32,
+    error: u32,
+    active: bool,
+}
+
+/// Tauri command: rebuild the system tray menu with current channel info
+#[tauri::command]
+pub fn rebuild_tray_menu_cmd(app: tauri::AppHandle, lang: Option<String>) {
+    if let Some(l) = lang {
+        if let Ok(mut stored) = TRAY_LANG.lock() {
+            *stored = l;
+        }
+    }
+    rebuild_tray_menu(&app);
+}
+
+/// Tauri command: update the schedule status shown in the tray menu
+#[tauri::command]
+pub fn update_tray_schedule(app: tauri::AppHandle, status: String) {
+    if let Ok(mut stored) = TRAY_SCHEDULE_STATUS.lock() {
+        *stored = status;
+    }
+    rebuild_tray_menu(&app);
+}
+
+/// Tauri command: update the download queue summary shown in the tray menu
+#[tauri::command]
+pub fn update_tray_download_status(app: tauri::AppHandle, status: TrayDownloadStatus) {
+    let mut should_rebuild = false;
+    if let Ok(mut stored) = TRAY_DOWNLOAD_STATUS.lock() {
+        if *stored != status {
+            *stored = status;
+            should_rebuild = true;
+        }
+    }
+    if should_rebuild {
+        rebuild_tray_menu(&app);
+    }
+}
+
+/// Setup system tray icon and menu
+pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    // Build a minimal initial menu (will be replaced by rebuild_tray_menu)
+    let show = MenuItemBuilder::with_id("show", "Open Youwee").build(app)?;
+    let quit = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
+    let menu = MenuBuilder::new(app)
+        .item(&show)
+        .separator()
+        .item(&quit)
+        .build()?;
+
+    #[cfg(target_os = "macos")]
+    let icon = tauri::image::Image::from_bytes(include_bytes!("../icons/tray-template.png"))
+        .expect("Failed to load tray icon");
+    #[cfg(not(target_os = "macos"))]
+    let icon = tauri::image::Image::from_bytes(include_bytes!("../icons/32x32.png"))
+        .expect("Failed to load tray icon");
+
+    let app_handle = app.handle().clone();
+    let app_handle_menu = app.handle().clone();
+
+    let tray_builder = TrayIconBuilder::with_id("main-tray")
+        .icon(icon)
+        .tooltip("Youwee")
+        .menu(&menu)
+        .on_menu_event(move |_tray, event| {
+            let id = event.id().as_ref();
+            if let Some(channel_id) = id.strip_prefix("ch_") {
+                // Channel item clicked: open app and navigate to channel
+                show_main_window(&app_handle_menu);
+                let _ = app_handle_menu.emit("tray-open-channel", channel_id.to_string());
+            } else {
+                match id {
+                    "check_now" => {
+                        services::polling::stop_polling();
+                        services::polling::start_polling(app_handle_menu.clone());
+                    }
+                    "settings" => {
+                        show_main_window(&app_handle_menu);
+                        let _ = app_handle_menu.emit("tray-open-settings", ());
+                    }
+                    "check_update" => {
+                        show_main_window(&app_handle_menu);
+                        let _ = app_handle_menu.emit("tray-check-update", ());
+                    }
+                    "show" => {
+                        show_main_window(&app_handle_menu);
+                    }
+                    "browser_extension" => {
+                        show_main_window(&app_handle_menu);
+                        let _ = app_handle_menu.emit("tray-open-extension", ());
+                    }
+                    "quit" => {
+                        // Polling stops in ExitRequested unless running jobs hold the exit
+                        app_handle_menu.exit(0);
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .on_tray_icon_event(move |_tray, event| {
+            // Left-click tray icon -> show/focus window
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(&app_handle);
+            }
+        });
+
+    #[cfg(target_os = "macos")]
+    let tray_builder = tray_builder.icon_as_template(true);
+
+    tray_builder.build(app)?;
+
+    // Populate tray menu with channel info
+    rebuild_tray_menu(&app.handle());
+
+    Ok(())
+}
+
+/// Rebuild the system tray menu with current followed channels and new video counts.
+/// Called after follow/unfollow, polling finds new videos, or downloads complete.
+pub fn rebuild_tray_menu(app_handle: &tauri::AppHandle) {
+    if let Err(e) = rebuild_tray_menu_inner(app_handle) {
+        log::error!("Failed to rebuild tray menu: {}", e);
+    }
+}
+
+/// Get the stored tray language code
+fn get_tray_lang() -> String {
+    TRAY_LANG.lock().map(|l| l.clone()).unwrap_or_default()
+}
+
+/// Translate a tray menu key based on the current language
+fn tray_text(key: &str) -> &'static str {
+    let lang = get_tray_lang();
+    let lang = if lang.is_empty() || lang.starts_with("en") {
+        "en"
+    } else if lang.starts_with("vi") {
+        "vi"
+    } else if lang.starts_with("zh") {
+        "zh-CN"
+    } else if lang.starts_with("fr") {
+        "fr"
+    } else if lang.starts_with("ja") {
+        "ja"
+    } else if lang.starts_with("es") {
+        "es"
+    } else {
+        "en"
+    };
+
+    match (lang, key) {
+        // Vietnamese
+        ("vi", "followed_channels") => "Kênh đang theo dõi",
+        ("vi", "no_channels") => "Chưa theo dõi kênh nào",
+        ("vi", "new_suffix") => "mới",
+        ("vi", "download_queue") => "Hàng đợi tải xuống",
+        ("vi", "downloading") => "Đang tải",
+        ("vi", "pending") => "Chờ tải",
+        ("vi", "completed") => "Hoàn tất",
+        ("vi", "errors") => "Lỗi",
+        ("vi", "idle") => "Rảnh",
+        ("vi", "remote_download") => "Tải từ xa",
+        ("vi", "running") => "Đang chạy",
+        ("vi", "disabled") => "Đã tắt",
+        ("vi", "check_all") => "Kiểm tra kênh theo dõi ngay",
+        ("vi", "settings") => "Cài đặt",
+        ("vi", "check_update") => "Kiểm tra cập nhật...",
+        ("vi", "open") => "Mở Youwee",
+        ("vi", "browser_extension") => "Browser Extension",
+        ("vi", "quit") => "Thoát",
+        // Chinese
+        ("zh-CN", "followed_channels") => "已关注的频道",
+        ("zh-CN", "no_channels") => "尚未关注任何频道",
+        ("zh-CN", "new_suffix") => "个新视频",
+        ("zh-CN", "download_queue") => "下载队列",
+        ("zh-CN", "downloading") => "下载中",
+        ("zh-CN", "pending") => "等待中",
+        ("zh-CN", "completed") => "已完成",
+        ("zh-CN", "errors") => "错误",
+        ("zh-CN", "idle") => "空闲",
+        ("zh-CN", "remote_download") => "远程下载",
+        ("zh-CN", "running") => "运行中",
+        ("zh-CN", "disabled") => "已禁用",
+        ("zh-CN", "check_all") => "立即检查已关注频道",
+        ("zh-CN", "settings") => "设置",
+        ("zh-CN", "check_update") => "检查更新...",
+        ("zh-CN", "open") => "打开 Youwee",
+        ("zh-CN", "browser_extension") => "浏览器扩展",
+        ("zh-CN", "quit") => "退出",
+        // French
+        ("fr", "followed_channels") => "Chaines suivies",
+        ("fr", "no_channels") => "Aucune chaine suivie",
+        ("fr", "new_suffix") => "nouvelles",
+        ("fr", "download_queue") => "File de telechargement",
+        ("fr", "downloading") => "Telechargement",
+        ("fr", "pending") => "En attente",
+        ("fr", "completed") => "Termines",
+        ("fr", "errors") => "Erreurs",
+        ("fr", "idle") => "Inactif",
+        ("fr", "remote_download") => "Telechargement distant",
+        ("fr", "running") => "En cours",
+        ("fr", "disabled") => "Desactive",
+        ("fr", "check_all") => "Verifier les chaines suivies maintenant",
+        ("fr", "settings") => "Parametres",
+        ("fr", "check_update") => "Verifier les mises a jour...",
+        ("fr", "open") => "Ouvrir Youwee",
+        ("fr", "browser_extension") => "Extension navigateur",
+        ("fr", "quit") => "Quitter",
+        // Japanese
+        ("ja", "followed_channels") => "フォロー中のチャンネル",
+        ("ja", "no_channels") => "フォロー中のチャンネルはありません",
+        ("ja", "new_suffix") => "件の新着",
+        ("ja", "download_queue") => "ダウンロードキュー",
+        ("ja", "downloading") => "ダウンロード中",
+        ("ja", "pending") => "待機中",
+        ("ja", "completed") => "完了",
+        ("ja", "errors") => "エラー",
+        ("ja", "idle") => "待機中",
+        ("ja", "remote_download") => "リモートダウンロード",
+        ("ja", "running") => "実行中",
+        ("ja", "disabled") => "無効",
+        ("ja", "check_all") => "フォロー中チャンネルを今すぐチェック",
+        ("ja", "settings") => "設定",
+        ("ja", "check_update") => "更新をチェック...",
+        ("ja", "open") => "Youwee を開く",
+        ("ja", "browser_extension") => "ブラウザ拡張機能",
+        ("ja", "quit") => "終了",
+        // Spanish
+        ("es", "followed_channels") => "Canales seguidos",
+        ("es", "no_channels") => "No sigues ningún canal",
+        ("es", "new_suffix") => "nuevos",
+        ("es", "download_queue") => "Cola de descargas",
+        ("es", "downloading") => "Descargando",
+        ("es", "pending") => "Pendiente",
+        ("es", "completed") => "Completado",
+        ("es", "errors") => "Errores",
+        ("es", "idle") => "Inactivo",
+        ("es", "remote_download") => "Descarga remota",
+        ("es", "running") => "Ejecutándose",
+        ("es", "disabled") => "Deshabilitado",
+        ("es", "check_all") => "Comprobar canales seguidos ahora",
+        ("es", "settings") => "Ajustes",
+        ("es", "check_update") => "Buscar actualizaciones...",
+        ("es", "open") => "Abrir Youwee",
+        ("es", "browser_extension") => "Extensión de navegador",
+        ("es", "quit") => "Salir",
+        // English (default)
+        (_, "followed_channels") => "Followed Channels",
+        (_, "no_channels") => "No channels followed",
+        (_, "new_suffix") => "new",
+        (_, "download_queue") => "Download Queue",
+        (_, "downloading") => "Downloading",
+        (_, "pending") => "Pending",
+        (_, "completed") => "Completed",
+        (_, "errors") => "Errors",
+        (_, "idle") => "Idle",
+        (_, "remote_download") => "Remote Download",
+        (_, "running") => "Running",
+        (_, "disabled") => "Disabled",
+        (_, "check_all") => "Check Followed Channels Now",
+        (_, "settings") => "Settings",
+        (_, "check_update") => "Check for Updates...",
+        (_, "open") => "Open Youwee",
+        (_, "browser_extension") => "Browser Extension",
+        (_, "quit") => "Quit",
+        _ => "???",
+    }
+}
+
+fn tray_download_status() -> TrayDownloadStatus {
+    TRAY_DOWNLOAD_STATUS
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or(TrayDownloadStatus {
+            pending: 0,
+            downloading: 0,
+            completed: 0,
+            error: 0,
+            active: false,
+        })
+}
+
+fn tray_download_summary(status: &TrayDownloadStatus) -> String {
+    let total = status.pending + status.downloading + status.completed + status.error;
+    let activity = if status.active || status.downloading > 0 {
+        tray_text("downloading")
+    } else {
+        tray_text("idle")
+    };
+    format!("{} ({})", activity, total)
+}
+
+fn tray_remote_status_label() -> String {
+    let status = services::telegram::get_status();
+    let state = match status.state {
+        services::telegram::TelegramStatusState::Running => tray_text("running"),
+        services::telegram::TelegramStatusState::Disabled => tray_text("disabled"),
+        services::telegram::TelegramStatusState::Error => tray_text("errors"),
+    };
+
+    if let Some(message) = status.message.filter(|message| !message.trim().is_empty()) {
+        format!("{}: {}", state, message)
+    } else {
+        state.to_string()
+    }
+}
+
+fn rebuild_tray_menu_inner(
+    app_handle: &tauri::AppHandle,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let channels = database::get_followed_channels_db().unwrap_or_default();
+    let channel_count = channels.len();
+    let download_status = tray_download_status();
+
+    // Build channel submenu
+    let submenu_label = format!("{} ({})", tray_text("followed_channels"), channel_count);
+    let mut submenu = SubmenuBuilder::new(app_handle, &submenu_label);
+
+    if channels.is_empty() {
+        let item = MenuItemBuilder::with_id("no_channels", tray_text("no_channels"))
+            .enabled(false)
+            .build(app_handle)?;
+        submenu = submenu.item(&item);
+    } else {
+        for ch in &channels {
+            let count = database::get_new_videos_count_db(Some(ch.id.clone())).unwrap_or(0);
+            let label = if count > 0 {
+                format!("{} ({} {})", ch.name, count, tray_text("new_suffix"))
+            } else {
+                ch.name.clone()
+            };
+            let item_id = format!("ch_{}", ch.id);
+            let item = MenuItemBuilder::with_id(item_id, &label).build(app_handle)?;
+            submenu = submenu.item(&item);
+        }
+    }
+
+    let built_submenu = submenu.build()?;
+    let download_label = format!(
+        "{} - {}",
+        tray_text("download_queue"),
+        tray_download_summary(&download_status)
+    );
+    let download_status_item = MenuItemBuilder::with_id("download_status", &download_label)
+        .enabled(false)
+        .build(app_handle)?;
+    let download_pending_item = MenuItemBuilder::with_id(
+        "download_pending",
+        format!("{}: {}", tray_text("pending"), download_status.pending),
+    )
+    .enabled(false)
+    .build(app_handle)?;
+    let download_downloading_item = MenuItemBuilder::with_id(
+        "download_downloading",
+        format!(
+            "{}: {}",
+            tray_text("downloading"),
+            download_status.downloading
+        ),
+    )
+    .enabled(false)
+    .build(app_handle)?;
+    let download_completed_item = MenuItemBuilder::with_id(
+        "download_completed",
+        format!("{}: {}", tray_text("completed"), download_status.completed),
+    )
+    .enabled(false)
+    .build(app_handle)?;
+    let download_error_item = MenuItemBuilder::with_id(
+        "download_error",
+        format!("{}: {}", tray_text("errors"), download_status.error),
+    )
+    .enabled(false)
+    .build(app_handle)?;
+    let download_submenu = SubmenuBuilder::new(app_handle, tray_text("download_queue"))
+        .item(&download_status_item)
+        .separator()
+        .item(&download_downloading_item)
+        .item(&download_pending_item)
+        .item(&download_completed_item)
+        .item(&download_error_item)
+        .build()?;
+
+    let remote_status = MenuItemBuilder::with_id(
+        "remote_download_status",
+        format!(
+            "{} - {}",
+            tray_text("remote_download"),
+            tray_remote_status_label()
+        ),
+    )
+    .enabled(false)
+    .build(app_handle)?;
+
+    // Build full menu
+    let check_now =
+        MenuItemBuilder::with_id("check_now", tray_text("check_all")).build(app_handle)?;
+    let settings = MenuItemBuilder::with_id("settings", tray_text("settings")).build(app_handle)?;
+    let check_update =
+        MenuItemBuilder::with_id("check_update", tray_text("check_update")).build(app_handle)?;
+    let show = MenuItemBuilder::with_id("show", tray_text("open")).build(app_handle)?;
+    let browser_extension =
+        MenuItemBuilder::with_id("browser_extension", tray_text("browser_extension"))
+            .build(app_handle)?;
+    let quit = MenuItemBuilder::with_id("quit", tray_text("quit")).build(app_handle)?;
+
+    let menu = MenuBuilder::new(app_handle)
+        .item(&show)
+        .separator()
+        .item(&download_submenu)
+        .item(&remote_status)
+        .separator()
+        .item(&built_submenu)
+        .item(&check_now)
+        .separator()
+        .item(&settings)
+        .item(&browser_extension)
+        .separator()
+        .item(&check_update)
+        .separator()
+        .item(&quit)
+        .build()?;
+
+    if let Some(tray) = app_handle.tray_by_id("main-tray") {
+        tray.set_menu(Some(menu))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// One AI request as stored in the `ai_usage` table
+#[derive(Clone, Debug, PartialEq)]
+pub struct AIUsageEntry {
+    pub feature: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// `None` for models without a known price
+    pub cost_usd: Option<f64>,
+    pub success: bool,
+}
+
+/// Requests of one feature on one day
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AIUsageBucket {
+    pub day: String,
+    pub feature: String,
+    pub requests: u64,
+    pub failures: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+    /// Requests to models without a known price, left out of `cost_usd`
+    pub unpriced_requests: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AIUsageReport {
+    /// First day included, `None` for all time
+    pub since: Option<String>,
+    pub buckets: Vec<AIUsageBucket>,
+    pub total_prompt_tokens: u64,
+    pub total_completion_tokens: u64,
+    pub total_cost_usd: f64,
+    pub month_cost_usd: f64,
+    pub monthly_budget_usd: Option<f64>,
+    pub budget_exceeded: bool,
+}
+
+/// Saved natural-language request for the processing assistant, with
+/// `{variable}` placeholders such as `{selection}` or `{target_size}`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub template: String,
+    pub variables: Vec<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Shareable JSON file of prompt templates
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateBundle {
+    pub version: u32,
+    pub templates: Vec<PromptTemplateBundleItem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateBundleItem {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub template: String,
+}
@@ -33,6 +33,7 @@ pub struct FollowedChannel {
     pub download_audio_bitrate: String,          // audio bitrate (128, 192, 256, 320, auto)
     pub download_preferred_fps: String,          // original, 30
     pub youtube_content_type: String,            // videos, shorts, streams, videos_shorts
+    pub filter_skip_shorts: bool,                // leave Shorts/Reels out of auto-download
 }
 
 /// A video belonging to a followed channel
@@ -112,6 +112,69 @@ pub struct DenoStatus {
     pub is_system: bool,
 }
 
+/// Outcome of the last yt-dlp, FFmpeg or Deno check, kept across restarts
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct DependencyHealth {
+    pub installed: bool,
+    pub version: Option<String>,
+    /// Unix seconds; `None` when the dependency was never checked
+    pub checked_at: Option<i64>,
+}
+
+/// Result of running a trivial script through the Deno runtime
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JsRuntimeCheck {
+    pub installed: bool,
+    pub working: bool,
+    pub binary_path: Option<String>,
+    pub is_system: bool,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// What the first-run setup still has to take care of
+#[derive(Clone, Serialize, Debug)]
+pub struct SetupStatus {
+    /// Onboarding was finished before
+    pub completed: bool,
+    pub ytdlp_installed: bool,
+    pub ffmpeg_installed: bool,
+    pub deno_installed: bool,
+    /// Requested folder, or the system Downloads folder as a suggestion
+    pub download_folder: Option<String>,
+    pub download_folder_writable: bool,
+    pub download_folder_error: Option<String>,
+    /// Steps still needed: "ytdlp", "ffmpeg", "deno", "download_folder"
+    pub missing: Vec<String>,
+}
+
+/// Steps picked on the onboarding screen
+#[derive(Clone, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct SetupChoices {
+    pub install_ytdlp: bool,
+    pub install_ffmpeg: bool,
+    pub install_deno: bool,
+    pub download_folder: Option<String>,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct SetupStepResult {
+    pub step: String,
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct SetupResult {
+    /// Every chosen step succeeded
+    pub completed: bool,
+    /// Validated download folder, when one was chosen
+    pub download_folder: Option<String>,
+    pub steps: Vec<SetupStepResult>,
+}
+
 /// gallery-dl installation status
 #[derive(Clone, Serialize, Debug)]
 pub struct GalleryDlStatus {
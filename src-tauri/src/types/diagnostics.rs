@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// Time spent in one stage of a yt-dlp extraction
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionPhaseTiming {
+    /// "startup", "metadata", "player_js" or "formats"
+    pub phase: String,
+    pub duration_ms: u64,
+    pub line_count: usize,
+}
+
+/// Result of `test_extraction`: where a simulated download spends its time
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionTestReport {
+    pub url: String,
+    pub success: bool,
+    pub total_ms: u64,
+    pub phases: Vec<ExtractionPhaseTiming>,
+    pub slowest_phase: Option<String>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+    pub error_code: Option<String>,
+    pub used_cookies: bool,
+    pub used_proxy: bool,
+}
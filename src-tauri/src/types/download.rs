@@ -1,24 +1,239 @@
-use serde::Serialize;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Serialize)]
-pub struct DownloadProgress {
-    pub id: String,
-    pub percent: f64,
-    pub speed: String,
-    pub eta: String,
-    pub status: String,
+/// Outcome of one entry of a download (a playlist item or the single video)
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadItemStatus {
+    #[default]
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadItemResult {
+    pub playlist_index: Option<u32>,
     pub title: Option<String>,
+    pub filepath: Option<String>,
+    pub history_id: Option<String>,
+    pub status: DownloadItemStatus,
+    pub error: Option<String>,
+}
+
+/// Result of `download_video`, one item per playlist entry or output file
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadSummary {
+    pub succeeded: u32,
+    pub failed: u32,
+    pub skipped: u32,
+    /// Some entries failed while others finished
+    pub partial: bool,
+    pub items: Vec<DownloadItemResult>,
+    /// Set for `simulate` runs, where nothing was downloaded
+    pub simulation: Option<Vec<SimulatedDownloadItem>>,
+}
+
+/// What one entry of a simulated download would produce
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedDownloadItem {
     pub playlist_index: Option<u32>,
-    pub playlist_count: Option<u32>,
-    pub filesize: Option<u64>,
-    pub resolution: Option<String>,
-    pub format_ext: Option<String>,
-    pub error_message: Option<String>,
-    pub error_code: Option<String>,
-    pub error_params: Option<Value>,
-    pub history_id: Option<String>, // Related history entry id (set when finished)
-    pub filepath: Option<String>,   // Final output path when finished
-    pub downloaded_size: Option<String>, // For live streams: "2.87 MiB"
-    pub elapsed_time: Option<String>, // For live streams: "00:00:07"
+    pub title: Option<String>,
+    pub format_id: Option<String>,
+    /// Human readable format description, e.g. `137 - 1920x1080 (1080p)+140 - audio only`
+    pub format: Option<String>,
+    pub ext: Option<String>,
+    pub estimated_size: Option<u64>,
+    pub filename: String,
+}
+
+impl DownloadSummary {
+    pub fn from_items(mut items: Vec<DownloadItemResult>) -> Self {
+        items.sort_by_key(|item| item.playlist_index.unwrap_or(0));
+        let count = |status| items.iter().filter(|item| item.status == status).count() as u32;
+        let (succeeded, failed, skipped) = (
+            count(DownloadItemStatus::Succeeded),
+            count(DownloadItemStatus::Failed),
+            count(DownloadItemStatus::Skipped),
+        );
+        Self {
+            succeeded,
+            failed,
+            skipped,
+            partial: failed > 0 && succeeded + skipped > 0,
+            items,
+            simulation: None,
+        }
+    }
+}
+
+/// Last known state of a running download, journaled so a crash can be
+/// reported on the next start instead of losing the job silently
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJournalEntry {
+    pub id: String,
+    pub url: String,
+    pub output_path: String,
+    pub output_template: String,
+    pub args: Vec<String>,
+    pub title: Option<String>,
+    pub stage: Option<String>,
+    pub percent: f64,
+    pub downloaded_size: Option<String>,
+    pub started_at: i64,
+    pub updated_at: i64,
+    /// Left over from an earlier run that ended before the download did
+    pub interrupted: bool,
+}
+
+/// Settings a download profile captures; unset fields keep the values the
+/// download was started with
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProfileSettings {
+    pub quality: Option<String>,
+    pub format: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_bitrate: Option<String>,
+    pub subtitle_mode: Option<String>,
+    pub subtitle_langs: Option<String>,
+    pub subtitle_embed: Option<bool>,
+    pub subtitle_format: Option<String>,
+    pub cookie_mode: Option<String>,
+    pub cookie_browser: Option<String>,
+    pub cookie_browser_profile: Option<String>,
+    pub cookie_file_path: Option<String>,
+    pub proxy_url: Option<String>,
+    /// Output folder the profile saves into
+    pub output_path: Option<String>,
+}
+
+/// A recently downloaded URL with the options it was downloaded with
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentInput {
+    pub url: String,
+    /// Channel or account the URL belongs to, when the URL shows it
+    pub channel: Option<String>,
+    pub options: DownloadProfileSettings,
+    pub used_at: i64,
+}
+
+/// Options suggested for a pasted URL, from its own last download or the
+/// last download from the same channel
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedInputOptions {
+    /// "url" or "channel"
+    pub matched_by: String,
+    pub input: RecentInput,
+}
+
+/// Changes for re-downloading a history entry; unset settings reuse the
+/// entry's previous ones
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedownloadOptions {
+    #[serde(flatten)]
+    pub settings: DownloadProfileSettings,
+    pub profile_id: Option<String>,
+    /// Add a linked sibling entry instead of replacing the existing one
+    pub keep_original: Option<bool>,
+    /// Remove the previous file once the new download succeeded
+    pub delete_old_file: Option<bool>,
+}
+
+/// Named set of download settings, e.g. "4K archive" or "quick audio"
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProfile {
+    pub id: String,
+    pub name: String,
+    pub settings: DownloadProfileSettings,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Download settings always used for one channel, e.g. "videos from this
+/// podcast channel download as audio into Podcasts"
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelRule {
+    pub id: String,
+    /// yt-dlp `extractor_key` of the channel's site, e.g. "Youtube"; empty on
+    /// rules saved before it was recorded
+    pub extractor_key: String,
+    /// yt-dlp `channel_id`, or `uploader_id` on sites without channels
+    pub channel_id: String,
+    pub channel_name: String,
+    pub settings: DownloadProfileSettings,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Post-download stage of a recipe; stages run in order on the downloaded file
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum RecipeStep {
+    /// Convert to an audio-only file; the downloaded video is removed unless kept
+    ExtractAudio {
+        format: String,
+        bitrate: Option<String>,
+        #[serde(default)]
+        keep_video: bool,
+    },
+    /// EBU R128 loudness normalization, -16 LUFS by default
+    NormalizeLoudness {
+        target_lufs: Option<f64>,
+    },
+    /// Write tags; `{title}` is replaced with the downloaded title
+    Tag {
+        title: Option<String>,
+        artist: Option<String>,
+        album: Option<String>,
+        genre: Option<String>,
+    },
+    MoveTo {
+        folder: String,
+    },
+}
+
+impl RecipeStep {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RecipeStep::ExtractAudio { .. } => "extract_audio",
+            RecipeStep::NormalizeLoudness { .. } => "normalize_loudness",
+            RecipeStep::Tag { .. } => "tag",
+            RecipeStep::MoveTo { .. } => "move_to",
+        }
+    }
+}
+
+/// Download settings plus post-processing stages, e.g. "1080p → audio →
+/// normalize → tag → Music"
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadRecipe {
+    pub id: String,
+    pub name: String,
+    pub download: DownloadProfileSettings,
+    pub steps: Vec<RecipeStep>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Outcome of one URL of `apply_download_recipe`
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeRunItem {
+    pub url: String,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
 }
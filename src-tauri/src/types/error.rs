@@ -7,7 +7,9 @@ pub mod code {
     pub const BACKEND_UNKNOWN: &str = "BACKEND_UNKNOWN";
     pub const VALIDATION_INVALID_URL: &str = "VALIDATION_INVALID_URL";
     pub const VALIDATION_INVALID_INPUT: &str = "VALIDATION_INVALID_INPUT";
+    pub const UNSUPPORTED_URL: &str = "UNSUPPORTED_URL";
     pub const DOWNLOAD_CANCELLED: &str = "DOWNLOAD_CANCELLED";
+    pub const CONFIRM_REQUIRED: &str = "CONFIRM_REQUIRED";
     pub const TRANSCRIPT_NOT_AVAILABLE: &str = "TRANSCRIPT_NOT_AVAILABLE";
     pub const YT_RATE_LIMITED: &str = "YT_RATE_LIMITED";
     pub const YT_PRIVATE_VIDEO: &str = "YT_PRIVATE_VIDEO";
@@ -23,12 +25,15 @@ pub mod code {
     pub const YT_COOKIE_DB_LOCKED: &str = "YT_COOKIE_DB_LOCKED";
     pub const YT_FRESH_COOKIES_REQUIRED: &str = "YT_FRESH_COOKIES_REQUIRED";
     pub const NETWORK_TIMEOUT: &str = "NETWORK_TIMEOUT";
+    pub const METADATA_TIMEOUT: &str = "METADATA_TIMEOUT";
     pub const NETWORK_REQUEST_FAILED: &str = "NETWORK_REQUEST_FAILED";
     pub const PROCESS_START_FAILED: &str = "PROCESS_START_FAILED";
     pub const PROCESS_EXECUTION_FAILED: &str = "PROCESS_EXECUTION_FAILED";
     pub const PROCESS_EXIT_NON_ZERO: &str = "PROCESS_EXIT_NON_ZERO";
     pub const PARSE_FAILED: &str = "PARSE_FAILED";
     pub const IO_OPERATION_FAILED: &str = "IO_OPERATION_FAILED";
+    pub const OUTPUT_DIR_NOT_FOUND: &str = "OUTPUT_DIR_NOT_FOUND";
+    pub const OUTPUT_DIR_NOT_WRITABLE: &str = "OUTPUT_DIR_NOT_WRITABLE";
     pub const DB_OPERATION_FAILED: &str = "DB_OPERATION_FAILED";
     pub const YTDLP_NOT_FOUND: &str = "YTDLP_NOT_FOUND";
     pub const YTDLP_SYSTEM_NOT_FOUND: &str = "YTDLP_SYSTEM_NOT_FOUND";
@@ -38,6 +43,9 @@ pub mod code {
     pub const ARIA2_NOT_FOUND: &str = "ARIA2_NOT_FOUND";
     pub const FFMPEG_NOT_FOUND: &str = "FFMPEG_NOT_FOUND";
     pub const FFMPEG_SYSTEM_MANAGED: &str = "FFMPEG_SYSTEM_MANAGED";
+    pub const JS_RUNTIME_BROKEN: &str = "JS_RUNTIME_BROKEN";
+    pub const BINARY_BLOCKED: &str = "BINARY_BLOCKED";
+    pub const INSTALL_IN_PROGRESS: &str = "INSTALL_IN_PROGRESS";
     pub const AI_API_ERROR: &str = "AI_API_ERROR";
     pub const AI_NO_API_KEY: &str = "AI_NO_API_KEY";
     pub const AI_NO_TRANSCRIPT: &str = "AI_NO_TRANSCRIPT";
@@ -89,6 +97,11 @@ impl BackendError {
         self
     }
 
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.wire.message = message.into();
+        self
+    }
+
     pub fn with_retryable(mut self, retryable: bool) -> Self {
         self.wire.retryable = Some(retryable);
         self
@@ -159,6 +172,9 @@ pub fn infer_error_code(message: &str) -> &'static str {
     if m.contains("download cancelled") || m.contains("canceled") || m.contains("cancelled") {
         return code::DOWNLOAD_CANCELLED;
     }
+    if m.contains("unsupported url") {
+        return code::UNSUPPORTED_URL;
+    }
     if m.contains("no transcript available") || m.contains("no subtitles") {
         return code::TRANSCRIPT_NOT_AVAILABLE;
     }
@@ -235,6 +251,12 @@ pub fn infer_error_code(message: &str) -> &'static str {
     if m.contains("ffmpeg not found") || m.contains("ffprobe not found") {
         return code::FFMPEG_NOT_FOUND;
     }
+    if m.contains("was blocked from running") {
+        return code::BINARY_BLOCKED;
+    }
+    if m.contains("javascript runtime") && m.contains("broken") {
+        return code::JS_RUNTIME_BROKEN;
+    }
     if m.contains("timed out") || m.contains("timeout") {
         return code::NETWORK_TIMEOUT;
     }
@@ -300,6 +322,7 @@ pub fn default_retryable(code: &str) -> bool {
     matches!(
         code,
         code::NETWORK_TIMEOUT
+            | code::METADATA_TIMEOUT
             | code::NETWORK_REQUEST_FAILED
             | code::YT_RATE_LIMITED
             | code::PROCESS_START_FAILED
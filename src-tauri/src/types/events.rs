@@ -0,0 +1,944 @@
+//! Payloads of the events the backend emits, with their wire contract.
+//!
+//! Every event is sent as its payload fields plus `type` and `version`, so
+//! listeners written against the bare payload keep working. Bump an event's
+//! version whenever a field is renamed, removed or changes type.
+
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Emitter};
+
+use super::{DependencyHealth, DownloadIntegrityReport};
+
+/// A payload type with a JSON schema for the frontend type generator
+pub trait EventPayload: Serialize + Clone {
+    const TYPE_NAME: &'static str;
+    fn schema() -> Value;
+}
+
+/// Name and version of one event, tied to its payload type
+pub struct EventContract<T> {
+    pub name: &'static str,
+    pub version: u32,
+    payload: PhantomData<fn() -> T>,
+}
+
+impl<T: EventPayload> EventContract<T> {
+    pub const fn new(name: &'static str, version: u32) -> Self {
+        Self {
+            name,
+            version,
+            payload: PhantomData,
+        }
+    }
+
+    pub fn envelope<'a>(&self, payload: &'a T) -> EventEnvelope<'a, T> {
+        EventEnvelope {
+            event_type: self.name,
+            version: self.version,
+            payload,
+        }
+    }
+
+    pub fn emit(&self, app: &AppHandle, payload: &T) -> tauri::Result<()> {
+        app.emit(self.name, self.envelope(payload))
+    }
+
+    fn describe(&self) -> Value {
+        json!({
+            "name": self.name,
+            "version": self.version,
+            "payload": { "$ref": format!("#/definitions/{}", T::TYPE_NAME) },
+        })
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct EventEnvelope<'a, T> {
+    #[serde(rename = "type")]
+    pub event_type: &'static str,
+    pub version: u32,
+    #[serde(flatten)]
+    pub payload: &'a T,
+}
+
+pub const DOWNLOAD_PROGRESS: EventContract<DownloadProgress> =
+    EventContract::new("download-progress", 1);
+pub const PROCESSING_PROGRESS: EventContract<ProcessingProgress> =
+    EventContract::new("processing-progress", 1);
+pub const METADATA_PROGRESS: EventContract<MetadataProgress> =
+    EventContract::new("metadata-progress", 1);
+pub const FFMPEG_DOWNLOAD_PROGRESS: EventContract<BinaryDownloadProgress> =
+    EventContract::new("ffmpeg-download-progress", 1);
+pub const DENO_DOWNLOAD_PROGRESS: EventContract<BinaryDownloadProgress> =
+    EventContract::new("deno-download-progress", 1);
+pub const SETUP_PROGRESS: EventContract<SetupProgress> = EventContract::new("setup-progress", 1);
+pub const WHISPER_MODEL_DOWNLOAD_PROGRESS: EventContract<WhisperModelDownloadProgress> =
+    EventContract::new("whisper-model-download-progress", 1);
+pub const PROCESSING_PLAN_PROGRESS: EventContract<ProcessingPlanProgress> =
+    EventContract::new("processing-plan-progress", 1);
+pub const PROCESSING_GRAPH_PROGRESS: EventContract<ProcessingGraphProgress> =
+    EventContract::new("processing-graph-progress", 1);
+pub const RECIPE_PROGRESS: EventContract<RecipeProgress> = EventContract::new("recipe-progress", 1);
+pub const APP_HEALTH: EventContract<AppHealth> = EventContract::new("app-health", 1);
+pub const AUDIO_COMPANION: EventContract<AudioCompanionResult> =
+    EventContract::new("audio-companion", 1);
+pub const PREVIEW_PROGRESS: EventContract<PreviewProgress> =
+    EventContract::new("preview-progress", 1);
+pub const AUDIO_BATCH_PROGRESS: EventContract<AudioBatchProgress> =
+    EventContract::new("audio-batch-progress", 1);
+pub const SUMMARY_PROGRESS: EventContract<SummaryProgress> =
+    EventContract::new("summary-progress", 1);
+pub const QUIT_REQUESTED: EventContract<QuitRequest> = EventContract::new("quit-requested", 1);
+pub const POST_QUEUE_ACTION: EventContract<PostQueueActionEvent> =
+    EventContract::new("post-queue-action", 1);
+pub const DOWNLOAD_RETRY: EventContract<DownloadRetryRequest> =
+    EventContract::new("download-retry", 1);
+pub const DOWNLOAD_INTEGRITY: EventContract<DownloadIntegrityReport> =
+    EventContract::new("download-integrity", 1);
+pub const DATABASE_RECOVERED: EventContract<DatabaseRecoveryReport> =
+    EventContract::new("database-recovered", 1);
+
+/// Events still sent with a bare `app.emit`; their payloads (plain strings,
+/// unit, hand-built JSON) predate the contracts and the frontend relies on them
+#[cfg(test)]
+const UNTYPED_EVENTS: &[&str] = &[
+    "channel-auto-download",
+    "channel-fetch-progress",
+    "channel-new-videos",
+    "external-cli-download",
+    "external-open-url",
+    "plugin-execution-output",
+    "plugin-execution-status",
+    "telegram-download-command",
+    "tray-check-update",
+    "tray-open-channel",
+    "tray-open-extension",
+    "tray-open-settings",
+];
+
+/// JSON type of a payload field; `None` accepts any value
+type FieldSpec = (&'static str, Option<&'static str>, bool);
+
+/// Object schema from `(name, json type, nullable)` fields. The envelope's
+/// `type` and `version` are added to every payload.
+fn object_schema(fields: &[FieldSpec]) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![json!("type"), json!("version")];
+    properties.insert("type".to_string(), json!({ "type": "string" }));
+    properties.insert("version".to_string(), json!({ "type": "integer" }));
+    for (name, json_type, nullable) in fields {
+        let schema = match (json_type, nullable) {
+            (None, _) => json!({}),
+            (Some(json_type), true) => json!({ "type": [json_type, "null"] }),
+            (Some(json_type), false) => json!({ "type": json_type }),
+        };
+        properties.insert(name.to_string(), schema);
+        if !nullable {
+            required.push(json!(name));
+        }
+    }
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+/// Progress of a yt-dlp, gallery-dl, direct or podcast download
+#[derive(Clone, Default, Serialize)]
+pub struct DownloadProgress {
+    pub id: String,
+    pub percent: f64,
+    pub speed: String,
+    pub eta: String,
+    pub status: String,
+    pub title: Option<String>,
+    pub playlist_index: Option<u32>,
+    pub playlist_count: Option<u32>,
+    pub filesize: Option<u64>,
+    pub resolution: Option<String>,
+    pub format_ext: Option<String>,
+    pub error_message: Option<String>,
+    pub error_code: Option<String>,
+    pub error_params: Option<Value>,
+    pub history_id: Option<String>, // Related history entry id (set when finished)
+    pub filepath: Option<String>,   // Final output path when finished
+    pub downloaded_size: Option<String>, // For live streams: "2.87 MiB"
+    pub elapsed_time: Option<String>, // For live streams: "00:00:07"
+    pub stage: Option<String>,      // pre_processing | downloading | merging | post_processing
+    pub stage_detail: Option<String>, // yt-dlp marker, e.g. "Merger" or "EmbedThumbnail"
+    pub indeterminate: bool,        // No percentage available for this stage
+}
+
+impl EventPayload for DownloadProgress {
+    const TYPE_NAME: &'static str = "DownloadProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("id", Some("string"), false),
+            ("percent", Some("number"), false),
+            ("speed", Some("string"), false),
+            ("eta", Some("string"), false),
+            ("status", Some("string"), false),
+            ("title", Some("string"), true),
+            ("playlist_index", Some("integer"), true),
+            ("playlist_count", Some("integer"), true),
+            ("filesize", Some("integer"), true),
+            ("resolution", Some("string"), true),
+            ("format_ext", Some("string"), true),
+            ("error_message", Some("string"), true),
+            ("error_code", Some("string"), true),
+            ("error_params", None, true),
+            ("history_id", Some("string"), true),
+            ("filepath", Some("string"), true),
+            ("downloaded_size", Some("string"), true),
+            ("elapsed_time", Some("string"), true),
+            ("stage", Some("string"), true),
+            ("stage_detail", Some("string"), true),
+            ("indeterminate", Some("boolean"), false),
+        ])
+    }
+}
+
+/// FFmpeg progress of a processing job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessingProgress {
+    pub job_id: String,
+    pub percent: f64,
+    pub frame: i64,
+    pub total_frames: i64,
+    pub fps: f64,
+    pub speed: String,
+    pub time: String,
+    pub size: String,
+}
+
+impl EventPayload for ProcessingProgress {
+    const TYPE_NAME: &'static str = "ProcessingProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("job_id", Some("string"), false),
+            ("percent", Some("number"), false),
+            ("frame", Some("integer"), false),
+            ("total_frames", Some("integer"), false),
+            ("fps", Some("number"), false),
+            ("speed", Some("string"), false),
+            ("time", Some("string"), false),
+            ("size", Some("string"), false),
+        ])
+    }
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct MetadataProgress {
+    pub id: String,
+    pub status: String, // "fetching", "finished", "error"
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    pub error_message: Option<String>,
+    pub error_code: Option<String>,
+    pub error_params: Option<Value>,
+}
+
+impl EventPayload for MetadataProgress {
+    const TYPE_NAME: &'static str = "MetadataProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("id", Some("string"), false),
+            ("status", Some("string"), false),
+            ("title", Some("string"), true),
+            ("thumbnail", Some("string"), true),
+            ("error_message", Some("string"), true),
+            ("error_code", Some("string"), true),
+            ("error_params", None, true),
+        ])
+    }
+}
+
+/// Progress of an FFmpeg or Deno binary install
+#[derive(Clone, Default, Serialize)]
+pub struct BinaryDownloadProgress {
+    pub stage: String,
+    pub percent: u8,
+    pub downloaded: u64,
+    pub total: u64,
+}
+
+impl EventPayload for BinaryDownloadProgress {
+    const TYPE_NAME: &'static str = "BinaryDownloadProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("stage", Some("string"), false),
+            ("percent", Some("integer"), false),
+            ("downloaded", Some("integer"), false),
+            ("total", Some("integer"), false),
+        ])
+    }
+}
+
+/// Progress of a local Whisper model download
+#[derive(Clone, Default, Serialize)]
+pub struct WhisperModelDownloadProgress {
+    pub model_id: String,
+    /// "downloading", "verifying" or "complete"
+    pub stage: String,
+    pub percent: u8,
+    pub downloaded: u64,
+    pub total: u64,
+}
+
+impl EventPayload for WhisperModelDownloadProgress {
+    const TYPE_NAME: &'static str = "WhisperModelDownloadProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("model_id", Some("string"), false),
+            ("stage", Some("string"), false),
+            ("percent", Some("integer"), false),
+            ("downloaded", Some("integer"), false),
+            ("total", Some("integer"), false),
+        ])
+    }
+}
+
+/// Step-level progress of a multi-step processing plan; each step's FFmpeg
+/// progress arrives as `processing-progress` under `job_id`
+#[derive(Clone, Default, Serialize)]
+pub struct ProcessingPlanProgress {
+    pub plan_id: String,
+    pub step_index: usize,
+    /// Position among the approved steps, from 1
+    pub step_number: usize,
+    pub step_count: usize,
+    pub job_id: String,
+    /// "running", "completed" or "failed"
+    pub status: String,
+    pub error: Option<String>,
+}
+
+impl EventPayload for ProcessingPlanProgress {
+    const TYPE_NAME: &'static str = "ProcessingPlanProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("plan_id", Some("string"), false),
+            ("step_index", Some("integer"), false),
+            ("step_number", Some("integer"), false),
+            ("step_count", Some("integer"), false),
+            ("job_id", Some("string"), false),
+            ("status", Some("string"), false),
+            ("error", Some("string"), true),
+        ])
+    }
+}
+
+/// Combined progress of jobs linked by dependencies
+#[derive(Clone, Default, Serialize)]
+pub struct ProcessingChainProgress {
+    pub job_ids: Vec<String>,
+    pub percent: f64,
+    pub status: String,
+}
+
+/// A job in a dependency graph changed status. Running FFmpeg jobs also
+/// report `processing-progress` under their own id.
+#[derive(Clone, Default, Serialize)]
+pub struct ProcessingGraphProgress {
+    pub graph_id: String,
+    pub job_id: String,
+    /// "running", "completed", "failed" or "skipped"
+    pub status: String,
+    pub error: Option<String>,
+    pub finished: usize,
+    pub total: usize,
+    pub chains: Vec<ProcessingChainProgress>,
+}
+
+impl EventPayload for ProcessingGraphProgress {
+    const TYPE_NAME: &'static str = "ProcessingGraphProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("graph_id", Some("string"), false),
+            ("job_id", Some("string"), false),
+            ("status", Some("string"), false),
+            ("error", Some("string"), true),
+            ("finished", Some("integer"), false),
+            ("total", Some("integer"), false),
+            ("chains", Some("array"), false),
+        ])
+    }
+}
+
+/// Stage change of a recipe run. The download stage reports
+/// `download-progress` and FFmpeg stages `processing-progress` under `job_id`.
+#[derive(Clone, Default, Serialize)]
+pub struct RecipeProgress {
+    pub run_id: String,
+    pub url: String,
+    /// Position of the URL in the batch, from 1
+    pub item_number: usize,
+    pub item_count: usize,
+    /// "download" or a step kind such as "normalize_loudness"
+    pub stage: String,
+    /// Position of the stage, from 1; the download is stage 1
+    pub stage_number: usize,
+    pub stage_count: usize,
+    pub job_id: String,
+    /// "running", "completed" or "failed"
+    pub status: String,
+    pub error: Option<String>,
+}
+
+impl EventPayload for RecipeProgress {
+    const TYPE_NAME: &'static str = "RecipeProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("run_id", Some("string"), false),
+            ("url", Some("string"), false),
+            ("item_number", Some("integer"), false),
+            ("item_count", Some("integer"), false),
+            ("stage", Some("string"), false),
+            ("stage_number", Some("integer"), false),
+            ("stage_count", Some("integer"), false),
+            ("job_id", Some("string"), false),
+            ("status", Some("string"), false),
+            ("error", Some("string"), true),
+        ])
+    }
+}
+
+/// Status snapshot sent once after startup for the frontend's badges
+#[derive(Clone, Default, Serialize)]
+pub struct AppHealth {
+    pub ytdlp: DependencyHealth,
+    pub ffmpeg: DependencyHealth,
+    pub deno: DependencyHealth,
+    /// "ok", "recovered" or "unavailable"
+    pub database_status: String,
+    pub database_error: Option<String>,
+    /// Downloads cut off by quitting or a crash that can be resumed
+    pub resumable_downloads: usize,
+    /// Followed channels checked by background polling
+    pub scheduled_jobs: usize,
+}
+
+impl EventPayload for AppHealth {
+    const TYPE_NAME: &'static str = "AppHealth";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("ytdlp", Some("object"), false),
+            ("ffmpeg", Some("object"), false),
+            ("deno", Some("object"), false),
+            ("database_status", Some("string"), false),
+            ("database_error", Some("string"), true),
+            ("resumable_downloads", Some("integer"), false),
+            ("scheduled_jobs", Some("integer"), false),
+        ])
+    }
+}
+
+/// Sent when the "also save audio" extraction of a download finishes
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AudioCompanionResult {
+    /// `cancel_ffmpeg` id of the extraction
+    pub job_id: String,
+    pub video_path: String,
+    pub audio_path: Option<String>,
+    pub history_id: Option<String>,
+    pub error: Option<String>,
+}
+
+impl EventPayload for AudioCompanionResult {
+    const TYPE_NAME: &'static str = "AudioCompanionResult";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("job_id", Some("string"), false),
+            ("video_path", Some("string"), false),
+            ("audio_path", Some("string"), true),
+            ("history_id", Some("string"), true),
+            ("error", Some("string"), true),
+        ])
+    }
+}
+
+/// Progress of a video or thumbnail preview transcode
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PreviewProgress {
+    /// `cancel_preview` id of the transcode
+    pub job_id: String,
+    pub input_path: String,
+    /// "video" or "thumbnail"
+    pub kind: String,
+    /// "starting", "progress", "complete", "cancelled" or "failed"
+    pub status: String,
+    pub percent: f64,
+}
+
+impl EventPayload for PreviewProgress {
+    const TYPE_NAME: &'static str = "PreviewProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("job_id", Some("string"), false),
+            ("input_path", Some("string"), false),
+            ("kind", Some("string"), false),
+            ("status", Some("string"), false),
+            ("percent", Some("number"), false),
+        ])
+    }
+}
+
+/// Progress of one file in `convert_audio_batch`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AudioBatchProgress {
+    pub batch_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub input_path: String,
+    pub percent: f64,
+    pub status: String, // "converting", "converted", "skipped" or "failed"
+}
+
+impl EventPayload for AudioBatchProgress {
+    const TYPE_NAME: &'static str = "AudioBatchProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("batch_id", Some("string"), false),
+            ("index", Some("integer"), false),
+            ("total", Some("integer"), false),
+            ("input_path", Some("string"), false),
+            ("percent", Some("number"), false),
+            ("status", Some("string"), false),
+        ])
+    }
+}
+
+/// Stage of a chunked long summary, sent only for requests with an id
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryProgress {
+    pub request_id: String,
+    pub stage: String,
+    pub chunk_index: Option<usize>,
+    pub chunk_count: usize,
+}
+
+impl EventPayload for SummaryProgress {
+    const TYPE_NAME: &'static str = "SummaryProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("requestId", Some("string"), false),
+            ("stage", Some("string"), false),
+            ("chunkIndex", Some("integer"), true),
+            ("chunkCount", Some("integer"), false),
+        ])
+    }
+}
+
+/// Kind of long-running work that should keep the machine awake
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActiveJobKind {
+    Download,
+    Processing,
+}
+
+/// Running download or ffmpeg job, listed when the user tries to quit
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveJob {
+    pub id: String,
+    pub kind: ActiveJobKind,
+    pub url: Option<String>,
+    pub output_path: Option<String>,
+    /// Settings the download was started with, so it can be resumed after a quit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<serde_json::Value>,
+}
+
+impl ActiveJob {
+    pub fn download(id: &str, url: &str, output_path: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            kind: ActiveJobKind::Download,
+            url: Some(url.to_string()),
+            output_path: Some(output_path.to_string()),
+            options: None,
+        }
+    }
+
+    pub fn with_options(mut self, options: Option<serde_json::Value>) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn processing(id: &str, output_path: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            kind: ActiveJobKind::Processing,
+            url: None,
+            output_path: Some(output_path.to_string()),
+            options: None,
+        }
+    }
+}
+
+/// `quit-requested` event payload
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuitRequest {
+    pub active_downloads: usize,
+    pub active_processing: usize,
+    pub jobs: Vec<ActiveJob>,
+}
+
+impl EventPayload for QuitRequest {
+    const TYPE_NAME: &'static str = "QuitRequest";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("activeDownloads", Some("integer"), false),
+            ("activeProcessing", Some("integer"), false),
+            ("jobs", Some("array"), false),
+        ])
+    }
+}
+
+/// What to do once the download queue drains
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostQueueAction {
+    #[default]
+    None,
+    Notify,
+    OpenFolder,
+    Sleep,
+    Shutdown,
+}
+
+impl PostQueueAction {
+    /// Power actions wait for the countdown so the user can still cancel
+    pub fn needs_confirmation(self) -> bool {
+        matches!(self, PostQueueAction::Sleep | PostQueueAction::Shutdown)
+    }
+}
+
+/// `post-queue-action` event payload
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostQueueActionEvent {
+    pub action: PostQueueAction,
+    pub status: String, // "pending", "cancelled", "done" or "failed"
+    pub delay_seconds: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl EventPayload for PostQueueActionEvent {
+    const TYPE_NAME: &'static str = "PostQueueActionEvent";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("action", Some("string"), false),
+            ("status", Some("string"), false),
+            ("delaySeconds", Some("integer"), true),
+            ("error", Some("string"), true),
+        ])
+    }
+}
+
+/// Emitted after `update_and_retry` so the queue restarts the failed item
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadRetryRequest {
+    pub id: String,
+    pub ytdlp_version: String,
+}
+
+impl EventPayload for DownloadRetryRequest {
+    const TYPE_NAME: &'static str = "DownloadRetryRequest";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("id", Some("string"), false),
+            ("ytdlpVersion", Some("string"), false),
+        ])
+    }
+}
+
+impl EventPayload for DownloadIntegrityReport {
+    const TYPE_NAME: &'static str = "DownloadIntegrityReport";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("historyId", Some("string"), false),
+            ("filepath", Some("string"), false),
+            ("status", Some("string"), false),
+            ("sha256", Some("string"), true),
+            ("previousSha256", Some("string"), true),
+            ("decodable", Some("boolean"), false),
+            ("durationSeconds", Some("number"), true),
+            ("expectedDuration", Some("integer"), true),
+            ("issues", Some("array"), false),
+            ("checkedAt", Some("string"), false),
+        ])
+    }
+}
+
+/// `database-recovered` event payload
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseRecoveryReport {
+    pub corrupt_path: String,
+    pub integrity_error: String,
+    pub restored_from_backup: bool,
+    pub tables_recovered: usize,
+    pub rows_recovered: usize,
+    pub rows_lost: usize,
+}
+
+impl EventPayload for DatabaseRecoveryReport {
+    const TYPE_NAME: &'static str = "DatabaseRecoveryReport";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("corruptPath", Some("string"), false),
+            ("integrityError", Some("string"), false),
+            ("restoredFromBackup", Some("boolean"), false),
+            ("tablesRecovered", Some("integer"), false),
+            ("rowsRecovered", Some("integer"), false),
+            ("rowsLost", Some("integer"), false),
+        ])
+    }
+}
+
+/// Combined progress of the first-run setup across all of its steps
+#[derive(Clone, Default, Serialize)]
+pub struct SetupProgress {
+    /// "ytdlp", "ffmpeg", "deno" or "download_folder"
+    pub step: String,
+    pub stage: String,
+    pub step_index: u32,
+    pub step_count: u32,
+    /// Overall percent of the whole setup
+    pub percent: u8,
+}
+
+impl EventPayload for SetupProgress {
+    const TYPE_NAME: &'static str = "SetupProgress";
+
+    fn schema() -> Value {
+        object_schema(&[
+            ("step", Some("string"), false),
+            ("stage", Some("string"), false),
+            ("step_index", Some("integer"), false),
+            ("step_count", Some("integer"), false),
+            ("percent", Some("integer"), false),
+        ])
+    }
+}
+
+/// JSON schema of every versioned event, for generating the TypeScript types
+pub fn event_schema() -> Value {
+    let mut definitions = Map::new();
+    definitions.insert(
+        DownloadProgress::TYPE_NAME.into(),
+        DownloadProgress::schema(),
+    );
+    definitions.insert(
+        ProcessingProgress::TYPE_NAME.into(),
+        ProcessingProgress::schema(),
+    );
+    definitions.insert(
+        MetadataProgress::TYPE_NAME.into(),
+        MetadataProgress::schema(),
+    );
+    definitions.insert(
+        BinaryDownloadProgress::TYPE_NAME.into(),
+        BinaryDownloadProgress::schema(),
+    );
+    definitions.insert(SetupProgress::TYPE_NAME.into(), SetupProgress::schema());
+    definitions.insert(
+        WhisperModelDownloadProgress::TYPE_NAME.into(),
+        WhisperModelDownloadProgress::schema(),
+    );
+    definitions.insert(
+        ProcessingPlanProgress::TYPE_NAME.into(),
+        ProcessingPlanProgress::schema(),
+    );
+    definitions.insert(
+        ProcessingGraphProgress::TYPE_NAME.into(),
+        ProcessingGraphProgress::schema(),
+    );
+    definitions.insert(RecipeProgress::TYPE_NAME.into(), RecipeProgress::schema());
+    definitions.insert(AppHealth::TYPE_NAME.into(), AppHealth::schema());
+    definitions.insert(
+        AudioCompanionResult::TYPE_NAME.into(),
+        AudioCompanionResult::schema(),
+    );
+    definitions.insert(PreviewProgress::TYPE_NAME.into(), PreviewProgress::schema());
+    definitions.insert(
+        AudioBatchProgress::TYPE_NAME.into(),
+        AudioBatchProgress::schema(),
+    );
+    definitions.insert(SummaryProgress::TYPE_NAME.into(), SummaryProgress::schema());
+    definitions.insert(QuitRequest::TYPE_NAME.into(), QuitRequest::schema());
+    definitions.insert(
+        PostQueueActionEvent::TYPE_NAME.into(),
+        PostQueueActionEvent::schema(),
+    );
+    definitions.insert(
+        DownloadRetryRequest::TYPE_NAME.into(),
+        DownloadRetryRequest::schema(),
+    );
+    definitions.insert(
+        DownloadIntegrityReport::TYPE_NAME.into(),
+        DownloadIntegrityReport::schema(),
+    );
+    definitions.insert(
+        DatabaseRecoveryReport::TYPE_NAME.into(),
+        DatabaseRecoveryReport::schema(),
+    );
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Youwee events",
+        "events": [
+            DOWNLOAD_PROGRESS.describe(),
+            PROCESSING_PROGRESS.describe(),
+            METADATA_PROGRESS.describe(),
+            FFMPEG_DOWNLOAD_PROGRESS.describe(),
+            DENO_DOWNLOAD_PROGRESS.describe(),
+            SETUP_PROGRESS.describe(),
+            WHISPER_MODEL_DOWNLOAD_PROGRESS.describe(),
+            PROCESSING_PLAN_PROGRESS.describe(),
+            PROCESSING_GRAPH_PROGRESS.describe(),
+            RECIPE_PROGRESS.describe(),
+            APP_HEALTH.describe(),
+            AUDIO_COMPANION.describe(),
+            PREVIEW_PROGRESS.describe(),
+            AUDIO_BATCH_PROGRESS.describe(),
+            SUMMARY_PROGRESS.describe(),
+            QUIT_REQUESTED.describe(),
+            POST_QUEUE_ACTION.describe(),
+            DOWNLOAD_RETRY.describe(),
+            DOWNLOAD_INTEGRITY.describe(),
+            DATABASE_RECOVERED.describe(),
+        ],
+        "definitions": definitions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_schema_matches<T: EventPayload + Default>(contract: &EventContract<T>) {
+        let payload = T::default();
+        let wire = serde_json::to_value(contract.envelope(&payload)).expect("serialize event");
+        let mut wire_keys: Vec<&String> = wire.as_object().expect("object").keys().collect();
+        let schema = T::schema();
+        let mut schema_keys: Vec<&String> = schema["properties"]
+            .as_object()
+            .expect("properties")
+            .keys()
+            .collect();
+        wire_keys.sort();
+        schema_keys.sort();
+        assert_eq!(
+            wire_keys, schema_keys,
+            "{} schema is out of date",
+            contract.name
+        );
+        assert_eq!(wire["type"], contract.name);
+    }
+
+    #[test]
+    fn event_schemas_match_serialized_payloads() {
+        assert_schema_matches(&DOWNLOAD_PROGRESS);
+        assert_schema_matches(&PROCESSING_PROGRESS);
+        assert_schema_matches(&METADATA_PROGRESS);
+        assert_schema_matches(&FFMPEG_DOWNLOAD_PROGRESS);
+        assert_schema_matches(&SETUP_PROGRESS);
+        assert_schema_matches(&WHISPER_MODEL_DOWNLOAD_PROGRESS);
+        assert_schema_matches(&PROCESSING_PLAN_PROGRESS);
+        assert_schema_matches(&PROCESSING_GRAPH_PROGRESS);
+        assert_schema_matches(&RECIPE_PROGRESS);
+        assert_schema_matches(&APP_HEALTH);
+        assert_schema_matches(&AUDIO_COMPANION);
+        assert_schema_matches(&PREVIEW_PROGRESS);
+        assert_schema_matches(&AUDIO_BATCH_PROGRESS);
+        assert_schema_matches(&SUMMARY_PROGRESS);
+        assert_schema_matches(&QUIT_REQUESTED);
+        assert_schema_matches(&POST_QUEUE_ACTION);
+        assert_schema_matches(&DOWNLOAD_RETRY);
+        assert_schema_matches(&DOWNLOAD_INTEGRITY);
+        assert_schema_matches(&DATABASE_RECOVERED);
+
+        let schema = event_schema();
+        for event in schema["events"].as_array().expect("events") {
+            let reference = event["payload"]["$ref"].as_str().expect("ref");
+            let name = reference.trim_start_matches("#/definitions/");
+            assert!(schema["definitions"].get(name).is_some(), "{}", reference);
+        }
+    }
+
+    /// Event names passed as string literals to `emit` anywhere in the backend
+    fn emitted_literal_names(dir: &std::path::Path, found: &mut Vec<(String, String)>) {
+        for entry in std::fs::read_dir(dir).expect("read source dir").flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                emitted_literal_names(&path, found);
+                continue;
+            }
+            // This file only emits through `EventContract::emit`
+            if path.extension().and_then(|ext| ext.to_str()) != Some("rs")
+                || path.ends_with("types/events.rs")
+            {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).expect("read source file");
+            for (pos, _) in source.match_indices(".emit(") {
+                let rest = source[pos + ".emit(".len()..].trim_start();
+                let Some(literal) = rest.strip_prefix('"') else {
+                    continue;
+                };
+                if let Some(end) = literal.find('"') {
+                    found.push((path.display().to_string(), literal[..end].to_string()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn new_events_go_through_a_contract() {
+        let this_file =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(std::path::Path::new(file!()));
+        let src = this_file
+            .parent()
+            .and_then(|types| types.parent())
+            .expect("src dir");
+        let mut found = Vec::new();
+        emitted_literal_names(src, &mut found);
+        assert!(
+            !found.is_empty(),
+            "no emit calls found under {}",
+            src.display()
+        );
+        for (file, name) in found {
+            assert!(
+                UNTYPED_EVENTS.contains(&name.as_str()),
+                "{} emits \"{}\" without an EventContract",
+                file,
+                name
+            );
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::DownloadProfileSettings;
+
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct HistoryTag {
@@ -17,6 +19,70 @@ pub struct HistoryCollection {
     pub item_count: Option<i64>,
 }
 
+/// Outcome of the download a history entry records
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryStatus {
+    #[default]
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl HistoryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryStatus::Completed => "completed",
+            HistoryStatus::Failed => "failed",
+            HistoryStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_db(value: Option<&str>) -> Self {
+        match value {
+            Some("failed") => HistoryStatus::Failed,
+            Some("cancelled") => HistoryStatus::Cancelled,
+            _ => HistoryStatus::Completed,
+        }
+    }
+}
+
+/// How the cover image of a download was stored
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailEmbed {
+    /// Cover picture stream (MP4, M4A, MP3, Opus)
+    AttachedPic,
+    /// Matroska attachment (MKV)
+    Attachment,
+    /// Saved next to the file, the container cannot hold one (WebM)
+    Sidecar,
+    /// Not saved: the container cannot hold one (WebM), or yt-dlp reported
+    /// no embed or thumbnail write for the file
+    Skipped,
+}
+
+impl ThumbnailEmbed {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThumbnailEmbed::AttachedPic => "attached_pic",
+            ThumbnailEmbed::Attachment => "attachment",
+            ThumbnailEmbed::Sidecar => "sidecar",
+            ThumbnailEmbed::Skipped => "skipped",
+        }
+    }
+
+    pub fn from_db(value: Option<&str>) -> Option<Self> {
+        match value? {
+            "attached_pic" => Some(ThumbnailEmbed::AttachedPic),
+            "attachment" => Some(ThumbnailEmbed::Attachment),
+            "sidecar" => Some(ThumbnailEmbed::Sidecar),
+            "skipped" => Some(ThumbnailEmbed::Skipped),
+            _ => None,
+        }
+    }
+}
+
 /// History entry structure
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct HistoryEntry {
@@ -34,6 +100,19 @@ pub struct HistoryEntry {
     pub file_exists: bool,
     pub summary: Option<String>,    // AI-generated summary
     pub time_range: Option<String>, // Time range cut (e.g. "00:10-01:00")
+    pub media_type: Option<String>, // "video", "audio", "image" or "subtitle"; None for legacy rows
+    pub favorite: bool,
+    pub notes: Option<String>,
+    pub custom_metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    pub playlist_index: Option<u32>, // Position in the source playlist, when known
+    pub lyrics_path: Option<String>, // Generated .lrc file next to the audio
+    pub variant_of: Option<String>,  // Entry this one is another quality of
+    pub is_short: bool,              // Vertical short-form clip (Shorts, Reels, TikTok)
+    pub status: HistoryStatus,
+    pub error_code: Option<String>, // Failed/cancelled attempts only
+    pub error_message: Option<String>,
+    pub thumbnail_embed: Option<ThumbnailEmbed>, // Only when embedding was requested
+    pub language: Option<String>, // Track language of subtitle-only downloads (e.g. "en")
     pub tags: Vec<HistoryTag>,
     pub collections: Vec<HistoryCollection>,
 }
@@ -74,6 +153,8 @@ pub enum HistoryMediaType {
     All,
     Video,
     Audio,
+    Image,
+    Subtitle,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
@@ -91,6 +172,7 @@ pub enum HistorySearchScope {
     All,
     Metadata,
     Summary,
+    Notes,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
@@ -105,4 +187,168 @@ pub struct HistoryAdvancedFilters {
     pub tag_ids: Option<Vec<String>>,
     pub collection_ids: Option<Vec<String>>,
     pub match_mode: Option<HistoryFilterMatchMode>,
+    pub favorites_only: Option<bool>,
+    /// Some(true) keeps only Shorts/Reels, Some(false) hides them
+    pub is_short: Option<bool>,
+    /// Only entries with one of these outcomes; unset lists every entry
+    pub statuses: Option<Vec<HistoryStatus>>,
+}
+
+/// Arguments of `get_history_page`, mirroring those of `get_history`
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPageQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub source: Option<String>,
+    pub search: Option<String>,
+    pub filters: Option<HistoryAdvancedFilters>,
+    pub sort: Option<HistorySort>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryFacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+/// One page of history plus the totals needed for pagination and filter chips
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    pub total: i64,
+    pub sources: Vec<HistoryFacetCount>,
+    pub formats: Vec<HistoryFacetCount>,
+}
+
+/// Result of re-checking a downloaded file for bit rot or an incomplete merge
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadIntegrityReport {
+    pub history_id: String,
+    pub filepath: String,
+    /// "ok", "missing", "corrupt" or "changed"
+    pub status: String,
+    pub sha256: Option<String>,
+    pub previous_sha256: Option<String>,
+    pub decodable: bool,
+    pub duration_seconds: Option<f64>,
+    pub expected_duration: Option<u64>,
+    pub issues: Vec<String>,
+    pub checked_at: String,
+}
+
+/// Format of another downloader's history accepted by `import_external_history`
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalHistoryKind {
+    /// yt-dlp `--download-archive` file (`<extractor> <id>` per line)
+    YtdlpArchive,
+    Stacher,
+    OpenVideoDownloader,
+    /// Folder of `.info.json` sidecars written by `--write-info-json`
+    InfoJson,
+}
+
+/// One download parsed from another tool, before it is written to history
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedHistoryRecord {
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    pub filepath: Option<String>,
+    pub filesize: Option<u64>,
+    pub duration: Option<u64>,
+    pub format: Option<String>,
+    pub source: Option<String>,
+    pub media_id: Option<String>, // "<extractor>:<id>", same shape as history.media_id
+    pub downloaded_at: Option<i64>,
+}
+
+/// A download that did not finish, kept in history so it can be retried
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FailedDownloadRecord {
+    pub url: String,
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    pub source: Option<String>,
+    pub time_range: Option<String>,
+    pub status: HistoryStatus,
+    pub error_code: String,
+    pub error_message: String,
+    /// Settings the attempt ran with, reused by `retry_failed`
+    pub options: DownloadProfileSettings,
+    pub ytdlp_args: Vec<String>,
+    /// Failed entry this attempt retried; updated instead of adding a new one
+    pub retry_of: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryImportReport {
+    pub scanned: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub archived: usize,
+    pub failed: usize,
+}
+
+/// How long history is kept. Every limit is optional; the default keeps everything.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryRetentionPolicy {
+    /// Keep at most this many entries, newest first (`None` = unlimited)
+    pub max_entries: Option<u32>,
+    /// Remove entries downloaded more than this many days ago
+    pub max_age_days: Option<u32>,
+    /// Age-based removal only applies to entries whose file is gone
+    pub only_missing_files: bool,
+    /// Also delete the media files of removed entries (manual pruning only)
+    pub delete_files: bool,
+}
+
+/// What `copy_to_clipboard` copies from a history entry
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryCopyKind {
+    Url,
+    Title,
+    MarkdownLink,
+    Filepath,
+}
+
+/// Playlist file format written by `export_playlist`
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistExportFormat {
+    M3u8,
+    Xspf,
+}
+
+/// Which history entries go into an exported playlist
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum PlaylistSelection {
+    /// These entries, in this order
+    Ids(Vec<String>),
+    /// Everything matching a history query, in its sort order
+    Filter(Box<HistoryPageQuery>),
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistExportReport {
+    pub path: String,
+    pub track_count: usize,
+    /// Selected entries left out because their file is gone
+    pub skipped_missing: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPruneReport {
+    pub entries_removed: usize,
+    pub files_deleted: usize,
 }
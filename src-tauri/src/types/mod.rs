@@ -1,19 +1,29 @@
+mod ai;
 mod channel;
 mod dependencies;
+mod diagnostics;
 mod download;
 mod error;
+mod events;
 mod history;
 mod log;
 mod plugin;
+mod podcast;
+mod search;
 mod video;
 mod youtube_search;
 
+pub use ai::*;
 pub use channel::*;
 pub use dependencies::*;
+pub use diagnostics::*;
 pub use download::*;
 pub use error::*;
+pub use events::*;
 pub use history::*;
 pub use log::*;
 pub use plugin::*;
+pub use podcast::*;
+pub use search::*;
 pub use video::*;
 pub use youtube_search::*;
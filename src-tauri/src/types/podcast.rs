@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Episode listed in a podcast RSS feed
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct PodcastEpisode {
+    pub guid: String,
+    pub title: String,
+    pub published_at: Option<String>, // RFC 3339 when the feed date parses
+    pub duration_seconds: Option<u64>,
+    pub enclosure_url: String,
+    pub enclosure_type: Option<String>,
+    pub enclosure_length: Option<u64>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub episode_number: Option<u32>,
+    pub season_number: Option<u32>,
+}
+
+/// Podcast feed with its downloadable episodes, newest first as published
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct PodcastFeed {
+    pub url: String,
+    pub title: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub link: Option<String>,
+    pub episodes: Vec<PodcastEpisode>,
+}
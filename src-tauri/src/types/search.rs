@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use super::PlaylistVideoEntry;
+
+/// Site searched through yt-dlp's search extractors
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoSearchSource {
+    #[default]
+    Youtube,
+    Soundcloud,
+    Bilibili,
+}
+
+impl VideoSearchSource {
+    /// yt-dlp search prefix, used as `<prefix><count>:<query>`
+    pub fn prefix(self) -> &'static str {
+        match self {
+            VideoSearchSource::Youtube => "ytsearch",
+            VideoSearchSource::Soundcloud => "scsearch",
+            VideoSearchSource::Bilibili => "bilisearch",
+        }
+    }
+}
+
+/// One page of search results
+#[derive(Clone, Serialize, Debug)]
+pub struct VideoSearchResponse {
+    pub entries: Vec<PlaylistVideoEntry>,
+    pub offset: u32,
+    pub has_more: bool,
+}
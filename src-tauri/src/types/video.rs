@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::ChannelRule;
+
 /// Video information returned from yt-dlp
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct VideoInfo {
@@ -8,6 +10,9 @@ pub struct VideoInfo {
     pub thumbnail: Option<String>,
     pub duration: Option<f64>,
     pub channel: Option<String>,
+    /// `channel_id`, or `uploader_id` on sites without channels
+    #[serde(default)]
+    pub channel_id: Option<String>,
     pub uploader: Option<String>,
     pub upload_date: Option<String>,
     pub view_count: Option<u64>,
@@ -20,6 +25,9 @@ pub struct VideoInfo {
     pub is_live: Option<bool>,       // true if currently live streaming
     pub was_live: Option<bool>,      // true if was a live stream (now ended)
     pub live_status: Option<String>, // "is_live", "was_live", "not_live", "is_upcoming"
+    // Vertical short-form clip (Shorts, Reels, TikTok)
+    #[serde(default)]
+    pub is_short: bool,
 }
 
 /// Format option from yt-dlp
@@ -38,6 +46,16 @@ pub struct FormatOption {
     pub format_note: Option<String>,
     pub fps: Option<f64>,
     pub quality: Option<f64>,
+    pub language: Option<String>,
+}
+
+/// Audio track language offered by the video (original audio or dubs)
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AudioTrackInfo {
+    pub language: String,
+    pub name: Option<String>,
+    pub is_default: bool,
+    pub format_count: u32,
 }
 
 /// Response containing video info and available formats
@@ -45,6 +63,9 @@ pub struct FormatOption {
 pub struct VideoInfoResponse {
     pub info: VideoInfo,
     pub formats: Vec<FormatOption>,
+    pub audio_tracks: Vec<AudioTrackInfo>,
+    /// Saved rule for the video's channel, to pre-fill the download options
+    pub channel_rule: Option<ChannelRule>,
 }
 
 /// Playlist entry with basic video info
@@ -60,10 +81,45 @@ pub struct PlaylistVideoEntry {
     pub playlist_title: Option<String>,
 }
 
-/// Subtitle information
+/// Videos suggested after a download; `source` is "extractor", "channel" or "none"
 #[derive(Clone, Serialize, Debug)]
+pub struct RelatedVideosResponse {
+    pub source: String,
+    pub entries: Vec<PlaylistVideoEntry>,
+}
+
+/// One downloadable file for a subtitle track
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct SubtitleFormat {
+    pub ext: String,
+    pub url: Option<String>,
+}
+
+/// Subtitle information
+#[derive(Clone, Serialize, Debug, PartialEq)]
 pub struct SubtitleInfo {
     pub lang: String,
     pub name: String,
     pub is_auto: bool,
+    pub formats: Vec<SubtitleFormat>,
+}
+
+/// Single comment fetched with `--write-comments`
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct VideoComment {
+    pub id: String,
+    pub author: Option<String>,
+    pub text: String,
+    pub like_count: Option<u64>,
+    pub is_pinned: bool,
+    pub author_is_uploader: bool,
+    pub parent: Option<String>, // "root" for top-level comments
+    pub timestamp: Option<i64>,
+}
+
+/// Comments returned for a video, truncated to the requested limit
+#[derive(Clone, Serialize, Debug)]
+pub struct VideoCommentsResponse {
+    pub comments: Vec<VideoComment>,
+    pub total_count: Option<u64>,
 }
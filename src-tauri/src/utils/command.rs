@@ -1,18 +1,122 @@
 //! Command utilities for cross-platform process spawning
 //!
 //! On Windows, console applications spawn a visible terminal window by default.
-//! This module provides utilities to hide the console window.
+//! This module provides utilities to hide the console window, and builds the
+//! environment every yt-dlp spawn runs with.
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
 
 use tokio::process::Command;
 
+use super::PYTHON_UTF8_ENV;
+
 /// Windows flag to prevent console window from appearing
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// Windows priority class for background work that shouldn't starve the desktop
+#[cfg(windows)]
+const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x00004000;
+
+/// Proxy variables that would otherwise leak from the user's shell
+const PROXY_ENV_VARS: [&str; 4] = ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "NO_PROXY"];
+
+/// Well-known tool directories (Deno, Bun, Homebrew) that GUI launches miss
+/// from PATH, e.g. when the app is started from Finder
+pub fn tool_path_dirs(home: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = home
+        .map(|home| {
+            vec![
+                home.join(".deno").join("bin"),
+                home.join(".bun").join("bin"),
+            ]
+        })
+        .unwrap_or_default();
+    if cfg!(not(windows)) {
+        dirs.extend([
+            PathBuf::from("/opt/homebrew/bin"),
+            PathBuf::from("/usr/local/bin"),
+        ]);
+    }
+    dirs
+}
+
+fn is_path_var(key: &OsStr) -> bool {
+    // Windows spells it `Path`; env names are case-insensitive there
+    key.to_str()
+        .is_some_and(|key| key.eq_ignore_ascii_case("PATH"))
+}
+
+fn is_proxy_var(key: &OsStr) -> bool {
+    key.to_str().is_some_and(|key| {
+        PROXY_ENV_VARS
+            .iter()
+            .any(|proxy| key.eq_ignore_ascii_case(proxy))
+    })
+}
+
+/// The complete environment for a spawned tool.
+///
+/// Starts from `inherited`, drops proxy variables unless `proxy_url` is
+/// configured (then both spellings point at it), and rebuilds PATH as
+/// `path_dirs` followed by the inherited entries, without duplicates.
+pub fn build_process_env(
+    inherited: impl IntoIterator<Item = (OsString, OsString)>,
+    path_dirs: &[PathBuf],
+    proxy_url: Option<&str>,
+) -> Vec<(OsString, OsString)> {
+    let mut inherited_path = None;
+    let mut env: Vec<(OsString, OsString)> = inherited
+        .into_iter()
+        .filter(|(key, value)| {
+            if is_path_var(key) {
+                inherited_path = Some(value.clone());
+                return false;
+            }
+            !is_proxy_var(key)
+        })
+        .collect();
+
+    let mut path_entries: Vec<PathBuf> = path_dirs.to_vec();
+    if let Some(inherited_path) = &inherited_path {
+        path_entries.extend(std::env::split_paths(inherited_path));
+    }
+    let mut seen = std::collections::HashSet::new();
+    path_entries.retain(|dir| !dir.as_os_str().is_empty() && seen.insert(dir.clone()));
+    if let Ok(path) = std::env::join_paths(path_entries) {
+        env.push(("PATH".into(), path));
+    }
+
+    if let Some(proxy) = proxy_url.map(str::trim).filter(|proxy| !proxy.is_empty()) {
+        for key in ["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"] {
+            env.push((key.into(), proxy.into()));
+        }
+    }
+
+    env.sort();
+    env
+}
+
 /// Extension trait to configure Command for hidden window on Windows
 pub trait CommandExt {
     /// Hide console window on Windows (no-op on other platforms)
     fn hide_window(&mut self) -> &mut Self;
+
+    /// Hide the console window and start below normal priority on Windows.
+    /// Unix callers lower priority by wrapping the program in `nice` instead.
+    fn below_normal_priority(&mut self) -> &mut Self;
+
+    /// Make Python programs such as yt-dlp write UTF-8 regardless of the
+    /// console code page
+    fn utf8_output(&mut self) -> &mut Self;
+
+    /// Replace the inherited environment with one from [`build_process_env`]
+    fn process_env(&mut self, env: &[(OsString, OsString)]) -> &mut Self;
+
+    /// Start the process in its own process group on Unix so an interrupt
+    /// reaches the ffmpeg children too. Windows delivers Ctrl+C per console.
+    fn new_process_group(&mut self) -> &mut Self;
 }
 
 impl CommandExt for Command {
@@ -26,6 +130,35 @@ impl CommandExt for Command {
     fn hide_window(&mut self) -> &mut Self {
         self
     }
+
+    #[cfg(windows)]
+    fn below_normal_priority(&mut self) -> &mut Self {
+        self.creation_flags(CREATE_NO_WINDOW | BELOW_NORMAL_PRIORITY_CLASS);
+        self
+    }
+
+    #[cfg(not(windows))]
+    fn below_normal_priority(&mut self) -> &mut Self {
+        self
+    }
+
+    fn utf8_output(&mut self) -> &mut Self {
+        self.envs(PYTHON_UTF8_ENV)
+    }
+
+    fn process_env(&mut self, env: &[(OsString, OsString)]) -> &mut Self {
+        self.env_clear().envs(env.iter().cloned())
+    }
+
+    #[cfg(unix)]
+    fn new_process_group(&mut self) -> &mut Self {
+        self.process_group(0)
+    }
+
+    #[cfg(not(unix))]
+    fn new_process_group(&mut self) -> &mut Self {
+        self
+    }
 }
 
 /// Extension trait for std::process::Command
@@ -41,4 +174,109 @@ impl CommandExt for std::process::Command {
     fn hide_window(&mut self) -> &mut Self {
         self
     }
+
+    #[cfg(windows)]
+    fn below_normal_priority(&mut self) -> &mut Self {
+        use std::os::windows::process::CommandExt as _;
+        self.creation_flags(CREATE_NO_WINDOW | BELOW_NORMAL_PRIORITY_CLASS);
+        self
+    }
+
+    #[cfg(not(windows))]
+    fn below_normal_priority(&mut self) -> &mut Self {
+        self
+    }
+
+    fn utf8_output(&mut self) -> &mut Self {
+        self.envs(PYTHON_UTF8_ENV)
+    }
+
+    fn process_env(&mut self, env: &[(OsString, OsString)]) -> &mut Self {
+        self.env_clear().envs(env.iter().cloned())
+    }
+
+    #[cfg(unix)]
+    fn new_process_group(&mut self) -> &mut Self {
+        use std::os::unix::process::CommandExt as _;
+        self.process_group(0)
+    }
+
+    #[cfg(not(unix))]
+    fn new_process_group(&mut self) -> &mut Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(OsString, OsString)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (OsString::from(key), OsString::from(value)))
+            .collect()
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn process_env_rebuilds_path_and_drops_inherited_proxy() {
+        let home = PathBuf::from("/home/me");
+        let mut dirs = vec![PathBuf::from("/data/youwee/bin")];
+        dirs.extend(tool_path_dirs(Some(&home)));
+        let inherited = vars(&[
+            ("HOME", "/home/me"),
+            ("PATH", "/usr/bin:/usr/local/bin:/bin"),
+            ("https_proxy", "http://corp:3128"),
+            ("ALL_PROXY", "socks5://corp:1080"),
+            ("LANG", "ja_JP.UTF-8"),
+        ]);
+
+        assert_eq!(
+            build_process_env(inherited.clone(), &dirs, None),
+            vars(&[
+                ("HOME", "/home/me"),
+                ("LANG", "ja_JP.UTF-8"),
+                (
+                    "PATH",
+                    "/data/youwee/bin:/home/me/.deno/bin:/home/me/.bun/bin:/opt/homebrew/bin:\
+                     /usr/local/bin:/usr/bin:/bin"
+                ),
+            ])
+        );
+
+        let with_proxy = build_process_env(inherited, &dirs, Some("http://127.0.0.1:8080"));
+        let proxies: Vec<_> = with_proxy
+            .iter()
+            .filter(|(key, _)| is_proxy_var(key))
+            .collect();
+        assert_eq!(proxies.len(), 4);
+        assert!(proxies
+            .iter()
+            .all(|(_, value)| value == "http://127.0.0.1:8080"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn process_env_rebuilds_path_and_drops_inherited_proxy() {
+        let home = PathBuf::from(r"C:\Users\me");
+        let mut dirs = vec![PathBuf::from(r"C:\Youwee\bin")];
+        dirs.extend(tool_path_dirs(Some(&home)));
+        let inherited = vars(&[
+            ("Path", r"C:\Windows\system32;C:\Youwee\bin"),
+            ("HTTPS_PROXY", "http://corp:3128"),
+            ("SystemRoot", r"C:\Windows"),
+        ]);
+
+        assert_eq!(
+            build_process_env(inherited, &dirs, None),
+            vars(&[
+                (
+                    "PATH",
+                    r"C:\Youwee\bin;C:\Users\me\.deno\bin;C:\Users\me\.bun\bin;C:\Windows\system32"
+                ),
+                ("SystemRoot", r"C:\Windows"),
+            ])
+        );
+    }
 }
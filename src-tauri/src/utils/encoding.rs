@@ -0,0 +1,139 @@
+//! Text encoding helpers for child process output
+//!
+//! yt-dlp is started with UTF-8 forced, but on Windows with a non-UTF-8
+//! locale (e.g. Chinese → GBK) older builds, ffmpeg and plugins may still
+//! write lines in the system ANSI code page, interleaved with UTF-8 ones.
+
+/// Environment that makes Python (and so yt-dlp) use UTF-8 for stdio
+pub const PYTHON_UTF8_ENV: [(&str, &str); 2] = [("PYTHONIOENCODING", "utf-8"), ("PYTHONUTF8", "1")];
+
+/// yt-dlp flags forcing UTF-8 output
+pub const YTDLP_ENCODING_ARGS: [&str; 2] = ["--encoding", "utf-8"];
+
+/// Decode raw bytes from a child process into a Rust String.
+///
+/// Tokio's `BufReader::lines()` returns `Err` on non-UTF-8 bytes, which
+/// silently stops a reading loop and loses the filepath. Each line is decoded
+/// on its own: valid UTF-8 is kept as is and only the other lines go through
+/// the Windows ANSI code page (lossy UTF-8 elsewhere), so one GBK line doesn't
+/// garble the UTF-8 lines around it.
+pub fn decode_process_output(bytes: &[u8]) -> String {
+    decode_lines_with(bytes, decode_ansi)
+}
+
+fn decode_lines_with(bytes: &[u8], fallback: impl Fn(&[u8]) -> String) -> String {
+    // Fast path: already valid UTF-8
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return s.to_string();
+    }
+    bytes
+        .split_inclusive(|b| *b == b'\n')
+        .map(|line| match std::str::from_utf8(line) {
+            Ok(s) => s.to_string(),
+            Err(_) => fallback(line),
+        })
+        .collect()
+}
+
+/// Decode via the Win32 `MultiByteToWideChar` API so CJK paths survive
+#[cfg(windows)]
+fn decode_ansi(bytes: &[u8]) -> String {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+
+    extern "system" {
+        fn MultiByteToWideChar(
+            code_page: u32,
+            flags: u32,
+            multi_byte_str: *const u8,
+            multi_byte: i32,
+            wide_char_str: *mut u16,
+            wide_char: i32,
+        ) -> i32;
+    }
+
+    const CP_ACP: u32 = 0; // System default Windows ANSI code page
+
+    unsafe {
+        let len = MultiByteToWideChar(
+            CP_ACP,
+            0,
+            bytes.as_ptr(),
+            bytes.len() as i32,
+            std::ptr::null_mut(),
+            0,
+        );
+        if len <= 0 {
+            return String::from_utf8_lossy(bytes).into_owned();
+        }
+        let mut wide = vec![0u16; len as usize];
+        MultiByteToWideChar(
+            CP_ACP,
+            0,
+            bytes.as_ptr(),
+            bytes.len() as i32,
+            wide.as_mut_ptr(),
+            len,
+        );
+        OsString::from_wide(&wide).to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(not(windows))]
+fn decode_ansi(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "中文" in GBK (code page 936)
+    const GBK_CHINESE: &[u8] = &[0xD6, 0xD0, 0xCE, 0xC4];
+    /// "日本語" in Shift_JIS (code page 932)
+    const SJIS_JAPANESE: &[u8] = &[0x93, 0xFA, 0x96, 0x7B, 0x8C, 0xEA];
+
+    fn line(prefix: &str, body: &[u8]) -> Vec<u8> {
+        [prefix.as_bytes(), body, b".mp4\n"].concat()
+    }
+
+    /// Stand-in for the ANSI code page so the test runs on every platform
+    fn fake_code_page(bytes: &[u8]) -> String {
+        let text = if bytes.windows(4).any(|w| w == GBK_CHINESE) {
+            "中文"
+        } else if bytes.windows(6).any(|w| w == SJIS_JAPANESE) {
+            "日本語"
+        } else {
+            "?"
+        };
+        format!("[ansi] {}\n", text)
+    }
+
+    #[test]
+    fn only_non_utf8_lines_use_the_fallback() {
+        let mut output = "[download] Destination: Tiếng Việt ⧸ 한국어.mp4\n"
+            .as_bytes()
+            .to_vec();
+        output.extend(line("ERROR: ", GBK_CHINESE));
+        output.extend("[download] 100% of 3.00MiB\n".as_bytes());
+        output.extend(line("WARNING: ", SJIS_JAPANESE));
+
+        assert_eq!(
+            decode_lines_with(&output, fake_code_page),
+            "[download] Destination: Tiếng Việt ⧸ 한국어.mp4\n\
+             [ansi] 中文\n\
+             [download] 100% of 3.00MiB\n\
+             [ansi] 日本語\n"
+        );
+    }
+
+    #[test]
+    fn invalid_bytes_never_drop_the_line() {
+        let utf8 = "after_move:\t/tmp/日本語.mp4";
+        assert_eq!(decode_process_output(utf8.as_bytes()), utf8);
+
+        let decoded = decode_process_output(&line("after_move:\t/tmp/", GBK_CHINESE));
+        assert!(decoded.starts_with("after_move:\t/tmp/"));
+        assert!(decoded.ends_with(".mp4\n"));
+    }
+}
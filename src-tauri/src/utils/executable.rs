@@ -0,0 +1,205 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::process::Command;
+
+use super::CommandExt;
+
+const BINARY_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Remove the `com.apple.quarantine` attribute browsers and some HTTP
+/// stacks attach to downloads, so Gatekeeper doesn't block the binary
+#[cfg(target_os = "macos")]
+pub fn clear_quarantine(path: &Path) -> Result<(), String> {
+    use std::ffi::{c_char, c_int, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    extern "C" {
+        fn removexattr(path: *const c_char, name: *const c_char, options: c_int) -> c_int;
+    }
+    // ENOATTR: the file was never quarantined
+    const ENOATTR: i32 = 93;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| format!("Invalid binary path: {}", path.display()))?;
+    let result = unsafe { removexattr(c_path.as_ptr(), c"com.apple.quarantine".as_ptr(), 0) };
+    if result == 0 {
+        return Ok(());
+    }
+    let error = std::io::Error::last_os_error();
+    if error.raw_os_error() == Some(ENOATTR) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to clear quarantine on {}: {}",
+            path.display(),
+            error
+        ))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn clear_quarantine(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+/// Make an installed binary runnable: executable bit on Unix, no quarantine on macOS
+pub async fn prepare_installed_binary(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| format!("Failed to get file metadata: {}", e))?
+            .permissions();
+        perms.set_mode(0o755);
+        tokio::fs::set_permissions(path, perms)
+            .await
+            .map_err(|e| format!("Failed to set permissions: {}", e))?;
+    }
+    clear_quarantine(path)
+}
+
+/// Error for a binary the OS refused to start, with what the user can do about it
+pub fn blocked_binary_message(path: &Path, reason: &str) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    #[cfg(target_os = "macos")]
+    let remedy = format!(
+        "Open System Settings > Privacy & Security and choose \"Allow Anyway\", or run: xattr -d com.apple.quarantine \"{}\"",
+        path.display()
+    );
+    #[cfg(not(target_os = "macos"))]
+    let remedy = format!(
+        "Check that \"{}\" is executable and not held back by antivirus software",
+        path.display()
+    );
+    format!("{} was blocked from running ({}). {}", name, reason, remedy)
+}
+
+/// Start `path` with `version_arg` to confirm the OS lets it execute. A
+/// non-zero exit is left to the caller; only refusals and kills count here.
+pub async fn verify_binary_runs(path: &Path, version_arg: &str) -> Result<(), String> {
+    let mut cmd = Command::new(path);
+    cmd.arg(version_arg)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    cmd.hide_window();
+
+    match tokio::time::timeout(BINARY_CHECK_TIMEOUT, cmd.status()).await {
+        Ok(Ok(status)) => {
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::ExitStatusExt;
+                // Gatekeeper and code-signing failures show up as SIGKILL
+                if let Some(signal) = status.signal() {
+                    return Err(blocked_binary_message(
+                        path,
+                        &format!("killed by signal {}", signal),
+                    ));
+                }
+            }
+            #[cfg(not(unix))]
+            let _ = status;
+            Ok(())
+        }
+        Ok(Err(e)) => Err(blocked_binary_message(path, &e.to_string())),
+        Err(_) => Err(blocked_binary_message(path, "it did not respond")),
+    }
+}
+
+/// Put a downloaded binary in place of `target` once it has been checked to
+/// run. The previous binary is moved aside first and put back when the swap
+/// fails, so a bad download never leaves the app without a working one.
+pub async fn install_verified_binary(
+    temp_path: &Path,
+    target: &Path,
+    version_arg: &str,
+) -> Result<(), String> {
+    let checked = match prepare_installed_binary(temp_path).await {
+        Ok(()) => verify_binary_runs(temp_path, version_arg).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = checked {
+        tokio::fs::remove_file(temp_path).await.ok();
+        return Err(e);
+    }
+
+    let backup = target.with_extension("bak");
+    let had_previous = target.exists();
+    if had_previous {
+        tokio::fs::rename(target, &backup)
+            .await
+            .map_err(|e| format!("Failed to back up the current binary: {}", e))?;
+    }
+    if let Err(e) = tokio::fs::rename(temp_path, target).await {
+        if had_previous {
+            tokio::fs::rename(&backup, target).await.ok();
+        }
+        tokio::fs::remove_file(temp_path).await.ok();
+        return Err(format!("Failed to rename binary: {}", e));
+    }
+    if had_previous {
+        // Still in use on Windows; the next update replaces it
+        tokio::fs::remove_file(&backup).await.ok();
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn prepared_binary_runs_and_killed_binary_is_reported() {
+        let dir = std::env::temp_dir().join(format!("youwee-exec-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ok = dir.join("tool");
+        std::fs::write(&ok, "#!/bin/sh\nexit 0\n").unwrap();
+        prepare_installed_binary(&ok).await.unwrap();
+        assert!(verify_binary_runs(&ok, "--version").await.is_ok());
+
+        let killed = dir.join("killed");
+        std::fs::write(&killed, "#!/bin/sh\nkill -9 $$\n").unwrap();
+        prepare_installed_binary(&killed).await.unwrap();
+        let error = verify_binary_runs(&killed, "--version").await.unwrap_err();
+        assert!(error.starts_with("killed was blocked from running (killed by signal 9)"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn broken_download_keeps_the_installed_binary() {
+        let dir = std::env::temp_dir().join(format!("youwee-install-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("tool");
+        std::fs::write(&target, "#!/bin/sh\necho old\n").unwrap();
+
+        let broken = dir.join("tool.tmp");
+        std::fs::write(&broken, "#!/bin/sh\nkill -9 $$\n").unwrap();
+        assert!(install_verified_binary(&broken, &target, "--version")
+            .await
+            .is_err());
+        assert_eq!(
+            std::fs::read_to_string(&target).unwrap(),
+            "#!/bin/sh\necho old\n"
+        );
+        assert!(!broken.exists());
+
+        let fixed = dir.join("tool.tmp");
+        std::fs::write(&fixed, "#!/bin/sh\nexit 0\n").unwrap();
+        install_verified_binary(&fixed, &target, "--version")
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&target).unwrap(),
+            "#!/bin/sh\nexit 0\n"
+        );
+        assert!(!dir.join("tool.bak").exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -55,6 +55,61 @@ fn apply_fps_filter(format_string: String, preferred_fps: Option<&str>) -> Strin
         .join("/")
 }
 
+/// Normalize requested audio track languages (e.g. "en", "pt-BR") for format filters
+pub fn normalize_audio_langs(audio_langs: &[String]) -> Vec<String> {
+    let mut langs: Vec<String> = Vec::new();
+    for lang in audio_langs {
+        let lang = lang.trim();
+        if lang.is_empty() || !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            continue;
+        }
+        if !langs
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(lang))
+        {
+            langs.push(lang.to_string());
+        }
+    }
+    langs
+}
+
+/// Prefer audio tracks in the requested languages, keeping the original selection as fallback.
+///
+/// One language narrows every `bestaudio` candidate. Several languages merge one
+/// audio stream per language, which needs `--audio-multistreams` and an MKV container.
+pub fn apply_audio_language_filter(format_string: &str, audio_langs: &[String]) -> String {
+    if audio_langs.is_empty() {
+        return format_string.to_string();
+    }
+
+    let with_lang = |audio: &str, lang: &str| {
+        audio.replacen("bestaudio", &format!("bestaudio[language^={}]", lang), 1)
+    };
+
+    let preferred: Vec<String> = format_string
+        .split('/')
+        .filter(|candidate| candidate.contains("bestaudio"))
+        .map(|candidate| match candidate.split_once("+bestaudio") {
+            Some((video, audio_rest)) if audio_langs.len() > 1 => {
+                let audio = format!("bestaudio{}", audio_rest);
+                let tracks = audio_langs
+                    .iter()
+                    .map(|lang| with_lang(&audio, lang))
+                    .collect::<Vec<_>>()
+                    .join("+");
+                format!("{}+{}", video, tracks)
+            }
+            _ => with_lang(candidate, &audio_langs[0]),
+        })
+        .collect();
+
+    if preferred.is_empty() {
+        return format_string.to_string();
+    }
+
+    format!("{}/{}", preferred.join("/"), format_string)
+}
+
 /// Build yt-dlp format string based on quality, format, codec and FPS preferences
 pub fn build_format_string(
     quality: &str,
@@ -231,9 +286,34 @@ pub fn build_format_string(
     apply_fps_filter(format_string, preferred_fps)
 }
 
+/// Default selector for short-form clips: H.264 MP4 capped at 1080 wide, so
+/// vertical clips don't go hunting for 4K VP9/AV1 streams. None when the user
+/// picked a resolution, another codec or another container.
+pub fn short_form_format_string(
+    quality: &str,
+    format: &str,
+    video_codec: &str,
+    preferred_fps: Option<&str>,
+) -> Option<String> {
+    let default_quality = matches!(quality, "best" | "8k" | "4k" | "2k" | "");
+    let auto_codec = matches!(video_codec, "auto" | "h264" | "");
+    (default_quality && auto_codec && format == "mp4").then(|| {
+        apply_fps_filter(
+            "bestvideo[width<=1080][vcodec^=avc][ext=mp4]+bestaudio[ext=m4a]/\
+             bestvideo[width<=1080][ext=mp4]+bestaudio[ext=m4a]/\
+             best[width<=1080][ext=mp4]/best"
+                .to_string(),
+            preferred_fps,
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::build_format_string;
+    use super::{
+        apply_audio_language_filter, build_format_string, normalize_audio_langs,
+        short_form_format_string,
+    };
 
     #[test]
     fn webm_4k_ignores_h264_and_uses_webm_streams() {
@@ -270,4 +350,54 @@ mod tests {
         assert!(!format.contains("[fps<="));
         assert!(format.contains("bestvideo[height<=1080][ext=mp4]"));
     }
+
+    #[test]
+    fn single_audio_language_narrows_audio_candidates_with_fallback() {
+        let format = apply_audio_language_filter(
+            "bestvideo[height<=1080]+bestaudio/best[height<=1080]/best",
+            &["es".to_string()],
+        );
+
+        assert_eq!(
+            format,
+            "bestvideo[height<=1080]+bestaudio[language^=es]/\
+             bestvideo[height<=1080]+bestaudio/best[height<=1080]/best"
+        );
+    }
+
+    #[test]
+    fn multiple_audio_languages_merge_one_stream_per_language() {
+        let format = apply_audio_language_filter(
+            "bestvideo[ext=mp4]+bestaudio[ext=m4a]/best",
+            &["en".to_string(), "ja".to_string()],
+        );
+
+        assert!(format.starts_with(
+            "bestvideo[ext=mp4]+bestaudio[language^=en][ext=m4a]+bestaudio[language^=ja][ext=m4a]/"
+        ));
+        assert!(format.ends_with("/bestvideo[ext=mp4]+bestaudio[ext=m4a]/best"));
+    }
+
+    #[test]
+    fn normalize_audio_langs_drops_invalid_and_duplicate_entries() {
+        let langs = normalize_audio_langs(&[
+            " en ".to_string(),
+            "EN".to_string(),
+            "pt-BR".to_string(),
+            "es]".to_string(),
+            String::new(),
+        ]);
+
+        assert_eq!(langs, vec!["en".to_string(), "pt-BR".to_string()]);
+    }
+
+    #[test]
+    fn short_form_default_only_replaces_best_mp4() {
+        let format =
+            short_form_format_string("4k", "mp4", "auto", None).expect("short-form selector");
+        assert!(format.starts_with("bestvideo[width<=1080][vcodec^=avc][ext=mp4]"));
+        assert!(short_form_format_string("720", "mp4", "auto", None).is_none());
+        assert!(short_form_format_string("best", "webm", "auto", None).is_none());
+        assert!(short_form_format_string("best", "mp4", "av1", None).is_none());
+    }
 }
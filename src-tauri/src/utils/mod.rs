@@ -1,17 +1,27 @@
 mod command;
+mod encoding;
+mod executable;
 mod extract;
 mod filename;
 mod firefox_profiles;
 mod format;
 mod path;
 mod progress;
+mod prompt_template;
 mod security;
+mod short_form;
+mod source;
 
 pub use command::*;
+pub use encoding::*;
+pub use executable::*;
 pub use extract::*;
 pub use filename::*;
 pub use firefox_profiles::*;
 pub use format::*;
 pub use path::*;
 pub use progress::*;
+pub use prompt_template::*;
 pub use security::*;
+pub use short_form::*;
+pub use source::*;
@@ -1,57 +1,228 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use tauri::{AppHandle, Manager};
+
+use crate::types::{code, BackendError};
 
 #[cfg(windows)]
 use super::command::CommandExt;
 
+/// File next to the executable that turns on portable mode
+const PORTABLE_MARKER_FILE: &str = "portable.txt";
+/// Launch flag that turns on portable mode
+const PORTABLE_FLAG: &str = "--portable";
+
+static PORTABLE_DATA_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Folder holding the executable, or the folder holding the `.app` bundle on macOS
+fn portable_base_dir(exe: &Path) -> Option<PathBuf> {
+    let dir = exe.parent()?;
+    let bundle = cfg!(target_os = "macos")
+        .then(|| {
+            dir.ancestors()
+                .find(|ancestor| ancestor.extension().is_some_and(|ext| ext == "app"))
+        })
+        .flatten();
+    Some(bundle.and_then(Path::parent).unwrap_or(dir).to_path_buf())
+}
+
+fn detect_portable_data_dir() -> Option<PathBuf> {
+    let base = portable_base_dir(&std::env::current_exe().ok()?)?;
+    let requested = std::env::args().any(|arg| arg == PORTABLE_FLAG)
+        || base.join(PORTABLE_MARKER_FILE).is_file();
+    requested.then(|| base.join("data"))
+}
+
+/// The `data/` folder next to the executable when running in portable mode
+pub fn portable_data_dir() -> Option<&'static Path> {
+    PORTABLE_DATA_DIR
+        .get_or_init(detect_portable_data_dir)
+        .as_deref()
+}
+
+/// Where the database, binaries, previews and settings are kept: `data/`
+/// next to the executable in portable mode, the OS app data folder otherwise.
+pub fn data_dir(app: &AppHandle) -> tauri::Result<PathBuf> {
+    match portable_data_dir() {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => app.path().app_data_dir(),
+    }
+}
+
 /// Sanitize and validate output path to prevent path traversal attacks
 pub fn sanitize_output_path(path: &str) -> Result<String, String> {
+    resolve_output_directory(path, true).map_err(|e| e.message().to_string())
+}
+
+/// Validate an output directory, optionally creating it, and make sure it is writable.
+///
+/// With `create_if_missing` disabled a missing folder is reported as
+/// `OUTPUT_DIR_NOT_FOUND` instead of being created. Read-only volumes and
+/// folders without write permission are reported as `OUTPUT_DIR_NOT_WRITABLE`
+/// before yt-dlp gets a chance to fail halfway through a download.
+pub fn resolve_output_directory(
+    path: &str,
+    create_if_missing: bool,
+) -> Result<String, BackendError> {
     // Check for empty path
     if path.is_empty() {
-        return Err(
-            "Invalid output path: path cannot be empty. Please select an output folder."
-                .to_string(),
-        );
+        return Err(BackendError::from_message(
+            "Invalid output path: path cannot be empty. Please select an output folder.",
+        ));
     }
 
     // Check for obvious path traversal attempts
     if path.contains("..") {
-        return Err("Invalid output path: path traversal detected".to_string());
+        return Err(BackendError::from_message(
+            "Invalid output path: path traversal detected",
+        ));
     }
 
     let path = Path::new(path);
 
     // Ensure the path is absolute
     if !path.is_absolute() {
-        return Err("Invalid output path: must be an absolute path".to_string());
+        return Err(BackendError::from_message(
+            "Invalid output path: must be an absolute path",
+        ));
     }
 
     // Create directory if it doesn't exist (for ChromeOS/Linux where Downloads may not exist)
     if !path.exists() {
-        std::fs::create_dir_all(path).map_err(|e| {
-            format!(
-                "Failed to create output directory: {}. Please select a different folder.",
-                e
+        if !create_if_missing {
+            return Err(BackendError::new(
+                code::OUTPUT_DIR_NOT_FOUND,
+                format!("Output folder does not exist: {}", path.display()),
             )
+            .with_retryable(false)
+            .with_param("path", path.to_string_lossy().to_string()));
+        }
+
+        std::fs::create_dir_all(path).map_err(|e| {
+            if is_write_denied_error(&e) {
+                output_directory_not_writable_error(path, &e)
+            } else {
+                BackendError::from_message(format!(
+                    "Failed to create output directory: {}. Please select a different folder.",
+                    e
+                ))
+            }
         })?;
     }
 
     // Canonicalize to resolve any symlinks and normalize the path
     let canonical = path
         .canonicalize()
-        .map_err(|e| format!("Invalid output path: {}", e))?;
+        .map_err(|e| BackendError::from_message(format!("Invalid output path: {}", e)))?;
 
     // Verify it's a directory
     if !canonical.is_dir() {
-        return Err("Invalid output path: not a directory".to_string());
+        return Err(BackendError::from_message(
+            "Invalid output path: not a directory",
+        ));
     }
 
+    probe_directory_writable(&canonical)?;
+
     canonical
         .to_str()
-        .ok_or_else(|| "Invalid output path: contains invalid UTF-8".to_string())
+        .ok_or_else(|| BackendError::from_message("Invalid output path: contains invalid UTF-8"))
         .map(|s| s.to_string())
 }
 
+/// Create and remove a small marker file to confirm the directory accepts writes.
+fn probe_directory_writable(dir: &Path) -> Result<(), BackendError> {
+    let probe_path = dir.join(format!(".youwee-write-test-{}", uuid::Uuid::new_v4()));
+    let result = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe_path);
+
+    match result {
+        Ok(file) => {
+            drop(file);
+            std::fs::remove_file(&probe_path).ok();
+            Ok(())
+        }
+        Err(e) if is_write_denied_error(&e) => Err(output_directory_not_writable_error(dir, &e)),
+        Err(e) => Err(BackendError::from_message(format!(
+            "Failed to write to output directory: {}",
+            e
+        ))),
+    }
+}
+
+fn is_write_denied_error(error: &std::io::Error) -> bool {
+    if error.kind() == std::io::ErrorKind::PermissionDenied {
+        return true;
+    }
+
+    // EROFS on Linux/macOS, ERROR_WRITE_PROTECT on Windows
+    #[cfg(unix)]
+    const READ_ONLY_OS_ERROR: i32 = 30;
+    #[cfg(windows)]
+    const READ_ONLY_OS_ERROR: i32 = 19;
+    #[cfg(not(any(unix, windows)))]
+    const READ_ONLY_OS_ERROR: i32 = -1;
+
+    error.raw_os_error() == Some(READ_ONLY_OS_ERROR)
+}
+
+fn output_directory_not_writable_error(dir: &Path, error: &std::io::Error) -> BackendError {
+    BackendError::new(
+        code::OUTPUT_DIR_NOT_WRITABLE,
+        format!(
+            "Output folder is not writable: {} ({}). Please select a different folder or check its permissions.",
+            dir.display(),
+            error
+        ),
+    )
+    .with_retryable(false)
+    .with_param("path", dir.to_string_lossy().to_string())
+}
+
+const AUDIO_EXTENSIONS: [&str; 8] = ["mp3", "m4a", "opus", "flac", "wav", "aac", "ogg", "oga"];
+const VIDEO_EXTENSIONS: [&str; 7] = ["mp4", "mkv", "webm", "mov", "avi", "flv", "m4v"];
+const IMAGE_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "webp", "gif", "heic", "avif"];
+/// Containers yt-dlp leaves behind as the finished download
+const FINAL_MEDIA_EXTENSIONS: [&str; 8] =
+    ["mp3", "m4a", "opus", "mp4", "mkv", "webm", "flac", "wav"];
+const SUBTITLE_EXTENSIONS: [&str; 6] = ["srt", "vtt", "ass", "ssa", "lrc", "ttml"];
+
+fn lowercase_extension(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+}
+
+/// Classify a downloaded file as "video", "audio" or "image" by its extension.
+///
+/// Unknown extensions count as video since that is what most downloads are.
+pub fn media_type_for_path(path: &str) -> &'static str {
+    match lowercase_extension(path) {
+        Some(ext) if AUDIO_EXTENSIONS.contains(&ext.as_str()) => "audio",
+        Some(ext) if IMAGE_EXTENSIONS.contains(&ext.as_str()) => "image",
+        Some(ext) if SUBTITLE_EXTENSIONS.contains(&ext.as_str()) => "subtitle",
+        _ => "video",
+    }
+}
+
+/// Whether a yt-dlp output line is a finished video or audio file
+pub fn is_media_output_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| FINAL_MEDIA_EXTENSIONS.contains(&ext))
+}
+
+/// Whether a yt-dlp output line is a downloaded image (photo posts)
+pub fn is_image_output_path(path: &str) -> bool {
+    lowercase_extension(path).is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
 /// Build candidate executable paths from the current process PATH plus platform fallbacks.
 ///
 /// On Windows, GUI apps can inherit a stale or reduced PATH from Explorer. To better match
@@ -147,6 +318,26 @@ fn push_path_string_candidates(candidates: &mut Vec<PathBuf>, path_var: &str, bi
     }
 }
 
+/// `dir/stem.ext`, or `dir/stem (n).ext` when that file already exists.
+/// An empty `extension` leaves the name without one.
+pub fn unique_output_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let file_name = |suffix: String| {
+        if extension.is_empty() {
+            format!("{}{}", stem, suffix)
+        } else {
+            format!("{}{}.{}", stem, suffix, extension)
+        }
+    };
+    let candidate = dir.join(file_name(String::new()));
+    if !candidate.exists() {
+        return candidate;
+    }
+    (1..)
+        .map(|n| dir.join(file_name(format!(" ({})", n))))
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
+
 fn unique_paths(paths: Vec<PathBuf>) -> Vec<PathBuf> {
     let mut unique = Vec::new();
     let mut seen = HashSet::new();
@@ -337,6 +528,36 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn portable_base_dir_is_next_to_the_executable_or_bundle() {
+        assert_eq!(
+            portable_base_dir(Path::new("/media/usb/Youwee/youwee")),
+            Some(PathBuf::from("/media/usb/Youwee"))
+        );
+        let bundled = portable_base_dir(Path::new("/Volumes/USB/Youwee.app/Contents/MacOS/youwee"));
+        if cfg!(target_os = "macos") {
+            assert_eq!(bundled, Some(PathBuf::from("/Volumes/USB")));
+        } else {
+            assert_eq!(
+                bundled,
+                Some(PathBuf::from("/Volumes/USB/Youwee.app/Contents/MacOS"))
+            );
+        }
+    }
+
+    #[test]
+    fn media_type_for_path_detects_gallery_images() {
+        assert_eq!(media_type_for_path("/tmp/post/photo_01.JPG"), "image");
+        assert_eq!(media_type_for_path("/tmp/post/slide.webp"), "image");
+        assert_eq!(media_type_for_path("/tmp/song.opus"), "audio");
+        assert_eq!(media_type_for_path("/tmp/video.mkv"), "video");
+        assert_eq!(media_type_for_path("/tmp/talk.en.srt"), "subtitle");
+        assert!(is_image_output_path("/tmp/post/slide.png"));
+        assert!(!is_media_output_path("/tmp/post/slide.png"));
+        assert!(is_media_output_path("/tmp/video.webm"));
+        assert!(!is_media_output_path("/tmp/video.info.json"));
+    }
+
     #[test]
     fn unique_paths_preserves_first_occurrence() {
         let paths = unique_paths(vec![
@@ -376,6 +597,45 @@ mod tests {
         );
     }
 
+    fn make_temp_root() -> PathBuf {
+        std::env::temp_dir().join(format!("youwee-output-dir-test-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn resolve_output_directory_creates_missing_tree_when_allowed() {
+        let root = make_temp_root();
+        let nested = root.join("fresh").join("downloads");
+
+        let resolved = resolve_output_directory(nested.to_str().expect("utf8 path"), true)
+            .expect("missing directory should be created");
+
+        assert!(Path::new(&resolved).is_dir());
+        let leftovers = std::fs::read_dir(&resolved)
+            .expect("read resolved directory")
+            .count();
+        assert_eq!(leftovers, 0, "write probe must clean up after itself");
+
+        std::fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn resolve_output_directory_reports_missing_folder_when_creation_is_off() {
+        let root = make_temp_root();
+
+        let error = resolve_output_directory(root.to_str().expect("utf8 path"), false)
+            .expect_err("missing directory should be rejected");
+
+        assert_eq!(error.code(), code::OUTPUT_DIR_NOT_FOUND);
+        assert_eq!(error.to_wire().retryable, Some(false));
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn resolve_output_directory_rejects_relative_and_traversal_paths() {
+        assert!(resolve_output_directory("relative/folder", true).is_err());
+        assert!(resolve_output_directory("/tmp/../etc", true).is_err());
+    }
+
     #[cfg(windows)]
     #[test]
     fn parses_windows_reg_query_path_value() {
@@ -1,8 +1,15 @@
-/// Parse yt-dlp progress output
-/// Returns (percent, speed, eta, playlist_index, playlist_count, downloaded_size, elapsed_time)
-pub fn parse_progress(
-    line: &str,
-) -> Option<(
+use super::format_size;
+
+/// Marker opening the progress lines produced by `YTDLP_PROGRESS_TEMPLATE`
+const PROGRESS_MARKER: &str = "[youwee-progress]";
+
+/// `--progress-template` emitting raw numbers, so progress parsing does not
+/// depend on yt-dlp's wording, unit formatting or the shell's locale.
+/// Fields: status|downloaded|total|total estimate|speed|eta|elapsed|playlist index|playlist count
+pub const YTDLP_PROGRESS_TEMPLATE: &str = "download:[youwee-progress] %(progress.status)s|%(progress.downloaded_bytes)s|%(progress.total_bytes)s|%(progress.total_bytes_estimate)s|%(progress.speed)s|%(progress.eta)s|%(progress.elapsed)s|%(info.playlist_index)s|%(info.n_entries)s";
+
+/// (percent, speed, eta, playlist_index, playlist_count, downloaded_size, elapsed_time)
+pub type ParsedProgress = (
     f64,
     String,
     String,
@@ -10,7 +17,79 @@ pub fn parse_progress(
     Option<u32>,
     Option<String>,
     Option<String>,
-)> {
+);
+
+/// `[hh:]mm:ss`, the way yt-dlp prints durations
+fn format_clock(seconds: f64) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    let (hours, minutes, secs) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+/// Parse a line produced by `YTDLP_PROGRESS_TEMPLATE`. Missing values arrive as "NA".
+fn parse_template_progress(line: &str) -> Option<ParsedProgress> {
+    let rest = &line[line.find(PROGRESS_MARKER)? + PROGRESS_MARKER.len()..];
+    let fields: Vec<&str> = rest.trim().split('|').map(str::trim).collect();
+    if fields.len() != 9 {
+        return None;
+    }
+    let number = |index: usize| {
+        fields[index]
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite() && *value >= 0.0)
+    };
+    let whole = |index: usize| fields[index].parse::<u32>().ok();
+
+    let finished = match fields[0] {
+        "downloading" => false,
+        "finished" => true,
+        _ => return None,
+    };
+    let downloaded = number(1).unwrap_or(0.0);
+    let total = number(2).or(number(3)).filter(|total| *total > 0.0);
+    let percent = match total {
+        _ if finished => 100.0,
+        Some(total) => ((downloaded / total * 1000.0).round() / 10.0).min(100.0),
+        None => 0.0,
+    };
+    let speed = number(4)
+        .map(|speed| format!("{}/s", format_size(speed as u64)))
+        .unwrap_or_default();
+    let eta = number(5).map(format_clock).unwrap_or_default();
+    // Without a known size (live streams) report what has been fetched so far
+    let (downloaded_size, elapsed_time) = if total.is_none() && !finished {
+        (
+            Some(format_size(downloaded as u64)),
+            number(6).map(format_clock),
+        )
+    } else {
+        (None, None)
+    };
+
+    Some((
+        percent,
+        speed,
+        eta,
+        whole(7),
+        whole(8),
+        downloaded_size,
+        elapsed_time,
+    ))
+}
+
+/// Parse yt-dlp progress output.
+/// Lines from `YTDLP_PROGRESS_TEMPLATE` are preferred; the default English
+/// progress lines are still understood for commands run without it.
+pub fn parse_progress(line: &str) -> Option<ParsedProgress> {
+    if line.contains(PROGRESS_MARKER) {
+        return parse_template_progress(line);
+    }
+
     let mut playlist_index: Option<u32> = None;
     let mut playlist_count: Option<u32> = None;
 
@@ -92,3 +171,200 @@ pub fn parse_progress(
 
     None
 }
+
+/// Coarse phase of a yt-dlp download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadStage {
+    PreProcessing,
+    Downloading,
+    Merging,
+    PostProcessing,
+}
+
+impl DownloadStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DownloadStage::PreProcessing => "pre_processing",
+            DownloadStage::Downloading => "downloading",
+            DownloadStage::Merging => "merging",
+            DownloadStage::PostProcessing => "post_processing",
+        }
+    }
+
+    /// Stages where yt-dlp prints no percentage
+    pub fn is_indeterminate(self) -> bool {
+        !matches!(self, DownloadStage::Downloading)
+    }
+}
+
+/// yt-dlp postprocessors that run after the media is on disk
+const POST_PROCESSORS: &[&str] = &[
+    "EmbedThumbnail",
+    "Metadata",
+    "ExtractAudio",
+    "EmbedSubtitle",
+    "FixupM3u8",
+    "FixupM4a",
+    "FixupStretched",
+    "FixupDuplicateMoov",
+    "FixupTimestamp",
+    "FixupDuration",
+    "ModifyChapters",
+    "SponsorBlock",
+    "SplitChapters",
+    "ThumbnailsConvertor",
+    "SubtitlesConvertor",
+    "VideoConvertor",
+    "VideoRemuxer",
+    "Exec",
+];
+
+/// Detect a stage marker in a yt-dlp output line.
+/// Returns the stage and the marker that produced it (e.g. "Merger").
+pub fn parse_download_stage(line: &str) -> Option<(DownloadStage, String)> {
+    let rest = line.trim_start().strip_prefix('[')?;
+    let marker = &rest[..rest.find(']')?];
+
+    let stage = match marker {
+        "download" | "youwee-progress" => DownloadStage::Downloading,
+        "Merger" => DownloadStage::Merging,
+        m if POST_PROCESSORS.contains(&m) => DownloadStage::PostProcessing,
+        // Extractor lines: "[youtube] abc: Downloading webpage", "[info] abc: Downloading 1 format(s)"
+        m if !m.is_empty()
+            && !m.contains(char::is_whitespace)
+            && m.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+            && rest.contains(": ") =>
+        {
+            DownloadStage::PreProcessing
+        }
+        _ => return None,
+    };
+    Some((stage, marker.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_download_stage_maps_yt_dlp_markers() {
+        let stage = parse_download_stage;
+        assert_eq!(
+            stage("[youtube] dQw4w9WgXcQ: Downloading webpage"),
+            Some((DownloadStage::PreProcessing, "youtube".to_string()))
+        );
+        assert_eq!(
+            stage("[download]  42.0% of 10.00MiB at 1.00MiB/s ETA 00:05").map(|(s, _)| s),
+            Some(DownloadStage::Downloading)
+        );
+        assert_eq!(
+            stage("[Merger] Merging formats into \"a.mkv\"").map(|(s, _)| s),
+            Some(DownloadStage::Merging)
+        );
+        assert_eq!(
+            stage("[EmbedThumbnail] ffmpeg: Adding thumbnail to \"a.mp4\""),
+            Some((DownloadStage::PostProcessing, "EmbedThumbnail".to_string()))
+        );
+        assert_eq!(
+            stage("[ExtractAudio] Destination: a.mp3").map(|(s, _)| s),
+            Some(DownloadStage::PostProcessing)
+        );
+        assert_eq!(stage("WARNING: [youtube] something"), None);
+        assert_eq!(stage("/home/user/a.mp4"), None);
+    }
+
+    #[test]
+    fn parse_progress_reads_template_lines() {
+        assert_eq!(
+            parse_progress(
+                "[youwee-progress] downloading|5242880|10485760|NA|1048576.5|5|2.1|3|12"
+            ),
+            Some((
+                50.0,
+                "1.00 MB/s".to_string(),
+                "00:05".to_string(),
+                Some(3),
+                Some(12),
+                None,
+                None
+            ))
+        );
+        // Live stream: no total size
+        assert_eq!(
+            parse_progress("[youwee-progress] downloading|3009413|NA|NA|518791|NA|3725|NA|NA"),
+            Some((
+                0.0,
+                "506.63 KB/s".to_string(),
+                String::new(),
+                None,
+                None,
+                Some("2.87 MB".to_string()),
+                Some("01:02:05".to_string())
+            ))
+        );
+        let finished = parse_progress("[youwee-progress] finished|10|NA|NA|NA|NA|1|NA|NA");
+        assert_eq!(finished.map(|p| p.0), Some(100.0));
+        assert_eq!(
+            parse_progress("[youwee-progress] error|1|2|NA|NA|NA|NA|NA|NA"),
+            None
+        );
+    }
+
+    /// Output captured from real downloads on each platform
+    const CAPTURED_OUTPUT: &[&str] = &[
+        "[youtube] Extracting URL: https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+        "[info] dQw4w9WgXcQ: Downloading 1 format(s): 137+140",
+        "[download] Destination: C:\\Users\\Ann\\Videos\\Song.f137.mp4",
+        "[download] Destination: /Users/ann/Movies/Song.f137.mp4",
+        "[download]  12.3% of   48.17MiB at    2.31MiB/s ETA 00:18",
+        "[download]  12.3% of ~  48.17MiB at  Unknown B/s ETA Unknown (frag 3/120)",
+        "\r[download] 100% of   48.17MiB in 00:00:21 at 2.25MiB/s   ",
+        "[download] Downloading item 4 of 27",
+        "[download] Downloading playlist: Mix",
+        "[download]    2.87MiB at  506.63KiB/s (00:00:07) (frag 91/2097)",
+        "[ExtractAudio] Destination: /home/ann/Music/Song.mp3",
+        "[Merger] Merging formats into \"/home/ann/Videos/Song.mkv\"",
+        "Deleting original file /home/ann/Videos/Song.f137.mp4 (pass -k to keep)",
+        "[download] /home/ann/Videos/Song.mp4 has already been downloaded",
+        "[youwee-progress] downloading|1024|NA|4096.0|NA|NA|0.5|1|2",
+        "[youwee-progress] downloading|NA|NA|NA|NA|NA|NA|NA|NA",
+        "[youwee-progress] finished|4096|4096|NA|2048.0|0|2.0|2|2",
+        "ERROR: [youtube] abc: Video unavailable",
+    ];
+
+    #[test]
+    fn parse_progress_handles_captured_output() {
+        let parsed: Vec<_> = CAPTURED_OUTPUT.iter().map(|l| parse_progress(l)).collect();
+        assert_eq!(parsed[4].as_ref().map(|p| p.0), Some(12.3));
+        assert_eq!(
+            parsed[9].as_ref().and_then(|p| p.5.clone()),
+            Some("2.87MiB".to_string())
+        );
+        assert_eq!(
+            parsed[14].as_ref().map(|p| (p.0, p.3, p.4)),
+            Some((25.0, Some(1), Some(2)))
+        );
+        for index in [0, 1, 2, 3, 10, 11, 12, 17] {
+            assert!(parsed[index].is_none(), "{}", CAPTURED_OUTPUT[index]);
+        }
+    }
+
+    #[test]
+    fn parse_progress_survives_mangled_output() {
+        // Truncations and byte noise from interleaved or mis-decoded output
+        let mut seed: u32 = 0x5eed;
+        let noise = ['|', '%', '\r', 'é', '语', '.', '-', ' ', '9', 'N'];
+        for line in CAPTURED_OUTPUT {
+            for cut in (0..=line.len()).filter(|cut| line.is_char_boundary(*cut)) {
+                let mut mangled = line[..cut].to_string();
+                for _ in 0..3 {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                    mangled.push(noise[(seed >> 16) as usize % noise.len()]);
+                }
+                if let Some((percent, ..)) = parse_progress(&mangled) {
+                    assert!((0.0..=100.0).contains(&percent), "{}", mangled);
+                }
+            }
+        }
+    }
+}
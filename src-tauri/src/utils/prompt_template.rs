@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+/// A `{name}` placeholder, or literal text between placeholders
+enum TemplatePart<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Split a template into text and `{variable}` parts; `{{` and `}}` are
+/// literal braces and anything else in braces stays as written
+fn template_parts(template: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find(['{', '}']) {
+        if open > 0 {
+            parts.push(TemplatePart::Text(&rest[..open]));
+        }
+        let after = &rest[open + 1..];
+        if rest[open..].starts_with("{{") || rest[open..].starts_with("}}") {
+            parts.push(TemplatePart::Text(&rest[open..open + 1]));
+            rest = &after[1..];
+            continue;
+        }
+        if rest[open..].starts_with('{') {
+            if let Some(close) = after.find('}') {
+                if is_variable_name(&after[..close]) {
+                    parts.push(TemplatePart::Variable(&after[..close]));
+                    rest = &after[close + 1..];
+                    continue;
+                }
+            }
+        }
+        parts.push(TemplatePart::Text(&rest[open..open + 1]));
+        rest = after;
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest));
+    }
+    parts
+}
+
+/// Variable names used by a template, in order of first use
+pub fn prompt_template_variables(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for part in template_parts(template) {
+        if let TemplatePart::Variable(name) = part {
+            if !names.iter().any(|existing| existing == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Fill `{variable}` placeholders from `context`; fails listing any variable
+/// without a value
+pub fn render_prompt_template(
+    template: &str,
+    context: &HashMap<String, String>,
+) -> Result<String, String> {
+    let missing: Vec<String> = prompt_template_variables(template)
+        .into_iter()
+        .filter(|name| {
+            context
+                .get(name)
+                .is_none_or(|value| value.trim().is_empty())
+        })
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Missing values for template variables: {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(template_parts(template)
+        .into_iter()
+        .map(|part| match part {
+            TemplatePart::Text(text) => text,
+            TemplatePart::Variable(name) => context[name].trim(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variables_are_filled_and_braces_escaped() {
+        let template = "Cut {selection}, scale to {resolution} and keep it under {target_size} \
+                        ({{literal}}, {not a var}, {resolution})";
+        assert_eq!(
+            prompt_template_variables(template),
+            ["selection", "resolution", "target_size"]
+        );
+
+        let mut context = HashMap::from([
+            ("selection".to_string(), "00:10 to 00:20".to_string()),
+            ("resolution".to_string(), "1280x720".to_string()),
+        ]);
+        assert_eq!(
+            render_prompt_template(template, &context).unwrap_err(),
+            "Missing values for template variables: target_size"
+        );
+        context.insert("target_size".to_string(), "50MB".to_string());
+        assert_eq!(
+            render_prompt_template(template, &context).unwrap(),
+            "Cut 00:10 to 00:20, scale to 1280x720 and keep it under 50MB \
+             ({literal}, {not a var}, 1280x720)"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use super::detect_source;
+
+/// Longest clip still treated as short-form (YouTube Shorts allow 3 minutes)
+pub const SHORT_FORM_MAX_SECONDS: f64 = 180.0;
+
+/// Vertical video of at most [`SHORT_FORM_MAX_SECONDS`]; an unknown duration
+/// counts as short when the frame is vertical
+pub fn is_short_form(width: Option<u64>, height: Option<u64>, duration: Option<f64>) -> bool {
+    let vertical = matches!((width, height), (Some(w), Some(h)) if w > 0 && h > w);
+    vertical && duration.is_none_or(|seconds| seconds <= SHORT_FORM_MAX_SECONDS)
+}
+
+/// URL of a Shorts/Reels page or a site that only hosts short-form clips
+pub fn is_short_form_url(url: &str) -> bool {
+    let url = url.to_lowercase();
+    ["/shorts/", "/reel/", "/reels/"]
+        .iter()
+        .any(|segment| url.contains(segment))
+        || matches!(
+            detect_source(&url).as_deref(),
+            Some("tiktok") | Some("douyin")
+        )
+}
+
+/// Short-form check for a yt-dlp info JSON entry; flat playlist entries carry
+/// no dimensions, so their URL decides
+pub fn is_short_form_info(json: &serde_json::Value) -> bool {
+    let number = |key: &str| json.get(key).and_then(|v| v.as_u64());
+    if is_short_form(
+        number("width"),
+        number("height"),
+        json.get("duration").and_then(|v| v.as_f64()),
+    ) {
+        return true;
+    }
+    ["webpage_url", "original_url", "url"]
+        .iter()
+        .filter_map(|key| json.get(*key).and_then(|v| v.as_str()))
+        .any(is_short_form_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_vertical_clips_and_short_form_urls() {
+        assert!(is_short_form(Some(1080), Some(1920), Some(42.0)));
+        assert!(!is_short_form(Some(1080), Some(1920), Some(600.0)));
+        assert!(!is_short_form(Some(1920), Some(1080), Some(42.0)));
+        assert!(is_short_form_url("https://www.youtube.com/shorts/abc"));
+        assert!(is_short_form_url("https://www.instagram.com/reel/xyz/"));
+        assert!(!is_short_form_url("https://www.youtube.com/watch?v=abc"));
+        assert!(is_short_form_info(&serde_json::json!({
+            "url": "https://www.youtube.com/shorts/abc",
+            "duration": 30.0
+        })));
+    }
+}
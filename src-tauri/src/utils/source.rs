@@ -0,0 +1,249 @@
+use serde::Serialize;
+
+/// A site we show with its own name and icon
+struct KnownSource {
+    id: &'static str,
+    name: &'static str,
+    /// Host the favicon is fetched from
+    domain: &'static str,
+    /// Lowercase yt-dlp `extractor_key` prefixes ("youtube" covers "YoutubeTab")
+    extractors: &'static [&'static str],
+    hosts: &'static [&'static str],
+}
+
+const KNOWN_SOURCES: &[KnownSource] = &[
+    KnownSource {
+        id: "youtube",
+        name: "YouTube",
+        domain: "youtube.com",
+        extractors: &["youtube"],
+        hosts: &["youtube.com", "youtu.be", "youtube-nocookie.com"],
+    },
+    KnownSource {
+        id: "tiktok",
+        name: "TikTok",
+        domain: "tiktok.com",
+        extractors: &["tiktok"],
+        hosts: &["tiktok.com"],
+    },
+    KnownSource {
+        id: "douyin",
+        name: "Douyin",
+        domain: "douyin.com",
+        extractors: &["douyin"],
+        hosts: &["douyin.com", "iesdouyin.com"],
+    },
+    KnownSource {
+        id: "facebook",
+        name: "Facebook",
+        domain: "facebook.com",
+        extractors: &["facebook"],
+        hosts: &["facebook.com", "fb.watch", "fb.com"],
+    },
+    KnownSource {
+        id: "instagram",
+        name: "Instagram",
+        domain: "instagram.com",
+        extractors: &["instagram"],
+        hosts: &["instagram.com"],
+    },
+    KnownSource {
+        id: "twitter",
+        name: "X (Twitter)",
+        domain: "x.com",
+        extractors: &["twitter"],
+        hosts: &["twitter.com", "x.com"],
+    },
+    KnownSource {
+        id: "bilibili",
+        name: "Bilibili",
+        domain: "bilibili.com",
+        extractors: &["bilibili", "bili"],
+        hosts: &["bilibili.com", "b23.tv"],
+    },
+    KnownSource {
+        id: "youku",
+        name: "Youku",
+        domain: "youku.com",
+        extractors: &["youku"],
+        hosts: &["youku.com"],
+    },
+    KnownSource {
+        id: "vimeo",
+        name: "Vimeo",
+        domain: "vimeo.com",
+        extractors: &["vimeo"],
+        hosts: &["vimeo.com"],
+    },
+    KnownSource {
+        id: "soundcloud",
+        name: "SoundCloud",
+        domain: "soundcloud.com",
+        extractors: &["soundcloud"],
+        hosts: &["soundcloud.com"],
+    },
+    KnownSource {
+        id: "twitch",
+        name: "Twitch",
+        domain: "twitch.tv",
+        extractors: &["twitch"],
+        hosts: &["twitch.tv"],
+    },
+    KnownSource {
+        id: "reddit",
+        name: "Reddit",
+        domain: "reddit.com",
+        extractors: &["reddit"],
+        hosts: &["reddit.com", "redd.it"],
+    },
+    KnownSource {
+        id: "dailymotion",
+        name: "Dailymotion",
+        domain: "dailymotion.com",
+        extractors: &["dailymotion"],
+        hosts: &["dailymotion.com", "dai.ly"],
+    },
+    KnownSource {
+        id: "niconico",
+        name: "Niconico",
+        domain: "nicovideo.jp",
+        extractors: &["niconico"],
+        hosts: &["nicovideo.jp", "nico.ms"],
+    },
+    KnownSource {
+        id: "bandcamp",
+        name: "Bandcamp",
+        domain: "bandcamp.com",
+        extractors: &["bandcamp"],
+        hosts: &["bandcamp.com"],
+    },
+];
+
+/// Display metadata for a history source id
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceInfo {
+    pub id: String,
+    pub display_name: String,
+    /// Site the favicon belongs to; None for "other" and unknown extractors
+    pub domain: Option<String>,
+    pub icon_path: Option<String>,
+}
+
+/// Lowercase host of `url` without port or credentials
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.trim_end_matches('.');
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+/// Site a URL belongs to, from its host name; "other" when we don't know it
+pub fn detect_source(url: &str) -> Option<String> {
+    let host = url_host(url).unwrap_or_default();
+    let known = KNOWN_SOURCES.iter().find(|source| {
+        source
+            .hosts
+            .iter()
+            .any(|known| host == *known || host.ends_with(&format!(".{}", known)))
+    });
+    Some(known.map_or("other", |source| source.id).to_string())
+}
+
+/// Source id for a yt-dlp `extractor_key` ("YoutubeTab" -> "youtube");
+/// None for the generic extractor, which says nothing about the site
+pub fn source_from_extractor(extractor_key: &str) -> Option<String> {
+    let key = extractor_key.trim().to_lowercase();
+    let key = key.split(':').next().unwrap_or_default();
+    if key.is_empty() || key == "generic" || key == "na" {
+        return None;
+    }
+    let known = KNOWN_SOURCES.iter().find(|source| {
+        source
+            .extractors
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+    });
+    Some(known.map_or(key, |source| source.id).to_string())
+}
+
+/// History source for a download: the extractor (or a known source id) the
+/// frontend passed, else the URL's host
+pub fn resolve_source(source: Option<&str>, url: &str) -> Option<String> {
+    source
+        .and_then(|source| {
+            KNOWN_SOURCES
+                .iter()
+                .find(|known| known.id == source)
+                .map(|known| known.id.to_string())
+                .or_else(|| source_from_extractor(source))
+        })
+        .or_else(|| detect_source(url))
+}
+
+/// Name, favicon domain and (not yet cached) icon of a source id
+pub fn source_info(id: &str) -> SourceInfo {
+    match KNOWN_SOURCES.iter().find(|known| known.id == id) {
+        Some(known) => SourceInfo {
+            id: known.id.to_string(),
+            display_name: known.name.to_string(),
+            domain: Some(known.domain.to_string()),
+            icon_path: None,
+        },
+        None => {
+            let mut chars = id.chars();
+            let display_name = match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => "Other".to_string(),
+            };
+            SourceInfo {
+                id: id.to_string(),
+                display_name,
+                domain: None,
+                icon_path: None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_come_from_hosts_and_extractor_keys() {
+        assert_eq!(
+            detect_source("https://m.youtube.com/watch?v=x").as_deref(),
+            Some("youtube")
+        );
+        assert_eq!(
+            detect_source("https://user@x.com:443/status/1").as_deref(),
+            Some("twitter")
+        );
+        // Substrings of other hosts no longer match
+        assert_eq!(
+            detect_source("https://dropbox.com/s/clip").as_deref(),
+            Some("other")
+        );
+
+        assert_eq!(
+            source_from_extractor("YoutubeTab").as_deref(),
+            Some("youtube")
+        );
+        assert_eq!(
+            source_from_extractor("BiliBili").as_deref(),
+            Some("bilibili")
+        );
+        assert_eq!(source_from_extractor("Generic"), None);
+        assert_eq!(
+            resolve_source(Some("PeerTube"), "https://v.example/w/1").as_deref(),
+            Some("peertube")
+        );
+        assert_eq!(
+            resolve_source(Some("Generic"), "https://vimeo.com/1").as_deref(),
+            Some("vimeo")
+        );
+        assert_eq!(source_info("peertube").display_name, "Peertube");
+    }
+}
@@ -1385,81 +1385,83 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
 
         try {
           await invoke('download_video', {
-            id: item.id,
-            url: item.url,
-            outputPath: itemSettings?.outputPath || settings.outputPath,
-            quality: itemSettings?.quality ?? settings.quality,
-            format: itemSettings?.format ?? settings.format,
-            downloadPlaylist: itemSettings?.downloadPlaylist ?? false,
-            playlistIndex: item.playlistIndex ?? null,
-            playlistTotal: item.playlistTotal ?? null,
-            numberPlaylistItems: itemSettings?.numberPlaylistItems ?? false,
-            queueIndex: item.queueIndex ?? null,
-            queueTotal: item.queueTotal ?? null,
-            numberQueueItems: itemSettings?.numberQueueItems ?? false,
-            splitEmbeddedChapters: itemSettings?.splitEmbeddedChapters ?? false,
-            numberChapterFiles: itemSettings?.numberChapterFiles ?? true,
-            autoOrganizeCollections: itemSettings?.autoOrganizeCollections ?? false,
-            playlistCollectionName: itemSettings?.playlistCollectionName ?? null,
-            videoCodec: itemSettings?.videoCodec ?? settings.videoCodec,
-            preferredFps: itemSettings?.preferredFps ?? settings.preferredFps,
-            audioBitrate: itemSettings?.audioBitrate ?? settings.audioBitrate,
-            playlistLimit:
-              itemSettings?.playlistLimit && itemSettings.playlistLimit > 0
-                ? itemSettings.playlistLimit
+            options: {
+              id: item.id,
+              url: item.url,
+              outputPath: itemSettings?.outputPath || settings.outputPath,
+              quality: itemSettings?.quality ?? settings.quality,
+              format: itemSettings?.format ?? settings.format,
+              downloadPlaylist: itemSettings?.downloadPlaylist ?? false,
+              playlistIndex: item.playlistIndex ?? null,
+              playlistTotal: item.playlistTotal ?? null,
+              numberPlaylistItems: itemSettings?.numberPlaylistItems ?? false,
+              queueIndex: item.queueIndex ?? null,
+              queueTotal: item.queueTotal ?? null,
+              numberQueueItems: itemSettings?.numberQueueItems ?? false,
+              splitEmbeddedChapters: itemSettings?.splitEmbeddedChapters ?? false,
+              numberChapterFiles: itemSettings?.numberChapterFiles ?? true,
+              autoOrganizeCollections: itemSettings?.autoOrganizeCollections ?? false,
+              playlistCollectionName: itemSettings?.playlistCollectionName ?? null,
+              videoCodec: itemSettings?.videoCodec ?? settings.videoCodec,
+              preferredFps: itemSettings?.preferredFps ?? settings.preferredFps,
+              audioBitrate: itemSettings?.audioBitrate ?? settings.audioBitrate,
+              playlistLimit:
+                itemSettings?.playlistLimit && itemSettings.playlistLimit > 0
+                  ? itemSettings.playlistLimit
+                  : null,
+              // Subtitle settings
+              subtitleMode: itemSettings?.subtitleMode ?? settings.subtitleMode,
+              subtitleLangs: (itemSettings?.subtitleLangs ?? settings.subtitleLangs).join(','),
+              subtitleEmbed: itemSettings?.subtitleEmbed ?? settings.subtitleEmbed,
+              subtitleFormat: itemSettings?.subtitleFormat ?? settings.subtitleFormat,
+              // Logging settings
+              logStderr,
+              // YouTube specific settings
+              useBunRuntime: settings.useBunRuntime,
+              useActualPlayerJs: settings.useActualPlayerJs,
+              // Network settings
+              ...buildCookieProxyInvokeOptions(cookieSettings, proxySettings),
+              // Post-processing settings
+              embedMetadata: settings.embedMetadata,
+              embedThumbnail: settings.embedThumbnail,
+              // Live stream settings
+              liveFromStart: itemSettings?.liveFromStart ?? settings.liveFromStart,
+              skipLive: itemSettings?.skipLive ?? false,
+              // Speed limit settings
+              speedLimit: settings.speedLimitEnabled
+                ? `${settings.speedLimitValue}${settings.speedLimitUnit}`
                 : null,
-            // Subtitle settings
-            subtitleMode: itemSettings?.subtitleMode ?? settings.subtitleMode,
-            subtitleLangs: (itemSettings?.subtitleLangs ?? settings.subtitleLangs).join(','),
-            subtitleEmbed: itemSettings?.subtitleEmbed ?? settings.subtitleEmbed,
-            subtitleFormat: itemSettings?.subtitleFormat ?? settings.subtitleFormat,
-            // Logging settings
-            logStderr,
-            // YouTube specific settings
-            useBunRuntime: settings.useBunRuntime,
-            useActualPlayerJs: settings.useActualPlayerJs,
-            // Network settings
-            ...buildCookieProxyInvokeOptions(cookieSettings, proxySettings),
-            // Post-processing settings
-            embedMetadata: settings.embedMetadata,
-            embedThumbnail: settings.embedThumbnail,
-            // Live stream settings
-            liveFromStart: itemSettings?.liveFromStart ?? settings.liveFromStart,
-            skipLive: itemSettings?.skipLive ?? false,
-            // Speed limit settings
-            speedLimit: settings.speedLimitEnabled
-              ? `${settings.speedLimitValue}${settings.speedLimitUnit}`
-              : null,
-            // External downloader settings
-            useAria2: itemSettings?.useAria2 ?? settings.useAria2,
-            aria2Args: itemSettings?.aria2Args ?? settings.aria2Args,
-            // yt-dlp advanced options
-            ytdlpAdvancedOptionsEnabled:
-              itemSettings?.ytdlpAdvancedOptionsEnabled ?? settings.ytdlpAdvancedOptionsEnabled,
-            ytdlpAdvancedOptions:
-              itemSettings?.ytdlpAdvancedOptions ?? settings.ytdlpAdvancedOptions,
-            // SponsorBlock settings
-            sponsorblockRemove: sponsorBlockArgs.remove,
-            sponsorblockMark: sponsorBlockArgs.mark,
-            // Download sections (time range)
-            downloadSections:
-              itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
-                ? `*${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
-                : null,
-            // No history_id for new downloads
-            historyId: null,
-            // Title from video info fetch
-            title: item.title || null,
-            // Thumbnail from video info fetch
-            thumbnail: item.thumbnail || null,
-            // Source/extractor from video info fetch
-            source: item.extractor || null,
-            pluginWorkflowSnapshots:
-              itemSettings?.pluginWorkflowSnapshots ?? loadPluginWorkflowSnapshots(),
-            postDownloadWorkflowSteps:
-              itemSettings?.postDownloadWorkflowSteps ?? loadPostDownloadWorkflowSteps(),
-            emitFailedWorkflow: false,
-            downloadKind: 'download',
+              // External downloader settings
+              useAria2: itemSettings?.useAria2 ?? settings.useAria2,
+              aria2Args: itemSettings?.aria2Args ?? settings.aria2Args,
+              // yt-dlp advanced options
+              ytdlpAdvancedOptionsEnabled:
+                itemSettings?.ytdlpAdvancedOptionsEnabled ?? settings.ytdlpAdvancedOptionsEnabled,
+              ytdlpAdvancedOptions:
+                itemSettings?.ytdlpAdvancedOptions ?? settings.ytdlpAdvancedOptions,
+              // SponsorBlock settings
+              sponsorblockRemove: sponsorBlockArgs.remove,
+              sponsorblockMark: sponsorBlockArgs.mark,
+              // Download sections (time range)
+              downloadSections:
+                itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
+                  ? `*${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
+                  : null,
+              // No history_id for new downloads
+              historyId: null,
+              // Title from video info fetch
+              title: item.title || null,
+              // Thumbnail from video info fetch
+              thumbnail: item.thumbnail || null,
+              // Source/extractor from video info fetch
+              source: item.extractor || null,
+              pluginWorkflowSnapshots:
+                itemSettings?.pluginWorkflowSnapshots ?? loadPluginWorkflowSnapshots(),
+              postDownloadWorkflowSteps:
+                itemSettings?.postDownloadWorkflowSteps ?? loadPostDownloadWorkflowSteps(),
+              emitFailedWorkflow: false,
+              downloadKind: 'download',
+            },
           });
 
           setItems((items) =>
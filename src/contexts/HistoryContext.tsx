@@ -598,32 +598,34 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
 
       try {
         await invoke('download_video', {
-          id: downloadId,
-          url: entry.url,
-          outputPath,
-          quality,
-          format,
-          downloadPlaylist: false,
-          videoCodec: 'auto',
-          audioBitrate: '192',
-          playlistLimit: null,
-          subtitleMode: 'off',
-          subtitleLangs: '',
-          subtitleEmbed: false,
-          subtitleFormat: 'srt',
-          logStderr,
-          useBunRuntime,
-          useActualPlayerJs,
-          historyId: entry.id,
-          ...networkOptions,
-          // External downloader settings
-          useAria2,
-          aria2Args,
-          ytdlpAdvancedOptionsEnabled,
-          ytdlpAdvancedOptions,
-          pluginWorkflowSnapshots: loadPluginWorkflowSnapshots(),
-          postDownloadWorkflowSteps: loadPostDownloadWorkflowSteps(),
-          downloadKind: 'history-redownload',
+          options: {
+            id: downloadId,
+            url: entry.url,
+            outputPath,
+            quality,
+            format,
+            downloadPlaylist: false,
+            videoCodec: 'auto',
+            audioBitrate: '192',
+            playlistLimit: null,
+            subtitleMode: 'off',
+            subtitleLangs: '',
+            subtitleEmbed: false,
+            subtitleFormat: 'srt',
+            logStderr,
+            useBunRuntime,
+            useActualPlayerJs,
+            historyId: entry.id,
+            ...networkOptions,
+            // External downloader settings
+            useAria2,
+            aria2Args,
+            ytdlpAdvancedOptionsEnabled,
+            ytdlpAdvancedOptions,
+            pluginWorkflowSnapshots: loadPluginWorkflowSnapshots(),
+            postDownloadWorkflowSteps: loadPostDownloadWorkflowSteps(),
+            downloadKind: 'history-redownload',
+          },
         });
 
         // Mark as completed
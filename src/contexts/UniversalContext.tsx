@@ -988,72 +988,74 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
 
         try {
           await invoke('download_video', {
-            id: item.id,
-            url: item.url,
-            outputPath: itemSettings?.outputPath || settings.outputPath,
-            quality: itemSettings?.quality ?? settings.quality,
-            format: itemSettings?.format ?? settings.format,
-            downloadPlaylist: false,
-            queueIndex: item.queueIndex ?? null,
-            queueTotal: item.queueTotal ?? null,
-            numberQueueItems: itemSettings?.numberQueueItems ?? false,
-            autoOrganizeCollections:
-              itemSettings?.autoOrganizeCollections ?? downloadSettings.autoOrganizeCollections,
-            playlistCollectionName: null,
-            videoCodec: resolveUniversalVideoCodec(itemSettings, settings),
-            preferredFps: itemSettings?.preferredFps ?? settings.preferredFps,
-            audioBitrate: itemSettings?.audioBitrate ?? settings.audioBitrate,
-            playlistLimit: null,
-            subtitleMode: 'off',
-            subtitleLangs: '',
-            subtitleEmbed: false,
-            subtitleFormat: 'srt',
-            // Logging settings
-            logStderr,
-            // Cookie settings
-            ...networkOptions,
-            // Post-processing settings (from main download settings)
-            embedMetadata: embedSettings.embedMetadata,
-            embedThumbnail: embedSettings.embedThumbnail,
-            splitEmbeddedChapters:
-              itemSettings?.splitEmbeddedChapters ?? downloadSettings.splitEmbeddedChapters,
-            numberChapterFiles:
-              itemSettings?.numberChapterFiles ?? downloadSettings.numberChapterFiles,
-            // Live stream settings
-            liveFromStart: itemSettings?.liveFromStart ?? settings.liveFromStart,
-            skipLive: itemSettings?.skipLive ?? false,
-            // Speed limit settings
-            speedLimit: settings.speedLimitEnabled
-              ? `${settings.speedLimitValue}${settings.speedLimitUnit}`
-              : null,
-            // External downloader settings (from item snapshot, fallback to global settings)
-            useAria2: itemSettings?.useAria2 ?? advancedSettings.useAria2,
-            aria2Args: itemSettings?.aria2Args ?? advancedSettings.aria2Args,
-            ytdlpAdvancedOptionsEnabled:
-              itemSettings?.ytdlpAdvancedOptionsEnabled ??
-              advancedSettings.ytdlpAdvancedOptionsEnabled,
-            ytdlpAdvancedOptions:
-              itemSettings?.ytdlpAdvancedOptions ?? advancedSettings.ytdlpAdvancedOptions,
-            // SponsorBlock settings
-            sponsorblockRemove: sponsorBlockArgs.remove,
-            sponsorblockMark: sponsorBlockArgs.mark,
-            // Download sections (time range)
-            downloadSections:
-              itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
-                ? `*${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
+            options: {
+              id: item.id,
+              url: item.url,
+              outputPath: itemSettings?.outputPath || settings.outputPath,
+              quality: itemSettings?.quality ?? settings.quality,
+              format: itemSettings?.format ?? settings.format,
+              downloadPlaylist: false,
+              queueIndex: item.queueIndex ?? null,
+              queueTotal: item.queueTotal ?? null,
+              numberQueueItems: itemSettings?.numberQueueItems ?? false,
+              autoOrganizeCollections:
+                itemSettings?.autoOrganizeCollections ?? downloadSettings.autoOrganizeCollections,
+              playlistCollectionName: null,
+              videoCodec: resolveUniversalVideoCodec(itemSettings, settings),
+              preferredFps: itemSettings?.preferredFps ?? settings.preferredFps,
+              audioBitrate: itemSettings?.audioBitrate ?? settings.audioBitrate,
+              playlistLimit: null,
+              subtitleMode: 'off',
+              subtitleLangs: '',
+              subtitleEmbed: false,
+              subtitleFormat: 'srt',
+              // Logging settings
+              logStderr,
+              // Cookie settings
+              ...networkOptions,
+              // Post-processing settings (from main download settings)
+              embedMetadata: embedSettings.embedMetadata,
+              embedThumbnail: embedSettings.embedThumbnail,
+              splitEmbeddedChapters:
+                itemSettings?.splitEmbeddedChapters ?? downloadSettings.splitEmbeddedChapters,
+              numberChapterFiles:
+                itemSettings?.numberChapterFiles ?? downloadSettings.numberChapterFiles,
+              // Live stream settings
+              liveFromStart: itemSettings?.liveFromStart ?? settings.liveFromStart,
+              skipLive: itemSettings?.skipLive ?? false,
+              // Speed limit settings
+              speedLimit: settings.speedLimitEnabled
+                ? `${settings.speedLimitValue}${settings.speedLimitUnit}`
                 : null,
-            // Title from video info fetch
-            title: item.title || null,
-            // Thumbnail from video info fetch (for non-YouTube sites)
-            thumbnail: item.thumbnail || null,
-            // Source/extractor from video info fetch (e.g. "BiliBili", "TikTok")
-            source: item.extractor || null,
-            pluginWorkflowSnapshots:
-              itemSettings?.pluginWorkflowSnapshots ?? loadPluginWorkflowSnapshots(),
-            postDownloadWorkflowSteps:
-              itemSettings?.postDownloadWorkflowSteps ?? loadPostDownloadWorkflowSteps(),
-            emitFailedWorkflow: false,
-            downloadKind: 'universal',
+              // External downloader settings (from item snapshot, fallback to global settings)
+              useAria2: itemSettings?.useAria2 ?? advancedSettings.useAria2,
+              aria2Args: itemSettings?.aria2Args ?? advancedSettings.aria2Args,
+              ytdlpAdvancedOptionsEnabled:
+                itemSettings?.ytdlpAdvancedOptionsEnabled ??
+                advancedSettings.ytdlpAdvancedOptionsEnabled,
+              ytdlpAdvancedOptions:
+                itemSettings?.ytdlpAdvancedOptions ?? advancedSettings.ytdlpAdvancedOptions,
+              // SponsorBlock settings
+              sponsorblockRemove: sponsorBlockArgs.remove,
+              sponsorblockMark: sponsorBlockArgs.mark,
+              // Download sections (time range)
+              downloadSections:
+                itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
+                  ? `*${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
+                  : null,
+              // Title from video info fetch
+              title: item.title || null,
+              // Thumbnail from video info fetch (for non-YouTube sites)
+              thumbnail: item.thumbnail || null,
+              // Source/extractor from video info fetch (e.g. "BiliBili", "TikTok")
+              source: item.extractor || null,
+              pluginWorkflowSnapshots:
+                itemSettings?.pluginWorkflowSnapshots ?? loadPluginWorkflowSnapshots(),
+              postDownloadWorkflowSteps:
+                itemSettings?.postDownloadWorkflowSteps ?? loadPostDownloadWorkflowSteps(),
+              emitFailedWorkflow: false,
+              downloadKind: 'universal',
+            },
           });
 
           setItems((items) =>
@@ -148,7 +148,7 @@ export async function getSavedChannelVideosByVideoIds(input: {
 }
 
 export async function downloadVideoCommand(input: Record<string, unknown>): Promise<void> {
-  await invoke('download_video', input);
+  await invoke('download_video', { options: input });
 }
 
 export async function stopDownloadCommand(): Promise<void> {